@@ -0,0 +1,138 @@
+use std::path::Path;
+use std::time::Duration;
+
+use faceguard_core::detection::domain::detector_backend::DetectorBackend;
+use faceguard_core::detection::domain::face_detector::FaceDetector;
+use faceguard_core::detection::domain::face_region_builder::FaceRegionBuilder;
+use faceguard_core::detection::infrastructure::bytetrack_tracker::ByteTracker;
+use faceguard_core::detection::infrastructure::model_registry::ModelChoice;
+use faceguard_core::detection::infrastructure::onnx_yolo_detector::OnnxYoloDetector;
+use faceguard_core::detection::infrastructure::skip_frame_detector::SkipFrameDetector;
+use faceguard_core::evaluation::infrastructure::{detection_benchmark, widerface_dataset};
+use faceguard_core::shared::constants::TRACKER_MAX_LOST;
+use faceguard_core::shared::job_error::JobError;
+
+use crate::exit_status::InvalidArgs;
+use crate::{build_verification_detector, BenchArgs, YoloSession};
+
+pub fn run(args: &BenchArgs, model_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    validate_bench_args(args).map_err(|e| InvalidArgs(e.into()))?;
+    let images = widerface_dataset::load(&args.dataset, args.min_face_size)
+        .map_err(|e| JobError::input(e.to_string()))?;
+    if images.is_empty() {
+        return Err(
+            JobError::input(format!("No labeled images in {}", args.dataset.display())).into(),
+        );
+    }
+    let yolo = YoloSession::new(ModelChoice::parse(&args.model)?, model_dir.to_path_buf())
+        .with_backend(DetectorBackend::parse(&args.detector_backend).unwrap_or_default());
+    let mut detector = build_bench_detector(&yolo, args.confidence, args.skip_frames)?;
+    let report = detection_benchmark::run(
+        detector.as_mut(),
+        &images,
+        args.match_iou,
+        Some(&|done, total| eprint!("\rBenchmarking... {done}/{total} images")),
+    )?;
+    eprintln!();
+
+    let faces: usize = images.iter().map(|image| image.faces.len()).sum();
+    let ignored: usize = images.iter().map(|image| image.ignored.len()).sum();
+    let score = report.score();
+    let rate = |value: Option<f64>| value.map_or("n/a".to_string(), |v| format!("{v:.3}"));
+    println!(
+        "Dataset: {} ({} images, {faces} faces, {ignored} ignored)",
+        args.dataset.display(),
+        report.images()
+    );
+    println!(
+        "Model: {}, confidence {}, skip frames {}, match IoU {}",
+        args.model, args.confidence, args.skip_frames, args.match_iou
+    );
+    println!(
+        "  Precision: {} ({} of {} detections)",
+        rate(score.precision()),
+        score.true_positives,
+        score.true_positives + score.false_positives
+    );
+    println!(
+        "  Recall:    {} ({} of {faces} faces)",
+        rate(score.recall()),
+        score.true_positives
+    );
+    println!("  F1:        {}", rate(score.f1()));
+    if let Some(latency) = report.latency() {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        println!(
+            "  Latency:   mean {:.1} ms, median {:.1} ms, p95 {:.1} ms, max {:.1} ms",
+            ms(latency.mean),
+            ms(latency.median),
+            ms(latency.p95),
+            ms(latency.max)
+        );
+    }
+    Ok(())
+}
+
+fn validate_bench_args(args: &BenchArgs) -> Result<(), String> {
+    if !args.dataset.is_dir() {
+        return Err(format!(
+            "Dataset folder not found: {}",
+            args.dataset.display()
+        ));
+    }
+    match ModelChoice::parse(&args.model)? {
+        ModelChoice::Custom(ref path) if !path.is_file() => {
+            return Err(format!("Model file not found: {}", path.display()));
+        }
+        _ => {}
+    }
+    if !(0.0..=1.0).contains(&args.confidence) {
+        return Err(format!(
+            "Confidence must be between 0.0 and 1.0, got {}",
+            args.confidence
+        ));
+    }
+    if args.skip_frames == 0 {
+        return Err("Skip frames must be at least 1".into());
+    }
+    if DetectorBackend::parse(&args.detector_backend).is_none() {
+        return Err(format!(
+            "Detector backend must be 'auto', 'cpu', 'cuda', 'coreml', or 'directml', got '{}'",
+            args.detector_backend
+        ));
+    }
+    if !(args.match_iou > 0.0 && args.match_iou <= 1.0) {
+        return Err(format!(
+            "Match IoU must be above 0.0 and at most 1.0, got {}",
+            args.match_iou
+        ));
+    }
+    Ok(())
+}
+
+/// Without skipping, every detection over `confidence` is scored, each
+/// image on its own: the tracker would drop those under its own 0.5
+/// threshold unless an earlier image left a track there. Skipping frames
+/// needs tracks to extrapolate, so then images run as one clip.
+fn build_bench_detector(
+    yolo: &YoloSession,
+    confidence: f64,
+    skip_frames: usize,
+) -> Result<Box<dyn FaceDetector>, Box<dyn std::error::Error>> {
+    if skip_frames > 1 {
+        let detector = build_verification_detector(yolo, confidence)?;
+        return Ok(Box::new(SkipFrameDetector::new(detector, skip_frames)?));
+    }
+    let (session, input_size) = yolo.get()?;
+    let detector = OnnxYoloDetector::from_shared_session(
+        session,
+        input_size,
+        FaceRegionBuilder::new(0.0, 0.0, None),
+        ByteTracker::new(TRACKER_MAX_LOST),
+        confidence,
+    )
+    .with_calibration(yolo.model.calibration())
+    .with_model_format(yolo.model.format())
+    .without_tracking();
+    Ok(Box::new(detector))
+}
@@ -0,0 +1,97 @@
+use std::path::Path;
+
+use faceguard_core::detection::domain::blur_target::BlurTarget;
+use faceguard_core::detection::domain::face_embedder::FaceEmbedder;
+use faceguard_core::detection::infrastructure::embedding_face_grouper::EmbeddingFaceGrouper;
+use faceguard_core::detection::infrastructure::model_registry::ModelChoice;
+use faceguard_core::identity::infrastructure::identity_store;
+use faceguard_core::shared::constants::EMBEDDING_MODEL_NAME;
+use faceguard_core::shared::job_error::JobError;
+use faceguard_core::video::domain::video_reader::VideoReader;
+use faceguard_core::video::infrastructure::image_file_reader::ImageFileReader;
+
+use crate::exit_status::InvalidArgs;
+use crate::{build_yolo_detector, identities_path, load_embedder, EnrollArgs, YoloSession};
+
+/// Detection settings for enrollment photos: the blur defaults, so
+/// reference crops are framed like the faces they are compared with.
+const ENROLL_CONFIDENCE: f64 = 0.5;
+const ENROLL_PADDING: f64 = 0.4;
+
+pub fn run(args: &EnrollArgs, model_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let path = identities_path(args.identities.as_deref())?;
+    let mut gallery = identity_store::load(&path, EMBEDDING_MODEL_NAME)?;
+    if args.list {
+        if gallery.is_empty() {
+            println!("No one is enrolled.");
+        }
+        for identity in gallery.identities() {
+            println!("{}: {} photo(s)", identity.name, identity.embeddings.len());
+        }
+        return Ok(());
+    }
+    if let Some(ref name) = args.remove {
+        if !gallery.remove(name) {
+            return Err(InvalidArgs(format!("{name} is not enrolled").into()).into());
+        }
+        identity_store::save(&path, EMBEDDING_MODEL_NAME, &gallery)?;
+        println!("Removed {name}");
+        return Ok(());
+    }
+    let name = args.name.as_deref().ok_or("--name is required")?;
+    if args.photos.is_empty() {
+        return Err(InvalidArgs("Give at least one reference photo".into()).into());
+    }
+    let yolo = YoloSession::new(ModelChoice::default(), model_dir.to_path_buf());
+    let embedder = load_embedder(model_dir)?;
+    let mut embeddings = Vec::with_capacity(args.photos.len());
+    for photo in &args.photos {
+        match reference_embedding(&yolo, &embedder, photo) {
+            Ok(embedding) => embeddings.push(embedding),
+            Err(e) => eprintln!("Skipping {}: {e}", photo.display()),
+        }
+    }
+    if embeddings.is_empty() {
+        return Err(JobError::input(format!("No usable reference photo of {name}")).into());
+    }
+    let count = embeddings.len();
+    gallery.enroll(name, embeddings);
+    identity_store::save(&path, EMBEDDING_MODEL_NAME, &gallery)?;
+    println!("Enrolled {count} photo(s) of {name} in {}", path.display());
+    Ok(())
+}
+
+/// Embedding of the one face in `photo`. A photo with no face or several
+/// is refused, since there'd be no telling whose face to enroll.
+fn reference_embedding(
+    yolo: &YoloSession,
+    embedder: &EmbeddingFaceGrouper,
+    photo: &Path,
+) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+    let mut reader = ImageFileReader::new();
+    reader.open(photo)?;
+    let frame = reader.frames().next().ok_or("no image data")??;
+    // The whole photo is searched: detection zones frame a camera's view
+    // for blurring and don't apply to enrollment photos.
+    let mut detector = build_yolo_detector(
+        yolo,
+        BlurTarget::Face,
+        ENROLL_CONFIDENCE,
+        ENROLL_PADDING,
+        0.0,
+        0.0,
+        None,
+        None,
+        &[],
+    )?;
+    let faces = detector.detect(&frame)?;
+    let [face] = faces.as_slice() else {
+        return Err(format!(
+            "found {} faces; a reference photo must show exactly one",
+            faces.len()
+        )
+        .into());
+    };
+    let crop = frame.square_crop(face);
+    embedder.embed(crop.data(), crop.width(), crop.height())
+}
@@ -0,0 +1,157 @@
+//! Exit codes and the summary line: the contract wrapper scripts branch on.
+//! Codes and status names are stable; add new ones rather than renumber.
+
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use faceguard_core::shared::cancellation::Cancelled;
+use faceguard_core::shared::job_error::{JobError, JobErrorKind};
+
+/// Prefix of the summary line, the last line the CLI writes to stderr.
+pub const SUMMARY_PREFIX: &str = "faceguard-summary";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    Success,
+    InvalidArgs,
+    Cancelled,
+    InputError,
+    ProcessingError,
+    OutputError,
+}
+
+impl ExitStatus {
+    pub fn code(self) -> u8 {
+        match self {
+            Self::Success => 0,
+            Self::InvalidArgs => 2,
+            Self::Cancelled => 3,
+            Self::InputError => 4,
+            Self::ProcessingError => 5,
+            Self::OutputError => 6,
+        }
+    }
+
+    /// Name written as `status=` in the summary line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Success => "ok",
+            Self::InvalidArgs => "invalid-args",
+            Self::Cancelled => "cancelled",
+            Self::InputError => "input-error",
+            Self::ProcessingError => "processing-error",
+            Self::OutputError => "output-error",
+        }
+    }
+
+    /// The status a failed run exits with. Cancellation wins, then input
+    /// and output errors tagged by core (see [`JobError`]), then argument
+    /// errors; anything else failed while processing.
+    pub fn of(error: &(dyn Error + 'static)) -> Self {
+        if Cancelled::reason_of(error).is_some() {
+            return Self::Cancelled;
+        }
+        match JobError::kind_of(error) {
+            Some(JobErrorKind::Input) => return Self::InputError,
+            Some(JobErrorKind::Output) => return Self::OutputError,
+            None => {}
+        }
+        if error.is::<InvalidArgs>() || error.is::<clap::Error>() {
+            return Self::InvalidArgs;
+        }
+        Self::ProcessingError
+    }
+}
+
+/// Marks an error found while checking the options, before any work.
+#[derive(Debug)]
+pub struct InvalidArgs(pub Box<dyn Error>);
+
+impl fmt::Display for InvalidArgs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Error for InvalidArgs {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.0)
+    }
+}
+
+/// One `key=value` line, e.g.
+/// `faceguard-summary status=input-error code=4 seconds=0.2 error="Input file not found: a.mp4"`.
+/// `error` is only present on failure, quoted with `"` and `\` escaped.
+pub fn summary_line(status: ExitStatus, elapsed: Duration, error: Option<&str>) -> String {
+    let mut line = format!(
+        "{SUMMARY_PREFIX} status={} code={} seconds={:.1}",
+        status.name(),
+        status.code(),
+        elapsed.as_secs_f64()
+    );
+    if let Some(message) = error {
+        let escaped = message
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n");
+        line.push_str(&format!(" error=\"{escaped}\""));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use faceguard_core::shared::cancellation::CancelReason;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Box::new(Cancelled(CancelReason::User)), ExitStatus::Cancelled)]
+    #[case(
+        Box::new(JobError::input("Input file not found")),
+        ExitStatus::InputError
+    )]
+    #[case(Box::new(JobError::output("Disk full")), ExitStatus::OutputError)]
+    #[case(Box::new(InvalidArgs("Bad value".into())), ExitStatus::InvalidArgs)]
+    #[case(
+        Box::new(InvalidArgs(JobError::input("Input file not found").into())),
+        ExitStatus::InputError
+    )]
+    #[case(
+        Box::new(InvalidArgs(Cancelled(CancelReason::User).into())),
+        ExitStatus::Cancelled
+    )]
+    #[case(
+        Box::new(clap::Error::new(clap::error::ErrorKind::UnknownArgument)),
+        ExitStatus::InvalidArgs
+    )]
+    #[case("Model failed".into(), ExitStatus::ProcessingError)]
+    fn test_of(#[case] error: Box<dyn Error>, #[case] expected: ExitStatus) {
+        assert_eq!(ExitStatus::of(&*error), expected);
+    }
+
+    #[test]
+    fn test_summary_line_without_error() {
+        assert_eq!(
+            summary_line(ExitStatus::Success, Duration::from_millis(42_740), None),
+            "faceguard-summary status=ok code=0 seconds=42.7"
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "Input file not found: a.mp4",
+        r#"error="Input file not found: a.mp4""#
+    )]
+    #[case(r#"Bad "quote""#, r#"error="Bad \"quote\"""#)]
+    #[case(r"C:\clips\a.mp4", r#"error="C:\\clips\\a.mp4""#)]
+    #[case("two\nlines", r#"error="two\nlines""#)]
+    fn test_summary_line_escapes_error(#[case] message: &str, #[case] expected: &str) {
+        let line = summary_line(ExitStatus::InputError, Duration::ZERO, Some(message));
+        assert_eq!(
+            line,
+            format!("faceguard-summary status=input-error code=4 seconds=0.0 {expected}")
+        );
+    }
+}
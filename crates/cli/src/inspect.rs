@@ -0,0 +1,121 @@
+use faceguard_core::pipeline::report_inspection::{inspect_report, ReportInspection, TrackSummary};
+use faceguard_core::shared::job_error::JobError;
+
+use crate::InspectArgs;
+
+/// Width of the per-track timeline bars printed by `inspect`.
+const INSPECT_TIMELINE_WIDTH: usize = 40;
+
+pub fn run(args: &InspectArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if !args.file.is_file() {
+        return Err(JobError::input(format!("Report not found: {}", args.file.display())).into());
+    }
+    let inspection = inspect_report(&args.file)?;
+    println!("{}: {}", inspection.kind.label(), args.file.display());
+    if let Some(source) = &inspection.source {
+        println!("  Source: {source}");
+    }
+    if let Some(output) = &inspection.output {
+        println!("  Output: {output}");
+    }
+    if let (Some(width), Some(height)) = (inspection.width, inspection.height) {
+        println!("  Video: {width}x{height}");
+    }
+    if inspection.total_frames > 0 {
+        let length = inspection
+            .seconds(inspection.total_frames)
+            .map(|s| format!(" ({})", format_clock(s)))
+            .unwrap_or_default();
+        println!("  Frames: {}{length}", inspection.total_frames);
+    }
+    if let Some(coverage) = inspection.coverage() {
+        println!(
+            "  Frames with faces: {} ({:.1}%)",
+            inspection.frames_with_faces,
+            coverage * 100.0
+        );
+    }
+
+    println!("\n{} tracks", inspection.tracks.len());
+    for track in &inspection.tracks {
+        print_inspected_track(&inspection, track);
+    }
+
+    let visible = inspection.unblurred_tracks().count();
+    if visible > 0 {
+        println!("\n{visible} tracks were left visible");
+    }
+    if !inspection.warnings.is_empty() {
+        println!("\n{} warnings", inspection.warnings.len());
+        for warning in &inspection.warnings {
+            println!("  [{}] {}", warning.kind, warning.message);
+        }
+    }
+    Ok(())
+}
+
+fn print_inspected_track(inspection: &ReportInspection, track: &TrackSummary) {
+    let id = track
+        .track_id
+        .map(|id| format!("#{id}"))
+        .unwrap_or_else(|| "untracked".into());
+    let mut line = format!("  {id:>9}");
+    match track.blurred {
+        Some(true) => line.push_str("  blurred"),
+        Some(false) => line.push_str("  VISIBLE"),
+        None => {}
+    }
+    if track.frames > 0 {
+        line.push_str(&format!("  {:>6} frames", track.frames));
+    }
+    if let Some(confidence) = track.mean_confidence {
+        line.push_str(&format!("  conf {confidence:.2}"));
+    }
+    if !track.label.is_empty() {
+        line.push_str(&format!("  \"{}\"", track.label));
+    }
+    if !track.blur_ranges.is_empty() {
+        line.push_str(&format!("  ranges {}", track.blur_ranges));
+    }
+    if !track.note.is_empty() {
+        line.push_str(&format!("  ({})", track.note));
+    }
+    println!("{line}");
+
+    if inspection.total_frames > 0 && !track.spans.is_empty() {
+        let bar: String = track
+            .occupancy(inspection.total_frames, INSPECT_TIMELINE_WIDTH)
+            .iter()
+            .map(|&on| if on { '#' } else { '.' })
+            .collect();
+        println!("             |{bar}|");
+    }
+    if !track.spans.is_empty() {
+        let spans: Vec<String> = track
+            .spans
+            .iter()
+            .map(
+                |span| match (inspection.seconds(span.start), inspection.seconds(span.end)) {
+                    (Some(start), Some(end)) => {
+                        format!("{}-{}", format_clock(start), format_clock(end))
+                    }
+                    _ => format!("frames {}-{}", span.start, span.end),
+                },
+            )
+            .collect();
+        println!("             {}", spans.join(", "));
+    }
+}
+
+/// Seconds as `M:SS.s`, or `H:MM:SS.s` from an hour on.
+pub(crate) fn format_clock(seconds: f64) -> String {
+    let tenths = (seconds.max(0.0) * 10.0).round() as u64;
+    let (hours, rest) = (tenths / 36_000, tenths % 36_000);
+    let (minutes, rest) = (rest / 600, rest % 600);
+    let (secs, tenth) = (rest / 10, rest % 10);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{secs:02}.{tenth}")
+    } else {
+        format!("{minutes}:{secs:02}.{tenth}")
+    }
+}
@@ -1,55 +1,219 @@
-use std::collections::HashSet;
+use std::cell::OnceCell;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::process;
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
-use clap::Parser;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
 
+use faceguard_core::audio::domain::audio_failure_policy::AudioFailurePolicy;
+use faceguard_core::audio::domain::bleep_style::BleepStyle;
+use faceguard_core::audio::domain::keyword_matcher::{KeywordMatcher, PhoneticStrictness};
+use faceguard_core::audio::domain::speech_language::SpeechLanguage;
+use faceguard_core::audio::domain::spoken_entity::PiiCategory;
+use faceguard_core::audio::infrastructure::voice_morph_transformer::DEFAULT_VOICE_MORPH_SEED;
 use faceguard_core::blurring::domain::frame_blurrer::FrameBlurrer;
-use faceguard_core::blurring::infrastructure::blurrer_factory::{create_blurrer, BlurShape};
+use faceguard_core::blurring::domain::redaction_rect::RedactionRect;
+use faceguard_core::blurring::domain::weak_blur::{widest_face, WeakBlur};
+use faceguard_core::blurring::infrastructure::blurrer_factory::{
+    create_anonymizer_with_context, create_deterministic_gpu_context, create_gpu_context,
+    create_multi_gpu_context, create_reference_anonymizer, AnonymizationStyle, BlurShape,
+};
+use faceguard_core::blurring::infrastructure::crowd_mode_blurrer::{
+    CrowdCoverage, CrowdModeBlurrer,
+};
+use faceguard_core::blurring::infrastructure::face_count_overlay::FaceCountOverlay;
+use faceguard_core::blurring::infrastructure::gpu_context::GpuContext;
+use faceguard_core::blurring::infrastructure::image_overlay_blurrer::ImageOverlayBlurrer;
+use faceguard_core::blurring::infrastructure::parity_checking_blurrer::{
+    ParityCheckingBlurrer, ParityReport,
+};
+#[cfg(feature = "plugins")]
+use faceguard_core::blurring::infrastructure::plugin_blurrer::EffectPlugin;
+use faceguard_core::blurring::infrastructure::static_redaction_blurrer::StaticRedactionBlurrer;
+use faceguard_core::blurring::infrastructure::text_redaction_blurrer::TextRedactionBlurrer;
+use faceguard_core::detection::domain::blur_target::BlurTarget;
+use faceguard_core::detection::domain::detection_zone::DetectionZone;
+use faceguard_core::detection::domain::detector_backend::DetectorBackend;
 use faceguard_core::detection::domain::face_detector::FaceDetector;
+use faceguard_core::detection::domain::face_embedder::FaceEmbedder;
 use faceguard_core::detection::domain::face_region_builder::FaceRegionBuilder;
+use faceguard_core::detection::domain::person_region_builder::{
+    PersonRegionBuilder, DEFAULT_PERSON_PADDING,
+};
 use faceguard_core::detection::domain::region_merger::RegionMerger;
 use faceguard_core::detection::domain::region_smoother::{RegionSmoother, DEFAULT_ALPHA};
+use faceguard_core::detection::domain::text_matcher::TextMatcher;
 use faceguard_core::detection::infrastructure::bytetrack_tracker::ByteTracker;
+use faceguard_core::detection::infrastructure::cache_key_params::{CacheKeyParam, CacheKeyParams};
+use faceguard_core::detection::infrastructure::cached_face_detector::CachedFaceDetector;
+use faceguard_core::detection::infrastructure::checkpointing_detector::CheckpointingDetector;
+use faceguard_core::detection::infrastructure::counting_face_detector::{
+    CountingFaceDetector, FaceCounts,
+};
+use faceguard_core::detection::infrastructure::detection_csv::write_detection_csv;
+use faceguard_core::detection::infrastructure::detection_disk_cache::DetectionDiskCache;
+use faceguard_core::detection::infrastructure::disk_backed_detections::DiskBackedDetections;
+use faceguard_core::detection::infrastructure::embedding_face_grouper::{
+    self, EmbeddingFaceGrouper,
+};
+use faceguard_core::detection::infrastructure::face_groups_file::{
+    read_face_groups, write_face_groups, GROUPS_FILE_NAME,
+};
+use faceguard_core::detection::infrastructure::histogram_face_grouper::HistogramFaceGrouper;
+use faceguard_core::detection::infrastructure::model_registry::{
+    ModelChoice, DEFAULT_DETECTION_MODEL, PERSON_DETECTION_MODEL,
+};
 use faceguard_core::detection::infrastructure::model_resolver;
-use faceguard_core::detection::infrastructure::onnx_yolo_detector::OnnxYoloDetector;
+use faceguard_core::detection::infrastructure::model_updates;
+use faceguard_core::detection::infrastructure::onnx_text_detector::OnnxTextDetector;
+use faceguard_core::detection::infrastructure::onnx_yolo_detector::{
+    session_input_size, ObservationLog, OnnxYoloDetector, SharedSession,
+};
+use faceguard_core::detection::infrastructure::recording_face_detector::RecordingFaceDetector;
 use faceguard_core::detection::infrastructure::skip_frame_detector::SkipFrameDetector;
+use faceguard_core::evaluation::domain::detection_score::DEFAULT_MATCH_IOU;
+use faceguard_core::identity::domain::identity_gallery::IdentityGallery;
+use faceguard_core::identity::infrastructure::allowlist_face_detector::AllowlistFaceDetector;
+use faceguard_core::identity::infrastructure::blocklist_face_detector::BlocklistFaceDetector;
+use faceguard_core::identity::infrastructure::identity_store;
+use faceguard_core::pipeline::anonymization_ledger::AnonymizationLedger;
 use faceguard_core::pipeline::blur_faces_use_case::BlurFacesUseCase;
 use faceguard_core::pipeline::blur_image_use_case::BlurImageUseCase;
+use faceguard_core::pipeline::blur_preset::BlurPreset;
+use faceguard_core::pipeline::blur_verification::{BlurVerificationReport, BlurVerifyingBlurrer};
+use faceguard_core::pipeline::detection_report::format_detection_report;
+use faceguard_core::pipeline::export_frame_use_case::ExportFrameUseCase;
+use faceguard_core::pipeline::face_selection::FaceSelection;
 use faceguard_core::pipeline::infrastructure::threaded_pipeline_executor::ThreadedPipelineExecutor;
+use faceguard_core::pipeline::parallel_segments_use_case::{
+    ParallelSegmentsUseCase, SegmentWorker,
+};
 use faceguard_core::pipeline::preview_faces_use_case::PreviewFacesUseCase;
+use faceguard_core::pipeline::resume_checkpoint::{ResumeCheckpoint, DEFAULT_CHECKPOINT_FRAMES};
+use faceguard_core::restoration::infrastructure::restoration_recording_blurrer::RestorationRecordingBlurrer;
+use faceguard_core::restoration::infrastructure::restoration_sidecar::RestorationSidecarWriter;
+use faceguard_core::shared::cancellation::{CancelReason, CancellationToken, Cancelled};
 use faceguard_core::shared::constants::{
-    IMAGE_EXTENSIONS, TRACKER_MAX_LOST, YOLO_MODEL_NAME, YOLO_MODEL_URL,
+    EMBEDDING_MODEL_NAME, EMBEDDING_MODEL_URL, IMAGE_EXTENSIONS, TEXT_DETECTION_MODEL_NAME,
+    TEXT_DETECTION_MODEL_URL, TEXT_RECOGNITION_MODEL_NAME, TEXT_RECOGNITION_MODEL_URL,
+    TRACKER_MAX_LOST, VIDEO_EXTENSIONS,
 };
+use faceguard_core::shared::cut_list::{CutList, RedactionAction};
+use faceguard_core::shared::forced_tracks::ForcedTracks;
+use faceguard_core::shared::job_error::JobError;
+use faceguard_core::shared::pixel_converter::PixelConverter;
+use faceguard_core::shared::time_map::TimeMap;
+use faceguard_core::shared::track_id_selection::{TrackIdSelection, TrackIdSpec};
+use faceguard_core::shared::track_time_rules::{parse_timestamp, TimeRange, TrackTimeRules};
+use faceguard_core::shared::warnings::Warnings;
+use faceguard_core::video::domain::decode_error_policy::DecodeErrorPolicy;
 use faceguard_core::video::domain::image_writer::ImageWriter;
+use faceguard_core::video::domain::video_codec::VideoCodec;
 use faceguard_core::video::domain::video_reader::VideoReader;
 use faceguard_core::video::domain::video_writer::VideoWriter;
+use faceguard_core::video::infrastructure::cut_range_reader::CutRangeReader;
+use faceguard_core::video::infrastructure::disk_space::{estimate_output_bytes, DiskSpaceCheck};
+use faceguard_core::video::infrastructure::fan_out_writer::FanOutWriter;
 use faceguard_core::video::infrastructure::ffmpeg_reader::FfmpegReader;
 use faceguard_core::video::infrastructure::ffmpeg_writer::FfmpegWriter;
+use faceguard_core::video::infrastructure::fps_converting_writer::{
+    FpsConversionMode, FpsConvertingWriter,
+};
 use faceguard_core::video::infrastructure::image_file_reader::ImageFileReader;
-use faceguard_core::video::infrastructure::image_file_writer::ImageFileWriter;
+use faceguard_core::video::infrastructure::image_file_writer::{
+    ImageFileWriter, ImageOutputFormat, PngCompression,
+};
+use faceguard_core::video::infrastructure::metadata_inspector::read_source_metadata;
+use faceguard_core::video::infrastructure::parted_writer::{PartWriterFactory, PartedWriter};
+use faceguard_core::video::infrastructure::segmented_output::{SegmentFormat, SegmentedOutput};
+
+mod bench;
+mod enroll;
+mod exit_status;
+mod inspect;
+mod restore;
+mod selftest;
+mod update_models;
+mod verify_ledger;
+
+use exit_status::{ExitStatus, InvalidArgs};
+
+/// Environment variable holding the restoration sidecar passphrase, so it
+/// stays out of shell history and process listings.
+const RESTORE_PASSPHRASE_ENV: &str = "FACEGUARD_RESTORE_PASSPHRASE";
+const LEDGER_PASSPHRASE_ENV: &str = "FACEGUARD_LEDGER_PASSPHRASE";
 
 /// Face detection and blurring for videos and images.
 #[derive(Parser)]
-#[command(name = "faceguard")]
+#[command(name = "faceguard", subcommand_negates_reqs = true)]
 struct Cli {
-    /// Input video or image file.
-    input: PathBuf,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Input video or image file. With --output-dir, a folder or a quoted
+    /// wildcard pattern such as 'clips/*.mp4'.
+    #[arg(required = true)]
+    input: Option<PathBuf>,
 
-    /// Output file (required unless --preview is used).
+    /// Output file (required unless --preview, --report, or --output-dir is used). A .m3u8 or .mpd
+    /// output writes an HLS or DASH playlist with segments beside it.
     output: Option<PathBuf>,
 
-    /// Face detection confidence threshold (0.0-1.0).
-    #[arg(long, default_value = "0.5")]
-    confidence: f64,
+    /// Blur every video and image in a folder or wildcard input into this folder, keeping file names.
+    #[arg(long, value_name = "DIR")]
+    output_dir: Option<PathBuf>,
 
-    /// Gaussian blur kernel size (must be odd).
-    #[arg(long, default_value = "201")]
-    blur_strength: usize,
+    /// Speed/protection preset: fast, balanced, or maximum. Sets the
+    /// detection interval, lookahead, blur strength, padding, uncertainty
+    /// growth, confidence, minimum track length, and --verify-blur; options
+    /// given explicitly override it.
+    #[arg(long, value_name = "PRESET")]
+    preset: Option<String>,
 
-    /// Blur shape: ellipse or rect.
-    #[arg(long, default_value = "ellipse")]
-    blur_shape: String,
+    #[command(flatten)]
+    detection: DetectionArgs,
+
+    #[command(flatten)]
+    blur: BlurArgs,
+
+    /// Raise --blur-strength to what the widest face in --detections needs, instead of only warning that it is too weak.
+    #[arg(long, requires = "detections")]
+    fit_blur_strength: bool,
+
+    /// What each region covers: face, head (face plus hair and ears), or person (the whole body, from a separate person detector).
+    #[arg(long, default_value = "face")]
+    blur_target: String,
+
+    /// Also blur a fixed rectangle: X,Y,W,H in pixels, optionally for frames START-END (e.g. 100,50,300,200@0-500). Repeatable.
+    #[arg(long, value_name = "X,Y,W,H[@START-END]")]
+    redact_rect: Vec<String>,
+
+    /// Also blur visible text: a keyword (case-insensitive), /REGEX/, or * for any text. Keywords and regexes read the text with an OCR model. Repeatable.
+    #[arg(long, value_name = "KEYWORD|/REGEX/|*")]
+    redact_text: Vec<String>,
+
+    /// Leave the faces of people enrolled with `faceguard enroll` unblurred; everyone else is blurred.
+    #[arg(long)]
+    allow_enrolled: bool,
+
+    /// Always blur this person enrolled with `faceguard enroll`, in every file, even where track selection, time ranges, or --allow-enrolled would leave them visible. Repeatable.
+    #[arg(long, value_name = "NAME")]
+    always_blur_person: Vec<String>,
+
+    /// Identities file for --allow-enrolled and --always-blur-person (default: identities.json in the FaceGuard data folder).
+    #[arg(long, value_name = "JSON")]
+    identities: Option<PathBuf>,
+
+    /// Blur frames with more regions than this as one region around the crowd (see --crowd-mode), keeping crowd scenes fast.
+    #[arg(long, value_name = "N")]
+    crowd_limit: Option<usize>,
+
+    /// What a frame over --crowd-limit is blurred as: area (one region around every face) or frame (the whole frame).
+    #[arg(long, default_value = "area")]
+    crowd_mode: String,
 
     /// Frames to look ahead for early face blur.
     #[arg(long, default_value = "10")]
@@ -59,25 +223,45 @@ struct Cli {
     #[arg(long, default_value = "2")]
     skip_frames: usize,
 
+    /// Ignore tracks seen on fewer frames than this as false positives (1 keeps every track).
+    #[arg(long, default_value = "3")]
+    min_track_frames: usize,
+
     /// Save face crops to directory instead of blurring.
     #[arg(long)]
     preview: Option<PathBuf>,
 
-    /// Only blur these track IDs (comma-separated).
-    #[arg(long, value_delimiter = ',')]
-    blur_ids: Option<Vec<u32>>,
+    /// Run detection only and write per-frame regions, track IDs, confidences, and landmarks to this JSON file; nothing is encoded.
+    #[arg(long, visible_alias = "dry-run", value_name = "JSON")]
+    report: Option<PathBuf>,
 
-    /// Blur all faces except these track IDs (comma-separated).
-    #[arg(long, value_delimiter = ',')]
-    exclude_ids: Option<Vec<u32>>,
+    /// Replay faces from a --report JSON file instead of detecting them, to blur the same scan with different settings.
+    #[arg(long, value_name = "JSON")]
+    detections: Option<PathBuf>,
 
-    /// Blur coverage: how far the blur extends beyond the face (0.0-1.0).
-    #[arg(long, default_value = "0.4")]
-    padding: f64,
+    /// Only blur these tracks: IDs, ranges (3-9), group labels (group:2), all, or none (comma-separated).
+    #[arg(long)]
+    blur_ids: Option<String>,
 
-    /// Shift blur center toward back of head for profile faces (-0.5 to 0.5).
-    #[arg(long, default_value = "0.0")]
-    center_offset: f64,
+    /// Blur all faces except these tracks (same syntax as --blur-ids).
+    #[arg(long)]
+    exclude_ids: Option<String>,
+
+    /// Preview folder from an earlier --preview run: enables group:N labels and checks IDs against the detected tracks.
+    #[arg(long)]
+    from_preview: Option<PathBuf>,
+
+    /// Face selection exported from the desktop app: blurs the faces selected there, in their time ranges. Detection settings must match the desktop scan's so track IDs do.
+    #[arg(long)]
+    selection: Option<PathBuf>,
+
+    /// Only blur a track during a time range: TRACK@START-END (e.g. 7@02:10-05:30).
+    #[arg(long, value_delimiter = ',')]
+    blur_track_range: Option<Vec<String>>,
+
+    /// Leave a track visible during a time range: TRACK@START-END.
+    #[arg(long, value_delimiter = ',')]
+    show_track_range: Option<Vec<String>>,
 
     /// H.264 CRF quality (0=lossless, 51=worst, default 18).
     #[arg(long)]
@@ -87,306 +271,2742 @@ struct Cli {
     #[arg(long, value_delimiter = ',')]
     audio_keywords: Option<Vec<String>>,
 
+    /// Also bleep words that sound like an --audio-keywords entry ("Mohammed" for "Muhammad"): strict, normal, or loose.
+    #[arg(long)]
+    phonetic_keywords: Option<String>,
+
+    /// Also bleep spoken personal information: comma-separated names, numbers, locations (enables audio processing).
+    #[arg(long, value_delimiter = ',')]
+    bleep_pii: Option<Vec<String>>,
+
+    /// Language of the speech for --audio-keywords and --bleep-pii: auto, or a code or name such as de or German.
+    #[arg(long, default_value = "auto")]
+    language: String,
+
     /// Voice disguise: off or on.
     #[arg(long, default_value = "off")]
     voice_disguise: String,
 
-    /// Bleep sound for censored words: tone or silence.
+    /// Seed for the voice disguise pitch contour (same seed, same audio).
+    #[arg(long)]
+    voice_seed: Option<u64>,
+
+    /// ONNX vocal-separation model: disguise only the voice it separates and keep music and ambience as they were.
+    #[arg(long)]
+    vocal_model: Option<PathBuf>,
+
+    /// What to do when audio processing fails after the video is encoded: fail, original, or silent.
+    #[arg(long, default_value = "fail")]
+    on_audio_error: String,
+
+    /// Bleep sound for censored words: tone, noise, or silence.
     #[arg(long, default_value = "tone")]
     bleep_sound: String,
-}
 
-fn main() {
-    env_logger::init();
+    /// Audio file to play over censored words instead of --bleep-sound, looped to fit.
+    #[arg(long)]
+    bleep_clip: Option<PathBuf>,
 
-    if let Err(e) = run() {
-        eprintln!("Error: {e}");
-        process::exit(1);
-    }
-}
+    /// Crossfade in milliseconds into and out of each bleep, to avoid clicks.
+    #[arg(long, default_value_t = 10)]
+    bleep_fade: u32,
 
-fn run() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
-    validate(&cli)?;
+    /// Limit voice disguise and bleeps to these ranges (e.g. 01:10-01:45,03:00-03:20).
+    #[arg(long)]
+    audio_ranges: Option<String>,
 
-    let detector = build_detector(&cli)?;
-    let blurrer = create_blurrer(parse_blur_shape(&cli.blur_shape), cli.blur_strength);
-    let input = cli.input;
-    let output = cli.output;
-    let lookahead = cli.lookahead;
-    let blur_ids = to_id_set(cli.blur_ids);
-    let exclude_ids = to_id_set(cli.exclude_ids);
-    let quality = cli.quality;
-    let audio_keywords = cli.audio_keywords;
-    let voice_disguise = cli.voice_disguise;
-    let bleep_sound = cli.bleep_sound;
+    /// Image output format: jpeg, png, webp, bmp, or tiff (default: from output extension).
+    #[arg(long)]
+    image_format: Option<String>,
 
-    if let Some(preview_dir) = cli.preview {
-        run_preview(&input, &preview_dir, detector)?;
-    } else if is_image(&input) {
-        run_image_blur(
-            &input,
-            output.as_ref().unwrap(),
-            detector,
-            blurrer,
-            blur_ids,
-            exclude_ids,
-        )?;
-    } else {
-        run_video_blur(
-            &input,
-            output.as_ref().unwrap(),
-            lookahead,
-            detector,
-            blurrer,
-            blur_ids,
-            exclude_ids,
-            quality,
-            &audio_keywords,
-            &voice_disguise,
-            &bleep_sound,
-        )?;
-    }
+    /// JPEG quality for image output (1-100, default 75).
+    #[arg(long)]
+    image_quality: Option<u8>,
 
-    Ok(())
-}
+    /// PNG compression for image output: fast, default, or best.
+    #[arg(long, default_value = "default")]
+    png_compression: String,
 
-fn run_preview(
-    input: &Path,
-    preview_dir: &Path,
-    detector: Box<dyn FaceDetector>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let mut reader = open_reader(input);
-    let metadata = reader.open(input)?;
-    let image_writer: Box<dyn ImageWriter> = Box::new(ImageFileWriter::new());
+    /// Write a per-frame detection CSV (frame, timestamp, faces, track IDs).
+    #[arg(long)]
+    detections_csv: Option<PathBuf>,
 
-    let progress: Box<dyn Fn(usize, usize) -> bool + Send> = Box::new(|current, total| {
-        eprint!("\rScanning frame {current}/{total}");
-        true
-    });
+    /// Burn a face-count badge into each output frame (debug aid).
+    #[arg(long)]
+    face_count_overlay: bool,
 
-    let mut use_case = PreviewFacesUseCase::new(reader, detector, image_writer, Some(progress));
-    let (crops, _cache) = use_case.execute(&metadata, preview_dir)?;
-    eprintln!();
-    log::info!(
-        "Saved {} face crops to {}",
-        crops.len(),
-        preview_dir.display()
-    );
-    Ok(())
-}
+    /// Rotate frames upright and write the output without rotation metadata.
+    #[arg(long)]
+    bake_rotation: bool,
 
-fn run_image_blur(
-    input: &Path,
-    output: &Path,
-    detector: Box<dyn FaceDetector>,
-    blurrer: Box<dyn FrameBlurrer>,
-    blur_ids: Option<HashSet<u32>>,
-    exclude_ids: Option<HashSet<u32>>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let reader: Box<dyn VideoReader> = Box::new(ImageFileReader::new());
-    let image_writer: Box<dyn ImageWriter> = Box::new(ImageFileWriter::new());
+    /// Decode on the GPU's video engine (VideoToolbox, D3D11VA/DXVA2, or VAAPI), falling back to software when unavailable.
+    #[arg(long)]
+    hwaccel: bool,
 
-    let mut use_case = BlurImageUseCase::new(
-        reader,
-        image_writer,
-        detector,
-        blurrer,
-        blur_ids,
-        exclude_ids,
-    );
-    use_case.execute(input, output)?;
-    log::info!("Output written to {}", output.display());
-    Ok(())
-}
+    /// Convert frames between YUV and RGB and resize them for detection on the GPU instead of the CPU.
+    #[arg(long)]
+    gpu_convert: bool,
 
-#[allow(clippy::too_many_arguments)]
-fn run_video_blur(
-    input: &Path,
-    output: &Path,
-    lookahead: usize,
-    detector: Box<dyn FaceDetector>,
-    blurrer: Box<dyn FrameBlurrer>,
-    blur_ids: Option<HashSet<u32>>,
-    exclude_ids: Option<HashSet<u32>>,
-    quality: Option<u32>,
-    audio_keywords: &Option<Vec<String>>,
-    voice_disguise: &str,
-    bleep_sound: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let mut reader: Box<dyn VideoReader> = Box::new(FfmpegReader::new());
-    let metadata = reader.open(input)?;
-    let has_audio = audio_keywords.is_some() || voice_disguise != "off";
-    let mut ffmpeg_writer = match quality {
-        Some(crf) => FfmpegWriter::new().with_crf(crf),
-        None => FfmpegWriter::new(),
-    };
-    if has_audio {
-        ffmpeg_writer.set_skip_audio_passthrough(true);
-    }
-    let writer: Box<dyn VideoWriter> = Box::new(ffmpeg_writer);
+    /// Run the detection model on auto (the platform's accelerator), cpu, cuda, coreml, or directml, falling back to the CPU when unavailable.
+    #[arg(long, default_value = "auto")]
+    detector_backend: String,
 
-    let total = metadata.total_frames;
-    let progress: Box<dyn Fn(usize, usize) -> bool + Send> = Box::new(move |current, _| {
-        eprint!("\rProcessing frame {current}/{total}");
-        true
-    });
+    /// Face detection model: yolo11n-face, yolov8n-face (fast on older machines), yolov8m-face (best with a GPU), scrfd-2.5g, scrfd-10g, or a path to an .onnx file with the YOLO output layout. Downloaded on first use.
+    #[arg(long, default_value = DEFAULT_DETECTION_MODEL)]
+    model: String,
 
-    let mut use_case = BlurFacesUseCase::new(
-        reader,
-        writer,
-        detector,
-        blurrer,
-        RegionMerger::new(),
-        Box::new(ThreadedPipelineExecutor::new()),
-        Some(lookahead),
-        blur_ids,
-        exclude_ids,
-        Some(progress),
-        None,
-    );
-    use_case.execute(&metadata, output)?;
-    eprintln!();
-    log::info!("Output written to {}", output.display());
+    /// Folder to read models from and download missing ones into, instead of the platform cache (or FACEGUARD_MODEL_DIR). Put the model files here beforehand for an install without network access.
+    #[arg(long, global = true, value_name = "DIR")]
+    model_dir: Option<PathBuf>,
 
-    // Audio processing (if keywords or voice disguise enabled)
-    if has_audio {
-        let transformer: Option<
-            Box<dyn faceguard_core::audio::domain::audio_transformer::AudioTransformer>,
-        > = match voice_disguise {
-            "on" => {
-                use faceguard_core::audio::infrastructure::formant_shift_transformer::DEFAULT_FORMANT_SHIFT_RATIO;
-                use faceguard_core::audio::infrastructure::pitch_shift_transformer::DEFAULT_SEMITONES;
-                use faceguard_core::audio::infrastructure::voice_morph_transformer::*;
-                Some(Box::new(VoiceMorphTransformer::new(
-                    DEFAULT_SEMITONES,
-                    DEFAULT_FORMANT_SHIFT_RATIO,
-                    DEFAULT_CONTOUR_WARP_RANGE,
-                )))
-            }
-            _ => None,
-        };
+    /// Download models even when no SHA-256 is recorded for them, so the file can't be checked (same as setting FACEGUARD_ALLOW_UNVERIFIED_MODELS=1).
+    #[arg(long, global = true)]
+    allow_unverified: bool,
 
-        let keywords = audio_keywords.clone().unwrap_or_default();
+    /// Apply an EDL or CSV cut list of blur-all/bleep/cut ranges.
+    #[arg(long)]
+    cut_list: Option<PathBuf>,
 
-        let bleep_mode = if bleep_sound == "silence" {
-            faceguard_core::audio::domain::word_censor::BleepMode::Silence
-        } else {
-            faceguard_core::audio::domain::word_censor::BleepMode::Tone
-        };
+    /// Also write an unblurred reference copy, frame-aligned with the output.
+    #[arg(long)]
+    reference_output: Option<PathBuf>,
 
-        let recognizer: Option<
-            Box<dyn faceguard_core::audio::domain::speech_recognizer::SpeechRecognizer>,
-        > = if !keywords.is_empty() {
-            use faceguard_core::audio::infrastructure::whisper_recognizer::WhisperRecognizer;
-            use faceguard_core::shared::constants::{WHISPER_MODEL_NAME, WHISPER_MODEL_URL};
+    /// Save the original pixels of every blurred face to this encrypted file, for `faceguard restore`. The passphrase is read from FACEGUARD_RESTORE_PASSPHRASE.
+    #[arg(long, value_name = "PATH")]
+    restore_sidecar: Option<PathBuf>,
 
-            log::info!("Resolving Whisper model: {WHISPER_MODEL_NAME}");
-            let whisper_path = model_resolver::resolve(
-                WHISPER_MODEL_NAME,
-                WHISPER_MODEL_URL,
-                None,
-                Some(Box::new(|downloaded, total| {
-                    if total > 0 {
-                        let pct = (downloaded as f64 / total as f64 * 100.0) as u32;
-                        eprint!("\rDownloading speech recognition model... {pct}%");
-                    }
-                })),
-            )?;
-            eprintln!();
-            Some(Box::new(WhisperRecognizer::new(&whisper_path)?))
-        } else {
-            None
-        };
+    /// Write an anonymization ledger to this JSON file: every track, whether it was blurred, and when it appears, for review without the footage.
+    #[arg(long, value_name = "PATH")]
+    ledger: Option<PathBuf>,
 
-        let use_case = faceguard_core::pipeline::process_audio_use_case::ProcessAudioUseCase::new(
-            Box::new(faceguard_core::video::infrastructure::ffmpeg_audio_reader::FfmpegAudioReader),
-            Box::new(faceguard_core::video::infrastructure::ffmpeg_audio_writer::FfmpegAudioWriter),
-            recognizer,
-            transformer,
-            keywords,
-            bleep_mode,
-        );
-        use_case.run(input, output)?;
-    }
+    /// Operator name recorded in the --ledger.
+    #[arg(long, requires = "ledger")]
+    operator: Option<String>,
 
-    Ok(())
-}
+    /// Sign the --ledger with the passphrase in FACEGUARD_LEDGER_PASSPHRASE, so later edits are detected by `faceguard verify-ledger`.
+    #[arg(long, requires = "ledger")]
+    sign_ledger: bool,
 
-fn build_detector(cli: &Cli) -> Result<Box<dyn FaceDetector>, Box<dyn std::error::Error>> {
-    log::info!("Resolving model: {YOLO_MODEL_NAME}");
-    let model_path = model_resolver::resolve(
-        YOLO_MODEL_NAME,
-        YOLO_MODEL_URL,
-        None,
-        Some(Box::new(download_progress)),
-    )?;
-    eprintln!();
+    /// Output video codec: h264, hevc, vp9, or av1. The output extension must suit it (.webm takes only vp9 and av1).
+    #[arg(long, default_value = "h264")]
+    codec: String,
 
-    let smoother = RegionSmoother::new(DEFAULT_ALPHA);
-    let region_builder =
-        FaceRegionBuilder::new(cli.padding, cli.center_offset, Some(Box::new(smoother)));
-    let tracker = ByteTracker::new(TRACKER_MAX_LOST);
-    let base: Box<dyn FaceDetector> = Box::new(OnnxYoloDetector::new(
-        &model_path,
-        region_builder,
-        tracker,
-        cli.confidence,
-    )?);
+    /// Also encode downscaled renditions by height (e.g. 720,480), written as <output>_720p.mp4.
+    #[arg(long, value_delimiter = ',')]
+    renditions: Option<Vec<u32>>,
 
-    if cli.skip_frames > 1 {
-        Ok(Box::new(SkipFrameDetector::new(base, cli.skip_frames)?))
-    } else {
-        Ok(base)
-    }
-}
+    /// Segment length in seconds for HLS (.m3u8) and DASH (.mpd) output.
+    #[arg(long, default_value = "6")]
+    segment_seconds: f64,
 
-fn validate(cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
-    if !cli.input.exists() {
-        return Err(format!("Input file not found: {}", cli.input.display()).into());
-    }
-    if cli.blur_ids.is_some() && cli.exclude_ids.is_some() {
-        return Err("--blur-ids and --exclude-ids are mutually exclusive".into());
-    }
-    if cli.preview.is_none() && cli.output.is_none() {
-        return Err("Output file is required unless --preview is used".into());
-    }
-    if cli.blur_strength == 0 || cli.blur_strength % 2 == 0 {
-        return Err(format!(
-            "Blur strength must be a positive odd integer, got {}",
-            cli.blur_strength
-        )
-        .into());
-    }
-    if !(0.0..=1.0).contains(&cli.confidence) {
-        return Err(format!(
-            "Confidence must be between 0.0 and 1.0, got {}",
-            cli.confidence
-        )
-        .into());
-    }
-    if let Some(q) = cli.quality {
-        if q > 51 {
-            return Err(format!("Quality must be between 0 and 51, got {q}").into());
-        }
+    /// Convert the output to this frame rate (e.g. 30 for a 60fps source).
+    #[arg(long)]
+    output_fps: Option<f64>,
+
+    /// Frame rate conversion: drop (drop/duplicate frames) or blend.
+    #[arg(long, default_value = "drop")]
+    fps_mode: String,
+
+    /// What to do with frames that fail to decode: fail, skip, or duplicate (repeat the previous frame).
+    #[arg(long, default_value = "fail", value_parser = parse_decode_error_policy)]
+    on_decode_error: DecodeErrorPolicy,
+
+    /// Reuse the previous output frame when nothing changed outside the blurred regions (fast for screen recordings and slideshows).
+    #[arg(long)]
+    reuse_unchanged: bool,
+
+    /// Blur on the GPU in fixed point, so output is bit-identical on every adapter and run.
+    #[arg(long)]
+    deterministic_gpu: bool,
+
+    /// Split each frame's faces across every GPU in the machine, not only the fastest.
+    #[arg(long)]
+    all_gpus: bool,
+
+    /// Compare GPU blur against the CPU every 30th frame and report adapters that disagree.
+    #[arg(long)]
+    verify_gpu: bool,
+
+    /// Re-run detection on about 1.5% of blurred frames and report faces still detectable inside a blurred region.
+    #[arg(long)]
+    verify_blur: bool,
+
+    /// Don't reuse or save detections in the on-disk detection cache.
+    #[arg(long)]
+    no_detection_cache: bool,
+
+    /// Size cap for the detection cache in MB; least recently used entries are deleted beyond it.
+    #[arg(long, default_value = "256")]
+    detection_cache_mb: u64,
+
+    /// Skip the check that the output folder has room for the export before starting.
+    #[arg(long)]
+    no_space_check: bool,
+
+    /// Save progress next to the output about every 1800 frames, and continue an interrupted export of the same input and options from its last checkpoint.
+    #[arg(long)]
+    resume: bool,
+
+    /// Split a long video into this many segments, blur them at the same time with a detector each, and join them without re-encoding. Track IDs restart in every segment, and --min-track-frames is ignored.
+    #[arg(long)]
+    parallel_segments: Option<usize>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Validate this install by running every subsystem on synthetic media.
+    Selftest,
+    /// Export the frame at a timestamp as an image, original or blurred.
+    Frame(FrameArgs),
+    /// List the metadata (GPS, device, timestamps, software) an export would strip.
+    Metadata(MetadataArgs),
+    /// Put the original faces back into a video blurred with --restore-sidecar.
+    Restore(RestoreArgs),
+    /// Summarize a detection report, detection CSV, or anonymization report without any media.
+    Inspect(InspectArgs),
+    /// Check for newer detection models and download them once confirmed. Never runs on its own.
+    UpdateModels(UpdateModelsArgs),
+    /// Register reference photos of a person whose face --allow-enrolled never blurs.
+    Enroll(EnrollArgs),
+    /// Check a signed --ledger against FACEGUARD_LEDGER_PASSPHRASE and list its decisions.
+    VerifyLedger(VerifyLedgerArgs),
+    /// Measure detection precision, recall, and latency on a folder of labeled images.
+    Bench(BenchArgs),
+}
+
+#[derive(clap::Args)]
+struct BenchArgs {
+    /// Folder with one WIDER FACE ground-truth .txt file and the images it lists (under images/ if present).
+    #[arg(long, value_name = "DIR")]
+    dataset: PathBuf,
+
+    /// Face detection model, as for blurring: a registered model ID or a path to an .onnx file.
+    #[arg(long, default_value = DEFAULT_DETECTION_MODEL)]
+    model: String,
+
+    /// Face detection confidence threshold (0.0-1.0).
+    #[arg(long, default_value = "0.5")]
+    confidence: f64,
+
+    /// Run detection every Nth image and extrapolate in between (1 = every image). Only meaningful when the images are consecutive frames of one clip.
+    #[arg(long, default_value = "1")]
+    skip_frames: usize,
+
+    /// Run the detection model on auto, cpu, cuda, coreml, or directml.
+    #[arg(long, default_value = "auto")]
+    detector_backend: String,
+
+    /// Overlap (IoU, 0.0-1.0) a detection needs with a labeled face to count as finding it.
+    #[arg(long, default_value_t = DEFAULT_MATCH_IOU)]
+    match_iou: f64,
+
+    /// Labeled faces narrower or shorter than this many pixels are neither counted as missed nor as found.
+    #[arg(long, default_value = "0", value_name = "PIXELS")]
+    min_face_size: u32,
+}
+
+#[derive(clap::Args)]
+struct VerifyLedgerArgs {
+    /// Ledger written with --ledger --sign-ledger.
+    ledger: PathBuf,
+}
+
+#[derive(clap::Args)]
+struct EnrollArgs {
+    /// Name to enroll the photos under; photos add to an existing enrollment.
+    #[arg(long, required_unless_present_any = ["list", "remove"])]
+    name: Option<String>,
+
+    /// Reference photos, each showing only that person's face.
+    #[arg(requires = "name")]
+    photos: Vec<PathBuf>,
+
+    /// List the enrolled people.
+    #[arg(long, conflicts_with_all = ["name", "remove"])]
+    list: bool,
+
+    /// Remove an enrolled person.
+    #[arg(long, value_name = "NAME", conflicts_with = "name")]
+    remove: Option<String>,
+
+    /// Identities file (default: identities.json in the FaceGuard data folder).
+    #[arg(long, value_name = "JSON")]
+    identities: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+struct UpdateModelsArgs {
+    /// Only list the available updates and their changelogs.
+    #[arg(long)]
+    check: bool,
+
+    /// Install the updates without asking.
+    #[arg(long, conflicts_with = "check")]
+    yes: bool,
+}
+
+#[derive(clap::Args)]
+struct InspectArgs {
+    /// Report written by --report, --detections-csv, or the desktop app's export.
+    file: PathBuf,
+}
+
+#[derive(clap::Args)]
+struct RestoreArgs {
+    /// Blurred video written with --restore-sidecar.
+    input: PathBuf,
+
+    /// Restoration sidecar written with it. The passphrase is read from FACEGUARD_RESTORE_PASSPHRASE.
+    #[arg(long)]
+    sidecar: PathBuf,
+
+    /// Output video with the original faces.
+    #[arg(long)]
+    out: PathBuf,
+}
+
+#[derive(clap::Args)]
+struct MetadataArgs {
+    /// Input video or image file.
+    input: PathBuf,
+}
+
+#[derive(clap::Args)]
+struct FrameArgs {
+    /// Input video file.
+    input: PathBuf,
+
+    /// Timestamp of the frame: SS, MM:SS, or HH:MM:SS (fractional seconds allowed).
+    #[arg(long, value_parser = parse_timestamp)]
+    at: f64,
+
+    /// Output image file; its extension picks the format.
+    #[arg(long)]
+    out: PathBuf,
+
+    /// Blur faces in the exported frame.
+    #[arg(long)]
+    blurred: bool,
+
+    #[command(flatten)]
+    detection: DetectionArgs,
+
+    #[command(flatten)]
+    blur: BlurArgs,
+
+    /// Frames after the timestamp whose faces are also blurred.
+    #[arg(long, default_value = "10")]
+    lookahead: usize,
+}
+
+/// Detection options shared by a blur run and `faceguard frame`.
+#[derive(clap::Args)]
+struct DetectionArgs {
+    /// Face detection confidence threshold (0.0-1.0), on the calibrated
+    /// scale shared by all detection models.
+    #[arg(long, default_value = "0.5")]
+    confidence: f64,
+
+    /// Blur coverage: how far the blur extends beyond the face (0.0-1.0).
+    #[arg(long, default_value = "0.4")]
+    padding: f64,
+
+    /// Shift blur center toward back of head for profile faces (-0.5 to 0.5).
+    #[arg(long, default_value = "0.0")]
+    center_offset: f64,
+
+    /// Grow regions by up to this fraction while detection is uncertain (0.0-1.0, 0 disables).
+    #[arg(long, default_value = "0.25")]
+    uncertainty_growth: f64,
+
+    /// Only detect faces inside this zone: X,Y,W,H as fractions of the frame (e.g. 0.6,0.1,0.3,0.8 for a doorway on the right). The model sees just the rectangle around all zones, and faces centered outside every zone are not blurred. For fixed cameras. Repeatable.
+    #[arg(long, value_name = "X,Y,W,H")]
+    detect_zone: Vec<String>,
+}
+
+/// How faces are covered, shared by a blur run and `faceguard frame`.
+#[derive(clap::Args)]
+struct BlurArgs {
+    /// Gaussian blur kernel size (must be odd).
+    #[arg(long, default_value = "201")]
+    blur_strength: usize,
+
+    /// Blur shape: ellipse or rect.
+    #[arg(long, default_value = "ellipse")]
+    blur_shape: String,
+
+    /// Fade an ellipse's blur into the picture over this outer fraction of its radius (0.0-1.0, 0 is a hard edge).
+    #[arg(long, default_value = "0.0")]
+    blur_feather: f32,
+
+    /// How faces are hidden: blur, pixelate, or solid (a --fill-color fill).
+    #[arg(long, default_value = "blur")]
+    style: String,
+
+    /// Color for --style solid, and behind transparent parts of
+    /// --overlay-image, as RRGGBB hex.
+    #[arg(long, default_value = "000000")]
+    fill_color: String,
+
+    /// Image (e.g. a PNG emoji) stretched over each face instead of a blur.
+    #[arg(long)]
+    overlay_image: Option<PathBuf>,
+
+    /// Effect plugin (a .so, .dylib, or .dll exporting faceguard_effect_abi_version and faceguard_effect_create) that covers each face instead of a blur. Runs with this program's privileges; only load plugins you trust. Needs a build with the `plugins` feature.
+    #[arg(long, value_name = "LIBRARY")]
+    effect_plugin: Option<PathBuf>,
+
+    /// Configuration text handed to --effect-plugin, in whatever format the plugin reads.
+    #[arg(long, default_value = "", value_name = "TEXT")]
+    effect_config: String,
+}
+
+fn main() -> ExitCode {
+    env_logger::init();
+    let started = Instant::now();
+
+    let (status, message) = match run() {
+        Ok(()) => (ExitStatus::Success, None),
+        Err(e) => {
+            match e.downcast_ref::<clap::Error>() {
+                Some(usage) => {
+                    let _ = usage.print();
+                }
+                None => eprintln!("Error: {e}"),
+            }
+            (ExitStatus::of(&*e), Some(e.to_string()))
+        }
+    };
+    eprintln!(
+        "{}",
+        exit_status::summary_line(status, started.elapsed(), message.as_deref())
+    );
+    ExitCode::from(status.code())
+}
+
+/// Cancels the returned token on Ctrl-C or SIGTERM, so a running job stops
+/// between frames and exits with the cancelled status. A second signal
+/// exits at once.
+fn cancel_on_signal() -> CancellationToken {
+    let cancelled = CancellationToken::new();
+    let token = cancelled.clone();
+    let installed = ctrlc::set_handler(move || {
+        if token.is_cancelled() {
+            std::process::exit(ExitStatus::Cancelled.code().into());
+        }
+        eprintln!("\nStopping; press Ctrl-C again to quit at once");
+        token.cancel(CancelReason::User);
+    });
+    if let Err(e) = installed {
+        log::warn!("Could not listen for Ctrl-C: {e}");
+    }
+    cancelled
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = match Cli::command().try_get_matches() {
+        Ok(matches) => matches,
+        // --help and --version are not failures.
+        Err(e) if !e.use_stderr() => e.exit(),
+        Err(e) => return Err(e.into()),
+    };
+    let mut cli = Cli::from_arg_matches(&matches)?;
+    let model_dir = match &cli.model_dir {
+        Some(dir) if !dir.is_dir() => {
+            return Err(
+                InvalidArgs(format!("Model folder not found: {}", dir.display()).into()).into(),
+            );
+        }
+        Some(dir) => dir.clone(),
+        None => model_resolver::model_cache_dir()?,
+    };
+    if cli.allow_unverified {
+        std::env::set_var(model_resolver::ALLOW_UNVERIFIED_ENV, "1");
+    }
+    let cancelled = cancel_on_signal();
+    match &cli.command {
+        Some(Command::Selftest) => return selftest::run(&model_dir),
+        Some(Command::Frame(args)) => return run_frame_export(args, &model_dir),
+        Some(Command::Metadata(args)) => return run_metadata_report(args),
+        Some(Command::Restore(args)) => return restore::run(args),
+        Some(Command::Inspect(args)) => return inspect::run(args),
+        Some(Command::UpdateModels(args)) => {
+            return update_models::run(args, &model_dir, &cancelled)
+        }
+        Some(Command::Enroll(args)) => return enroll::run(args, &model_dir),
+        Some(Command::VerifyLedger(args)) => return verify_ledger::run(args),
+        Some(Command::Bench(args)) => return bench::run(args, &model_dir),
+        None => {}
+    }
+    apply_preset(&mut cli, &matches).map_err(InvalidArgs)?;
+    validate(&cli).map_err(InvalidArgs)?;
+    check_replayed_blur_strength(&mut cli)?;
+
+    let yolo = YoloSession::new(detection_model(&cli), model_dir.clone())
+        .with_backend(detector_backend(&cli));
+    let text = TextSessions::for_patterns(&cli.redact_text, model_dir.clone())?;
+    let identities = if cli.allow_enrolled || !cli.always_blur_person.is_empty() {
+        Some(EnrolledIdentities::load(
+            &model_dir,
+            cli.identities.as_deref(),
+            cli.allow_enrolled,
+            &cli.always_blur_person,
+        )?)
+    } else {
+        None
+    };
+    let gpu_context = if cli.all_gpus {
+        create_multi_gpu_context(cli.deterministic_gpu)
+    } else if cli.deterministic_gpu {
+        create_deterministic_gpu_context()
+    } else {
+        create_gpu_context()
+    };
+    if let Some(ref ctx) = gpu_context {
+        if cli.all_gpus {
+            log::info!(
+                "Blurring on {} GPUs, led by {}",
+                ctx.adapter_count(),
+                ctx.adapter_name()
+            );
+        }
+    }
+    let input = cli.input.clone().ok_or("Input file is required")?;
+    match cli.output_dir {
+        Some(ref output_dir) => run_batch(
+            &cli,
+            &input,
+            output_dir,
+            &yolo,
+            &text,
+            identities.as_ref(),
+            gpu_context,
+            &cancelled,
+        ),
+        None => process_input(
+            &cli,
+            &input,
+            cli.output.as_deref(),
+            &yolo,
+            &text,
+            identities.as_ref(),
+            gpu_context,
+            &cancelled,
+        ),
+    }
+}
+
+/// Replaces the defaults of the options bundled by `--preset` with the
+/// preset's values. Options given on the command line keep their value.
+fn apply_preset(cli: &mut Cli, matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(ref name) = cli.preset else {
+        return Ok(());
+    };
+    let preset = BlurPreset::parse(name)
+        .ok_or_else(|| format!("Unknown preset '{name}' (expected fast, balanced, or maximum)"))?;
+    let settings = preset.settings();
+    let defaulted = |id: &str| matches.value_source(id) != Some(ValueSource::CommandLine);
+
+    if defaulted("skip_frames") {
+        cli.skip_frames = settings.detection_interval;
+    }
+    if defaulted("lookahead") {
+        cli.lookahead = settings.lookahead;
+    }
+    if defaulted("blur_strength") {
+        cli.blur.blur_strength = settings.blur_strength;
+    }
+    if defaulted("padding") {
+        cli.detection.padding = settings.padding;
+    }
+    if defaulted("uncertainty_growth") {
+        cli.detection.uncertainty_growth = settings.uncertainty_growth;
+    }
+    if defaulted("confidence") {
+        cli.detection.confidence = settings.confidence;
+    }
+    if defaulted("min_track_frames") {
+        cli.min_track_frames = settings.min_track_frames;
+    }
+    // Leak checks only run on blurred videos; the preset enables them
+    // there without making image and detect-only runs an error.
+    let blurs_video = !detect_only(cli) && !cli.input.as_deref().is_some_and(is_image);
+    cli.verify_blur |= settings.verify_blur && blurs_video;
+    Ok(())
+}
+
+/// Blurs every supported file of a folder or wildcard input into
+/// `output_dir`. The model sessions and GPU context are built once for the
+/// whole batch; a file that fails is reported and the rest still run.
+/// Cancelling stops the whole batch.
+#[allow(clippy::too_many_arguments)]
+fn run_batch(
+    cli: &Cli,
+    input: &Path,
+    output_dir: &Path,
+    yolo: &YoloSession,
+    text: &TextSessions,
+    identities: Option<&EnrolledIdentities>,
+    gpu_context: Option<Arc<GpuContext>>,
+    cancelled: &CancellationToken,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let files = batch_inputs(input)?;
+    if files.is_empty() {
+        return Err(format!("No videos or images found in {}", input.display()).into());
+    }
+    std::fs::create_dir_all(output_dir).map_err(JobError::output)?;
+    let mut failed = 0;
+    let mut first_status = None;
+    for (i, file) in files.iter().enumerate() {
+        eprintln!("[{}/{}] {}", i + 1, files.len(), file.display());
+        let output = output_dir.join(file.file_name().unwrap_or_default());
+        if let Err(e) = process_input(
+            cli,
+            file,
+            Some(&output),
+            yolo,
+            text,
+            identities,
+            gpu_context.clone(),
+            cancelled,
+        ) {
+            if Cancelled::reason_of(&*e).is_some() {
+                return Err(e);
+            }
+            eprintln!("Error: {}: {e}", file.display());
+            first_status.get_or_insert(ExitStatus::of(&*e));
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        // The batch exits with the status of its first failure.
+        let message = format!("{failed} of {} files failed", files.len());
+        return Err(match first_status {
+            Some(ExitStatus::InputError) => JobError::input(message).into(),
+            Some(ExitStatus::OutputError) => JobError::output(message).into(),
+            _ => message.into(),
+        });
+    }
+    log::info!(
+        "Blurred {} files into {}",
+        files.len(),
+        output_dir.display()
+    );
+    Ok(())
+}
+
+/// Processes one input: a detection report, a preview, or a blur into
+/// `output`, as the options ask.
+#[allow(clippy::too_many_arguments)]
+fn process_input(
+    cli: &Cli,
+    input: &Path,
+    output: Option<&Path>,
+    yolo: &YoloSession,
+    text: &TextSessions,
+    identities: Option<&EnrolledIdentities>,
+    gpu_context: Option<Arc<GpuContext>>,
+    cancelled: &CancellationToken,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let observations = cli.report.is_some().then(ObservationLog::default);
+    let converter = pixel_converter(cli, gpu_context.as_ref());
+    let disk_cache = open_detection_cache(cli, yolo, input)?;
+    let cached = disk_cache
+        .as_ref()
+        .and_then(|(cache, key)| cache.load_streamed(key, DiskBackedDetections::new()));
+    let cache_hit = cached.is_some();
+    let resume = match output {
+        Some(output) if cli.resume && !is_image(input) && !detect_only(cli) => {
+            Some(open_resume(input, output)?)
+        }
+        _ => None,
+    };
+    let detector: Box<dyn FaceDetector> = match cached {
+        Some(detections) => {
+            log::info!("Reusing cached detections for {} frames", detections.len());
+            Box::new(CachedFaceDetector::disk_backed(detections))
+        }
+        None => match cli.detections {
+            Some(ref path) => {
+                log::info!("Replaying detections from {}", path.display());
+                Box::new(CachedFaceDetector::from_json_file(path)?)
+            }
+            None => build_detector(cli, yolo, observations.clone(), converter.clone())?,
+        },
+    };
+    // A resumed export only detects the frames after its checkpoint.
+    let resuming = resume.as_ref().is_some_and(|r| r.checkpoint.is_some());
+    let pending_store = disk_cache.filter(|_| !cache_hit && !resuming);
+    let (detector, detection_log) =
+        if cli.detections_csv.is_some() || cli.ledger.is_some() || pending_store.is_some() {
+            let recorder = RecordingFaceDetector::new(detector);
+            let recorded = recorder.log();
+            (Box::new(recorder) as Box<dyn FaceDetector>, Some(recorded))
+        } else {
+            (detector, None)
+        };
+    // After the recorder, so caches and CSVs keep every face.
+    let (mut detector, forced_tracks) = match identities {
+        Some(identities) => identities.wrap(detector),
+        None => (detector, ForcedTracks::new()),
+    };
+    let blur_shape = parse_blur_shape(&cli.blur.blur_shape);
+    let style = anonymization_style(
+        &cli.blur.style,
+        &cli.blur.fill_color,
+        cli.blur.overlay_image.as_deref(),
+        cli.blur.effect_plugin.as_deref(),
+        &cli.blur.effect_config,
+    )?;
+    let mut blurrer = create_anonymizer_with_context(
+        style.clone(),
+        blur_shape,
+        cli.blur.blur_strength,
+        cli.blur.blur_feather,
+        gpu_context.clone(),
+    );
+    // Rectangles and text are blurred whole, so their blurrers are rectangular.
+    let redactor = || {
+        create_anonymizer_with_context(
+            style.clone(),
+            BlurShape::Rectangular,
+            cli.blur.blur_strength,
+            0.0,
+            gpu_context.clone(),
+        )
+    };
+    let rect_redactor = (!cli.redact_rect.is_empty()).then(redactor);
+    let text_redactor = text.matcher.as_ref().map(|_| redactor());
+    let parity = match (&gpu_context, cli.verify_gpu) {
+        (Some(ctx), true) => {
+            let checker = ParityCheckingBlurrer::new(
+                blurrer,
+                create_reference_anonymizer(
+                    style,
+                    blur_shape,
+                    cli.blur.blur_strength,
+                    cli.blur.blur_feather,
+                ),
+                ctx.adapter_name(),
+            );
+            let report = checker.report();
+            blurrer = Box::new(checker);
+            Some((ctx.adapter_name().to_string(), report))
+        }
+        (None, true) => {
+            log::info!("No GPU adapter found; --verify-gpu has nothing to check");
+            None
+        }
+        _ => None,
+    };
+    if cli.face_count_overlay {
+        (detector, blurrer) = face_count_overlay(detector, blurrer);
+    }
+    if let Some(redactor) = rect_redactor {
+        let rects = parse_redact_rects(&cli.redact_rect)?;
+        blurrer = Box::new(StaticRedactionBlurrer::new(blurrer, redactor, rects));
+    }
+    if let (Some(redactor), Some(matcher)) = (text_redactor, &text.matcher) {
+        blurrer = Box::new(TextRedactionBlurrer::new(
+            blurrer,
+            redactor,
+            Box::new(text.detector()?),
+            matcher.clone(),
+        ));
+    }
+    let verification = if cli.verify_blur {
+        // Leaks are faces, whatever the regions cover.
+        let face_model;
+        let faces = if !blur_target(&cli).detects_people() {
+            yolo
+        } else {
+            face_model = YoloSession::new(ModelChoice::default(), yolo.model_dir.clone())
+                .with_backend(yolo.backend);
+            &face_model
+        };
+        let verifier = BlurVerifyingBlurrer::new(
+            blurrer,
+            build_verification_detector(faces, cli.detection.confidence)?,
+        );
+        let report = verifier.report();
+        blurrer = Box::new(verifier);
+        Some(report)
+    } else {
+        None
+    };
+    let image_writer = build_image_writer(cli);
+    let format_override = image_format(cli);
+    let output = match (output, format_override) {
+        (Some(path), Some(format)) if is_image(input) => {
+            Some(path.with_extension(format.extension()))
+        }
+        (path, _) => path.map(Path::to_path_buf),
+    };
+    // The first segment uses the detector and blurrer above.
+    let segment_workers = match cli.parallel_segments {
+        Some(segments) if !is_image(input) => (1..segments)
+            .map(|_| {
+                build_segment_worker(
+                    build_detector(cli, yolo, None, converter.clone())?,
+                    cli,
+                    gpu_context.clone(),
+                )
+            })
+            .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?,
+        _ => Vec::new(),
+    };
+    let lookahead = cli.lookahead;
+    let selection = match cli.selection {
+        Some(ref path) => Some(load_selection(cli, yolo, path, input)?),
+        None => None,
+    };
+    let (blur_ids, exclude_ids) = match selection {
+        Some(ref selection) => (Some(selection.blur_ids()), None),
+        None => resolve_id_filters(cli)?,
+    };
+    let quality = cli.quality;
+    let audio_keywords = &cli.audio_keywords;
+    let bleep_pii: Vec<PiiCategory> = cli
+        .bleep_pii
+        .iter()
+        .flatten()
+        .filter_map(|category| PiiCategory::parse(category))
+        .collect();
+    let keyword_matcher = keyword_matcher(cli)?;
+    let language = SpeechLanguage::parse(&cli.language)?;
+    let voice_disguise = &cli.voice_disguise;
+    let voice_seed = cli.voice_seed.unwrap_or(DEFAULT_VOICE_MORPH_SEED);
+    let audio_failure_policy = AudioFailurePolicy::parse(&cli.on_audio_error).unwrap_or_default();
+    let bleep_style = bleep_style(cli)?;
+    let bleep_fade = cli.bleep_fade as f64 / 1000.0;
+    let bake_rotation = cli.bake_rotation;
+    let mut time_rules = match selection {
+        Some(ref selection) => selection.time_rules(),
+        None => build_time_rules(&cli.blur_track_range, &cli.show_track_range)?,
+    };
+    let cut_list = match cli.cut_list {
+        Some(ref path) => {
+            let fps = open_reader(input, false, false)
+                .open(input)
+                .map_err(|e| JobError::input(e.to_string()))?
+                .fps;
+            CutList::load(path, fps)?
+        }
+        None => CutList::default(),
+    };
+    for range in cut_list.ranges(RedactionAction::BlurAll) {
+        time_rules.blur_all_during(range);
+    }
+    if cli.restore_sidecar.is_some() && !cut_list.ranges(RedactionAction::Cut).is_empty() {
+        return Err("--restore-sidecar can't be used with cut ranges, which drop frames".into());
+    }
+    let restoration = match cli.restore_sidecar {
+        Some(ref path) => {
+            let writer = RestorationSidecarWriter::create(path, &restore_passphrase()?)?;
+            let recorder = RestorationRecordingBlurrer::new(blurrer, writer);
+            let sidecar = recorder.sidecar();
+            blurrer = Box::new(recorder);
+            Some((path, sidecar))
+        }
+        None => None,
+    };
+    // Outermost, so the sidecar and verification see the crowd region that
+    // is actually blurred.
+    let crowd_frames = match cli.crowd_limit {
+        Some(limit) => {
+            let coverage = CrowdCoverage::parse(&cli.crowd_mode).unwrap_or_default();
+            let crowd = CrowdModeBlurrer::new(blurrer, limit).with_coverage(coverage);
+            let frames = crowd.crowd_frames();
+            blurrer = Box::new(crowd);
+            Some(frames)
+        }
+        None => None,
+    };
+    let audio_ranges = match cli.audio_ranges {
+        Some(ref spec) => TimeRange::parse_list(spec)?,
+        None => Vec::new(),
+    };
+    let ledger_decisions = cli
+        .ledger
+        .as_ref()
+        .map(|_| (blur_ids.clone(), exclude_ids.clone(), forced_tracks.clone()));
+
+    if let Some(ref report_path) = cli.report {
+        run_detection_report(
+            input,
+            report_path,
+            detector,
+            observations.unwrap_or_default(),
+            bake_rotation,
+            cli.hwaccel,
+            blurs_with_gaussian(cli).then_some(cli.blur.blur_strength),
+            cancelled,
+        )?;
+    } else if let Some(ref preview_dir) = cli.preview {
+        run_preview(
+            input,
+            preview_dir,
+            detector,
+            bake_rotation,
+            cli.hwaccel,
+            cli.min_track_frames,
+            cancelled,
+        )?;
+    } else if is_image(input) {
+        run_image_blur(
+            input,
+            output.as_ref().unwrap(),
+            image_writer,
+            detector,
+            blurrer,
+            blur_ids,
+            exclude_ids,
+            forced_tracks,
+        )?;
+    } else {
+        if !cli.no_space_check {
+            // Segmented output is packaged from an intermediate copy, and
+            // resumable and parallel output joined from parts, which need
+            // the same room as a remux.
+            let audio_remux = cli.resume
+                || cli.parallel_segments.is_some()
+                || audio_keywords.is_some()
+                || !bleep_pii.is_empty()
+                || voice_disguise != "off"
+                || !cut_list.ranges(RedactionAction::Bleep).is_empty()
+                || !cut_list.ranges(RedactionAction::Cut).is_empty()
+                || SegmentFormat::from_path(output.as_ref().unwrap()).is_some();
+            check_disk_space(
+                input,
+                output.as_ref().unwrap(),
+                cli.renditions.as_deref().unwrap_or_default(),
+                cli.reference_output.as_deref(),
+                audio_remux,
+            )?;
+        }
+        run_video_blur(
+            input,
+            output.as_ref().unwrap(),
+            lookahead,
+            detector,
+            blurrer,
+            blur_ids,
+            exclude_ids,
+            forced_tracks,
+            quality,
+            VideoCodec::parse(&cli.codec).unwrap_or_default(),
+            audio_keywords,
+            keyword_matcher,
+            &bleep_pii,
+            language,
+            voice_disguise,
+            voice_seed,
+            cli.vocal_model.as_deref(),
+            &yolo.model_dir,
+            audio_failure_policy,
+            bleep_style,
+            bleep_fade,
+            bake_rotation,
+            cli.hwaccel,
+            converter,
+            time_rules,
+            audio_ranges,
+            &cut_list,
+            cli.reference_output.as_deref(),
+            cli.renditions.as_deref().unwrap_or_default(),
+            cli.segment_seconds,
+            cli.output_fps
+                .map(|fps| (fps, parse_fps_mode(&cli.fps_mode))),
+            cli.on_decode_error,
+            cli.reuse_unchanged,
+            cli.min_track_frames,
+            resume,
+            segment_workers,
+            cancelled,
+        )?;
+    }
+
+    if let Some(frames) = crowd_frames {
+        let frames = frames.load(std::sync::atomic::Ordering::Relaxed);
+        if frames > 0 {
+            log::info!("Crowd mode blurred {frames} frames as one region");
+        }
+    }
+
+    if let Some((adapter, report)) = parity {
+        let report = report.lock().map_err(|_| "Parity report poisoned")?;
+        print_parity_report(&adapter, &report);
+    }
+
+    if let Some(report) = verification {
+        let report = report
+            .lock()
+            .map_err(|_| "Blur verification report poisoned")?;
+        print_verification_report(&report);
+    }
+
+    if let Some((path, sidecar)) = restoration {
+        let mut sidecar = sidecar.lock().map_err(|_| "Restoration sidecar poisoned")?;
+        let frames = sidecar.finish()?;
+        log::info!(
+            "Original faces of {frames} frames saved to {}",
+            path.display()
+        );
+    }
+
+    if let (Some((cache, key)), Some(recorded)) = (&pending_store, &detection_log) {
+        let mut detections = recorded.lock().map_err(|_| "Detection log poisoned")?;
+        if let Err(e) = cache.store_streamed(key, &mut detections) {
+            log::warn!("Could not save detections to the cache: {e}");
+        }
+    }
+
+    if let (Some(path), Some(recorded), Some((blur_ids, exclude_ids, forced))) =
+        (&cli.ledger, &detection_log, ledger_decisions)
+    {
+        let mut detections = recorded.lock().map_err(|_| "Detection log poisoned")?;
+        let detections = detections.to_map()?;
+        let blurred: HashSet<u32> = detections
+            .values()
+            .flatten()
+            .filter_map(|region| region.track_id)
+            .filter(|&id| {
+                forced.contains(id)
+                    || (blur_ids.as_ref().map_or(true, |ids| ids.contains(&id))
+                        && !exclude_ids.as_ref().is_some_and(|ids| ids.contains(&id)))
+            })
+            .collect();
+        let fps = open_reader(input, bake_rotation, false)
+            .open(input)
+            .map_err(|e| JobError::input(e.to_string()))?
+            .fps;
+        let joined = |specs: &Option<Vec<String>>| specs.as_deref().unwrap_or_default().join(",");
+        let ledger = AnonymizationLedger::new(
+            input,
+            output.unwrap(),
+            cli.operator.as_deref().unwrap_or_default(),
+        )
+        .with_setting("blur_target", &cli.blur_target)
+        .with_setting("confidence", cli.detection.confidence)
+        .with_setting("style", &cli.blur.style)
+        .with_setting("blur_shape", &cli.blur.blur_shape)
+        .with_setting("blur_strength", cli.blur.blur_strength)
+        .with_setting("blur_ids", cli.blur_ids.as_deref().unwrap_or("all"))
+        .with_setting(
+            "exclude_ids",
+            cli.exclude_ids.as_deref().unwrap_or_default(),
+        )
+        .with_setting("blur_track_range", joined(&cli.blur_track_range))
+        .with_setting("show_track_range", joined(&cli.show_track_range))
+        .with_tracks(&detections, fps, &HashMap::new(), &blurred);
+        let passphrase = if cli.sign_ledger {
+            Some(ledger_passphrase()?)
+        } else {
+            None
+        };
+        std::fs::write(path, ledger.to_json(passphrase.as_deref())?)?;
+        log::info!("Ledger written to {}", path.display());
+    }
+
+    if let (Some(csv_path), Some(recorded)) = (&cli.detections_csv, detection_log) {
+        let metadata = open_reader(input, bake_rotation, false)
+            .open(input)
+            .map_err(|e| JobError::input(e.to_string()))?;
+        let mut detections = recorded.lock().map_err(|_| "Detection log poisoned")?;
+        write_detection_csv(
+            csv_path,
+            &mut detections,
+            metadata.fps,
+            metadata.total_frames,
+        )?;
+        log::info!("Detection CSV written to {}", csv_path.display());
+    }
+
+    Ok(())
+}
+
+fn run_preview(
+    input: &Path,
+    preview_dir: &Path,
+    detector: Box<dyn FaceDetector>,
+    bake_rotation: bool,
+    hw_accel: bool,
+    min_track_frames: usize,
+    cancelled: &CancellationToken,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut reader = open_reader(input, bake_rotation, hw_accel);
+    let metadata = reader
+        .open(input)
+        .map_err(|e| JobError::input(e.to_string()))?;
+    let image_writer: Box<dyn ImageWriter> = Box::new(ImageFileWriter::new());
+
+    let progress: Box<dyn Fn(usize, usize) -> bool + Send> = Box::new(|current, total| {
+        eprint!("\rScanning frame {current}/{total}");
+        true
+    });
+
+    let mut use_case = PreviewFacesUseCase::new(reader, detector, image_writer, Some(progress))
+        .with_grouper(Box::new(HistogramFaceGrouper::default()))
+        .with_min_track_frames(min_track_frames)
+        .with_cancellation(cancelled.clone());
+    let (crops, _cache) = use_case.execute(&metadata, preview_dir)?;
+    eprintln!();
+    log::info!(
+        "Saved {} face crops to {}",
+        crops.len(),
+        preview_dir.display()
+    );
+    let groups = use_case.take_groups();
+    let groups_path = preview_dir.join(GROUPS_FILE_NAME);
+    write_face_groups(&groups_path, &groups)?;
+    log::info!(
+        "Wrote {} face groups to {}",
+        groups.len(),
+        groups_path.display()
+    );
+    Ok(())
+}
+
+fn run_detection_report(
+    input: &Path,
+    report_path: &Path,
+    detector: Box<dyn FaceDetector>,
+    observations: ObservationLog,
+    bake_rotation: bool,
+    hw_accel: bool,
+    blur_strength: Option<usize>,
+    cancelled: &CancellationToken,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut reader = open_reader(input, bake_rotation, hw_accel);
+    let metadata = reader
+        .open(input)
+        .map_err(|e| JobError::input(e.to_string()))?;
+
+    let progress: Box<dyn Fn(usize, usize) -> bool + Send> = Box::new(|current, total| {
+        eprint!("\rScanning frame {current}/{total}");
+        true
+    });
+
+    let mut use_case = PreviewFacesUseCase::new(
+        reader,
+        detector,
+        Box::new(ImageFileWriter::new()),
+        Some(progress),
+    )
+    .with_cancellation(cancelled.clone());
+    let detections = use_case.scan(&metadata)?;
+    eprintln!();
+    let observations = std::mem::take(
+        &mut *observations
+            .lock()
+            .map_err(|_| "Observation log poisoned")?,
+    );
+    std::fs::write(
+        report_path,
+        format_detection_report(&detections, &observations, &metadata),
+    )?;
+    log::info!(
+        "Detection report for {} frames written to {}",
+        detections.len(),
+        report_path.display()
+    );
+    let widest = widest_face(detections.values().flatten());
+    if let Some(weak) = blur_strength.and_then(|strength| WeakBlur::check(widest, strength)) {
+        print_weak_blur(&weak);
+    }
+    Ok(())
+}
+
+/// With `--detections`, the faces are known before blurring, so a blur
+/// strength too weak for the widest is raised (`--fit-blur-strength`) or
+/// warned about.
+fn check_replayed_blur_strength(cli: &mut Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(ref path) = cli.detections else {
+        return Ok(());
+    };
+    if !blurs_with_gaussian(cli) {
+        return Ok(());
+    }
+    let mut widest = 0;
+    CachedFaceDetector::from_json_file(path)?
+        .for_each_frame(|regions| widest = widest.max(widest_face(regions)))?;
+    let Some(weak) = WeakBlur::check(widest, cli.blur.blur_strength) else {
+        return Ok(());
+    };
+    if cli.fit_blur_strength {
+        log::info!("{weak}; blurring with strength {}", weak.recommended);
+        cli.blur.blur_strength = weak.recommended;
+    } else {
+        print_weak_blur(&weak);
+    }
+    Ok(())
+}
+
+/// Whether `--blur-strength` applies: pixelation, fills, and overlays size
+/// themselves to each face, and a plugin's effect is its own.
+fn blurs_with_gaussian(cli: &Cli) -> bool {
+    cli.blur.overlay_image.is_none()
+        && cli.blur.effect_plugin.is_none()
+        && !matches!(cli.blur.style.as_str(), "pixelate" | "solid")
+}
+
+/// A [`WeakBlur`] with its fix, on stderr with the progress.
+fn print_weak_blur(weak: &WeakBlur) {
+    eprintln!(
+        "Blur strength warning: {weak}. Use --blur-strength {} or more (or --fit-blur-strength with --detections) to hide them.",
+        weak.recommended
+    );
+}
+
+fn run_frame_export(args: &FrameArgs, model_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    validate_frame_args(args).map_err(InvalidArgs)?;
+    // A still has no display matrix, so rotation goes into the pixels.
+    let reader: Box<dyn VideoReader> = Box::new(FfmpegReader::new().with_bake_rotation(true));
+    let mut use_case = ExportFrameUseCase::new(reader, Box::new(ImageFileWriter::new()));
+    if args.blurred {
+        let detector = build_yolo_detector(
+            &YoloSession::new(ModelChoice::default(), model_dir.to_path_buf()),
+            BlurTarget::Face,
+            args.detection.confidence,
+            args.detection.padding,
+            args.detection.center_offset,
+            args.detection.uncertainty_growth,
+            None,
+            None,
+            &parse_detect_zones(&args.detection.detect_zone)?,
+        )?;
+        let blurrer = create_anonymizer_with_context(
+            anonymization_style(
+                &args.blur.style,
+                &args.blur.fill_color,
+                args.blur.overlay_image.as_deref(),
+                args.blur.effect_plugin.as_deref(),
+                &args.blur.effect_config,
+            )?,
+            parse_blur_shape(&args.blur.blur_shape),
+            args.blur.blur_strength,
+            args.blur.blur_feather,
+            create_gpu_context(),
+        );
+        use_case = use_case.with_blur(detector, blurrer, args.lookahead);
+    }
+    let index = use_case.execute(&args.input, args.at, &args.out)?;
+    log::info!("Frame {index} written to {}", args.out.display());
+    Ok(())
+}
+
+fn run_metadata_report(args: &MetadataArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if !args.input.exists() {
+        return Err(
+            JobError::input(format!("Input file not found: {}", args.input.display())).into(),
+        );
+    }
+    let fields = read_source_metadata(&args.input)?;
+    let name = args.input.display();
+    if fields.is_empty() {
+        println!("No metadata found in {name}");
+        return Ok(());
+    }
+    println!(
+        "{} metadata fields in {name}; exports strip all of them:",
+        fields.len()
+    );
+    let scope_width = fields.iter().map(|f| f.scope.len()).max().unwrap_or(0);
+    let mut category = None;
+    for field in &fields {
+        if category != Some(field.category) {
+            category = Some(field.category);
+            println!("\n{}", field.category.label());
+        }
+        println!(
+            "  {:scope_width$}  {} = {}",
+            field.scope, field.key, field.value
+        );
+    }
+    Ok(())
+}
+
+fn ledger_passphrase() -> Result<String, Box<dyn std::error::Error>> {
+    match std::env::var(LEDGER_PASSPHRASE_ENV) {
+        Ok(passphrase) if !passphrase.is_empty() => Ok(passphrase),
+        _ => Err(format!("Set {LEDGER_PASSPHRASE_ENV} to the ledger signing passphrase").into()),
+    }
+}
+
+fn restore_passphrase() -> Result<String, Box<dyn std::error::Error>> {
+    match std::env::var(RESTORE_PASSPHRASE_ENV) {
+        Ok(passphrase) if !passphrase.is_empty() => Ok(passphrase),
+        _ => Err(
+            format!("Set {RESTORE_PASSPHRASE_ENV} to the restoration sidecar passphrase").into(),
+        ),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_image_blur(
+    input: &Path,
+    output: &Path,
+    image_writer: ImageFileWriter,
+    detector: Box<dyn FaceDetector>,
+    blurrer: Box<dyn FrameBlurrer>,
+    blur_ids: Option<HashSet<u32>>,
+    exclude_ids: Option<HashSet<u32>>,
+    forced_tracks: ForcedTracks,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let reader: Box<dyn VideoReader> = Box::new(ImageFileReader::new());
+    let image_writer: Box<dyn ImageWriter> = Box::new(image_writer);
+
+    let mut use_case = BlurImageUseCase::new(
+        reader,
+        image_writer,
+        detector,
+        blurrer,
+        blur_ids,
+        exclude_ids,
+    )
+    .with_forced_tracks(forced_tracks);
+    use_case.execute(input, output)?;
+    log::info!("Output written to {}", output.display());
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_video_blur(
+    input: &Path,
+    output: &Path,
+    lookahead: usize,
+    detector: Box<dyn FaceDetector>,
+    blurrer: Box<dyn FrameBlurrer>,
+    blur_ids: Option<HashSet<u32>>,
+    exclude_ids: Option<HashSet<u32>>,
+    forced_tracks: ForcedTracks,
+    quality: Option<u32>,
+    codec: VideoCodec,
+    audio_keywords: &Option<Vec<String>>,
+    keyword_matcher: KeywordMatcher,
+    bleep_pii: &[PiiCategory],
+    language: SpeechLanguage,
+    voice_disguise: &str,
+    voice_seed: u64,
+    vocal_model: Option<&Path>,
+    model_dir: &Path,
+    audio_failure_policy: AudioFailurePolicy,
+    bleep_style: BleepStyle,
+    bleep_fade: f64,
+    bake_rotation: bool,
+    hw_accel: bool,
+    converter: Option<Arc<dyn PixelConverter>>,
+    time_rules: TrackTimeRules,
+    audio_ranges: Vec<TimeRange>,
+    cut_list: &CutList,
+    reference_output: Option<&Path>,
+    renditions: &[u32],
+    segment_seconds: f64,
+    output_fps: Option<(f64, FpsConversionMode)>,
+    decode_errors: DecodeErrorPolicy,
+    reuse_unchanged: bool,
+    min_track_frames: usize,
+    resume: Option<Resume>,
+    segment_workers: Vec<(Box<dyn FaceDetector>, Box<dyn FrameBlurrer>)>,
+    cancelled: &CancellationToken,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let warnings = Warnings::new();
+    let ffmpeg_reader = || {
+        let reader = FfmpegReader::new()
+            .with_bake_rotation(bake_rotation)
+            .with_hw_accel(hw_accel)
+            .with_decode_error_policy(decode_errors)
+            .with_warnings(warnings.clone());
+        match converter {
+            Some(ref converter) => reader.with_pixel_converter(converter.clone()),
+            None => reader,
+        }
+    };
+    let mut reader: Box<dyn VideoReader> = Box::new(ffmpeg_reader());
+    let bleep_ranges = cut_list.ranges(RedactionAction::Bleep);
+    let cut_ranges = cut_list.ranges(RedactionAction::Cut);
+    // The reader, every writer, and the audio share one output timeline,
+    // so frames and bleeps land at the same times after cuts. With
+    // --output-fps, frames are spaced at the validated output rate; the
+    // writers take the source rate otherwise.
+    let time_map = match output_fps {
+        Some((fps, _)) => TimeMap::new().with_frame_rate(fps),
+        None => TimeMap::new(),
+    }
+    .with_cuts(&cut_ranges);
+    if !cut_ranges.is_empty() {
+        reader = Box::new(CutRangeReader::new(reader, time_map.clone()));
+    }
+    let metadata = reader
+        .open(input)
+        .map_err(|e| JobError::input(e.to_string()))?;
+    let mut detector = detector;
+    let mut start_frame = 0;
+    let mut min_track_frames = min_track_frames;
+    if let Some(checkpoint) = resume.as_ref().and_then(|r| r.checkpoint.as_ref()) {
+        log::info!("Resuming at frame {}", checkpoint.next_frame);
+        reader.seek(checkpoint.next_frame)?;
+        detector.restore_state(&checkpoint.detector)?;
+        start_frame = checkpoint.next_frame;
+        // Tracks that began before the checkpoint would look short to
+        // the filter and stay visible.
+        min_track_frames = 0;
+    }
+    // Cuts must be applied to the audio too, or it would drift out of sync.
+    let has_audio = audio_keywords.is_some()
+        || !bleep_pii.is_empty()
+        || voice_disguise != "off"
+        || !bleep_ranges.is_empty()
+        || !cut_ranges.is_empty();
+    // Processed audio is muxed into the finished file, which a playlist is not.
+    let segmented = |path: &Path| {
+        SegmentFormat::from_path(path)
+            .map(|format| SegmentedOutput::new(format).with_segment_seconds(segment_seconds))
+    };
+    if has_audio
+        && std::iter::once(output)
+            .chain(reference_output)
+            .any(|p| segmented(p).is_some())
+    {
+        return Err("Audio processing and cut lists need an .mp4 output, not HLS or DASH".into());
+    }
+    let convert_fps = |writer: Box<dyn VideoWriter>| -> Box<dyn VideoWriter> {
+        match output_fps {
+            Some((fps, mode)) => Box::new(FpsConvertingWriter::new(writer, fps, mode)),
+            None => writer,
+        }
+    };
+    let new_writer = |skip_audio: bool, path: &Path| -> Box<dyn VideoWriter> {
+        let mut ffmpeg_writer = match quality {
+            Some(crf) => FfmpegWriter::new().with_crf(crf),
+            None => FfmpegWriter::new(),
+        }
+        .with_codec(codec)
+        .with_time_map(time_map.clone())
+        .with_warnings(warnings.clone());
+        ffmpeg_writer = converting_writer(ffmpeg_writer, converter.as_ref());
+        if let Some(segmented) = segmented(path) {
+            ffmpeg_writer = ffmpeg_writer.with_segmented_output(segmented);
+        }
+        if skip_audio {
+            ffmpeg_writer.set_skip_audio_passthrough(true);
+        }
+        convert_fps(Box::new(ffmpeg_writer))
+    };
+    let rendition_paths: Vec<PathBuf> = renditions
+        .iter()
+        .map(|&height| rendition_path(output, height))
+        .collect();
+    // Parts of resumable and parallel output get the audio when joined.
+    let new_part = || -> PartWriterFactory {
+        let warnings = warnings.clone();
+        let converter = converter.clone();
+        let time_map = time_map.clone();
+        Box::new(move || {
+            let writer = match quality {
+                Some(crf) => FfmpegWriter::new().with_crf(crf),
+                None => FfmpegWriter::new(),
+            }
+            .with_codec(codec)
+            .with_time_map(time_map.clone())
+            .with_warnings(warnings.clone());
+            Box::new(converting_writer(writer, converter.as_ref()))
+        })
+    };
+    let writer: Box<dyn VideoWriter> = if let Some(ref resume) = resume {
+        let checkpointing = CheckpointingDetector::new(detector, DEFAULT_CHECKPOINT_FRAMES);
+        let states = checkpointing.states();
+        detector = Box::new(checkpointing);
+        let settings = resume.settings.clone();
+        let path = resume.path.clone();
+        let finished = resume
+            .checkpoint
+            .as_ref()
+            .map(|c| c.parts.clone())
+            .unwrap_or_default();
+        let mut parted = PartedWriter::new(new_part(), DEFAULT_CHECKPOINT_FRAMES)
+            .with_finished_parts(finished)
+            .with_warnings(warnings.clone())
+            .with_on_part_finished(Box::new(move |next_frame, parts| {
+                // Frames reused unchanged were not detected, so there may
+                // be no state to resume from; the previous checkpoint stays.
+                let Some(detector) = states.take(next_frame - 1) else {
+                    return Ok(());
+                };
+                let checkpoint = ResumeCheckpoint {
+                    settings: settings.clone(),
+                    next_frame,
+                    parts: parts.to_vec(),
+                    detector,
+                };
+                if let Err(e) = checkpoint.save(&path) {
+                    log::warn!("Could not save the resume checkpoint: {e}");
+                }
+                Ok(())
+            }));
+        parted.set_skip_audio_passthrough(has_audio);
+        Box::new(parted)
+    } else if renditions.is_empty() {
+        new_writer(has_audio, output)
+    } else {
+        // One detect/blur pass feeds every rendition.
+        let mut fan_out = FanOutWriter::new(new_writer(has_audio, output));
+        for (&height, path) in renditions.iter().zip(&rendition_paths) {
+            let mut rendition = match quality {
+                Some(crf) => FfmpegWriter::new().with_crf(crf),
+                None => FfmpegWriter::new(),
+            }
+            .with_codec(codec)
+            .with_output_height(height)
+            .with_time_map(time_map.clone())
+            .with_warnings(warnings.clone());
+            if let Some(segmented) = segmented(path) {
+                rendition = rendition.with_segmented_output(segmented);
+            }
+            rendition.set_skip_audio_passthrough(has_audio);
+            fan_out = fan_out.with_output(convert_fps(Box::new(rendition)), path.clone());
+        }
+        Box::new(fan_out)
+    };
+
+    let total = metadata.total_frames;
+    let progress: Box<dyn Fn(usize, usize) -> bool + Send> = Box::new(move |current, _| {
+        eprint!("\rProcessing frame {}/{total}", start_frame + current);
+        true
+    });
+
+    if segment_workers.is_empty() {
+        let mut use_case = BlurFacesUseCase::new(
+            reader,
+            writer,
+            detector,
+            blurrer,
+            RegionMerger::new(),
+            Box::new(ThreadedPipelineExecutor::new()),
+            Some(lookahead),
+            blur_ids,
+            exclude_ids,
+            Some(progress),
+            Some(cancelled.clone()),
+        )
+        .with_time_rules(time_rules)
+        .with_forced_tracks(forced_tracks)
+        .with_decode_error_policy(decode_errors)
+        .with_reuse_unchanged_frames(reuse_unchanged)
+        .with_min_track_frames(min_track_frames)
+        .with_warnings(warnings.clone());
+        if let Some(path) = reference_output {
+            // The reference keeps the source audio unless cuts must be applied.
+            use_case = use_case
+                .with_reference_output(new_writer(!cut_ranges.is_empty(), path), path.into());
+        }
+        use_case.execute(&metadata, output)?;
+    } else {
+        // Each segment reads the input with a reader of its own.
+        reader.close();
+        let workers = std::iter::once((detector, blurrer))
+            .chain(segment_workers)
+            .map(|(detector, blurrer)| SegmentWorker {
+                reader: Box::new(ffmpeg_reader()),
+                detector,
+                blurrer,
+            })
+            .collect();
+        let mut use_case = ParallelSegmentsUseCase::new(
+            workers,
+            new_part(),
+            Some(lookahead),
+            Some(progress),
+            Some(cancelled.clone()),
+        )
+        .with_decode_error_policy(decode_errors)
+        .with_reuse_unchanged_frames(reuse_unchanged)
+        .with_warnings(warnings.clone());
+        use_case.set_skip_audio_passthrough(has_audio);
+        use_case.execute(input, output)?;
+    }
+    eprintln!();
+    if let Some(ref resume) = resume {
+        ResumeCheckpoint::remove(&resume.path)?;
+    }
+    log::info!("Output written to {}", output.display());
+    for path in &rendition_paths {
+        log::info!("Rendition written to {}", path.display());
+    }
+
+    if let Some(path) = reference_output {
+        if !cut_ranges.is_empty() {
+            faceguard_core::pipeline::process_audio_use_case::ProcessAudioUseCase::new(
+                Box::new(
+                    faceguard_core::video::infrastructure::ffmpeg_audio_reader::FfmpegAudioReader,
+                ),
+                Box::new(
+                    faceguard_core::video::infrastructure::ffmpeg_audio_writer::FfmpegAudioWriter,
+                ),
+                None,
+                None,
+                Vec::new(),
+                BleepStyle::Silence,
+            )
+            .with_time_map(time_map.clone())
+            .with_cancellation(cancelled.clone())
+            .run(input, path)?;
+        }
+        log::info!("Reference copy written to {}", path.display());
+    }
+
+    // Audio processing (if keywords or voice disguise enabled)
+    if has_audio {
+        let transformer: Option<
+            Box<dyn faceguard_core::audio::domain::audio_transformer::AudioTransformer>,
+        > = match voice_disguise {
+            "on" => {
+                use faceguard_core::audio::infrastructure::formant_shift_transformer::DEFAULT_FORMANT_SHIFT_RATIO;
+                use faceguard_core::audio::infrastructure::pitch_shift_transformer::DEFAULT_SEMITONES;
+                use faceguard_core::audio::infrastructure::voice_morph_transformer::*;
+                let morph: Box<
+                    dyn faceguard_core::audio::domain::audio_transformer::AudioTransformer,
+                > = Box::new(
+                    VoiceMorphTransformer::new(
+                        DEFAULT_SEMITONES,
+                        DEFAULT_FORMANT_SHIFT_RATIO,
+                        DEFAULT_CONTOUR_WARP_RANGE,
+                    )
+                    .with_seed(voice_seed),
+                );
+                match vocal_model {
+                    Some(model) => {
+                        use faceguard_core::audio::infrastructure::onnx_vocal_separator::OnnxVocalSeparator;
+                        use faceguard_core::audio::infrastructure::separated_voice_transformer::SeparatedVoiceTransformer;
+                        Some(Box::new(SeparatedVoiceTransformer::new(
+                            Box::new(OnnxVocalSeparator::new(model)?),
+                            morph,
+                        )))
+                    }
+                    None => Some(morph),
+                }
+            }
+            _ => None,
+        };
+
+        let keywords = audio_keywords.clone().unwrap_or_default();
+
+        let recognizer: Option<
+            Box<dyn faceguard_core::audio::domain::speech_recognizer::SpeechRecognizer>,
+        > = if !keywords.is_empty() || !bleep_pii.is_empty() {
+            use faceguard_core::audio::infrastructure::whisper_recognizer::WhisperRecognizer;
+            use faceguard_core::shared::constants::{WHISPER_MODEL_NAME, WHISPER_MODEL_URL};
+
+            log::info!("Resolving Whisper model: {WHISPER_MODEL_NAME}");
+            let whisper_path = model_resolver::resolve_in_dir(
+                model_dir,
+                WHISPER_MODEL_NAME,
+                WHISPER_MODEL_URL,
+                None,
+                Some(Box::new(|downloaded, total| {
+                    if total > 0 {
+                        let pct = (downloaded as f64 / total as f64 * 100.0) as u32;
+                        eprint!("\rDownloading speech recognition model... {pct}%");
+                    }
+                })),
+            )?;
+            eprintln!();
+            Some(Box::new(
+                WhisperRecognizer::new(&whisper_path)?.with_language(language),
+            ))
+        } else {
+            None
+        };
+
+        let use_case = faceguard_core::pipeline::process_audio_use_case::ProcessAudioUseCase::new(
+            Box::new(faceguard_core::video::infrastructure::ffmpeg_audio_reader::FfmpegAudioReader),
+            Box::new(faceguard_core::video::infrastructure::ffmpeg_audio_writer::FfmpegAudioWriter),
+            recognizer,
+            transformer,
+            keywords,
+            bleep_style,
+        )
+        .with_bleep_fade(bleep_fade)
+        .with_time_ranges(audio_ranges)
+        .with_bleep_ranges(bleep_ranges)
+        .with_time_map(time_map)
+        .with_extra_outputs(rendition_paths)
+        .with_failure_policy(audio_failure_policy)
+        .with_warnings(warnings.clone())
+        .with_keyword_matcher(keyword_matcher)
+        .with_cancellation(cancelled.clone());
+        let use_case = if bleep_pii.is_empty() {
+            use_case
+        } else {
+            use faceguard_core::audio::infrastructure::pattern_entity_recognizer::PatternEntityRecognizer;
+            use_case.with_entity_recognizer(Box::new(PatternEntityRecognizer::new(
+                bleep_pii.iter().copied(),
+            )))
+        };
+        use_case.run(input, output)?;
+    }
+
+    print_warnings(&warnings);
+    Ok(())
+}
+
+/// `--resume` for one export: where its checkpoint goes, the key of its
+/// input and options, and the checkpoint to continue from, if any.
+struct Resume {
+    path: PathBuf,
+    settings: String,
+    checkpoint: Option<ResumeCheckpoint>,
+}
+
+/// Looks for a checkpoint of an interrupted export to `output`. One left by
+/// another input or other options, or whose parts are gone, is ignored and
+/// the export starts over.
+fn open_resume(input: &Path, output: &Path) -> Result<Resume, Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args_os()
+        .skip(1)
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .filter(|arg| arg != "--resume")
+        .collect();
+    let settings = DetectionDiskCache::key(input, &args.join("\0"))
+        .map_err(|e| JobError::input(e.to_string()))?;
+    let path = ResumeCheckpoint::path_for(output);
+    let checkpoint = ResumeCheckpoint::load(&path)?.filter(|checkpoint| {
+        let usable =
+            checkpoint.settings == settings && checkpoint.parts.iter().all(|p| p.is_file());
+        if !usable {
+            log::warn!(
+                "Ignoring {}: it was saved for another input or other options; starting over",
+                path.display()
+            );
+        }
+        usable
+    });
+    Ok(Resume {
+        path,
+        settings,
+        checkpoint,
+    })
+}
+
+/// Fails before any frame is processed when an output folder lacks room for
+/// the export, as estimated from the input's size and resolution.
+fn check_disk_space(
+    input: &Path,
+    output: &Path,
+    renditions: &[u32],
+    reference_output: Option<&Path>,
+    audio_remux: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let metadata = open_reader(input, false, false)
+        .open(input)
+        .map_err(|e| JobError::input(e.to_string()))?;
+    let input_bytes = std::fs::metadata(input)?.len();
+    let full = estimate_output_bytes(&metadata, input_bytes, None);
+    let mut check = DiskSpaceCheck::new()
+        .with_output(output, full)
+        .with_audio_remux(audio_remux);
+    for &height in renditions {
+        check = check.with_output(
+            rendition_path(output, height),
+            estimate_output_bytes(&metadata, input_bytes, Some(height)),
+        );
+    }
+    if let Some(path) = reference_output {
+        check = check.with_output(path, full);
+    }
+    check.run().map_err(|e| JobError::output(e.to_string()))?;
+    Ok(())
+}
+
+/// GPU/CPU comparison totals from `--verify-gpu`, on stderr with the progress.
+fn print_parity_report(adapter: &str, report: &ParityReport) {
+    if report.is_divergent() {
+        eprintln!(
+            "GPU parity FAILED on {adapter}: {} of {} checked regions differ from the CPU by up to {} levels; those frames used the CPU result. Consider --deterministic-gpu.",
+            report.divergent_regions, report.regions_checked, report.max_difference
+        );
+    } else {
+        eprintln!(
+            "GPU parity OK on {adapter}: {} regions checked, max difference {}",
+            report.regions_checked, report.max_difference
+        );
+    }
+}
+
+/// Sampled re-detection totals from `--verify-blur`, on stderr with the progress.
+fn print_verification_report(report: &BlurVerificationReport) {
+    if !report.has_leaks() {
+        eprintln!(
+            "Blur verification OK: no faces detectable after blurring on {} sampled frames",
+            report.frames_checked
+        );
+        return;
+    }
+    eprintln!(
+        "Blur verification FAILED: {} face(s) still detectable inside blurred regions on {} sampled frames. Review these frames before publishing:",
+        report.leaks.len(),
+        report.frames_checked
+    );
+    for leak in &report.leaks {
+        let face = &leak.face;
+        eprintln!(
+            "  - frame {}: {}x{} at ({}, {})",
+            leak.frame_index, face.width, face.height, face.x, face.y
+        );
+    }
+}
+
+/// Post-run summary of non-fatal problems, on stderr with the progress.
+fn print_warnings(warnings: &Warnings) {
+    let warnings = warnings.snapshot();
+    if warnings.is_empty() {
+        return;
+    }
+    eprintln!("Completed with {} warning(s):", warnings.len());
+    for warning in &warnings {
+        eprintln!("  - [{}] {warning}", warning.kind.code());
+    }
+}
+
+/// `out.mp4` at 720 → `out_720p.mp4`, next to the main output.
+fn rendition_path(output: &Path, height: u32) -> PathBuf {
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match output.extension() {
+        Some(ext) => format!("{stem}_{height}p.{}", ext.to_string_lossy()),
+        None => format!("{stem}_{height}p"),
+    };
+    output.with_file_name(name)
+}
+
+/// A detection model's ONNX session, loaded on first use and shared by
+/// every file of a batch so the model is resolved and optimized only once.
+/// The `--model` face model unless `--blur-target person` asks for the
+/// person model.
+struct YoloSession {
+    model: ModelChoice,
+    model_dir: PathBuf,
+    backend: DetectorBackend,
+    loaded: OnceCell<(SharedSession, u32)>,
+}
+
+/// The model that finds what `--blur-target` asks for. Checked by
+/// `validate`.
+fn detection_model(cli: &Cli) -> ModelChoice {
+    if blur_target(cli).detects_people() {
+        ModelChoice::Registered(&PERSON_DETECTION_MODEL)
+    } else {
+        ModelChoice::parse(&cli.model).unwrap_or_default()
+    }
+}
+
+impl YoloSession {
+    fn new(model: ModelChoice, model_dir: PathBuf) -> Self {
+        Self {
+            model,
+            model_dir,
+            backend: DetectorBackend::Auto,
+            loaded: OnceCell::new(),
+        }
+    }
+
+    fn with_backend(mut self, backend: DetectorBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// The model's name for detection cache keys: a registered model's
+    /// file, tagged with its version once updated, or a custom file's path.
+    fn cache_tag(&self) -> String {
+        match &self.model {
+            ModelChoice::Registered(model) => {
+                model_updates::model_tag_in(&self.model_dir, model.spec.name)
+            }
+            ModelChoice::Custom(path) => path.display().to_string(),
+        }
+    }
+
+    fn get(&self) -> Result<(SharedSession, u32), Box<dyn std::error::Error>> {
+        if let Some(loaded) = self.loaded.get() {
+            return Ok(loaded.clone());
+        }
+        let model_path = match self.model {
+            ModelChoice::Registered(model) => {
+                log::info!("Resolving model: {}", model.spec.name);
+                let path = model_resolver::resolve_in_dir(
+                    &self.model_dir,
+                    model.spec.name,
+                    model.spec.url,
+                    None,
+                    Some(Box::new(download_progress)),
+                )?;
+                eprintln!();
+                path
+            }
+            ModelChoice::Custom(ref path) => path.clone(),
+        };
+        let session = OnnxYoloDetector::build_session_on(&model_path, self.backend)?;
+        let input_size = self
+            .model
+            .format()
+            .input
+            .size
+            .unwrap_or_else(|| session_input_size(&session));
+        Ok(self
+            .loaded
+            .get_or_init(|| (Arc::new(Mutex::new(session)), input_size))
+            .clone())
+    }
+}
+
+/// Text models for `--redact-text`, shared by every file of a batch like
+/// [`YoloSession`]. The recognition model is only loaded when a pattern
+/// has to read the text.
+struct TextSessions {
+    matcher: Option<TextMatcher>,
+    model_dir: PathBuf,
+    loaded: OnceCell<(SharedSession, Option<SharedSession>)>,
+}
+
+impl TextSessions {
+    fn for_patterns(patterns: &[String], model_dir: PathBuf) -> Result<Self, String> {
+        let matcher = TextMatcher::parse(patterns)?;
+        Ok(Self {
+            matcher: (!matcher.is_empty()).then_some(matcher),
+            model_dir,
+            loaded: OnceCell::new(),
+        })
+    }
+
+    fn detector(&self) -> Result<OnnxTextDetector, Box<dyn std::error::Error>> {
+        let (detection, recognition) = match self.loaded.get() {
+            Some(loaded) => loaded.clone(),
+            None => {
+                let reads = self
+                    .matcher
+                    .as_ref()
+                    .is_some_and(TextMatcher::needs_reading);
+                let detection = load_session(
+                    &self.model_dir,
+                    TEXT_DETECTION_MODEL_NAME,
+                    TEXT_DETECTION_MODEL_URL,
+                )?;
+                let recognition = if reads {
+                    Some(load_session(
+                        &self.model_dir,
+                        TEXT_RECOGNITION_MODEL_NAME,
+                        TEXT_RECOGNITION_MODEL_URL,
+                    )?)
+                } else {
+                    None
+                };
+                self.loaded.get_or_init(|| (detection, recognition)).clone()
+            }
+        };
+        let detector = OnnxTextDetector::from_shared_session(detection);
+        Ok(match recognition {
+            Some(session) => detector.with_recognizer(session),
+            None => detector,
+        })
+    }
+}
+
+/// People enrolled with `faceguard enroll` and the embedding model that
+/// recognizes them, loaded once for `--allow-enrolled` and
+/// `--always-blur-person` and shared by every file of a batch.
+struct EnrolledIdentities {
+    embedder: Arc<EmbeddingFaceGrouper>,
+    gallery: IdentityGallery,
+    allow: bool,
+    blocked: Vec<String>,
+}
+
+impl EnrolledIdentities {
+    fn load(
+        model_dir: &Path,
+        path: Option<&Path>,
+        allow: bool,
+        blocked: &[String],
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = identities_path(path)?;
+        let gallery = identity_store::load(&path, EMBEDDING_MODEL_NAME)?;
+        if gallery.is_empty() {
+            return Err(InvalidArgs(
+                format!(
+                    "No one is enrolled in {}; add people with `faceguard enroll`",
+                    path.display()
+                )
+                .into(),
+            )
+            .into());
+        }
+        if let Some(name) = blocked.iter().find(|name| !gallery.contains(name)) {
+            return Err(InvalidArgs(
+                format!(
+                    "'{name}' is not enrolled in {}; see `faceguard enroll --list`",
+                    path.display()
+                )
+                .into(),
+            )
+            .into());
+        }
+        Ok(Self {
+            embedder: Arc::new(load_embedder(model_dir)?),
+            gallery,
+            allow,
+            blocked: blocked.to_vec(),
+        })
+    }
+
+    /// Wraps `detector` with the blocklist and allowlist as asked, and
+    /// returns the tracks the blocklist marks for blurring.
+    fn wrap(&self, detector: Box<dyn FaceDetector>) -> (Box<dyn FaceDetector>, ForcedTracks) {
+        let mut detector = detector;
+        let mut forced = ForcedTracks::new();
+        if !self.blocked.is_empty() {
+            let blocklist = BlocklistFaceDetector::new(
+                detector,
+                Box::new(SharedEmbedder(self.embedder.clone())),
+                self.gallery.clone(),
+                self.blocked.iter().cloned(),
+            );
+            forced = blocklist.forced_tracks();
+            detector = Box::new(blocklist);
+        }
+        if self.allow {
+            // A blocked person is never left unblurred.
+            let mut allowed = self.gallery.clone();
+            for name in &self.blocked {
+                allowed.remove(name);
+            }
+            detector = Box::new(AllowlistFaceDetector::new(
+                detector,
+                Box::new(SharedEmbedder(self.embedder.clone())),
+                allowed,
+            ));
+        }
+        (detector, forced)
+    }
+}
+
+/// The batch's one embedding session, handed to each file's detector.
+struct SharedEmbedder(Arc<EmbeddingFaceGrouper>);
+
+impl FaceEmbedder for SharedEmbedder {
+    fn embed(
+        &self,
+        rgb_data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        self.0.embed(rgb_data, width, height)
+    }
+}
+
+fn identities_path(path: Option<&Path>) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    match path {
+        Some(path) => Ok(path.to_path_buf()),
+        None => Ok(identity_store::default_path()
+            .ok_or("No data folder on this system; pass --identities")?),
+    }
+}
+
+fn load_embedder(model_dir: &Path) -> Result<EmbeddingFaceGrouper, Box<dyn std::error::Error>> {
+    log::info!("Resolving model: {EMBEDDING_MODEL_NAME}");
+    let model_path = model_resolver::resolve_in_dir(
+        model_dir,
+        EMBEDDING_MODEL_NAME,
+        EMBEDDING_MODEL_URL,
+        None,
+        Some(Box::new(download_progress)),
+    )?;
+    eprintln!();
+    EmbeddingFaceGrouper::new(&model_path, embedding_face_grouper::DEFAULT_THRESHOLD)
+}
+
+fn load_session(
+    model_dir: &Path,
+    name: &str,
+    url: &str,
+) -> Result<SharedSession, Box<dyn std::error::Error>> {
+    log::info!("Resolving model: {name}");
+    let model_path = model_resolver::resolve_in_dir(
+        model_dir,
+        name,
+        url,
+        None,
+        Some(Box::new(download_progress)),
+    )?;
+    eprintln!();
+    Ok(Arc::new(Mutex::new(OnnxYoloDetector::build_session(
+        &model_path,
+    )?)))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_yolo_detector(
+    yolo: &YoloSession,
+    target: BlurTarget,
+    confidence: f64,
+    padding: f64,
+    center_offset: f64,
+    uncertainty_growth: f64,
+    observations: Option<ObservationLog>,
+    converter: Option<Arc<dyn PixelConverter>>,
+    zones: &[DetectionZone],
+) -> Result<Box<dyn FaceDetector>, Box<dyn std::error::Error>> {
+    let (session, input_size) = yolo.get()?;
+    let smoother = Box::new(RegionSmoother::new(DEFAULT_ALPHA));
+    let tracker = ByteTracker::new(TRACKER_MAX_LOST);
+    let detector = if target.detects_people() {
+        // --padding is sized for faces; a body box needs far less.
+        let region_builder = PersonRegionBuilder::new(DEFAULT_PERSON_PADDING, Some(smoother));
+        OnnxYoloDetector::persons_from_shared_session(
+            session,
+            input_size,
+            region_builder,
+            tracker,
+            confidence,
+        )
+    } else {
+        let mut region_builder = FaceRegionBuilder::new(padding, center_offset, Some(smoother))
+            .with_uncertainty_growth(uncertainty_growth);
+        if target == BlurTarget::Head {
+            region_builder = region_builder.with_head_coverage();
+        }
+        OnnxYoloDetector::from_shared_session(
+            session,
+            input_size,
+            region_builder,
+            tracker,
+            confidence,
+        )
+    };
+    let mut detector = detector
+        .with_calibration(yolo.model.calibration())
+        .with_model_format(yolo.model.format())
+        .with_zones(zones.to_vec());
+    if let Some(log) = observations {
+        detector = detector.with_observation_log(log);
+    }
+    if let Some(converter) = converter {
+        detector = detector.with_pixel_converter(converter);
+    }
+    Ok(Box::new(detector))
+}
+
+/// Plain detector for `--verify-blur`: raw face boxes with no padding or
+/// smoothing, since it only sees scattered frames.
+fn build_verification_detector(
+    yolo: &YoloSession,
+    confidence: f64,
+) -> Result<Box<dyn FaceDetector>, Box<dyn std::error::Error>> {
+    let (session, input_size) = yolo.get()?;
+    let detector = OnnxYoloDetector::from_shared_session(
+        session,
+        input_size,
+        FaceRegionBuilder::new(0.0, 0.0, None),
+        ByteTracker::new(TRACKER_MAX_LOST),
+        confidence,
+    )
+    .with_calibration(yolo.model.calibration())
+    .with_model_format(yolo.model.format());
+    Ok(Box::new(detector))
+}
+
+fn build_detector(
+    cli: &Cli,
+    yolo: &YoloSession,
+    observations: Option<ObservationLog>,
+    converter: Option<Arc<dyn PixelConverter>>,
+) -> Result<Box<dyn FaceDetector>, Box<dyn std::error::Error>> {
+    let base = build_yolo_detector(
+        yolo,
+        blur_target(cli),
+        cli.detection.confidence,
+        cli.detection.padding,
+        cli.detection.center_offset,
+        cli.detection.uncertainty_growth,
+        observations,
+        converter,
+        &parse_detect_zones(&cli.detection.detect_zone)?,
+    )?;
+    if cli.skip_frames > 1 {
+        Ok(Box::new(SkipFrameDetector::new(base, cli.skip_frames)?))
+    } else {
+        Ok(base)
+    }
+}
+
+/// `--face-count-overlay`: counts the faces `detector` finds on each frame
+/// and badges the frame with that count once `blurrer` has drawn it.
+fn face_count_overlay(
+    detector: Box<dyn FaceDetector>,
+    blurrer: Box<dyn FrameBlurrer>,
+) -> (Box<dyn FaceDetector>, Box<dyn FrameBlurrer>) {
+    let counts = FaceCounts::default();
+    (
+        Box::new(CountingFaceDetector::new(detector, counts.clone())),
+        Box::new(FaceCountOverlay::new(blurrer, counts)),
+    )
+}
+
+/// The detector and blurrer for each further `--parallel-segments`
+/// segment: `detector`, and the anonymizer with the overlay and rectangles
+/// the first segment's blurrer has. Options that add more are rejected
+/// with `--parallel-segments`.
+fn build_segment_worker(
+    mut detector: Box<dyn FaceDetector>,
+    cli: &Cli,
+    gpu_context: Option<Arc<GpuContext>>,
+) -> Result<(Box<dyn FaceDetector>, Box<dyn FrameBlurrer>), Box<dyn std::error::Error>> {
+    let style = anonymization_style(
+        &cli.blur.style,
+        &cli.blur.fill_color,
+        cli.blur.overlay_image.as_deref(),
+        cli.blur.effect_plugin.as_deref(),
+        &cli.blur.effect_config,
+    )?;
+    let mut blurrer = create_anonymizer_with_context(
+        style.clone(),
+        parse_blur_shape(&cli.blur.blur_shape),
+        cli.blur.blur_strength,
+        cli.blur.blur_feather,
+        gpu_context.clone(),
+    );
+    if cli.face_count_overlay {
+        (detector, blurrer) = face_count_overlay(detector, blurrer);
+    }
+    if !cli.redact_rect.is_empty() {
+        let redactor = create_anonymizer_with_context(
+            style,
+            BlurShape::Rectangular,
+            cli.blur.blur_strength,
+            0.0,
+            gpu_context,
+        );
+        let rects = parse_redact_rects(&cli.redact_rect)?;
+        blurrer = Box::new(StaticRedactionBlurrer::new(blurrer, redactor, rects));
+    }
+    Ok((detector, blurrer))
+}
+
+/// The detection cache and this run's key, unless caching is off or does
+/// not apply: images and previews are not cached, reports need the model's
+/// confidences, which the cache does not keep, `--reuse-unchanged`
+/// skips detection on some frames, so its detections are incomplete,
+/// `--detections` already supplies them, and `--parallel-segments` restarts
+/// track IDs in every segment.
+fn open_detection_cache(
+    cli: &Cli,
+    yolo: &YoloSession,
+    input: &Path,
+) -> Result<Option<(DetectionDiskCache, String)>, Box<dyn std::error::Error>> {
+    if cli.no_detection_cache
+        || detect_only(cli)
+        || cli.reuse_unchanged
+        || cli.detections.is_some()
+        || cli.parallel_segments.is_some()
+        || is_image(input)
+    {
+        return Ok(None);
+    }
+    let Some(dir) = DetectionDiskCache::default_dir() else {
+        return Ok(None);
+    };
+    // Cuts renumber frames, so the cut list is part of the key.
+    let cut_list = match cli.cut_list {
+        Some(ref path) => std::fs::read_to_string(path)?,
+        None => String::new(),
+    };
+    let params = detection_params(cli, yolo, &cut_list);
+    let key = DetectionDiskCache::key(input, &params.to_string())?;
+    let cache = DetectionDiskCache::new(dir).with_max_bytes(cli.detection_cache_mb * 1024 * 1024);
+    Ok(Some((cache, key)))
+}
+
+/// The settings that decide which faces are detected and the track IDs
+/// they get, with the text of the cut list, if any.
+fn detection_params(cli: &Cli, yolo: &YoloSession, cut_list: &str) -> CacheKeyParams {
+    let target = blur_target(cli);
+    let params = CacheKeyParams::new()
+        .with(CacheKeyParam::Model, yolo.cache_tag())
+        .with(CacheKeyParam::Target, target.name())
+        .with(CacheKeyParam::Confidence, cli.detection.confidence)
+        .with(CacheKeyParam::Padding, cli.detection.padding)
+        .with(CacheKeyParam::CenterOffset, cli.detection.center_offset)
+        .with(
+            CacheKeyParam::UncertaintyGrowth,
+            cli.detection.uncertainty_growth,
+        )
+        .with(CacheKeyParam::SkipFrames, cli.skip_frames)
+        .with(CacheKeyParam::BakeRotation, cli.bake_rotation)
+        .with(CacheKeyParam::OnDecodeError, cli.on_decode_error.name())
+        .with(CacheKeyParam::CutList, format!("{cut_list:?}"));
+    if cli.detection.detect_zone.is_empty() {
+        return params;
+    }
+    // Only with zones, so scans from before zones existed still match.
+    let zones = parse_detect_zones(&cli.detection.detect_zone).unwrap_or_default();
+    let zones: Vec<String> = zones.iter().map(DetectionZone::to_string).collect();
+    params.with(CacheKeyParam::DetectZones, zones.join(";"))
+}
+
+/// Reads `--selection`, refusing one made on a scan that detected with
+/// other settings: its track IDs would name other faces here.
+fn load_selection(
+    cli: &Cli,
+    yolo: &YoloSession,
+    path: &Path,
+    input: &Path,
+) -> Result<FaceSelection, Box<dyn std::error::Error>> {
+    let selection = FaceSelection::load(path)?;
+    let changed = selection.changed_settings(&detection_params(cli, yolo, ""));
+    if !changed.is_empty() {
+        let names: Vec<&str> = changed.iter().map(CacheKeyParam::name).collect();
+        return Err(format!(
+            "The selection was made on a scan with other detection settings ({}), so its track IDs would pick other faces; run with the settings it was made with",
+            names.join(", ")
+        )
+        .into());
+    }
+    let name = input.file_name().unwrap_or_default().to_string_lossy();
+    if !selection.source.is_empty() && selection.source != name {
+        log::warn!(
+            "The selection was made on {}, not {name}; its track IDs only match on the same video",
+            selection.source
+        );
+    }
+    log::info!(
+        "Blurring {} of {} tracks from {}",
+        selection.blur_ids().len(),
+        selection.tracks.len(),
+        path.display()
+    );
+    Ok(selection)
+}
+
+fn build_image_writer(cli: &Cli) -> ImageFileWriter {
+    let mut writer =
+        ImageFileWriter::new().with_png_compression(parse_png_compression(&cli.png_compression));
+    if let Some(format) = image_format(cli) {
+        writer = writer.with_format(format);
+    }
+    if let Some(quality) = cli.image_quality {
+        writer = writer.with_jpeg_quality(quality);
+    }
+    writer
+}
+
+fn validate_detection_args(args: &DetectionArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if !(0.0..=1.0).contains(&args.confidence) {
+        return Err(format!(
+            "Confidence must be between 0.0 and 1.0, got {}",
+            args.confidence
+        )
+        .into());
     }
-    if !(0.0..=1.0).contains(&cli.padding) {
-        return Err(format!("Padding must be between 0.0 and 1.0, got {}", cli.padding).into());
+    if !(0.0..=1.0).contains(&args.padding) {
+        return Err(format!("Padding must be between 0.0 and 1.0, got {}", args.padding).into());
     }
-    if !(-0.5..=0.5).contains(&cli.center_offset) {
+    if !(-0.5..=0.5).contains(&args.center_offset) {
         return Err(format!(
             "Center offset must be between -0.5 and 0.5, got {}",
-            cli.center_offset
+            args.center_offset
+        )
+        .into());
+    }
+    if !(0.0..=1.0).contains(&args.uncertainty_growth) {
+        return Err(format!(
+            "Uncertainty growth must be between 0.0 and 1.0, got {}",
+            args.uncertainty_growth
+        )
+        .into());
+    }
+    parse_detect_zones(&args.detect_zone)?;
+    Ok(())
+}
+
+fn validate_blur_args(args: &BlurArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if args.blur_strength == 0 || args.blur_strength % 2 == 0 {
+        return Err(format!(
+            "Blur strength must be a positive odd integer, got {}",
+            args.blur_strength
         )
         .into());
     }
-    if cli.blur_shape != "ellipse" && cli.blur_shape != "rect" {
+    if args.blur_shape != "ellipse" && args.blur_shape != "rect" {
         return Err(format!(
             "Blur shape must be 'ellipse' or 'rect', got '{}'",
-            cli.blur_shape
+            args.blur_shape
+        )
+        .into());
+    }
+    if !(0.0..=1.0).contains(&args.blur_feather) {
+        return Err(format!(
+            "Blur feather must be between 0.0 and 1.0, got {}",
+            args.blur_feather
+        )
+        .into());
+    }
+    validate_style(
+        &args.style,
+        &args.fill_color,
+        args.overlay_image.as_deref(),
+        args.effect_plugin.as_deref(),
+    )
+}
+
+fn validate_frame_args(args: &FrameArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if !args.input.exists() {
+        return Err(
+            JobError::input(format!("Input file not found: {}", args.input.display())).into(),
+        );
+    }
+    if is_image(&args.input) {
+        return Err("frame export needs a video input".into());
+    }
+    let known_format = args
+        .out
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(ImageOutputFormat::from_extension)
+        .is_some();
+    if !known_format {
+        return Err("--out must end in .jpg, .png, .webp, .bmp, or .tiff".into());
+    }
+    validate_detection_args(&args.detection)?;
+    validate_blur_args(&args.blur)?;
+    Ok(())
+}
+
+fn validate(cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let input = cli.input.as_ref().ok_or("Input file is required")?;
+    if let Some(ref output_dir) = cli.output_dir {
+        validate_batch(cli, input, output_dir)?;
+    } else if input.is_dir() {
+        return Err(format!(
+            "{} is a folder; add --output-dir to blur every file in it",
+            input.display()
+        )
+        .into());
+    } else if !input.exists() {
+        return Err(JobError::input(format!("Input file not found: {}", input.display())).into());
+    }
+    if cli.report.is_some() && (cli.preview.is_some() || cli.output.is_some()) {
+        return Err("--report runs detection only; it takes no output file or --preview".into());
+    }
+    if let Some(ref detections) = cli.detections {
+        if cli.report.is_some() {
+            return Err("--detections can't be combined with --report, which detects faces".into());
+        }
+        if !detections.is_file() {
+            return Err(format!("Detections file not found: {}", detections.display()).into());
+        }
+    }
+    if cli.cut_list.is_some() && (is_image(input) || detect_only(cli)) {
+        return Err("--cut-list only applies when blurring a video".into());
+    }
+    if let Some(ref reference) = cli.reference_output {
+        if is_image(input) || detect_only(cli) {
+            return Err("--reference-output only applies when blurring a video".into());
+        }
+        if Some(reference) == cli.output.as_ref() || reference == input {
+            return Err("--reference-output must differ from the input and output".into());
+        }
+    }
+    if let Some(ref ledger) = cli.ledger {
+        if is_image(input) || detect_only(cli) {
+            return Err("--ledger only applies when blurring a video".into());
+        }
+        if Some(ledger) == cli.output.as_ref() || ledger == input {
+            return Err("--ledger must differ from the input and output".into());
+        }
+        if cli.sign_ledger {
+            ledger_passphrase()?;
+        }
+    }
+    if let Some(ref sidecar) = cli.restore_sidecar {
+        if is_image(input) || detect_only(cli) {
+            return Err("--restore-sidecar only applies when blurring a video".into());
+        }
+        if Some(sidecar) == cli.output.as_ref() || sidecar == input {
+            return Err("--restore-sidecar must differ from the input and output".into());
+        }
+        let frame_changing = [
+            ("--output-fps", cli.output_fps.is_some()),
+            ("--reuse-unchanged", cli.reuse_unchanged),
+            (
+                "HLS or DASH output",
+                cli.output
+                    .as_deref()
+                    .and_then(SegmentFormat::from_path)
+                    .is_some(),
+            ),
+        ];
+        if let Some((name, _)) = frame_changing.iter().find(|(_, set)| *set) {
+            return Err(format!(
+                "--restore-sidecar can't be used with {name}; restoring needs every output frame to match its input frame"
+            )
+            .into());
+        }
+        restore_passphrase()?;
+    }
+    if cli.verify_blur && (is_image(input) || detect_only(cli)) {
+        return Err("--verify-blur only applies when blurring a video".into());
+    }
+    if cli.resume {
+        if is_image(input) || detect_only(cli) {
+            return Err("--resume only applies when blurring a video".into());
+        }
+        let whole_run = [
+            ("--cut-list", cli.cut_list.is_some()),
+            ("--reference-output", cli.reference_output.is_some()),
+            ("--renditions", cli.renditions.is_some()),
+            ("--output-fps", cli.output_fps.is_some()),
+            ("--ledger", cli.ledger.is_some()),
+            ("--detections-csv", cli.detections_csv.is_some()),
+            ("--restore-sidecar", cli.restore_sidecar.is_some()),
+            (
+                "--on-decode-error duplicate",
+                cli.on_decode_error == DecodeErrorPolicy::DuplicatePrevious,
+            ),
+            (
+                "HLS or DASH output",
+                cli.output
+                    .as_deref()
+                    .and_then(SegmentFormat::from_path)
+                    .is_some(),
+            ),
+        ];
+        if let Some((name, _)) = whole_run.iter().find(|(_, set)| *set) {
+            return Err(format!(
+                "--resume can't be used with {name}, which needs every frame in one run"
+            )
+            .into());
+        }
+    }
+    if let Some(segments) = cli.parallel_segments {
+        if is_image(input) || detect_only(cli) {
+            return Err("--parallel-segments only applies when blurring a video".into());
+        }
+        if segments < 2 {
+            return Err("--parallel-segments must be at least 2".into());
+        }
+        let by_track_or_whole_run = [
+            ("--blur-ids", cli.blur_ids.is_some()),
+            ("--exclude-ids", cli.exclude_ids.is_some()),
+            ("--from-preview", cli.from_preview.is_some()),
+            ("--selection", cli.selection.is_some()),
+            ("--blur-track-range", cli.blur_track_range.is_some()),
+            ("--show-track-range", cli.show_track_range.is_some()),
+            ("--allow-enrolled", cli.allow_enrolled),
+            ("--always-blur-person", !cli.always_blur_person.is_empty()),
+            ("--detections", cli.detections.is_some()),
+            ("--detections-csv", cli.detections_csv.is_some()),
+            ("--ledger", cli.ledger.is_some()),
+            ("--restore-sidecar", cli.restore_sidecar.is_some()),
+            ("--cut-list", cli.cut_list.is_some()),
+            ("--reference-output", cli.reference_output.is_some()),
+            ("--renditions", cli.renditions.is_some()),
+            ("--output-fps", cli.output_fps.is_some()),
+            ("--crowd-limit", cli.crowd_limit.is_some()),
+            ("--redact-text", !cli.redact_text.is_empty()),
+            ("--verify-gpu", cli.verify_gpu),
+            ("--verify-blur", cli.verify_blur),
+            ("--resume", cli.resume),
+            (
+                "--on-decode-error duplicate",
+                cli.on_decode_error == DecodeErrorPolicy::DuplicatePrevious,
+            ),
+            (
+                "HLS or DASH output",
+                cli.output
+                    .as_deref()
+                    .and_then(SegmentFormat::from_path)
+                    .is_some(),
+            ),
+        ];
+        if let Some((name, _)) = by_track_or_whole_run.iter().find(|(_, set)| *set) {
+            return Err(format!(
+                "--parallel-segments can't be used with {name}, which needs track IDs or every frame from one run"
+            )
+            .into());
+        }
+    }
+    if let Some(ref renditions) = cli.renditions {
+        if is_image(input) || detect_only(cli) {
+            return Err("--renditions only applies when blurring a video".into());
+        }
+        if renditions.iter().any(|&h| h < 2) {
+            return Err("--renditions heights must be at least 2".into());
+        }
+    }
+    if cli.blur_ids.is_some() && cli.exclude_ids.is_some() {
+        return Err("--blur-ids and --exclude-ids are mutually exclusive".into());
+    }
+    for spec in [&cli.blur_ids, &cli.exclude_ids].into_iter().flatten() {
+        let spec = TrackIdSpec::parse(spec)?;
+        if spec.uses_groups() && cli.from_preview.is_none() {
+            return Err("group:N labels need --from-preview <dir> from a --preview run".into());
+        }
+    }
+    if let Some(ref dir) = cli.from_preview {
+        if !dir.is_dir() {
+            return Err(format!("Preview folder not found: {}", dir.display()).into());
+        }
+    }
+    if cli.selection.is_some() {
+        if detect_only(cli) {
+            return Err("--selection only applies when blurring".into());
+        }
+        let chosen_elsewhere = [
+            ("--blur-ids", cli.blur_ids.is_some()),
+            ("--exclude-ids", cli.exclude_ids.is_some()),
+            ("--from-preview", cli.from_preview.is_some()),
+            ("--blur-track-range", cli.blur_track_range.is_some()),
+            ("--show-track-range", cli.show_track_range.is_some()),
+        ];
+        if let Some((name, _)) = chosen_elsewhere.iter().find(|(_, set)| *set) {
+            return Err(format!(
+                "--selection can't be used with {name}; the selection already says which faces to blur and when"
+            )
+            .into());
+        }
+    }
+    if !detect_only(cli) && cli.output.is_none() && cli.output_dir.is_none() {
+        return Err(
+            "Output file is required unless --preview, --report, or --output-dir is used".into(),
+        );
+    }
+    if let Some(ref output) = cli.output {
+        if SegmentFormat::from_path(output).is_some() && (is_image(input) || cli.preview.is_some())
+        {
+            return Err("HLS and DASH output only applies when blurring a video".into());
+        }
+    }
+    if cli.segment_seconds <= 0.0 || !cli.segment_seconds.is_finite() {
+        return Err(format!(
+            "Segment seconds must be greater than 0, got {}",
+            cli.segment_seconds
+        )
+        .into());
+    }
+    validate_detection_args(&cli.detection)?;
+    validate_blur_args(&cli.blur)?;
+    if let Some(q) = cli.quality {
+        if q > 51 {
+            return Err(format!("Quality must be between 0 and 51, got {q}").into());
+        }
+    }
+    TextMatcher::parse(&cli.redact_text)?;
+    if !cli.redact_text.is_empty() && detect_only(cli) {
+        return Err("--redact-text only applies when blurring".into());
+    }
+    if cli.allow_enrolled && detect_only(cli) {
+        return Err("--allow-enrolled only applies when blurring".into());
+    }
+    if cli.allow_enrolled && blur_target(cli).detects_people() {
+        // Enrollments are faces; a body crop never matches one.
+        return Err("--allow-enrolled needs --blur-target face".into());
+    }
+    if !cli.always_blur_person.is_empty() && detect_only(cli) {
+        return Err("--always-blur-person only applies when blurring".into());
+    }
+    if !cli.always_blur_person.is_empty() && blur_target(cli).detects_people() {
+        return Err("--always-blur-person needs --blur-target face".into());
+    }
+    if cli.identities.is_some() && !cli.allow_enrolled && cli.always_blur_person.is_empty() {
+        return Err(
+            "--identities only applies with --allow-enrolled or --always-blur-person".into(),
+        );
+    }
+    let rects = parse_redact_rects(&cli.redact_rect)?;
+    if cli.reuse_unchanged && rects.iter().any(|r| r.frames.is_some()) {
+        // A reused frame would miss a rectangle that starts on it.
+        return Err("--redact-rect frame ranges can't be used with --reuse-unchanged".into());
+    }
+    if cli.crowd_limit == Some(0) {
+        return Err("Crowd limit must be at least 1".into());
+    }
+    if CrowdCoverage::parse(&cli.crowd_mode).is_none() {
+        return Err(format!(
+            "Crowd mode must be 'area' or 'frame', got '{}'",
+            cli.crowd_mode
+        )
+        .into());
+    }
+    if BlurTarget::parse(&cli.blur_target).is_none() {
+        return Err(format!(
+            "Blur target must be 'face', 'head', or 'person', got '{}'",
+            cli.blur_target
+        )
+        .into());
+    }
+    match ModelChoice::parse(&cli.model)? {
+        ModelChoice::Custom(ref path) if !path.is_file() => {
+            return Err(format!("Model file not found: {}", path.display()).into());
+        }
+        _ => {}
+    }
+    if cli.model != DEFAULT_DETECTION_MODEL && blur_target(cli).detects_people() {
+        return Err(
+            "--model picks a face model; --blur-target person uses the person detector".into(),
+        );
+    }
+    if DetectorBackend::parse(&cli.detector_backend).is_none() {
+        return Err(format!(
+            "Detector backend must be 'auto', 'cpu', 'cuda', 'coreml', or 'directml', got '{}'",
+            cli.detector_backend
+        )
+        .into());
+    }
+    if let Some(ref format) = cli.image_format {
+        if ImageOutputFormat::from_extension(format).is_none() {
+            return Err(format!(
+                "Image format must be 'jpeg', 'png', 'webp', 'bmp', or 'tiff', got '{format}'"
+            )
+            .into());
+        }
+    }
+    if let Some(q) = cli.image_quality {
+        if !(1..=100).contains(&q) {
+            return Err(format!("Image quality must be between 1 and 100, got {q}").into());
+        }
+    }
+    let valid_png_compressions = ["fast", "default", "best"];
+    if !valid_png_compressions.contains(&cli.png_compression.as_str()) {
+        return Err(format!(
+            "PNG compression must be 'fast', 'default', or 'best', got '{}'",
+            cli.png_compression
         )
         .into());
     }
+    if let Some(fps) = cli.output_fps {
+        if !(fps > 0.0 && fps <= 240.0) {
+            return Err(format!("Output FPS must be between 0 and 240, got {fps}").into());
+        }
+        if is_image(input) || detect_only(cli) {
+            return Err("--output-fps only applies when blurring a video".into());
+        }
+    }
+    let valid_fps_modes = ["drop", "blend"];
+    if !valid_fps_modes.contains(&cli.fps_mode.as_str()) {
+        return Err(format!("FPS mode must be 'drop' or 'blend', got '{}'", cli.fps_mode).into());
+    }
+    let codec = VideoCodec::parse(&cli.codec).ok_or_else(|| {
+        format!(
+            "Codec must be 'h264', 'hevc', 'vp9', or 'av1', got '{}'",
+            cli.codec
+        )
+    })?;
+    if codec != VideoCodec::default() && (is_image(input) || detect_only(cli)) {
+        return Err("--codec only applies when blurring a video".into());
+    }
+    if !is_image(input) {
+        for path in cli.output.iter().chain(&cli.reference_output) {
+            codec.check_container(path)?;
+        }
+    }
     let valid_disguises = ["off", "on"];
     if !valid_disguises.contains(&cli.voice_disguise.as_str()) {
         return Err(format!(
@@ -395,17 +3015,155 @@ fn validate(cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
         )
         .into());
     }
-    let valid_bleep_sounds = ["tone", "silence"];
+    for category in cli.bleep_pii.iter().flatten() {
+        if PiiCategory::parse(category).is_none() {
+            return Err(format!(
+                "--bleep-pii takes names, numbers, or locations, got '{category}'"
+            )
+            .into());
+        }
+    }
+    keyword_matcher(cli)?;
+    if cli.phonetic_keywords.is_some() && cli.audio_keywords.is_none() {
+        return Err("--phonetic-keywords only applies with --audio-keywords".into());
+    }
+    SpeechLanguage::parse(&cli.language)?;
+    if AudioFailurePolicy::parse(&cli.on_audio_error).is_none() {
+        return Err(format!(
+            "Audio error policy must be 'fail', 'original', or 'silent', got '{}'",
+            cli.on_audio_error
+        )
+        .into());
+    }
+    if cli.voice_seed.is_some() && cli.voice_disguise == "off" {
+        return Err("--voice-seed only applies with --voice-disguise on".into());
+    }
+    if let Some(ref model) = cli.vocal_model {
+        if cli.voice_disguise == "off" {
+            return Err("--vocal-model only applies with --voice-disguise on".into());
+        }
+        if !model.is_file() {
+            return Err(format!("Vocal separation model not found: {}", model.display()).into());
+        }
+    }
+    let valid_bleep_sounds = ["tone", "noise", "silence"];
     if !valid_bleep_sounds.contains(&cli.bleep_sound.as_str()) {
         return Err(format!(
-            "Bleep sound must be 'tone' or 'silence', got '{}'",
+            "Bleep sound must be 'tone', 'noise', or 'silence', got '{}'",
             cli.bleep_sound
         )
         .into());
     }
+    if let Some(ref clip) = cli.bleep_clip {
+        if !clip.is_file() {
+            return Err(format!("Bleep clip not found: {}", clip.display()).into());
+        }
+    }
+    Ok(())
+}
+
+/// Checks a folder or wildcard run. Options that name tracks, time ranges,
+/// or files only make sense for one input, so they are refused.
+fn validate_batch(
+    cli: &Cli,
+    input: &Path,
+    output_dir: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let folder = batch_folder(input);
+    if !folder.is_dir() {
+        return Err(
+            JobError::input(format!("Input folder not found: {}", folder.display())).into(),
+        );
+    }
+    if cli.output.is_some() {
+        return Err("--output-dir replaces the output file; pass one or the other".into());
+    }
+    let single_input_options = [
+        ("--preview", cli.preview.is_some()),
+        ("--report", cli.report.is_some()),
+        ("--detections", cli.detections.is_some()),
+        ("--detections-csv", cli.detections_csv.is_some()),
+        ("--blur-ids", cli.blur_ids.is_some()),
+        ("--exclude-ids", cli.exclude_ids.is_some()),
+        ("--from-preview", cli.from_preview.is_some()),
+        ("--blur-track-range", cli.blur_track_range.is_some()),
+        ("--show-track-range", cli.show_track_range.is_some()),
+        ("--audio-ranges", cli.audio_ranges.is_some()),
+        ("--cut-list", cli.cut_list.is_some()),
+        ("--reference-output", cli.reference_output.is_some()),
+        ("--restore-sidecar", cli.restore_sidecar.is_some()),
+        ("--ledger", cli.ledger.is_some()),
+    ];
+    if let Some((name, _)) = single_input_options.iter().find(|(_, set)| *set) {
+        return Err(format!(
+            "{name} applies to a single input; it can't be used with --output-dir"
+        )
+        .into());
+    }
+    if let (Ok(from), Ok(to)) = (folder.canonicalize(), output_dir.canonicalize()) {
+        if from == to {
+            return Err("--output-dir must differ from the input folder, or the originals would be overwritten".into());
+        }
+    }
     Ok(())
 }
 
+/// The folder a batch reads from: the input itself, or the folder of a
+/// wildcard pattern.
+fn batch_folder(input: &Path) -> &Path {
+    if input.is_dir() {
+        return input;
+    }
+    input
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+}
+
+/// Videos and images directly inside a folder input, or those whose names
+/// match a wildcard input (`*` and `?`), sorted by path. Subfolders are not
+/// searched.
+fn batch_inputs(input: &Path) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let pattern: Option<Vec<char>> = (!input.is_dir())
+        .then(|| input.file_name())
+        .flatten()
+        .map(|name| name.to_string_lossy().chars().collect());
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(batch_folder(input))? {
+        let path = entry?.path();
+        let name: Vec<char> = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .chars()
+            .collect();
+        let matches = pattern
+            .as_ref()
+            .map_or(true, |pattern| wildcard_match(pattern, &name));
+        if matches && path.is_file() && (is_image(&path) || is_video(&path)) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn wildcard_match(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| wildcard_match(rest, &name[skip..])),
+        Some(('?', rest)) => !name.is_empty() && wildcard_match(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && wildcard_match(rest, &name[1..]),
+    }
+}
+
+fn is_video(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| VIDEO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
 fn is_image(path: &Path) -> bool {
     path.extension()
         .and_then(|ext| ext.to_str())
@@ -413,12 +3171,120 @@ fn is_image(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
-fn open_reader(input: &Path) -> Box<dyn VideoReader> {
+fn build_time_rules(
+    blur_ranges: &Option<Vec<String>>,
+    show_ranges: &Option<Vec<String>>,
+) -> Result<TrackTimeRules, Box<dyn std::error::Error>> {
+    let mut rules = TrackTimeRules::new();
+    for spec in blur_ranges.iter().flatten() {
+        let (track_id, range) = TrackTimeRules::parse_rule(spec)?;
+        rules.blur_during(track_id, range);
+    }
+    for spec in show_ranges.iter().flatten() {
+        let (track_id, range) = TrackTimeRules::parse_rule(spec)?;
+        rules.show_during(track_id, range);
+    }
+    Ok(rules)
+}
+
+fn parse_redact_rects(specs: &[String]) -> Result<Vec<RedactionRect>, String> {
+    specs
+        .iter()
+        .map(|spec| RedactionRect::parse(spec))
+        .collect()
+}
+
+fn parse_detect_zones(specs: &[String]) -> Result<Vec<DetectionZone>, String> {
+    specs
+        .iter()
+        .map(|spec| DetectionZone::parse(spec))
+        .collect()
+}
+
+/// Whether this run only detects faces (`--preview` or `--report`) rather
+/// than writing a blurred output.
+fn detect_only(cli: &Cli) -> bool {
+    cli.preview.is_some() || cli.report.is_some()
+}
+
+/// `writer`, converting to YUV on `converter` when `--gpu-convert` gave
+/// one.
+fn converting_writer(
+    writer: FfmpegWriter,
+    converter: Option<&Arc<dyn PixelConverter>>,
+) -> FfmpegWriter {
+    match converter {
+        Some(converter) => writer.with_pixel_converter(converter.clone()),
+        None => writer,
+    }
+}
+
+/// The GPU context as a pixel converter, for `--gpu-convert`.
+fn pixel_converter(
+    cli: &Cli,
+    gpu_context: Option<&Arc<GpuContext>>,
+) -> Option<Arc<dyn PixelConverter>> {
+    if !cli.gpu_convert {
+        return None;
+    }
+    match gpu_context {
+        Some(ctx) => Some(ctx.clone() as Arc<dyn PixelConverter>),
+        None => {
+            log::info!("No GPU adapter found; --gpu-convert converts on the CPU");
+            None
+        }
+    }
+}
+
+fn open_reader(input: &Path, bake_rotation: bool, hw_accel: bool) -> Box<dyn VideoReader> {
     if is_image(input) {
         Box::new(ImageFileReader::new())
     } else {
-        Box::new(FfmpegReader::new())
+        Box::new(
+            FfmpegReader::new()
+                .with_bake_rotation(bake_rotation)
+                .with_hw_accel(hw_accel),
+        )
+    }
+}
+
+/// Checked by `validate`.
+fn blur_target(cli: &Cli) -> BlurTarget {
+    BlurTarget::parse(&cli.blur_target).unwrap_or_default()
+}
+
+/// Checked by `validate`.
+fn detector_backend(cli: &Cli) -> DetectorBackend {
+    DetectorBackend::parse(&cli.detector_backend).unwrap_or_default()
+}
+
+/// The sound that replaces censored words: `--bleep-clip` when given,
+/// otherwise `--bleep-sound`.
+fn bleep_style(cli: &Cli) -> Result<BleepStyle, Box<dyn std::error::Error>> {
+    use faceguard_core::video::domain::audio_reader::AudioReader;
+    use faceguard_core::video::infrastructure::ffmpeg_audio_reader::FfmpegAudioReader;
+
+    if let Some(ref path) = cli.bleep_clip {
+        let clip = FfmpegAudioReader
+            .read_audio(path, 16000)?
+            .ok_or_else(|| format!("Bleep clip has no audio: {}", path.display()))?;
+        return Ok(BleepStyle::Clip(Arc::new(clip)));
     }
+    Ok(match cli.bleep_sound.as_str() {
+        "noise" => BleepStyle::BrownNoise,
+        "silence" => BleepStyle::Silence,
+        _ => BleepStyle::default(),
+    })
+}
+
+fn keyword_matcher(cli: &Cli) -> Result<KeywordMatcher, Box<dyn std::error::Error>> {
+    let Some(ref strictness) = cli.phonetic_keywords else {
+        return Ok(KeywordMatcher::Exact);
+    };
+    let strictness = PhoneticStrictness::parse(strictness).ok_or_else(|| {
+        format!("Phonetic strictness must be 'strict', 'normal', or 'loose', got '{strictness}'")
+    })?;
+    Ok(KeywordMatcher::Phonetic(strictness))
 }
 
 fn parse_blur_shape(shape: &str) -> BlurShape {
@@ -429,8 +3295,167 @@ fn parse_blur_shape(shape: &str) -> BlurShape {
     }
 }
 
-fn to_id_set(ids: Option<Vec<u32>>) -> Option<HashSet<u32>> {
-    ids.map(|v| v.into_iter().collect())
+fn validate_style(
+    style: &str,
+    fill_color: &str,
+    overlay_image: Option<&Path>,
+    effect_plugin: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !["blur", "pixelate", "solid"].contains(&style) {
+        return Err(format!("Style must be 'blur', 'pixelate', or 'solid', got '{style}'").into());
+    }
+    if parse_hex_color(fill_color).is_none() {
+        return Err(format!("Fill color must be RRGGBB hex, got '{fill_color}'").into());
+    }
+    if let Some(path) = overlay_image {
+        if style != "blur" {
+            return Err(format!("--overlay-image replaces --style; drop --style {style}").into());
+        }
+        if !path.is_file() {
+            return Err(format!("Overlay image not found: {}", path.display()).into());
+        }
+    }
+    if let Some(path) = effect_plugin {
+        if !cfg!(feature = "plugins") {
+            return Err(
+                "--effect-plugin needs a build with the `plugins` feature (cargo build --features plugins)".into(),
+            );
+        }
+        if style != "blur" || overlay_image.is_some() {
+            return Err("--effect-plugin replaces --style and --overlay-image".into());
+        }
+        if !path.is_file() {
+            return Err(format!("Effect plugin not found: {}", path.display()).into());
+        }
+    }
+    Ok(())
+}
+
+/// `--overlay-image` and `--effect-plugin` take the place of the default
+/// blur style.
+fn anonymization_style(
+    style: &str,
+    fill_color: &str,
+    overlay_image: Option<&Path>,
+    effect_plugin: Option<&Path>,
+    effect_config: &str,
+) -> Result<AnonymizationStyle, Box<dyn std::error::Error>> {
+    #[cfg(feature = "plugins")]
+    if let Some(path) = effect_plugin {
+        return Ok(AnonymizationStyle::Plugin(EffectPlugin::load(
+            path,
+            effect_config,
+        )?));
+    }
+    #[cfg(not(feature = "plugins"))]
+    let _ = (effect_plugin, effect_config);
+    let color = parse_hex_color(fill_color).unwrap_or([0, 0, 0]);
+    if let Some(path) = overlay_image {
+        return Ok(AnonymizationStyle::Overlay {
+            image: ImageOverlayBlurrer::load_image(path)?,
+            backing: color,
+        });
+    }
+    Ok(match style {
+        "pixelate" => AnonymizationStyle::Pixelate,
+        "solid" => AnonymizationStyle::Solid(color),
+        _ => AnonymizationStyle::Blur,
+    })
+}
+
+/// `RRGGBB`, with or without a leading `#`.
+fn parse_hex_color(hex: &str) -> Option<[u8; 3]> {
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+fn image_format(cli: &Cli) -> Option<ImageOutputFormat> {
+    cli.image_format
+        .as_deref()
+        .and_then(ImageOutputFormat::from_extension)
+}
+
+fn parse_fps_mode(mode: &str) -> FpsConversionMode {
+    match mode {
+        "blend" => FpsConversionMode::Blend,
+        _ => FpsConversionMode::DropDuplicate,
+    }
+}
+
+fn parse_decode_error_policy(policy: &str) -> Result<DecodeErrorPolicy, String> {
+    DecodeErrorPolicy::parse(policy).ok_or_else(|| {
+        format!("Decode error policy must be 'fail', 'skip', or 'duplicate', got '{policy}'")
+    })
+}
+
+fn parse_png_compression(level: &str) -> PngCompression {
+    match level {
+        "fast" => PngCompression::Fast,
+        "best" => PngCompression::Best,
+        _ => PngCompression::Default,
+    }
+}
+
+/// Expands `--blur-ids`/`--exclude-ids` into the ID sets the use cases
+/// take. `all` and `none` map onto "no filter" and "empty blur set", so an
+/// excluded `all` blurs nothing.
+#[allow(clippy::type_complexity)]
+fn resolve_id_filters(
+    cli: &Cli,
+) -> Result<(Option<HashSet<u32>>, Option<HashSet<u32>>), Box<dyn std::error::Error>> {
+    let (groups, known) = match cli.from_preview {
+        Some(ref dir) => {
+            let groups_path = dir.join(GROUPS_FILE_NAME);
+            let groups = if groups_path.exists() {
+                read_face_groups(&groups_path)?
+            } else {
+                Vec::new()
+            };
+            (groups, Some(preview_track_ids(dir)?))
+        }
+        None => (Vec::new(), None),
+    };
+    let resolve = |spec: &Option<String>| -> Result<Option<TrackIdSelection>, String> {
+        match spec {
+            Some(spec) => Ok(Some(
+                TrackIdSpec::parse(spec)?.resolve(&groups, known.as_ref())?,
+            )),
+            None => Ok(None),
+        }
+    };
+    Ok(
+        match (resolve(&cli.blur_ids)?, resolve(&cli.exclude_ids)?) {
+            (Some(TrackIdSelection::Ids(ids)), _) => (Some(ids), None),
+            (None, Some(TrackIdSelection::Ids(ids))) => (None, Some(ids)),
+            (None, Some(TrackIdSelection::All)) => (Some(HashSet::new()), None),
+            _ => (None, None),
+        },
+    )
+}
+
+/// Track IDs of the crops (`<id>.jpg`) saved by a preview run.
+fn preview_track_ids(dir: &Path) -> Result<HashSet<u32>, Box<dyn std::error::Error>> {
+    let mut ids = HashSet::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "jpg") {
+            if let Some(id) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse().ok())
+            {
+                ids.insert(id);
+            }
+        }
+    }
+    if ids.is_empty() {
+        return Err(format!("No face crops found in {}", dir.display()).into());
+    }
+    Ok(ids)
 }
 
 fn download_progress(downloaded: u64, total: u64) {
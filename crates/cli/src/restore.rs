@@ -0,0 +1,39 @@
+use faceguard_core::pipeline::restore_faces_use_case::RestoreFacesUseCase;
+use faceguard_core::restoration::infrastructure::restoration_sidecar::RestorationSidecarReader;
+use faceguard_core::shared::job_error::JobError;
+use faceguard_core::video::infrastructure::ffmpeg_reader::FfmpegReader;
+use faceguard_core::video::infrastructure::ffmpeg_writer::FfmpegWriter;
+
+use crate::exit_status::InvalidArgs;
+use crate::{restore_passphrase, RestoreArgs};
+
+pub fn run(args: &RestoreArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if !args.input.exists() {
+        return Err(
+            JobError::input(format!("Input file not found: {}", args.input.display())).into(),
+        );
+    }
+    if !args.sidecar.is_file() {
+        return Err(JobError::input(format!(
+            "Restoration sidecar not found: {}",
+            args.sidecar.display()
+        ))
+        .into());
+    }
+    if args.out == args.input {
+        return Err(InvalidArgs("--out must differ from the input".into()).into());
+    }
+    let passphrase = restore_passphrase().map_err(InvalidArgs)?;
+    let sidecar = RestorationSidecarReader::open(&args.sidecar, &passphrase)?;
+    let mut use_case = RestoreFacesUseCase::new(
+        Box::new(FfmpegReader::new()),
+        Box::new(FfmpegWriter::new()),
+        sidecar,
+    );
+    let frames = use_case.execute(&args.input, &args.out)?;
+    log::info!(
+        "Restored faces on {frames} frames into {}",
+        args.out.display()
+    );
+    Ok(())
+}
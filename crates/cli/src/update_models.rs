@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use faceguard_core::detection::infrastructure::model_updates::{self, ModelUpdate};
+use faceguard_core::shared::cancellation::CancellationToken;
+
+use crate::UpdateModelsArgs;
+
+pub fn run(
+    args: &UpdateModelsArgs,
+    model_dir: &Path,
+    cancelled: &CancellationToken,
+) -> Result<(), Box<dyn std::error::Error>> {
+    eprintln!("Checking for model updates...");
+    let updates = model_updates::check_for_updates_in(model_dir)?;
+    if updates.is_empty() {
+        println!("All models are up to date.");
+        return Ok(());
+    }
+    for update in &updates {
+        println!("\n{}", update.summary());
+        for line in update.release.changelog.lines() {
+            println!("  {line}");
+        }
+        if update.needs_app_update {
+            if let Some(required) = &update.release.min_app_version {
+                println!("  Needs FaceGuard {required} or newer; update the app to install it.");
+            }
+        }
+    }
+    let installable: Vec<&ModelUpdate> = updates.iter().filter(|u| !u.needs_app_update).collect();
+    if args.check || installable.is_empty() {
+        return Ok(());
+    }
+    if !args.yes && !confirm(&format!("\nDownload {} update(s)?", installable.len()))? {
+        println!("No models were changed.");
+        return Ok(());
+    }
+    for update in installable {
+        let name = update.release.name.clone();
+        let path = model_updates::install_update_in(
+            model_dir,
+            update,
+            Some(Box::new(move |downloaded, total| {
+                if total > 0 {
+                    let pct = (downloaded as f64 / total as f64 * 100.0) as u32;
+                    eprint!("\rDownloading {name}... {pct}%");
+                }
+            })),
+            cancelled,
+        )?;
+        eprintln!();
+        println!(
+            "Installed {} v{} at {}",
+            update.release.name,
+            update.release.version,
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Asks a yes/no question on the terminal; anything but "y" or "yes" is no.
+fn confirm(question: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    use std::io::Write;
+    print!("{question} [y/N] ");
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
@@ -0,0 +1,45 @@
+use faceguard_core::pipeline::anonymization_ledger::AnonymizationLedger;
+use faceguard_core::shared::job_error::JobError;
+
+use crate::exit_status::InvalidArgs;
+use crate::inspect::format_clock;
+use crate::{ledger_passphrase, VerifyLedgerArgs};
+
+pub fn run(args: &VerifyLedgerArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if !args.ledger.is_file() {
+        return Err(JobError::input(format!("Ledger not found: {}", args.ledger.display())).into());
+    }
+    let passphrase = ledger_passphrase().map_err(InvalidArgs)?;
+    let json = std::fs::read_to_string(&args.ledger)?;
+    let ledger = AnonymizationLedger::verify(&json, &passphrase)?;
+    println!("Signature valid: {}", args.ledger.display());
+    println!("  Source: {}", ledger.source);
+    println!("  Output: {}", ledger.output);
+    if !ledger.operator.is_empty() {
+        println!("  Operator: {}", ledger.operator);
+    }
+    println!("  FaceGuard {}", ledger.app_version);
+    for (name, value) in ledger.settings.iter().filter(|(_, v)| !v.is_empty()) {
+        println!("  {name} = {value}");
+    }
+
+    println!("\n{} tracks", ledger.tracks.len());
+    for track in &ledger.tracks {
+        let mut line = format!(
+            "  {:>9}  {}",
+            format!("#{}", track.track_id),
+            if track.blurred { "blurred" } else { "VISIBLE" }
+        );
+        if !track.label.is_empty() {
+            line.push_str(&format!("  \"{}\"", track.label));
+        }
+        println!("{line}");
+        let appearances: Vec<String> = track
+            .appearances
+            .iter()
+            .map(|range| format!("{}-{}", format_clock(range.start), format_clock(range.end)))
+            .collect();
+        println!("             {}", appearances.join(", "));
+    }
+    Ok(())
+}
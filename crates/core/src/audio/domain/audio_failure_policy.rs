@@ -0,0 +1,52 @@
+/// What to do when the audio step fails after the video has been encoded.
+///
+/// Audio runs last, so a recognizer error or an undecodable codec would
+/// otherwise throw away a finished video. The fallbacks keep the video and
+/// report the problem as a warning instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AudioFailurePolicy {
+    /// Fail the job. The output is left without audio.
+    #[default]
+    Fail,
+    /// Mux the source audio without disguise or bleeps. Cuts are still
+    /// applied so it stays in sync. Leaks whatever the processing was
+    /// meant to hide, so the warning says so.
+    OriginalAudio,
+    /// Mux a silent track of the source audio's length, or leave the
+    /// output without audio when the source audio cannot be decoded.
+    Silent,
+}
+
+impl AudioFailurePolicy {
+    /// Parses `fail`, `original`, or `silent`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "fail" => Some(Self::Fail),
+            "original" | "original-audio" => Some(Self::OriginalAudio),
+            "silent" | "silence" => Some(Self::Silent),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("fail", Some(AudioFailurePolicy::Fail))]
+    #[case("original", Some(AudioFailurePolicy::OriginalAudio))]
+    #[case("Original-Audio", Some(AudioFailurePolicy::OriginalAudio))]
+    #[case("silent", Some(AudioFailurePolicy::Silent))]
+    #[case("silence", Some(AudioFailurePolicy::Silent))]
+    #[case("ignore", None)]
+    fn test_parse(#[case] input: &str, #[case] expected: Option<AudioFailurePolicy>) {
+        assert_eq!(AudioFailurePolicy::parse(input), expected);
+    }
+
+    #[test]
+    fn test_default_fails() {
+        assert_eq!(AudioFailurePolicy::default(), AudioFailurePolicy::Fail);
+    }
+}
@@ -38,6 +38,43 @@ impl AudioSegment {
     pub fn sample_index_at_time(&self, time: f64) -> usize {
         (time * self.sample_rate as f64 * self.channels as f64) as usize
     }
+
+    /// Removes the samples between `start` (inclusive) and `end`
+    /// (exclusive) seconds, shortening the segment.
+    pub fn remove_range(&mut self, start: f64, end: f64) {
+        let channels = self.channels.max(1) as usize;
+        let total_frames = self.samples.len() / channels;
+        let to_frame = |t: f64| ((t.max(0.0) * self.sample_rate as f64) as usize).min(total_frames);
+        let (start_frame, end_frame) = (to_frame(start), to_frame(end));
+        if start_frame < end_frame {
+            self.samples
+                .drain(start_frame * channels..end_frame * channels);
+        }
+    }
+
+    /// Replaces samples between `start` and `end` seconds with those from
+    /// `processed` (same format, same length), ramping linearly over
+    /// `fade` seconds at each edge so the splice does not click.
+    pub fn blend_range(&mut self, processed: &AudioSegment, start: f64, end: f64, fade: f64) {
+        let channels = self.channels.max(1) as usize;
+        let total_frames = self.samples.len().min(processed.samples.len()) / channels;
+        let to_frame = |t: f64| ((t.max(0.0) * self.sample_rate as f64) as usize).min(total_frames);
+        let (start_frame, end_frame) = (to_frame(start), to_frame(end));
+        let fade_frames = (fade.max(0.0) * self.sample_rate as f64) as usize;
+
+        for frame in start_frame..end_frame {
+            let from_edge = (frame - start_frame).min(end_frame - 1 - frame);
+            let weight = if fade_frames == 0 || from_edge >= fade_frames {
+                1.0
+            } else {
+                (from_edge + 1) as f32 / (fade_frames + 1) as f32
+            };
+            for c in 0..channels {
+                let i = frame * channels + c;
+                self.samples[i] += (processed.samples[i] - self.samples[i]) * weight;
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -73,6 +110,58 @@ mod tests {
         assert_eq!(seg.sample_index_at_time(0.5), 8000);
     }
 
+    #[test]
+    fn test_blend_range_replaces_only_inside_range() {
+        let mut seg = AudioSegment::new(vec![0.0; 100], 100, 1);
+        let processed = AudioSegment::new(vec![1.0; 100], 100, 1);
+        seg.blend_range(&processed, 0.2, 0.5, 0.0);
+        assert_eq!(seg.samples()[19], 0.0);
+        assert_eq!(seg.samples()[20], 1.0);
+        assert_eq!(seg.samples()[49], 1.0);
+        assert_eq!(seg.samples()[50], 0.0);
+    }
+
+    #[test]
+    fn test_blend_range_fades_edges() {
+        let mut seg = AudioSegment::new(vec![0.0; 100], 100, 1);
+        let processed = AudioSegment::new(vec![1.0; 100], 100, 1);
+        seg.blend_range(&processed, 0.2, 0.6, 0.05);
+        assert!(seg.samples()[20] > 0.0 && seg.samples()[20] < 0.5);
+        assert!(seg.samples()[22] > seg.samples()[20]);
+        assert_eq!(seg.samples()[40], 1.0);
+        assert!(seg.samples()[59] < 0.5);
+    }
+
+    #[test]
+    fn test_blend_range_keeps_stereo_frames_aligned() {
+        let mut seg = AudioSegment::new(vec![0.0; 200], 100, 2);
+        let processed = AudioSegment::new(vec![1.0; 200], 100, 2);
+        seg.blend_range(&processed, 0.5, 2.0, 0.0);
+        assert_eq!(seg.samples()[99], 0.0);
+        assert_eq!(seg.samples()[100], 1.0);
+        assert_eq!(seg.samples()[101], 1.0);
+        assert_eq!(seg.samples()[199], 1.0);
+    }
+
+    #[test]
+    fn test_remove_range_shortens_segment() {
+        let samples: Vec<f32> = (0..100).map(|i| i as f32).collect();
+        let mut seg = AudioSegment::new(samples, 100, 1);
+        seg.remove_range(0.2, 0.5);
+        assert_eq!(seg.samples().len(), 70);
+        assert_eq!(seg.samples()[19], 19.0);
+        assert_eq!(seg.samples()[20], 50.0);
+    }
+
+    #[test]
+    fn test_remove_range_past_end_is_clamped() {
+        let mut seg = AudioSegment::new(vec![0.0; 200], 100, 2);
+        seg.remove_range(0.5, 10.0);
+        assert_eq!(seg.samples().len(), 100);
+        seg.remove_range(5.0, 6.0);
+        assert_eq!(seg.samples().len(), 100);
+    }
+
     #[test]
     fn test_samples_mut() {
         let mut seg = AudioSegment::new(vec![0.0; 100], 16000, 1);
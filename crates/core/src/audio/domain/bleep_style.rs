@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use super::audio_segment::AudioSegment;
+
+pub const DEFAULT_BLEEP_FREQUENCY: f64 = 1000.0;
+
+/// Crossfade at each edge of a bleep, long enough to avoid a click but
+/// well inside the padding around a word.
+pub const DEFAULT_BLEEP_FADE: f64 = 0.01;
+
+/// Peak level of generated tones and noise: clearly audible over speech
+/// without clipping.
+const BLEEP_LEVEL: f32 = 0.3;
+
+/// What replaces a censored span of audio.
+#[derive(Clone, Debug)]
+pub enum BleepStyle {
+    /// A sine tone at `frequency` Hz.
+    Tone { frequency: f64 },
+    /// Brown noise: a low rumble, less piercing than a tone.
+    BrownNoise,
+    /// Zero samples.
+    Silence,
+    /// A user-supplied sound, looped to fill spans longer than it.
+    Clip(Arc<AudioSegment>),
+}
+
+impl Default for BleepStyle {
+    fn default() -> Self {
+        Self::Tone {
+            frequency: DEFAULT_BLEEP_FREQUENCY,
+        }
+    }
+}
+
+impl BleepStyle {
+    /// `frames` mono samples of the sound at `sample_rate`, from its start.
+    /// Noise is seeded the same way every time, so exports are repeatable.
+    pub fn render(&self, frames: usize, sample_rate: u32) -> Vec<f32> {
+        match self {
+            Self::Tone { frequency } => (0..frames)
+                .map(|i| {
+                    let t = i as f64 / sample_rate as f64;
+                    (2.0 * std::f64::consts::PI * frequency * t).sin() as f32 * BLEEP_LEVEL
+                })
+                .collect(),
+            Self::BrownNoise => brown_noise(frames),
+            Self::Silence => vec![0.0; frames],
+            Self::Clip(clip) => looped(clip, frames, sample_rate),
+        }
+    }
+}
+
+/// Integrated white noise from a fixed-seed xorshift generator, with a
+/// slight leak so it stays centred on zero.
+fn brown_noise(frames: usize) -> Vec<f32> {
+    let mut state: u32 = 0x9E37_79B9;
+    let mut level = 0.0f32;
+    (0..frames)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let white = state as f32 / u32::MAX as f32 * 2.0 - 1.0;
+            level = (level + 0.02 * white) / 1.02;
+            (level * 3.5).clamp(-1.0, 1.0) * BLEEP_LEVEL
+        })
+        .collect()
+}
+
+/// `clip` mixed down to mono, resampled linearly to `sample_rate`, and
+/// repeated to `frames` samples.
+fn looped(clip: &AudioSegment, frames: usize, sample_rate: u32) -> Vec<f32> {
+    let channels = clip.channels().max(1) as usize;
+    let mono: Vec<f32> = clip
+        .samples()
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect();
+    if mono.is_empty() {
+        return vec![0.0; frames];
+    }
+    let step = clip.sample_rate() as f64 / sample_rate as f64;
+    (0..frames)
+        .map(|i| {
+            let position = (i as f64 * step) % mono.len() as f64;
+            let index = position as usize;
+            let next = mono[(index + 1) % mono.len()];
+            let fraction = (position - index as f64) as f32;
+            mono[index] + (next - mono[index]) * fraction
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples.iter().fold(0.0, |max, s| max.max(s.abs()))
+    }
+
+    #[test]
+    fn test_tone_peaks_at_bleep_level() {
+        let tone = BleepStyle::default().render(16000, 16000);
+        assert!((peak(&tone) - BLEEP_LEVEL).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_brown_noise_is_audible_and_repeatable() {
+        let noise = BleepStyle::BrownNoise.render(16000, 16000);
+        assert!(peak(&noise) > 0.05);
+        assert!(peak(&noise) <= BLEEP_LEVEL);
+        assert_eq!(noise, BleepStyle::BrownNoise.render(16000, 16000));
+    }
+
+    #[test]
+    fn test_silence_is_zero() {
+        assert!(BleepStyle::Silence
+            .render(100, 16000)
+            .iter()
+            .all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_clip_is_mixed_down_and_looped() {
+        let clip = AudioSegment::new(vec![0.2, 0.4, 0.6, 0.8], 16000, 2);
+        let rendered = BleepStyle::Clip(Arc::new(clip)).render(5, 16000);
+        let expected = [0.3, 0.7, 0.3, 0.7, 0.3];
+        for (got, want) in rendered.iter().zip(expected) {
+            assert!((got - want).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_clip_is_resampled() {
+        let clip = AudioSegment::new(vec![0.0, 1.0, 0.0, 1.0], 8000, 1);
+        let rendered = BleepStyle::Clip(Arc::new(clip)).render(4, 16000);
+        let expected = [0.0, 0.5, 1.0, 0.5];
+        for (got, want) in rendered.iter().zip(expected) {
+            assert!((got - want).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use super::spoken_entity::SpokenEntity;
+use super::transcript::TranscriptWord;
+
+/// Domain interface for finding personal information in a transcript.
+///
+/// Implementations run a named-entity pass over the words a
+/// [`SpeechRecognizer`](super::speech_recognizer::SpeechRecognizer)
+/// produced and return the entities they find, in transcript order.
+pub trait EntityRecognizer: Send {
+    fn find_entities(
+        &self,
+        transcript: &[TranscriptWord],
+    ) -> Result<Vec<SpokenEntity>, Box<dyn std::error::Error>>;
+}
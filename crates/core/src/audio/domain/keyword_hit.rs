@@ -0,0 +1,48 @@
+use super::censor_region::CensorRegion;
+use super::transcript::TranscriptWord;
+
+/// A recognized word that matched a bleep keyword, with the recognizer's
+/// timing and confidence, so it can be reviewed before it is bleeped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeywordHit {
+    /// The keyword the word matched, as the user wrote it.
+    pub keyword: String,
+    pub word: TranscriptWord,
+}
+
+impl KeywordHit {
+    /// The span to bleep, widened by `padding` on each side.
+    pub fn censor_region(&self, padding: f64) -> CensorRegion {
+        CensorRegion {
+            start_time: self.word.start_time,
+            end_time: self.word.end_time,
+            padding,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_censor_region_spans_the_word() {
+        let hit = KeywordHit {
+            keyword: "john".to_string(),
+            word: TranscriptWord {
+                word: "John".to_string(),
+                start_time: 1.0,
+                end_time: 1.4,
+                confidence: 0.8,
+            },
+        };
+        assert_eq!(
+            hit.censor_region(0.05),
+            CensorRegion {
+                start_time: 1.0,
+                end_time: 1.4,
+                padding: 0.05,
+            }
+        );
+    }
+}
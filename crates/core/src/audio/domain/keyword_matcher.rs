@@ -0,0 +1,275 @@
+/// How closely a phonetic match must agree with the keyword.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PhoneticStrictness {
+    /// Same sound and at most [`STRICT_EDIT_DISTANCE`] letters spelled
+    /// differently: "Mohammed" for "Muhammad", but not "Mehmet".
+    Strict,
+    /// Same sound: "Katherine" for "Kathryn", "Jon" for "John".
+    #[default]
+    Normal,
+    /// Same sound once voicing and h, w, and y are ignored too: "Steven"
+    /// for "Stephen", "Mehmet" for "Muhammad". Catches the most variants
+    /// and the most unrelated words.
+    Loose,
+}
+
+/// Letters a strict phonetic match may spell differently.
+pub const STRICT_EDIT_DISTANCE: usize = 2;
+
+impl PhoneticStrictness {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "strict" => Some(Self::Strict),
+            "normal" => Some(Self::Normal),
+            "loose" => Some(Self::Loose),
+            _ => None,
+        }
+    }
+}
+
+/// Decides whether a transcribed word is a keyword.
+///
+/// Names are often transcribed with a spelling other than the one listed,
+/// so besides the exact (case-insensitive) comparison, a keyword can match
+/// every word that sounds like it. Sounds are compared through a
+/// Metaphone-style key: spelling variants of one sound are folded
+/// together ("ph" and "f", "c" and "k"), vowels after the first letter
+/// are dropped, and repeated sounds collapse.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeywordMatcher {
+    /// The same word, ignoring case.
+    #[default]
+    Exact,
+    /// A word that sounds like the keyword, or is spelled like it.
+    Phonetic(PhoneticStrictness),
+}
+
+impl KeywordMatcher {
+    pub fn matches(&self, keyword: &str, word: &str) -> bool {
+        let (keyword, word) = (keyword.to_lowercase(), word.to_lowercase());
+        if keyword == word {
+            return true;
+        }
+        let Self::Phonetic(strictness) = self else {
+            return false;
+        };
+        let loose = *strictness == PhoneticStrictness::Loose;
+        let key = phonetic_key(&keyword, loose);
+        if key.is_empty() || key != phonetic_key(&word, loose) {
+            return false;
+        }
+        *strictness != PhoneticStrictness::Strict
+            || edit_distance(&keyword, &word) <= STRICT_EDIT_DISTANCE
+    }
+}
+
+/// The sound of `word` as a string of consonant classes, with `A` for a
+/// leading vowel. `loose` also merges voiced and unvoiced consonants and
+/// drops h, w, and y.
+pub fn phonetic_key(word: &str, loose: bool) -> String {
+    let letters: Vec<char> = word
+        .to_lowercase()
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .collect();
+    let at = |i: usize| letters.get(i).copied().unwrap_or(' ');
+    let is_vowel = |c: char| "aeiou".contains(c);
+    let is_soft = |c: char| "eiy".contains(c);
+
+    let mut sounds: Vec<char> = Vec::new();
+    let mut i = 0;
+    // Silent first letters.
+    if matches!(
+        (at(0), at(1)),
+        ('k', 'n') | ('g', 'n') | ('p', 'n') | ('w', 'r') | ('p', 's')
+    ) {
+        i = 1;
+    }
+    while i < letters.len() {
+        let (c, next) = (letters[i], at(i + 1));
+        let mut step = 1;
+        let sound = match c {
+            _ if is_vowel(c) => (i == 0).then_some('A'),
+            'b' => Some('B'),
+            'c' if next == 'h' => {
+                step = 2;
+                Some('X')
+            }
+            'c' if next == 'k' => {
+                step = 2;
+                Some('K')
+            }
+            'c' if is_soft(next) => Some('S'),
+            'c' | 'k' | 'q' => Some('K'),
+            'd' if next == 'g' && is_soft(at(i + 2)) => {
+                step = 2;
+                Some('J')
+            }
+            'd' => Some('D'),
+            'f' => Some('F'),
+            'v' => Some('V'),
+            'g' if next == 'h' => {
+                step = 2;
+                (i == 0).then_some('K')
+            }
+            'g' if is_soft(next) => Some('J'),
+            'g' => Some('K'),
+            // Sounded only before a vowel and not after another consonant
+            // it softens ("ch", "sh", "th" are handled with that letter).
+            'h' => (is_vowel(next) && (i == 0 || is_vowel(at(i - 1)))).then_some('H'),
+            'j' => Some('J'),
+            'l' => Some('L'),
+            'm' => Some('M'),
+            'n' => Some('N'),
+            'p' if next == 'h' => {
+                step = 2;
+                Some('F')
+            }
+            'p' => Some('P'),
+            'r' => Some('R'),
+            's' if next == 'h' => {
+                step = 2;
+                Some('X')
+            }
+            's' if next == 'c' && at(i + 2) == 'h' => {
+                step = 3;
+                sounds.push('S');
+                Some('K')
+            }
+            's' | 'z' => Some('S'),
+            't' if next == 'h' => {
+                step = 2;
+                Some('0')
+            }
+            't' => Some('T'),
+            'w' | 'y' => is_vowel(next).then_some(c.to_ascii_uppercase()),
+            'x' if i == 0 => Some('S'),
+            'x' => {
+                sounds.push('K');
+                Some('S')
+            }
+            _ => None,
+        };
+        sounds.extend(sound);
+        i += step;
+    }
+
+    let mut key = String::new();
+    for sound in sounds {
+        let sound = if loose { unvoiced(sound) } else { Some(sound) };
+        if let Some(sound) = sound {
+            if !key.ends_with(sound) {
+                key.push(sound);
+            }
+        }
+    }
+    key
+}
+
+/// The loose class of a sound: voiced consonants as their unvoiced pair,
+/// and h, w, and y dropped.
+fn unvoiced(sound: char) -> Option<char> {
+    match sound {
+        'B' => Some('P'),
+        'D' => Some('T'),
+        'V' => Some('F'),
+        'J' => Some('X'),
+        'H' | 'W' | 'Y' => None,
+        other => Some(other),
+    }
+}
+
+/// Levenshtein distance between `a` and `b`, by characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("Muhammad", "Mohammed")]
+    #[case("Kathryn", "Catherine")]
+    #[case("John", "Jon")]
+    #[case("Philip", "Filip")]
+    #[case("Geoff", "Jeff")]
+    fn test_normal_matches_spelling_variants(#[case] keyword: &str, #[case] word: &str) {
+        let matcher = KeywordMatcher::Phonetic(PhoneticStrictness::Normal);
+        assert!(matcher.matches(keyword, word), "{keyword} / {word}");
+    }
+
+    #[rstest]
+    #[case("Muhammad", "Michael")]
+    #[case("Jon", "Tom")]
+    #[case("Anna", "Emma")]
+    fn test_normal_rejects_other_names(#[case] keyword: &str, #[case] word: &str) {
+        let matcher = KeywordMatcher::Phonetic(PhoneticStrictness::Normal);
+        assert!(!matcher.matches(keyword, word), "{keyword} / {word}");
+    }
+
+    #[test]
+    fn test_strictness_controls_how_far_variants_reach() {
+        let strict = KeywordMatcher::Phonetic(PhoneticStrictness::Strict);
+        let normal = KeywordMatcher::Phonetic(PhoneticStrictness::Normal);
+        let loose = KeywordMatcher::Phonetic(PhoneticStrictness::Loose);
+
+        assert!(strict.matches("Muhammad", "Mohammed"));
+        assert!(!strict.matches("John", "Jane"));
+        assert!(normal.matches("John", "Jane"));
+        assert!(!strict.matches("Kathryn", "Catherine"));
+        assert!(normal.matches("Kathryn", "Catherine"));
+        assert!(!normal.matches("Stephen", "Steven"));
+        assert!(loose.matches("Stephen", "Steven"));
+        assert!(loose.matches("Muhammad", "Mehmet"));
+    }
+
+    #[test]
+    fn test_exact_ignores_case_only() {
+        assert!(KeywordMatcher::Exact.matches("John", "JOHN"));
+        assert!(!KeywordMatcher::Exact.matches("John", "Jon"));
+    }
+
+    #[test]
+    fn test_words_without_letters_never_match_phonetically() {
+        let matcher = KeywordMatcher::Phonetic(PhoneticStrictness::Loose);
+        assert!(!matcher.matches("42", "17"));
+    }
+
+    #[rstest]
+    #[case("knight", false, "NT")]
+    #[case("phone", false, "FN")]
+    #[case("anna", false, "AN")]
+    #[case("xavier", false, "SVR")]
+    #[case("david", true, "TFT")]
+    fn test_phonetic_key(#[case] word: &str, #[case] loose: bool, #[case] expected: &str) {
+        assert_eq!(phonetic_key(word, loose), expected);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("muhammad", "mohammed"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+
+    #[test]
+    fn test_parse_strictness() {
+        assert_eq!(
+            PhoneticStrictness::parse(" Loose "),
+            Some(PhoneticStrictness::Loose)
+        );
+        assert_eq!(PhoneticStrictness::parse("fuzzy"), None);
+    }
+}
@@ -1,6 +1,14 @@
+pub mod audio_failure_policy;
 pub mod audio_segment;
 pub mod audio_transformer;
+pub mod bleep_style;
 pub mod censor_region;
+pub mod entity_recognizer;
+pub mod keyword_hit;
+pub mod keyword_matcher;
+pub mod speech_language;
 pub mod speech_recognizer;
+pub mod spoken_entity;
 pub mod transcript;
+pub mod voice_separator;
 pub mod word_censor;
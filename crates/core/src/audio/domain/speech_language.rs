@@ -0,0 +1,174 @@
+use std::fmt;
+
+/// Languages Whisper transcribes, as ISO 639-1 codes with English names,
+/// most spoken first.
+pub const LANGUAGES: &[(&str, &str)] = &[
+    ("en", "English"),
+    ("zh", "Chinese"),
+    ("es", "Spanish"),
+    ("hi", "Hindi"),
+    ("ar", "Arabic"),
+    ("fr", "French"),
+    ("pt", "Portuguese"),
+    ("ru", "Russian"),
+    ("bn", "Bengali"),
+    ("ja", "Japanese"),
+    ("de", "German"),
+    ("id", "Indonesian"),
+    ("ur", "Urdu"),
+    ("ko", "Korean"),
+    ("tr", "Turkish"),
+    ("vi", "Vietnamese"),
+    ("it", "Italian"),
+    ("fa", "Persian"),
+    ("pl", "Polish"),
+    ("uk", "Ukrainian"),
+    ("nl", "Dutch"),
+    ("th", "Thai"),
+    ("ta", "Tamil"),
+    ("te", "Telugu"),
+    ("mr", "Marathi"),
+    ("ms", "Malay"),
+    ("tl", "Tagalog"),
+    ("sw", "Swahili"),
+    ("ro", "Romanian"),
+    ("el", "Greek"),
+    ("cs", "Czech"),
+    ("hu", "Hungarian"),
+    ("sv", "Swedish"),
+    ("he", "Hebrew"),
+    ("da", "Danish"),
+    ("fi", "Finnish"),
+    ("no", "Norwegian"),
+    ("sk", "Slovak"),
+    ("bg", "Bulgarian"),
+    ("hr", "Croatian"),
+    ("sr", "Serbian"),
+    ("lt", "Lithuanian"),
+    ("lv", "Latvian"),
+    ("et", "Estonian"),
+    ("sl", "Slovenian"),
+    ("ca", "Catalan"),
+    ("gl", "Galician"),
+    ("eu", "Basque"),
+    ("cy", "Welsh"),
+    ("ga", "Irish"),
+    ("is", "Icelandic"),
+    ("af", "Afrikaans"),
+    ("am", "Amharic"),
+    ("az", "Azerbaijani"),
+    ("be", "Belarusian"),
+    ("bs", "Bosnian"),
+    ("gu", "Gujarati"),
+    ("ha", "Hausa"),
+    ("hy", "Armenian"),
+    ("ka", "Georgian"),
+    ("kk", "Kazakh"),
+    ("km", "Khmer"),
+    ("kn", "Kannada"),
+    ("lo", "Lao"),
+    ("mk", "Macedonian"),
+    ("ml", "Malayalam"),
+    ("mn", "Mongolian"),
+    ("my", "Burmese"),
+    ("ne", "Nepali"),
+    ("pa", "Punjabi"),
+    ("ps", "Pashto"),
+    ("si", "Sinhala"),
+    ("so", "Somali"),
+    ("sq", "Albanian"),
+    ("uz", "Uzbek"),
+    ("yo", "Yoruba"),
+];
+
+/// The language speech is transcribed in: detected from the audio, or
+/// fixed by the user when detection guesses wrong, as it can on short or
+/// noisy clips. Keywords only match when the speech is transcribed in the
+/// language they are written in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpeechLanguage {
+    /// Detected from the first seconds of speech.
+    #[default]
+    Auto,
+    /// An ISO 639-1 code from [`LANGUAGES`].
+    Fixed(&'static str),
+}
+
+impl SpeechLanguage {
+    /// `auto`, or a language's code or English name, in any case.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim().to_lowercase();
+        if s == "auto" {
+            return Ok(Self::Auto);
+        }
+        LANGUAGES
+            .iter()
+            .find(|(code, name)| *code == s || name.to_lowercase() == s)
+            .map(|&(code, _)| Self::Fixed(code))
+            .ok_or_else(|| format!("Unknown language '{s}' (expected auto or a code such as en)"))
+    }
+
+    /// Auto first, then every language in [`LANGUAGES`] order.
+    pub fn all() -> Vec<Self> {
+        std::iter::once(Self::Auto)
+            .chain(LANGUAGES.iter().map(|&(code, _)| Self::Fixed(code)))
+            .collect()
+    }
+
+    /// The code passed to the recognizer; `auto` asks it to detect.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Fixed(code) => code,
+        }
+    }
+}
+
+impl fmt::Display for SpeechLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Auto => write!(f, "Detect automatically"),
+            Self::Fixed(code) => {
+                let name = LANGUAGES
+                    .iter()
+                    .find(|(c, _)| c == code)
+                    .map_or(*code, |&(_, name)| name);
+                write!(f, "{name}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("auto", SpeechLanguage::Auto)]
+    #[case("de", SpeechLanguage::Fixed("de"))]
+    #[case(" FR ", SpeechLanguage::Fixed("fr"))]
+    #[case("Spanish", SpeechLanguage::Fixed("es"))]
+    fn test_parse(#[case] input: &str, #[case] expected: SpeechLanguage) {
+        assert_eq!(SpeechLanguage::parse(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_language() {
+        assert!(SpeechLanguage::parse("klingon").is_err());
+    }
+
+    #[test]
+    fn test_display_names_the_language() {
+        assert_eq!(SpeechLanguage::Fixed("ja").to_string(), "Japanese");
+        assert_eq!(SpeechLanguage::Auto.code(), "auto");
+    }
+
+    #[test]
+    fn test_all_starts_with_auto_and_has_no_duplicates() {
+        let all = SpeechLanguage::all();
+        assert_eq!(all[0], SpeechLanguage::Auto);
+        let codes: std::collections::HashSet<_> = all.iter().map(|l| l.code()).collect();
+        assert_eq!(codes.len(), all.len());
+    }
+}
@@ -0,0 +1,94 @@
+use super::keyword_hit::KeywordHit;
+use super::transcript::TranscriptWord;
+
+/// A kind of personal information that can be bleeped without naming the
+/// words in advance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PiiCategory {
+    /// People's names.
+    Name,
+    /// Phone numbers, account numbers, and other digit sequences.
+    Number,
+    /// Street addresses and place names.
+    Location,
+}
+
+impl PiiCategory {
+    pub const ALL: [PiiCategory; 3] = [Self::Name, Self::Number, Self::Location];
+
+    /// Accepts the plural (`names`, as the CLI lists them) or the singular.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "names" | "name" => Some(Self::Name),
+            "numbers" | "number" => Some(Self::Number),
+            "locations" | "location" => Some(Self::Location),
+            _ => None,
+        }
+    }
+
+    /// Shown in place of a keyword when a hit is reviewed.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Number => "number",
+            Self::Location => "location",
+        }
+    }
+}
+
+/// Consecutive transcript words that together are one piece of personal
+/// information, such as a full name or a phone number.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpokenEntity {
+    pub category: PiiCategory,
+    pub words: Vec<TranscriptWord>,
+}
+
+impl SpokenEntity {
+    /// One hit per word, labelled with the category, so entities are
+    /// reviewed and bleeped like keyword matches.
+    pub fn hits(&self) -> Vec<KeywordHit> {
+        self.words
+            .iter()
+            .map(|word| KeywordHit {
+                keyword: self.category.label().to_string(),
+                word: word.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("names", Some(PiiCategory::Name))]
+    #[case(" Number ", Some(PiiCategory::Number))]
+    #[case("locations", Some(PiiCategory::Location))]
+    #[case("emails", None)]
+    fn test_parse(#[case] input: &str, #[case] expected: Option<PiiCategory>) {
+        assert_eq!(PiiCategory::parse(input), expected);
+    }
+
+    #[test]
+    fn test_hits_label_every_word() {
+        let word = |w: &str, start: f64| TranscriptWord {
+            word: w.to_string(),
+            start_time: start,
+            end_time: start + 0.3,
+            confidence: 0.9,
+        };
+        let entity = SpokenEntity {
+            category: PiiCategory::Name,
+            words: vec![word("Jane", 1.0), word("Doe", 1.3)],
+        };
+
+        let hits = entity.hits();
+
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|hit| hit.keyword == "name"));
+        assert_eq!(hits[1].word.word, "Doe");
+    }
+}
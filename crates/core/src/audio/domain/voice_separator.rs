@@ -0,0 +1,63 @@
+use super::audio_segment::AudioSegment;
+
+/// Audio split into the voice and everything else. The stems add back up
+/// to the input, so mixing them after changing one leaves the other as it
+/// was.
+#[derive(Clone, Debug)]
+pub struct Stems {
+    pub vocals: AudioSegment,
+    pub background: AudioSegment,
+}
+
+impl Stems {
+    /// The sum of both stems, in the vocals' format. A stem that came out
+    /// shorter than the other is treated as silent past its end.
+    pub fn mix(&self) -> AudioSegment {
+        let (vocals, background) = (self.vocals.samples(), self.background.samples());
+        let samples = (0..vocals.len().max(background.len()))
+            .map(|i| {
+                let sum = vocals.get(i).unwrap_or(&0.0) + background.get(i).unwrap_or(&0.0);
+                sum.clamp(-1.0, 1.0)
+            })
+            .collect();
+        AudioSegment::new(samples, self.vocals.sample_rate(), self.vocals.channels())
+    }
+}
+
+/// Domain interface for separating speech from music and ambience, so a
+/// voice disguise changes only the voice.
+///
+/// Implementations return stems in the input's format and length.
+pub trait VoiceSeparator: Send {
+    fn separate(&self, audio: &AudioSegment) -> Result<Stems, Box<dyn std::error::Error>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mix_adds_stems() {
+        let stems = Stems {
+            vocals: AudioSegment::new(vec![0.1, 0.2, 0.3], 16000, 1),
+            background: AudioSegment::new(vec![0.4, -0.2], 16000, 1),
+        };
+
+        let mixed = stems.mix();
+
+        let expected = [0.5, 0.0, 0.3];
+        for (got, want) in mixed.samples().iter().zip(expected) {
+            assert!((got - want).abs() < 1e-6);
+        }
+        assert_eq!(mixed.samples().len(), 3);
+    }
+
+    #[test]
+    fn test_mix_clamps_to_full_scale() {
+        let stems = Stems {
+            vocals: AudioSegment::new(vec![0.8, -0.8], 16000, 1),
+            background: AudioSegment::new(vec![0.8, -0.8], 16000, 1),
+        };
+        assert_eq!(stems.mix().samples(), &[1.0, -1.0]);
+    }
+}
@@ -1,71 +1,80 @@
 use super::audio_segment::AudioSegment;
+use super::bleep_style::BleepStyle;
 use super::censor_region::CensorRegion;
+use super::keyword_hit::KeywordHit;
+use super::keyword_matcher::KeywordMatcher;
 use super::transcript::TranscriptWord;
 
 pub const DEFAULT_BLEEP_PADDING: f64 = 0.05;
-pub const DEFAULT_BLEEP_FREQUENCY: f64 = 1000.0;
-
-/// How censored regions are replaced in the audio.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum BleepMode {
-    /// Replace with a sine-wave tone at a given frequency.
-    Tone,
-    /// Replace with silence (zero samples).
-    Silence,
-}
 
 pub struct WordCensor;
 
 impl WordCensor {
+    /// Words of `transcript` that `matcher` takes for a keyword, in
+    /// transcript order.
+    pub fn find_hits(
+        transcript: &[TranscriptWord],
+        keywords: &[String],
+        matcher: &KeywordMatcher,
+    ) -> Vec<KeywordHit> {
+        transcript
+            .iter()
+            .filter_map(|w| {
+                keywords
+                    .iter()
+                    .find(|k| matcher.matches(k, &w.word))
+                    .map(|k| KeywordHit {
+                        keyword: k.clone(),
+                        word: w.clone(),
+                    })
+            })
+            .collect()
+    }
+
     pub fn find_censor_regions(
         transcript: &[TranscriptWord],
         keywords: &[String],
         padding: f64,
     ) -> Vec<CensorRegion> {
-        if keywords.is_empty() {
-            return Vec::new();
-        }
-
-        let lower_keywords: Vec<String> = keywords.iter().map(|k| k.to_lowercase()).collect();
-
-        transcript
+        Self::find_hits(transcript, keywords, &KeywordMatcher::Exact)
             .iter()
-            .filter(|w| lower_keywords.contains(&w.word.to_lowercase()))
-            .map(|w| CensorRegion {
-                start_time: w.start_time,
-                end_time: w.end_time,
-                padding,
-            })
+            .map(|hit| hit.censor_region(padding))
             .collect()
     }
 
+    /// Replaces each region with `style`, crossfading from the original
+    /// over `fade` seconds at both edges so the cut does not click. Every
+    /// channel gets the same sound.
     pub fn apply_bleep(
         audio: &mut AudioSegment,
         regions: &[CensorRegion],
-        frequency: f64,
-        mode: BleepMode,
+        style: &BleepStyle,
+        fade: f64,
     ) {
-        let sample_rate = audio.sample_rate() as f64;
-        let channels = audio.channels() as usize;
+        let sample_rate = audio.sample_rate();
+        let channels = audio.channels().max(1) as usize;
+        let total_frames = audio.samples().len() / channels;
+        let to_frame = |t: f64| ((t.max(0.0) * sample_rate as f64) as usize).min(total_frames);
+        let fade_frames = (fade.max(0.0) * sample_rate as f64) as usize;
 
         for region in regions {
-            let start = audio.sample_index_at_time(region.effective_start());
-            let end = audio
-                .sample_index_at_time(region.effective_end())
-                .min(audio.samples().len());
-
+            let start = to_frame(region.effective_start());
+            let end = to_frame(region.effective_end());
+            if start >= end {
+                continue;
+            }
+            let bleep = style.render(end - start, sample_rate);
             let samples = audio.samples_mut();
-            match mode {
-                BleepMode::Tone => {
-                    for (offset, sample) in samples[start..end].iter_mut().enumerate() {
-                        let t = offset as f64 / (sample_rate * channels as f64);
-                        *sample = (2.0 * std::f64::consts::PI * frequency * t).sin() as f32 * 0.3;
-                    }
-                }
-                BleepMode::Silence => {
-                    for sample in samples[start..end].iter_mut() {
-                        *sample = 0.0;
-                    }
+            for (offset, &replacement) in bleep.iter().enumerate() {
+                let from_edge = offset.min(end - start - 1 - offset);
+                let weight = if from_edge >= fade_frames {
+                    1.0
+                } else {
+                    (from_edge + 1) as f32 / (fade_frames + 1) as f32
+                };
+                let frame = (start + offset) * channels;
+                for sample in &mut samples[frame..frame + channels] {
+                    *sample += (replacement - *sample) * weight;
                 }
             }
         }
@@ -129,6 +138,31 @@ mod tests {
         assert_eq!(regions.len(), 2);
     }
 
+    #[test]
+    fn test_find_hits_keeps_keyword_and_word() {
+        let transcript = vec![word("hi", 0.0, 0.2), word("JOHN", 0.3, 0.6)];
+        let keywords = vec!["john".to_string()];
+        let hits = WordCensor::find_hits(&transcript, &keywords, &KeywordMatcher::Exact);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].keyword, "john");
+        assert_eq!(hits[0].word, transcript[1]);
+    }
+
+    #[test]
+    fn test_find_hits_phonetic_catches_spelling_variants() {
+        use crate::audio::domain::keyword_matcher::PhoneticStrictness;
+
+        let transcript = vec![word("Mohammed", 0.0, 0.4), word("Michael", 0.5, 0.9)];
+        let keywords = vec!["Muhammad".to_string()];
+        let matcher = KeywordMatcher::Phonetic(PhoneticStrictness::Normal);
+
+        let hits = WordCensor::find_hits(&transcript, &keywords, &matcher);
+
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].keyword, "Muhammad");
+        assert_eq!(hits[0].word.word, "Mohammed");
+    }
+
     #[test]
     fn test_find_no_matches() {
         let transcript = vec![word("hello", 0.0, 0.5)];
@@ -154,12 +188,7 @@ mod tests {
             end_time: 1.0,
             padding: 0.0,
         }];
-        WordCensor::apply_bleep(
-            &mut audio,
-            &regions,
-            DEFAULT_BLEEP_FREQUENCY,
-            BleepMode::Tone,
-        );
+        WordCensor::apply_bleep(&mut audio, &regions, &BleepStyle::default(), 0.0);
 
         let start = audio.sample_index_at_time(0.5);
         let end = audio.sample_index_at_time(1.0);
@@ -181,12 +210,7 @@ mod tests {
             end_time: 1.0,
             padding: 0.0,
         }];
-        WordCensor::apply_bleep(
-            &mut audio,
-            &regions,
-            DEFAULT_BLEEP_FREQUENCY,
-            BleepMode::Tone,
-        );
+        WordCensor::apply_bleep(&mut audio, &regions, &BleepStyle::default(), 0.0);
 
         let before_energy: f64 = audio.samples()[0..8000]
             .iter()
@@ -203,12 +227,7 @@ mod tests {
             end_time: 1.5,
             padding: 0.1,
         }];
-        WordCensor::apply_bleep(
-            &mut audio,
-            &regions,
-            DEFAULT_BLEEP_FREQUENCY,
-            BleepMode::Tone,
-        );
+        WordCensor::apply_bleep(&mut audio, &regions, &BleepStyle::default(), 0.0);
 
         let idx = audio.sample_index_at_time(0.95);
         assert!(audio.samples()[idx].abs() > 0.0);
@@ -218,7 +237,7 @@ mod tests {
     fn test_apply_bleep_empty_regions_no_change() {
         let mut audio = silent_segment(1.0, 16000);
         let original = audio.samples().to_vec();
-        WordCensor::apply_bleep(&mut audio, &[], DEFAULT_BLEEP_FREQUENCY, BleepMode::Tone);
+        WordCensor::apply_bleep(&mut audio, &[], &BleepStyle::default(), 0.0);
         assert_eq!(audio.samples(), &original[..]);
     }
 
@@ -230,12 +249,7 @@ mod tests {
             end_time: 1.0,
             padding: 0.0,
         }];
-        WordCensor::apply_bleep(
-            &mut audio,
-            &regions,
-            DEFAULT_BLEEP_FREQUENCY,
-            BleepMode::Silence,
-        );
+        WordCensor::apply_bleep(&mut audio, &regions, &BleepStyle::Silence, 0.0);
 
         let start = audio.sample_index_at_time(0.5);
         let end = audio.sample_index_at_time(1.0);
@@ -251,4 +265,37 @@ mod tests {
         assert!(audio.samples()[0].abs() > 0.0);
         assert!(audio.samples()[end + 1].abs() > 0.0);
     }
+
+    #[test]
+    fn test_apply_bleep_fades_in_and_out() {
+        let mut audio = AudioSegment::new(vec![0.5f32; 16000], 16000, 1);
+        let regions = vec![CensorRegion {
+            start_time: 0.25,
+            end_time: 0.75,
+            padding: 0.0,
+        }];
+        WordCensor::apply_bleep(&mut audio, &regions, &BleepStyle::Silence, 0.01);
+
+        let samples = audio.samples();
+        let start = audio.sample_index_at_time(0.25);
+        // Partly faded at the edge, fully replaced past the fade.
+        assert!(samples[start] > 0.0 && samples[start] < 0.5);
+        assert_eq!(samples[audio.sample_index_at_time(0.5)], 0.0);
+        assert_eq!(samples[start - 1], 0.5);
+    }
+
+    #[test]
+    fn test_apply_bleep_fills_every_channel() {
+        let mut audio = AudioSegment::new(vec![0.0f32; 32000], 16000, 2);
+        let regions = vec![CensorRegion {
+            start_time: 0.0,
+            end_time: 1.0,
+            padding: 0.0,
+        }];
+        WordCensor::apply_bleep(&mut audio, &regions, &BleepStyle::BrownNoise, 0.0);
+
+        let samples = audio.samples();
+        assert!(samples.chunks_exact(2).all(|frame| frame[0] == frame[1]));
+        assert!(samples.iter().any(|&s| s != 0.0));
+    }
 }
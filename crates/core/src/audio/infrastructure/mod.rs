@@ -1,4 +1,7 @@
 pub mod formant_shift_transformer;
+pub mod onnx_vocal_separator;
+pub mod pattern_entity_recognizer;
 pub mod pitch_shift_transformer;
+pub mod separated_voice_transformer;
 pub mod voice_morph_transformer;
 pub mod whisper_recognizer;
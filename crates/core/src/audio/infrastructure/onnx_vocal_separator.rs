@@ -0,0 +1,239 @@
+use std::f64::consts::PI;
+use std::path::Path;
+use std::sync::Mutex;
+
+use ndarray::Array3;
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+
+use crate::audio::domain::audio_segment::AudioSegment;
+use crate::audio::domain::voice_separator::{Stems, VoiceSeparator};
+use crate::detection::infrastructure::execution_provider::preferred_execution_providers;
+
+/// STFT frame size the model's spectrogram is computed with.
+pub const SEPARATOR_WINDOW_SIZE: usize = 1024;
+
+/// Hop size between successive STFT frames.
+pub const SEPARATOR_HOP_SIZE: usize = 256;
+
+/// Frequency bins per STFT frame.
+const BINS: usize = SEPARATOR_WINDOW_SIZE / 2 + 1;
+
+/// Separates the voice with an ONNX spectrogram-masking model.
+///
+/// The model takes the magnitude spectrogram of the mono mix as a
+/// `[1, frames, bins]` float tensor (a Hann-windowed STFT with
+/// [`SEPARATOR_WINDOW_SIZE`] samples per frame, [`SEPARATOR_HOP_SIZE`]
+/// apart, at the audio's sample rate) and returns a same-shaped mask of
+/// how much of each bin is voice. The mask is applied to every channel's
+/// spectrum to get the vocals; the background is whatever the vocals leave
+/// of the input, so nothing is lost when the stems are mixed back.
+pub struct OnnxVocalSeparator {
+    session: Mutex<ort::session::Session>,
+}
+
+impl OnnxVocalSeparator {
+    pub fn new(model_path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        if !model_path.exists() {
+            return Err(format!(
+                "Vocal separation model not found at: {}",
+                model_path.display()
+            )
+            .into());
+        }
+        let session = ort::session::Session::builder()?
+            .with_execution_providers(preferred_execution_providers())?
+            .commit_from_file(model_path)?;
+        Ok(Self {
+            session: Mutex::new(session),
+        })
+    }
+
+    /// The model's voice mask for `magnitudes`, one row per STFT frame.
+    fn mask(&self, magnitudes: &[Vec<f32>]) -> Result<Vec<Vec<f32>>, Box<dyn std::error::Error>> {
+        let frames = magnitudes.len();
+        let input = Array3::from_shape_vec((1, frames, BINS), magnitudes.concat())?;
+        let input_value = ort::value::TensorRef::from_array_view(input.view())?;
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|e| format!("Session lock poisoned: {e}"))?;
+        let outputs = session.run(ort::inputs![input_value])?;
+        if outputs.len() == 0 {
+            return Err("Vocal separation model produced no outputs".into());
+        }
+        let tensor = outputs[0].try_extract_array::<f32>()?;
+        if tensor.len() != frames * BINS {
+            return Err(format!(
+                "Vocal separation model returned {} values for a {frames}x{BINS} spectrogram",
+                tensor.len()
+            )
+            .into());
+        }
+        let values: Vec<f32> = tensor.iter().map(|m| m.clamp(0.0, 1.0)).collect();
+        Ok(values.chunks_exact(BINS).map(<[f32]>::to_vec).collect())
+    }
+}
+
+impl VoiceSeparator for OnnxVocalSeparator {
+    fn separate(&self, audio: &AudioSegment) -> Result<Stems, Box<dyn std::error::Error>> {
+        let channels = audio.channels().max(1) as usize;
+        let channel = |c: usize| -> Vec<f64> {
+            audio
+                .samples()
+                .iter()
+                .skip(c)
+                .step_by(channels)
+                .map(|&s| s as f64)
+                .collect()
+        };
+        let mono: Vec<f64> = audio
+            .samples()
+            .chunks_exact(channels)
+            .map(|frame| frame.iter().map(|&s| s as f64).sum::<f64>() / channels as f64)
+            .collect();
+        if mono.len() < SEPARATOR_WINDOW_SIZE {
+            // Too short to analyse: leave it all in the background.
+            return Ok(Stems {
+                vocals: AudioSegment::new(
+                    vec![0.0; audio.samples().len()],
+                    audio.sample_rate(),
+                    audio.channels(),
+                ),
+                background: audio.clone(),
+            });
+        }
+
+        let mask = self.mask(&magnitudes(&mono))?;
+        let mut vocals = vec![0.0f32; audio.samples().len()];
+        for c in 0..channels {
+            for (i, v) in masked(&channel(c), &mask).into_iter().enumerate() {
+                vocals[i * channels + c] = v as f32;
+            }
+        }
+        let background = audio
+            .samples()
+            .iter()
+            .zip(&vocals)
+            .map(|(s, v)| s - v)
+            .collect();
+        Ok(Stems {
+            vocals: AudioSegment::new(vocals, audio.sample_rate(), audio.channels()),
+            background: AudioSegment::new(background, audio.sample_rate(), audio.channels()),
+        })
+    }
+}
+
+fn hann() -> Vec<f64> {
+    (0..SEPARATOR_WINDOW_SIZE)
+        .map(|i| 0.5 * (1.0 - (2.0 * PI * i as f64 / SEPARATOR_WINDOW_SIZE as f64).cos()))
+        .collect()
+}
+
+fn frame_count(len: usize) -> usize {
+    (len - SEPARATOR_WINDOW_SIZE) / SEPARATOR_HOP_SIZE + 1
+}
+
+/// Magnitude spectrogram of `samples`, one row of [`BINS`] per frame.
+fn magnitudes(samples: &[f64]) -> Vec<Vec<f32>> {
+    let hann = hann();
+    let fft = FftPlanner::<f64>::new().plan_fft_forward(SEPARATOR_WINDOW_SIZE);
+    (0..frame_count(samples.len()))
+        .map(|frame| {
+            let start = frame * SEPARATOR_HOP_SIZE;
+            let mut buf: Vec<Complex<f64>> = (0..SEPARATOR_WINDOW_SIZE)
+                .map(|i| Complex::new(samples[start + i] * hann[i], 0.0))
+                .collect();
+            fft.process(&mut buf);
+            buf[..BINS].iter().map(|c| c.norm() as f32).collect()
+        })
+        .collect()
+}
+
+/// `samples` with each STFT bin scaled by `mask`, resynthesized by
+/// overlap-add. Samples no frame covers are left out of the result (zero).
+fn masked(samples: &[f64], mask: &[Vec<f32>]) -> Vec<f64> {
+    let n = samples.len();
+    let hann = hann();
+    let mut planner = FftPlanner::<f64>::new();
+    let forward = planner.plan_fft_forward(SEPARATOR_WINDOW_SIZE);
+    let inverse = planner.plan_fft_inverse(SEPARATOR_WINDOW_SIZE);
+    let mut output = vec![0.0f64; n];
+    let mut window_sum = vec![0.0f64; n];
+
+    for (frame, gains) in mask.iter().enumerate().take(frame_count(n)) {
+        let start = frame * SEPARATOR_HOP_SIZE;
+        let mut buf: Vec<Complex<f64>> = (0..SEPARATOR_WINDOW_SIZE)
+            .map(|i| Complex::new(samples[start + i] * hann[i], 0.0))
+            .collect();
+        forward.process(&mut buf);
+        for k in 0..BINS {
+            buf[k] *= gains[k] as f64;
+        }
+        // Keep the spectrum conjugate-symmetric so the result is real.
+        for k in 1..BINS - 1 {
+            buf[SEPARATOR_WINDOW_SIZE - k] = buf[k].conj();
+        }
+        inverse.process(&mut buf);
+        let norm = 1.0 / SEPARATOR_WINDOW_SIZE as f64;
+        for i in 0..SEPARATOR_WINDOW_SIZE {
+            output[start + i] += buf[i].re * norm * hann[i];
+            window_sum[start + i] += hann[i] * hann[i];
+        }
+    }
+
+    for (value, sum) in output.iter_mut().zip(&window_sum) {
+        *value = if *sum > 1e-6 { *value / sum } else { 0.0 };
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(len: usize) -> Vec<f64> {
+        (0..len)
+            .map(|i| (2.0 * PI * 440.0 * i as f64 / 16000.0).sin() * 0.5)
+            .collect()
+    }
+
+    #[test]
+    fn test_new_nonexistent_path_returns_error() {
+        let result = OnnxVocalSeparator::new(Path::new("/nonexistent/separator.onnx"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_full_mask_reconstructs_input() {
+        let samples = tone(8000);
+        let mask = vec![vec![1.0; BINS]; frame_count(samples.len())];
+
+        let output = masked(&samples, &mask);
+
+        // Away from the edges, where frames overlap fully.
+        for i in SEPARATOR_WINDOW_SIZE..samples.len() - SEPARATOR_WINDOW_SIZE {
+            assert!((output[i] - samples[i]).abs() < 1e-6, "sample {i}");
+        }
+    }
+
+    #[test]
+    fn test_empty_mask_silences_input() {
+        let samples = tone(4000);
+        let mask = vec![vec![0.0; BINS]; frame_count(samples.len())];
+        assert!(masked(&samples, &mask).iter().all(|s| s.abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_magnitudes_peak_at_tone_frequency() {
+        let spectrogram = magnitudes(&tone(4000));
+        let peak = spectrogram[0]
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(bin, _)| bin)
+            .unwrap();
+        let expected = (440.0 / 16000.0 * SEPARATOR_WINDOW_SIZE as f64).round() as usize;
+        assert!(peak.abs_diff(expected) <= 1);
+    }
+}
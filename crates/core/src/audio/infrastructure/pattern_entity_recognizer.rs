@@ -0,0 +1,342 @@
+use std::collections::HashSet;
+
+use crate::audio::domain::entity_recognizer::EntityRecognizer;
+use crate::audio::domain::spoken_entity::{PiiCategory, SpokenEntity};
+use crate::audio::domain::transcript::TranscriptWord;
+
+/// Digits a spoken number needs before it is bleeped. Shorter ones are
+/// usually counts, times, and prices rather than phone or account numbers.
+pub const MIN_NUMBER_DIGITS: usize = 4;
+
+/// Longest run of capitalized words taken as one name or place.
+const MAX_ENTITY_WORDS: usize = 3;
+
+/// Capitalized words that are not names: sentence openers, titles, days,
+/// and months.
+const COMMON_CAPITALIZED: &str = "\
+    i i'm i've i'll i'd a an the and but or so if when what where who why how this that these \
+    those there here it it's its we you he she they my our your his her their yes no okay ok \
+    well oh hi hello hey thanks thank please let's now then just also because after before \
+    today tomorrow yesterday some all maybe sure right actually do did does is are was were \
+    can could would should will not don't in on at for with from to of as mr mrs ms miss dr \
+    doctor professor monday tuesday wednesday thursday friday saturday sunday january february \
+    march april may june july august september october november december";
+
+/// Spoken number words and the digits each one stands for.
+const NUMBER_WORDS: &[(&str, usize)] = &[
+    ("zero", 1),
+    ("one", 1),
+    ("two", 1),
+    ("three", 1),
+    ("four", 1),
+    ("five", 1),
+    ("six", 1),
+    ("seven", 1),
+    ("eight", 1),
+    ("nine", 1),
+    ("ten", 2),
+    ("eleven", 2),
+    ("twelve", 2),
+    ("thirteen", 2),
+    ("fourteen", 2),
+    ("fifteen", 2),
+    ("sixteen", 2),
+    ("seventeen", 2),
+    ("eighteen", 2),
+    ("nineteen", 2),
+    ("twenty", 2),
+    ("thirty", 2),
+    ("forty", 2),
+    ("fifty", 2),
+    ("sixty", 2),
+    ("seventy", 2),
+    ("eighty", 2),
+    ("ninety", 2),
+    ("hundred", 2),
+    ("thousand", 3),
+    ("double", 1),
+    ("triple", 2),
+];
+
+/// Words that end a street name, such as "Baker Street".
+const STREET_SUFFIXES: &[&str] = &[
+    "street",
+    "st",
+    "avenue",
+    "ave",
+    "road",
+    "rd",
+    "lane",
+    "drive",
+    "boulevard",
+    "way",
+    "court",
+    "place",
+    "close",
+    "crescent",
+    "terrace",
+    "square",
+    "highway",
+    "parkway",
+];
+
+/// Words after which a capitalized name is a place.
+const LOCATION_PREPOSITIONS: &[&str] = &["in", "at", "from", "near"];
+
+/// Word pairs after which the next word is a name, even uncapitalized.
+const NAME_CUES: &[&[&str]] = &[&["name", "is"], &["name's"], &["called"], &["call", "me"]];
+
+/// Finds names, numbers, and places in a transcript with word patterns,
+/// without a model.
+///
+/// - Numbers are runs of digits or spoken number words ("five five five
+///   one two"), bleeped once they add up to [`MIN_NUMBER_DIGITS`].
+/// - Locations are a number or capitalized words ending in a street
+///   suffix ("221 Baker Street"), or capitalized words after "in", "at",
+///   "from", or "near".
+/// - Names are other capitalized words that don't open the transcript and
+///   aren't common sentence openers, and any word after "my name is" or
+///   "call me".
+///
+/// It relies on the recognizer's capitalization, and a capitalized noun
+/// opening a sentence can read as a name. Hits are meant to be reviewed.
+pub struct PatternEntityRecognizer {
+    categories: HashSet<PiiCategory>,
+}
+
+impl PatternEntityRecognizer {
+    /// Finds only the entities in `categories`.
+    pub fn new(categories: impl IntoIterator<Item = PiiCategory>) -> Self {
+        Self {
+            categories: categories.into_iter().collect(),
+        }
+    }
+
+    /// The entity starting at word `i` and the index just past it.
+    fn entity_at(
+        &self,
+        words: &[TranscriptWord],
+        lower: &[String],
+        i: usize,
+    ) -> Option<(PiiCategory, usize)> {
+        let (numbers, digits) = number_run(lower, i);
+        if numbers > i {
+            // A house number leads an address.
+            if self.categories.contains(&PiiCategory::Location) {
+                if let Some(end) = street_end(words, lower, numbers) {
+                    return Some((PiiCategory::Location, end));
+                }
+            }
+            return (digits >= MIN_NUMBER_DIGITS).then_some((PiiCategory::Number, numbers));
+        }
+
+        let cued = follows_name_cue(lower, i);
+        if !cued && !(i > 0 && is_proper(words, lower, i)) {
+            return None;
+        }
+        if let Some(end) = street_end(words, lower, i) {
+            return Some((PiiCategory::Location, end));
+        }
+        let mut end = i + 1;
+        while end < words.len() && end - i < MAX_ENTITY_WORDS && is_proper(words, lower, end) {
+            end += 1;
+        }
+        let after_preposition = i > 0 && LOCATION_PREPOSITIONS.contains(&lower[i - 1].as_str());
+        if after_preposition && !cued {
+            Some((PiiCategory::Location, end))
+        } else {
+            Some((PiiCategory::Name, end))
+        }
+    }
+}
+
+impl EntityRecognizer for PatternEntityRecognizer {
+    fn find_entities(
+        &self,
+        transcript: &[TranscriptWord],
+    ) -> Result<Vec<SpokenEntity>, Box<dyn std::error::Error>> {
+        let lower: Vec<String> = transcript.iter().map(|w| w.word.to_lowercase()).collect();
+        let mut entities = Vec::new();
+        let mut i = 0;
+        while i < transcript.len() {
+            match self.entity_at(transcript, &lower, i) {
+                Some((category, end)) => {
+                    if self.categories.contains(&category) {
+                        entities.push(SpokenEntity {
+                            category,
+                            words: transcript[i..end].to_vec(),
+                        });
+                    }
+                    i = end;
+                }
+                None => i += 1,
+            }
+        }
+        Ok(entities)
+    }
+}
+
+/// Digits `word` stands for, if it is part of a number. "Oh" only counts
+/// inside a number, as in "five oh two".
+fn number_digits(word: &str, in_run: bool) -> Option<usize> {
+    let digits = word.chars().filter(char::is_ascii_digit).count();
+    if digits > 0 {
+        let numeric = word
+            .chars()
+            .all(|c| c.is_ascii_digit() || "-.,/+()".contains(c));
+        return numeric.then_some(digits);
+    }
+    if in_run && (word == "oh" || word == "o") {
+        return Some(1);
+    }
+    NUMBER_WORDS
+        .iter()
+        .find(|(number, _)| *number == word)
+        .map(|&(_, digits)| digits)
+}
+
+/// The end of the run of number words starting at `start`, and how many
+/// digits it adds up to.
+fn number_run(lower: &[String], start: usize) -> (usize, usize) {
+    let mut end = start;
+    let mut digits = 0;
+    while let Some(d) = lower.get(end).and_then(|w| number_digits(w, end > start)) {
+        digits += d;
+        end += 1;
+    }
+    (end, digits)
+}
+
+/// The end of a street name starting at `start`: capitalized words
+/// followed by a street suffix, which may be lowercase.
+fn street_end(words: &[TranscriptWord], lower: &[String], start: usize) -> Option<usize> {
+    let mut end = start;
+    while end < words.len() && end - start <= MAX_ENTITY_WORDS {
+        if end > start && STREET_SUFFIXES.contains(&lower[end].as_str()) {
+            return Some(end + 1);
+        }
+        if !is_capitalized(&words[end].word) {
+            return None;
+        }
+        end += 1;
+    }
+    None
+}
+
+fn follows_name_cue(lower: &[String], i: usize) -> bool {
+    NAME_CUES.iter().any(|cue| {
+        i >= cue.len()
+            && lower[i - cue.len()..i]
+                .iter()
+                .zip(cue.iter())
+                .all(|(word, cue)| word == cue)
+    })
+}
+
+/// Capitalized, and not a common word or a number.
+fn is_proper(words: &[TranscriptWord], lower: &[String], i: usize) -> bool {
+    is_capitalized(&words[i].word)
+        && !COMMON_CAPITALIZED.split_whitespace().any(|c| c == lower[i])
+        && number_digits(&lower[i], false).is_none()
+}
+
+fn is_capitalized(word: &str) -> bool {
+    word.chars().next().is_some_and(char::is_uppercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcript(text: &str) -> Vec<TranscriptWord> {
+        text.split_whitespace()
+            .enumerate()
+            .map(|(i, w)| TranscriptWord {
+                word: w.to_string(),
+                start_time: i as f64 * 0.5,
+                end_time: i as f64 * 0.5 + 0.4,
+                confidence: 0.9,
+            })
+            .collect()
+    }
+
+    fn found(text: &str, categories: &[PiiCategory]) -> Vec<(PiiCategory, String)> {
+        PatternEntityRecognizer::new(categories.iter().copied())
+            .find_entities(&transcript(text))
+            .unwrap()
+            .into_iter()
+            .map(|entity| {
+                let words: Vec<String> = entity.words.into_iter().map(|w| w.word).collect();
+                (entity.category, words.join(" "))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_finds_capitalized_names() {
+        assert_eq!(
+            found("So I met Jane Doe yesterday", &PiiCategory::ALL),
+            vec![(PiiCategory::Name, "Jane Doe".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_name_cue_finds_lowercase_name() {
+        assert_eq!(
+            found("hi my name is bob", &PiiCategory::ALL),
+            vec![(PiiCategory::Name, "bob".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_common_capitalized_words_are_not_names() {
+        assert!(found("Well I think The plan works on Monday", &PiiCategory::ALL).is_empty());
+    }
+
+    #[test]
+    fn test_finds_spoken_and_written_numbers() {
+        assert_eq!(
+            found(
+                "call five five five oh one two three or 555-0199",
+                &PiiCategory::ALL
+            ),
+            vec![
+                (
+                    PiiCategory::Number,
+                    "five five five oh one two three".to_string()
+                ),
+                (PiiCategory::Number, "555-0199".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_short_numbers_are_kept() {
+        assert!(found("we need two boxes at 10", &PiiCategory::ALL).is_empty());
+    }
+
+    #[test]
+    fn test_finds_addresses_and_places() {
+        assert_eq!(
+            found(
+                "she lives at 221 Baker Street near Camden",
+                &PiiCategory::ALL
+            ),
+            vec![
+                (PiiCategory::Location, "221 Baker Street".to_string()),
+                (PiiCategory::Location, "Camden".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_only_requested_categories_are_returned() {
+        assert_eq!(
+            found(
+                "Jane lives in Leeds on 0113 496 0000",
+                &[PiiCategory::Location]
+            ),
+            vec![(PiiCategory::Location, "Leeds".to_string())]
+        );
+    }
+}
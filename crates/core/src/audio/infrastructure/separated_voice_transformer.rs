@@ -0,0 +1,77 @@
+use crate::audio::domain::audio_segment::AudioSegment;
+use crate::audio::domain::audio_transformer::AudioTransformer;
+use crate::audio::domain::voice_separator::VoiceSeparator;
+
+/// Decorator that disguises only the voice: the audio is split into
+/// stems, `inner` transforms the vocals, and the untouched background
+/// (music, ambience) is mixed back in.
+pub struct SeparatedVoiceTransformer {
+    separator: Box<dyn VoiceSeparator>,
+    inner: Box<dyn AudioTransformer>,
+}
+
+impl SeparatedVoiceTransformer {
+    pub fn new(separator: Box<dyn VoiceSeparator>, inner: Box<dyn AudioTransformer>) -> Self {
+        Self { separator, inner }
+    }
+}
+
+impl AudioTransformer for SeparatedVoiceTransformer {
+    fn transform(&self, audio: &mut AudioSegment) -> Result<(), Box<dyn std::error::Error>> {
+        let mut stems = self.separator.separate(audio)?;
+        self.inner.transform(&mut stems.vocals)?;
+        *audio = stems.mix();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::domain::voice_separator::Stems;
+
+    /// Calls the first half of each sample voice, the rest background.
+    struct HalfSeparator;
+
+    impl VoiceSeparator for HalfSeparator {
+        fn separate(&self, audio: &AudioSegment) -> Result<Stems, Box<dyn std::error::Error>> {
+            let half: Vec<f32> = audio.samples().iter().map(|s| s / 2.0).collect();
+            Ok(Stems {
+                vocals: AudioSegment::new(half.clone(), audio.sample_rate(), audio.channels()),
+                background: AudioSegment::new(half, audio.sample_rate(), audio.channels()),
+            })
+        }
+    }
+
+    struct Mute;
+
+    impl AudioTransformer for Mute {
+        fn transform(&self, audio: &mut AudioSegment) -> Result<(), Box<dyn std::error::Error>> {
+            audio.samples_mut().fill(0.0);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_only_vocals_are_transformed() {
+        let transformer = SeparatedVoiceTransformer::new(Box::new(HalfSeparator), Box::new(Mute));
+        let mut audio = AudioSegment::new(vec![0.4, -0.8, 0.2], 16000, 1);
+
+        transformer.transform(&mut audio).unwrap();
+
+        assert_eq!(audio.samples(), &[0.2, -0.4, 0.1]);
+    }
+
+    #[test]
+    fn test_separator_errors_propagate() {
+        struct Failing;
+        impl VoiceSeparator for Failing {
+            fn separate(&self, _: &AudioSegment) -> Result<Stems, Box<dyn std::error::Error>> {
+                Err("no model".into())
+            }
+        }
+        let transformer = SeparatedVoiceTransformer::new(Box::new(Failing), Box::new(Mute));
+        let mut audio = AudioSegment::new(vec![0.4], 16000, 1);
+        assert!(transformer.transform(&mut audio).is_err());
+    }
+}
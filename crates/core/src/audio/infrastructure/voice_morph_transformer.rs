@@ -6,6 +6,10 @@ use crate::audio::infrastructure::formant_shift_transformer::FormantShiftTransfo
 /// The shift varies by +/- this amount around the base shift via random walk.
 pub const DEFAULT_CONTOUR_WARP_RANGE: f64 = 0.5;
 
+/// Default seed for the contour random walk. The same seed, input, and
+/// settings always produce the same output audio.
+pub const DEFAULT_VOICE_MORPH_SEED: u64 = 42;
+
 /// Step size for the random walk (fraction of warp range per analysis hop).
 const CONTOUR_STEP_SIZE: f64 = 0.1;
 
@@ -17,6 +21,7 @@ const CONTOUR_STEP_SIZE: f64 = 0.1;
 pub struct VoiceMorphTransformer {
     base_semitones: f64,
     contour_warp_range: f64,
+    seed: u64,
     formant_shifter: FormantShiftTransformer,
 }
 
@@ -25,9 +30,17 @@ impl VoiceMorphTransformer {
         Self {
             base_semitones,
             contour_warp_range,
+            seed: DEFAULT_VOICE_MORPH_SEED,
             formant_shifter: FormantShiftTransformer::new(formant_ratio),
         }
     }
+
+    /// Seeds the contour random walk. Re-exporting with the seed of an
+    /// earlier export reproduces its audio exactly.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
 }
 
 /// Simple LCG for deterministic random walk.
@@ -76,7 +89,7 @@ impl AudioTransformer for VoiceMorphTransformer {
 
         // Step 2: Generate synthesis marks covering the full output duration
         // with per-mark varying shift via random walk
-        let mut rng = Lcg::new(42);
+        let mut rng = Lcg::new(self.seed);
         let mut warp_offset = 0.0f64;
         let mut grain_sources: Vec<(f64, usize)> = Vec::new();
         let mut synth_pos = 0.0f64;
@@ -211,4 +224,38 @@ mod tests {
             "Morph with contour warping should differ from constant shift"
         );
     }
+
+    #[test]
+    fn test_voice_morph_same_seed_is_identical() {
+        let mut first = speech_like_segment(16000);
+        let mut second = first.clone();
+        let transformer = || {
+            VoiceMorphTransformer::new(
+                DEFAULT_SEMITONES,
+                DEFAULT_FORMANT_SHIFT_RATIO,
+                DEFAULT_CONTOUR_WARP_RANGE,
+            )
+            .with_seed(7)
+        };
+        transformer().transform(&mut first).unwrap();
+        transformer().transform(&mut second).unwrap();
+        assert_eq!(first.samples(), second.samples());
+    }
+
+    #[test]
+    fn test_voice_morph_different_seeds_differ() {
+        let mut first = speech_like_segment(16000);
+        let mut second = first.clone();
+        let transformer = |seed| {
+            VoiceMorphTransformer::new(
+                DEFAULT_SEMITONES,
+                DEFAULT_FORMANT_SHIFT_RATIO,
+                DEFAULT_CONTOUR_WARP_RANGE,
+            )
+            .with_seed(seed)
+        };
+        transformer(1).transform(&mut first).unwrap();
+        transformer(2).transform(&mut second).unwrap();
+        assert_ne!(first.samples(), second.samples());
+    }
 }
@@ -3,15 +3,22 @@ use std::path::{Path, PathBuf};
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
 
 use crate::audio::domain::audio_segment::AudioSegment;
+use crate::audio::domain::speech_language::SpeechLanguage;
 use crate::audio::domain::speech_recognizer::SpeechRecognizer;
 use crate::audio::domain::transcript::TranscriptWord;
+use crate::shared::constants::WHISPER_SAMPLE_RATE;
 
 /// Speech recognizer using whisper.cpp via whisper-rs.
 ///
-/// Transcribes audio to word-level timestamped text using the Whisper tiny.en model.
+/// Transcribes audio to word-level timestamped text using the multilingual
+/// Whisper tiny model, in the language it detects unless one is set with
+/// [`WhisperRecognizer::with_language`]. Expects mono audio at
+/// [`WHISPER_SAMPLE_RATE`], as `FfmpegAudioReader` produces when asked for
+/// it.
 #[derive(Debug)]
 pub struct WhisperRecognizer {
     model_path: PathBuf,
+    language: SpeechLanguage,
 }
 
 impl WhisperRecognizer {
@@ -21,9 +28,16 @@ impl WhisperRecognizer {
         }
         Ok(Self {
             model_path: model_path.to_path_buf(),
+            language: SpeechLanguage::default(),
         })
     }
 
+    /// Transcribes in `language` instead of detecting it.
+    pub fn with_language(mut self, language: SpeechLanguage) -> Self {
+        self.language = language;
+        self
+    }
+
     pub fn model_path(&self) -> &Path {
         &self.model_path
     }
@@ -34,6 +48,14 @@ impl SpeechRecognizer for WhisperRecognizer {
         &self,
         audio: &AudioSegment,
     ) -> Result<Vec<TranscriptWord>, Box<dyn std::error::Error>> {
+        if audio.sample_rate() != WHISPER_SAMPLE_RATE || audio.channels() != 1 {
+            return Err(format!(
+                "Whisper needs mono {WHISPER_SAMPLE_RATE} Hz audio, got {} channels at {} Hz",
+                audio.channels(),
+                audio.sample_rate()
+            )
+            .into());
+        }
         let ctx = WhisperContext::new_with_params(
             self.model_path.to_str().ok_or("Invalid model path")?,
             WhisperContextParameters::default(),
@@ -45,7 +67,7 @@ impl SpeechRecognizer for WhisperRecognizer {
             .map_err(|e| format!("Failed to create Whisper state: {e}"))?;
 
         let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 0 });
-        params.set_language(Some("en"));
+        params.set_language(Some(self.language.code()));
         params.set_translate(false);
         params.set_token_timestamps(true);
         params.set_print_special(false);
@@ -68,8 +90,8 @@ impl SpeechRecognizer for WhisperRecognizer {
                 None => continue,
             };
 
-            let n_tokens = segment.n_tokens();
-            for tok_idx in 0..n_tokens {
+            let mut tokens = Vec::new();
+            for tok_idx in 0..segment.n_tokens() {
                 let token = match segment.get_token(tok_idx) {
                     Some(t) => t,
                     None => continue,
@@ -82,35 +104,78 @@ impl SpeechRecognizer for WhisperRecognizer {
 
                 // Skip special tokens (start with [, like [_BEG_], [_SOT_], etc.)
                 let trimmed = text.trim();
-                if trimmed.is_empty() || trimmed.starts_with('[') || trimmed.starts_with('<') {
+                if trimmed.starts_with('[') || trimmed.starts_with('<') {
                     continue;
                 }
 
-                let token_data = token.token_data();
-                let prob = token.token_probability();
-
                 // Token timestamps are in centiseconds (10ms units)
-                let start_time = token_data.t0 as f64 / 100.0;
-                let end_time = token_data.t1 as f64 / 100.0;
-
-                // Skip tokens with invalid timestamps
-                if end_time <= start_time {
-                    continue;
-                }
-
-                words.push(TranscriptWord {
-                    word: trimmed.to_string(),
-                    start_time,
-                    end_time,
-                    confidence: prob,
+                let token_data = token.token_data();
+                tokens.push(Token {
+                    text: text.to_string(),
+                    start_time: token_data.t0 as f64 / 100.0,
+                    end_time: token_data.t1 as f64 / 100.0,
+                    probability: token.token_probability(),
                 });
             }
+            // A segment never ends mid-word.
+            words.extend(join_tokens(tokens));
         }
 
         Ok(words)
     }
 }
 
+/// One decoded Whisper token. Tokens are sub-word pieces; a leading space
+/// marks the start of a word.
+struct Token {
+    text: String,
+    start_time: f64,
+    end_time: f64,
+    probability: f32,
+}
+
+/// Joins tokens into words, so a keyword Whisper spells as several tokens
+/// ("Johnson" as " John" + "son") still matches. Punctuation never extends
+/// a word's span and is stripped from word edges. A word's confidence is
+/// that of its least likely token; words without a valid span are dropped.
+fn join_tokens(tokens: Vec<Token>) -> Vec<TranscriptWord> {
+    let mut words: Vec<TranscriptWord> = Vec::new();
+    let mut starts_word = true;
+    for token in tokens {
+        let new_word = starts_word || token.text.starts_with(char::is_whitespace);
+        let piece = token.text.trim();
+        let is_word = piece.chars().any(char::is_alphanumeric);
+        match words.last_mut() {
+            Some(word) if !new_word => {
+                word.word.push_str(piece);
+                if is_word {
+                    word.end_time = word.end_time.max(token.end_time);
+                    word.confidence = word.confidence.min(token.probability);
+                }
+            }
+            _ if is_word => words.push(TranscriptWord {
+                word: piece.to_string(),
+                start_time: token.start_time,
+                end_time: token.end_time,
+                confidence: token.probability,
+            }),
+            _ => {}
+        }
+        // Spaced-off punctuation leaves the next token starting a word.
+        starts_word = token.text.ends_with(char::is_whitespace) || (new_word && !is_word);
+    }
+    words
+        .into_iter()
+        .filter_map(|mut w| {
+            w.word = w
+                .word
+                .trim_matches(|c: char| !c.is_alphanumeric())
+                .to_string();
+            (w.end_time > w.start_time).then_some(w)
+        })
+        .collect()
+}
+
 fn num_cpus() -> usize {
     std::thread::available_parallelism()
         .map(|n| n.get())
@@ -137,6 +202,66 @@ mod tests {
         );
     }
 
+    fn token(text: &str, start: f64, end: f64, probability: f32) -> Token {
+        Token {
+            text: text.to_string(),
+            start_time: start,
+            end_time: end,
+            probability,
+        }
+    }
+
+    #[test]
+    fn test_join_tokens_merges_sub_words() {
+        let words = join_tokens(vec![
+            token(" Call", 0.0, 0.3, 0.9),
+            token(" John", 0.3, 0.5, 0.8),
+            token("son", 0.5, 0.7, 0.6),
+            token(".", 0.7, 0.8, 0.99),
+        ]);
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[1].word, "Johnson");
+        assert_eq!(words[1].start_time, 0.3);
+        assert_eq!(words[1].end_time, 0.7);
+        assert_eq!(words[1].confidence, 0.6);
+    }
+
+    #[test]
+    fn test_join_tokens_strips_punctuation_and_empty_spans() {
+        let words = join_tokens(vec![
+            token(" \"secret", 0.0, 0.4, 0.9),
+            token(",\"", 0.4, 0.5, 0.9),
+            token(" -", 0.5, 0.6, 0.9),
+            token(" um", 0.6, 0.6, 0.9),
+        ]);
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].word, "secret");
+        assert_eq!(words[0].end_time, 0.4);
+    }
+
+    #[test]
+    fn test_join_tokens_keeps_inner_punctuation() {
+        let words = join_tokens(vec![
+            token(" \"", 0.0, 0.1, 0.9),
+            token("well", 0.1, 0.3, 0.9),
+            token("-", 0.3, 0.4, 0.9),
+            token("known", 0.4, 0.6, 0.9),
+        ]);
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].word, "well-known");
+        assert_eq!(words[0].start_time, 0.1);
+    }
+
+    #[test]
+    fn test_transcribe_rejects_stereo_audio() {
+        let recognizer = WhisperRecognizer {
+            model_path: PathBuf::from("unused.bin"),
+            language: SpeechLanguage::default(),
+        };
+        let audio = AudioSegment::new(vec![0.0; 3200], WHISPER_SAMPLE_RATE, 2);
+        assert!(recognizer.transcribe(&audio).is_err());
+    }
+
     #[test]
     #[ignore] // Requires whisper model file
     fn test_transcribe_does_not_crash_on_sine_wave() {
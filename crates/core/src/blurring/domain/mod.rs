@@ -1 +1,4 @@
 pub mod frame_blurrer;
+pub mod overlap_merger;
+pub mod redaction_rect;
+pub mod weak_blur;
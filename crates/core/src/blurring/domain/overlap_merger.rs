@@ -0,0 +1,248 @@
+use crate::shared::region::Region;
+
+/// How far, in ellipse radii, the smooth union of two ellipses reaches
+/// into the crease between them. Fills the sliver left where two faces
+/// touch without visibly rounding out faces that only overlap slightly.
+const CREASE_FILL: f64 = 0.35;
+
+/// Regions whose rectangles overlap or touch, blurred as one so shared
+/// pixels are blurred once and no sharp gap is left between them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MergedRegion {
+    /// Bounding rectangle of every member, in frame pixels.
+    pub bounds: Region,
+    pub members: Vec<Region>,
+}
+
+/// Splits `regions` into those that touch no other region, in their
+/// original order, and groups of touching regions.
+///
+/// Grouping is transitive: a chain of faces each overlapping the next is
+/// one group even when its ends are far apart.
+pub fn merge_overlapping(regions: &[Region]) -> (Vec<Region>, Vec<MergedRegion>) {
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    let mut parent: Vec<usize> = (0..regions.len()).collect();
+
+    for i in 0..regions.len() {
+        for j in i + 1..regions.len() {
+            if touches(&regions[i], &regions[j]) {
+                let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<Region>> = vec![Vec::new(); regions.len()];
+    for (i, region) in regions.iter().enumerate() {
+        let r = root(&mut parent, i);
+        groups[r].push(region.clone());
+    }
+
+    let mut single = Vec::new();
+    let mut merged = Vec::new();
+    for mut members in groups.into_iter().filter(|g| !g.is_empty()) {
+        if members.len() == 1 {
+            single.append(&mut members);
+        } else {
+            merged.push(MergedRegion {
+                bounds: bounds_of(&members),
+                members,
+            });
+        }
+    }
+    (single, merged)
+}
+
+impl MergedRegion {
+    /// Whether frame pixel `(x, y)` is covered by the merged mask.
+    ///
+    /// Rectangular members cover their union. Elliptical members cover a
+    /// smooth union of their ellipses, which contains every ellipse and
+    /// also fills the crease where two of them meet.
+    ///
+    /// Only the two nearest ellipses are blended. Blending every member
+    /// lowers the distance a little more per member, so a lookahead window
+    /// of near-identical ellipses from one moving face would swell the
+    /// mask well past any of them.
+    pub fn covers(&self, x: i32, y: i32, elliptical: bool) -> bool {
+        if !elliptical {
+            return self
+                .members
+                .iter()
+                .any(|r| x >= r.x && x < r.x + r.width && y >= r.y && y < r.y + r.height);
+        }
+        let (mut nearest, mut second) = (f64::INFINITY, f64::INFINITY);
+        for d in self
+            .members
+            .iter()
+            .filter_map(|r| ellipse_distance(r, x, y))
+        {
+            if d < nearest {
+                second = nearest;
+                nearest = d;
+            } else if d < second {
+                second = d;
+            }
+        }
+        smooth_min(nearest, second, CREASE_FILL) <= 1.0
+    }
+}
+
+/// Normalized distance of `(x, y)` from the center of `region`'s ellipse:
+/// 1.0 on the outline, measured the same way `composite_ellipse` tests
+/// pixels.
+fn ellipse_distance(region: &Region, x: i32, y: i32) -> Option<f64> {
+    let (ecx, ecy) = region.ellipse_center_in_roi();
+    let (semi_a, semi_b) = region.ellipse_axes();
+    if semi_a <= 0.0 || semi_b <= 0.0 {
+        return None;
+    }
+    let dx = (x - region.x) as f64 - ecx;
+    let dy = (y - region.y) as f64 - ecy;
+    Some((dx * dx / (semi_a * semi_a) + dy * dy / (semi_b * semi_b)).sqrt())
+}
+
+/// Polynomial smooth minimum: equal to `min(a, b)` when they differ by
+/// more than `k`, and up to `k / 4` below it when they are close.
+fn smooth_min(a: f64, b: f64, k: f64) -> f64 {
+    let h = (k - (a - b).abs()).max(0.0);
+    a.min(b) - h * h / (4.0 * k)
+}
+
+fn touches(a: &Region, b: &Region) -> bool {
+    a.x <= b.x + b.width && b.x <= a.x + a.width && a.y <= b.y + b.height && b.y <= a.y + a.height
+}
+
+fn bounds_of(members: &[Region]) -> Region {
+    let x = members.iter().map(|r| r.x).min().unwrap_or(0);
+    let y = members.iter().map(|r| r.y).min().unwrap_or(0);
+    let right = members.iter().map(|r| r.x + r.width).max().unwrap_or(0);
+    let bottom = members.iter().map(|r| r.y + r.height).max().unwrap_or(0);
+    Region {
+        x,
+        y,
+        width: right - x,
+        height: bottom - y,
+        track_id: None,
+        full_width: None,
+        full_height: None,
+        unclamped_x: None,
+        unclamped_y: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(x: i32, y: i32, w: i32, h: i32) -> Region {
+        Region {
+            x,
+            y,
+            width: w,
+            height: h,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    #[test]
+    fn test_separate_regions_stay_single() {
+        let regions = [region(0, 0, 10, 10), region(20, 0, 10, 10)];
+        let (single, merged) = merge_overlapping(&regions);
+        assert_eq!(single, regions.to_vec());
+        assert!(merged.is_empty());
+    }
+
+    #[test]
+    fn test_overlapping_regions_merge_with_bounds() {
+        let regions = [
+            region(0, 0, 20, 20),
+            region(50, 50, 5, 5),
+            region(15, 5, 20, 30),
+        ];
+        let (single, merged) = merge_overlapping(&regions);
+        assert_eq!(single, vec![region(50, 50, 5, 5)]);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].bounds, region(0, 0, 35, 35));
+        assert_eq!(merged[0].members.len(), 2);
+    }
+
+    #[test]
+    fn test_touching_regions_merge() {
+        let (_, merged) = merge_overlapping(&[region(0, 0, 10, 10), region(10, 0, 10, 10)]);
+        assert_eq!(merged.len(), 1);
+    }
+
+    #[test]
+    fn test_chains_merge_transitively() {
+        let regions = [
+            region(0, 0, 10, 10),
+            region(30, 0, 10, 10),
+            region(8, 0, 25, 10),
+        ];
+        let (single, merged) = merge_overlapping(&regions);
+        assert!(single.is_empty());
+        assert_eq!(merged[0].members.len(), 3);
+    }
+
+    #[test]
+    fn test_rectangular_mask_is_union() {
+        let (_, merged) = merge_overlapping(&[region(0, 0, 10, 10), region(5, 5, 10, 10)]);
+        let group = &merged[0];
+        assert!(group.covers(2, 2, false));
+        assert!(group.covers(12, 12, false));
+        assert!(!group.covers(12, 2, false));
+    }
+
+    #[test]
+    fn test_elliptical_mask_contains_each_ellipse() {
+        let (_, merged) = merge_overlapping(&[region(0, 0, 20, 20), region(15, 0, 20, 20)]);
+        let group = &merged[0];
+        assert!(group.covers(10, 10, true));
+        assert!(group.covers(25, 10, true));
+        assert!(!group.covers(0, 0, true));
+        assert!(!group.covers(34, 19, true));
+    }
+
+    #[test]
+    fn test_elliptical_mask_fills_crease() {
+        // Two 20 px circles side by side meet at x = 20. Just above and
+        // below the contact point each circle alone leaves a sharp notch.
+        let a = region(0, 0, 20, 20);
+        let b = region(20, 0, 20, 20);
+        let (notch_x, notch_y) = (20, 7);
+        assert!(ellipse_distance(&a, notch_x, notch_y).unwrap() > 1.0);
+        assert!(ellipse_distance(&b, notch_x, notch_y).unwrap() > 1.0);
+
+        let (_, merged) = merge_overlapping(&[a, b]);
+        assert!(merged[0].covers(notch_x, notch_y, true));
+    }
+
+    #[test]
+    fn test_stacked_ellipses_do_not_swell() {
+        // A slow face over a lookahead window: ten 40 px circles, each
+        // shifted 1 px. A point 15% of a radius above the nearest stays
+        // outside, as it would for two circles.
+        let stack: Vec<Region> = (0..10).map(|i| region(i, 0, 40, 40)).collect();
+        let (_, merged) = merge_overlapping(&stack);
+        assert!(!merged[0].covers(25, -3, true));
+        assert!(merged[0].covers(25, 0, true));
+    }
+
+    #[test]
+    fn test_smooth_min_matches_min_when_far_apart() {
+        assert_eq!(smooth_min(0.2, 1.5, CREASE_FILL), 0.2);
+        assert!(smooth_min(1.0, 1.0, CREASE_FILL) < 1.0);
+    }
+}
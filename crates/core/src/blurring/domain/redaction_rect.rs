@@ -0,0 +1,140 @@
+use crate::shared::region::Region;
+
+/// A fixed rectangle to blur whatever the detector finds: a name tag, a
+/// screen, a document. Applies to every frame, or to an inclusive range of
+/// frame indices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RedactionRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub frames: Option<(usize, usize)>,
+}
+
+impl RedactionRect {
+    /// Parses `X,Y,W,H` or `X,Y,W,H@START-END` (e.g. `100,50,300,200@0-500`),
+    /// in pixels and frame indices.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid =
+            || format!("Redaction rectangle '{spec}' must be X,Y,W,H or X,Y,W,H@START-END");
+        let (rect, frames) = match spec.split_once('@') {
+            Some((rect, frames)) => (rect, Some(frames)),
+            None => (spec, None),
+        };
+        let values: Vec<i32> = rect
+            .split(',')
+            .map(|v| v.trim().parse().map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        let [x, y, width, height] = values[..] else {
+            return Err(invalid());
+        };
+        if x < 0 || y < 0 || width <= 0 || height <= 0 {
+            return Err(format!(
+                "Redaction rectangle '{spec}' needs a non-negative position and a positive size"
+            ));
+        }
+        let frames = match frames {
+            Some(range) => {
+                let (start, end) = range.split_once('-').ok_or_else(invalid)?;
+                let start: usize = start.trim().parse().map_err(|_| invalid())?;
+                let end: usize = end.trim().parse().map_err(|_| invalid())?;
+                if end < start {
+                    return Err(format!(
+                        "Redaction rectangle '{spec}' ends before it starts"
+                    ));
+                }
+                Some((start, end))
+            }
+            None => None,
+        };
+        Ok(Self {
+            x,
+            y,
+            width,
+            height,
+            frames,
+        })
+    }
+
+    pub fn active_at(&self, frame_index: usize) -> bool {
+        self.frames
+            .map_or(true, |(start, end)| (start..=end).contains(&frame_index))
+    }
+
+    /// The rectangle clipped to the frame, or `None` if it lies outside.
+    pub fn region(&self, frame_w: u32, frame_h: u32) -> Option<Region> {
+        let right = (self.x + self.width).min(frame_w as i32);
+        let bottom = (self.y + self.height).min(frame_h as i32);
+        if right <= self.x || bottom <= self.y {
+            return None;
+        }
+        Some(Region {
+            x: self.x,
+            y: self.y,
+            width: right - self.x,
+            height: bottom - self.y,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn test_parse_with_frame_range() {
+        let rect = RedactionRect::parse("100,50,300,200@0-500").unwrap();
+        assert_eq!(
+            rect,
+            RedactionRect {
+                x: 100,
+                y: 50,
+                width: 300,
+                height: 200,
+                frames: Some((0, 500)),
+            }
+        );
+        assert!(rect.active_at(500));
+        assert!(!rect.active_at(501));
+    }
+
+    #[test]
+    fn test_parse_without_range_covers_every_frame() {
+        let rect = RedactionRect::parse(" 0, 0, 10, 10 ").unwrap();
+        assert_eq!(rect.frames, None);
+        assert!(rect.active_at(usize::MAX));
+    }
+
+    #[rstest]
+    #[case("100,50,300")]
+    #[case("100,50,300,200,1")]
+    #[case("a,50,300,200")]
+    #[case("100,50,0,200")]
+    #[case("-5,50,300,200")]
+    #[case("100,50,300,200@500-0")]
+    #[case("100,50,300,200@10")]
+    fn test_parse_rejects(#[case] spec: &str) {
+        assert!(RedactionRect::parse(spec).is_err());
+    }
+
+    #[test]
+    fn test_region_is_clipped_to_frame() {
+        let rect = RedactionRect::parse("600,400,100,100").unwrap();
+        let region = rect.region(640, 480).unwrap();
+        assert_eq!(
+            (region.x, region.y, region.width, region.height),
+            (600, 400, 40, 80)
+        );
+        assert!(RedactionRect::parse("700,0,10,10")
+            .unwrap()
+            .region(640, 480)
+            .is_none());
+    }
+}
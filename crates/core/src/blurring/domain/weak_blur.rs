@@ -0,0 +1,110 @@
+use std::fmt;
+
+use crate::shared::region::Region;
+
+/// Smallest Gaussian kernel, as a fraction of a face's width, that still
+/// hides it. A kernel a fifth of the face wide (sigma about a thirtieth)
+/// runs the eyes, nose, and mouth together; a smaller one leaves their
+/// shapes readable on a large face.
+pub const MIN_KERNEL_FRACTION: f64 = 0.2;
+
+/// Blur strength (Gaussian kernel size) for faces up to `face_width`
+/// pixels wide: the odd kernel of at least [`MIN_KERNEL_FRACTION`] of it.
+pub fn recommended_blur_strength(face_width: u32) -> usize {
+    (face_width as f64 * MIN_KERNEL_FRACTION).ceil() as usize | 1
+}
+
+/// Width in pixels of the widest of `regions`, or 0 if there are none.
+/// Regions cut off by the frame edge count at their full width.
+pub fn widest_face<'a>(regions: impl IntoIterator<Item = &'a Region>) -> u32 {
+    regions
+        .into_iter()
+        .map(|region| region.full_width.unwrap_or(region.width).max(0) as u32)
+        .max()
+        .unwrap_or(0)
+}
+
+/// A blur strength too weak for the largest face a scan found, so faces
+/// may stay recognizable through the blur.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WeakBlur {
+    pub face_width: u32,
+    pub blur_strength: usize,
+    /// The strength that would be enough.
+    pub recommended: usize,
+}
+
+impl WeakBlur {
+    /// `None` when `blur_strength` is enough for faces up to `face_width`
+    /// pixels wide.
+    pub fn check(face_width: u32, blur_strength: usize) -> Option<Self> {
+        let recommended = recommended_blur_strength(face_width);
+        (blur_strength < recommended).then_some(Self {
+            face_width,
+            blur_strength,
+            recommended,
+        })
+    }
+}
+
+impl fmt::Display for WeakBlur {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "faces up to {}px wide detected; blur strength {} may be insufficient",
+            self.face_width, self.blur_strength
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn region(width: i32, full_width: Option<i32>) -> Region {
+        Region {
+            x: 0,
+            y: 0,
+            width,
+            height: width,
+            track_id: None,
+            full_width,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    #[rstest]
+    #[case(900, 181)]
+    #[case(1000, 201)]
+    #[case(40, 9)]
+    #[case(0, 1)]
+    fn test_recommended_strength_is_odd(#[case] width: u32, #[case] expected: usize) {
+        assert_eq!(recommended_blur_strength(width), expected);
+    }
+
+    #[test]
+    fn test_widest_face_counts_cut_off_faces_whole() {
+        let regions = [region(300, None), region(200, Some(500))];
+        assert_eq!(widest_face(&regions), 500);
+        assert_eq!(widest_face([]), 0);
+    }
+
+    #[test]
+    fn test_weak_blur_on_large_face() {
+        let weak = WeakBlur::check(900, 61).unwrap();
+        assert_eq!(weak.recommended, 181);
+        assert_eq!(
+            weak.to_string(),
+            "faces up to 900px wide detected; blur strength 61 may be insufficient"
+        );
+    }
+
+    #[test]
+    fn test_default_strength_covers_small_faces() {
+        assert_eq!(WeakBlur::check(600, 201), None);
+        assert_eq!(WeakBlur::check(0, 1), None);
+    }
+}
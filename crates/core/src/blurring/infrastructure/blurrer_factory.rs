@@ -1,12 +1,23 @@
 use std::sync::Arc;
 
+use image::RgbaImage;
+
 use crate::blurring::domain::frame_blurrer::FrameBlurrer;
 
+use crate::shared::constants::PIXELATE_BLOCKS;
+
 use super::cpu_elliptical_blurrer::CpuEllipticalBlurrer;
+use super::cpu_pixelate_blurrer::CpuPixelateBlurrer;
 use super::cpu_rectangular_blurrer::CpuRectangularBlurrer;
 use super::gpu_context::GpuContext;
 use super::gpu_elliptical_blurrer::GpuEllipticalBlurrer;
+use super::gpu_pixelate_blurrer::GpuPixelateBlurrer;
 use super::gpu_rectangular_blurrer::GpuRectangularBlurrer;
+use super::image_overlay_blurrer::ImageOverlayBlurrer;
+use super::overlap_compositing_blurrer::OverlapCompositingBlurrer;
+#[cfg(feature = "plugins")]
+use super::plugin_blurrer::{plugin_anonymizer, EffectPlugin};
+use super::solid_fill_blurrer::SolidFillBlurrer;
 
 /// Blur shape preference.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -15,22 +26,48 @@ pub enum BlurShape {
     Rectangular,
 }
 
+/// How a face is made unrecognizable, within the region's [`BlurShape`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum AnonymizationStyle {
+    /// Gaussian blur of the given kernel size.
+    #[default]
+    Blur,
+    /// Mosaic of flat blocks, `PIXELATE_BLOCKS` across the face.
+    Pixelate,
+    /// Flat fill of an RGB color, for total occlusion.
+    Solid([u8; 3]),
+    /// An image such as an emoji stretched over the face. Its transparent
+    /// parts show the `backing` color, so the face is still fully covered.
+    Overlay {
+        image: Arc<RgbaImage>,
+        backing: [u8; 3],
+    },
+    /// An effect from a dynamically loaded plugin, for redaction styles
+    /// of an organization's own.
+    #[cfg(feature = "plugins")]
+    Plugin(Arc<EffectPlugin>),
+}
+
 /// Creates the best available blurrer, preferring GPU when available.
 ///
 /// Probes for a wgpu adapter at startup. If one is found, returns a GPU
 /// blurrer; otherwise falls back to the CPU implementation. Logs which
-/// backend is selected.
+/// backend is selected. Overlapping faces are blurred as one, through an
+/// `OverlapCompositingBlurrer`.
 pub fn create_blurrer(shape: BlurShape, kernel_size: usize) -> Box<dyn FrameBlurrer> {
-    create_blurrer_with_context(shape, kernel_size, None)
+    create_blurrer_with_context(shape, kernel_size, 0.0, None)
 }
 
 /// Creates a blurrer using a pre-built GPU context, avoiding expensive re-initialization.
 ///
 /// Pass `Some(ctx)` to reuse an existing GPU context. Pass `None` to probe for GPU
-/// and create a new context (or fall back to CPU).
+/// and create a new context (or fall back to CPU). `feather` softens an
+/// elliptical blur's edge over that fraction of its radius (0.0 to 1.0),
+/// the same way on both backends; rectangles ignore it.
 pub fn create_blurrer_with_context(
     shape: BlurShape,
     kernel_size: usize,
+    feather: f32,
     gpu_context: Option<Arc<GpuContext>>,
 ) -> Box<dyn FrameBlurrer> {
     let ctx = gpu_context.or_else(|| GpuContext::new().map(Arc::new));
@@ -40,20 +77,78 @@ pub fn create_blurrer_with_context(
             shape,
             kernel_size
         );
-        match shape {
-            BlurShape::Elliptical => Box::new(GpuEllipticalBlurrer::new(ctx, kernel_size as u32)),
-            BlurShape::Rectangular => Box::new(GpuRectangularBlurrer::new(ctx, kernel_size as u32)),
-        }
+        composited(shape, |shape| match shape {
+            BlurShape::Elliptical => Box::new(
+                GpuEllipticalBlurrer::new(ctx.clone(), kernel_size as u32).with_feather(feather),
+            ),
+            BlurShape::Rectangular => {
+                Box::new(GpuRectangularBlurrer::new(ctx.clone(), kernel_size as u32))
+            }
+        })
     } else {
         log::info!(
             "No GPU available, using CPU backend for {:?} blur (kernel_size={})",
             shape,
             kernel_size
         );
-        match shape {
-            BlurShape::Elliptical => Box::new(CpuEllipticalBlurrer::new(kernel_size)),
+        composited(shape, |shape| match shape {
+            BlurShape::Elliptical => {
+                Box::new(CpuEllipticalBlurrer::new(kernel_size).with_feather(feather as f64))
+            }
             BlurShape::Rectangular => Box::new(CpuRectangularBlurrer::new(kernel_size)),
+        })
+    }
+}
+
+/// Creates the anonymizer for `style`, on the GPU when one is available.
+///
+/// `Blur` is the same as [`create_blurrer_with_context`]. `Pixelate` uses
+/// the GPU like blurring does; `Solid` and `Overlay` always run on the CPU,
+/// where a fill is already cheap, and a `Plugin` wherever its effect runs. `kernel_size` and `feather` only apply to
+/// `Blur`.
+/// Blurs and mosaics merge overlapping faces; fills and overlays simply
+/// cover them in turn, which leaves no seam.
+pub fn create_anonymizer_with_context(
+    style: AnonymizationStyle,
+    shape: BlurShape,
+    kernel_size: usize,
+    feather: f32,
+    gpu_context: Option<Arc<GpuContext>>,
+) -> Box<dyn FrameBlurrer> {
+    let elliptical = shape == BlurShape::Elliptical;
+    match style {
+        AnonymizationStyle::Blur => {
+            create_blurrer_with_context(shape, kernel_size, feather, gpu_context)
+        }
+        AnonymizationStyle::Pixelate => {
+            match gpu_context.or_else(|| GpuContext::new().map(Arc::new)) {
+                Some(ctx) => {
+                    log::info!("Using GPU backend for {:?} pixelation", shape);
+                    composited(shape, |shape| {
+                        Box::new(GpuPixelateBlurrer::new(
+                            ctx.clone(),
+                            PIXELATE_BLOCKS,
+                            shape == BlurShape::Elliptical,
+                        ))
+                    })
+                }
+                None => {
+                    log::info!(
+                        "No GPU available, using CPU backend for {:?} pixelation",
+                        shape
+                    );
+                    composited(shape, pixelate_on_cpu)
+                }
+            }
+        }
+        AnonymizationStyle::Solid(color) => {
+            Box::new(SolidFillBlurrer::new(elliptical).with_color(color))
         }
+        AnonymizationStyle::Overlay { image, backing } => {
+            Box::new(ImageOverlayBlurrer::new(image, elliptical).with_backing(backing))
+        }
+        #[cfg(feature = "plugins")]
+        AnonymizationStyle::Plugin(plugin) => plugin_anonymizer(&plugin, elliptical),
     }
 }
 
@@ -65,6 +160,78 @@ pub fn create_gpu_context() -> Option<Arc<GpuContext>> {
     GpuContext::new().map(Arc::new)
 }
 
+/// Like [`create_gpu_context`], but the context blurs in fixed point so its
+/// output is bit-identical across adapters, drivers, and runs.
+pub fn create_deterministic_gpu_context() -> Option<Arc<GpuContext>> {
+    GpuContext::new().map(|ctx| Arc::new(ctx.with_deterministic(true)))
+}
+
+/// Creates a context that splits each frame's regions across every GPU of
+/// the machine (see [`GpuContext::new_multi_adapter`]), in fixed point when
+/// `deterministic`.
+pub fn create_multi_gpu_context(deterministic: bool) -> Option<Arc<GpuContext>> {
+    GpuContext::new_multi_adapter().map(|ctx| Arc::new(ctx.with_deterministic(deterministic)))
+}
+
+/// Creates the full-resolution CPU blurrer that other backends are
+/// compared against by `ParityCheckingBlurrer`.
+pub fn create_reference_blurrer(
+    shape: BlurShape,
+    kernel_size: usize,
+    feather: f32,
+) -> Box<dyn FrameBlurrer> {
+    composited(shape, |shape| match shape {
+        BlurShape::Elliptical => {
+            Box::new(CpuEllipticalBlurrer::exact(kernel_size).with_feather(feather as f64))
+        }
+        BlurShape::Rectangular => Box::new(CpuRectangularBlurrer::exact(kernel_size)),
+    })
+}
+
+/// Like [`create_reference_blurrer`], for any style. Pixelation, fills, and
+/// overlays have no shortcuts, so their CPU versions are the reference as
+/// they are. A plugin is its own reference.
+pub fn create_reference_anonymizer(
+    style: AnonymizationStyle,
+    shape: BlurShape,
+    kernel_size: usize,
+    feather: f32,
+) -> Box<dyn FrameBlurrer> {
+    let elliptical = shape == BlurShape::Elliptical;
+    match style {
+        AnonymizationStyle::Blur => create_reference_blurrer(shape, kernel_size, feather),
+        AnonymizationStyle::Pixelate => composited(shape, pixelate_on_cpu),
+        AnonymizationStyle::Solid(color) => {
+            Box::new(SolidFillBlurrer::new(elliptical).with_color(color))
+        }
+        AnonymizationStyle::Overlay { image, backing } => {
+            Box::new(ImageOverlayBlurrer::new(image, elliptical).with_backing(backing))
+        }
+        #[cfg(feature = "plugins")]
+        AnonymizationStyle::Plugin(plugin) => plugin_anonymizer(&plugin, elliptical),
+    }
+}
+
+/// Wraps the `make(shape)` blurrer so overlapping regions are blurred as
+/// one, using `make(Rectangular)` for each group's bounding rectangle.
+fn composited(
+    shape: BlurShape,
+    make: impl Fn(BlurShape) -> Box<dyn FrameBlurrer>,
+) -> Box<dyn FrameBlurrer> {
+    Box::new(OverlapCompositingBlurrer::new(
+        make(shape),
+        make(BlurShape::Rectangular),
+        shape == BlurShape::Elliptical,
+    ))
+}
+
+fn pixelate_on_cpu(shape: BlurShape) -> Box<dyn FrameBlurrer> {
+    Box::new(CpuPixelateBlurrer::new(
+        PIXELATE_BLOCKS,
+        shape == BlurShape::Elliptical,
+    ))
+}
+
 /// Returns true if a GPU adapter is available for compute shaders.
 pub fn gpu_available() -> bool {
     GpuContext::is_available()
@@ -127,6 +294,98 @@ mod tests {
         assert!(frame.data()[neighbor] > 0);
     }
 
+    #[test]
+    fn test_deterministic_context_is_deterministic() {
+        if let Some(ctx) = create_deterministic_gpu_context() {
+            assert!(ctx.is_deterministic());
+        }
+    }
+
+    #[test]
+    fn test_reference_blurrer_blurs() {
+        let blurrer = create_reference_blurrer(BlurShape::Elliptical, 201, 0.0);
+        let mut frame = make_frame(50, 50, 0);
+        frame.data_mut()[(25 * 50 + 25) * 3] = 255;
+        blurrer.blur(&mut frame, &[region(10, 10, 30, 30)]).unwrap();
+        assert!(frame.data()[(25 * 50 + 25) * 3] < 255);
+    }
+
+    #[test]
+    fn test_reference_blurrer_blurs_overlap_once() {
+        let mut frame = make_frame(64, 64, 0);
+        for (i, value) in frame.data_mut().iter_mut().enumerate() {
+            *value = (i * 37 % 251) as u8;
+        }
+        let mut bounds = frame.clone();
+        create_reference_blurrer(BlurShape::Rectangular, 15, 0.0)
+            .blur(&mut frame, &[region(4, 4, 30, 30), region(20, 10, 30, 30)])
+            .unwrap();
+        CpuRectangularBlurrer::exact(15)
+            .blur(&mut bounds, &[region(4, 4, 46, 36)])
+            .unwrap();
+        let shared = (20 * 64 + 25) * 3;
+        assert_eq!(frame.data()[shared], bounds.data()[shared]);
+    }
+
+    #[test]
+    fn test_pixelate_anonymizer_flattens_region() {
+        let blurrer = create_anonymizer_with_context(
+            AnonymizationStyle::Pixelate,
+            BlurShape::Rectangular,
+            5,
+            0.0,
+            None,
+        );
+        let mut frame = make_frame(64, 64, 0);
+        // Two neighboring pixels in the same block end up equal.
+        frame.data_mut()[(8 * 64 + 8) * 3] = 200;
+        blurrer.blur(&mut frame, &[region(8, 8, 48, 48)]).unwrap();
+        let data = frame.data();
+        assert_eq!(data[(8 * 64 + 8) * 3], data[(8 * 64 + 9) * 3]);
+        assert!(data[(8 * 64 + 8) * 3] > 0);
+    }
+
+    #[test]
+    fn test_solid_anonymizer_fills_color() {
+        let blurrer = create_anonymizer_with_context(
+            AnonymizationStyle::Solid([10, 20, 30]),
+            BlurShape::Rectangular,
+            5,
+            0.0,
+            None,
+        );
+        let mut frame = make_frame(50, 50, 200);
+        blurrer.blur(&mut frame, &[region(10, 10, 20, 20)]).unwrap();
+        let inside = (15 * 50 + 15) * 3;
+        assert_eq!(&frame.data()[inside..inside + 3], &[10, 20, 30]);
+    }
+
+    #[test]
+    fn test_overlay_anonymizer_covers_region() {
+        let style = AnonymizationStyle::Overlay {
+            image: Arc::new(RgbaImage::from_pixel(2, 2, image::Rgba([0, 255, 0, 255]))),
+            backing: [0, 0, 0],
+        };
+        let blurrer = create_anonymizer_with_context(style, BlurShape::Rectangular, 5, 0.0, None);
+        let mut frame = make_frame(50, 50, 200);
+        blurrer.blur(&mut frame, &[region(10, 10, 20, 20)]).unwrap();
+        let inside = (15 * 50 + 15) * 3;
+        assert_eq!(&frame.data()[inside..inside + 3], &[0, 255, 0]);
+    }
+
+    #[test]
+    fn test_reference_anonymizer_matches_style() {
+        let blurrer = create_reference_anonymizer(
+            AnonymizationStyle::Solid([0; 3]),
+            BlurShape::Rectangular,
+            201,
+            0.0,
+        );
+        let mut frame = make_frame(50, 50, 200);
+        blurrer.blur(&mut frame, &[region(10, 10, 20, 20)]).unwrap();
+        assert_eq!(frame.data()[(15 * 50 + 15) * 3], 0);
+    }
+
     #[test]
     fn test_gpu_available_returns_bool() {
         let _ = gpu_available();
@@ -12,9 +12,11 @@ const DEFAULT_KERNEL_SIZE: usize = 201;
 ///
 /// Blurs the rectangular ROI and then composites only the elliptical area
 /// back into the frame. Uses the Region's edge-aware ellipse geometry so
-/// the blur extends smoothly off frame edges.
+/// the blur extends smoothly off frame edges. `with_feather` fades the
+/// ellipse's edge into the original pixels instead of cutting it hard.
 pub struct CpuEllipticalBlurrer {
     kernel: Vec<f32>,
+    feather: f64,
     scale: usize,
     small_kernel: Vec<f32>,
     roi_buf: RefCell<Vec<u8>>,
@@ -27,12 +29,34 @@ impl CpuEllipticalBlurrer {
         let small_k = (kernel_size / scale) | 1;
         Self {
             kernel: gaussian::gaussian_kernel_1d(kernel_size),
+            feather: 0.0,
             scale,
             small_kernel: gaussian::gaussian_kernel_1d(small_k),
             roi_buf: RefCell::new(Vec::new()),
             blur_temp: RefCell::new(Vec::new()),
         }
     }
+
+    /// Full-resolution blur without the downscale shortcut. Slower, but it
+    /// is the reference other backends are checked against.
+    pub fn exact(kernel_size: usize) -> Self {
+        let kernel = gaussian::gaussian_kernel_1d(kernel_size);
+        Self {
+            small_kernel: kernel.clone(),
+            kernel,
+            feather: 0.0,
+            scale: 1,
+            roi_buf: RefCell::new(Vec::new()),
+            blur_temp: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Fades the blur out over the outer `feather` (0.0 to 1.0) of the
+    /// ellipse's radius; 0.0, the default, is a hard edge.
+    pub fn with_feather(mut self, feather: f64) -> Self {
+        self.feather = feather.clamp(0.0, 1.0);
+        self
+    }
 }
 
 impl Default for CpuEllipticalBlurrer {
@@ -83,47 +107,13 @@ impl FrameBlurrer for CpuEllipticalBlurrer {
                 &mut temp,
             );
 
-            composite_ellipse(data, &roi, fw, channels, rect, r);
+            gaussian::composite_ellipse_feathered(data, &roi, fw, channels, rect, r, self.feather);
         }
 
         Ok(())
     }
 }
 
-/// Write blurred pixels back to the frame only within the ellipse mask.
-fn composite_ellipse(
-    data: &mut [u8],
-    roi: &[u8],
-    frame_width: usize,
-    channels: usize,
-    rect: RoiRect,
-    region: &Region,
-) {
-    let (ecx, ecy) = region.ellipse_center_in_roi();
-    let (semi_a, semi_b) = region.ellipse_axes();
-
-    if semi_a <= 0.0 || semi_b <= 0.0 {
-        return;
-    }
-
-    let inv_a_sq = 1.0 / (semi_a * semi_a);
-    let inv_b_sq = 1.0 / (semi_b * semi_b);
-
-    for row in 0..rect.h {
-        for col in 0..rect.w {
-            let dx = col as f64 - ecx;
-            let dy = row as f64 - ecy;
-
-            if dx * dx * inv_a_sq + dy * dy * inv_b_sq <= 1.0 {
-                let frame_offset = ((rect.y + row) * frame_width + (rect.x + col)) * channels;
-                let roi_offset = (row * rect.w + col) * channels;
-                data[frame_offset..frame_offset + channels]
-                    .copy_from_slice(&roi[roi_offset..roi_offset + channels]);
-            }
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -290,4 +280,36 @@ mod tests {
         let blurrer = CpuEllipticalBlurrer::new(5);
         blurrer.blur(&mut frame, &[region(40, 40, 30, 30)]).unwrap();
     }
+
+    #[test]
+    fn test_exact_skips_downscale() {
+        let blurrer = CpuEllipticalBlurrer::exact(201);
+        assert_eq!(blurrer.scale, 1);
+        assert_eq!(blurrer.kernel.len(), 201);
+    }
+
+    #[test]
+    fn test_feather_softens_only_the_edge() {
+        let mut hard = make_frame(40, 40, 0);
+        for y in 0..40 {
+            for x in 0..20 {
+                hard.data_mut()[(y * 40 + x) * 3] = 255;
+            }
+        }
+        let mut soft = hard.clone();
+        CpuEllipticalBlurrer::exact(9)
+            .blur(&mut hard, &[region(0, 0, 40, 40)])
+            .unwrap();
+        CpuEllipticalBlurrer::exact(9)
+            .with_feather(0.5)
+            .blur(&mut soft, &[region(0, 0, 40, 40)])
+            .unwrap();
+
+        let at = |frame: &Frame, x: usize, y: usize| frame.data()[(y * 40 + x) * 3];
+        // Blurred as before near the centre; closer to the original near
+        // the outline.
+        assert_eq!(at(&soft, 19, 20), at(&hard, 19, 20));
+        assert!(at(&soft, 2, 20) > at(&hard, 2, 20));
+        assert_eq!(at(&soft, 0, 0), 255);
+    }
 }
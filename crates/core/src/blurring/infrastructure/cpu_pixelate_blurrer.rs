@@ -0,0 +1,180 @@
+use std::cell::RefCell;
+
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
+use crate::shared::constants::PIXELATE_BLOCKS;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+use super::gaussian::{self, RoiRect};
+use super::pixelate;
+
+/// CPU mosaic anonymizer.
+///
+/// Replaces each region with blocks of its mean color, sized so the face is
+/// about `blocks_across` blocks wide. Elliptical mode composites only the
+/// inscribed ellipse, like `CpuEllipticalBlurrer`. One pass over the pixels,
+/// so much cheaper than a large Gaussian.
+pub struct CpuPixelateBlurrer {
+    blocks_across: usize,
+    elliptical: bool,
+    roi_buf: RefCell<Vec<u8>>,
+}
+
+impl CpuPixelateBlurrer {
+    pub fn new(blocks_across: usize, elliptical: bool) -> Self {
+        Self {
+            blocks_across,
+            elliptical,
+            roi_buf: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl Default for CpuPixelateBlurrer {
+    fn default() -> Self {
+        Self::new(PIXELATE_BLOCKS, true)
+    }
+}
+
+impl FrameBlurrer for CpuPixelateBlurrer {
+    fn blur(
+        &self,
+        frame: &mut Frame,
+        regions: &[Region],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let fw = frame.width() as usize;
+        let fh = frame.height() as usize;
+        let channels = frame.channels() as usize;
+        let data = frame.data_mut();
+
+        for r in regions {
+            let rx = r.x.max(0) as usize;
+            let ry = r.y.max(0) as usize;
+            let rw = (r.width.max(0) as usize).min(fw.saturating_sub(rx));
+            let rh = (r.height.max(0) as usize).min(fh.saturating_sub(ry));
+
+            if rw == 0 || rh == 0 {
+                continue;
+            }
+
+            let rect = RoiRect {
+                x: rx,
+                y: ry,
+                w: rw,
+                h: rh,
+            };
+            let mut roi = self.roi_buf.borrow_mut();
+
+            gaussian::extract_roi(data, fw, channels, rect, &mut roi);
+            pixelate::pixelate_roi_in_place(
+                &mut roi,
+                rw,
+                rh,
+                channels,
+                pixelate::block_size(r, self.blocks_across),
+            );
+            if self.elliptical {
+                gaussian::composite_ellipse(data, &roi, fw, channels, rect, r);
+            } else {
+                gaussian::write_roi_back(data, &roi, fw, channels, rect);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_frame(width: u32, height: u32, value: u8) -> Frame {
+        let data = vec![value; (width * height * 3) as usize];
+        Frame::new(data, width, height, 3, 0)
+    }
+
+    fn region(x: i32, y: i32, w: i32, h: i32) -> Region {
+        Region {
+            x,
+            y,
+            width: w,
+            height: h,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    /// A frame whose red channel is a horizontal gradient.
+    fn gradient_frame(width: u32, height: u32) -> Frame {
+        let mut frame = make_frame(width, height, 0);
+        let data = frame.data_mut();
+        for y in 0..height as usize {
+            for x in 0..width as usize {
+                data[(y * width as usize + x) * 3] = (x * 2) as u8;
+            }
+        }
+        frame
+    }
+
+    #[test]
+    fn test_no_regions_frame_unchanged() {
+        let mut frame = gradient_frame(50, 50);
+        let original = frame.data().to_vec();
+        CpuPixelateBlurrer::new(10, false)
+            .blur(&mut frame, &[])
+            .unwrap();
+        assert_eq!(frame.data(), &original[..]);
+    }
+
+    #[test]
+    fn test_region_becomes_flat_blocks() {
+        let mut frame = gradient_frame(50, 50);
+        // 40 px region, 4 blocks across: 10 px blocks.
+        CpuPixelateBlurrer::new(4, false)
+            .blur(&mut frame, &[region(0, 0, 40, 40)])
+            .unwrap();
+
+        let red = |x: usize, y: usize| frame.data()[(y * 50 + x) * 3];
+        // Block 0 covers x 0..10: mean of 0, 2, ..., 18 is 9.
+        assert_eq!(red(0, 0), 9);
+        assert_eq!(red(9, 9), 9);
+        assert_eq!(red(10, 0), 29);
+        // Outside the region the gradient is untouched.
+        assert_eq!(red(45, 0), 90);
+    }
+
+    #[test]
+    fn test_elliptical_leaves_corners() {
+        let mut frame = gradient_frame(50, 50);
+        let original = frame.data().to_vec();
+        CpuPixelateBlurrer::new(4, true)
+            .blur(&mut frame, &[region(0, 0, 40, 40)])
+            .unwrap();
+
+        let corner = (39 * 50 + 39) * 3;
+        assert_eq!(frame.data()[corner], original[corner]);
+        let center = (20 * 50 + 21) * 3;
+        assert_ne!(frame.data()[center], original[center]);
+    }
+
+    #[test]
+    fn test_zero_size_region_skipped() {
+        let mut frame = gradient_frame(50, 50);
+        let original = frame.data().to_vec();
+        CpuPixelateBlurrer::default()
+            .blur(&mut frame, &[region(10, 10, 0, 20)])
+            .unwrap();
+        assert_eq!(frame.data(), &original[..]);
+    }
+
+    #[test]
+    fn test_region_extending_beyond_frame_does_not_panic() {
+        let mut frame = gradient_frame(50, 50);
+        CpuPixelateBlurrer::default()
+            .blur(&mut frame, &[region(40, 40, 30, 30)])
+            .unwrap();
+    }
+}
@@ -32,6 +32,19 @@ impl CpuRectangularBlurrer {
             blur_temp: RefCell::new(Vec::new()),
         }
     }
+
+    /// Full-resolution blur without the downscale shortcut. Slower, but it
+    /// is the reference other backends are checked against.
+    pub fn exact(kernel_size: usize) -> Self {
+        let kernel = gaussian::gaussian_kernel_1d(kernel_size);
+        Self {
+            small_kernel: kernel.clone(),
+            kernel,
+            scale: 1,
+            roi_buf: RefCell::new(Vec::new()),
+            blur_temp: RefCell::new(Vec::new()),
+        }
+    }
 }
 
 impl Default for CpuRectangularBlurrer {
@@ -229,4 +242,11 @@ mod tests {
         let blurrer = CpuRectangularBlurrer::new(5);
         blurrer.blur(&mut frame, &[region(40, 40, 30, 30)]).unwrap();
     }
+
+    #[test]
+    fn test_exact_skips_downscale() {
+        let blurrer = CpuRectangularBlurrer::exact(201);
+        assert_eq!(blurrer.scale, 1);
+        assert_eq!(blurrer.kernel.len(), 201);
+    }
 }
@@ -0,0 +1,257 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// An ellipse inscribed in a rectangle scaled by √2 contains the original
+/// rectangle, corners included.
+const ELLIPSE_COVER_SCALE: f64 = std::f64::consts::SQRT_2;
+
+/// What a crowd frame is blurred as.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CrowdCoverage {
+    /// One region around every face of the frame.
+    #[default]
+    Area,
+    /// The whole frame.
+    FullFrame,
+}
+
+impl CrowdCoverage {
+    /// Parses `area` or `frame`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "area" => Some(Self::Area),
+            "frame" | "full-frame" => Some(Self::FullFrame),
+            _ => None,
+        }
+    }
+}
+
+/// Decorator that blurs crowd frames as a single region.
+///
+/// Blur cost grows with the number of regions, and a stadium or protest
+/// shot can have hundreds; GPU batches grow with them. Above `limit`
+/// regions, the frame's regions are replaced by one covering them all (or
+/// the whole frame), which costs about as much as one large face. The
+/// region is sized so an elliptical blurrer still covers the corners.
+/// Switching into and out of crowd mode is logged with the frame index,
+/// and `crowd_frames()` counts the frames it applied to.
+pub struct CrowdModeBlurrer {
+    inner: Box<dyn FrameBlurrer>,
+    limit: usize,
+    coverage: CrowdCoverage,
+    active: AtomicBool,
+    crowd_frames: Arc<AtomicUsize>,
+}
+
+impl CrowdModeBlurrer {
+    pub fn new(inner: Box<dyn FrameBlurrer>, limit: usize) -> Self {
+        Self {
+            inner,
+            limit: limit.max(1),
+            coverage: CrowdCoverage::Area,
+            active: AtomicBool::new(false),
+            crowd_frames: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn with_coverage(mut self, coverage: CrowdCoverage) -> Self {
+        self.coverage = coverage;
+        self
+    }
+
+    /// Shared count of frames blurred in crowd mode, readable after the
+    /// blurrer has been moved into a pipeline.
+    pub fn crowd_frames(&self) -> Arc<AtomicUsize> {
+        self.crowd_frames.clone()
+    }
+}
+
+impl FrameBlurrer for CrowdModeBlurrer {
+    fn blur(
+        &self,
+        frame: &mut Frame,
+        regions: &[Region],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let crowded = regions.len() > self.limit;
+        if crowded != self.active.swap(crowded, Ordering::Relaxed) {
+            if crowded {
+                log::info!(
+                    "Crowd mode on at frame {}: {} regions (limit {}), blurring {} as one region",
+                    frame.index(),
+                    regions.len(),
+                    self.limit,
+                    match self.coverage {
+                        CrowdCoverage::Area => "the crowd area",
+                        CrowdCoverage::FullFrame => "the whole frame",
+                    }
+                );
+            } else {
+                log::info!("Crowd mode off at frame {}", frame.index());
+            }
+        }
+        if !crowded {
+            return self.inner.blur(frame, regions);
+        }
+        self.crowd_frames.fetch_add(1, Ordering::Relaxed);
+        let region = match self.coverage {
+            CrowdCoverage::Area => crowd_region(regions, frame.width(), frame.height()),
+            CrowdCoverage::FullFrame => full_frame_region(frame.width(), frame.height()),
+        };
+        self.inner.blur(frame, &[region])
+    }
+}
+
+/// One region whose rectangle and inscribed ellipse both cover every
+/// region in `regions`.
+pub fn crowd_region(regions: &[Region], frame_w: u32, frame_h: u32) -> Region {
+    let left = regions.iter().map(|r| r.x).min().unwrap_or(0) as f64;
+    let top = regions.iter().map(|r| r.y).min().unwrap_or(0) as f64;
+    let right = regions.iter().map(|r| r.x + r.width).max().unwrap_or(0) as f64;
+    let bottom = regions.iter().map(|r| r.y + r.height).max().unwrap_or(0) as f64;
+
+    let cx = (left + right) / 2.0;
+    let cy = (top + bottom) / 2.0;
+    let half_w = (right - left) / 2.0 * ELLIPSE_COVER_SCALE;
+    let half_h = (bottom - top) / 2.0 * ELLIPSE_COVER_SCALE;
+    covering(cx, cy, half_w, half_h, frame_w, frame_h)
+}
+
+/// A region over the whole frame, ellipse included.
+fn full_frame_region(frame_w: u32, frame_h: u32) -> Region {
+    let half_w = frame_w as f64 / 2.0;
+    let half_h = frame_h as f64 / 2.0;
+    covering(
+        half_w,
+        half_h,
+        half_w * ELLIPSE_COVER_SCALE,
+        half_h * ELLIPSE_COVER_SCALE,
+        frame_w,
+        frame_h,
+    )
+}
+
+/// Rounds outward, with a pixel to spare, so integer geometry still
+/// covers what the float geometry does.
+fn covering(cx: f64, cy: f64, half_w: f64, half_h: f64, frame_w: u32, frame_h: u32) -> Region {
+    let ux = (cx - half_w).floor() as i32 - 1;
+    let uy = (cy - half_h).floor() as i32 - 1;
+    let right = (cx + half_w).ceil() as i32 + 1;
+    let bottom = (cy + half_h).ceil() as i32 + 1;
+    let x = ux.max(0);
+    let y = uy.max(0);
+    Region {
+        x,
+        y,
+        width: (right.min(frame_w as i32) - x).max(0),
+        height: (bottom.min(frame_h as i32) - y).max(0),
+        track_id: None,
+        full_width: Some(right - ux),
+        full_height: Some(bottom - uy),
+        unclamped_x: Some(ux),
+        unclamped_y: Some(uy),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn region(x: i32, y: i32, w: i32, h: i32) -> Region {
+        Region {
+            x,
+            y,
+            width: w,
+            height: h,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    /// Records the regions it was asked to blur.
+    struct Recorder(Arc<Mutex<Vec<Vec<Region>>>>);
+
+    impl FrameBlurrer for Recorder {
+        fn blur(
+            &self,
+            _frame: &mut Frame,
+            regions: &[Region],
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            self.0.lock().unwrap().push(regions.to_vec());
+            Ok(())
+        }
+    }
+
+    fn crowd(count: i32) -> Vec<Region> {
+        (0..count)
+            .map(|i| region(100 + i * 20, 200, 16, 16))
+            .collect()
+    }
+
+    #[test]
+    fn test_passes_regions_through_up_to_the_limit() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let blurrer = CrowdModeBlurrer::new(Box::new(Recorder(seen.clone())), 5);
+        let mut frame = Frame::new(vec![0; 1000 * 500 * 3], 1000, 500, 3, 0);
+
+        blurrer.blur(&mut frame, &crowd(5)).unwrap();
+
+        assert_eq!(seen.lock().unwrap()[0].len(), 5);
+        assert_eq!(blurrer.crowd_frames().load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_replaces_a_crowd_with_one_covering_region() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let blurrer = CrowdModeBlurrer::new(Box::new(Recorder(seen.clone())), 5);
+        let mut frame = Frame::new(vec![0; 1000 * 500 * 3], 1000, 500, 3, 0);
+        let regions = crowd(6);
+
+        blurrer.blur(&mut frame, &regions).unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen[0].len(), 1);
+        let area = &seen[0][0];
+        let (cx, cy) = area.ellipse_center_in_roi();
+        let (ax, ay) = area.ellipse_axes();
+        for r in &regions {
+            for (px, py) in [(r.x, r.y), (r.x + r.width, r.y + r.height)] {
+                let dx = (px - area.x) as f64 - cx;
+                let dy = (py - area.y) as f64 - cy;
+                assert!((dx / ax).powi(2) + (dy / ay).powi(2) <= 1.0);
+            }
+        }
+        assert_eq!(blurrer.crowd_frames().load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_full_frame_coverage() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let blurrer = CrowdModeBlurrer::new(Box::new(Recorder(seen.clone())), 2)
+            .with_coverage(CrowdCoverage::FullFrame);
+        let mut frame = Frame::new(vec![0; 320 * 240 * 3], 320, 240, 3, 0);
+
+        blurrer.blur(&mut frame, &crowd(3)).unwrap();
+
+        let seen = seen.lock().unwrap();
+        let area = &seen[0][0];
+        assert_eq!((area.x, area.y, area.width, area.height), (0, 0, 320, 240));
+    }
+
+    #[test]
+    fn test_parse_coverage() {
+        assert_eq!(CrowdCoverage::parse("area"), Some(CrowdCoverage::Area));
+        assert_eq!(
+            CrowdCoverage::parse("Frame"),
+            Some(CrowdCoverage::FullFrame)
+        );
+        assert_eq!(CrowdCoverage::parse("stadium"), None);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
+use crate::detection::infrastructure::counting_face_detector::FaceCounts;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// 3x5 bitmap glyphs for digits 0-9, one row per entry, MSB = leftmost pixel.
+const DIGIT_GLYPHS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+const BADGE_MARGIN: usize = 8;
+const BADGE_BACKGROUND: [u8; 3] = [0, 0, 0];
+const BADGE_FOREGROUND: [u8; 3] = [255, 255, 0];
+
+/// Debug decorator that burns the number of faces detected on the frame
+/// into it.
+///
+/// The count comes from a `CountingFaceDetector` sharing `counts`, not
+/// from the regions blurred, which lookahead merging fills in from
+/// neighbouring frames. Delegates blurring to the inner blurrer, then draws
+/// a small badge in the top-left corner. The badge scales with frame height
+/// so it stays legible on 4K sources without dominating small ones. Frames
+/// the detector never saw get no badge.
+pub struct FaceCountOverlay {
+    inner: Box<dyn FrameBlurrer>,
+    counts: FaceCounts,
+}
+
+impl FaceCountOverlay {
+    pub fn new(inner: Box<dyn FrameBlurrer>, counts: FaceCounts) -> Self {
+        Self { inner, counts }
+    }
+}
+
+impl FrameBlurrer for FaceCountOverlay {
+    fn blur(
+        &self,
+        frame: &mut Frame,
+        regions: &[Region],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.blur(frame, regions)?;
+        let count = self
+            .counts
+            .lock()
+            .map_err(|_| "Face counts poisoned")?
+            .remove(&frame.index());
+        if let Some(count) = count {
+            draw_badge(frame, count);
+        }
+        Ok(())
+    }
+}
+
+fn draw_badge(frame: &mut Frame, count: usize) {
+    let fw = frame.width() as usize;
+    let fh = frame.height() as usize;
+    let channels = frame.channels() as usize;
+    if channels < 3 {
+        return;
+    }
+
+    let scale = (fh / 120).max(1);
+    let digits: Vec<usize> = count
+        .to_string()
+        .bytes()
+        .map(|b| (b - b'0') as usize)
+        .collect();
+    let pad = scale;
+    let advance = (GLYPH_WIDTH + 1) * scale;
+    let badge_w = digits.len() * advance - scale + 2 * pad;
+    let badge_h = GLYPH_HEIGHT * scale + 2 * pad;
+    let data = frame.data_mut();
+
+    fill_rect(
+        data,
+        fw,
+        fh,
+        channels,
+        (BADGE_MARGIN, BADGE_MARGIN, badge_w, badge_h),
+        BADGE_BACKGROUND,
+    );
+
+    for (i, &digit) in digits.iter().enumerate() {
+        let origin_x = BADGE_MARGIN + pad + i * advance;
+        let origin_y = BADGE_MARGIN + pad;
+        for (row, bits) in DIGIT_GLYPHS[digit].iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                    fill_rect(
+                        data,
+                        fw,
+                        fh,
+                        channels,
+                        (origin_x + col * scale, origin_y + row * scale, scale, scale),
+                        BADGE_FOREGROUND,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Fills an `(x, y, w, h)` rectangle, clipped to the frame.
+fn fill_rect(
+    data: &mut [u8],
+    frame_width: usize,
+    frame_height: usize,
+    channels: usize,
+    rect: (usize, usize, usize, usize),
+    color: [u8; 3],
+) {
+    let (x, y, w, h) = rect;
+    let x_end = (x + w).min(frame_width);
+    let y_end = (y + h).min(frame_height);
+    for py in y..y_end {
+        for px in x..x_end {
+            let idx = (py * frame_width + px) * channels;
+            data[idx..idx + 3].copy_from_slice(&color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct PassthroughBlurrer;
+
+    impl FrameBlurrer for PassthroughBlurrer {
+        fn blur(&self, _: &mut Frame, _: &[Region]) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+    }
+
+    fn make_frame(width: u32, height: u32) -> Frame {
+        Frame::new(
+            vec![128u8; (width * height * 3) as usize],
+            width,
+            height,
+            3,
+            0,
+        )
+    }
+
+    fn region() -> Region {
+        Region {
+            x: 50,
+            y: 50,
+            width: 20,
+            height: 20,
+            track_id: Some(1),
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    fn pixel(frame: &Frame, x: usize, y: usize) -> [u8; 3] {
+        let idx = (y * frame.width() as usize + x) * 3;
+        let d = frame.data();
+        [d[idx], d[idx + 1], d[idx + 2]]
+    }
+
+    /// An overlay whose detector found `count` faces on frame 0.
+    fn overlay_with_count(count: usize) -> FaceCountOverlay {
+        let counts = FaceCounts::default();
+        counts.lock().unwrap().insert(0, count);
+        FaceCountOverlay::new(Box::new(PassthroughBlurrer), counts)
+    }
+
+    #[test]
+    fn test_badge_drawn_in_top_left() {
+        let mut frame = make_frame(100, 100);
+        overlay_with_count(1).blur(&mut frame, &[region()]).unwrap();
+        assert_eq!(pixel(&frame, BADGE_MARGIN, BADGE_MARGIN), BADGE_BACKGROUND);
+    }
+
+    #[test]
+    fn test_digit_pixels_use_foreground() {
+        let mut frame = make_frame(100, 100);
+        overlay_with_count(0).blur(&mut frame, &[]).unwrap();
+        // Top-left pixel of the "0" glyph is set
+        assert_eq!(
+            pixel(&frame, BADGE_MARGIN + 1, BADGE_MARGIN + 1),
+            BADGE_FOREGROUND
+        );
+    }
+
+    #[test]
+    fn test_pixels_away_from_badge_unchanged() {
+        let mut frame = make_frame(100, 100);
+        overlay_with_count(2)
+            .blur(&mut frame, &[region(), region()])
+            .unwrap();
+        assert_eq!(pixel(&frame, 90, 90), [128, 128, 128]);
+    }
+
+    #[test]
+    fn test_counts_detections_not_merged_regions() {
+        // Lookahead merging added a region the detector didn't find here.
+        let mut one = make_frame(100, 100);
+        let mut twelve = make_frame(100, 100);
+        overlay_with_count(1)
+            .blur(&mut one, &[region(), region()])
+            .unwrap();
+        overlay_with_count(12)
+            .blur(&mut twelve, &[region()])
+            .unwrap();
+        let probe_x = BADGE_MARGIN + 7;
+        assert_eq!(pixel(&one, probe_x, BADGE_MARGIN), [128, 128, 128]);
+        assert_eq!(pixel(&twelve, probe_x, BADGE_MARGIN), BADGE_BACKGROUND);
+    }
+
+    #[test]
+    fn test_frame_without_count_has_no_badge() {
+        let mut frame = make_frame(100, 100);
+        let overlay = FaceCountOverlay::new(Box::new(PassthroughBlurrer), FaceCounts::default());
+        overlay.blur(&mut frame, &[region()]).unwrap();
+        assert_eq!(pixel(&frame, BADGE_MARGIN, BADGE_MARGIN), [128, 128, 128]);
+    }
+
+    #[test]
+    fn test_count_is_taken_once_drawn() {
+        let counts = FaceCounts::default();
+        counts.lock().unwrap().insert(0, 1);
+        let overlay = FaceCountOverlay::new(Box::new(PassthroughBlurrer), counts.clone());
+        overlay.blur(&mut make_frame(100, 100), &[]).unwrap();
+        assert!(counts.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tiny_frame_does_not_panic() {
+        let mut frame = make_frame(4, 4);
+        overlay_with_count(1).blur(&mut frame, &[region()]).unwrap();
+    }
+}
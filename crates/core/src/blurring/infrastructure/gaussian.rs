@@ -1,3 +1,5 @@
+use crate::shared::region::Region;
+
 /// ROI rectangle within a frame, used to pass region coordinates without many arguments.
 #[derive(Clone, Copy)]
 pub struct RoiRect {
@@ -28,6 +30,67 @@ pub fn gaussian_kernel_1d(kernel_size: usize) -> Vec<f32> {
     kernel_f64.iter().map(|&v| v as f32).collect()
 }
 
+/// Fixed-point value of a weight of 1.0 in [`quantize_kernel`] output.
+pub const FIXED_POINT_ONE: u32 = 1 << 16;
+
+/// Convert kernel weights to fixed point summing to exactly [`FIXED_POINT_ONE`].
+///
+/// Weights are normalized first, so unnormalized input is fine. The rounding
+/// remainder goes to the center tap. With 8-bit pixels every weighted sum
+/// fits in a `u32`, which makes integer blurs bit-exact on any hardware.
+pub fn quantize_kernel(weights: &[f32]) -> Vec<u32> {
+    let sum: f64 = weights.iter().map(|&w| w as f64).sum();
+    let mut fixed: Vec<u32> = weights
+        .iter()
+        .map(|&w| (w as f64 / sum * FIXED_POINT_ONE as f64).round() as u32)
+        .collect();
+    let total: i64 = fixed.iter().map(|&w| w as i64).sum();
+    let center = fixed.len() / 2;
+    fixed[center] = (fixed[center] as i64 + FIXED_POINT_ONE as i64 - total) as u32;
+    fixed
+}
+
+/// Integer separable blur with [`quantize_kernel`] weights, rounding after
+/// each pass. Mirrors the GPU shader's fixed-point mode.
+#[cfg(test)]
+pub fn separable_gaussian_blur_fixed(
+    data: &mut [u8],
+    width: usize,
+    height: usize,
+    channels: usize,
+    kernel: &[u32],
+) {
+    let half = kernel.len() / 2;
+    let round = |sum: u32| ((sum + FIXED_POINT_ONE / 2) >> 16) as u8;
+    let mut temp = vec![0u8; data.len()];
+    for y in 0..height {
+        for x in 0..width {
+            for c in 0..channels {
+                let mut sum = 0u32;
+                for (k, &w) in kernel.iter().enumerate() {
+                    let sx = (x as isize + k as isize - half as isize).clamp(0, width as isize - 1)
+                        as usize;
+                    sum += data[(y * width + sx) * channels + c] as u32 * w;
+                }
+                temp[(y * width + x) * channels + c] = round(sum);
+            }
+        }
+    }
+    for y in 0..height {
+        for x in 0..width {
+            for c in 0..channels {
+                let mut sum = 0u32;
+                for (k, &w) in kernel.iter().enumerate() {
+                    let sy = (y as isize + k as isize - half as isize).clamp(0, height as isize - 1)
+                        as usize;
+                    sum += temp[(sy * width + x) * channels + c] as u32 * w;
+                }
+                data[(y * width + x) * channels + c] = round(sum);
+            }
+        }
+    }
+}
+
 /// Convenience wrapper that allocates its own temp buffer.
 #[cfg(test)]
 pub fn separable_gaussian_blur(
@@ -131,6 +194,82 @@ pub fn write_roi_back(
     }
 }
 
+/// Write blurred ROI pixels back to the frame only within the region's ellipse.
+pub fn composite_ellipse(
+    data: &mut [u8],
+    roi: &[u8],
+    frame_width: usize,
+    channels: usize,
+    rect: RoiRect,
+    region: &Region,
+) {
+    composite_ellipse_feathered(data, roi, frame_width, channels, rect, region, 0.0);
+}
+
+/// Like [`composite_ellipse`], with an edge that fades from the blur to the
+/// original pixels over the outer `feather` of the ellipse's radius.
+///
+/// `feather` is 0.0 (a hard cut) to 1.0 (a fade from the centre). Nothing
+/// outside the ellipse changes, and within `1.0 - feather` of the radius
+/// the blur is copied as it is. The fade is a smoothstep of the normalized
+/// radius, the same curve the GPU shader uses.
+pub fn composite_ellipse_feathered(
+    data: &mut [u8],
+    roi: &[u8],
+    frame_width: usize,
+    channels: usize,
+    rect: RoiRect,
+    region: &Region,
+    feather: f64,
+) {
+    let (ecx, ecy) = region.ellipse_center_in_roi();
+    let (semi_a, semi_b) = region.ellipse_axes();
+
+    if semi_a <= 0.0 || semi_b <= 0.0 {
+        return;
+    }
+
+    let inv_a_sq = 1.0 / (semi_a * semi_a);
+    let inv_b_sq = 1.0 / (semi_b * semi_b);
+    let feather = feather.clamp(0.0, 1.0);
+
+    for row in 0..rect.h {
+        for col in 0..rect.w {
+            let dx = col as f64 - ecx;
+            let dy = row as f64 - ecy;
+            let dist = dx * dx * inv_a_sq + dy * dy * inv_b_sq;
+
+            if dist > 1.0 {
+                continue;
+            }
+            let frame_offset = ((rect.y + row) * frame_width + (rect.x + col)) * channels;
+            let roi_offset = (row * rect.w + col) * channels;
+            let weight = ellipse_edge_weight(dist.sqrt(), feather);
+            if weight >= 1.0 {
+                data[frame_offset..frame_offset + channels]
+                    .copy_from_slice(&roi[roi_offset..roi_offset + channels]);
+                continue;
+            }
+            for c in 0..channels {
+                let original = data[frame_offset + c] as f64;
+                let blurred = roi[roi_offset + c] as f64;
+                data[frame_offset + c] = (original + (blurred - original) * weight).round() as u8;
+            }
+        }
+    }
+}
+
+/// How much of the blur shows at normalized ellipse radius `r` (1.0 on the
+/// outline): 1.0 up to `1.0 - feather`, then a smoothstep down to 0.0 at
+/// the outline.
+pub fn ellipse_edge_weight(r: f64, feather: f64) -> f64 {
+    if feather <= 0.0 {
+        return if r <= 1.0 { 1.0 } else { 0.0 };
+    }
+    let t = ((r - (1.0 - feather)) / feather).clamp(0.0, 1.0);
+    1.0 - t * t * (3.0 - 2.0 * t)
+}
+
 /// Apply Gaussian blur to an ROI buffer, using downscale optimization for large kernels.
 #[allow(clippy::too_many_arguments)]
 pub fn blur_roi_in_place(
@@ -234,6 +373,7 @@ pub fn upscale(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn test_kernel_sums_to_one() {
@@ -299,6 +439,60 @@ mod tests {
         assert_eq!(data, original);
     }
 
+    #[rstest]
+    #[case(1)]
+    #[case(5)]
+    #[case(201)]
+    fn test_quantized_kernel_sums_to_one(#[case] size: usize) {
+        let k = quantize_kernel(&gaussian_kernel_1d(size));
+        assert_eq!(k.len(), size);
+        assert_eq!(k.iter().sum::<u32>(), FIXED_POINT_ONE);
+        assert_eq!(k.iter().max(), Some(&k[size / 2]));
+    }
+
+    #[test]
+    fn test_quantize_kernel_normalizes_input() {
+        assert_eq!(
+            quantize_kernel(&[1.0, 2.0, 1.0]),
+            vec![
+                FIXED_POINT_ONE / 4,
+                FIXED_POINT_ONE / 2,
+                FIXED_POINT_ONE / 4
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fixed_blur_uniform_image_unchanged() {
+        let mut data = vec![200u8; 12 * 9 * 3];
+        separable_gaussian_blur_fixed(
+            &mut data,
+            12,
+            9,
+            3,
+            &quantize_kernel(&gaussian_kernel_1d(7)),
+        );
+        assert!(data.iter().all(|&v| v == 200));
+    }
+
+    #[test]
+    fn test_fixed_blur_close_to_float_blur() {
+        let mut float = vec![0u8; 10 * 10 * 3];
+        float[(5 * 10 + 5) * 3] = 255;
+        let mut fixed = float.clone();
+        separable_gaussian_blur(&mut float, 10, 10, 3, 5);
+        separable_gaussian_blur_fixed(
+            &mut fixed,
+            10,
+            10,
+            3,
+            &quantize_kernel(&gaussian_kernel_1d(5)),
+        );
+        for (a, b) in float.iter().zip(&fixed) {
+            assert!((*a as i32 - *b as i32).abs() <= 1);
+        }
+    }
+
     #[test]
     fn test_downscale_upscale_roundtrip() {
         // Uniform image should survive roundtrip
@@ -309,4 +503,48 @@ mod tests {
         let big = upscale(&small, sw, sh, 3, 8, 8);
         assert!(big.iter().all(|&v| (v as i32 - 100).abs() <= 1));
     }
+
+    #[rstest]
+    #[case(0.0, 0.0, 1.0)]
+    #[case(0.0, 1.0, 1.0)]
+    #[case(0.0, 1.01, 0.0)]
+    #[case(0.5, 0.5, 1.0)]
+    #[case(0.5, 0.75, 0.5)]
+    #[case(0.5, 1.0, 0.0)]
+    fn test_ellipse_edge_weight(#[case] feather: f64, #[case] r: f64, #[case] expected: f64) {
+        assert!((ellipse_edge_weight(r, feather) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_feathered_composite_fades_toward_the_outline() {
+        let region = Region {
+            x: 0,
+            y: 0,
+            width: 21,
+            height: 21,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        };
+        let rect = RoiRect {
+            x: 0,
+            y: 0,
+            w: 21,
+            h: 21,
+        };
+        let roi = vec![200u8; 21 * 21];
+        let mut hard = vec![0u8; 21 * 21];
+        let mut soft = hard.clone();
+        composite_ellipse(&mut hard, &roi, 21, 1, rect, &region);
+        composite_ellipse_feathered(&mut soft, &roi, 21, 1, rect, &region, 0.5);
+
+        let row = |data: &[u8]| data[10 * 21..11 * 21].to_vec();
+        // Centre fully blurred, the edge partly, and the corner untouched.
+        assert_eq!(soft[10 * 21 + 10], 200);
+        assert!(row(&soft)[1] > 0 && row(&soft)[1] < row(&hard)[1]);
+        assert_eq!(soft[0], 0);
+        assert_eq!(hard[10 * 21 + 10], 200);
+    }
 }
@@ -2,17 +2,24 @@ use std::sync::{Arc, Mutex};
 
 use wgpu;
 
+use super::gaussian::quantize_kernel;
+use super::gpu_convert::ConvertState;
+
 /// Descriptor for a single region to blur in a batch.
 pub struct RoiDescriptor {
     pub pixels: Vec<u32>,
     pub width: u32,
     pub height: u32,
+    /// Gaussian kernel size, or the block size for `pixelate_rois`.
     pub kernel_size: u32,
     pub ellipse_cx: f32,
     pub ellipse_cy: f32,
     pub ellipse_a: f32,
     pub ellipse_b: f32,
     pub use_ellipse: bool,
+    /// Outer fraction of the ellipse's radius over which the blur fades
+    /// into the original pixels; 0.0 is a hard edge.
+    pub ellipse_feather: f32,
 }
 
 /// Shared GPU context for blur operations.
@@ -20,17 +27,45 @@ pub struct RoiDescriptor {
 /// Holds the wgpu device, queue, shader module, and pipeline so they
 /// can be reused across frames without re-initialization. GPU buffers
 /// are cached internally and reused across `blur_roi()` calls.
+///
+/// A batch runs in waves of up to `buffer_slots` ROIs, each ROI in its own
+/// set of buffers, so a wave's dispatches don't wait on each other and a
+/// large GPU has enough work in flight to stay busy. A context made with
+/// [`new_multi_adapter`](Self::new_multi_adapter) also splits each batch
+/// across the machine's other GPUs.
+///
+/// The context is also a
+/// [`PixelConverter`](crate::shared::pixel_converter::PixelConverter),
+/// converting pixel formats and letterboxing detector input on the
+/// preferred adapter.
+///
+/// In deterministic mode the shader accumulates in fixed-point integers
+/// instead of floats, so the same input produces bit-identical output on
+/// every adapter, driver, and run.
 pub struct GpuContext {
     pub device: Arc<wgpu::Device>,
     pub queue: Arc<wgpu::Queue>,
     pub shader: wgpu::ShaderModule,
     pub pipeline: wgpu::ComputePipeline,
+    /// Mosaic pipeline; shares the blur pipeline's bind group layout.
+    pub pixelate_pipeline: wgpu::ComputePipeline,
     pub bind_group_layout: wgpu::BindGroupLayout,
     /// Interior-mutable buffer cache. Mutex is always uncontended
     /// because blur() is called from a single thread per blurrer instance.
     buffers: Mutex<CachedBuffers>,
+    buffer_slots: usize,
+    /// Pipelines and buffers of the format conversions (see
+    /// `PixelConverter`), built on the first conversion.
+    pub(super) convert_state: Mutex<Option<ConvertState>>,
+    /// Contexts on other adapters that take a share of each batch.
+    helpers: Vec<GpuContext>,
+    adapter_name: String,
+    deterministic: bool,
 }
 
+/// ROIs of a batch in flight at once on one adapter, by default.
+pub const DEFAULT_BUFFER_SLOTS: usize = 4;
+
 /// Packed params matching the WGSL uniform layout (48 bytes, 12 x u32).
 #[repr(C)]
 #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
@@ -45,25 +80,30 @@ pub struct GpuBlurParams {
     pub ellipse_b: f32,
     pub use_ellipse: u32,
     pub direction: u32,
-    pub _pad0: u32,
-    pub _pad1: u32,
+    pub fixed_point: u32,
+    pub ellipse_feather: f32,
 }
 
-/// Pre-allocated GPU buffers reused across blur_roi() calls.
+/// Buffers one ROI of a wave runs in, reused across `blur_roi()` calls.
 ///
-/// Buffers are sized to `capacity` pixels. When a larger ROI arrives,
-/// all four pixel buffers are reallocated (grow-only, never shrink).
-/// Params buffers are fixed at 48 bytes and never reallocated.
-struct CachedBuffers {
+/// Pixel buffers are sized to `capacity` pixels. When a larger ROI
+/// arrives, all three are reallocated (grow-only, never shrink). Params
+/// buffers are fixed at 48 bytes and never reallocated.
+struct RoiSlot {
     capacity: usize,
     input: wgpu::Buffer,
     output: wgpu::Buffer,
     original: wgpu::Buffer,
-    staging: wgpu::Buffer,
     params_h: wgpu::Buffer,
     params_v: wgpu::Buffer,
     kernel_weights: wgpu::Buffer,
     kernel_weights_capacity: usize,
+}
+
+/// Pre-allocated GPU buffers reused across blur_roi() calls. Slots are
+/// created as waves first need them.
+struct CachedBuffers {
+    slots: Vec<RoiSlot>,
     /// Large staging buffer for batch readback (sum of all ROI sizes).
     batch_staging: Option<wgpu::Buffer>,
     batch_staging_capacity: usize,
@@ -71,10 +111,12 @@ struct CachedBuffers {
 
 const INITIAL_CAPACITY: usize = 512 * 512;
 
+const INITIAL_KERNEL_CAPACITY: usize = 201;
+
 fn make_pixel_buffers(
     device: &wgpu::Device,
     capacity: usize,
-) -> (wgpu::Buffer, wgpu::Buffer, wgpu::Buffer, wgpu::Buffer) {
+) -> (wgpu::Buffer, wgpu::Buffer, wgpu::Buffer) {
     let size = (capacity * 4) as u64;
     let input = device.create_buffer(&wgpu::BufferDescriptor {
         label: Some("cached-input"),
@@ -96,24 +138,120 @@ fn make_pixel_buffers(
         usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
         mapped_at_creation: false,
     });
-    let staging = device.create_buffer(&wgpu::BufferDescriptor {
-        label: Some("cached-staging"),
-        size,
-        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+    (input, output, original)
+}
+
+fn make_kernel_buffer(device: &wgpu::Device, taps: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("cached-kernel-weights"),
+        size: (taps * 4) as u64,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
         mapped_at_creation: false,
-    });
-    (input, output, original, staging)
+    })
+}
+
+impl RoiSlot {
+    fn new(device: &wgpu::Device) -> Self {
+        let (input, output, original) = make_pixel_buffers(device, INITIAL_CAPACITY);
+        let params = |label| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size: std::mem::size_of::<GpuBlurParams>() as u64,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        };
+        Self {
+            capacity: INITIAL_CAPACITY,
+            input,
+            output,
+            original,
+            params_h: params("cached-params-h"),
+            params_v: params("cached-params-v"),
+            kernel_weights: make_kernel_buffer(device, INITIAL_KERNEL_CAPACITY),
+            kernel_weights_capacity: INITIAL_KERNEL_CAPACITY,
+        }
+    }
+
+    /// Grows the buffers to hold `pixels` pixels and `taps` kernel weights.
+    fn reserve(&mut self, device: &wgpu::Device, pixels: usize, taps: usize) {
+        if pixels > self.capacity {
+            (self.input, self.output, self.original) = make_pixel_buffers(device, pixels);
+            self.capacity = pixels;
+        }
+        if taps > self.kernel_weights_capacity {
+            self.kernel_weights = make_kernel_buffer(device, taps);
+            self.kernel_weights_capacity = taps;
+        }
+    }
+
+    fn bind_group(
+        &self,
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        params: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("roi-bind-group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.input.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.output.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: self.original.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: self.kernel_weights.as_entire_binding(),
+                },
+            ],
+        })
+    }
 }
 
 impl GpuContext {
     /// Create a new GPU context. Returns `None` if no suitable adapter is available.
     pub fn new() -> Option<Self> {
         let instance = wgpu::Instance::default();
-        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::HighPerformance,
-            compatible_surface: None,
-            force_fallback_adapter: false,
-        }))?;
+        Self::on_adapter(&preferred_adapter(&instance)?)
+    }
+
+    /// Like [`new`](Self::new), but each batch is also split across every
+    /// other GPU on the preferred adapter's backend, integrated ones
+    /// included. Software adapters are left out. With one GPU this is
+    /// `new()`.
+    pub fn new_multi_adapter() -> Option<Self> {
+        let instance = wgpu::Instance::default();
+        let preferred = preferred_adapter(&instance)?;
+        let info = preferred.get_info();
+        let mut others = instance.enumerate_adapters(info.backend.into());
+        // Identical cards report identical info, so only drop one match.
+        if let Some(same) = others.iter().position(|a| a.get_info() == info) {
+            others.remove(same);
+        }
+        let mut ctx = Self::on_adapter(&preferred)?;
+        ctx.helpers = others
+            .iter()
+            .filter(|a| a.get_info().device_type != wgpu::DeviceType::Cpu)
+            .filter_map(Self::on_adapter)
+            .collect();
+        Some(ctx)
+    }
+
+    fn on_adapter(adapter: &wgpu::Adapter) -> Option<Self> {
+        let info = adapter.get_info();
+        let adapter_name = format!("{} ({:?})", info.name, info.backend);
 
         let (device, queue) = pollster::block_on(adapter.request_device(
             &wgpu::DeviceDescriptor {
@@ -205,40 +343,21 @@ impl GpuContext {
             cache: None,
         });
 
-        let (input, output, original, staging) = make_pixel_buffers(&device, INITIAL_CAPACITY);
-
-        let params_size = std::mem::size_of::<GpuBlurParams>() as u64;
-        let params_h = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("cached-params-h"),
-            size: params_size,
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
+        let pixelate_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("pixelate-shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/pixelate.wgsl").into()),
         });
-        let params_v = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("cached-params-v"),
-            size: params_size,
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-
-        let initial_kernel_capacity = 201;
-        let kernel_weights = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("cached-kernel-weights"),
-            size: (initial_kernel_capacity * 4) as u64,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
+        let pixelate_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("pixelate-pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &pixelate_shader,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
         });
 
         let buffers = Mutex::new(CachedBuffers {
-            capacity: INITIAL_CAPACITY,
-            input,
-            output,
-            original,
-            staging,
-            params_h,
-            params_v,
-            kernel_weights,
-            kernel_weights_capacity: initial_kernel_capacity,
+            slots: Vec::new(),
             batch_staging: None,
             batch_staging_capacity: 0,
         });
@@ -248,20 +367,60 @@ impl GpuContext {
             queue,
             shader,
             pipeline,
+            pixelate_pipeline,
             bind_group_layout,
             buffers,
+            buffer_slots: DEFAULT_BUFFER_SLOTS,
+            convert_state: Mutex::new(None),
+            helpers: Vec::new(),
+            adapter_name,
+            deterministic: false,
         })
     }
 
+    /// Use fixed-point arithmetic in the shader so output is reproducible
+    /// across adapters and runs.
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self.helpers = self
+            .helpers
+            .into_iter()
+            .map(|helper| helper.with_deterministic(deterministic))
+            .collect();
+        self
+    }
+
+    /// ROIs of a batch in flight at once on each adapter; 1 runs them one
+    /// after another. Each slot holds its own buffers, sized to the
+    /// largest ROI it has run.
+    pub fn with_buffer_slots(mut self, slots: usize) -> Self {
+        self.buffer_slots = slots.max(1);
+        self.helpers = self
+            .helpers
+            .into_iter()
+            .map(|helper| helper.with_buffer_slots(slots))
+            .collect();
+        self
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    /// Adapter name and graphics backend, for logs and parity reports.
+    /// With several adapters, the preferred one.
+    pub fn adapter_name(&self) -> &str {
+        &self.adapter_name
+    }
+
+    /// Adapters each batch is split across.
+    pub fn adapter_count(&self) -> usize {
+        1 + self.helpers.len()
+    }
+
     /// Probe for GPU availability without allocating pixel buffers or pipelines.
     pub fn is_available() -> bool {
-        let instance = wgpu::Instance::default();
-        pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::HighPerformance,
-            compatible_surface: None,
-            force_fallback_adapter: false,
-        }))
-        .is_some()
+        preferred_adapter(&wgpu::Instance::default()).is_some()
     }
 
     /// Run a two-pass blur on an ROI. Convenience wrapper around `blur_rois`.
@@ -288,213 +447,226 @@ impl GpuContext {
             ellipse_a,
             ellipse_b,
             use_ellipse,
+            ellipse_feather: 0.0,
         };
         let mut results = self.blur_rois(&[roi]);
         results.remove(0)
     }
 
-    /// Batch-blur multiple ROIs with a single GPU readback.
+    /// Batch-blur multiple ROIs with a single GPU readback per adapter.
     ///
-    /// Each ROI is processed through the two-pass blur pipeline. All results
-    /// are collected into a single staging buffer and read back with one
-    /// `device.poll(Wait)` call, eliminating per-region synchronous stalls.
+    /// Each ROI is processed through the two-pass blur pipeline, a wave of
+    /// `buffer_slots` ROIs per submission. All results are collected into a
+    /// single staging buffer and read back with one `device.poll(Wait)`
+    /// call, eliminating per-region synchronous stalls.
     pub fn blur_rois(&self, rois: &[RoiDescriptor]) -> Vec<Vec<u32>> {
+        self.run_rois(rois, RoiPass::Gaussian)
+    }
+
+    /// Batch-pixelate multiple ROIs, each with blocks of `kernel_size`
+    /// pixels. One pass per ROI and one readback, like `blur_rois`. Integer
+    /// math, so the result is the same on every adapter and matches the CPU.
+    /// The ellipse fields are ignored.
+    pub fn pixelate_rois(&self, rois: &[RoiDescriptor]) -> Vec<Vec<u32>> {
+        self.run_rois(rois, RoiPass::Pixelate)
+    }
+
+    fn run_rois(&self, rois: &[RoiDescriptor], kind: RoiPass) -> Vec<Vec<u32>> {
+        if self.helpers.is_empty() || rois.len() < 2 {
+            return self.run_rois_here(rois, kind);
+        }
+        // Contiguous shares, so the results concatenate in order.
+        let share = rois.len().div_ceil(self.adapter_count());
+        let contexts = std::iter::once(self).chain(&self.helpers);
+        std::thread::scope(|scope| {
+            let shares: Vec<_> = rois
+                .chunks(share)
+                .zip(contexts)
+                .map(|(chunk, ctx)| scope.spawn(move || ctx.run_rois_here(chunk, kind)))
+                .collect();
+            shares
+                .into_iter()
+                .flat_map(|share| {
+                    share
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        })
+    }
+
+    /// Runs `rois` on this context's own adapter.
+    fn run_rois_here(&self, rois: &[RoiDescriptor], kind: RoiPass) -> Vec<Vec<u32>> {
         if rois.is_empty() {
             return vec![];
         }
 
         let mut cache = self.buffers.lock().unwrap();
+        let CachedBuffers {
+            slots,
+            batch_staging,
+            batch_staging_capacity,
+        } = &mut *cache;
 
-        let max_pixels = rois
-            .iter()
-            .map(|r| (r.width * r.height) as usize)
-            .max()
-            .unwrap_or(0);
         let total_staging_bytes: u64 = rois
             .iter()
             .map(|r| (r.width as u64) * (r.height as u64) * 4)
             .sum();
 
-        if max_pixels > cache.capacity {
-            let (inp, out, orig, stg) = make_pixel_buffers(&self.device, max_pixels);
-            cache.input = inp;
-            cache.output = out;
-            cache.original = orig;
-            cache.staging = stg;
-            cache.capacity = max_pixels;
-        }
-
         let total_staging_usize = total_staging_bytes as usize;
-        if total_staging_usize > cache.batch_staging_capacity || cache.batch_staging.is_none() {
-            cache.batch_staging = Some(self.device.create_buffer(&wgpu::BufferDescriptor {
+        if total_staging_usize > *batch_staging_capacity || batch_staging.is_none() {
+            *batch_staging = Some(self.device.create_buffer(&wgpu::BufferDescriptor {
                 label: Some("batch-staging"),
                 size: total_staging_bytes,
                 usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
                 mapped_at_creation: false,
             }));
-            cache.batch_staging_capacity = total_staging_usize;
+            *batch_staging_capacity = total_staging_usize;
         }
+        let batch_buf = batch_staging.as_ref().unwrap();
 
         let mut offsets: Vec<(u64, usize)> = Vec::with_capacity(rois.len());
         let mut staging_offset: u64 = 0;
 
-        for roi in rois {
-            let pixel_count = (roi.width * roi.height) as usize;
-            let buf_size = (pixel_count * 4) as u64;
-            let kernel_radius = roi.kernel_size / 2;
-            let sigma = roi.kernel_size as f32 / 6.0;
-            let kernel_len = (2 * kernel_radius + 1) as usize;
-
-            let sigma64 = roi.kernel_size as f64 / 6.0;
-            let half = kernel_radius as f64;
-            let weights: Vec<f32> = (0..kernel_len)
-                .map(|i| {
-                    let x = i as f64 - half;
-                    (-x * x / (2.0 * sigma64 * sigma64)).exp() as f32
-                })
-                .collect();
-
-            if kernel_len > cache.kernel_weights_capacity {
-                cache.kernel_weights = self.device.create_buffer(&wgpu::BufferDescriptor {
-                    label: Some("cached-kernel-weights"),
-                    size: (kernel_len * 4) as u64,
-                    usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-                    mapped_at_creation: false,
-                });
-                cache.kernel_weights_capacity = kernel_len;
+        for wave in rois.chunks(self.buffer_slots) {
+            while slots.len() < wave.len() {
+                slots.push(RoiSlot::new(&self.device));
             }
-            self.queue
-                .write_buffer(&cache.kernel_weights, 0, bytemuck::cast_slice(&weights));
-
-            self.queue
-                .write_buffer(&cache.input, 0, bytemuck::cast_slice(&roi.pixels));
-            self.queue
-                .write_buffer(&cache.original, 0, bytemuck::cast_slice(&roi.pixels));
-
-            let params_h = GpuBlurParams {
-                width: roi.width,
-                height: roi.height,
-                kernel_radius,
-                sigma,
-                ellipse_cx: roi.ellipse_cx,
-                ellipse_cy: roi.ellipse_cy,
-                ellipse_a: roi.ellipse_a,
-                ellipse_b: roi.ellipse_b,
-                use_ellipse: if roi.use_ellipse { 1 } else { 0 },
-                direction: 0,
-                _pad0: 0,
-                _pad1: 0,
-            };
-            self.queue
-                .write_buffer(&cache.params_h, 0, bytemuck::bytes_of(&params_h));
-
-            let params_v = GpuBlurParams {
-                direction: 1,
-                ..params_h
-            };
-            self.queue
-                .write_buffer(&cache.params_v, 0, bytemuck::bytes_of(&params_v));
-
-            let bg_h = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("bg-h"),
-                layout: &self.bind_group_layout,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: cache.params_h.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: cache.input.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 2,
-                        resource: cache.output.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 3,
-                        resource: cache.original.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 4,
-                        resource: cache.kernel_weights.as_entire_binding(),
-                    },
-                ],
-            });
-
-            let bg_v = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("bg-v"),
-                layout: &self.bind_group_layout,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: cache.params_v.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: cache.input.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 2,
-                        resource: cache.output.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 3,
-                        resource: cache.original.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 4,
-                        resource: cache.kernel_weights.as_entire_binding(),
-                    },
-                ],
-            });
 
-            let workgroups_x = roi.width.div_ceil(16);
-            let workgroups_y = roi.height.div_ceil(16);
+            // Every ROI of a wave has its own slot, so the writes below all
+            // land before the wave's submission without clobbering each other.
+            let mut bind_groups = Vec::with_capacity(wave.len());
+            for (roi, slot) in wave.iter().zip(slots.iter_mut()) {
+                // The pixelate shader reads the block size from `kernel_radius`.
+                let kernel_radius = match kind {
+                    RoiPass::Gaussian => roi.kernel_size / 2,
+                    RoiPass::Pixelate => roi.kernel_size,
+                };
+                let sigma = roi.kernel_size as f32 / 6.0;
+                let kernel_len = match kind {
+                    RoiPass::Gaussian => (2 * kernel_radius + 1) as usize,
+                    RoiPass::Pixelate => 0,
+                };
+                slot.reserve(&self.device, (roi.width * roi.height) as usize, kernel_len);
+
+                if kind == RoiPass::Gaussian {
+                    // The shader reads raw u32s: fixed-point weights, or f32 bits.
+                    let float_weights = kernel_weights(roi.kernel_size);
+                    let weights: Vec<u32> = if self.deterministic {
+                        quantize_kernel(&float_weights)
+                    } else {
+                        float_weights.iter().map(|w| w.to_bits()).collect()
+                    };
+                    self.queue.write_buffer(
+                        &slot.kernel_weights,
+                        0,
+                        bytemuck::cast_slice(&weights),
+                    );
+                }
+
+                self.queue
+                    .write_buffer(&slot.input, 0, bytemuck::cast_slice(&roi.pixels));
+                self.queue
+                    .write_buffer(&slot.original, 0, bytemuck::cast_slice(&roi.pixels));
+
+                let params_h = GpuBlurParams {
+                    width: roi.width,
+                    height: roi.height,
+                    kernel_radius,
+                    sigma,
+                    ellipse_cx: roi.ellipse_cx,
+                    ellipse_cy: roi.ellipse_cy,
+                    ellipse_a: roi.ellipse_a,
+                    ellipse_b: roi.ellipse_b,
+                    use_ellipse: if roi.use_ellipse { 1 } else { 0 },
+                    direction: 0,
+                    fixed_point: self.deterministic as u32,
+                    ellipse_feather: roi.ellipse_feather,
+                };
+                self.queue
+                    .write_buffer(&slot.params_h, 0, bytemuck::bytes_of(&params_h));
+
+                let params_v = GpuBlurParams {
+                    direction: 1,
+                    ..params_h
+                };
+                self.queue
+                    .write_buffer(&slot.params_v, 0, bytemuck::bytes_of(&params_v));
+
+                bind_groups.push((
+                    slot.bind_group(&self.device, &self.bind_group_layout, &slot.params_h),
+                    slot.bind_group(&self.device, &self.bind_group_layout, &slot.params_v),
+                ));
+            }
 
+            let workgroups =
+                |roi: &RoiDescriptor| (roi.width.div_ceil(16), roi.height.div_ceil(16));
             let mut encoder = self
                 .device
                 .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                     label: Some("blur-encoder"),
                 });
 
-            {
-                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-                    label: Some("horizontal"),
-                    timestamp_writes: None,
-                });
-                pass.set_pipeline(&self.pipeline);
-                pass.set_bind_group(0, &bg_h, &[]);
-                pass.dispatch_workgroups(workgroups_x, workgroups_y, 1);
+            // One pass per direction holds the whole wave's dispatches; they
+            // touch separate buffers, so none has to wait for another.
+            match kind {
+                RoiPass::Gaussian => {
+                    {
+                        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                            label: Some("horizontal"),
+                            timestamp_writes: None,
+                        });
+                        pass.set_pipeline(&self.pipeline);
+                        for (roi, (bg_h, _)) in wave.iter().zip(&bind_groups) {
+                            let (x, y) = workgroups(roi);
+                            pass.set_bind_group(0, bg_h, &[]);
+                            pass.dispatch_workgroups(x, y, 1);
+                        }
+                    }
+
+                    for (roi, slot) in wave.iter().zip(slots.iter()) {
+                        let buf_size = (roi.width * roi.height * 4) as u64;
+                        encoder.copy_buffer_to_buffer(&slot.output, 0, &slot.input, 0, buf_size);
+                    }
+
+                    {
+                        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                            label: Some("vertical"),
+                            timestamp_writes: None,
+                        });
+                        pass.set_pipeline(&self.pipeline);
+                        for (roi, (_, bg_v)) in wave.iter().zip(&bind_groups) {
+                            let (x, y) = workgroups(roi);
+                            pass.set_bind_group(0, bg_v, &[]);
+                            pass.dispatch_workgroups(x, y, 1);
+                        }
+                    }
+                }
+                RoiPass::Pixelate => {
+                    let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                        label: Some("pixelate"),
+                        timestamp_writes: None,
+                    });
+                    pass.set_pipeline(&self.pixelate_pipeline);
+                    for (roi, (bg_h, _)) in wave.iter().zip(&bind_groups) {
+                        let (x, y) = workgroups(roi);
+                        pass.set_bind_group(0, bg_h, &[]);
+                        pass.dispatch_workgroups(x, y, 1);
+                    }
+                }
             }
 
-            encoder.copy_buffer_to_buffer(&cache.output, 0, &cache.input, 0, buf_size);
-
-            {
-                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-                    label: Some("vertical"),
-                    timestamp_writes: None,
-                });
-                pass.set_pipeline(&self.pipeline);
-                pass.set_bind_group(0, &bg_v, &[]);
-                pass.dispatch_workgroups(workgroups_x, workgroups_y, 1);
+            for (roi, slot) in wave.iter().zip(slots.iter()) {
+                let pixel_count = (roi.width * roi.height) as usize;
+                let buf_size = (pixel_count * 4) as u64;
+                encoder.copy_buffer_to_buffer(&slot.output, 0, batch_buf, staging_offset, buf_size);
+                offsets.push((staging_offset, pixel_count));
+                staging_offset += buf_size;
             }
 
-            encoder.copy_buffer_to_buffer(
-                &cache.output,
-                0,
-                cache.batch_staging.as_ref().unwrap(),
-                staging_offset,
-                buf_size,
-            );
-
             self.queue.submit(Some(encoder.finish()));
-
-            offsets.push((staging_offset, pixel_count));
-            staging_offset += buf_size;
         }
 
-        let batch_buf = cache.batch_staging.as_ref().unwrap();
         let slice = batch_buf.slice(..total_staging_bytes);
         slice.map_async(wgpu::MapMode::Read, |_| {});
         self.device.poll(wgpu::Maintain::Wait);
@@ -517,6 +689,37 @@ impl GpuContext {
     }
 }
 
+/// The adapter [`GpuContext::new`] runs on: the high-performance one.
+fn preferred_adapter(instance: &wgpu::Instance) -> Option<wgpu::Adapter> {
+    pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::HighPerformance,
+        compatible_surface: None,
+        force_fallback_adapter: false,
+    }))
+}
+
+/// Which shader `run_rois` dispatches.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RoiPass {
+    /// Two-pass separable Gaussian blur.
+    Gaussian,
+    /// One-pass mosaic.
+    Pixelate,
+}
+
+/// Unnormalized Gaussian weights for the shader, `2 * (kernel_size / 2) + 1` taps.
+pub fn kernel_weights(kernel_size: u32) -> Vec<f32> {
+    let kernel_radius = kernel_size / 2;
+    let sigma = kernel_size as f64 / 6.0;
+    let half = kernel_radius as f64;
+    (0..2 * kernel_radius + 1)
+        .map(|i| {
+            let x = i as f64 - half;
+            (-x * x / (2.0 * sigma * sigma)).exp() as f32
+        })
+        .collect()
+}
+
 /// Pack RGB frame data for a rectangular ROI into GPU-ready u32 pixels.
 #[allow(clippy::too_many_arguments)]
 pub fn pack_roi(
@@ -627,6 +830,7 @@ mod tests {
                 ellipse_a: 2.0,
                 ellipse_b: 2.0,
                 use_ellipse: false,
+                ellipse_feather: 0.0,
             },
             RoiDescriptor {
                 pixels: pixels_b,
@@ -638,6 +842,7 @@ mod tests {
                 ellipse_a: 3.0,
                 ellipse_b: 3.0,
                 use_ellipse: false,
+                ellipse_feather: 0.0,
             },
         ];
         let batch = ctx.blur_rois(&rois);
@@ -646,4 +851,117 @@ mod tests {
         assert_eq!(batch[0], single_a, "batch ROI 0 must match individual");
         assert_eq!(batch[1], single_b, "batch ROI 1 must match individual");
     }
+
+    fn noisy_roi(side: u32, kernel_size: u32) -> RoiDescriptor {
+        let center = side as f32 / 2.0;
+        RoiDescriptor {
+            pixels: (0..side * side)
+                .map(|i| i.wrapping_mul(2_654_435_761) & 0x00FF_FFFF)
+                .collect(),
+            width: side,
+            height: side,
+            kernel_size,
+            ellipse_cx: center,
+            ellipse_cy: center,
+            ellipse_a: center,
+            ellipse_b: center,
+            use_ellipse: false,
+            ellipse_feather: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_waves_match_one_roi_at_a_time() {
+        let (Some(serial), Some(waves)) = (GpuContext::new(), GpuContext::new()) else {
+            return;
+        };
+        let serial = serial.with_buffer_slots(1);
+        let waves = waves.with_buffer_slots(3);
+        // Seven ROIs of different sizes and kernels: two full waves and a
+        // partial one, each slot growing as it goes.
+        let rois = || -> Vec<RoiDescriptor> {
+            (0..7)
+                .map(|i| noisy_roi(8 + 6 * i, 3 + 2 * (i % 3)))
+                .collect()
+        };
+
+        assert_eq!(waves.blur_rois(&rois()), serial.blur_rois(&rois()));
+        assert_eq!(waves.pixelate_rois(&rois()), serial.pixelate_rois(&rois()));
+    }
+
+    #[test]
+    fn test_multi_adapter_matches_single_in_fixed_point() {
+        let (Some(single), Some(multi)) = (GpuContext::new(), GpuContext::new_multi_adapter())
+        else {
+            return;
+        };
+        let single = single.with_deterministic(true);
+        let multi = multi.with_deterministic(true);
+        let rois = || -> Vec<RoiDescriptor> { (0..5).map(|i| noisy_roi(10 + i, 5)).collect() };
+
+        assert_eq!(multi.blur_rois(&rois()), single.blur_rois(&rois()));
+    }
+
+    fn noisy_pixels(width: usize, height: usize) -> Vec<u8> {
+        (0..width * height * 3)
+            .map(|i| (i * 37 % 251) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn test_deterministic_blur_is_bit_exact_with_cpu_fixed_point() {
+        let Some(ctx) = GpuContext::new().map(|c| c.with_deterministic(true)) else {
+            return;
+        };
+        let (w, h, kernel_size) = (23, 17, 9);
+        let rgb = noisy_pixels(w, h);
+        let packed = pack_roi(&rgb, w, 3, 0, 0, w, h);
+        let result = ctx.blur_roi(
+            &packed,
+            w as u32,
+            h as u32,
+            kernel_size,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            false,
+        );
+        let mut gpu = vec![0u8; w * h * 3];
+        unpack_roi(&mut gpu, &result, w, 3, 0, 0, w, h);
+
+        let mut cpu = rgb;
+        crate::blurring::infrastructure::gaussian::separable_gaussian_blur_fixed(
+            &mut cpu,
+            w,
+            h,
+            3,
+            &quantize_kernel(&kernel_weights(kernel_size)),
+        );
+        assert_eq!(gpu, cpu);
+    }
+
+    #[test]
+    fn test_deterministic_blur_repeats_exactly() {
+        let Some(ctx) = GpuContext::new().map(|c| c.with_deterministic(true)) else {
+            return;
+        };
+        let packed = pack_roi(&noisy_pixels(40, 40), 40, 3, 0, 0, 40, 40);
+        let first = ctx.blur_roi(&packed, 40, 40, 31, 0.0, 0.0, 0.0, 0.0, false);
+        for _ in 0..5 {
+            assert_eq!(
+                ctx.blur_roi(&packed, 40, 40, 31, 0.0, 0.0, 0.0, 0.0, false),
+                first
+            );
+        }
+    }
+
+    #[test]
+    fn test_kernel_weights_are_symmetric_and_odd() {
+        let w = kernel_weights(8);
+        assert_eq!(w.len(), 9);
+        for i in 0..w.len() / 2 {
+            assert_eq!(w[i], w[w.len() - 1 - i]);
+        }
+    }
 }
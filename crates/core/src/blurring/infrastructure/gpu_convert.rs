@@ -0,0 +1,469 @@
+//! Pixel format conversion and letterboxing on a [`GpuContext`].
+//!
+//! Each conversion uploads its input, runs one compute pass, and reads the
+//! result back. Buffers are cached on the context and only grow, like the
+//! blur buffers. Conversions always run on the context's own adapter,
+//! never on its helpers.
+
+use crate::shared::color_space::ColorSpace;
+use crate::shared::frame::Frame;
+use crate::shared::letterbox::Letterbox;
+use crate::shared::pixel_converter::PixelConverter;
+use crate::shared::yuv420::{Yuv420, YuvCoefficients};
+
+use super::gpu_context::GpuContext;
+
+/// Invocations per workgroup in the conversion shaders.
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Workgroups a dispatch may have along one dimension.
+const MAX_GROUPS_PER_DIMENSION: u32 = 65_535;
+
+/// Packed params matching the WGSL uniform layout (64 bytes, 16 x u32).
+#[repr(C)]
+#[derive(Clone, Copy, Default, bytemuck::Pod, bytemuck::Zeroable)]
+struct ConvertParams {
+    width: u32,
+    height: u32,
+    chroma_width: u32,
+    chroma_height: u32,
+    kr: f32,
+    kb: f32,
+    luma_offset: f32,
+    luma_scale: f32,
+    chroma_scale: f32,
+    channels: u32,
+    size: u32,
+    pad_x: u32,
+    pad_y: u32,
+    scaled_width: u32,
+    scaled_height: u32,
+    /// Output words (or floats) the dispatch writes.
+    count: u32,
+}
+
+impl ConvertParams {
+    fn yuv(width: u32, height: u32, color: ColorSpace) -> Self {
+        let coefficients = YuvCoefficients::of(color);
+        Self {
+            width,
+            height,
+            chroma_width: width.div_ceil(2),
+            chroma_height: height.div_ceil(2),
+            kr: coefficients.kr,
+            kb: coefficients.kb,
+            luma_offset: coefficients.luma_offset,
+            luma_scale: coefficients.luma_scale,
+            chroma_scale: coefficients.chroma_scale,
+            ..Self::default()
+        }
+    }
+}
+
+/// Which conversion shader a dispatch runs.
+#[derive(Clone, Copy)]
+enum Conversion {
+    YuvToRgb,
+    RgbToYuv,
+    Letterbox,
+}
+
+/// A storage buffer that is reallocated when a larger one is needed.
+struct GrowingBuffer {
+    label: &'static str,
+    usage: wgpu::BufferUsages,
+    buffer: Option<wgpu::Buffer>,
+}
+
+impl GrowingBuffer {
+    fn new(label: &'static str, usage: wgpu::BufferUsages) -> Self {
+        Self {
+            label,
+            usage,
+            buffer: None,
+        }
+    }
+
+    fn reserve(&mut self, device: &wgpu::Device, size: u64) -> &wgpu::Buffer {
+        if self.buffer.as_ref().map_or(true, |b| b.size() < size) {
+            self.buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(self.label),
+                size,
+                usage: self.usage,
+                mapped_at_creation: false,
+            }));
+        }
+        self.buffer.as_ref().unwrap()
+    }
+}
+
+/// Pipelines and buffers of the conversion shaders, built on first use so
+/// a context that only blurs never compiles them.
+pub(super) struct ConvertState {
+    layout: wgpu::BindGroupLayout,
+    yuv_to_rgb: wgpu::ComputePipeline,
+    rgb_to_yuv: wgpu::ComputePipeline,
+    letterbox: wgpu::ComputePipeline,
+    params: wgpu::Buffer,
+    input: GrowingBuffer,
+    output: GrowingBuffer,
+    staging: GrowingBuffer,
+}
+
+impl ConvertState {
+    fn new(device: &wgpu::Device) -> Self {
+        let storage = |binding, read_only| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("convert-bind-group-layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage(1, true),
+                storage(2, false),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("convert-pipeline-layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |label, source: &str| {
+            let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(label),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                module: &module,
+                entry_point: Some("main"),
+                compilation_options: Default::default(),
+                cache: None,
+            })
+        };
+        let yuv_to_rgb = pipeline("yuv420-to-rgb", include_str!("shaders/yuv420_to_rgb.wgsl"));
+        let rgb_to_yuv = pipeline("rgb-to-yuv420", include_str!("shaders/rgb_to_yuv420.wgsl"));
+        let letterbox = pipeline("letterbox", include_str!("shaders/letterbox.wgsl"));
+        let params = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("convert-params"),
+            size: std::mem::size_of::<ConvertParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        Self {
+            layout,
+            yuv_to_rgb,
+            rgb_to_yuv,
+            letterbox,
+            params,
+            input: GrowingBuffer::new(
+                "convert-input",
+                wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            ),
+            output: GrowingBuffer::new(
+                "convert-output",
+                wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            ),
+            staging: GrowingBuffer::new(
+                "convert-staging",
+                wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            ),
+        }
+    }
+}
+
+impl GpuContext {
+    /// Runs `conversion` over `input` with `invocations` shader invocations
+    /// and hands the first `output_bytes` bytes of the result to `read`.
+    /// `input` must be a whole number of words.
+    fn convert<T>(
+        &self,
+        conversion: Conversion,
+        params: ConvertParams,
+        input: &[u8],
+        invocations: u32,
+        output_bytes: usize,
+        read: impl FnOnce(&[u8]) -> T,
+    ) -> T {
+        debug_assert_eq!(input.len() % 4, 0);
+        let mut guard = self.convert_state.lock().unwrap();
+        let state = guard.get_or_insert_with(|| ConvertState::new(&self.device));
+        let output_size = output_bytes.next_multiple_of(4) as u64;
+
+        self.queue
+            .write_buffer(&state.params, 0, bytemuck::bytes_of(&params));
+        let input_buffer = state.input.reserve(&self.device, input.len() as u64);
+        self.queue.write_buffer(input_buffer, 0, input);
+        let output_buffer = state.output.reserve(&self.device, output_size);
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("convert-bind-group"),
+            layout: &state.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: state.params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: input_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: output_buffer.as_entire_binding(),
+                },
+            ],
+        });
+        let pipeline = match conversion {
+            Conversion::YuvToRgb => &state.yuv_to_rgb,
+            Conversion::RgbToYuv => &state.rgb_to_yuv,
+            Conversion::Letterbox => &state.letterbox,
+        };
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("convert-encoder"),
+            });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("convert"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            // Large frames need more workgroups than one dimension allows;
+            // the shaders flatten the grid back into an index.
+            let groups = invocations.div_ceil(WORKGROUP_SIZE).max(1);
+            let x = groups.min(MAX_GROUPS_PER_DIMENSION);
+            pass.dispatch_workgroups(x, groups.div_ceil(x), 1);
+        }
+        let staging = state.staging.reserve(&self.device, output_size);
+        encoder.copy_buffer_to_buffer(output_buffer, 0, staging, 0, output_size);
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = staging.slice(..output_size);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        self.device.poll(wgpu::Maintain::Wait);
+        let mapped = slice.get_mapped_range();
+        let result = read(&mapped[..output_bytes]);
+        drop(mapped);
+        staging.unmap();
+        result
+    }
+}
+
+impl PixelConverter for GpuContext {
+    fn yuv420_to_rgb(&self, yuv: &Yuv420, color: ColorSpace) -> Vec<u8> {
+        let pixels = yuv.width * yuv.height;
+        let output_bytes = pixels as usize * 3;
+        let params = ConvertParams {
+            count: output_bytes.div_ceil(4) as u32,
+            ..ConvertParams::yuv(yuv.width, yuv.height, color)
+        };
+        let mut input = Vec::with_capacity((yuv.y.len() + 2 * yuv.u.len()).next_multiple_of(4));
+        input.extend_from_slice(&yuv.y);
+        input.extend_from_slice(&yuv.u);
+        input.extend_from_slice(&yuv.v);
+        input.resize(input.len().next_multiple_of(4), 0);
+        self.convert(
+            Conversion::YuvToRgb,
+            params,
+            &input,
+            pixels.div_ceil(4),
+            output_bytes,
+            <[u8]>::to_vec,
+        )
+    }
+
+    fn rgb_to_yuv420(&self, rgb: &[u8], width: u32, height: u32, color: ColorSpace) -> Yuv420 {
+        let params = ConvertParams::yuv(width, height, color);
+        let luma = (width * height) as usize;
+        let chroma = (params.chroma_width * params.chroma_height) as usize;
+        let output_bytes = luma + 2 * chroma;
+        let words = output_bytes.div_ceil(4) as u32;
+        let params = ConvertParams {
+            count: words,
+            ..params
+        };
+        let padded;
+        let input = if rgb.len() % 4 == 0 {
+            rgb
+        } else {
+            let mut copy = rgb.to_vec();
+            copy.resize(rgb.len().next_multiple_of(4), 0);
+            padded = copy;
+            &padded
+        };
+        self.convert(
+            Conversion::RgbToYuv,
+            params,
+            input,
+            words,
+            output_bytes,
+            |planes| Yuv420 {
+                width,
+                height,
+                y: planes[..luma].to_vec(),
+                u: planes[luma..luma + chroma].to_vec(),
+                v: planes[luma + chroma..].to_vec(),
+            },
+        )
+    }
+
+    fn letterbox(&self, frame: &Frame, letterbox: &Letterbox, out: &mut [f32]) {
+        let count = 3 * letterbox.size * letterbox.size;
+        let params = ConvertParams {
+            width: frame.width(),
+            height: frame.height(),
+            channels: frame.channels() as u32,
+            size: letterbox.size,
+            pad_x: letterbox.pad_x,
+            pad_y: letterbox.pad_y,
+            scaled_width: letterbox.width,
+            scaled_height: letterbox.height,
+            count,
+            ..ConvertParams::default()
+        };
+        let tables: Vec<u32> = (0..letterbox.width)
+            .map(|x| letterbox.source_x(x))
+            .chain((0..letterbox.height).map(|y| letterbox.source_y(y)))
+            .collect();
+        let data = frame.data();
+        let mut input = Vec::with_capacity(data.len().next_multiple_of(4) + tables.len() * 4);
+        input.extend_from_slice(data);
+        input.resize(data.len().next_multiple_of(4), 0);
+        input.extend_from_slice(bytemuck::cast_slice(&tables));
+        self.convert(
+            Conversion::Letterbox,
+            params,
+            &input,
+            count,
+            count as usize * 4,
+            |values| out.copy_from_slice(bytemuck::cast_slice(values)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::color_space::{ColorMatrix, ColorRange};
+    use rstest::rstest;
+
+    /// Deterministic noise, so every code value and block shape comes up.
+    fn noise(len: usize, seed: u32) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (state >> 24) as u8
+            })
+            .collect()
+    }
+
+    fn assert_within_one_level(gpu: &[u8], cpu: &[u8]) {
+        assert_eq!(gpu.len(), cpu.len());
+        for (i, (g, c)) in gpu.iter().zip(cpu).enumerate() {
+            assert!(g.abs_diff(*c) <= 1, "byte {i}: GPU {g}, CPU {c}");
+        }
+    }
+
+    #[rstest]
+    #[case(ColorMatrix::Bt601, ColorRange::Limited, 64, 48)]
+    #[case(ColorMatrix::Bt709, ColorRange::Full, 33, 17)]
+    fn test_yuv_to_rgb_matches_cpu(
+        #[case] matrix: ColorMatrix,
+        #[case] range: ColorRange,
+        #[case] width: u32,
+        #[case] height: u32,
+    ) {
+        let Some(ctx) = GpuContext::new() else {
+            return;
+        };
+        let color = ColorSpace::new(matrix, range);
+        let chroma = (width.div_ceil(2) * height.div_ceil(2)) as usize;
+        let yuv = Yuv420 {
+            width,
+            height,
+            y: noise((width * height) as usize, 1),
+            u: noise(chroma, 2),
+            v: noise(chroma, 3),
+        };
+
+        assert_within_one_level(&ctx.yuv420_to_rgb(&yuv, color), &yuv.to_rgb(color));
+    }
+
+    #[rstest]
+    #[case(ColorMatrix::Bt709, ColorRange::Limited, 64, 48)]
+    #[case(ColorMatrix::Bt601, ColorRange::Full, 33, 17)]
+    fn test_rgb_to_yuv_matches_cpu(
+        #[case] matrix: ColorMatrix,
+        #[case] range: ColorRange,
+        #[case] width: u32,
+        #[case] height: u32,
+    ) {
+        let Some(ctx) = GpuContext::new() else {
+            return;
+        };
+        let color = ColorSpace::new(matrix, range);
+        let rgb = noise((width * height * 3) as usize, 4);
+
+        let gpu = ctx.rgb_to_yuv420(&rgb, width, height, color);
+        let cpu = Yuv420::from_rgb(&rgb, width, height, color);
+
+        assert_within_one_level(&gpu.y, &cpu.y);
+        assert_within_one_level(&gpu.u, &cpu.u);
+        assert_within_one_level(&gpu.v, &cpu.v);
+    }
+
+    #[rstest]
+    #[case(97, 41, 64)]
+    #[case(40, 90, 32)]
+    fn test_letterbox_matches_cpu(#[case] width: u32, #[case] height: u32, #[case] size: u32) {
+        let Some(ctx) = GpuContext::new() else {
+            return;
+        };
+        let frame = Frame::new(noise((width * height * 3) as usize, 5), width, height, 3, 0);
+        let letterbox = Letterbox::new(width, height, size);
+        let len = (3 * size * size) as usize;
+        let (mut gpu, mut cpu) = (vec![0.0; len], vec![0.0; len]);
+
+        ctx.letterbox(&frame, &letterbox, &mut gpu);
+        letterbox.fill(&frame, &mut cpu);
+
+        for (i, (g, c)) in gpu.iter().zip(&cpu).enumerate() {
+            assert!((g - c).abs() < 1e-6, "value {i}: GPU {g}, CPU {c}");
+        }
+    }
+
+    #[test]
+    fn test_buffers_grow_between_sizes() {
+        let Some(ctx) = GpuContext::new() else {
+            return;
+        };
+        let color = ColorSpace::default();
+        for (width, height) in [(8, 8), (40, 30), (8, 8)] {
+            let rgb = noise((width * height * 3) as usize, width);
+            let yuv = ctx.rgb_to_yuv420(&rgb, width, height, color);
+            assert_eq!(yuv.y.len(), (width * height) as usize);
+            assert_eq!(ctx.yuv420_to_rgb(&yuv, color).len(), rgb.len());
+        }
+    }
+}
@@ -4,6 +4,7 @@ use crate::blurring::domain::frame_blurrer::FrameBlurrer;
 use crate::shared::frame::Frame;
 use crate::shared::region::Region;
 
+use super::gaussian::{self, RoiRect};
 use super::gpu_context::{pack_roi, unpack_roi, GpuContext, RoiDescriptor};
 
 const DEFAULT_KERNEL_SIZE: u32 = 201;
@@ -12,15 +13,29 @@ const DEFAULT_KERNEL_SIZE: u32 = 201;
 ///
 /// Runs a two-pass separable Gaussian blur on the GPU with an ellipse mask.
 /// Uses the Region's edge-aware ellipse geometry so the blur extends
-/// smoothly off frame edges.
+/// smoothly off frame edges. With a deterministic context the ellipse mask
+/// is applied on the CPU, like `CpuEllipticalBlurrer` does. `with_feather`
+/// softens the ellipse's edge on either path, matching the CPU blurrer.
 pub struct GpuEllipticalBlurrer {
     ctx: Arc<GpuContext>,
     kernel_size: u32,
+    feather: f32,
 }
 
 impl GpuEllipticalBlurrer {
     pub fn new(ctx: Arc<GpuContext>, kernel_size: u32) -> Self {
-        Self { ctx, kernel_size }
+        Self {
+            ctx,
+            kernel_size,
+            feather: 0.0,
+        }
+    }
+
+    /// Fades the blur out over the outer `feather` (0.0 to 1.0) of the
+    /// ellipse's radius, like `CpuEllipticalBlurrer::with_feather`.
+    pub fn with_feather(mut self, feather: f32) -> Self {
+        self.feather = feather.clamp(0.0, 1.0);
+        self
     }
 
     pub fn with_default_kernel(ctx: Arc<GpuContext>) -> Self {
@@ -44,7 +59,8 @@ impl FrameBlurrer for GpuEllipticalBlurrer {
         let data = frame.data_mut();
 
         let mut descriptors: Vec<RoiDescriptor> = Vec::with_capacity(regions.len());
-        let mut region_info: Vec<(usize, usize, usize, usize)> = Vec::with_capacity(regions.len());
+        let mut region_info: Vec<(RoiRect, &Region)> = Vec::with_capacity(regions.len());
+        let mask_on_gpu = !self.ctx.is_deterministic();
 
         for r in regions {
             let rx = r.x.max(0) as usize;
@@ -68,15 +84,37 @@ impl FrameBlurrer for GpuEllipticalBlurrer {
                 ellipse_cy: ecy as f32,
                 ellipse_a: semi_a as f32,
                 ellipse_b: semi_b as f32,
-                use_ellipse: true,
+                use_ellipse: mask_on_gpu,
+                ellipse_feather: self.feather,
             });
-            region_info.push((rx, ry, rw, rh));
+            let rect = RoiRect {
+                x: rx,
+                y: ry,
+                w: rw,
+                h: rh,
+            };
+            region_info.push((rect, r));
         }
 
         let results = self.ctx.blur_rois(&descriptors);
 
-        for (result, &(rx, ry, rw, rh)) in results.iter().zip(region_info.iter()) {
-            unpack_roi(data, result, fw, channels, rx, ry, rw, rh);
+        let mut roi = Vec::new();
+        for (result, &(rect, region)) in results.iter().zip(region_info.iter()) {
+            if mask_on_gpu {
+                unpack_roi(data, result, fw, channels, rect.x, rect.y, rect.w, rect.h);
+            } else {
+                roi.resize(rect.w * rect.h * channels, 0);
+                unpack_roi(&mut roi, result, rect.w, channels, 0, 0, rect.w, rect.h);
+                gaussian::composite_ellipse_feathered(
+                    data,
+                    &roi,
+                    fw,
+                    channels,
+                    rect,
+                    region,
+                    self.feather as f64,
+                );
+            }
         }
 
         Ok(())
@@ -86,6 +124,7 @@ impl FrameBlurrer for GpuEllipticalBlurrer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::blurring::infrastructure::cpu_elliptical_blurrer::CpuEllipticalBlurrer;
 
     fn make_frame(width: u32, height: u32, value: u8) -> Frame {
         let data = vec![value; (width * height * 3) as usize];
@@ -110,6 +149,10 @@ mod tests {
         GpuContext::new().map(Arc::new)
     }
 
+    fn try_deterministic_context() -> Option<Arc<GpuContext>> {
+        GpuContext::new().map(|ctx| Arc::new(ctx.with_deterministic(true)))
+    }
+
     #[test]
     fn test_no_regions_frame_unchanged() {
         let ctx = match try_gpu_context() {
@@ -222,4 +265,43 @@ mod tests {
         let blurrer = GpuEllipticalBlurrer::new(ctx, 5);
         blurrer.blur(&mut frame, &[region(40, 40, 30, 30)]).unwrap();
     }
+
+    #[test]
+    fn test_deterministic_mode_keeps_corners_outside_ellipse() {
+        let Some(ctx) = try_deterministic_context() else {
+            return;
+        };
+        let mut frame = make_frame(50, 50, 0);
+        frame.data_mut()[(20 * 50 + 20) * 3] = 255;
+        frame.data_mut()[0] = 90;
+        let blurrer = GpuEllipticalBlurrer::new(ctx, 5);
+        blurrer.blur(&mut frame, &[region(0, 0, 40, 40)]).unwrap();
+        assert_eq!(frame.data()[0], 90);
+        assert!(frame.data()[(20 * 50 + 20) * 3] < 255);
+    }
+
+    #[test]
+    fn test_feather_matches_cpu() {
+        let Some(ctx) = try_gpu_context() else {
+            return;
+        };
+        let mut gpu = make_frame(40, 40, 0);
+        for (i, value) in gpu.data_mut().iter_mut().enumerate() {
+            *value = (i * 37 % 251) as u8;
+        }
+        let mut cpu = gpu.clone();
+        let r = region(0, 0, 40, 40);
+        GpuEllipticalBlurrer::new(ctx, 9)
+            .with_feather(0.4)
+            .blur(&mut gpu, &[r.clone()])
+            .unwrap();
+        CpuEllipticalBlurrer::exact(9)
+            .with_feather(0.4)
+            .blur(&mut cpu, &[r])
+            .unwrap();
+        // The float path truncates, so it may run a level or two darker.
+        for (g, c) in gpu.data().iter().zip(cpu.data()) {
+            assert!((*g as i32 - *c as i32).abs() <= 3);
+        }
+    }
 }
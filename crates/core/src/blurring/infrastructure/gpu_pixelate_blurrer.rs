@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
+use crate::shared::constants::PIXELATE_BLOCKS;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+use super::gaussian::{self, RoiRect};
+use super::gpu_context::{pack_roi, unpack_roi, GpuContext, RoiDescriptor};
+use super::pixelate;
+
+/// GPU mosaic anonymizer using a wgpu compute shader.
+///
+/// Block averages are computed on the GPU in one batched dispatch. The
+/// ellipse mask, in elliptical mode, is applied on the CPU, so the output
+/// matches `CpuPixelateBlurrer` exactly on every adapter.
+pub struct GpuPixelateBlurrer {
+    ctx: Arc<GpuContext>,
+    blocks_across: usize,
+    elliptical: bool,
+}
+
+impl GpuPixelateBlurrer {
+    pub fn new(ctx: Arc<GpuContext>, blocks_across: usize, elliptical: bool) -> Self {
+        Self {
+            ctx,
+            blocks_across,
+            elliptical,
+        }
+    }
+
+    pub fn with_default_blocks(ctx: Arc<GpuContext>, elliptical: bool) -> Self {
+        Self::new(ctx, PIXELATE_BLOCKS, elliptical)
+    }
+}
+
+impl FrameBlurrer for GpuPixelateBlurrer {
+    fn blur(
+        &self,
+        frame: &mut Frame,
+        regions: &[Region],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if regions.is_empty() {
+            return Ok(());
+        }
+
+        let fw = frame.width() as usize;
+        let fh = frame.height() as usize;
+        let channels = frame.channels() as usize;
+        let data = frame.data_mut();
+
+        let mut descriptors: Vec<RoiDescriptor> = Vec::with_capacity(regions.len());
+        let mut region_info: Vec<(RoiRect, &Region)> = Vec::with_capacity(regions.len());
+
+        for r in regions {
+            let rx = r.x.max(0) as usize;
+            let ry = r.y.max(0) as usize;
+            let rw = (r.width.max(0) as usize).min(fw.saturating_sub(rx));
+            let rh = (r.height.max(0) as usize).min(fh.saturating_sub(ry));
+
+            if rw == 0 || rh == 0 {
+                continue;
+            }
+
+            descriptors.push(RoiDescriptor {
+                pixels: pack_roi(data, fw, channels, rx, ry, rw, rh),
+                width: rw as u32,
+                height: rh as u32,
+                kernel_size: pixelate::block_size(r, self.blocks_across) as u32,
+                ellipse_cx: 0.0,
+                ellipse_cy: 0.0,
+                ellipse_a: 0.0,
+                ellipse_b: 0.0,
+                use_ellipse: false,
+                ellipse_feather: 0.0,
+            });
+            let rect = RoiRect {
+                x: rx,
+                y: ry,
+                w: rw,
+                h: rh,
+            };
+            region_info.push((rect, r));
+        }
+
+        let results = self.ctx.pixelate_rois(&descriptors);
+
+        let mut roi = Vec::new();
+        for (result, &(rect, region)) in results.iter().zip(region_info.iter()) {
+            if self.elliptical {
+                roi.resize(rect.w * rect.h * channels, 0);
+                unpack_roi(&mut roi, result, rect.w, channels, 0, 0, rect.w, rect.h);
+                gaussian::composite_ellipse(data, &roi, fw, channels, rect, region);
+            } else {
+                unpack_roi(data, result, fw, channels, rect.x, rect.y, rect.w, rect.h);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blurring::infrastructure::cpu_pixelate_blurrer::CpuPixelateBlurrer;
+
+    fn region(x: i32, y: i32, w: i32, h: i32) -> Region {
+        Region {
+            x,
+            y,
+            width: w,
+            height: h,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    fn noise_frame(width: u32, height: u32) -> Frame {
+        let data = (0..width * height * 3)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8)
+            .collect();
+        Frame::new(data, width, height, 3, 0)
+    }
+
+    fn try_gpu_context() -> Option<Arc<GpuContext>> {
+        GpuContext::new().map(Arc::new)
+    }
+
+    #[test]
+    fn test_no_regions_frame_unchanged() {
+        let Some(ctx) = try_gpu_context() else {
+            return;
+        };
+        let mut frame = noise_frame(50, 50);
+        let original = frame.data().to_vec();
+        GpuPixelateBlurrer::new(ctx, 8, true)
+            .blur(&mut frame, &[])
+            .unwrap();
+        assert_eq!(frame.data(), &original[..]);
+    }
+
+    #[test]
+    fn test_matches_cpu_exactly() {
+        let Some(ctx) = try_gpu_context() else {
+            return;
+        };
+        let regions = [region(3, 5, 37, 29), region(40, 40, 30, 30)];
+        for elliptical in [false, true] {
+            let mut gpu = noise_frame(64, 64);
+            let mut cpu = gpu.clone();
+            GpuPixelateBlurrer::new(ctx.clone(), 6, elliptical)
+                .blur(&mut gpu, &regions)
+                .unwrap();
+            CpuPixelateBlurrer::new(6, elliptical)
+                .blur(&mut cpu, &regions)
+                .unwrap();
+            assert_eq!(gpu.data(), cpu.data(), "elliptical={elliptical}");
+        }
+    }
+}
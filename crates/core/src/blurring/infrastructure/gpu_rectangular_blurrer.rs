@@ -65,6 +65,7 @@ impl FrameBlurrer for GpuRectangularBlurrer {
                 ellipse_a: 0.0,
                 ellipse_b: 0.0,
                 use_ellipse: false,
+                ellipse_feather: 0.0,
             });
             region_info.push((rx, ry, rw, rh));
         }
@@ -0,0 +1,241 @@
+use std::cell::RefCell;
+use std::error::Error;
+use std::path::Path;
+use std::sync::Arc;
+
+use image::RgbaImage;
+
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+use super::gaussian::{self, RoiRect};
+
+/// Covers each region with a user-supplied image, such as an emoji or
+/// sticker, stretched to the region.
+///
+/// The image is scaled to the region's unclamped size, so it slides off
+/// frame edges instead of squashing. Transparent parts of the image show a
+/// flat backing color (black by default) rather than the face, so the
+/// region is always fully covered. Elliptical mode covers only the
+/// inscribed ellipse.
+pub struct ImageOverlayBlurrer {
+    image: Arc<RgbaImage>,
+    backing: [u8; 3],
+    elliptical: bool,
+    roi_buf: RefCell<Vec<u8>>,
+}
+
+impl ImageOverlayBlurrer {
+    pub fn new(image: Arc<RgbaImage>, elliptical: bool) -> Self {
+        Self {
+            image,
+            backing: [0, 0, 0],
+            elliptical,
+            roi_buf: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Shows `color` (RGB) through transparent parts of the image instead
+    /// of black.
+    pub fn with_backing(mut self, color: [u8; 3]) -> Self {
+        self.backing = color;
+        self
+    }
+
+    /// Reads an overlay image from a PNG or any other format `image` reads.
+    pub fn load_image(path: &Path) -> Result<Arc<RgbaImage>, Box<dyn Error>> {
+        let image = image::open(path)
+            .map_err(|e| format!("Could not read overlay image {}: {e}", path.display()))?
+            .to_rgba8();
+        if image.width() == 0 || image.height() == 0 {
+            return Err(format!("Overlay image {} is empty", path.display()).into());
+        }
+        Ok(Arc::new(image))
+    }
+
+    /// Fills `roi` (the visible part of `region`, at `rect`) with the image
+    /// over the backing color, sampled bilinearly.
+    fn render(&self, roi: &mut Vec<u8>, channels: usize, rect: RoiRect, region: &Region) {
+        let (iw, ih) = (self.image.width() as usize, self.image.height() as usize);
+        let full_w = region.full_width.unwrap_or(region.width).max(1) as f32;
+        let full_h = region.full_height.unwrap_or(region.height).max(1) as f32;
+        let left = region.unclamped_x.unwrap_or(region.x) as f32;
+        let top = region.unclamped_y.unwrap_or(region.y) as f32;
+        let scale_x = iw as f32 / full_w;
+        let scale_y = ih as f32 / full_h;
+
+        roi.resize(rect.w * rect.h * channels, 0);
+        for row in 0..rect.h {
+            let v = ((rect.y + row) as f32 - top + 0.5) * scale_y - 0.5;
+            for col in 0..rect.w {
+                let u = ((rect.x + col) as f32 - left + 0.5) * scale_x - 0.5;
+                let [r, g, b, a] = sample_bilinear(&self.image, iw, ih, u, v);
+                let offset = (row * rect.w + col) * channels;
+                for (c, value) in [r, g, b].into_iter().enumerate().take(channels) {
+                    let back = self.backing[c] as f32;
+                    roi[offset + c] = (value * a + back * (1.0 - a)).round() as u8;
+                }
+                roi[offset + 3.min(channels)..offset + channels].fill(255);
+            }
+        }
+    }
+}
+
+/// RGB in 0..=255 and alpha in 0..=1 at `(u, v)`, clamped to the edges.
+fn sample_bilinear(image: &RgbaImage, iw: usize, ih: usize, u: f32, v: f32) -> [f32; 4] {
+    let u = u.clamp(0.0, (iw - 1) as f32);
+    let v = v.clamp(0.0, (ih - 1) as f32);
+    let (x0, y0) = (u.floor() as u32, v.floor() as u32);
+    let x1 = (x0 + 1).min(iw as u32 - 1);
+    let y1 = (y0 + 1).min(ih as u32 - 1);
+    let (fx, fy) = (u - x0 as f32, v - y0 as f32);
+
+    let mut out = [0.0; 4];
+    for (c, value) in out.iter_mut().enumerate() {
+        let at = |x: u32, y: u32| image.get_pixel(x, y).0[c] as f32;
+        let top = at(x0, y0) * (1.0 - fx) + at(x1, y0) * fx;
+        let bottom = at(x0, y1) * (1.0 - fx) + at(x1, y1) * fx;
+        *value = top * (1.0 - fy) + bottom * fy;
+    }
+    out[3] /= 255.0;
+    out
+}
+
+impl FrameBlurrer for ImageOverlayBlurrer {
+    fn blur(
+        &self,
+        frame: &mut Frame,
+        regions: &[Region],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let fw = frame.width() as usize;
+        let fh = frame.height() as usize;
+        let channels = frame.channels() as usize;
+        let data = frame.data_mut();
+
+        for r in regions {
+            let rx = r.x.max(0) as usize;
+            let ry = r.y.max(0) as usize;
+            let rw = (r.width.max(0) as usize).min(fw.saturating_sub(rx));
+            let rh = (r.height.max(0) as usize).min(fh.saturating_sub(ry));
+
+            if rw == 0 || rh == 0 {
+                continue;
+            }
+
+            let rect = RoiRect {
+                x: rx,
+                y: ry,
+                w: rw,
+                h: rh,
+            };
+            let mut roi = self.roi_buf.borrow_mut();
+            self.render(&mut roi, channels, rect, r);
+
+            if self.elliptical {
+                gaussian::composite_ellipse(data, &roi, fw, channels, rect, r);
+            } else {
+                gaussian::write_roi_back(data, &roi, fw, channels, rect);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn make_frame(width: u32, height: u32, value: u8) -> Frame {
+        let data = vec![value; (width * height * 3) as usize];
+        Frame::new(data, width, height, 3, 0)
+    }
+
+    fn region(x: i32, y: i32, w: i32, h: i32) -> Region {
+        Region {
+            x,
+            y,
+            width: w,
+            height: h,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    fn pixel(frame: &Frame, x: usize, y: usize) -> [u8; 3] {
+        let offset = (y * frame.width() as usize + x) * 3;
+        frame.data()[offset..offset + 3].try_into().unwrap()
+    }
+
+    /// Left half opaque red, right half fully transparent.
+    fn half_red() -> Arc<RgbaImage> {
+        Arc::new(RgbaImage::from_fn(4, 4, |x, _| {
+            if x < 2 {
+                Rgba([255, 0, 0, 255])
+            } else {
+                Rgba([0, 0, 255, 0])
+            }
+        }))
+    }
+
+    #[test]
+    fn test_image_is_stretched_over_region() {
+        let mut frame = make_frame(50, 50, 200);
+        ImageOverlayBlurrer::new(half_red(), false)
+            .blur(&mut frame, &[region(10, 10, 20, 20)])
+            .unwrap();
+
+        assert_eq!(pixel(&frame, 12, 20), [255, 0, 0]);
+        assert_eq!(pixel(&frame, 5, 5), [200, 200, 200]);
+    }
+
+    #[test]
+    fn test_transparent_pixels_show_backing_not_face() {
+        let mut frame = make_frame(50, 50, 200);
+        ImageOverlayBlurrer::new(half_red(), false)
+            .with_backing([0, 80, 0])
+            .blur(&mut frame, &[region(10, 10, 20, 20)])
+            .unwrap();
+
+        assert_eq!(pixel(&frame, 28, 20), [0, 80, 0]);
+    }
+
+    #[test]
+    fn test_image_slides_off_frame_edge() {
+        let mut frame = make_frame(50, 50, 200);
+        // Only the right (transparent) half of the face is on screen.
+        let mut r = region(0, 10, 10, 20);
+        r.full_width = Some(20);
+        r.unclamped_x = Some(-10);
+        ImageOverlayBlurrer::new(half_red(), false)
+            .blur(&mut frame, &[r])
+            .unwrap();
+
+        assert_eq!(pixel(&frame, 8, 20), [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_ellipse_leaves_corners() {
+        let mut frame = make_frame(50, 50, 200);
+        ImageOverlayBlurrer::new(half_red(), true)
+            .blur(&mut frame, &[region(10, 10, 20, 20)])
+            .unwrap();
+
+        assert_eq!(pixel(&frame, 10, 10), [200, 200, 200]);
+        assert_eq!(pixel(&frame, 14, 20), [255, 0, 0]);
+    }
+
+    #[test]
+    fn test_load_image_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sticker.png");
+        half_red().save(&path).unwrap();
+        assert_eq!(ImageOverlayBlurrer::load_image(&path).unwrap(), half_red());
+        assert!(ImageOverlayBlurrer::load_image(&dir.path().join("missing.png")).is_err());
+    }
+}
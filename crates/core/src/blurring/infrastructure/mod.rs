@@ -1,7 +1,21 @@
 pub mod blurrer_factory;
 pub mod cpu_elliptical_blurrer;
+pub mod cpu_pixelate_blurrer;
 pub mod cpu_rectangular_blurrer;
+pub mod crowd_mode_blurrer;
+pub mod face_count_overlay;
 mod gaussian;
 pub mod gpu_context;
+mod gpu_convert;
 pub mod gpu_elliptical_blurrer;
+pub mod gpu_pixelate_blurrer;
 pub mod gpu_rectangular_blurrer;
+pub mod image_overlay_blurrer;
+pub mod overlap_compositing_blurrer;
+pub mod parity_checking_blurrer;
+mod pixelate;
+#[cfg(feature = "plugins")]
+pub mod plugin_blurrer;
+pub mod solid_fill_blurrer;
+pub mod static_redaction_blurrer;
+pub mod text_redaction_blurrer;
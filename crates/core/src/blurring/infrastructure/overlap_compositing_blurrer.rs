@@ -0,0 +1,201 @@
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
+use crate::blurring::domain::overlap_merger::{merge_overlapping, MergedRegion};
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// Decorator that blurs each group of overlapping regions as one.
+///
+/// Blurring overlapping faces one after another blurs the shared pixels
+/// twice, leaving a visible seam, and two ellipses that only just meet
+/// leave a sharp sliver between them. Regions that touch no other region
+/// go to the inner blurrer unchanged. Each group of touching regions is
+/// instead blurred once, over its bounding rectangle, from the original
+/// pixels, and written back through one merged mask (see
+/// `MergedRegion::covers`).
+pub struct OverlapCompositingBlurrer {
+    inner: Box<dyn FrameBlurrer>,
+    rectangular: Box<dyn FrameBlurrer>,
+    elliptical: bool,
+}
+
+impl OverlapCompositingBlurrer {
+    /// `rectangular` must anonymize the same way as `inner` but over whole
+    /// rectangles; it blurs each group's bounding rectangle. `elliptical`
+    /// is the shape `inner` draws.
+    pub fn new(
+        inner: Box<dyn FrameBlurrer>,
+        rectangular: Box<dyn FrameBlurrer>,
+        elliptical: bool,
+    ) -> Self {
+        Self {
+            inner,
+            rectangular,
+            elliptical,
+        }
+    }
+
+    fn blur_group(
+        &self,
+        frame: &mut Frame,
+        group: &MergedRegion,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let fw = frame.width() as i32;
+        let fh = frame.height() as i32;
+        let channels = frame.channels() as usize;
+        let b = &group.bounds;
+        let x0 = b.x.clamp(0, fw);
+        let y0 = b.y.clamp(0, fh);
+        let x1 = (b.x + b.width).clamp(0, fw);
+        let y1 = (b.y + b.height).clamp(0, fh);
+        if x1 <= x0 || y1 <= y0 {
+            return Ok(());
+        }
+        let (w, h) = ((x1 - x0) as usize, (y1 - y0) as usize);
+        let stride = fw as usize * channels;
+
+        let mut pixels = Vec::with_capacity(w * h * channels);
+        for row in y0 as usize..y1 as usize {
+            let start = row * stride + x0 as usize * channels;
+            pixels.extend_from_slice(&frame.data()[start..start + w * channels]);
+        }
+        let mut patch = Frame::new(pixels, w as u32, h as u32, channels as u8, frame.index());
+        let whole = Region {
+            x: 0,
+            y: 0,
+            width: w as i32,
+            height: h as i32,
+            ..b.clone()
+        };
+        self.rectangular.blur(&mut patch, &[whole])?;
+
+        let data = frame.data_mut();
+        for row in 0..h {
+            for col in 0..w {
+                let (x, y) = (x0 + col as i32, y0 + row as i32);
+                if group.covers(x, y, self.elliptical) {
+                    let dst = y as usize * stride + x as usize * channels;
+                    let src = (row * w + col) * channels;
+                    data[dst..dst + channels].copy_from_slice(&patch.data()[src..src + channels]);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FrameBlurrer for OverlapCompositingBlurrer {
+    fn blur(
+        &self,
+        frame: &mut Frame,
+        regions: &[Region],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (single, merged) = merge_overlapping(regions);
+        if merged.is_empty() {
+            return self.inner.blur(frame, regions);
+        }
+        for group in &merged {
+            self.blur_group(frame, group)?;
+        }
+        self.inner.blur(frame, &single)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blurring::infrastructure::cpu_elliptical_blurrer::CpuEllipticalBlurrer;
+    use crate::blurring::infrastructure::cpu_rectangular_blurrer::CpuRectangularBlurrer;
+
+    fn region(x: i32, y: i32, w: i32, h: i32) -> Region {
+        Region {
+            x,
+            y,
+            width: w,
+            height: h,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    fn noise_frame(width: u32, height: u32) -> Frame {
+        let data = (0..width * height * 3)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8)
+            .collect();
+        Frame::new(data, width, height, 3, 0)
+    }
+
+    fn elliptical() -> OverlapCompositingBlurrer {
+        OverlapCompositingBlurrer::new(
+            Box::new(CpuEllipticalBlurrer::exact(15)),
+            Box::new(CpuRectangularBlurrer::exact(15)),
+            true,
+        )
+    }
+
+    #[test]
+    fn test_separate_regions_match_inner() {
+        let regions = [region(2, 2, 20, 20), region(40, 30, 20, 20)];
+        let mut composited = noise_frame(64, 64);
+        let mut plain = composited.clone();
+        elliptical().blur(&mut composited, &regions).unwrap();
+        CpuEllipticalBlurrer::exact(15)
+            .blur(&mut plain, &regions)
+            .unwrap();
+        assert_eq!(composited.data(), plain.data());
+    }
+
+    #[test]
+    fn test_overlap_is_blurred_once() {
+        // Blurring the union once equals blurring its bounding rectangle,
+        // wherever the merged mask covers.
+        let regions = [region(4, 4, 30, 30), region(20, 10, 30, 30)];
+        let mut composited = noise_frame(64, 64);
+        let mut once = composited.clone();
+        OverlapCompositingBlurrer::new(
+            Box::new(CpuRectangularBlurrer::exact(15)),
+            Box::new(CpuRectangularBlurrer::exact(15)),
+            false,
+        )
+        .blur(&mut composited, &regions)
+        .unwrap();
+        CpuRectangularBlurrer::exact(15)
+            .blur(&mut once, &[region(4, 4, 46, 36)])
+            .unwrap();
+
+        let at = |f: &Frame, x: usize, y: usize| f.data()[(y * 64 + x) * 3];
+        assert_eq!(at(&composited, 25, 20), at(&once, 25, 20));
+        // Outside both rectangles but inside their bounds: untouched.
+        let original = noise_frame(64, 64);
+        assert_eq!(at(&composited, 45, 5), at(&original, 45, 5));
+    }
+
+    #[test]
+    fn test_crease_between_touching_ellipses_is_covered() {
+        let regions = [region(0, 0, 20, 20), region(20, 0, 20, 20)];
+        let original = noise_frame(64, 64);
+        let mut composited = original.clone();
+        let mut plain = original.clone();
+        elliptical().blur(&mut composited, &regions).unwrap();
+        CpuEllipticalBlurrer::exact(15)
+            .blur(&mut plain, &regions)
+            .unwrap();
+
+        let notch = (7 * 64 + 20) * 3;
+        assert_eq!(plain.data()[notch], original.data()[notch]);
+        assert_ne!(composited.data()[notch], original.data()[notch]);
+    }
+
+    #[test]
+    fn test_region_beyond_frame_does_not_panic() {
+        let mut frame = noise_frame(64, 64);
+        elliptical()
+            .blur(
+                &mut frame,
+                &[region(50, 50, 30, 30), region(55, 40, 30, 30)],
+            )
+            .unwrap();
+    }
+}
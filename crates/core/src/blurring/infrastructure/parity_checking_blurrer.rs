@@ -0,0 +1,439 @@
+use std::cell::Cell;
+use std::sync::{Arc, Mutex};
+
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// Largest per-channel difference from the reference that still counts as
+/// a match. The float GPU path truncates after each pass, so it sits a
+/// level or two below the CPU's rounded result.
+pub const DEFAULT_PARITY_TOLERANCE: u8 = 3;
+
+/// Check one frame in this many by default; the reference is a
+/// full-resolution CPU blur and far slower than the GPU.
+pub const DEFAULT_PARITY_INTERVAL: usize = 30;
+
+/// Running totals of a [`ParityCheckingBlurrer`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParityReport {
+    pub regions_checked: usize,
+    pub divergent_regions: usize,
+    /// Largest per-channel difference seen in any checked region.
+    pub max_difference: u8,
+}
+
+impl ParityReport {
+    pub fn is_divergent(&self) -> bool {
+        self.divergent_regions > 0
+    }
+}
+
+/// Decorator that blurs a sample of frames with both the inner blurrer and
+/// a reference, and compares the results region by region.
+///
+/// Meant for catching GPU adapters and drivers whose float math strays from
+/// the CPU implementation. A region differs when any channel is more than
+/// the tolerance away from the reference. A frame with a differing region
+/// is written with the reference pixels, and the first one is logged with
+/// the backend's label. Regions overlapping another region are skipped,
+/// since backends may blur overlaps in a different order.
+pub struct ParityCheckingBlurrer {
+    inner: Box<dyn FrameBlurrer>,
+    reference: Box<dyn FrameBlurrer>,
+    label: String,
+    tolerance: u8,
+    interval: usize,
+    calls: Cell<usize>,
+    report: Arc<Mutex<ParityReport>>,
+}
+
+impl ParityCheckingBlurrer {
+    pub fn new(
+        inner: Box<dyn FrameBlurrer>,
+        reference: Box<dyn FrameBlurrer>,
+        label: impl Into<String>,
+    ) -> Self {
+        Self {
+            inner,
+            reference,
+            label: label.into(),
+            tolerance: DEFAULT_PARITY_TOLERANCE,
+            interval: DEFAULT_PARITY_INTERVAL,
+            calls: Cell::new(0),
+            report: Arc::new(Mutex::new(ParityReport::default())),
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Check every `interval`th call, starting with the first. Clamped to 1.
+    pub fn with_interval(mut self, interval: usize) -> Self {
+        self.interval = interval.max(1);
+        self
+    }
+
+    /// Shared handle to the totals, readable after the blurrer is moved
+    /// into a pipeline.
+    pub fn report(&self) -> Arc<Mutex<ParityReport>> {
+        self.report.clone()
+    }
+}
+
+impl FrameBlurrer for ParityCheckingBlurrer {
+    fn blur(
+        &self,
+        frame: &mut Frame,
+        regions: &[Region],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let call = self.calls.get();
+        self.calls.set(call + 1);
+        if regions.is_empty() || call % self.interval != 0 {
+            return self.inner.blur(frame, regions);
+        }
+
+        let mut expected = frame.clone();
+        self.reference.blur(&mut expected, regions)?;
+        self.inner.blur(frame, regions)?;
+
+        let mut report = self.report.lock().unwrap_or_else(|e| e.into_inner());
+        let mut worst = None;
+        for (i, region) in regions.iter().enumerate() {
+            if overlaps_another(i, regions) {
+                continue;
+            }
+            let Some(difference) = region_difference(frame, &expected, region) else {
+                continue;
+            };
+            report.regions_checked += 1;
+            report.max_difference = report.max_difference.max(difference);
+            if difference > self.tolerance {
+                report.divergent_regions += 1;
+                worst = worst.max(Some(difference));
+            }
+        }
+
+        if let Some(difference) = worst {
+            if report.divergent_regions == 1 {
+                log::warn!(
+                    "Blur on {} differs from the reference by {} levels in frame {} \
+                     (tolerance {}); using the reference for checked frames",
+                    self.label,
+                    difference,
+                    frame.index(),
+                    self.tolerance
+                );
+            }
+            frame.data_mut().copy_from_slice(expected.data());
+        }
+        Ok(())
+    }
+}
+
+fn overlaps_another(index: usize, regions: &[Region]) -> bool {
+    let a = &regions[index];
+    regions.iter().enumerate().any(|(j, b)| {
+        j != index
+            && a.x < b.x + b.width
+            && b.x < a.x + a.width
+            && a.y < b.y + b.height
+            && b.y < a.y + a.height
+    })
+}
+
+/// Largest per-channel difference inside the region's on-frame rectangle,
+/// or `None` when the region is entirely off-frame.
+fn region_difference(actual: &Frame, expected: &Frame, region: &Region) -> Option<u8> {
+    let fw = actual.width() as usize;
+    let fh = actual.height() as usize;
+    let channels = actual.channels() as usize;
+    let rx = region.x.max(0) as usize;
+    let ry = region.y.max(0) as usize;
+    let rw = (region.width.max(0) as usize).min(fw.saturating_sub(rx));
+    let rh = (region.height.max(0) as usize).min(fh.saturating_sub(ry));
+    if rw == 0 || rh == 0 {
+        return None;
+    }
+
+    let mut max = 0u8;
+    for row in ry..ry + rh {
+        let start = (row * fw + rx) * channels;
+        let end = start + rw * channels;
+        for (a, b) in actual.data()[start..end]
+            .iter()
+            .zip(&expected.data()[start..end])
+        {
+            max = max.max(a.abs_diff(*b));
+        }
+    }
+    Some(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blurring::infrastructure::cpu_elliptical_blurrer::CpuEllipticalBlurrer;
+    use crate::blurring::infrastructure::cpu_rectangular_blurrer::CpuRectangularBlurrer;
+    use crate::blurring::infrastructure::gpu_context::GpuContext;
+    use crate::blurring::infrastructure::gpu_elliptical_blurrer::GpuEllipticalBlurrer;
+    use crate::blurring::infrastructure::gpu_rectangular_blurrer::GpuRectangularBlurrer;
+    use rstest::rstest;
+
+    /// Leaves the frame untouched, standing in for a broken backend.
+    struct NoopBlurrer;
+
+    impl FrameBlurrer for NoopBlurrer {
+        fn blur(&self, _: &mut Frame, _: &[Region]) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+    }
+
+    /// Shifts every byte in the regions by a fixed amount.
+    struct OffsetBlurrer(u8);
+
+    impl FrameBlurrer for OffsetBlurrer {
+        fn blur(
+            &self,
+            frame: &mut Frame,
+            regions: &[Region],
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            CpuRectangularBlurrer::exact(5).blur(frame, regions)?;
+            let fw = frame.width() as usize;
+            let data = frame.data_mut();
+            for r in regions {
+                for y in r.y as usize..(r.y + r.height) as usize {
+                    for x in r.x as usize..(r.x + r.width) as usize {
+                        for c in 0..3 {
+                            let idx = (y * fw + x) * 3 + c;
+                            data[idx] = data[idx].saturating_add(self.0);
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn region(x: i32, y: i32, w: i32, h: i32) -> Region {
+        Region {
+            x,
+            y,
+            width: w,
+            height: h,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    /// High-contrast noise, which exposes rounding differences that a flat
+    /// or smooth image would hide.
+    fn noisy_frame(width: u32, height: u32, index: usize) -> Frame {
+        let data = (0..(width * height * 3) as usize)
+            .map(|i| (i * 37 % 251) as u8)
+            .collect();
+        Frame::new(data, width, height, 3, index)
+    }
+
+    fn checker(inner: Box<dyn FrameBlurrer>) -> ParityCheckingBlurrer {
+        ParityCheckingBlurrer::new(inner, Box::new(CpuRectangularBlurrer::exact(5)), "test")
+            .with_interval(1)
+    }
+
+    fn report_of(blurrer: &ParityCheckingBlurrer) -> ParityReport {
+        blurrer.report().lock().unwrap().clone()
+    }
+
+    #[test]
+    fn test_identical_backends_match() {
+        let blurrer = checker(Box::new(CpuRectangularBlurrer::exact(5)));
+        let mut frame = noisy_frame(60, 40, 0);
+        blurrer
+            .blur(&mut frame, &[region(5, 5, 20, 20), region(30, 10, 20, 20)])
+            .unwrap();
+        let report = report_of(&blurrer);
+        assert_eq!(report.regions_checked, 2);
+        assert_eq!(report.max_difference, 0);
+        assert!(!report.is_divergent());
+    }
+
+    #[test]
+    fn test_difference_within_tolerance_is_kept() {
+        let blurrer = checker(Box::new(OffsetBlurrer(2)));
+        let mut frame = noisy_frame(40, 40, 0);
+        let mut expected = frame.clone();
+        OffsetBlurrer(2)
+            .blur(&mut expected, &[region(5, 5, 20, 20)])
+            .unwrap();
+        blurrer.blur(&mut frame, &[region(5, 5, 20, 20)]).unwrap();
+
+        assert!(!report_of(&blurrer).is_divergent());
+        assert_eq!(frame.data(), expected.data());
+    }
+
+    #[test]
+    fn test_divergent_backend_is_flagged_and_replaced() {
+        let blurrer = checker(Box::new(NoopBlurrer));
+        let mut frame = noisy_frame(40, 40, 0);
+        let mut expected = frame.clone();
+        CpuRectangularBlurrer::exact(5)
+            .blur(&mut expected, &[region(5, 5, 20, 20)])
+            .unwrap();
+        blurrer.blur(&mut frame, &[region(5, 5, 20, 20)]).unwrap();
+
+        let report = report_of(&blurrer);
+        assert_eq!(report.divergent_regions, 1);
+        assert!(report.max_difference > DEFAULT_PARITY_TOLERANCE);
+        assert_eq!(frame.data(), expected.data());
+    }
+
+    #[test]
+    fn test_tolerance_is_configurable() {
+        let blurrer = checker(Box::new(OffsetBlurrer(2))).with_tolerance(1);
+        let mut frame = noisy_frame(40, 40, 0);
+        blurrer.blur(&mut frame, &[region(5, 5, 20, 20)]).unwrap();
+        assert!(report_of(&blurrer).is_divergent());
+    }
+
+    #[test]
+    fn test_only_every_interval_frame_is_checked() {
+        let blurrer = checker(Box::new(NoopBlurrer)).with_interval(3);
+        for i in 0..7 {
+            let mut frame = noisy_frame(40, 40, i);
+            blurrer.blur(&mut frame, &[region(5, 5, 20, 20)]).unwrap();
+        }
+        // Calls 0, 3, and 6.
+        assert_eq!(report_of(&blurrer).regions_checked, 3);
+    }
+
+    #[test]
+    fn test_unchecked_frames_use_inner_output() {
+        let blurrer = checker(Box::new(NoopBlurrer)).with_interval(2);
+        let mut first = noisy_frame(40, 40, 0);
+        blurrer.blur(&mut first, &[region(5, 5, 20, 20)]).unwrap();
+        let mut second = noisy_frame(40, 40, 1);
+        let original = second.clone();
+        blurrer.blur(&mut second, &[region(5, 5, 20, 20)]).unwrap();
+        assert_eq!(second.data(), original.data());
+    }
+
+    #[test]
+    fn test_overlapping_and_off_frame_regions_are_skipped() {
+        let blurrer = checker(Box::new(CpuRectangularBlurrer::exact(5)));
+        let mut frame = noisy_frame(40, 40, 0);
+        blurrer
+            .blur(
+                &mut frame,
+                &[
+                    region(0, 0, 20, 20),
+                    region(10, 10, 20, 20),
+                    region(50, 50, 10, 10),
+                ],
+            )
+            .unwrap();
+        assert_eq!(report_of(&blurrer).regions_checked, 0);
+    }
+
+    #[test]
+    fn test_frames_without_regions_still_advance_the_interval() {
+        let blurrer = checker(Box::new(NoopBlurrer)).with_interval(2);
+        let mut frame = noisy_frame(40, 40, 0);
+        blurrer.blur(&mut frame, &[]).unwrap();
+        blurrer.blur(&mut frame, &[region(5, 5, 20, 20)]).unwrap();
+        // The empty call still advanced the interval, so this one is unchecked.
+        assert_eq!(report_of(&blurrer).regions_checked, 0);
+    }
+
+    fn gpu_blurrer(ctx: Arc<GpuContext>, ellipse: bool, kernel: usize) -> Box<dyn FrameBlurrer> {
+        if ellipse {
+            Box::new(GpuEllipticalBlurrer::new(ctx, kernel as u32))
+        } else {
+            Box::new(GpuRectangularBlurrer::new(ctx, kernel as u32))
+        }
+    }
+
+    fn cpu_reference(ellipse: bool, kernel: usize) -> Box<dyn FrameBlurrer> {
+        if ellipse {
+            Box::new(CpuEllipticalBlurrer::exact(kernel))
+        } else {
+            Box::new(CpuRectangularBlurrer::exact(kernel))
+        }
+    }
+
+    fn assert_gpu_matches_cpu(ctx: Arc<GpuContext>, ellipse: bool, kernel: usize) {
+        let label = ctx.adapter_name().to_string();
+        let blurrer = ParityCheckingBlurrer::new(
+            gpu_blurrer(ctx, ellipse, kernel),
+            cpu_reference(ellipse, kernel),
+            label.clone(),
+        )
+        .with_interval(1);
+        // The last region runs off the right and bottom edges.
+        let regions = [
+            region(4, 6, 37, 29),
+            region(45, 2, 24, 40),
+            region(72, 20, 30, 35),
+        ];
+        let mut frame = noisy_frame(100, 50, 0);
+        blurrer.blur(&mut frame, &regions).unwrap();
+
+        let report = report_of(&blurrer);
+        assert_eq!(report.regions_checked, 3);
+        assert!(
+            !report.is_divergent(),
+            "{label}: GPU differs from CPU by {} levels",
+            report.max_difference
+        );
+    }
+
+    #[rstest]
+    #[case(false, 3)]
+    #[case(false, 15)]
+    #[case(false, 51)]
+    #[case(true, 3)]
+    #[case(true, 15)]
+    #[case(true, 51)]
+    fn test_gpu_matches_cpu_within_tolerance(#[case] ellipse: bool, #[case] kernel: usize) {
+        let Some(ctx) = GpuContext::new() else {
+            return;
+        };
+        assert_gpu_matches_cpu(Arc::new(ctx), ellipse, kernel);
+    }
+
+    #[rstest]
+    #[case(false, 15)]
+    #[case(true, 15)]
+    #[case(true, 51)]
+    fn test_deterministic_gpu_matches_cpu_within_tolerance(
+        #[case] ellipse: bool,
+        #[case] kernel: usize,
+    ) {
+        let Some(ctx) = GpuContext::new() else {
+            return;
+        };
+        assert_gpu_matches_cpu(Arc::new(ctx.with_deterministic(true)), ellipse, kernel);
+    }
+
+    #[rstest]
+    #[case(false)]
+    #[case(true)]
+    fn test_deterministic_gpu_repeats_exactly(#[case] ellipse: bool) {
+        let Some(ctx) = GpuContext::new() else {
+            return;
+        };
+        let blurrer = gpu_blurrer(Arc::new(ctx.with_deterministic(true)), ellipse, 21);
+        let regions = [region(4, 6, 37, 29), region(45, 2, 24, 40)];
+        let mut first = noisy_frame(90, 50, 0);
+        blurrer.blur(&mut first, &regions).unwrap();
+        for _ in 0..3 {
+            let mut again = noisy_frame(90, 50, 0);
+            blurrer.blur(&mut again, &regions).unwrap();
+            assert_eq!(again.data(), first.data());
+        }
+    }
+}
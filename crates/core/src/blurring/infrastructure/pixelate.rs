@@ -0,0 +1,112 @@
+use crate::shared::region::Region;
+
+/// Side of a mosaic block for `region`, so the face is about
+/// `blocks_across` blocks wide whatever its size on screen. Uses the
+/// unclamped size, so a face sliding off the frame edge keeps its block size.
+pub fn block_size(region: &Region, blocks_across: usize) -> usize {
+    let side = region
+        .full_width
+        .unwrap_or(region.width)
+        .max(region.full_height.unwrap_or(region.height))
+        .max(0) as usize;
+    side.div_ceil(blocks_across.max(1)).max(2)
+}
+
+/// Replaces each `block` × `block` tile of an ROI buffer with its mean
+/// color, rounded to nearest. Tiles are aligned to the ROI's top-left
+/// corner; those at the right and bottom edges may be smaller.
+///
+/// Integer arithmetic only, so the GPU shader reproduces it exactly.
+pub fn pixelate_roi_in_place(roi: &mut [u8], rw: usize, rh: usize, channels: usize, block: usize) {
+    let block = block.max(1);
+    let mut sums = vec![0u32; channels];
+    for by in (0..rh).step_by(block) {
+        let bh = block.min(rh - by);
+        for bx in (0..rw).step_by(block) {
+            let bw = block.min(rw - bx);
+            let count = (bw * bh) as u32;
+
+            sums.fill(0);
+            for row in by..by + bh {
+                let start = (row * rw + bx) * channels;
+                for pixel in roi[start..start + bw * channels].chunks_exact(channels) {
+                    for (sum, &value) in sums.iter_mut().zip(pixel) {
+                        *sum += value as u32;
+                    }
+                }
+            }
+            let mean: Vec<u8> = sums
+                .iter()
+                .map(|sum| ((sum + count / 2) / count) as u8)
+                .collect();
+
+            for row in by..by + bh {
+                let start = (row * rw + bx) * channels;
+                for pixel in roi[start..start + bw * channels].chunks_exact_mut(channels) {
+                    pixel.copy_from_slice(&mean);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(w: i32, h: i32) -> Region {
+        Region {
+            x: 0,
+            y: 0,
+            width: w,
+            height: h,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    #[test]
+    fn test_block_size_follows_face_size() {
+        assert_eq!(block_size(&region(80, 100), 10), 10);
+        assert_eq!(block_size(&region(400, 300), 10), 40);
+    }
+
+    #[test]
+    fn test_block_size_uses_unclamped_size() {
+        let mut r = region(20, 100);
+        r.full_width = Some(100);
+        assert_eq!(block_size(&r, 10), 10);
+    }
+
+    #[test]
+    fn test_block_size_has_a_floor() {
+        assert_eq!(block_size(&region(4, 4), 10), 2);
+        assert_eq!(block_size(&region(0, 0), 10), 2);
+    }
+
+    #[test]
+    fn test_tile_becomes_its_rounded_mean() {
+        // One 2x2 tile, single channel: 0, 1, 1, 1 -> mean 0.75 -> 1.
+        let mut roi = vec![0, 1, 1, 1];
+        pixelate_roi_in_place(&mut roi, 2, 2, 1, 2);
+        assert_eq!(roi, vec![1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_edge_tiles_are_averaged_on_their_own() {
+        // 3x1 with 2-wide blocks: [10, 20] and [90].
+        let mut roi = vec![10, 20, 90];
+        pixelate_roi_in_place(&mut roi, 3, 1, 1, 2);
+        assert_eq!(roi, vec![15, 15, 90]);
+    }
+
+    #[test]
+    fn test_channels_are_averaged_separately() {
+        let mut roi = vec![0, 100, 200, 100, 100, 0];
+        pixelate_roi_in_place(&mut roi, 2, 1, 3, 2);
+        assert_eq!(roi, vec![50, 100, 100, 50, 100, 100]);
+    }
+}
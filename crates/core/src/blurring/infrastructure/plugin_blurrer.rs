@@ -0,0 +1,489 @@
+use std::ffi::{c_char, c_void, CString, NulError};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use libloading::Library;
+use thiserror::Error;
+
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// Version of the effect plugin ABI this build speaks. A plugin reports the
+/// version it was built against from [`PLUGIN_ABI_VERSION_SYMBOL`], and
+/// any other is refused before the rest of the plugin is called.
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Symbol every effect plugin exports, with the signature of
+/// [`AbiVersionFn`].
+pub const PLUGIN_ABI_VERSION_SYMBOL: &str = "faceguard_effect_abi_version";
+
+/// Symbol every effect plugin exports, with the signature of
+/// [`CreateEffectFn`].
+pub const PLUGIN_ENTRY_POINT: &str = "faceguard_effect_create";
+
+/// A region as a plugin sees it: the visible rectangle, clipped to the
+/// frame, and the full one, which may extend past the frame's edges.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PluginRegion {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub full_x: i32,
+    pub full_y: i32,
+    pub full_width: i32,
+    pub full_height: i32,
+    /// The tracker's ID, or -1 for an untracked region.
+    pub track_id: i64,
+}
+
+impl From<&Region> for PluginRegion {
+    fn from(region: &Region) -> Self {
+        Self {
+            x: region.x,
+            y: region.y,
+            width: region.width,
+            height: region.height,
+            full_x: region.unclamped_x.unwrap_or(region.x),
+            full_y: region.unclamped_y.unwrap_or(region.y),
+            full_width: region.full_width.unwrap_or(region.width),
+            full_height: region.full_height.unwrap_or(region.height),
+            track_id: region.track_id.map_or(-1, i64::from),
+        }
+    }
+}
+
+/// Covers `count` regions in `pixels`, packed rows of `width * height`
+/// pixels with `channels` bytes each (RGB). Returns 0 on success.
+pub type BlurEffectFn = unsafe extern "C" fn(
+    state: *mut c_void,
+    pixels: *mut u8,
+    width: u32,
+    height: u32,
+    channels: u32,
+    regions: *const PluginRegion,
+    count: usize,
+) -> i32;
+
+/// Frees an effect's state. Called once, after its last `blur`.
+pub type DestroyEffectFn = unsafe extern "C" fn(state: *mut c_void);
+
+/// One effect instance, filled in by the plugin's entry point.
+#[repr(C)]
+pub struct PluginEffect {
+    /// Passed back to `blur` and `destroy`. Only ever used from one thread
+    /// at a time, though not always the same one.
+    pub state: *mut c_void,
+    pub blur: Option<BlurEffectFn>,
+    pub destroy: Option<DestroyEffectFn>,
+}
+
+/// Signature of [`PLUGIN_ABI_VERSION_SYMBOL`]: the [`PLUGIN_ABI_VERSION`]
+/// the plugin was built against. It must not depend on any other call.
+pub type AbiVersionFn = unsafe extern "C" fn() -> u32;
+
+/// Signature of [`PLUGIN_ENTRY_POINT`]: builds an effect from the
+/// configuration string (UTF-8, NUL-terminated, possibly empty) into
+/// `out`, covering only the inscribed ellipse when `elliptical`. Returns 0
+/// on success.
+pub type CreateEffectFn =
+    unsafe extern "C" fn(config: *const c_char, elliptical: bool, out: *mut PluginEffect) -> i32;
+
+#[derive(Error, Debug)]
+pub enum PluginError {
+    #[error("could not load effect plugin {path}: {source}")]
+    Load {
+        path: PathBuf,
+        #[source]
+        source: libloading::Error,
+    },
+    #[error("{path} is not a FaceGuard effect plugin (no {symbol}): {source}")]
+    MissingEntryPoint {
+        path: PathBuf,
+        symbol: &'static str,
+        #[source]
+        source: libloading::Error,
+    },
+    #[error("effect plugin configuration contains a NUL byte")]
+    InvalidConfig(#[from] NulError),
+    #[error("effect plugin {path} refused its configuration (code {code})")]
+    Create { path: PathBuf, code: i32 },
+    #[error("effect plugin {path} uses ABI version {found}, expected {expected}")]
+    AbiVersion {
+        path: PathBuf,
+        found: u32,
+        expected: u32,
+    },
+    #[error("effect plugin {path} has no blur function")]
+    MissingBlur { path: PathBuf },
+    #[error("effect plugin {path} failed on frame {frame} (code {code})")]
+    Blur {
+        path: PathBuf,
+        frame: usize,
+        code: i32,
+    },
+}
+
+/// A loaded effect plugin: a dynamic library exporting
+/// [`PLUGIN_ABI_VERSION_SYMBOL`] and [`PLUGIN_ENTRY_POINT`], and the
+/// configuration each of its effects is built with.
+///
+/// Loading runs the library's initialization code with the app's
+/// privileges, so only load plugins you trust.
+pub struct EffectPlugin {
+    path: PathBuf,
+    config: CString,
+    create: CreateEffectFn,
+    /// Keeps `create` and the effects' functions mapped. `None` for entry
+    /// points linked into the program.
+    _library: Option<Library>,
+}
+
+impl EffectPlugin {
+    /// Loads the library at `path`, checks its ABI version, and builds a
+    /// first effect from `config`, so a missing symbol, an ABI mismatch, or
+    /// a refused configuration shows up before any frame is processed.
+    pub fn load(path: &Path, config: &str) -> Result<Arc<Self>, PluginError> {
+        // SAFETY: loading runs the library's initializers; plugins are
+        // trusted code chosen by the user, as documented on the type.
+        let library = unsafe { Library::new(path) }.map_err(|source| PluginError::Load {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let missing = |symbol| {
+            move |source| PluginError::MissingEntryPoint {
+                path: path.to_path_buf(),
+                symbol,
+                source,
+            }
+        };
+        // SAFETY: both symbols are declared with these signatures by every
+        // version of the plugin ABI; only the version is called before it
+        // has been checked.
+        let abi_version =
+            unsafe { library.get::<AbiVersionFn>(PLUGIN_ABI_VERSION_SYMBOL.as_bytes()) }
+                .map(|symbol| *symbol)
+                .map_err(missing(PLUGIN_ABI_VERSION_SYMBOL))?;
+        let create = unsafe { library.get::<CreateEffectFn>(PLUGIN_ENTRY_POINT.as_bytes()) }
+            .map(|symbol| *symbol)
+            .map_err(missing(PLUGIN_ENTRY_POINT))?;
+        Self::from_entry_points(path, config, abi_version, create, Some(library))
+    }
+
+    fn from_entry_points(
+        path: &Path,
+        config: &str,
+        abi_version: AbiVersionFn,
+        create: CreateEffectFn,
+        library: Option<Library>,
+    ) -> Result<Arc<Self>, PluginError> {
+        // SAFETY: the version function takes nothing and only returns a
+        // number, whatever ABI the plugin speaks.
+        let found = unsafe { abi_version() };
+        if found != PLUGIN_ABI_VERSION {
+            return Err(PluginError::AbiVersion {
+                path: path.to_path_buf(),
+                found,
+                expected: PLUGIN_ABI_VERSION,
+            });
+        }
+        let plugin = Arc::new(Self {
+            path: path.to_path_buf(),
+            config: CString::new(config)?,
+            create,
+            _library: library,
+        });
+        plugin.instantiate(false)?;
+        Ok(plugin)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Builds a new effect, with state of its own.
+    pub fn instantiate(self: &Arc<Self>, elliptical: bool) -> Result<PluginBlurrer, PluginError> {
+        let mut effect = PluginEffect {
+            state: std::ptr::null_mut(),
+            blur: None,
+            destroy: None,
+        };
+        // SAFETY: `config` is NUL-terminated and outlives the call, and
+        // `effect` is a valid place for the plugin to fill in.
+        let code = unsafe { (self.create)(self.config.as_ptr(), elliptical, &mut effect) };
+        if code != 0 {
+            return Err(PluginError::Create {
+                path: self.path.clone(),
+                code,
+            });
+        }
+        // The ABI version was checked at load, so `destroy` is safe to call
+        // once the blurrer is dropped, even if it is refused below.
+        let blurrer = PluginBlurrer {
+            effect,
+            plugin: Arc::clone(self),
+        };
+        if blurrer.effect.blur.is_none() {
+            return Err(PluginError::MissingBlur {
+                path: self.path.clone(),
+            });
+        }
+        Ok(blurrer)
+    }
+}
+
+/// The effect as a [`FrameBlurrer`]. The plugin built one when it was
+/// loaded, so this rarely fails; when it does, the blurrer fails the job
+/// on its first frame rather than leave faces uncovered.
+pub fn plugin_anonymizer(plugin: &Arc<EffectPlugin>, elliptical: bool) -> Box<dyn FrameBlurrer> {
+    match plugin.instantiate(elliptical) {
+        Ok(blurrer) => Box::new(blurrer),
+        Err(e) => Box::new(UnavailableEffect(e.to_string())),
+    }
+}
+
+/// Stands in for an effect the plugin could not build.
+struct UnavailableEffect(String);
+
+impl FrameBlurrer for UnavailableEffect {
+    fn blur(
+        &self,
+        _frame: &mut Frame,
+        _regions: &[Region],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Err(self.0.clone().into())
+    }
+}
+
+impl fmt::Debug for EffectPlugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EffectPlugin")
+            .field("path", &self.path)
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Two plugins are equal when they load the same file with the same
+/// configuration, and so make the same effect.
+impl PartialEq for EffectPlugin {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path && self.config == other.config
+    }
+}
+
+impl Eq for EffectPlugin {}
+
+/// An effect from an [`EffectPlugin`], covering regions however the plugin
+/// likes. It gets every region of a frame at once, overlapping or not, and
+/// is trusted to cover each one: nothing checks its output.
+pub struct PluginBlurrer {
+    effect: PluginEffect,
+    plugin: Arc<EffectPlugin>,
+}
+
+// SAFETY: the plugin ABI requires effect state to be usable from any one
+// thread at a time. `FrameBlurrer` is not `Sync`, so calls never overlap.
+unsafe impl Send for PluginBlurrer {}
+
+impl FrameBlurrer for PluginBlurrer {
+    fn blur(
+        &self,
+        frame: &mut Frame,
+        regions: &[Region],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if regions.is_empty() {
+            return Ok(());
+        }
+        let Some(blur) = self.effect.blur else {
+            return Ok(());
+        };
+        let regions: Vec<PluginRegion> = regions.iter().map(PluginRegion::from).collect();
+        let (width, height, channels) = (frame.width(), frame.height(), frame.channels());
+        let index = frame.index();
+        // SAFETY: the pixel buffer holds `width * height * channels` bytes
+        // and the region slice `regions.len()` entries, both alive for the
+        // call; `state` came from the same plugin's entry point.
+        let code = unsafe {
+            blur(
+                self.effect.state,
+                frame.data_mut().as_mut_ptr(),
+                width,
+                height,
+                channels as u32,
+                regions.as_ptr(),
+                regions.len(),
+            )
+        };
+        if code != 0 {
+            return Err(PluginError::Blur {
+                path: self.plugin.path.clone(),
+                frame: index,
+                code,
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+impl Drop for PluginBlurrer {
+    fn drop(&mut self) {
+        if let Some(destroy) = self.effect.destroy {
+            // SAFETY: called once, after the last `blur`, while `plugin`
+            // still keeps the library loaded.
+            unsafe { destroy(self.effect.state) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    unsafe extern "C" fn current_abi() -> u32 {
+        PLUGIN_ABI_VERSION
+    }
+
+    unsafe extern "C" fn future_abi() -> u32 {
+        PLUGIN_ABI_VERSION + 1
+    }
+
+    /// Fills every region's visible rectangle with the gray level given
+    /// as its configuration.
+    unsafe extern "C" fn create_fill(
+        config: *const c_char,
+        _elliptical: bool,
+        out: *mut PluginEffect,
+    ) -> i32 {
+        let Ok(level) = CStr::from_ptr(config).to_str().unwrap().parse::<u8>() else {
+            return 22;
+        };
+        *out = PluginEffect {
+            state: Box::into_raw(Box::new(level)).cast(),
+            blur: Some(fill),
+            destroy: Some(destroy_fill),
+        };
+        0
+    }
+
+    unsafe extern "C" fn fill(
+        state: *mut c_void,
+        pixels: *mut u8,
+        width: u32,
+        height: u32,
+        channels: u32,
+        regions: *const PluginRegion,
+        count: usize,
+    ) -> i32 {
+        let level = *state.cast::<u8>();
+        let row = (width * channels) as usize;
+        let pixels = std::slice::from_raw_parts_mut(pixels, row * height as usize);
+        for r in std::slice::from_raw_parts(regions, count) {
+            for y in r.y..r.y + r.height {
+                let start = y as usize * row + r.x as usize * channels as usize;
+                pixels[start..start + (r.width * channels as i32) as usize].fill(level);
+            }
+        }
+        0
+    }
+
+    static DESTROYED: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe extern "C" fn destroy_fill(state: *mut c_void) {
+        drop(Box::from_raw(state.cast::<u8>()));
+        DESTROYED.fetch_add(1, Ordering::SeqCst);
+    }
+
+    static NEWER_CREATED: AtomicUsize = AtomicUsize::new(0);
+
+    /// Entry point of a plugin built for a newer ABI, whose effect struct
+    /// this build could not read.
+    unsafe extern "C" fn create_newer(
+        _config: *const c_char,
+        _elliptical: bool,
+        _out: *mut PluginEffect,
+    ) -> i32 {
+        NEWER_CREATED.fetch_add(1, Ordering::SeqCst);
+        0
+    }
+
+    fn fill_plugin(config: &str) -> Result<Arc<EffectPlugin>, PluginError> {
+        EffectPlugin::from_entry_points(Path::new("fill"), config, current_abi, create_fill, None)
+    }
+
+    fn region(x: i32, y: i32, w: i32, h: i32) -> Region {
+        Region {
+            x,
+            y,
+            width: w,
+            height: h,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    #[test]
+    fn test_effect_covers_regions() {
+        let plugin = fill_plugin("7").unwrap();
+        let blurrer = plugin.instantiate(false).unwrap();
+        let mut frame = Frame::new(vec![200; 4 * 4 * 3], 4, 4, 3, 0);
+        blurrer.blur(&mut frame, &[region(1, 1, 2, 2)]).unwrap();
+        let at = |x: usize, y: usize| frame.data()[(y * 4 + x) * 3];
+        assert_eq!(at(1, 1), 7);
+        assert_eq!(at(2, 2), 7);
+        assert_eq!(at(0, 0), 200);
+        assert_eq!(at(3, 3), 200);
+    }
+
+    #[test]
+    fn test_dropping_effect_destroys_its_state() {
+        let plugin = fill_plugin("7").unwrap();
+        let before = DESTROYED.load(Ordering::SeqCst);
+        drop(plugin.instantiate(true).unwrap());
+        assert!(DESTROYED.load(Ordering::SeqCst) > before);
+    }
+
+    #[test]
+    fn test_refused_config_fails_at_load() {
+        let err = fill_plugin("dark").unwrap_err();
+        assert!(matches!(err, PluginError::Create { code: 22, .. }), "{err}");
+    }
+
+    #[test]
+    fn test_other_abi_version_is_refused_before_create() {
+        let err =
+            EffectPlugin::from_entry_points(Path::new("new"), "7", future_abi, create_newer, None)
+                .unwrap_err();
+        assert!(
+            matches!(err, PluginError::AbiVersion { found: 2, .. }),
+            "{err}"
+        );
+        assert_eq!(NEWER_CREATED.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_missing_library_fails_to_load() {
+        let err = EffectPlugin::load(Path::new("/nonexistent/libeffect.so"), "").unwrap_err();
+        assert!(matches!(err, PluginError::Load { .. }), "{err}");
+    }
+
+    #[test]
+    fn test_region_without_full_geometry_uses_visible_one() {
+        let mut r = region(0, 5, 10, 20);
+        r.track_id = Some(3);
+        let plugin = PluginRegion::from(&r);
+        assert_eq!((plugin.full_x, plugin.full_y), (0, 5));
+        assert_eq!((plugin.full_width, plugin.full_height), (10, 20));
+        assert_eq!(plugin.track_id, 3);
+        assert_eq!(PluginRegion::from(&region(0, 0, 1, 1)).track_id, -1);
+    }
+}
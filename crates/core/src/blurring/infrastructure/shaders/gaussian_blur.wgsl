@@ -5,6 +5,9 @@
 // masked composite step.
 //
 // Kernel weights are pre-computed on CPU and passed via `kernel_weights`.
+// They are f32 bit patterns, or in fixed-point mode integers summing to
+// 65536. Fixed-point accumulates in u32 and rounds after each pass, so the
+// result is bit-exact on every adapter.
 
 struct Params {
     width: u32,
@@ -18,15 +21,15 @@ struct Params {
     ellipse_b: f32,   // semi-axis y
     use_ellipse: u32, // 0 = rectangular (no mask), 1 = elliptical
     direction: u32,        // 0 = horizontal, 1 = vertical
-    _pad0: u32,
-    _pad1: u32,
+    fixed_point: u32,      // 0 = f32 weights, 1 = 16.16 fixed-point weights
+    ellipse_feather: f32,  // outer fraction of the radius the blur fades over; 0 = hard edge
 }
 
 @group(0) @binding(0) var<uniform> params: Params;
 @group(0) @binding(1) var<storage, read> input: array<u32>;
 @group(0) @binding(2) var<storage, read_write> output: array<u32>;
 @group(0) @binding(3) var<storage, read> original: array<u32>;
-@group(0) @binding(4) var<storage, read> kernel_weights: array<f32>;
+@group(0) @binding(4) var<storage, read> kernel_weights: array<u32>;
 
 fn unpack_rgba(packed: u32) -> vec4<f32> {
     return vec4<f32>(
@@ -45,6 +48,33 @@ fn pack_rgba(v: vec4<f32>) -> u32 {
     return r | (g << 8u) | (b << 16u) | (a << 24u);
 }
 
+fn unpack_rgba_u(packed: u32) -> vec4<u32> {
+    return vec4<u32>(
+        packed & 0xFFu,
+        (packed >> 8u) & 0xFFu,
+        (packed >> 16u) & 0xFFu,
+        (packed >> 24u) & 0xFFu,
+    );
+}
+
+fn blur_fixed_point(x: u32, y: u32) -> u32 {
+    var sum = vec4<u32>(0u);
+    let radius = i32(params.kernel_radius);
+    for (var k = -radius; k <= radius; k = k + 1) {
+        var sample_idx: u32;
+        if params.direction == 0u {
+            let sx = clamp(i32(x) + k, 0, i32(params.width) - 1);
+            sample_idx = y * params.width + u32(sx);
+        } else {
+            let sy = clamp(i32(y) + k, 0, i32(params.height) - 1);
+            sample_idx = u32(sy) * params.width + x;
+        }
+        sum += unpack_rgba_u(input[sample_idx]) * kernel_weights[k + radius];
+    }
+    let v = (sum + vec4<u32>(32768u)) >> vec4<u32>(16u);
+    return v.x | (v.y << 8u) | (v.z << 16u) | (v.w << 24u);
+}
+
 @compute @workgroup_size(16, 16)
 fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
     let x = gid.x;
@@ -55,6 +85,14 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
     }
 
     let idx = y * params.width + x;
+
+    // Fixed-point mode leaves ellipse masking to the CPU, whose f64 test
+    // is the same on every machine.
+    if params.fixed_point == 1u {
+        output[idx] = blur_fixed_point(x, y);
+        return;
+    }
+
     var color = vec4<f32>(0.0);
     var weight_sum = 0.0;
 
@@ -65,7 +103,7 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
         for (var k = -radius; k <= radius; k = k + 1) {
             let sx = clamp(i32(x) + k, 0, i32(params.width) - 1);
             let sample_idx = y * params.width + u32(sx);
-            let w = kernel_weights[k + radius];
+            let w = bitcast<f32>(kernel_weights[k + radius]);
             color += unpack_rgba(input[sample_idx]) * w;
             weight_sum += w;
         }
@@ -74,7 +112,7 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
         for (var k = -radius; k <= radius; k = k + 1) {
             let sy = clamp(i32(y) + k, 0, i32(params.height) - 1);
             let sample_idx = u32(sy) * params.width + x;
-            let w = kernel_weights[k + radius];
+            let w = bitcast<f32>(kernel_weights[k + radius]);
             color += unpack_rgba(input[sample_idx]) * w;
             weight_sum += w;
         }
@@ -94,6 +132,13 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
         if dist > 1.0 {
             // Outside ellipse: use original pixel
             blurred = unpack_rgba(original[idx]);
+        } else if params.ellipse_feather > 0.0 {
+            // Fade to the original over the outer part of the radius, with
+            // the smoothstep `gaussian::ellipse_edge_weight` uses on the CPU.
+            let f = params.ellipse_feather;
+            let t = clamp((sqrt(dist) - (1.0 - f)) / f, 0.0, 1.0);
+            let weight = 1.0 - t * t * (3.0 - 2.0 * t);
+            blurred = mix(unpack_rgba(original[idx]), blurred, weight);
         }
     }
 
@@ -0,0 +1,64 @@
+// Letterbox resize into a planar RGB model input, matching
+// `Letterbox::fill` on the CPU.
+//
+// Input is the frame's bytes packed four to a word, followed by the
+// source column of each scaled column and then the source row of each
+// scaled row. The tables come from the CPU, so both pick the same pixels.
+// Each invocation writes one value of the 3 x size x size output.
+
+struct Params {
+    width: u32,
+    height: u32,
+    chroma_width: u32,
+    chroma_height: u32,
+    kr: f32,
+    kb: f32,
+    luma_offset: f32,
+    luma_scale: f32,
+    chroma_scale: f32,
+    channels: u32,
+    size: u32,
+    pad_x: u32,
+    pad_y: u32,
+    scaled_width: u32,
+    scaled_height: u32,
+    count: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> input: array<u32>;
+@group(0) @binding(2) var<storage, read_write> output: array<f32>;
+
+// 114/255 gray, the YOLO letterbox convention.
+const FILL: f32 = 0.44705883;
+
+fn byte_at(i: u32) -> u32 {
+    return (input[i >> 2u] >> ((i & 3u) * 8u)) & 0xFFu;
+}
+
+@compute @workgroup_size(64)
+fn main(
+    @builtin(global_invocation_id) gid: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let index = gid.y * groups.x * 64u + gid.x;
+    if index >= params.count {
+        return;
+    }
+    let plane = params.size * params.size;
+    let c = index / plane;
+    let tx = (index % plane) % params.size;
+    let ty = (index % plane) / params.size;
+    if tx < params.pad_x || ty < params.pad_y
+        || tx >= params.pad_x + params.scaled_width
+        || ty >= params.pad_y + params.scaled_height {
+        output[index] = FILL;
+        return;
+    }
+
+    let table = (params.width * params.height * params.channels + 3u) / 4u;
+    let sx = input[table + tx - params.pad_x];
+    let sy = input[table + params.scaled_width + ty - params.pad_y];
+    let i = (sy * params.width + sx) * params.channels + c;
+    output[index] = f32(byte_at(i)) / 255.0;
+}
@@ -0,0 +1,63 @@
+// Mosaic: each block x block tile becomes its mean color.
+//
+// Shares the blur shader's bindings so it runs on the same bind group
+// layout. `kernel_radius` holds the block size; the ellipse, direction,
+// and weights are unused (the ellipse mask is applied on the CPU).
+// Accumulates in integers and rounds to nearest, matching
+// `pixelate_roi_in_place` on the CPU bit for bit.
+
+struct Params {
+    width: u32,
+    height: u32,
+    kernel_radius: u32,
+    sigma: f32,
+    ellipse_cx: f32,
+    ellipse_cy: f32,
+    ellipse_a: f32,
+    ellipse_b: f32,
+    use_ellipse: u32,
+    direction: u32,
+    fixed_point: u32,
+    ellipse_feather: f32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> input: array<u32>;
+@group(0) @binding(2) var<storage, read_write> output: array<u32>;
+@group(0) @binding(3) var<storage, read> original: array<u32>;
+@group(0) @binding(4) var<storage, read> kernel_weights: array<u32>;
+
+fn unpack_rgba_u(packed: u32) -> vec4<u32> {
+    return vec4<u32>(
+        packed & 0xFFu,
+        (packed >> 8u) & 0xFFu,
+        (packed >> 16u) & 0xFFu,
+        (packed >> 24u) & 0xFFu,
+    );
+}
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let x = gid.x;
+    let y = gid.y;
+
+    if x >= params.width || y >= params.height {
+        return;
+    }
+
+    let block = max(params.kernel_radius, 1u);
+    let x0 = (x / block) * block;
+    let y0 = (y / block) * block;
+    let x1 = min(x0 + block, params.width);
+    let y1 = min(y0 + block, params.height);
+
+    var sum = vec4<u32>(0u);
+    for (var sy = y0; sy < y1; sy = sy + 1u) {
+        for (var sx = x0; sx < x1; sx = sx + 1u) {
+            sum += unpack_rgba_u(input[sy * params.width + sx]);
+        }
+    }
+    let count = (x1 - x0) * (y1 - y0);
+    let v = (sum + vec4<u32>(count / 2u)) / vec4<u32>(count);
+    output[y * params.width + x] = v.x | (v.y << 8u) | (v.z << 16u) | (v.w << 24u);
+}
@@ -0,0 +1,102 @@
+// Packed RGB24 to planar YUV 4:2:0, matching `Yuv420::from_rgb` on the
+// CPU to within a level: each chroma sample comes from the mean color of
+// its 2x2 block.
+//
+// Input is RGB bytes packed four to a word. Output is the Y, U, and V
+// planes back to back, one word of four bytes per invocation.
+
+struct Params {
+    width: u32,
+    height: u32,
+    chroma_width: u32,
+    chroma_height: u32,
+    kr: f32,
+    kb: f32,
+    luma_offset: f32,
+    luma_scale: f32,
+    chroma_scale: f32,
+    channels: u32,
+    size: u32,
+    pad_x: u32,
+    pad_y: u32,
+    scaled_width: u32,
+    scaled_height: u32,
+    count: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> input: array<u32>;
+@group(0) @binding(2) var<storage, read_write> output: array<u32>;
+
+fn byte_at(i: u32) -> u32 {
+    return (input[i >> 2u] >> ((i & 3u) * 8u)) & 0xFFu;
+}
+
+fn to_code(value: f32) -> u32 {
+    return u32(clamp(floor(value + 0.5), 0.0, 255.0));
+}
+
+fn rgb_at(x: u32, y: u32) -> vec3<f32> {
+    let i = (y * params.width + x) * 3u;
+    return vec3<f32>(f32(byte_at(i)), f32(byte_at(i + 1u)), f32(byte_at(i + 2u)));
+}
+
+fn luma_of(rgb: vec3<f32>) -> f32 {
+    let kg = 1.0 - params.kr - params.kb;
+    return params.kr * rgb.x + kg * rgb.y + params.kb * rgb.z;
+}
+
+fn block_mean(c: u32) -> vec3<f32> {
+    let bx = (c % params.chroma_width) * 2u;
+    let by = (c / params.chroma_width) * 2u;
+    var sum = vec3<f32>(0.0);
+    var count = 0.0;
+    for (var y = by; y < min(by + 2u, params.height); y++) {
+        for (var x = bx; x < min(bx + 2u, params.width); x++) {
+            sum += rgb_at(x, y);
+            count += 1.0;
+        }
+    }
+    return sum / count;
+}
+
+// Code value of byte `i` of the output planes.
+fn code_at(i: u32) -> u32 {
+    let luma_plane = params.width * params.height;
+    let chroma_plane = params.chroma_width * params.chroma_height;
+    let scale = params.chroma_scale / 255.0;
+    if i < luma_plane {
+        let rgb = rgb_at(i % params.width, i / params.width);
+        return to_code(params.luma_offset + luma_of(rgb) * params.luma_scale / 255.0);
+    }
+    if i < luma_plane + chroma_plane {
+        let rgb = block_mean(i - luma_plane);
+        let cb = (rgb.z - luma_of(rgb)) / (2.0 * (1.0 - params.kb));
+        return to_code(128.0 + cb * scale);
+    }
+    let rgb = block_mean(i - luma_plane - chroma_plane);
+    let cr = (rgb.x - luma_of(rgb)) / (2.0 * (1.0 - params.kr));
+    return to_code(128.0 + cr * scale);
+}
+
+@compute @workgroup_size(64)
+fn main(
+    @builtin(global_invocation_id) gid: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let word = gid.y * groups.x * 64u + gid.x;
+    if word >= params.count {
+        return;
+    }
+    let total = params.width * params.height
+        + 2u * params.chroma_width * params.chroma_height;
+
+    var packed = 0u;
+    for (var k = 0u; k < 4u; k++) {
+        let i = word * 4u + k;
+        if i < total {
+            packed |= code_at(i) << (k * 8u);
+        }
+    }
+    output[word] = packed;
+}
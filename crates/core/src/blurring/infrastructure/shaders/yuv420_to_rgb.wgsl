@@ -0,0 +1,86 @@
+// Planar YUV 4:2:0 to packed RGB24, matching `YuvCoefficients::to_rgb`
+// on the CPU to within a level.
+//
+// Input is the Y, U, and V planes back to back, as bytes packed four to a
+// word. Each invocation converts four pixels into three output words.
+
+struct Params {
+    width: u32,
+    height: u32,
+    chroma_width: u32,
+    chroma_height: u32,
+    kr: f32,
+    kb: f32,
+    luma_offset: f32,
+    luma_scale: f32,
+    chroma_scale: f32,
+    channels: u32,
+    size: u32,
+    pad_x: u32,
+    pad_y: u32,
+    scaled_width: u32,
+    scaled_height: u32,
+    count: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> input: array<u32>;
+@group(0) @binding(2) var<storage, read_write> output: array<u32>;
+
+fn byte_at(i: u32) -> u32 {
+    return (input[i >> 2u] >> ((i & 3u) * 8u)) & 0xFFu;
+}
+
+fn to_code(value: f32) -> u32 {
+    return u32(clamp(floor(value + 0.5), 0.0, 255.0));
+}
+
+fn pixel_rgb(pixel: u32) -> vec3<u32> {
+    let x = pixel % params.width;
+    let y = pixel / params.width;
+    let luma_plane = params.width * params.height;
+    let chroma_plane = params.chroma_width * params.chroma_height;
+    let c = (y / 2u) * params.chroma_width + x / 2u;
+
+    let kg = 1.0 - params.kr - params.kb;
+    let luma = (f32(byte_at(pixel)) - params.luma_offset) * 255.0 / params.luma_scale;
+    let cb = (f32(byte_at(luma_plane + c)) - 128.0) * 255.0 / params.chroma_scale;
+    let cr = (f32(byte_at(luma_plane + chroma_plane + c)) - 128.0) * 255.0 / params.chroma_scale;
+    let r = luma + 2.0 * (1.0 - params.kr) * cr;
+    let b = luma + 2.0 * (1.0 - params.kb) * cb;
+    let g = (luma - params.kr * r - params.kb * b) / kg;
+    return vec3<u32>(to_code(r), to_code(g), to_code(b));
+}
+
+@compute @workgroup_size(64)
+fn main(
+    @builtin(global_invocation_id) gid: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let index = gid.y * groups.x * 64u + gid.x;
+    let pixels = params.width * params.height;
+    let first = index * 4u;
+    if first >= pixels {
+        return;
+    }
+
+    var bytes: array<u32, 12>;
+    for (var i = 0u; i < 4u; i++) {
+        var rgb = vec3<u32>(0u);
+        if first + i < pixels {
+            rgb = pixel_rgb(first + i);
+        }
+        bytes[i * 3u] = rgb.x;
+        bytes[i * 3u + 1u] = rgb.y;
+        bytes[i * 3u + 2u] = rgb.z;
+    }
+    for (var w = 0u; w < 3u; w++) {
+        let word = index * 3u + w;
+        if word < params.count {
+            output[word] = bytes[w * 4u]
+                | (bytes[w * 4u + 1u] << 8u)
+                | (bytes[w * 4u + 2u] << 16u)
+                | (bytes[w * 4u + 3u] << 24u);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::cell::RefCell;
+
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+use super::gaussian::{self, RoiRect};
+
+/// Covers each region with a flat color (black by default), leaving
+/// nothing of the face to recover. Elliptical mode fills only the inscribed
+/// ellipse. Cheap enough that it has no GPU version.
+pub struct SolidFillBlurrer {
+    color: [u8; 3],
+    elliptical: bool,
+    roi_buf: RefCell<Vec<u8>>,
+}
+
+impl SolidFillBlurrer {
+    pub fn new(elliptical: bool) -> Self {
+        Self {
+            color: [0, 0, 0],
+            elliptical,
+            roi_buf: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Fills with `color` (RGB) instead of black.
+    pub fn with_color(mut self, color: [u8; 3]) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+impl FrameBlurrer for SolidFillBlurrer {
+    fn blur(
+        &self,
+        frame: &mut Frame,
+        regions: &[Region],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let fw = frame.width() as usize;
+        let fh = frame.height() as usize;
+        let channels = frame.channels() as usize;
+        let data = frame.data_mut();
+
+        for r in regions {
+            let rx = r.x.max(0) as usize;
+            let ry = r.y.max(0) as usize;
+            let rw = (r.width.max(0) as usize).min(fw.saturating_sub(rx));
+            let rh = (r.height.max(0) as usize).min(fh.saturating_sub(ry));
+
+            if rw == 0 || rh == 0 {
+                continue;
+            }
+
+            let rect = RoiRect {
+                x: rx,
+                y: ry,
+                w: rw,
+                h: rh,
+            };
+            let mut roi = self.roi_buf.borrow_mut();
+            roi.clear();
+            for _ in 0..rw * rh {
+                roi.extend_from_slice(&self.color[..channels.min(3)]);
+                roi.resize(roi.len() + channels.saturating_sub(3), 0);
+            }
+
+            if self.elliptical {
+                gaussian::composite_ellipse(data, &roi, fw, channels, rect, r);
+            } else {
+                gaussian::write_roi_back(data, &roi, fw, channels, rect);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_frame(width: u32, height: u32, value: u8) -> Frame {
+        let data = vec![value; (width * height * 3) as usize];
+        Frame::new(data, width, height, 3, 0)
+    }
+
+    fn region(x: i32, y: i32, w: i32, h: i32) -> Region {
+        Region {
+            x,
+            y,
+            width: w,
+            height: h,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    #[test]
+    fn test_rectangle_is_filled() {
+        let mut frame = make_frame(50, 50, 200);
+        SolidFillBlurrer::new(false)
+            .with_color([10, 20, 30])
+            .blur(&mut frame, &[region(10, 10, 20, 20)])
+            .unwrap();
+
+        let inside = (15 * 50 + 15) * 3;
+        assert_eq!(&frame.data()[inside..inside + 3], &[10, 20, 30]);
+        assert_eq!(frame.data()[0], 200);
+    }
+
+    #[test]
+    fn test_ellipse_leaves_corners() {
+        let mut frame = make_frame(50, 50, 200);
+        SolidFillBlurrer::new(true)
+            .blur(&mut frame, &[region(10, 10, 20, 20)])
+            .unwrap();
+
+        let center = (20 * 50 + 20) * 3;
+        assert_eq!(frame.data()[center], 0);
+        let corner = (10 * 50 + 10) * 3;
+        assert_eq!(frame.data()[corner], 200);
+    }
+
+    #[test]
+    fn test_region_extending_beyond_frame_does_not_panic() {
+        let mut frame = make_frame(50, 50, 128);
+        SolidFillBlurrer::new(true)
+            .blur(&mut frame, &[region(40, 40, 30, 30)])
+            .unwrap();
+    }
+}
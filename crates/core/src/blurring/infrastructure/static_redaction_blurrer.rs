@@ -0,0 +1,114 @@
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
+use crate::blurring::domain::redaction_rect::RedactionRect;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// Decorator that also blurs fixed rectangles the detector will never find.
+///
+/// Detected regions go to the inner blurrer; the rectangles active on the
+/// frame go to `redactor`, normally a rectangular blurrer of the same
+/// style, so the whole rectangle is covered rather than its inscribed
+/// ellipse. Rectangles are blurred after the faces.
+pub struct StaticRedactionBlurrer {
+    inner: Box<dyn FrameBlurrer>,
+    redactor: Box<dyn FrameBlurrer>,
+    rects: Vec<RedactionRect>,
+}
+
+impl StaticRedactionBlurrer {
+    pub fn new(
+        inner: Box<dyn FrameBlurrer>,
+        redactor: Box<dyn FrameBlurrer>,
+        rects: Vec<RedactionRect>,
+    ) -> Self {
+        Self {
+            inner,
+            redactor,
+            rects,
+        }
+    }
+}
+
+impl FrameBlurrer for StaticRedactionBlurrer {
+    fn blur(
+        &self,
+        frame: &mut Frame,
+        regions: &[Region],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.blur(frame, regions)?;
+        let active: Vec<Region> = self
+            .rects
+            .iter()
+            .filter(|r| r.active_at(frame.index()))
+            .filter_map(|r| r.region(frame.width(), frame.height()))
+            .collect();
+        if active.is_empty() {
+            return Ok(());
+        }
+        self.redactor.blur(frame, &active)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blurring::infrastructure::solid_fill_blurrer::SolidFillBlurrer;
+    use std::sync::{Arc, Mutex};
+
+    /// Records the regions it was asked to blur.
+    struct Recorder(Arc<Mutex<Vec<Vec<Region>>>>);
+
+    impl FrameBlurrer for Recorder {
+        fn blur(
+            &self,
+            _frame: &mut Frame,
+            regions: &[Region],
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            self.0.lock().unwrap().push(regions.to_vec());
+            Ok(())
+        }
+    }
+
+    fn blurrer(rects: &[&str]) -> (StaticRedactionBlurrer, Arc<Mutex<Vec<Vec<Region>>>>) {
+        let faces = Arc::new(Mutex::new(Vec::new()));
+        let rects = rects
+            .iter()
+            .map(|s| RedactionRect::parse(s).unwrap())
+            .collect();
+        let blurrer = StaticRedactionBlurrer::new(
+            Box::new(Recorder(faces.clone())),
+            Box::new(SolidFillBlurrer::new(false).with_color([255, 0, 0])),
+            rects,
+        );
+        (blurrer, faces)
+    }
+
+    #[test]
+    fn test_fills_whole_rectangle_on_active_frames() {
+        let (blurrer, faces) = blurrer(&["2,2,4,4@1-2"]);
+        let mut early = Frame::new(vec![0; 10 * 10 * 3], 10, 10, 3, 0);
+        let mut active = Frame::new(vec![0; 10 * 10 * 3], 10, 10, 3, 2);
+
+        blurrer.blur(&mut early, &[]).unwrap();
+        blurrer.blur(&mut active, &[]).unwrap();
+
+        assert!(early.data().iter().all(|&v| v == 0));
+        let px = |x: usize, y: usize| active.data()[(y * 10 + x) * 3];
+        assert_eq!((px(2, 2), px(5, 5), px(6, 6)), (255, 255, 0));
+        assert_eq!(faces.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_detected_regions_still_reach_inner_blurrer() {
+        let (blurrer, faces) = blurrer(&["0,0,2,2"]);
+        let mut frame = Frame::new(vec![0; 10 * 10 * 3], 10, 10, 3, 0);
+        let face = RedactionRect::parse("5,5,3,3")
+            .unwrap()
+            .region(10, 10)
+            .unwrap();
+
+        blurrer.blur(&mut frame, &[face.clone()]).unwrap();
+
+        assert_eq!(faces.lock().unwrap()[0], vec![face]);
+    }
+}
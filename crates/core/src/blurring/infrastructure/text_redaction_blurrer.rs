@@ -0,0 +1,195 @@
+use std::cell::RefCell;
+
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
+use crate::detection::domain::text_detector::TextDetector;
+use crate::detection::domain::text_matcher::TextMatcher;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// Frames a matched text box stays blurred after it was last found, so a
+/// word the detector misses for a frame or two does not flash into view.
+pub const DEFAULT_TEXT_HOLD_FRAMES: usize = 5;
+
+/// Decorator that also blurs visible text the matcher accepts: street
+/// signs, documents, phone screens.
+///
+/// Text is looked for on the frame as it arrives, before the inner blurrer
+/// covers anything, and matching boxes go to `redactor` afterwards, like
+/// [`StaticRedactionBlurrer`](super::static_redaction_blurrer::StaticRedactionBlurrer)'s
+/// rectangles.
+pub struct TextRedactionBlurrer {
+    inner: Box<dyn FrameBlurrer>,
+    redactor: Box<dyn FrameBlurrer>,
+    detector: RefCell<Box<dyn TextDetector>>,
+    matcher: TextMatcher,
+    hold: usize,
+    /// Matched boxes of recent frames, by frame index.
+    recent: RefCell<Vec<(usize, Vec<Region>)>>,
+}
+
+impl TextRedactionBlurrer {
+    pub fn new(
+        inner: Box<dyn FrameBlurrer>,
+        redactor: Box<dyn FrameBlurrer>,
+        detector: Box<dyn TextDetector>,
+        matcher: TextMatcher,
+    ) -> Self {
+        Self {
+            inner,
+            redactor,
+            detector: RefCell::new(detector),
+            matcher,
+            hold: DEFAULT_TEXT_HOLD_FRAMES,
+            recent: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Frames a box stays blurred after it was last matched; `0` blurs
+    /// only what is found on each frame.
+    pub fn with_hold(mut self, frames: usize) -> Self {
+        self.hold = frames;
+        self
+    }
+}
+
+impl FrameBlurrer for TextRedactionBlurrer {
+    fn blur(
+        &self,
+        frame: &mut Frame,
+        regions: &[Region],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let matched: Vec<Region> = self
+            .detector
+            .borrow_mut()
+            .detect_text(frame)?
+            .into_iter()
+            .filter(|b| self.matcher.matches(b.text.as_deref()))
+            .map(|b| b.region)
+            .collect();
+        self.inner.blur(frame, regions)?;
+
+        let index = frame.index();
+        let mut recent = self.recent.borrow_mut();
+        // Frames from after a backward seek are dropped too.
+        recent.retain(|&(seen, _)| seen <= index && index - seen <= self.hold);
+        if !matched.is_empty() {
+            recent.push((index, matched));
+        }
+        let active: Vec<Region> = recent
+            .iter()
+            .flat_map(|(_, boxes)| boxes.iter().cloned())
+            .collect();
+        if active.is_empty() {
+            return Ok(());
+        }
+        self.redactor.blur(frame, &active)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blurring::infrastructure::solid_fill_blurrer::SolidFillBlurrer;
+    use crate::detection::domain::text_detector::TextBox;
+    use std::collections::HashMap;
+
+    /// Finds the scripted boxes on each frame index.
+    struct ScriptedText(HashMap<usize, Vec<TextBox>>);
+
+    impl TextDetector for ScriptedText {
+        fn detect_text(
+            &mut self,
+            frame: &Frame,
+        ) -> Result<Vec<TextBox>, Box<dyn std::error::Error>> {
+            Ok(self.0.get(&frame.index()).cloned().unwrap_or_default())
+        }
+    }
+
+    fn text_box(x: i32, text: Option<&str>) -> TextBox {
+        TextBox {
+            region: Region {
+                x,
+                y: 0,
+                width: 2,
+                height: 2,
+                track_id: None,
+                full_width: None,
+                full_height: None,
+                unclamped_x: None,
+                unclamped_y: None,
+            },
+            text: text.map(str::to_string),
+        }
+    }
+
+    fn blurrer(script: HashMap<usize, Vec<TextBox>>, patterns: &[&str]) -> TextRedactionBlurrer {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        TextRedactionBlurrer::new(
+            Box::new(SolidFillBlurrer::new(false).with_color([0, 0, 255])),
+            Box::new(SolidFillBlurrer::new(false).with_color([255, 0, 0])),
+            Box::new(ScriptedText(script)),
+            TextMatcher::parse(&patterns).unwrap(),
+        )
+    }
+
+    fn red_at(frame: &Frame, x: usize) -> bool {
+        frame.data()[x * 3] == 255
+    }
+
+    #[test]
+    fn test_blurs_only_matching_text() {
+        let script = HashMap::from([(
+            0,
+            vec![text_box(0, Some("ACME Corp")), text_box(4, Some("Main St"))],
+        )]);
+        let blurrer = blurrer(script, &["acme"]);
+        let mut frame = Frame::new(vec![0; 10 * 2 * 3], 10, 2, 3, 0);
+
+        blurrer.blur(&mut frame, &[]).unwrap();
+
+        assert!(red_at(&frame, 0));
+        assert!(!red_at(&frame, 4));
+    }
+
+    #[test]
+    fn test_unread_text_needs_any_pattern() {
+        let script = HashMap::from([(0, vec![text_box(0, None)])]);
+        let mut frame = Frame::new(vec![0; 10 * 2 * 3], 10, 2, 3, 0);
+
+        blurrer(script.clone(), &["acme"])
+            .blur(&mut frame, &[])
+            .unwrap();
+        assert!(!red_at(&frame, 0));
+
+        blurrer(script, &["*"]).blur(&mut frame, &[]).unwrap();
+        assert!(red_at(&frame, 0));
+    }
+
+    #[test]
+    fn test_text_is_held_for_missed_frames() {
+        let script = HashMap::from([(0, vec![text_box(0, Some("acme"))])]);
+        let blurrer = blurrer(script, &["acme"]).with_hold(2);
+
+        let blurred: Vec<bool> = (0..4)
+            .map(|i| {
+                let mut frame = Frame::new(vec![0; 10 * 2 * 3], 10, 2, 3, i);
+                blurrer.blur(&mut frame, &[]).unwrap();
+                red_at(&frame, 0)
+            })
+            .collect();
+
+        assert_eq!(blurred, vec![true, true, true, false]);
+    }
+
+    #[test]
+    fn test_text_is_redacted_over_the_inner_blur() {
+        let script = HashMap::from([(0, vec![text_box(0, Some("acme"))])]);
+        let blurrer = blurrer(script, &["acme"]);
+        let mut frame = Frame::new(vec![0; 10 * 2 * 3], 10, 2, 3, 0);
+        let face = text_box(0, None).region;
+
+        blurrer.blur(&mut frame, &[face]).unwrap();
+
+        assert!(red_at(&frame, 0));
+    }
+}
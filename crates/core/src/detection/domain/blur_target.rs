@@ -0,0 +1,67 @@
+/// What each blur region covers.
+///
+/// Faces are enough in most footage, but hair, clothing, and tattoos can
+/// identify someone whose face is hidden.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BlurTarget {
+    /// The face, from forehead to chin.
+    #[default]
+    Face,
+    /// The whole head: the face region extended over hair and ears.
+    Head,
+    /// The whole body, from a person detector instead of the face model.
+    /// Also hides faces, and people seen from behind.
+    Person,
+}
+
+impl BlurTarget {
+    pub const ALL: [BlurTarget; 3] = [Self::Face, Self::Head, Self::Person];
+
+    /// Parses `face`, `head`, or `person` (also `body`).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "face" => Some(Self::Face),
+            "head" => Some(Self::Head),
+            "person" | "body" => Some(Self::Person),
+            _ => None,
+        }
+    }
+
+    /// Name accepted by [`BlurTarget::parse`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Face => "face",
+            Self::Head => "head",
+            Self::Person => "person",
+        }
+    }
+
+    /// Whether regions come from the person detector rather than the face
+    /// model.
+    pub fn detects_people(&self) -> bool {
+        *self == Self::Person
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("face", Some(BlurTarget::Face))]
+    #[case(" Head ", Some(BlurTarget::Head))]
+    #[case("person", Some(BlurTarget::Person))]
+    #[case("body", Some(BlurTarget::Person))]
+    #[case("torso", None)]
+    fn test_parse(#[case] input: &str, #[case] expected: Option<BlurTarget>) {
+        assert_eq!(BlurTarget::parse(input), expected);
+    }
+
+    #[test]
+    fn test_name_round_trips() {
+        for target in BlurTarget::ALL {
+            assert_eq!(BlurTarget::parse(target.name()), Some(target));
+        }
+    }
+}
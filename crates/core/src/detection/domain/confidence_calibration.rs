@@ -0,0 +1,156 @@
+/// Maps the user-facing sensitivity threshold onto a model's raw confidence
+/// scale.
+///
+/// Detection models disagree on how confident they are: one model's 0.25
+/// can be another's 0.6. Each registered model carries a calibration so the
+/// same threshold (e.g. "Medium sensitivity") removes a comparable share of
+/// detections whichever model is loaded. Thresholds are expressed on the
+/// calibrated scale and converted once with [`raw_threshold`].
+///
+/// [`raw_threshold`]: ConfidenceCalibration::raw_threshold
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfidenceCalibration {
+    /// The model's scores are already on the reference scale.
+    Identity,
+    /// Temperature scaling: `calibrated = sigmoid(logit(raw) / T)`.
+    /// `T > 1` means the model is over-confident.
+    Temperature(f64),
+    /// Piecewise-linear `(calibrated, raw)` points, sorted by calibrated
+    /// value, typically taken from matching percentiles of the two models'
+    /// score distributions on a reference set. Values outside the table
+    /// are clamped to its ends.
+    Percentiles(&'static [(f64, f64)]),
+}
+
+impl ConfidenceCalibration {
+    /// Raw model score that corresponds to a calibrated `threshold`.
+    pub fn raw_threshold(&self, threshold: f64) -> f64 {
+        match *self {
+            ConfidenceCalibration::Identity => threshold,
+            ConfidenceCalibration::Temperature(t) => {
+                if threshold <= 0.0 || threshold >= 1.0 || t <= 0.0 {
+                    return threshold;
+                }
+                sigmoid(t * logit(threshold))
+            }
+            ConfidenceCalibration::Percentiles(points) => interpolate(points, threshold),
+        }
+    }
+}
+
+fn logit(p: f64) -> f64 {
+    (p / (1.0 - p)).ln()
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn interpolate(points: &[(f64, f64)], x: f64) -> f64 {
+    let (Some(&(first_x, first_y)), Some(&(last_x, last_y))) = (points.first(), points.last())
+    else {
+        return x;
+    };
+    if x <= first_x {
+        return first_y;
+    }
+    if x >= last_x {
+        return last_y;
+    }
+    for pair in points.windows(2) {
+        let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+        if x <= x1 {
+            if x1 <= x0 {
+                return y1;
+            }
+            return y0 + (y1 - y0) * (x - x0) / (x1 - x0);
+        }
+    }
+    last_y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rstest::rstest;
+
+    const TABLE: &[(f64, f64)] = &[(0.1, 0.2), (0.5, 0.6), (0.9, 0.8)];
+
+    #[rstest]
+    #[case(0.0)]
+    #[case(0.25)]
+    #[case(1.0)]
+    fn test_identity_passes_threshold_through(#[case] threshold: f64) {
+        assert_relative_eq!(
+            ConfidenceCalibration::Identity.raw_threshold(threshold),
+            threshold
+        );
+    }
+
+    #[test]
+    fn test_temperature_one_is_identity() {
+        assert_relative_eq!(
+            ConfidenceCalibration::Temperature(1.0).raw_threshold(0.3),
+            0.3,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_temperature_keeps_midpoint() {
+        assert_relative_eq!(
+            ConfidenceCalibration::Temperature(2.0).raw_threshold(0.5),
+            0.5,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_overconfident_model_needs_higher_raw_threshold() {
+        let raw = ConfidenceCalibration::Temperature(2.0).raw_threshold(0.25);
+        // sigmoid(2 * logit(0.25)) = 1 / (1 + 9)
+        assert_relative_eq!(raw, 0.1, epsilon = 1e-12);
+        let raw = ConfidenceCalibration::Temperature(2.0).raw_threshold(0.75);
+        assert_relative_eq!(raw, 0.9, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_temperature_leaves_bounds_alone() {
+        let cal = ConfidenceCalibration::Temperature(2.0);
+        assert_relative_eq!(cal.raw_threshold(0.0), 0.0);
+        assert_relative_eq!(cal.raw_threshold(1.0), 1.0);
+    }
+
+    #[rstest]
+    #[case(0.1, 0.2)]
+    #[case(0.3, 0.4)]
+    #[case(0.5, 0.6)]
+    #[case(0.7, 0.7)]
+    #[case(0.9, 0.8)]
+    fn test_percentiles_interpolate(#[case] threshold: f64, #[case] expected: f64) {
+        assert_relative_eq!(
+            ConfidenceCalibration::Percentiles(TABLE).raw_threshold(threshold),
+            expected,
+            epsilon = 1e-12
+        );
+    }
+
+    #[rstest]
+    #[case(0.0, 0.2)]
+    #[case(1.0, 0.8)]
+    fn test_percentiles_clamp_outside_table(#[case] threshold: f64, #[case] expected: f64) {
+        assert_relative_eq!(
+            ConfidenceCalibration::Percentiles(TABLE).raw_threshold(threshold),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_empty_percentile_table_is_identity() {
+        assert_relative_eq!(
+            ConfidenceCalibration::Percentiles(&[]).raw_threshold(0.4),
+            0.4
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use std::fmt;
+
+use crate::shared::letterbox::Crop;
+
+/// A part of the frame where faces can appear, as fractions of its width
+/// and height so it fits any resolution of the same camera.
+///
+/// When zones are set, detection only looks inside them: the frame is
+/// cropped to the rectangle around all zones before the letterbox, and
+/// faces centered outside every zone are dropped. On fixed-camera footage
+/// where people only pass through a doorway, the model then spends its
+/// whole input on the doorway.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DetectionZone {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl DetectionZone {
+    /// Parses `X,Y,W,H` as fractions from 0 to 1 (e.g. `0.6,0.1,0.3,0.8`
+    /// for a doorway on the right).
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("Detection zone '{spec}' must be X,Y,W,H as fractions of 0-1");
+        let values: Vec<f64> = spec
+            .split(',')
+            .map(|v| v.trim().parse().map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        let [x, y, width, height] = values[..] else {
+            return Err(invalid());
+        };
+        let fraction = |v: f64| (0.0..=1.0).contains(&v);
+        if !(fraction(x) && fraction(y) && width > 0.0 && height > 0.0) {
+            return Err(invalid());
+        }
+        if x + width > 1.0 + 1e-9 || y + height > 1.0 + 1e-9 {
+            return Err(format!("Detection zone '{spec}' extends past the frame"));
+        }
+        Ok(Self {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    /// Whether the pixel `(px, py)` of a `frame_w`×`frame_h` frame lies in
+    /// the zone.
+    pub fn contains(&self, px: f64, py: f64, frame_w: u32, frame_h: u32) -> bool {
+        let (nx, ny) = (px / frame_w as f64, py / frame_h as f64);
+        nx >= self.x && nx <= self.x + self.width && ny >= self.y && ny <= self.y + self.height
+    }
+
+    /// The pixel rectangle around all `zones`, rounded outward and clipped
+    /// to the frame, or `None` without zones.
+    pub fn bounds(zones: &[Self], frame_w: u32, frame_h: u32) -> Option<Crop> {
+        if zones.is_empty() || frame_w == 0 || frame_h == 0 {
+            return None;
+        }
+        let (fw, fh) = (frame_w as f64, frame_h as f64);
+        let left = zones.iter().map(|z| z.x).fold(f64::INFINITY, f64::min);
+        let top = zones.iter().map(|z| z.y).fold(f64::INFINITY, f64::min);
+        let right = zones.iter().map(|z| z.x + z.width).fold(0.0, f64::max);
+        let bottom = zones.iter().map(|z| z.y + z.height).fold(0.0, f64::max);
+        let x = ((left * fw).floor() as u32).min(frame_w - 1);
+        let y = ((top * fh).floor() as u32).min(frame_h - 1);
+        let x2 = ((right * fw).ceil() as u32).clamp(x + 1, frame_w);
+        let y2 = ((bottom * fh).ceil() as u32).clamp(y + 1, frame_h);
+        Some(Crop {
+            x,
+            y,
+            width: x2 - x,
+            height: y2 - y,
+        })
+    }
+}
+
+/// Written as [`DetectionZone::parse`] reads it.
+impl fmt::Display for DetectionZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{},{}", self.x, self.y, self.width, self.height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn test_parse_round_trips() {
+        let zone = DetectionZone::parse("0.6, 0.1, 0.3, 0.8").unwrap();
+        assert_eq!(zone.to_string(), "0.6,0.1,0.3,0.8");
+        assert_eq!(DetectionZone::parse(&zone.to_string()), Ok(zone));
+    }
+
+    #[rstest]
+    #[case("0.1,0.1,0.5")]
+    #[case("a,0,1,1")]
+    #[case("0,0,0,1")]
+    #[case("-0.1,0,0.5,0.5")]
+    #[case("0,0,100,200")]
+    #[case("0.8,0,0.5,0.5")]
+    fn test_parse_rejects(#[case] spec: &str) {
+        assert!(DetectionZone::parse(spec).is_err(), "{spec}");
+    }
+
+    #[test]
+    fn test_contains_uses_fractions_of_frame() {
+        let zone = DetectionZone::parse("0.5,0,0.5,1").unwrap();
+        assert!(zone.contains(1500.0, 500.0, 1920, 1080));
+        assert!(!zone.contains(500.0, 500.0, 1920, 1080));
+    }
+
+    #[test]
+    fn test_bounds_cover_every_zone() {
+        let zones = [
+            DetectionZone::parse("0.1,0.2,0.2,0.2").unwrap(),
+            DetectionZone::parse("0.5,0.5,0.25,0.25").unwrap(),
+        ];
+        let crop = DetectionZone::bounds(&zones, 1000, 500).unwrap();
+        assert_eq!(
+            crop,
+            Crop {
+                x: 100,
+                y: 100,
+                width: 650,
+                height: 275
+            }
+        );
+    }
+
+    #[test]
+    fn test_no_zones_is_whole_frame() {
+        assert_eq!(DetectionZone::bounds(&[], 640, 480), None);
+    }
+
+    #[test]
+    fn test_tiny_zone_keeps_a_pixel() {
+        let zone = DetectionZone::parse("1,1,0.000001,0.000001").unwrap();
+        let crop = DetectionZone::bounds(&[zone], 10, 10).unwrap();
+        assert_eq!((crop.x, crop.y, crop.width, crop.height), (9, 9, 1, 1));
+    }
+}
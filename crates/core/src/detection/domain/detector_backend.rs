@@ -0,0 +1,86 @@
+/// Which ONNX Runtime execution provider runs the detection model.
+///
+/// A provider this build or machine can't use falls back to the CPU with a
+/// warning, so asking for one is always safe.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DetectorBackend {
+    /// The platform's usual accelerator: CoreML on macOS, DirectML on
+    /// Windows, the CPU elsewhere.
+    #[default]
+    Auto,
+    Cpu,
+    /// NVIDIA GPUs. Needs a build with the `cuda` feature and the CUDA
+    /// runtime installed.
+    Cuda,
+    /// Apple's GPU and Neural Engine, on macOS.
+    CoreMl,
+    /// Any DirectX 12 GPU, on Windows.
+    DirectMl,
+}
+
+impl DetectorBackend {
+    pub const ALL: [DetectorBackend; 5] = [
+        Self::Auto,
+        Self::Cpu,
+        Self::Cuda,
+        Self::CoreMl,
+        Self::DirectMl,
+    ];
+
+    /// Parses `auto`, `cpu`, `cuda`, `coreml`, or `directml`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "cpu" => Some(Self::Cpu),
+            "cuda" => Some(Self::Cuda),
+            "coreml" => Some(Self::CoreMl),
+            "directml" => Some(Self::DirectMl),
+            _ => None,
+        }
+    }
+
+    /// Name accepted by [`DetectorBackend::parse`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Cpu => "cpu",
+            Self::Cuda => "cuda",
+            Self::CoreMl => "coreml",
+            Self::DirectMl => "directml",
+        }
+    }
+
+    /// Name to show people.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Auto => "Automatic",
+            Self::Cpu => "CPU",
+            Self::Cuda => "CUDA",
+            Self::CoreMl => "CoreML",
+            Self::DirectMl => "DirectML",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("auto", Some(DetectorBackend::Auto))]
+    #[case(" CUDA ", Some(DetectorBackend::Cuda))]
+    #[case("coreml", Some(DetectorBackend::CoreMl))]
+    #[case("DirectML", Some(DetectorBackend::DirectMl))]
+    #[case("tensorrt", None)]
+    fn test_parse(#[case] input: &str, #[case] expected: Option<DetectorBackend>) {
+        assert_eq!(DetectorBackend::parse(input), expected);
+    }
+
+    #[test]
+    fn test_name_round_trips() {
+        for backend in DetectorBackend::ALL {
+            assert_eq!(DetectorBackend::parse(backend.name()), Some(backend));
+        }
+    }
+}
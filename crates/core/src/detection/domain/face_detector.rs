@@ -1,10 +1,27 @@
 use crate::shared::frame::Frame;
 use crate::shared::region::Region;
 
+/// What a detector remembers between frames, as saved to a resume
+/// checkpoint. Opaque outside the detector that saved it.
+pub type DetectorState = serde_json::Value;
+
 /// Domain interface for face detection.
 ///
 /// Implementations may be stateful (e.g., tracking across frames),
 /// hence `&mut self`.
 pub trait FaceDetector: Send {
     fn detect(&mut self, frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>>;
+
+    /// The detector's memory of past frames (tracks, smoothing), so an
+    /// interrupted run can resume without starting tracks over. Stateless
+    /// detectors save nothing.
+    fn save_state(&self) -> Result<DetectorState, Box<dyn std::error::Error>> {
+        Ok(DetectorState::Null)
+    }
+
+    /// Picks up where the detector that saved `state` left off. Must be
+    /// called on a detector built with the same settings.
+    fn restore_state(&mut self, _state: &DetectorState) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(())
+    }
 }
@@ -0,0 +1,13 @@
+/// Domain interface for turning a face crop into an identity embedding.
+///
+/// Takes RGB crop data with its width and height and returns an
+/// L2-normalized vector, so the dot product of two embeddings is their
+/// cosine similarity.
+pub trait FaceEmbedder: Send {
+    fn embed(
+        &self,
+        rgb_data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<f32>, Box<dyn std::error::Error>>;
+}
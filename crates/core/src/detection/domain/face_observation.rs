@@ -0,0 +1,19 @@
+use std::collections::HashMap;
+
+use crate::detection::domain::face_landmarks::FaceLandmarks;
+
+/// What the model reported for a tracked face on a frame it ran on.
+///
+/// Regions carry only blur geometry. Observations keep the model's score
+/// and landmarks beside them, keyed by frame and track, for auditing what
+/// the detector saw.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FaceObservation {
+    /// Raw model score of the detection matched to the track.
+    pub confidence: f64,
+    pub landmarks: Option<FaceLandmarks>,
+}
+
+/// Observations by frame index, then track ID. Frames the model skipped and
+/// tracks coasting without a matched detection have no entry.
+pub type FaceObservations = HashMap<usize, HashMap<u32, FaceObservation>>;
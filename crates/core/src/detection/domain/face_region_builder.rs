@@ -1,7 +1,9 @@
+use std::collections::HashMap;
+
 use crate::shared::region::Region;
 
 use super::face_landmarks::FaceLandmarks;
-use super::region_smoother::{RegionSmootherInterface, SmoothParams};
+use super::region_smoother::{RegionSmootherInterface, SmoothParams, SmootherSnapshot};
 
 /// Default padding factor applied symmetrically around the face.
 pub const DEFAULT_PADDING: f64 = 0.4;
@@ -15,14 +17,79 @@ pub type BBox = (f64, f64, f64, f64);
 /// Default center offset: no shift.
 pub const DEFAULT_CENTER_OFFSET: f64 = 0.0;
 
+/// Default growth on uncertainty: the fraction a region's width and height
+/// grow by when its detection is least certain.
+pub const DEFAULT_UNCERTAINTY_GROWTH: f64 = 0.25;
+
+/// Detection scores at or above this are treated as certain.
+const CONFIDENT_SCORE: f64 = 0.7;
+
+/// Detection scores at or below this get the full growth.
+const UNCERTAIN_SCORE: f64 = 0.3;
+
+/// Fraction of the growth a track keeps per frame once it is confident
+/// again, so regions shrink back over a few frames instead of snapping.
+const GROWTH_DECAY: f64 = 0.8;
+
+/// Head coverage: extra height, as a fraction of the face box height,
+/// for hair above the forehead and the jaw line below the chin.
+const HEAD_EXTRA_HEIGHT: f64 = 0.6;
+
+/// Head coverage: upward shift of the center, as a fraction of the face
+/// box height, since most of the extra height is above the face.
+const HEAD_RAISE: f64 = 0.2;
+
+/// Head coverage: extra width, as a fraction of the face box width, for
+/// ears and hair at the sides.
+const HEAD_EXTRA_WIDTH: f64 = 0.3;
+
+/// How sure the detector is of a box, for growth on uncertainty.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DetectionQuality {
+    /// Raw detection score.
+    pub confidence: f64,
+    /// The track was just picked up again after being lost.
+    pub reacquired: bool,
+}
+
+impl DetectionQuality {
+    /// A confident detection of a continuously tracked face.
+    pub const CERTAIN: Self = Self {
+        confidence: 1.0,
+        reacquired: false,
+    };
+
+    /// 0.0 for a confident, continuously tracked box, up to 1.0 for a weak
+    /// or just re-acquired one.
+    fn uncertainty(&self) -> f64 {
+        if self.reacquired {
+            return 1.0;
+        }
+        ((CONFIDENT_SCORE - self.confidence) / (CONFIDENT_SCORE - UNCERTAIN_SCORE)).clamp(0.0, 1.0)
+    }
+}
+
+/// What a region builder remembers of each track between frames.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RegionBuilderSnapshot {
+    pub smoothing: SmootherSnapshot,
+    /// Growth level per track, for builders that grow on uncertainty.
+    pub growth: Vec<(u32, f64)>,
+}
+
 /// Converts detection boxes + optional landmarks into blur regions.
 ///
 /// Handles profile-aware sizing, center blending, padding, and
-/// minimum width constraints.
+/// minimum width constraints. Optionally grows regions while detection is
+/// uncertain, to cover localization error.
 pub struct FaceRegionBuilder {
     padding: f64,
     center_offset: f64,
     smoother: Option<Box<dyn RegionSmootherInterface>>,
+    uncertainty_growth: f64,
+    /// Current growth level (0.0-1.0) per track.
+    growth_levels: HashMap<u32, f64>,
+    head_coverage: bool,
 }
 
 impl FaceRegionBuilder {
@@ -35,9 +102,27 @@ impl FaceRegionBuilder {
             padding,
             center_offset,
             smoother,
+            uncertainty_growth: 0.0,
+            growth_levels: HashMap::new(),
+            head_coverage: false,
         }
     }
 
+    /// Extends each region from the face to the whole head: up over the
+    /// hair, down over the jaw, and out over the ears.
+    pub fn with_head_coverage(mut self) -> Self {
+        self.head_coverage = true;
+        self
+    }
+
+    /// Grows regions by up to `growth` (a fraction of their size) when the
+    /// detection score is low or the track was just re-acquired. The growth
+    /// fades out over a few frames once detection is confident again.
+    pub fn with_uncertainty_growth(mut self, growth: f64) -> Self {
+        self.uncertainty_growth = growth.max(0.0);
+        self
+    }
+
     pub fn build(
         &mut self,
         bbox: BBox,
@@ -45,23 +130,93 @@ impl FaceRegionBuilder {
         frame_h: u32,
         landmarks: Option<&FaceLandmarks>,
         track_id: Option<u32>,
+    ) -> Region {
+        self.build_with_quality(
+            bbox,
+            frame_w,
+            frame_h,
+            landmarks,
+            track_id,
+            DetectionQuality::CERTAIN,
+        )
+    }
+
+    /// Like [`build`](Self::build), growing the region when `quality` is
+    /// uncertain.
+    pub fn build_with_quality(
+        &mut self,
+        bbox: BBox,
+        frame_w: u32,
+        frame_h: u32,
+        landmarks: Option<&FaceLandmarks>,
+        track_id: Option<u32>,
+        quality: DetectionQuality,
     ) -> Region {
         let profile_ratio = match landmarks {
             Some(lm) if lm.has_visible() => lm.profile_ratio(),
             _ => 0.0,
         };
 
-        let (half_w, half_h) = self.compute_half_size(bbox, profile_ratio);
-        let (cx, cy) = self.compute_center(bbox, landmarks, profile_ratio, half_w);
+        let (mut half_w, mut half_h) = self.compute_half_size(bbox, profile_ratio);
+        let (cx, mut cy) = self.compute_center(bbox, landmarks, profile_ratio, half_w);
+        if self.head_coverage {
+            let box_h = bbox.3 - bbox.1;
+            half_w += (bbox.2 - bbox.0) * HEAD_EXTRA_WIDTH / 2.0;
+            half_h += box_h * HEAD_EXTRA_HEIGHT / 2.0;
+            cy -= box_h * HEAD_RAISE;
+        }
 
         let mut params: SmoothParams = [cx, cy, half_w, half_h];
         if let Some(ref mut smoother) = self.smoother {
             params = smoother.smooth(params, track_id);
         }
 
+        // After smoothing, so growth takes effect on the uncertain frame
+        // itself rather than easing in.
+        let scale = 1.0 + self.uncertainty_growth * self.growth_level(track_id, quality);
+        params[2] *= scale;
+        params[3] *= scale;
+
         self.params_to_region(params, frame_w, frame_h, track_id)
     }
 
+    /// The smoothing and growth of each track, for resuming a run.
+    pub fn snapshot(&self) -> RegionBuilderSnapshot {
+        let mut growth: Vec<(u32, f64)> =
+            self.growth_levels.iter().map(|(&id, &g)| (id, g)).collect();
+        growth.sort_by_key(|&(id, _)| id);
+        RegionBuilderSnapshot {
+            smoothing: self
+                .smoother
+                .as_ref()
+                .map_or_else(Vec::new, |s| s.snapshot()),
+            growth,
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: RegionBuilderSnapshot) {
+        if let Some(ref mut smoother) = self.smoother {
+            smoother.restore(snapshot.smoothing);
+        }
+        self.growth_levels = snapshot.growth.into_iter().collect();
+    }
+
+    /// Jumps up to the current uncertainty, then decays while the track
+    /// stays confident.
+    fn growth_level(&mut self, track_id: Option<u32>, quality: DetectionQuality) -> f64 {
+        if self.uncertainty_growth == 0.0 {
+            return 0.0;
+        }
+        let uncertainty = quality.uncertainty();
+        let Some(tid) = track_id else {
+            return uncertainty;
+        };
+        let previous = self.growth_levels.get(&tid).copied().unwrap_or(0.0);
+        let level = uncertainty.max(previous * GROWTH_DECAY);
+        self.growth_levels.insert(tid, level);
+        level
+    }
+
     fn compute_center(
         &self,
         bbox: BBox,
@@ -214,6 +369,21 @@ mod tests {
         assert!(region_contains_point(&r, bbox.2, bbox.3));
     }
 
+    #[test]
+    fn test_head_coverage_extends_mostly_upward() {
+        let face = builder().build(frontal_box(), FRAME_W, FRAME_H, None, None);
+        let head =
+            builder()
+                .with_head_coverage()
+                .build(frontal_box(), FRAME_W, FRAME_H, None, None);
+
+        let above = face.y - head.y;
+        let below = (head.y + head.height) - (face.y + face.height);
+        assert!(head.width > face.width);
+        assert!(above > 0 && below > 0);
+        assert!(above > below * 3, "above {above}, below {below}");
+    }
+
     // ── Profile face ────────────────────────────────────────────────
 
     #[test]
@@ -512,4 +682,109 @@ mod tests {
         assert_eq!(r.full_width.unwrap(), 200);
         assert_eq!(r.full_height.unwrap(), 200);
     }
+
+    // ── Growth on uncertainty ───────────────────────────────────────
+
+    fn low_confidence() -> DetectionQuality {
+        DetectionQuality {
+            confidence: UNCERTAIN_SCORE,
+            reacquired: false,
+        }
+    }
+
+    #[test]
+    fn test_growth_off_by_default() {
+        let mut b = builder();
+        let certain = b.build(frontal_box(), FRAME_W, FRAME_H, None, Some(1));
+        let weak = b.build_with_quality(
+            frontal_box(),
+            FRAME_W,
+            FRAME_H,
+            None,
+            Some(2),
+            low_confidence(),
+        );
+        assert_eq!(certain.full_width, weak.full_width);
+    }
+
+    #[test]
+    fn test_confident_detection_does_not_grow() {
+        let mut b = builder().with_uncertainty_growth(0.5);
+        let r = b.build(frontal_box(), FRAME_W, FRAME_H, None, Some(1));
+        // half_w = 200 * 1.4 / 2 = 140
+        assert_eq!(r.full_width.unwrap(), 280);
+    }
+
+    #[rstest]
+    #[case(DetectionQuality { confidence: 0.3, reacquired: false }, 420.0)]
+    #[case(DetectionQuality { confidence: 0.5, reacquired: false }, 350.0)]
+    #[case(DetectionQuality { confidence: 0.9, reacquired: true }, 420.0)]
+    fn test_uncertain_detection_grows(
+        #[case] quality: DetectionQuality,
+        #[case] expected_width: f64,
+    ) {
+        let mut b = builder().with_uncertainty_growth(0.5);
+        let r = b.build_with_quality(frontal_box(), FRAME_W, FRAME_H, None, Some(1), quality);
+        // Full growth: half_w = 140 * 1.5 = 210
+        assert_relative_eq!(r.full_width.unwrap() as f64, expected_width, epsilon = 1.0);
+    }
+
+    #[test]
+    fn test_growth_shrinks_back_once_confident() {
+        let mut b = builder().with_uncertainty_growth(0.5);
+        let grown = b.build_with_quality(
+            frontal_box(),
+            FRAME_W,
+            FRAME_H,
+            None,
+            Some(1),
+            low_confidence(),
+        );
+        let widths: Vec<i32> = (0..30)
+            .map(|_| {
+                b.build(frontal_box(), FRAME_W, FRAME_H, None, Some(1))
+                    .full_width
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(widths[0] < grown.full_width.unwrap());
+        assert!(widths[0] > 280, "should shrink gradually, not snap back");
+        assert!(widths.windows(2).all(|w| w[1] <= w[0]));
+        assert_eq!(*widths.last().unwrap(), 280);
+    }
+
+    #[test]
+    fn test_growth_is_per_track() {
+        let mut b = builder().with_uncertainty_growth(0.5);
+        b.build_with_quality(
+            frontal_box(),
+            FRAME_W,
+            FRAME_H,
+            None,
+            Some(1),
+            low_confidence(),
+        );
+        let other = b.build(frontal_box(), FRAME_W, FRAME_H, None, Some(2));
+        assert_eq!(other.full_width.unwrap(), 280);
+    }
+
+    #[test]
+    fn test_restored_builder_keeps_growth() {
+        let mut b = builder().with_uncertainty_growth(0.5);
+        b.build_with_quality(
+            frontal_box(),
+            FRAME_W,
+            FRAME_H,
+            None,
+            Some(1),
+            low_confidence(),
+        );
+        let mut restored = builder().with_uncertainty_growth(0.5);
+        restored.restore(b.snapshot());
+
+        let expected = b.build(frontal_box(), FRAME_W, FRAME_H, None, Some(1));
+        let resumed = restored.build(frontal_box(), FRAME_W, FRAME_H, None, Some(1));
+        assert_eq!(resumed, expected);
+    }
 }
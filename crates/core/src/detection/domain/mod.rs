@@ -1,6 +1,17 @@
+pub mod blur_target;
+pub mod confidence_calibration;
+pub mod detection_zone;
+pub mod detector_backend;
 pub mod face_detector;
+pub mod face_embedder;
 pub mod face_grouper;
 pub mod face_landmarks;
+pub mod face_observation;
 pub mod face_region_builder;
+pub mod mouth_activity;
+pub mod person_region_builder;
 pub mod region_merger;
 pub mod region_smoother;
+pub mod text_detector;
+pub mod text_matcher;
+pub mod track_duration_filter;
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// Per-track mouth activity as `(frame index, activity)` pairs in frame
+/// order. Activity runs from 0 (still) to 1.
+pub type MouthActivity = HashMap<u32, Vec<(usize, f64)>>;
+
+const GRID_WIDTH: usize = 12;
+const GRID_HEIGHT: usize = 4;
+
+/// Vertical bands of a blur region, as fractions of its height. Regions are
+/// padded around the face, so the eyes sit near the top third and the
+/// mouth near the bottom third.
+const UPPER_BAND: (f64, f64) = (0.2, 0.45);
+const LOWER_BAND: (f64, f64) = (0.55, 0.8);
+/// Horizontal extent of both bands, leaving out the cheeks and background.
+const CENTER_BAND: (f64, f64) = (0.25, 0.75);
+
+/// Coarse luma samples of a face's eye band and mouth band, compared across
+/// consecutive frames to tell a talking face from a still one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FacePatch {
+    upper: Vec<f64>,
+    lower: Vec<f64>,
+}
+
+impl FacePatch {
+    /// Samples `region` of `frame`, or `None` when the region is too small
+    /// to sample or the frame is not RGB.
+    pub fn sample(frame: &Frame, region: &Region) -> Option<Self> {
+        if frame.channels() < 3
+            || (region.width as usize) < GRID_WIDTH
+            || (region.height as usize) < GRID_HEIGHT * 4
+        {
+            return None;
+        }
+        Some(Self {
+            upper: sample_band(frame, region, UPPER_BAND),
+            lower: sample_band(frame, region, LOWER_BAND),
+        })
+    }
+
+    /// How much the mouth band changed since `previous`, minus how much the
+    /// eye band changed, so head turns and camera motion, which move both,
+    /// largely cancel out.
+    pub fn activity_since(&self, previous: &FacePatch) -> f64 {
+        (mean_difference(&self.lower, &previous.lower)
+            - mean_difference(&self.upper, &previous.upper))
+        .max(0.0)
+    }
+}
+
+fn sample_band(frame: &Frame, region: &Region, band: (f64, f64)) -> Vec<f64> {
+    let data = frame.data();
+    let stride = frame.width() as usize * frame.channels() as usize;
+    let channels = frame.channels() as usize;
+    let (w, h) = (region.width as f64, region.height as f64);
+    let mut samples = Vec::with_capacity(GRID_WIDTH * GRID_HEIGHT);
+    for gy in 0..GRID_HEIGHT {
+        let fy = band.0 + (band.1 - band.0) * (gy as f64 + 0.5) / GRID_HEIGHT as f64;
+        let y = (region.y as f64 + fy * h) as usize;
+        for gx in 0..GRID_WIDTH {
+            let fx = CENTER_BAND.0
+                + (CENTER_BAND.1 - CENTER_BAND.0) * (gx as f64 + 0.5) / GRID_WIDTH as f64;
+            let x = (region.x as f64 + fx * w) as usize;
+            let i = y.min(frame.height() as usize - 1) * stride
+                + x.min(frame.width() as usize - 1) * channels;
+            let luma =
+                0.299 * data[i] as f64 + 0.587 * data[i + 1] as f64 + 0.114 * data[i + 2] as f64;
+            samples.push(luma / 255.0);
+        }
+    }
+    samples
+}
+
+fn mean_difference(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y).abs()).sum::<f64>() / a.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    const SIZE: u32 = 100;
+
+    fn region() -> Region {
+        Region {
+            x: 0,
+            y: 0,
+            width: SIZE as i32,
+            height: SIZE as i32,
+            track_id: Some(1),
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    /// A gray frame whose rows in `fill` (as fractions of height) are `value`.
+    fn frame(fills: &[((f64, f64), u8)]) -> Frame {
+        let mut data = vec![128u8; (SIZE * SIZE * 3) as usize];
+        for &((top, bottom), value) in fills {
+            let rows = (top * SIZE as f64) as usize..(bottom * SIZE as f64) as usize;
+            for row in rows {
+                let start = row * SIZE as usize * 3;
+                data[start..start + SIZE as usize * 3].fill(value);
+            }
+        }
+        Frame::new(data, SIZE, SIZE, 3, 0)
+    }
+
+    #[test]
+    fn test_still_face_has_no_activity() {
+        let a = FacePatch::sample(&frame(&[]), &region()).unwrap();
+        let b = FacePatch::sample(&frame(&[]), &region()).unwrap();
+        assert_relative_eq!(b.activity_since(&a), 0.0);
+    }
+
+    #[test]
+    fn test_mouth_change_is_activity() {
+        let a = FacePatch::sample(&frame(&[]), &region()).unwrap();
+        let b = FacePatch::sample(&frame(&[(LOWER_BAND, 0)]), &region()).unwrap();
+        assert!(b.activity_since(&a) > 0.4);
+    }
+
+    #[test]
+    fn test_whole_face_change_cancels_out() {
+        let a = FacePatch::sample(&frame(&[]), &region()).unwrap();
+        let b = FacePatch::sample(&frame(&[((0.0, 1.0), 0)]), &region()).unwrap();
+        assert_relative_eq!(b.activity_since(&a), 0.0);
+    }
+
+    #[test]
+    fn test_eye_change_alone_is_not_activity() {
+        let a = FacePatch::sample(&frame(&[]), &region()).unwrap();
+        let b = FacePatch::sample(&frame(&[(UPPER_BAND, 0)]), &region()).unwrap();
+        assert_relative_eq!(b.activity_since(&a), 0.0);
+    }
+
+    #[test]
+    fn test_tiny_region_is_not_sampled() {
+        let tiny = Region {
+            width: 8,
+            height: 8,
+            ..region()
+        };
+        assert!(FacePatch::sample(&frame(&[]), &tiny).is_none());
+    }
+}
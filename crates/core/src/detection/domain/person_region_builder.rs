@@ -0,0 +1,105 @@
+use crate::shared::region::Region;
+
+use super::face_region_builder::{BBox, RegionBuilderSnapshot};
+use super::region_smoother::{RegionSmootherInterface, SmoothParams};
+
+/// Default padding for person regions: loose enough to cover arms and
+/// hair that stick out of the detector's box.
+pub const DEFAULT_PERSON_PADDING: f64 = 0.1;
+
+/// Converts person detection boxes into blur regions.
+///
+/// Unlike [`FaceRegionBuilder`](super::face_region_builder::FaceRegionBuilder)
+/// there are no landmarks to steer by: the region is the box itself, padded
+/// on every side and optionally smoothed per track. Clamping keeps the
+/// unclamped geometry, as for faces.
+pub struct PersonRegionBuilder {
+    padding: f64,
+    smoother: Option<Box<dyn RegionSmootherInterface>>,
+}
+
+impl PersonRegionBuilder {
+    pub fn new(padding: f64, smoother: Option<Box<dyn RegionSmootherInterface>>) -> Self {
+        Self {
+            padding: padding.max(0.0),
+            smoother,
+        }
+    }
+
+    /// The smoothing of each track, for resuming a run.
+    pub fn snapshot(&self) -> RegionBuilderSnapshot {
+        RegionBuilderSnapshot {
+            smoothing: self
+                .smoother
+                .as_ref()
+                .map_or_else(Vec::new, |s| s.snapshot()),
+            growth: Vec::new(),
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: RegionBuilderSnapshot) {
+        if let Some(ref mut smoother) = self.smoother {
+            smoother.restore(snapshot.smoothing);
+        }
+    }
+
+    pub fn build(
+        &mut self,
+        bbox: BBox,
+        frame_w: u32,
+        frame_h: u32,
+        track_id: Option<u32>,
+    ) -> Region {
+        let cx = (bbox.0 + bbox.2) / 2.0;
+        let cy = (bbox.1 + bbox.3) / 2.0;
+        let half_w = (bbox.2 - bbox.0) * (1.0 + self.padding) / 2.0;
+        let half_h = (bbox.3 - bbox.1) * (1.0 + self.padding) / 2.0;
+
+        let mut params: SmoothParams = [cx, cy, half_w, half_h];
+        if let Some(ref mut smoother) = self.smoother {
+            params = smoother.smooth(params, track_id);
+        }
+        let [cx, cy, half_w, half_h] = params;
+
+        let ux = (cx - half_w) as i32;
+        let uy = (cy - half_h) as i32;
+        let x = ux.max(0);
+        let y = uy.max(0);
+        Region {
+            x,
+            y,
+            width: ((cx + half_w).min(frame_w as f64) as i32 - x).max(0),
+            height: ((cy + half_h).min(frame_h as f64) as i32 - y).max(0),
+            track_id,
+            full_width: Some((half_w * 2.0) as i32),
+            full_height: Some((half_h * 2.0) as i32),
+            unclamped_x: Some(ux),
+            unclamped_y: Some(uy),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pads_box_on_every_side() {
+        let mut builder = PersonRegionBuilder::new(0.2, None);
+        let r = builder.build((100.0, 100.0, 200.0, 400.0), 1000, 1000, Some(3));
+
+        assert_eq!((r.x, r.y, r.width, r.height), (90, 70, 120, 360));
+        assert_eq!(r.track_id, Some(3));
+    }
+
+    #[test]
+    fn test_clamps_to_frame_but_keeps_full_size() {
+        let mut builder = PersonRegionBuilder::new(0.0, None);
+        let r = builder.build((-50.0, 600.0, 150.0, 1200.0), 1000, 1000, None);
+
+        assert_eq!((r.x, r.y, r.width, r.height), (0, 600, 150, 400));
+        assert_eq!(r.full_width, Some(200));
+        assert_eq!(r.full_height, Some(600));
+        assert_eq!(r.unclamped_x, Some(-50));
+    }
+}
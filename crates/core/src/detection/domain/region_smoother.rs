@@ -3,9 +3,21 @@ use std::collections::HashMap;
 /// Smoothing parameters: [cx, cy, half_w, half_h].
 pub type SmoothParams = [f64; 4];
 
+/// The last smoothed parameters of each track, by track ID.
+pub type SmootherSnapshot = Vec<(u32, SmoothParams)>;
+
 /// Domain interface for temporal smoothing of region parameters.
 pub trait RegionSmootherInterface: Send {
     fn smooth(&mut self, params: SmoothParams, track_id: Option<u32>) -> SmoothParams;
+
+    /// What the smoother remembers of each track, for resuming a run.
+    fn snapshot(&self) -> SmootherSnapshot {
+        Vec::new()
+    }
+
+    /// Continues from a [`snapshot`](Self::snapshot), forgetting the
+    /// tracks seen so far.
+    fn restore(&mut self, _snapshot: SmootherSnapshot) {}
 }
 
 /// EMA (Exponential Moving Average) smoother with per-track state.
@@ -54,6 +66,16 @@ impl RegionSmootherInterface for RegionSmoother {
         self.state.insert(tid, smoothed);
         smoothed
     }
+
+    fn snapshot(&self) -> SmootherSnapshot {
+        let mut snapshot: SmootherSnapshot = self.state.iter().map(|(&id, &p)| (id, p)).collect();
+        snapshot.sort_by_key(|&(id, _)| id);
+        snapshot
+    }
+
+    fn restore(&mut self, snapshot: SmootherSnapshot) {
+        self.state = snapshot.into_iter().collect();
+    }
 }
 
 #[cfg(test)]
@@ -164,4 +186,18 @@ mod tests {
         // alpha=1: ema = 1*current + 0*prev = current
         assert_eq!(result, second);
     }
+
+    #[test]
+    fn test_restored_smoother_continues_tracks() {
+        let mut smoother = RegionSmoother::new(0.6);
+        smoother.smooth([100.0, 200.0, 50.0, 60.0], Some(1));
+        let mut restored = RegionSmoother::new(0.6);
+        restored.restore(smoother.snapshot());
+
+        let next = [110.0, 210.0, 55.0, 65.0];
+        assert_eq!(
+            restored.smooth(next, Some(1)),
+            smoother.smooth(next, Some(1))
+        );
+    }
 }
@@ -0,0 +1,17 @@
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// A line or block of text found on a frame, and what it says when the
+/// detector can read it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextBox {
+    pub region: Region,
+    pub text: Option<String>,
+}
+
+/// Domain interface for finding text (signs, documents, screens) on a frame.
+///
+/// `&mut self` so implementations can reuse buffers between frames.
+pub trait TextDetector: Send {
+    fn detect_text(&mut self, frame: &Frame) -> Result<Vec<TextBox>, Box<dyn std::error::Error>>;
+}
@@ -0,0 +1,124 @@
+use regex::Regex;
+
+/// Which detected text to redact.
+#[derive(Clone, Debug)]
+pub enum TextPattern {
+    /// Any text, read or not.
+    Any,
+    /// Text containing this keyword, compared case-insensitively.
+    Keyword(String),
+    Regex(Regex),
+}
+
+impl TextPattern {
+    /// Parses `*` (any text), `/REGEX/`, or a keyword.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        if spec == "*" {
+            return Ok(Self::Any);
+        }
+        if let Some(pattern) = spec
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_suffix('/'))
+        {
+            return Regex::new(pattern)
+                .map(Self::Regex)
+                .map_err(|e| format!("Invalid text pattern '{spec}': {e}"));
+        }
+        if spec.is_empty() {
+            return Err("Text keyword must not be empty".to_string());
+        }
+        Ok(Self::Keyword(spec.to_lowercase()))
+    }
+
+    /// Whether the text has to be read (OCR) to decide.
+    pub fn needs_reading(&self) -> bool {
+        !matches!(self, Self::Any)
+    }
+
+    /// `text` is `None` when the detector did not read the box, which only
+    /// [`TextPattern::Any`] accepts.
+    pub fn matches(&self, text: Option<&str>) -> bool {
+        match (self, text) {
+            (Self::Any, _) => true,
+            (Self::Keyword(keyword), Some(text)) => text.to_lowercase().contains(keyword),
+            (Self::Regex(regex), Some(text)) => regex.is_match(text),
+            (_, None) => false,
+        }
+    }
+}
+
+/// The patterns given with `--redact-text`; a box is redacted when any of
+/// them matches.
+#[derive(Clone, Debug, Default)]
+pub struct TextMatcher {
+    patterns: Vec<TextPattern>,
+}
+
+impl TextMatcher {
+    pub fn parse(specs: &[String]) -> Result<Self, String> {
+        let patterns = specs
+            .iter()
+            .map(|spec| TextPattern::parse(spec))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { patterns })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    pub fn needs_reading(&self) -> bool {
+        self.patterns.iter().any(TextPattern::needs_reading)
+    }
+
+    pub fn matches(&self, text: Option<&str>) -> bool {
+        self.patterns.iter().any(|p| p.matches(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn matcher(specs: &[&str]) -> TextMatcher {
+        let specs: Vec<String> = specs.iter().map(|s| s.to_string()).collect();
+        TextMatcher::parse(&specs).unwrap()
+    }
+
+    #[rstest]
+    #[case("acme", Some("ACME Corp"), true)]
+    #[case("acme", Some("Main Street"), false)]
+    #[case("acme", None, false)]
+    #[case("/\\d{3}-\\d{4}/", Some("Call 555-1234"), true)]
+    #[case("/\\d{3}-\\d{4}/", Some("Call us"), false)]
+    #[case("*", None, true)]
+    #[case("*", Some("anything"), true)]
+    fn test_pattern_matches(#[case] spec: &str, #[case] text: Option<&str>, #[case] hit: bool) {
+        assert_eq!(TextPattern::parse(spec).unwrap().matches(text), hit);
+    }
+
+    #[test]
+    fn test_only_keywords_and_regexes_need_reading() {
+        assert!(!matcher(&["*"]).needs_reading());
+        assert!(matcher(&["*", "acme"]).needs_reading());
+        assert!(matcher(&["/x+/"]).needs_reading());
+    }
+
+    #[test]
+    fn test_any_pattern_redacts() {
+        let m = matcher(&["acme", "/^EXIT$/"]);
+        assert!(m.matches(Some("EXIT")));
+        assert!(m.matches(Some("acme")));
+        assert!(!m.matches(Some("exit")));
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("  ")]
+    #[case("/[unclosed/")]
+    fn test_parse_rejects(#[case] spec: &str) {
+        assert!(TextPattern::parse(spec).is_err());
+    }
+}
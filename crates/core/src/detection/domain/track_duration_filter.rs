@@ -0,0 +1,180 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::shared::region::Region;
+
+/// Tracks seen on fewer frames than this are treated as false positives.
+/// Three frames is 0.1 s at 30 fps: long enough to drop single-frame hits
+/// on posters, statues, and patterns, short enough to keep a real face
+/// crossing the shot.
+pub const DEFAULT_MIN_TRACK_FRAMES: usize = 3;
+
+/// Drops tracks that are seen on fewer than `min_frames` frames in total.
+///
+/// Single-frame false positives otherwise show up as junk thumbnails and
+/// as blur flashes in the output. A `min_frames` of 0 or 1 keeps every
+/// track, for paranoid mode. Regions without a track ID are always kept.
+///
+/// While streaming, only the frames up to the end of the lookahead window
+/// are known, so a track is dropped only once it is known to be short: it
+/// is missing from the last buffered frame and its frames so far fall short
+/// of the minimum. Anything that may still grow is kept, and a lookahead of
+/// fewer than `min_frames - 1` frames weakens the filter rather than
+/// risking an unblurred face.
+#[derive(Clone, Debug, Default)]
+pub struct TrackDurationFilter {
+    min_frames: usize,
+    seen: HashMap<u32, usize>,
+}
+
+impl TrackDurationFilter {
+    pub fn new(min_frames: usize) -> Self {
+        Self {
+            min_frames,
+            seen: HashMap::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.min_frames > 1
+    }
+
+    /// Tracks in `window` (the current frame, then its lookahead frames)
+    /// that are known to end short of the minimum.
+    pub fn short_tracks(&self, window: &[&[Region]]) -> HashSet<u32> {
+        let Some(last) = window.last() else {
+            return HashSet::new();
+        };
+        if !self.is_enabled() {
+            return HashSet::new();
+        }
+        let still_visible: HashSet<u32> = track_ids(last).collect();
+        let mut in_window: HashMap<u32, usize> = HashMap::new();
+        for regions in window {
+            for track_id in track_ids(regions).collect::<HashSet<_>>() {
+                *in_window.entry(track_id).or_default() += 1;
+            }
+        }
+        in_window
+            .into_iter()
+            .filter(|(id, count)| {
+                let total = count + self.seen.get(id).copied().unwrap_or(0);
+                !still_visible.contains(id) && total < self.min_frames
+            })
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Counts the frame leaving the window, before any tracks are dropped
+    /// from it.
+    pub fn record(&mut self, regions: &[Region]) {
+        for track_id in track_ids(regions).collect::<HashSet<_>>() {
+            *self.seen.entry(track_id).or_default() += 1;
+        }
+    }
+}
+
+/// Tracks seen on fewer than `min_frames` frames of a complete scan.
+pub fn short_tracks_in(
+    detections: &HashMap<usize, Vec<Region>>,
+    min_frames: usize,
+) -> HashSet<u32> {
+    let mut counts: HashMap<u32, usize> = HashMap::new();
+    for regions in detections.values() {
+        for track_id in track_ids(regions).collect::<HashSet<_>>() {
+            *counts.entry(track_id).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .filter(|&(_, count)| count < min_frames)
+        .map(|(track_id, _)| track_id)
+        .collect()
+}
+
+fn track_ids(regions: &[Region]) -> impl Iterator<Item = u32> + '_ {
+    regions.iter().filter_map(|r| r.track_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(track_id: Option<u32>) -> Region {
+        Region {
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 10,
+            track_id,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    /// Frames holding one region per listed track.
+    fn frames(tracks: &[&[u32]]) -> Vec<Vec<Region>> {
+        tracks
+            .iter()
+            .map(|ids| ids.iter().map(|&id| region(Some(id))).collect())
+            .collect()
+    }
+
+    fn window(frames: &[Vec<Region>]) -> Vec<&[Region]> {
+        frames.iter().map(Vec::as_slice).collect()
+    }
+
+    #[test]
+    fn test_single_frame_track_is_short() {
+        let filter = TrackDurationFilter::new(3);
+        let frames = frames(&[&[1, 2], &[2], &[2], &[]]);
+        assert_eq!(filter.short_tracks(&window(&frames)), HashSet::from([1]));
+    }
+
+    #[test]
+    fn test_track_still_visible_at_window_end_is_kept() {
+        let filter = TrackDurationFilter::new(5);
+        let frames = frames(&[&[1], &[1]]);
+        assert!(filter.short_tracks(&window(&frames)).is_empty());
+    }
+
+    #[test]
+    fn test_recorded_frames_count_toward_minimum() {
+        let mut filter = TrackDurationFilter::new(3);
+        let past = frames(&[&[1], &[1]]);
+        for regions in &past {
+            filter.record(regions);
+        }
+        let frames = frames(&[&[1], &[]]);
+        assert!(filter.short_tracks(&window(&frames)).is_empty());
+    }
+
+    #[test]
+    fn test_disabled_filter_keeps_everything() {
+        let frames = frames(&[&[1], &[]]);
+        assert!(TrackDurationFilter::new(1)
+            .short_tracks(&window(&frames))
+            .is_empty());
+        assert!(TrackDurationFilter::new(0)
+            .short_tracks(&window(&frames))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_untracked_regions_are_never_short() {
+        let filter = TrackDurationFilter::new(3);
+        let frames = vec![vec![region(None)], vec![]];
+        assert!(filter.short_tracks(&window(&frames)).is_empty());
+    }
+
+    #[test]
+    fn test_short_tracks_in_complete_scan() {
+        let detections: HashMap<usize, Vec<Region>> = frames(&[&[1, 2], &[2], &[2, 3], &[3]])
+            .into_iter()
+            .enumerate()
+            .collect();
+        assert_eq!(short_tracks_in(&detections, 3), HashSet::from([1, 3]));
+        assert!(short_tracks_in(&detections, 1).is_empty());
+    }
+}
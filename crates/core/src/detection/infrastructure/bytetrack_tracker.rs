@@ -19,6 +19,9 @@ pub struct Track {
     pub id: u32,
     pub bbox: [f64; 4],
     pub det_index: Option<usize>,
+    /// Matched this frame after being lost for at least one frame, so its
+    /// box may still be catching up with the face.
+    pub reacquired: bool,
 }
 
 const HIGH_THRESH: f64 = 0.5;
@@ -30,9 +33,26 @@ struct TrackState {
     bbox: [f64; 4],
     frames_lost: usize,
     matched: bool,
+    reacquired: bool,
     det_index: Option<usize>,
 }
 
+/// A track the tracker still remembers, matched or not.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackSnapshot {
+    pub id: u32,
+    pub bbox: [f64; 4],
+    pub frames_lost: usize,
+}
+
+/// Everything the tracker carries from one frame to the next, so an
+/// interrupted run can go on with the same track IDs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackerSnapshot {
+    pub tracks: Vec<TrackSnapshot>,
+    pub next_id: u32,
+}
+
 pub struct ByteTracker {
     tracks: Vec<TrackState>,
     next_id: u32,
@@ -61,9 +81,43 @@ impl ByteTracker {
         self.active_tracks()
     }
 
+    pub fn snapshot(&self) -> TrackerSnapshot {
+        TrackerSnapshot {
+            tracks: self
+                .tracks
+                .iter()
+                .map(|t| TrackSnapshot {
+                    id: t.id,
+                    bbox: t.bbox,
+                    frames_lost: t.frames_lost,
+                })
+                .collect(),
+            next_id: self.next_id,
+        }
+    }
+
+    /// Continues from a [`snapshot`](Self::snapshot), replacing the
+    /// current tracks.
+    pub fn restore(&mut self, snapshot: TrackerSnapshot) {
+        self.tracks = snapshot
+            .tracks
+            .into_iter()
+            .map(|t| TrackState {
+                id: t.id,
+                bbox: t.bbox,
+                frames_lost: t.frames_lost,
+                matched: false,
+                reacquired: false,
+                det_index: None,
+            })
+            .collect();
+        self.next_id = snapshot.next_id;
+    }
+
     fn reset_match_flags(&mut self) {
         for track in &mut self.tracks {
             track.matched = false;
+            track.reacquired = false;
             track.det_index = None;
         }
     }
@@ -104,6 +158,7 @@ impl ByteTracker {
 
     fn apply_match(&mut self, track_idx: usize, det_idx: usize, bbox: &[f64; 4]) {
         self.tracks[track_idx].bbox = *bbox;
+        self.tracks[track_idx].reacquired = self.tracks[track_idx].frames_lost > 0;
         self.tracks[track_idx].frames_lost = 0;
         self.tracks[track_idx].matched = true;
         self.tracks[track_idx].det_index = Some(det_idx);
@@ -122,6 +177,7 @@ impl ByteTracker {
                     bbox: detections[*di].bbox,
                     frames_lost: 0,
                     matched: true,
+                    reacquired: false,
                     det_index: Some(*di),
                 });
                 self.next_id += 1;
@@ -149,6 +205,7 @@ impl ByteTracker {
                 id: t.id,
                 bbox: t.bbox,
                 det_index: t.det_index,
+                reacquired: t.reacquired,
             })
             .collect()
     }
@@ -256,6 +313,17 @@ mod tests {
         let t2 = tracker.update(&[det(12.0, 12.0, 62.0, 62.0, 0.9)]);
         assert_eq!(t2.len(), 1);
         assert_eq!(t2[0].id, id);
+        assert!(t2[0].reacquired);
+    }
+
+    #[test]
+    fn test_continuous_track_is_not_reacquired() {
+        let mut tracker = ByteTracker::new(3);
+        let t1 = tracker.update(&[det(10.0, 10.0, 60.0, 60.0, 0.9)]);
+        assert!(!t1[0].reacquired);
+
+        let t2 = tracker.update(&[det(12.0, 12.0, 62.0, 62.0, 0.9)]);
+        assert!(!t2[0].reacquired);
     }
 
     #[test]
@@ -305,6 +373,23 @@ mod tests {
         assert!(ids.contains(&id_b));
     }
 
+    #[test]
+    fn test_restored_tracker_keeps_ids() {
+        let mut tracker = ByteTracker::new(5);
+        let t1 = tracker.update(&[det(10.0, 10.0, 60.0, 60.0, 0.9)]);
+        tracker.update(&[]);
+        let mut restored = ByteTracker::new(5);
+        restored.restore(tracker.snapshot());
+
+        let t2 = restored.update(&[
+            det(12.0, 12.0, 62.0, 62.0, 0.9),
+            det(200.0, 200.0, 250.0, 250.0, 0.9),
+        ]);
+        assert_eq!(t2[0].id, t1[0].id);
+        assert!(t2[0].reacquired);
+        assert_eq!(t2[1].id, t1[0].id + 1, "new tracks continue the numbering");
+    }
+
     #[test]
     fn test_iou_bbox_no_overlap() {
         let a = [0.0, 0.0, 10.0, 10.0];
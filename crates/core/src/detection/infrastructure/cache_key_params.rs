@@ -0,0 +1,172 @@
+use std::fmt;
+
+/// A setting that goes into a detection cache key. Changing one means the
+/// faces are detected again, with new track IDs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CacheKeyParam {
+    Model,
+    Target,
+    Confidence,
+    Padding,
+    CenterOffset,
+    UncertaintyGrowth,
+    SkipFrames,
+    BakeRotation,
+    OnDecodeError,
+    CutList,
+    DetectZones,
+}
+
+impl CacheKeyParam {
+    pub const ALL: &[CacheKeyParam] = &[
+        Self::Model,
+        Self::Target,
+        Self::Confidence,
+        Self::Padding,
+        Self::CenterOffset,
+        Self::UncertaintyGrowth,
+        Self::SkipFrames,
+        Self::BakeRotation,
+        Self::OnDecodeError,
+        Self::CutList,
+        Self::DetectZones,
+    ];
+
+    /// The param written as `name`, if this build knows it.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|param| param.name() == name)
+    }
+
+    /// Name written into the key string.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Model => "model",
+            Self::Target => "target",
+            Self::Confidence => "confidence",
+            Self::Padding => "padding",
+            Self::CenterOffset => "center_offset",
+            Self::UncertaintyGrowth => "uncertainty_growth",
+            Self::SkipFrames => "skip_frames",
+            Self::BakeRotation => "bake_rotation",
+            Self::OnDecodeError => "on_decode_error",
+            Self::CutList => "cut_list",
+            Self::DetectZones => "detect_zones",
+        }
+    }
+}
+
+/// The settings a detection cache key is built from, with their values.
+///
+/// Formats as the `name=value ...` string passed to
+/// [`DetectionDiskCache::key`](super::detection_disk_cache::DetectionDiskCache::key).
+/// Kept by name so an app can tell which settings a scan depends on, and
+/// which of them a change would invalidate, before the change is made.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CacheKeyParams {
+    values: Vec<(CacheKeyParam, String)>,
+}
+
+impl CacheKeyParams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `param` with `value`, after the ones added before.
+    pub fn with(mut self, param: CacheKeyParam, value: impl fmt::Display) -> Self {
+        self.values.push((param, value.to_string()));
+        self
+    }
+
+    /// Whether the key depends on `param`.
+    pub fn contains(&self, param: CacheKeyParam) -> bool {
+        self.values.iter().any(|&(p, _)| p == param)
+    }
+
+    /// Each param with its value, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = (CacheKeyParam, &str)> {
+        self.values
+            .iter()
+            .map(|(param, value)| (*param, value.as_str()))
+    }
+
+    fn value(&self, param: CacheKeyParam) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|&&(p, _)| p == param)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Params whose value differs from `other`'s, then params only `other`
+    /// has.
+    pub fn changed(&self, other: &Self) -> Vec<CacheKeyParam> {
+        let mut changed: Vec<CacheKeyParam> = self
+            .values
+            .iter()
+            .map(|&(param, _)| param)
+            .filter(|&param| self.value(param) != other.value(param))
+            .collect();
+        changed.extend(
+            other
+                .values
+                .iter()
+                .map(|&(param, _)| param)
+                .filter(|&param| !self.contains(param)),
+        );
+        changed
+    }
+}
+
+impl fmt::Display for CacheKeyParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (param, value)) in self.values.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}={value}", param.name())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(confidence: f64, skip_frames: u32) -> CacheKeyParams {
+        CacheKeyParams::new()
+            .with(CacheKeyParam::Model, "yolo11n-pose_widerface.onnx")
+            .with(CacheKeyParam::Confidence, confidence)
+            .with(CacheKeyParam::SkipFrames, skip_frames)
+    }
+
+    #[test]
+    fn test_formats_as_key_string() {
+        assert_eq!(
+            params(0.5, 2).to_string(),
+            "model=yolo11n-pose_widerface.onnx confidence=0.5 skip_frames=2"
+        );
+    }
+
+    #[test]
+    fn test_names_round_trip() {
+        for &param in CacheKeyParam::ALL {
+            assert_eq!(CacheKeyParam::from_name(param.name()), Some(param));
+        }
+        assert_eq!(CacheKeyParam::from_name("unknown"), None);
+    }
+
+    #[test]
+    fn test_changed_lists_differing_params() {
+        assert_eq!(
+            params(0.5, 2).changed(&params(0.4, 2)),
+            vec![CacheKeyParam::Confidence]
+        );
+        assert!(params(0.5, 2).changed(&params(0.5, 2)).is_empty());
+    }
+
+    #[test]
+    fn test_changed_includes_params_only_one_side_has() {
+        let more = params(0.5, 2).with(CacheKeyParam::Target, "face");
+        assert_eq!(params(0.5, 2).changed(&more), vec![CacheKeyParam::Target]);
+    }
+}
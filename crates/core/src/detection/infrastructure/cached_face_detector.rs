@@ -1,7 +1,13 @@
 use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
 use std::sync::Arc;
 
+use serde_json::Value;
+
 use crate::detection::domain::face_detector::FaceDetector;
+use crate::detection::infrastructure::disk_backed_detections::DiskBackedDetections;
+use crate::pipeline::detection_report::REPORT_SCHEMA;
 use crate::shared::frame::Frame;
 use crate::shared::region::Region;
 
@@ -10,25 +16,135 @@ use crate::shared::region::Region;
 /// Used when a preview pass has already detected all faces — the blur pass
 /// can reuse those exact regions, guaranteeing that track IDs match what
 /// the user selected in the preview UI.
+///
+/// `from_json_file` loads a saved detection pass, so a video can be scanned
+/// once and blurred many times with different settings.
 pub struct CachedFaceDetector {
-    cache: Arc<HashMap<usize, Vec<Region>>>,
+    cache: Cache,
+}
+
+enum Cache {
+    Shared(Arc<HashMap<usize, Vec<Region>>>),
+    DiskBacked(DiskBackedDetections),
 }
 
 impl CachedFaceDetector {
     pub fn new(cache: Arc<HashMap<usize, Vec<Region>>>) -> Self {
-        Self { cache }
+        Self {
+            cache: Cache::Shared(cache),
+        }
+    }
+
+    /// Replays detections that may have spilled to disk, as loaded from the
+    /// detection cache for a long recording.
+    pub fn disk_backed(detections: DiskBackedDetections) -> Self {
+        Self {
+            cache: Cache::DiskBacked(detections),
+        }
+    }
+
+    /// Replays a detection report written by
+    /// `pipeline::detection_report::format_detection_report`.
+    ///
+    /// Only regions and track IDs are used; scores and landmarks are
+    /// ignored. The report stores regions clamped to the frame, so an
+    /// ellipse over a face cut off by the edge is fitted to the visible
+    /// part.
+    pub fn from_json_file(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read detections {}: {e}", path.display()))?;
+        Self::from_json(&json)
+    }
+
+    /// Like `from_json_file`, from a report already in memory.
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(Self::new(Arc::new(parse_detection_report(json)?)))
+    }
+
+    /// Calls `f` with the regions of every frame it replays, in no
+    /// particular order.
+    pub fn for_each_frame(&mut self, mut f: impl FnMut(&[Region])) -> std::io::Result<()> {
+        match &mut self.cache {
+            Cache::Shared(cache) => {
+                for regions in cache.values() {
+                    f(regions);
+                }
+                Ok(())
+            }
+            Cache::DiskBacked(detections) => detections.for_each(|_, regions| {
+                f(regions);
+                Ok(())
+            }),
+        }
     }
 }
 
+fn parse_detection_report(json: &str) -> Result<HashMap<usize, Vec<Region>>, Box<dyn Error>> {
+    let report: Value =
+        serde_json::from_str(json).map_err(|e| format!("Detections are not valid JSON: {e}"))?;
+    let report = REPORT_SCHEMA.upgrade(report)?;
+    let frames = report["frames"]
+        .as_array()
+        .ok_or("Detections have no frames list")?;
+    let mut detections = HashMap::with_capacity(frames.len());
+    for entry in frames {
+        let frame = entry["frame"]
+            .as_u64()
+            .ok_or("Detections list a frame without a frame number")? as usize;
+        let faces = entry["faces"]
+            .as_array()
+            .ok_or_else(|| format!("Detections for frame {frame} have no faces list"))?;
+        let regions = faces
+            .iter()
+            .map(|face| parse_region(face, frame))
+            .collect::<Result<Vec<_>, _>>()?;
+        detections.insert(frame, regions);
+    }
+    Ok(detections)
+}
+
+fn parse_region(face: &Value, frame: usize) -> Result<Region, Box<dyn Error>> {
+    let coordinate = |key: &str| {
+        face[key]
+            .as_i64()
+            .and_then(|v| i32::try_from(v).ok())
+            .ok_or_else(|| format!("A face on frame {frame} has no valid {key}"))
+    };
+    let track_id = match &face["track_id"] {
+        Value::Null => None,
+        id => Some(
+            id.as_u64()
+                .and_then(|id| u32::try_from(id).ok())
+                .ok_or_else(|| format!("A face on frame {frame} has an invalid track_id"))?,
+        ),
+    };
+    Ok(Region {
+        x: coordinate("x")?,
+        y: coordinate("y")?,
+        width: coordinate("width")?,
+        height: coordinate("height")?,
+        track_id,
+        full_width: None,
+        full_height: None,
+        unclamped_x: None,
+        unclamped_y: None,
+    })
+}
+
 impl FaceDetector for CachedFaceDetector {
     fn detect(&mut self, frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
-        Ok(self.cache.get(&frame.index()).cloned().unwrap_or_default())
+        Ok(match &mut self.cache {
+            Cache::Shared(cache) => cache.get(&frame.index()).cloned(),
+            Cache::DiskBacked(detections) => detections.get(frame.index())?,
+        }
+        .unwrap_or_default())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     fn frame(index: usize) -> Frame {
         Frame::new(vec![0u8; 100 * 100 * 3], 100, 100, 3, index)
@@ -91,6 +207,82 @@ mod tests {
         assert!(detector.detect(&frame(99)).unwrap().is_empty());
     }
 
+    #[test]
+    fn test_visits_every_frame() {
+        let cache = Arc::new(HashMap::from([
+            (0, vec![region(1, 10)]),
+            (1, vec![region(1, 20), region(2, 60)]),
+        ]));
+        let mut detector = CachedFaceDetector::new(cache);
+        let mut regions = 0;
+
+        detector
+            .for_each_frame(|frame| regions += frame.len())
+            .unwrap();
+
+        assert_eq!(regions, 3);
+    }
+
+    #[test]
+    fn test_replays_disk_backed_detections() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut detections = DiskBackedDetections::new()
+            .with_memory_frames(1)
+            .with_spill_dir(dir.path());
+        detections.insert(0, vec![region(1, 10)]).unwrap();
+        detections.insert(1, vec![]).unwrap();
+        detections.insert(2, vec![region(1, 30)]).unwrap();
+        let mut detector = CachedFaceDetector::disk_backed(detections);
+
+        assert_eq!(detector.detect(&frame(0)).unwrap(), vec![region(1, 10)]);
+        assert!(detector.detect(&frame(1)).unwrap().is_empty());
+        assert_eq!(detector.detect(&frame(2)).unwrap(), vec![region(1, 30)]);
+        assert!(detector.detect(&frame(3)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_replays_json_report() {
+        let json = r#"{
+  "version": 1,
+  "width": 640,
+  "height": 480,
+  "fps": 25,
+  "total_frames": 3,
+  "frames": [
+    {"frame": 0, "timestamp": 0.000, "faces": [{"track_id": 1, "x": 10, "y": 20, "width": 50, "height": 50, "confidence": 0.9, "landmarks": null}]},
+    {"frame": 2, "timestamp": 0.080, "faces": [{"track_id": null, "x": 30, "y": 20, "width": 50, "height": 50, "confidence": null, "landmarks": null}]}
+  ]
+}"#;
+        let mut detector = CachedFaceDetector::from_json(json).unwrap();
+
+        assert_eq!(detector.detect(&frame(0)).unwrap(), vec![region(1, 10)]);
+        assert!(detector.detect(&frame(1)).unwrap().is_empty());
+        let untracked = detector.detect(&frame(2)).unwrap();
+        assert_eq!(untracked[0].x, 30);
+        assert_eq!(untracked[0].track_id, None);
+    }
+
+    #[rstest]
+    #[case::not_json("faces")]
+    #[case::no_version(r#"{"frames": []}"#)]
+    #[case::newer_version(r#"{"version": 2, "frames": []}"#)]
+    #[case::missing_coordinate(
+        r#"{"version": 1, "frames": [{"frame": 0, "faces": [{"track_id": 1, "x": 1, "y": 2, "width": 3}]}]}"#
+    )]
+    #[case::negative_track_id(
+        r#"{"version": 1, "frames": [{"frame": 0, "faces": [{"track_id": -1, "x": 1, "y": 2, "width": 3, "height": 4}]}]}"#
+    )]
+    fn test_rejects_malformed_json(#[case] json: &str) {
+        assert!(CachedFaceDetector::from_json(json).is_err());
+    }
+
+    #[test]
+    fn test_json_file_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = CachedFaceDetector::from_json_file(&dir.path().join("missing.json"));
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_track_ids_are_preserved() {
         let cache = Arc::new(HashMap::from([(0, vec![region(42, 10), region(7, 60)])]));
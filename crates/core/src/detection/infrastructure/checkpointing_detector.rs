@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use crate::detection::domain::face_detector::{DetectorState, FaceDetector};
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// Detector states by the index of the frame they were saved after.
+///
+/// Filled on the detection thread and taken from where output is
+/// written, which runs behind detection by the lookahead. Cloning is cheap
+/// and every clone sees the same states.
+#[derive(Clone, Debug, Default)]
+pub struct DetectorStates {
+    states: Arc<Mutex<BTreeMap<usize, DetectorState>>>,
+}
+
+impl DetectorStates {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn insert(&self, frame: usize, state: DetectorState) {
+        self.states
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(frame, state);
+    }
+
+    /// The state saved after `frame`, dropping it and every older one.
+    /// `None` when no state was saved after that frame.
+    pub fn take(&self, frame: usize) -> Option<DetectorState> {
+        let mut states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        let newer = states.split_off(&(frame + 1));
+        let state = states.remove(&frame);
+        *states = newer;
+        state
+    }
+}
+
+/// Decorator that saves the detector's state after every `interval`th
+/// frame, so a resume checkpoint can be written once those frames are
+/// encoded.
+///
+/// Wraps the whole detector chain, so every decorator's state is saved.
+/// Frames the pipeline reuses without detecting save nothing.
+pub struct CheckpointingDetector {
+    inner: Box<dyn FaceDetector>,
+    interval: usize,
+    states: DetectorStates,
+}
+
+impl CheckpointingDetector {
+    pub fn new(inner: Box<dyn FaceDetector>, interval: usize) -> Self {
+        Self {
+            inner,
+            interval: interval.max(1),
+            states: DetectorStates::new(),
+        }
+    }
+
+    /// Returns a handle to the saved states that stays valid after the
+    /// detector is moved.
+    pub fn states(&self) -> DetectorStates {
+        self.states.clone()
+    }
+}
+
+impl FaceDetector for CheckpointingDetector {
+    fn detect(&mut self, frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+        let regions = self.inner.detect(frame)?;
+        if (frame.index() + 1) % self.interval == 0 {
+            self.states.insert(frame.index(), self.inner.save_state()?);
+        }
+        Ok(regions)
+    }
+
+    fn save_state(&self) -> Result<DetectorState, Box<dyn std::error::Error>> {
+        self.inner.save_state()
+    }
+
+    fn restore_state(&mut self, state: &DetectorState) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.restore_state(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts the frames it has seen, as its state.
+    struct CountingDetector {
+        seen: u64,
+    }
+
+    impl FaceDetector for CountingDetector {
+        fn detect(&mut self, _frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+            self.seen += 1;
+            Ok(Vec::new())
+        }
+
+        fn save_state(&self) -> Result<DetectorState, Box<dyn std::error::Error>> {
+            Ok(self.seen.into())
+        }
+    }
+
+    fn frame(index: usize) -> Frame {
+        Frame::new(vec![0u8; 10 * 10 * 3], 10, 10, 3, index)
+    }
+
+    #[test]
+    fn test_saves_state_after_every_interval() {
+        let mut detector = CheckpointingDetector::new(Box::new(CountingDetector { seen: 0 }), 4);
+        let states = detector.states();
+
+        for i in 0..10 {
+            detector.detect(&frame(i)).unwrap();
+        }
+
+        assert_eq!(states.take(2), None);
+        assert_eq!(states.take(3), Some(4.into()));
+        assert_eq!(states.take(7), Some(8.into()));
+    }
+
+    #[test]
+    fn test_take_drops_older_states() {
+        let mut detector = CheckpointingDetector::new(Box::new(CountingDetector { seen: 0 }), 2);
+        let states = detector.states();
+        for i in 0..6 {
+            detector.detect(&frame(i)).unwrap();
+        }
+
+        assert_eq!(states.take(3), Some(4.into()));
+        assert_eq!(states.take(1), None);
+        assert_eq!(states.take(5), Some(6.into()));
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::detection::domain::face_detector::{DetectorState, FaceDetector};
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// Number of faces detected per frame index, shared between a
+/// `CountingFaceDetector` on the detection thread and its reader.
+pub type FaceCounts = Arc<Mutex<HashMap<usize, usize>>>;
+
+/// Decorator that records how many faces the inner detector found on each
+/// frame, before lookahead merging adds or drops any.
+///
+/// The reader is expected to take each count out once it is used, so only
+/// the frames between detection and blurring are held.
+pub struct CountingFaceDetector {
+    inner: Box<dyn FaceDetector>,
+    counts: FaceCounts,
+}
+
+impl CountingFaceDetector {
+    pub fn new(inner: Box<dyn FaceDetector>, counts: FaceCounts) -> Self {
+        Self { inner, counts }
+    }
+}
+
+impl FaceDetector for CountingFaceDetector {
+    fn detect(&mut self, frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+        let regions = self.inner.detect(frame)?;
+        self.counts
+            .lock()
+            .map_err(|_| "Face counts poisoned")?
+            .insert(frame.index(), regions.len());
+        Ok(regions)
+    }
+
+    fn save_state(&self) -> Result<DetectorState, Box<dyn std::error::Error>> {
+        self.inner.save_state()
+    }
+
+    fn restore_state(&mut self, state: &DetectorState) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.restore_state(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StubDetector;
+
+    impl FaceDetector for StubDetector {
+        fn detect(&mut self, frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+            Ok((0..frame.index())
+                .map(|i| Region {
+                    x: i as i32,
+                    y: 0,
+                    width: 10,
+                    height: 10,
+                    track_id: None,
+                    full_width: None,
+                    full_height: None,
+                    unclamped_x: None,
+                    unclamped_y: None,
+                })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_counts_detections_by_frame_index() {
+        let counts = FaceCounts::default();
+        let mut detector = CountingFaceDetector::new(Box::new(StubDetector), counts.clone());
+
+        detector
+            .detect(&Frame::new(vec![0; 12], 2, 2, 3, 0))
+            .unwrap();
+        detector
+            .detect(&Frame::new(vec![0; 12], 2, 2, 3, 3))
+            .unwrap();
+
+        let counts = counts.lock().unwrap();
+        assert_eq!(counts.get(&0), Some(&0));
+        assert_eq!(counts.get(&3), Some(&3));
+    }
+}
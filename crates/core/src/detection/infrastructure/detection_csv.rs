@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::detection::infrastructure::disk_backed_detections::DiskBackedDetections;
+use crate::shared::region::Region;
+
+pub const CSV_HEADER: &str = "frame,timestamp,faces,track_ids";
+
+/// Formats a per-frame detection summary as CSV.
+///
+/// Every frame in `0..total_frames` gets a row, including frames with no
+/// detections, so analysts can spot coverage gaps as well as crowd scenes.
+/// Track IDs are sorted and `;`-separated to keep the column comma-free.
+/// Timestamps are in seconds; sources without a frame rate (images) report 0.
+pub fn format_detection_csv(
+    detections: &HashMap<usize, Vec<Region>>,
+    fps: f64,
+    total_frames: usize,
+) -> String {
+    let last_detected = detections.keys().max().map(|&i| i + 1).unwrap_or(0);
+    let frame_count = total_frames.max(last_detected);
+
+    let mut out = String::with_capacity(32 * (frame_count + 1));
+    out.push_str(CSV_HEADER);
+    out.push('\n');
+
+    for frame in 0..frame_count {
+        let regions = detections.get(&frame).map(Vec::as_slice).unwrap_or(&[]);
+        out.push_str(&format_row(frame, regions, fps));
+    }
+
+    out
+}
+
+/// Writes the per-frame detection summary to `path`, streaming rows so
+/// spilled detections of a long recording are read back one block at a
+/// time rather than formatted in memory.
+pub fn write_detection_csv(
+    path: &Path,
+    detections: &mut DiskBackedDetections,
+    fps: f64,
+    total_frames: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "{CSV_HEADER}")?;
+    let mut next = 0;
+    detections.for_each(|frame, regions| {
+        for empty in next..frame {
+            out.write_all(format_row(empty, &[], fps).as_bytes())?;
+        }
+        out.write_all(format_row(frame, regions, fps).as_bytes())?;
+        next = frame + 1;
+        Ok(())
+    })?;
+    for empty in next..total_frames {
+        out.write_all(format_row(empty, &[], fps).as_bytes())?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn format_row(frame: usize, regions: &[Region], fps: f64) -> String {
+    let mut ids: Vec<u32> = regions.iter().filter_map(|r| r.track_id).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    let ids: Vec<String> = ids.iter().map(u32::to_string).collect();
+    let timestamp = if fps > 0.0 { frame as f64 / fps } else { 0.0 };
+    format!(
+        "{frame},{timestamp:.3},{},{}\n",
+        regions.len(),
+        ids.join(";")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(track_id: Option<u32>) -> Region {
+        Region {
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 10,
+            track_id,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    fn rows(csv: &str) -> Vec<&str> {
+        csv.lines().skip(1).collect()
+    }
+
+    #[test]
+    fn test_header_is_first_line() {
+        let csv = format_detection_csv(&HashMap::new(), 30.0, 0);
+        assert_eq!(csv.lines().next(), Some(CSV_HEADER));
+    }
+
+    #[test]
+    fn test_frames_without_detections_get_zero_rows() {
+        let csv = format_detection_csv(&HashMap::new(), 30.0, 3);
+        assert_eq!(rows(&csv), vec!["0,0.000,0,", "1,0.033,0,", "2,0.067,0,"]);
+    }
+
+    #[test]
+    fn test_track_ids_sorted_and_semicolon_separated() {
+        let detections = HashMap::from([(0, vec![region(Some(7)), region(Some(2))])]);
+        let csv = format_detection_csv(&detections, 25.0, 1);
+        assert_eq!(rows(&csv), vec!["0,0.000,2,2;7"]);
+    }
+
+    #[test]
+    fn test_untracked_regions_counted_but_not_listed() {
+        let detections = HashMap::from([(0, vec![region(None), region(Some(1))])]);
+        let csv = format_detection_csv(&detections, 25.0, 1);
+        assert_eq!(rows(&csv), vec!["0,0.000,2,1"]);
+    }
+
+    #[test]
+    fn test_zero_fps_reports_zero_timestamp() {
+        let detections = HashMap::from([(0, vec![region(Some(1))])]);
+        let csv = format_detection_csv(&detections, 0.0, 1);
+        assert_eq!(rows(&csv), vec!["0,0.000,1,1"]);
+    }
+
+    #[test]
+    fn test_streamed_file_matches_formatted_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let map = HashMap::from([
+            (1, vec![region(Some(3))]),
+            (2, vec![]),
+            (6, vec![region(Some(1)), region(None)]),
+        ]);
+        let mut detections = DiskBackedDetections::new()
+            .with_memory_frames(1)
+            .with_spill_dir(dir.path());
+        for frame in [1, 2, 6] {
+            detections.insert(frame, map[&frame].clone()).unwrap();
+        }
+        let path = dir.path().join("detections.csv");
+
+        write_detection_csv(&path, &mut detections, 30.0, 9).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            format_detection_csv(&map, 30.0, 9)
+        );
+    }
+
+    #[test]
+    fn test_extends_past_total_frames_when_detections_do() {
+        let detections = HashMap::from([(4, vec![region(Some(1))])]);
+        let csv = format_detection_csv(&detections, 10.0, 2);
+        assert_eq!(rows(&csv).len(), 5);
+        assert_eq!(rows(&csv)[4], "4,0.400,1,1");
+    }
+}
@@ -0,0 +1,564 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::detection::infrastructure::disk_backed_detections::DiskBackedDetections;
+use crate::shared::region::Region;
+
+/// Default size cap for the cache folder: 256 MiB.
+pub const DEFAULT_CACHE_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+const ENTRY_EXTENSION: &str = "detections";
+const MAGIC: &str = "faceguard-detections";
+
+/// Entry layout version. Version 1 headers carry no field count and always
+/// hold `V1_REGION_FIELDS` fields per region. Version 2 adds the count, so
+/// fields appended to a region later (confidence, landmarks, rotation) are
+/// skipped by builds that predate them instead of making entries unreadable.
+const CACHE_VERSION: u32 = 2;
+const V1_REGION_FIELDS: usize = 9;
+
+/// Fields written per region by this build.
+pub(crate) const REGION_FIELDS: usize = 9;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Per-frame detections, keyed by frame index.
+pub type Detections = HashMap<usize, Vec<Region>>;
+
+/// Full detection results stored on disk, so re-exporting the same file
+/// with other blur settings skips the detect stage, even after a restart.
+///
+/// Entries are keyed by a hash of the input's content plus a caller-built
+/// string of every parameter that affects detection, so renaming or moving
+/// a file still hits and changing a detection setting misses. Each entry is
+/// a small text file; the least recently used are deleted once the folder
+/// grows past the size cap. Unreadable entries count as misses and are
+/// removed.
+pub struct DetectionDiskCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl DetectionDiskCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes: DEFAULT_CACHE_MAX_BYTES,
+        }
+    }
+
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// `FaceGuard/detections` under the platform cache folder.
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|d| d.join("FaceGuard").join("detections"))
+    }
+
+    /// Cache key for `input` read with detection settings `params`.
+    ///
+    /// Reads the whole file, which is quick next to running detection on it.
+    pub fn key(input: &Path, params: &str) -> io::Result<String> {
+        let mut file = File::open(input)?;
+        let mut hash = FNV_OFFSET;
+        let mut buf = vec![0u8; 1 << 20];
+        loop {
+            let n = file.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            hash = fnv1a(hash, &buf[..n]);
+        }
+        Ok(format!(
+            "{hash:016x}-{:016x}",
+            fnv1a(FNV_OFFSET, params.as_bytes())
+        ))
+    }
+
+    /// Stored detections for `key`, or `None` on a miss.
+    pub fn load(&self, key: &str) -> Option<Detections> {
+        self.load_with(key, Detections::new(), |detections, index, regions| {
+            detections.insert(index, regions);
+            Ok(())
+        })
+    }
+
+    /// Like `load`, but streams the entry into a `DiskBackedDetections`, so
+    /// a multi-hour recording's detections never sit in memory at once.
+    pub fn load_streamed(
+        &self,
+        key: &str,
+        detections: DiskBackedDetections,
+    ) -> Option<DiskBackedDetections> {
+        self.load_with(key, detections, |detections, index, regions| {
+            detections.insert(index, regions)
+        })
+    }
+
+    /// Stores `detections` under `key`, then prunes to the size cap.
+    pub fn store(&self, key: &str, detections: &Detections) -> io::Result<()> {
+        self.store_with(key, |out| {
+            let mut frames: Vec<_> = detections.iter().collect();
+            frames.sort_by_key(|(index, _)| **index);
+            for (&index, regions) in frames {
+                write_frame_line(out, index, regions)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Like `store`, but reads spilled frames back one block at a time.
+    pub fn store_streamed(
+        &self,
+        key: &str,
+        detections: &mut DiskBackedDetections,
+    ) -> io::Result<()> {
+        self.store_with(key, |out| {
+            detections.for_each(|index, regions| write_frame_line(out, index, regions))
+        })
+    }
+
+    fn load_with<T>(
+        &self,
+        key: &str,
+        mut detections: T,
+        mut insert: impl FnMut(&mut T, usize, Vec<Region>) -> io::Result<()>,
+    ) -> Option<T> {
+        let path = self.entry_path(key);
+        let file = File::open(&path).ok()?;
+        let result = read_entry(BufReader::new(file), |index, regions| {
+            insert(&mut detections, index, regions).map_err(|e| e.to_string())
+        });
+        match result {
+            Ok(()) => {
+                // Refresh the modification time so pruning sees it as recently used.
+                if let Ok(file) = File::options().append(true).open(&path) {
+                    let _ = file.set_modified(SystemTime::now());
+                }
+                Some(detections)
+            }
+            Err(e) => {
+                log::warn!("Discarding unreadable detection cache entry {key}: {e}");
+                let _ = fs::remove_file(&path);
+                None
+            }
+        }
+    }
+
+    fn store_with(
+        &self,
+        key: &str,
+        write_frames: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+    ) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.entry_path(key);
+        let temp_path = path.with_extension("part");
+        let result =
+            write_entry(&temp_path, write_frames).and_then(|()| fs::rename(&temp_path, &path));
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+        result?;
+        self.prune()?;
+        Ok(())
+    }
+
+    /// Deletes least recently used entries until the folder fits the cap.
+    /// Returns the number of bytes freed.
+    pub fn prune(&self) -> io::Result<u64> {
+        let mut entries = self.entries()?;
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        entries.sort_by_key(|(_, _, modified)| *modified);
+
+        let mut freed = 0;
+        for (path, len, _) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            fs::remove_file(&path)?;
+            total -= len;
+            freed += len;
+        }
+        Ok(freed)
+    }
+
+    /// Deletes every entry.
+    pub fn clear(&self) -> io::Result<()> {
+        for (path, _, _) in self.entries()? {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Total size of all entries in bytes.
+    pub fn size(&self) -> io::Result<u64> {
+        Ok(self.entries()?.iter().map(|(_, len, _)| len).sum())
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(key).with_extension(ENTRY_EXTENSION)
+    }
+
+    fn entries(&self) -> io::Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let read_dir = match fs::read_dir(&self.dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut entries = Vec::new();
+        for entry in read_dir {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(ENTRY_EXTENSION) {
+                continue;
+            }
+            let meta = fs::metadata(&path)?;
+            entries.push((path, meta.len(), meta.modified()?));
+        }
+        Ok(entries)
+    }
+}
+
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &b in bytes {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+fn write_entry(
+    path: &Path,
+    write_frames: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "{MAGIC} {CACHE_VERSION} {REGION_FIELDS}")?;
+    write_frames(&mut out)?;
+    out.into_inner().map_err(|e| e.into_error())?.sync_all()
+}
+
+/// One line per frame: the frame index, then `REGION_FIELDS` fields per
+/// region (`x y width height track_id full_width full_height unclamped_x
+/// unclamped_y`, `-` for none). A frame with no faces is its index alone.
+/// New region fields are only ever appended.
+pub(crate) fn write_frame_line(
+    out: &mut impl Write,
+    index: usize,
+    regions: &[Region],
+) -> io::Result<()> {
+    write!(out, "{index}")?;
+    for r in regions {
+        write!(
+            out,
+            " {} {} {} {} {} {} {} {} {}",
+            r.x,
+            r.y,
+            r.width,
+            r.height,
+            optional(r.track_id),
+            optional(r.full_width),
+            optional(r.full_height),
+            optional(r.unclamped_x),
+            optional(r.unclamped_y)
+        )?;
+    }
+    writeln!(out)
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| v.to_string())
+}
+
+fn read_entry(
+    reader: impl BufRead,
+    mut insert: impl FnMut(usize, Vec<Region>) -> Result<(), String>,
+) -> Result<(), String> {
+    let mut lines = reader.lines();
+    let region_fields = match lines.next() {
+        Some(Ok(line)) => parse_header(&line)?,
+        _ => return Err("missing header".into()),
+    };
+
+    for (n, line) in lines.enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        let (index, regions) =
+            parse_frame_line(&line, region_fields).ok_or_else(|| format!("bad line {}", n + 2))?;
+        insert(index, regions)?;
+    }
+    Ok(())
+}
+
+/// Fields per region in an entry with header `line`, migrating older
+/// versions.
+fn parse_header(line: &str) -> Result<usize, String> {
+    let mut parts = line.split_whitespace();
+    if parts.next() != Some(MAGIC) {
+        return Err("missing header".into());
+    }
+    let version: u32 = parts
+        .next()
+        .and_then(|v| v.parse().ok())
+        .ok_or("missing version")?;
+    let region_fields = match version {
+        1 => Some(V1_REGION_FIELDS),
+        2..=CACHE_VERSION => parts.next().and_then(|f| f.parse().ok()),
+        _ => return Err(format!("written by a newer version ({version})")),
+    };
+    match region_fields {
+        Some(fields) if fields >= V1_REGION_FIELDS => Ok(fields),
+        _ => Err("bad region field count".into()),
+    }
+}
+
+/// Parses one line written by `write_frame_line` with `region_fields`
+/// fields per region. Fields past the ones this build knows are ignored.
+pub(crate) fn parse_frame_line(line: &str, region_fields: usize) -> Option<(usize, Vec<Region>)> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let (index, rest) = fields.split_first()?;
+    if region_fields < V1_REGION_FIELDS || rest.len() % region_fields != 0 {
+        return None;
+    }
+    let regions = rest
+        .chunks(region_fields)
+        .map(|f| {
+            Some(Region {
+                x: f[0].parse().ok()?,
+                y: f[1].parse().ok()?,
+                width: f[2].parse().ok()?,
+                height: f[3].parse().ok()?,
+                track_id: parse_optional(f[4])?,
+                full_width: parse_optional(f[5])?,
+                full_height: parse_optional(f[6])?,
+                unclamped_x: parse_optional(f[7])?,
+                unclamped_y: parse_optional(f[8])?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some((index.parse().ok()?, regions))
+}
+
+/// `Some(None)` for `-`, `None` when the field is malformed.
+fn parse_optional<T: std::str::FromStr>(field: &str) -> Option<Option<T>> {
+    if field == "-" {
+        Some(None)
+    } else {
+        field.parse().ok().map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use std::time::Duration;
+
+    fn region(track_id: Option<u32>, x: i32) -> Region {
+        Region {
+            x,
+            y: -4,
+            width: 50,
+            height: 60,
+            track_id,
+            full_width: Some(70),
+            full_height: None,
+            unclamped_x: Some(x - 20),
+            unclamped_y: None,
+        }
+    }
+
+    fn detections() -> Detections {
+        HashMap::from([
+            (0, vec![region(Some(1), 10), region(None, 80)]),
+            (1, vec![]),
+            (2, vec![region(Some(7), -3)]),
+        ])
+    }
+
+    fn write_input(dir: &Path, name: &str, content: &[u8]) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn set_age(cache: &DetectionDiskCache, key: &str, seconds_ago: u64) {
+        let file = File::options()
+            .append(true)
+            .open(cache.entry_path(key))
+            .unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(seconds_ago))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_store_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DetectionDiskCache::new(dir.path());
+        cache.store("abc", &detections()).unwrap();
+        assert_eq!(cache.load("abc"), Some(detections()));
+    }
+
+    #[test]
+    fn test_streamed_store_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DetectionDiskCache::new(dir.path().join("cache"));
+        let spill = || {
+            DiskBackedDetections::new()
+                .with_memory_frames(1)
+                .with_spill_dir(dir.path().join("spill"))
+        };
+        let mut recorded = spill();
+        let mut frames: Vec<_> = detections().into_iter().collect();
+        frames.sort_by_key(|(index, _)| *index);
+        for (index, regions) in frames {
+            recorded.insert(index, regions).unwrap();
+        }
+        assert!(recorded.has_spilled());
+
+        cache.store_streamed("abc", &mut recorded).unwrap();
+        assert_eq!(cache.load("abc"), Some(detections()));
+        let mut loaded = cache.load_streamed("abc", spill()).unwrap();
+        assert!(loaded.has_spilled());
+        assert_eq!(loaded.to_map().unwrap(), detections());
+    }
+
+    #[test]
+    fn test_missing_entry_is_a_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DetectionDiskCache::new(dir.path().join("not-created"));
+        assert_eq!(cache.load("abc"), None);
+        assert_eq!(cache.size().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_corrupt_entry_is_a_miss_and_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DetectionDiskCache::new(dir.path());
+        fs::write(
+            cache.entry_path("abc"),
+            format!("{MAGIC} {CACHE_VERSION} {REGION_FIELDS}\n0 1 2 3\n"),
+        )
+        .unwrap();
+        assert_eq!(cache.load("abc"), None);
+        assert!(!cache.entry_path("abc").exists());
+    }
+
+    #[test]
+    fn test_version_1_entry_is_migrated() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DetectionDiskCache::new(dir.path());
+        fs::write(
+            cache.entry_path("abc"),
+            "faceguard-detections 1\n0 10 -4 50 60 1 70 - -10 -\n1\n",
+        )
+        .unwrap();
+        let expected = HashMap::from([(0, vec![region(Some(1), 10)]), (1, vec![])]);
+        assert_eq!(cache.load("abc"), Some(expected));
+    }
+
+    #[test]
+    fn test_fields_appended_by_newer_builds_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DetectionDiskCache::new(dir.path());
+        fs::write(
+            cache.entry_path("abc"),
+            "faceguard-detections 2 11\n\
+             0 10 -4 50 60 1 70 - -10 - 0.93 15 80 -4 50 60 - 70 - 60 - 0.51 -\n",
+        )
+        .unwrap();
+        let expected = HashMap::from([(0, vec![region(Some(1), 10), region(None, 80)])]);
+        assert_eq!(cache.load("abc"), Some(expected));
+    }
+
+    #[rstest]
+    #[case::newer_version("faceguard-detections 3 9")]
+    #[case::no_field_count("faceguard-detections 2")]
+    #[case::too_few_fields("faceguard-detections 2 8")]
+    #[case::other_file("some-other-cache 1")]
+    fn test_unreadable_header_is_a_miss(#[case] header: &str) {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DetectionDiskCache::new(dir.path());
+        fs::write(cache.entry_path("abc"), format!("{header}\n1\n")).unwrap();
+        assert_eq!(cache.load("abc"), None);
+    }
+
+    #[test]
+    fn test_key_follows_content_not_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = write_input(dir.path(), "a.mp4", b"same bytes");
+        let b = write_input(dir.path(), "b.mp4", b"same bytes");
+        let c = write_input(dir.path(), "c.mp4", b"other bytes");
+        let key = |p: &Path| DetectionDiskCache::key(p, "conf=0.5").unwrap();
+        assert_eq!(key(&a), key(&b));
+        assert_ne!(key(&a), key(&c));
+    }
+
+    #[test]
+    fn test_key_follows_params() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = write_input(dir.path(), "a.mp4", b"bytes");
+        assert_ne!(
+            DetectionDiskCache::key(&input, "conf=0.5").unwrap(),
+            DetectionDiskCache::key(&input, "conf=0.6").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_prune_removes_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let unlimited = DetectionDiskCache::new(dir.path());
+        for key in ["old", "mid", "new"] {
+            unlimited.store(key, &detections()).unwrap();
+        }
+        set_age(&unlimited, "old", 300);
+        set_age(&unlimited, "mid", 200);
+        set_age(&unlimited, "new", 100);
+        let entry_size = unlimited.size().unwrap() / 3;
+
+        let cache = DetectionDiskCache::new(dir.path()).with_max_bytes(entry_size * 2);
+        assert_eq!(cache.prune().unwrap(), entry_size);
+        assert!(cache.load("old").is_none());
+        assert!(cache.load("mid").is_some());
+        assert!(cache.load("new").is_some());
+    }
+
+    #[test]
+    fn test_load_marks_entry_as_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let unlimited = DetectionDiskCache::new(dir.path());
+        unlimited.store("a", &detections()).unwrap();
+        unlimited.store("b", &detections()).unwrap();
+        set_age(&unlimited, "a", 300);
+        set_age(&unlimited, "b", 200);
+        let entry_size = unlimited.size().unwrap() / 2;
+
+        assert!(unlimited.load("a").is_some());
+        let cache = DetectionDiskCache::new(dir.path()).with_max_bytes(entry_size);
+        cache.prune().unwrap();
+        assert!(cache.load("a").is_some());
+        assert!(cache.load("b").is_none());
+    }
+
+    #[test]
+    fn test_store_keeps_folder_under_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DetectionDiskCache::new(dir.path()).with_max_bytes(1);
+        cache.store("a", &detections()).unwrap();
+        assert_eq!(cache.size().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_clear_removes_only_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DetectionDiskCache::new(dir.path());
+        cache.store("a", &detections()).unwrap();
+        fs::write(dir.path().join("notes.txt"), "keep").unwrap();
+        cache.clear().unwrap();
+        assert_eq!(cache.size().unwrap(), 0);
+        assert!(dir.path().join("notes.txt").exists());
+    }
+}
@@ -0,0 +1,214 @@
+/// JSON forms of the tracker and region builder snapshots and of regions,
+/// for detectors saving their state to a resume checkpoint.
+use std::error::Error;
+
+use serde_json::{json, Value};
+
+use crate::detection::domain::face_detector::DetectorState;
+use crate::detection::domain::face_region_builder::RegionBuilderSnapshot;
+use crate::shared::region::Region;
+
+use super::bytetrack_tracker::{TrackSnapshot, TrackerSnapshot};
+
+pub(crate) const INVALID: &str = "The resume checkpoint has an invalid detector state";
+
+pub fn tracker_to_json(snapshot: &TrackerSnapshot) -> DetectorState {
+    let tracks: Vec<Value> = snapshot
+        .tracks
+        .iter()
+        .map(|t| json!({ "id": t.id, "bbox": t.bbox, "frames_lost": t.frames_lost }))
+        .collect();
+    json!({ "next_id": snapshot.next_id, "tracks": tracks })
+}
+
+pub fn tracker_from_json(state: &DetectorState) -> Result<TrackerSnapshot, Box<dyn Error>> {
+    let tracks = state["tracks"]
+        .as_array()
+        .ok_or(INVALID)?
+        .iter()
+        .map(|t| {
+            Ok(TrackSnapshot {
+                id: as_u32(&t["id"])?,
+                bbox: floats(&t["bbox"])?,
+                frames_lost: t["frames_lost"].as_u64().ok_or(INVALID)? as usize,
+            })
+        })
+        .collect::<Result<_, Box<dyn Error>>>()?;
+    Ok(TrackerSnapshot {
+        tracks,
+        next_id: as_u32(&state["next_id"])?,
+    })
+}
+
+pub fn builder_to_json(snapshot: &RegionBuilderSnapshot) -> DetectorState {
+    let smoothing: Vec<Value> = snapshot
+        .smoothing
+        .iter()
+        .map(|(id, params)| json!({ "id": id, "params": params }))
+        .collect();
+    let growth: Vec<Value> = snapshot
+        .growth
+        .iter()
+        .map(|(id, level)| json!({ "id": id, "level": level }))
+        .collect();
+    json!({ "smoothing": smoothing, "growth": growth })
+}
+
+pub fn builder_from_json(state: &DetectorState) -> Result<RegionBuilderSnapshot, Box<dyn Error>> {
+    let smoothing = state["smoothing"]
+        .as_array()
+        .ok_or(INVALID)?
+        .iter()
+        .map(|s| Ok((as_u32(&s["id"])?, floats(&s["params"])?)))
+        .collect::<Result<_, Box<dyn Error>>>()?;
+    let growth = state["growth"]
+        .as_array()
+        .ok_or(INVALID)?
+        .iter()
+        .map(|g| Ok((as_u32(&g["id"])?, g["level"].as_f64().ok_or(INVALID)?)))
+        .collect::<Result<_, Box<dyn Error>>>()?;
+    Ok(RegionBuilderSnapshot { smoothing, growth })
+}
+
+pub fn regions_to_json(regions: &[Region]) -> DetectorState {
+    let regions: Vec<Value> = regions
+        .iter()
+        .map(|r| {
+            json!({
+                "x": r.x,
+                "y": r.y,
+                "width": r.width,
+                "height": r.height,
+                "track_id": r.track_id,
+                "full_width": r.full_width,
+                "full_height": r.full_height,
+                "unclamped_x": r.unclamped_x,
+                "unclamped_y": r.unclamped_y,
+            })
+        })
+        .collect();
+    Value::Array(regions)
+}
+
+pub fn regions_from_json(state: &DetectorState) -> Result<Vec<Region>, Box<dyn Error>> {
+    state
+        .as_array()
+        .ok_or(INVALID)?
+        .iter()
+        .map(|r| {
+            Ok(Region {
+                x: as_i32(&r["x"])?,
+                y: as_i32(&r["y"])?,
+                width: as_i32(&r["width"])?,
+                height: as_i32(&r["height"])?,
+                track_id: optional(&r["track_id"], as_u32)?,
+                full_width: optional(&r["full_width"], as_i32)?,
+                full_height: optional(&r["full_height"], as_i32)?,
+                unclamped_x: optional(&r["unclamped_x"], as_i32)?,
+                unclamped_y: optional(&r["unclamped_y"], as_i32)?,
+            })
+        })
+        .collect()
+}
+
+fn optional<T>(
+    value: &Value,
+    parse: fn(&Value) -> Result<T, Box<dyn Error>>,
+) -> Result<Option<T>, Box<dyn Error>> {
+    if value.is_null() {
+        Ok(None)
+    } else {
+        parse(value).map(Some)
+    }
+}
+
+fn as_i32(value: &Value) -> Result<i32, Box<dyn Error>> {
+    Ok(value
+        .as_i64()
+        .and_then(|v| i32::try_from(v).ok())
+        .ok_or(INVALID)?)
+}
+
+pub(crate) fn as_u32(value: &Value) -> Result<u32, Box<dyn Error>> {
+    Ok(value
+        .as_u64()
+        .and_then(|v| u32::try_from(v).ok())
+        .ok_or(INVALID)?)
+}
+
+pub(crate) fn floats<const N: usize>(value: &Value) -> Result<[f64; N], Box<dyn Error>> {
+    let values: Vec<f64> = value
+        .as_array()
+        .ok_or(INVALID)?
+        .iter()
+        .map(|v| v.as_f64().ok_or(INVALID))
+        .collect::<Result<_, _>>()?;
+    Ok(values.try_into().map_err(|_| INVALID)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_round_trips() {
+        let snapshot = TrackerSnapshot {
+            tracks: vec![TrackSnapshot {
+                id: 3,
+                bbox: [1.5, 2.0, 30.25, 40.0],
+                frames_lost: 2,
+            }],
+            next_id: 7,
+        };
+        let json = tracker_to_json(&snapshot);
+        assert_eq!(tracker_from_json(&json).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn test_builder_round_trips() {
+        let snapshot = RegionBuilderSnapshot {
+            smoothing: vec![(1, [10.0, 20.0, 5.5, 6.5])],
+            growth: vec![(1, 0.25)],
+        };
+        let json = builder_to_json(&snapshot);
+        assert_eq!(builder_from_json(&json).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn test_regions_round_trip() {
+        let regions = vec![
+            Region {
+                x: 0,
+                y: 5,
+                width: 20,
+                height: 30,
+                track_id: Some(4),
+                full_width: Some(25),
+                full_height: Some(30),
+                unclamped_x: Some(-5),
+                unclamped_y: Some(5),
+            },
+            Region {
+                x: 1,
+                y: 2,
+                width: 3,
+                height: 4,
+                track_id: None,
+                full_width: None,
+                full_height: None,
+                unclamped_x: None,
+                unclamped_y: None,
+            },
+        ];
+        assert_eq!(
+            regions_from_json(&regions_to_json(&regions)).unwrap(),
+            regions
+        );
+    }
+
+    #[test]
+    fn test_malformed_state_is_error() {
+        assert!(tracker_from_json(&json!({ "tracks": [{ "id": 1 }], "next_id": 2 })).is_err());
+        assert!(builder_from_json(&Value::Null).is_err());
+    }
+}
@@ -0,0 +1,426 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::detection::infrastructure::detection_disk_cache::{
+    parse_frame_line, write_frame_line, Detections, REGION_FIELDS,
+};
+use crate::shared::region::Region;
+
+/// Frames held in memory before older ones spill to disk: five minutes at
+/// 30 fps.
+pub const DEFAULT_MEMORY_FRAMES: usize = 9000;
+
+const SPILL_EXTENSION: &str = "detections-spill";
+
+static NEXT_SPILL_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Per-frame detections that spill to a temporary file once more than a set
+/// number of frames are held, so memory stays flat on multi-hour recordings
+/// instead of growing with every frame.
+///
+/// Frames arrive in order from the pipeline. Whenever the in-memory map
+/// outgrows its limit, every frame past the last spilled one is appended to
+/// the file as one block, in the same line format as `DetectionDiskCache`
+/// entries. Only the block bounds stay in memory; a lookup reads back one
+/// block and keeps it, so replaying frames in order reads each block once.
+/// Inserting a frame that was already spilled keeps the new regions in
+/// memory, where they shadow the spilled ones. The file is deleted on drop.
+pub struct DiskBackedDetections {
+    memory: BTreeMap<usize, Vec<Region>>,
+    memory_frames: usize,
+    spill_dir: PathBuf,
+    spill: Option<Spill>,
+    len: usize,
+}
+
+impl DiskBackedDetections {
+    pub fn new() -> Self {
+        Self {
+            memory: BTreeMap::new(),
+            memory_frames: DEFAULT_MEMORY_FRAMES,
+            spill_dir: std::env::temp_dir(),
+            spill: None,
+            len: 0,
+        }
+    }
+
+    pub fn with_memory_frames(mut self, memory_frames: usize) -> Self {
+        self.memory_frames = memory_frames;
+        self
+    }
+
+    pub fn with_spill_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.spill_dir = dir.into();
+        self
+    }
+
+    /// Number of distinct frames stored.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether any frames have been written to disk.
+    pub fn has_spilled(&self) -> bool {
+        self.spill.is_some()
+    }
+
+    /// Stores the regions of frame `index`, replacing any stored before.
+    pub fn insert(&mut self, index: usize, regions: Vec<Region>) -> io::Result<()> {
+        let is_new = !self.memory.contains_key(&index)
+            && match self.spilled_through() {
+                Some(last) if index <= last => self.read_spilled(index)?.is_none(),
+                _ => true,
+            };
+        self.memory.insert(index, regions);
+        if is_new {
+            self.len += 1;
+        }
+        if self.memory.len() > self.memory_frames {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Regions stored for frame `index`, or `None` if it was never inserted.
+    pub fn get(&mut self, index: usize) -> io::Result<Option<Vec<Region>>> {
+        if let Some(regions) = self.memory.get(&index) {
+            return Ok(Some(regions.clone()));
+        }
+        self.read_spilled(index)
+    }
+
+    /// Calls `f` for every stored frame in ascending index order, reading
+    /// spilled frames back one block at a time.
+    pub fn for_each(
+        &mut self,
+        mut f: impl FnMut(usize, &[Region]) -> io::Result<()>,
+    ) -> io::Result<()> {
+        let mut memory = self.memory.iter().peekable();
+        if let Some(spill) = self.spill.as_mut() {
+            for block in 0..spill.blocks.len() {
+                for (index, regions) in spill.read_block(block)? {
+                    while let Some((&m, r)) = memory.next_if(|&(&m, _)| m < index) {
+                        f(m, r)?;
+                    }
+                    match memory.next_if(|&(&m, _)| m == index) {
+                        Some((_, r)) => f(index, r)?,
+                        None => f(index, &regions)?,
+                    }
+                }
+            }
+        }
+        for (&index, regions) in memory {
+            f(index, regions)?;
+        }
+        Ok(())
+    }
+
+    /// Every frame collected into one in-memory map.
+    pub fn to_map(&mut self) -> io::Result<Detections> {
+        let mut map = Detections::with_capacity(self.len);
+        self.for_each(|index, regions| {
+            map.insert(index, regions.to_vec());
+            Ok(())
+        })?;
+        Ok(map)
+    }
+
+    fn spilled_through(&self) -> Option<usize> {
+        self.spill
+            .as_ref()
+            .and_then(|s| s.blocks.last())
+            .map(|b| b.last)
+    }
+
+    fn read_spilled(&mut self, index: usize) -> io::Result<Option<Vec<Region>>> {
+        match self.spill.as_mut() {
+            Some(spill) => spill.get(index),
+            None => Ok(None),
+        }
+    }
+
+    /// Moves every in-memory frame past the last spilled one into a new
+    /// block. Frames at or before it stay in memory as overrides.
+    fn spill(&mut self) -> io::Result<()> {
+        let first = self.spilled_through().map_or(0, |last| last + 1);
+        let tail = self.memory.split_off(&first);
+        if tail.is_empty() {
+            return Ok(());
+        }
+        if self.spill.is_none() {
+            self.spill = Some(Spill::create(&self.spill_dir)?);
+        }
+        self.spill
+            .as_mut()
+            .expect("spill file was just created")
+            .append(&tail)
+    }
+}
+
+impl Default for DiskBackedDetections {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Detections> for DiskBackedDetections {
+    /// Wraps an in-memory map without a frame limit, so nothing spills.
+    fn from(detections: Detections) -> Self {
+        Self {
+            len: detections.len(),
+            memory: detections.into_iter().collect(),
+            memory_frames: usize::MAX,
+            ..Self::new()
+        }
+    }
+}
+
+/// A range of frame indices written to the spill file in one piece.
+struct Block {
+    first: usize,
+    last: usize,
+    offset: u64,
+    bytes: u64,
+}
+
+struct Spill {
+    path: PathBuf,
+    // Taken on drop so the file is closed before it is deleted.
+    file: Option<File>,
+    blocks: Vec<Block>,
+    cached: Option<(usize, HashMap<usize, Vec<Region>>)>,
+}
+
+impl Spill {
+    fn create(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let id = NEXT_SPILL_ID.fetch_add(1, Ordering::Relaxed);
+        let path = dir
+            .join(format!("faceguard-{}-{id}", std::process::id()))
+            .with_extension(SPILL_EXTENSION);
+        let file = File::options()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok(Self {
+            path,
+            file: Some(file),
+            blocks: Vec::new(),
+            cached: None,
+        })
+    }
+
+    fn file(&mut self) -> &mut File {
+        self.file.as_mut().expect("spill file is open until drop")
+    }
+
+    fn append(&mut self, frames: &BTreeMap<usize, Vec<Region>>) -> io::Result<()> {
+        let (Some(&first), Some(&last)) = (frames.keys().next(), frames.keys().next_back()) else {
+            return Ok(());
+        };
+        let file = self.file();
+        let offset = file.seek(SeekFrom::End(0))?;
+        let mut out = BufWriter::new(&mut *file);
+        for (&index, regions) in frames {
+            write_frame_line(&mut out, index, regions)?;
+        }
+        out.flush()?;
+        drop(out);
+        let bytes = file.stream_position()? - offset;
+        self.blocks.push(Block {
+            first,
+            last,
+            offset,
+            bytes,
+        });
+        Ok(())
+    }
+
+    fn get(&mut self, index: usize) -> io::Result<Option<Vec<Region>>> {
+        let block = self.blocks.partition_point(|b| b.last < index);
+        if !self.blocks.get(block).is_some_and(|b| b.first <= index) {
+            return Ok(None);
+        }
+        if self.cached.as_ref().map(|(cached, _)| *cached) != Some(block) {
+            let frames = self.read_block(block)?.into_iter().collect();
+            self.cached = Some((block, frames));
+        }
+        Ok(self
+            .cached
+            .as_ref()
+            .and_then(|(_, frames)| frames.get(&index).cloned()))
+    }
+
+    fn read_block(&mut self, block: usize) -> io::Result<Vec<(usize, Vec<Region>)>> {
+        let (offset, bytes) = (self.blocks[block].offset, self.blocks[block].bytes);
+        let file = self.file();
+        file.seek(SeekFrom::Start(offset))?;
+        let mut text = String::new();
+        file.take(bytes).read_to_string(&mut text)?;
+        text.lines()
+            .map(|line| {
+                parse_frame_line(line, REGION_FIELDS).ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, "corrupt detection spill file")
+                })
+            })
+            .collect()
+    }
+}
+
+impl Drop for Spill {
+    fn drop(&mut self) {
+        self.file = None;
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(x: i32) -> Region {
+        Region {
+            x,
+            y: 0,
+            width: 10,
+            height: 10,
+            track_id: Some(1),
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    fn spilling(dir: &Path, memory_frames: usize) -> DiskBackedDetections {
+        DiskBackedDetections::new()
+            .with_memory_frames(memory_frames)
+            .with_spill_dir(dir)
+    }
+
+    fn spill_files(dir: &Path) -> usize {
+        fs::read_dir(dir).unwrap().count()
+    }
+
+    #[test]
+    fn test_stays_in_memory_under_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut detections = spilling(dir.path(), 10);
+        for i in 0..10 {
+            detections.insert(i, vec![region(i as i32)]).unwrap();
+        }
+        assert!(!detections.has_spilled());
+        assert_eq!(spill_files(dir.path()), 0);
+        assert_eq!(detections.get(4).unwrap(), Some(vec![region(4)]));
+    }
+
+    #[test]
+    fn test_spilled_frames_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut detections = spilling(dir.path(), 3);
+        for i in 0..20 {
+            let regions = if i % 4 == 0 {
+                vec![]
+            } else {
+                vec![region(i as i32)]
+            };
+            detections.insert(i, regions).unwrap();
+        }
+        assert!(detections.has_spilled());
+        assert!(detections.memory.len() <= 3);
+        assert_eq!(detections.len(), 20);
+        for i in 0..20 {
+            let expected = if i % 4 == 0 {
+                vec![]
+            } else {
+                vec![region(i as i32)]
+            };
+            assert_eq!(detections.get(i).unwrap(), Some(expected), "frame {i}");
+        }
+        assert_eq!(detections.get(20).unwrap(), None);
+    }
+
+    #[test]
+    fn test_missing_frames_between_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut detections = spilling(dir.path(), 1);
+        detections.insert(2, vec![region(2)]).unwrap();
+        detections.insert(5, vec![region(5)]).unwrap();
+        detections.insert(9, vec![region(9)]).unwrap();
+        assert_eq!(detections.get(3).unwrap(), None);
+        assert_eq!(detections.get(0).unwrap(), None);
+        assert_eq!(detections.get(5).unwrap(), Some(vec![region(5)]));
+    }
+
+    #[test]
+    fn test_reinserting_spilled_frame_shadows_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut detections = spilling(dir.path(), 2);
+        for i in 0..6 {
+            detections.insert(i, vec![region(i as i32)]).unwrap();
+        }
+        detections.insert(1, vec![region(100)]).unwrap();
+        assert_eq!(detections.len(), 6);
+        assert_eq!(detections.get(1).unwrap(), Some(vec![region(100)]));
+
+        let mut seen = Vec::new();
+        detections
+            .for_each(|index, regions| {
+                seen.push((index, regions[0].x));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(seen, vec![(0, 0), (1, 100), (2, 2), (3, 3), (4, 4), (5, 5)]);
+    }
+
+    #[test]
+    fn test_to_map_matches_inserted_frames() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut detections = spilling(dir.path(), 4);
+        let mut expected = Detections::new();
+        for i in 0..25 {
+            detections.insert(i * 2, vec![region(i as i32)]).unwrap();
+            expected.insert(i * 2, vec![region(i as i32)]);
+        }
+        assert_eq!(detections.to_map().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_indices_past_u32() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut detections = spilling(dir.path(), 1);
+        let base = u32::MAX as usize + 10;
+        for i in 0..4 {
+            detections.insert(base + i, vec![region(i as i32)]).unwrap();
+        }
+        assert_eq!(detections.get(base + 2).unwrap(), Some(vec![region(2)]));
+    }
+
+    #[test]
+    fn test_spill_file_removed_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut detections = spilling(dir.path(), 1);
+        detections.insert(0, vec![region(0)]).unwrap();
+        detections.insert(1, vec![region(1)]).unwrap();
+        assert_eq!(spill_files(dir.path()), 1);
+        drop(detections);
+        assert_eq!(spill_files(dir.path()), 0);
+    }
+
+    #[test]
+    fn test_from_map_never_spills() {
+        let map = Detections::from([(0, vec![region(0)]), (3, vec![])]);
+        let mut detections = DiskBackedDetections::from(map);
+        assert_eq!(detections.len(), 2);
+        assert!(!detections.has_spilled());
+        assert_eq!(detections.get(3).unwrap(), Some(vec![]));
+    }
+}
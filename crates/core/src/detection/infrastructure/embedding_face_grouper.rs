@@ -1,10 +1,12 @@
 /// ArcFace embedding-based face grouper using ONNX Runtime.
 ///
 /// Clusters faces by cosine similarity of ArcFace embeddings. Preferred
-/// over histogram grouping when model availability and latency allow.
+/// over histogram grouping when model availability and latency allow. Also
+/// a [`FaceEmbedder`], for matching faces against enrolled identities.
 use std::path::Path;
 use std::sync::Mutex;
 
+use crate::detection::domain::face_embedder::FaceEmbedder;
 use crate::detection::domain::face_grouper::FaceGrouper;
 use crate::detection::infrastructure::math;
 
@@ -37,7 +39,9 @@ impl EmbeddingFaceGrouper {
             threshold,
         })
     }
+}
 
+impl FaceEmbedder for EmbeddingFaceGrouper {
     fn embed(
         &self,
         rgb_data: &[u8],
@@ -1,17 +1,75 @@
+use ort::execution_providers::{
+    CUDAExecutionProvider, CoreMLExecutionProvider, DirectMLExecutionProvider, ExecutionProvider,
+    ExecutionProviderDispatch,
+};
+
+use crate::detection::domain::detector_backend::DetectorBackend;
+
 /// Return the preferred ONNX execution providers for the current platform.
 ///
 /// Falls back to CPU if the platform-specific provider is unavailable.
-pub fn preferred_execution_providers() -> Vec<ort::execution_providers::ExecutionProviderDispatch> {
+pub fn preferred_execution_providers() -> Vec<ExecutionProviderDispatch> {
     #[cfg(target_os = "macos")]
     {
-        vec![ort::execution_providers::CoreMLExecutionProvider::default().build()]
+        vec![CoreMLExecutionProvider::default().build()]
     }
     #[cfg(target_os = "windows")]
     {
-        vec![ort::execution_providers::DirectMLExecutionProvider::default().build()]
+        vec![DirectMLExecutionProvider::default().build()]
     }
     #[cfg(not(any(target_os = "macos", target_os = "windows")))]
     {
         vec![]
     }
 }
+
+/// Return the execution providers for `backend`.
+///
+/// An empty list runs on the CPU. A provider that this platform or ONNX
+/// Runtime build lacks is logged and left out, and so is one that fails
+/// to load when the session is built.
+pub fn execution_providers(backend: DetectorBackend) -> Vec<ExecutionProviderDispatch> {
+    match backend {
+        DetectorBackend::Auto => preferred_execution_providers(),
+        DetectorBackend::Cpu => vec![],
+        DetectorBackend::Cuda => if_available(CUDAExecutionProvider::default(), backend),
+        DetectorBackend::CoreMl => if_available(CoreMLExecutionProvider::default(), backend),
+        DetectorBackend::DirectMl => if_available(DirectMLExecutionProvider::default(), backend),
+    }
+}
+
+fn if_available<E>(provider: E, backend: DetectorBackend) -> Vec<ExecutionProviderDispatch>
+where
+    E: ExecutionProvider,
+    ExecutionProviderDispatch: From<E>,
+{
+    if provider.supported_by_platform() && provider.is_available().unwrap_or(false) {
+        vec![provider.into()]
+    } else {
+        log::warn!(
+            "{} is not available in this build; detecting on the CPU",
+            backend.label()
+        );
+        vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_uses_no_providers() {
+        assert!(execution_providers(DetectorBackend::Cpu).is_empty());
+    }
+
+    #[test]
+    fn test_unsupported_platform_falls_back_to_cpu() {
+        let foreign = if cfg!(target_os = "windows") {
+            DetectorBackend::CoreMl
+        } else {
+            DetectorBackend::DirectMl
+        };
+        assert!(execution_providers(foreign).is_empty());
+    }
+}
@@ -0,0 +1,105 @@
+use std::path::Path;
+
+/// File name used for the groups listing inside a preview folder.
+pub const GROUPS_FILE_NAME: &str = "groups.txt";
+
+/// Formats identity groups as one `group:N ID,ID,...` line per group.
+///
+/// Groups are numbered from 1 in the order given; the `group:N` label is
+/// exactly what `--blur-ids`/`--exclude-ids` accept, so users can copy it.
+pub fn format_face_groups(groups: &[Vec<u32>]) -> String {
+    let mut out = String::from("# group track_ids\n");
+    for (i, group) in groups.iter().enumerate() {
+        let ids: Vec<String> = group.iter().map(u32::to_string).collect();
+        out.push_str(&format!("group:{} {}\n", i + 1, ids.join(",")));
+    }
+    out
+}
+
+/// Parses the output of [`format_face_groups`]. Blank lines and `#`
+/// comments are ignored; groups are returned in label order.
+pub fn parse_face_groups(text: &str) -> Result<Vec<Vec<u32>>, String> {
+    let mut numbered: Vec<(usize, Vec<u32>)> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = || format!("Invalid groups line '{line}' (expected group:N ID,ID,...)");
+        let (label, ids) = line.split_once(char::is_whitespace).ok_or_else(invalid)?;
+        let number: usize = label
+            .strip_prefix("group:")
+            .and_then(|n| n.parse().ok())
+            .ok_or_else(invalid)?;
+        let ids = ids
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|id| id.parse().map_err(|_| invalid()))
+            .collect::<Result<Vec<u32>, String>>()?;
+        numbered.push((number, ids));
+    }
+    numbered.sort_by_key(|(number, _)| *number);
+    for (i, (number, _)) in numbered.iter().enumerate() {
+        if *number != i + 1 {
+            return Err(format!("Groups file is missing group:{}", i + 1));
+        }
+    }
+    Ok(numbered.into_iter().map(|(_, ids)| ids).collect())
+}
+
+pub fn write_face_groups(
+    path: &Path,
+    groups: &[Vec<u32>],
+) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::write(path, format_face_groups(groups))?;
+    Ok(())
+}
+
+pub fn read_face_groups(path: &Path) -> Result<Vec<Vec<u32>>, Box<dyn std::error::Error>> {
+    let text = std::fs::read_to_string(path)?;
+    Ok(parse_face_groups(&text)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_numbers_groups_from_one() {
+        let text = format_face_groups(&[vec![1, 4], vec![2]]);
+        assert_eq!(text, "# group track_ids\ngroup:1 1,4\ngroup:2 2\n");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let groups = vec![vec![3, 9, 12], vec![5], vec![7, 8]];
+        assert_eq!(
+            parse_face_groups(&format_face_groups(&groups)).unwrap(),
+            groups
+        );
+    }
+
+    #[test]
+    fn test_parse_orders_by_label() {
+        let groups = parse_face_groups("group:2 5\ngroup:1 1,2\n").unwrap();
+        assert_eq!(groups, vec![vec![1, 2], vec![5]]);
+    }
+
+    #[test]
+    fn test_parse_rejects_gaps() {
+        assert!(parse_face_groups("group:1 1\ngroup:3 2\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_ids() {
+        assert!(parse_face_groups("group:1 1,x\n").is_err());
+    }
+
+    #[test]
+    fn test_write_and_read_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(GROUPS_FILE_NAME);
+        write_face_groups(&path, &[vec![1], vec![2, 3]]).unwrap();
+        assert_eq!(read_face_groups(&path).unwrap(), vec![vec![1], vec![2, 3]]);
+    }
+}
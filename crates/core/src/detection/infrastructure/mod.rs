@@ -1,9 +1,22 @@
 pub mod bytetrack_tracker;
+pub mod cache_key_params;
 pub mod cached_face_detector;
+pub mod checkpointing_detector;
+pub mod counting_face_detector;
+pub mod detection_csv;
+pub mod detection_disk_cache;
+pub mod detector_state;
+pub mod disk_backed_detections;
 pub mod embedding_face_grouper;
 pub mod execution_provider;
+pub mod face_groups_file;
 pub mod histogram_face_grouper;
 pub mod math;
+pub mod model_registry;
 pub mod model_resolver;
+pub mod model_updates;
+pub mod mouth_activity_detector;
+pub mod onnx_text_detector;
 pub mod onnx_yolo_detector;
+pub mod recording_face_detector;
 pub mod skip_frame_detector;
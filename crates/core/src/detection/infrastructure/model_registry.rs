@@ -0,0 +1,279 @@
+use std::path::{Path, PathBuf};
+
+use crate::detection::domain::confidence_calibration::ConfidenceCalibration;
+use crate::detection::infrastructure::model_resolver::ModelSpec;
+use crate::shared::constants::{
+    PERSON_MODEL_NAME, PERSON_MODEL_URL, SCRFD_10G_MODEL_NAME, SCRFD_10G_MODEL_URL,
+    SCRFD_2_5G_MODEL_NAME, SCRFD_2_5G_MODEL_URL, YOLOV8M_FACE_MODEL_NAME, YOLOV8M_FACE_MODEL_URL,
+    YOLOV8N_FACE_MODEL_NAME, YOLOV8N_FACE_MODEL_URL, YOLO_MODEL_NAME, YOLO_MODEL_URL,
+};
+
+/// How a detection model lays out its outputs, which decides how they are
+/// decoded into boxes and landmarks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputLayout {
+    /// One `[1, features, anchors]` tensor (or its transpose): box center
+    /// and size, score, then class scores or landmark triples.
+    Yolo,
+    /// Score, box-distance, and landmark-distance maps for strides 8, 16,
+    /// and 32, in that order, as InsightFace exports them.
+    Scrfd,
+}
+
+/// What a detection model takes as input: a square, letterboxed RGB image
+/// with each channel value `v` (0–255) fed as `(v - mean) / std`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InputSpec {
+    /// Side of the square input. `None` reads it from the model, falling
+    /// back to 640 when the model accepts any size.
+    pub size: Option<u32>,
+    pub mean: f32,
+    pub std: f32,
+}
+
+/// Input and output conventions of a detection model.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ModelFormat {
+    pub input: InputSpec,
+    pub output: OutputLayout,
+}
+
+impl ModelFormat {
+    /// Ultralytics YOLO: values in 0–1, size from the model.
+    pub const YOLO: Self = Self {
+        input: InputSpec {
+            size: None,
+            mean: 0.0,
+            std: 255.0,
+        },
+        output: OutputLayout::Yolo,
+    };
+
+    /// InsightFace SCRFD: values centered on 127.5, exported with a
+    /// dynamic size and run at 640.
+    pub const SCRFD: Self = Self {
+        input: InputSpec {
+            size: Some(640),
+            mean: 127.5,
+            std: 128.0,
+        },
+        output: OutputLayout::Scrfd,
+    };
+}
+
+/// A detection model that can be picked by ID, e.g. `--model scrfd-2.5g`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DetectionModel {
+    pub id: &'static str,
+    pub spec: ModelSpec,
+    pub format: ModelFormat,
+    /// One line for listings: what the model suits.
+    pub summary: &'static str,
+}
+
+/// ID of the face model used unless another is picked.
+pub const DEFAULT_DETECTION_MODEL: &str = "yolo11n-face";
+
+/// Face detectors that can be picked by ID, smallest first within each
+/// family.
+///
+/// The bundled YOLO11n model defines the sensitivity scale. The others
+/// use their raw scores until a calibration against it is measured, so the
+/// same sensitivity can keep more or fewer faces with them.
+pub const DETECTION_MODELS: &[DetectionModel] = &[
+    DetectionModel {
+        id: DEFAULT_DETECTION_MODEL,
+        spec: ModelSpec {
+            name: YOLO_MODEL_NAME,
+            url: YOLO_MODEL_URL,
+            calibration: ConfidenceCalibration::Identity,
+            sha256: None,
+        },
+        format: ModelFormat::YOLO,
+        summary: "YOLO11 nano; the default, fast on any machine",
+    },
+    DetectionModel {
+        id: "yolov8n-face",
+        spec: ModelSpec {
+            name: YOLOV8N_FACE_MODEL_NAME,
+            url: YOLOV8N_FACE_MODEL_URL,
+            calibration: ConfidenceCalibration::Identity,
+            sha256: None,
+        },
+        format: ModelFormat::YOLO,
+        summary: "YOLOv8 nano; fast on older machines",
+    },
+    DetectionModel {
+        id: "yolov8m-face",
+        spec: ModelSpec {
+            name: YOLOV8M_FACE_MODEL_NAME,
+            url: YOLOV8M_FACE_MODEL_URL,
+            calibration: ConfidenceCalibration::Identity,
+            sha256: None,
+        },
+        format: ModelFormat::YOLO,
+        summary: "YOLOv8 medium; finds smaller faces, best with a GPU",
+    },
+    DetectionModel {
+        id: "scrfd-2.5g",
+        spec: ModelSpec {
+            name: SCRFD_2_5G_MODEL_NAME,
+            url: SCRFD_2_5G_MODEL_URL,
+            calibration: ConfidenceCalibration::Identity,
+            sha256: None,
+        },
+        format: ModelFormat::SCRFD,
+        summary: "SCRFD 2.5 GFLOPs; the lightest",
+    },
+    DetectionModel {
+        id: "scrfd-10g",
+        spec: ModelSpec {
+            name: SCRFD_10G_MODEL_NAME,
+            url: SCRFD_10G_MODEL_URL,
+            calibration: ConfidenceCalibration::Identity,
+            sha256: None,
+        },
+        format: ModelFormat::SCRFD,
+        summary: "SCRFD 10 GFLOPs; more accurate in crowds",
+    },
+];
+
+/// The COCO person detector behind `BlurTarget::Person`. Not a face model,
+/// so it can't be picked in place of one.
+pub const PERSON_DETECTION_MODEL: DetectionModel = DetectionModel {
+    id: "yolo11n-person",
+    spec: ModelSpec {
+        name: PERSON_MODEL_NAME,
+        url: PERSON_MODEL_URL,
+        calibration: ConfidenceCalibration::Identity,
+        sha256: None,
+    },
+    format: ModelFormat::YOLO,
+    summary: "YOLO11 nano, COCO person class",
+};
+
+/// The registered face model with ID `id`.
+pub fn find(id: &str) -> Option<&'static DetectionModel> {
+    DETECTION_MODELS.iter().find(|model| model.id == id)
+}
+
+/// The bundled face model.
+pub fn default_model() -> &'static DetectionModel {
+    find(DEFAULT_DETECTION_MODEL).expect("the default model is registered")
+}
+
+/// A picked detection model: a registered one, resolved through the model
+/// cache, or an ONNX file of the user's own.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ModelChoice {
+    Registered(&'static DetectionModel),
+    /// Read as it is, never downloaded. Assumed to follow the YOLO layout
+    /// with five landmarks, like the bundled model.
+    Custom(PathBuf),
+}
+
+impl Default for ModelChoice {
+    fn default() -> Self {
+        Self::Registered(default_model())
+    }
+}
+
+impl ModelChoice {
+    /// A registered model's ID, or a path to an `.onnx` file.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        if let Some(model) = find(value) {
+            return Ok(Self::Registered(model));
+        }
+        let path = Path::new(value);
+        let is_onnx = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("onnx"));
+        if is_onnx {
+            return Ok(Self::Custom(path.to_path_buf()));
+        }
+        let ids: Vec<&str> = DETECTION_MODELS.iter().map(|model| model.id).collect();
+        Err(format!(
+            "Unknown model '{value}': expected {} or a path to an .onnx file",
+            ids.join(", ")
+        ))
+    }
+
+    /// The ID, or the custom file's name.
+    pub fn name(&self) -> String {
+        match self {
+            Self::Registered(model) => model.id.to_string(),
+            Self::Custom(path) => path.file_name().map_or_else(
+                || path.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            ),
+        }
+    }
+
+    pub fn format(&self) -> ModelFormat {
+        match self {
+            Self::Registered(model) => model.format,
+            Self::Custom(_) => ModelFormat::YOLO,
+        }
+    }
+
+    pub fn calibration(&self) -> ConfidenceCalibration {
+        match self {
+            Self::Registered(model) => model.spec.calibration,
+            Self::Custom(_) => ConfidenceCalibration::Identity,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn test_ids_and_files_are_unique() {
+        let ids: std::collections::HashSet<_> = DETECTION_MODELS.iter().map(|m| m.id).collect();
+        let names: std::collections::HashSet<_> =
+            DETECTION_MODELS.iter().map(|m| m.spec.name).collect();
+        assert_eq!(ids.len(), DETECTION_MODELS.len());
+        assert_eq!(names.len(), DETECTION_MODELS.len());
+    }
+
+    #[test]
+    fn test_default_is_the_bundled_reference_model() {
+        let model = default_model();
+        assert_eq!(model.spec.name, YOLO_MODEL_NAME);
+        assert_eq!(model.spec.calibration, ConfidenceCalibration::Identity);
+        assert_eq!(ModelChoice::default(), ModelChoice::Registered(model));
+    }
+
+    #[rstest]
+    #[case("yolov8n-face", OutputLayout::Yolo)]
+    #[case("scrfd-2.5g", OutputLayout::Scrfd)]
+    fn test_parse_registered_id(#[case] id: &str, #[case] layout: OutputLayout) {
+        let choice = ModelChoice::parse(id).unwrap();
+        assert_eq!(choice.name(), id);
+        assert_eq!(choice.format().output, layout);
+    }
+
+    #[test]
+    fn test_parse_custom_onnx_path() {
+        let choice = ModelChoice::parse("models/My-Face.ONNX").unwrap();
+        assert_eq!(
+            choice,
+            ModelChoice::Custom(PathBuf::from("models/My-Face.ONNX"))
+        );
+        assert_eq!(choice.name(), "My-Face.ONNX");
+        assert_eq!(choice.format(), ModelFormat::YOLO);
+    }
+
+    #[test]
+    fn test_parse_unknown_id_lists_models() {
+        let error = ModelChoice::parse("yolov9-face").unwrap_err();
+        assert!(error.contains("scrfd-10g"), "{error}");
+    }
+
+    #[test]
+    fn test_person_model_cannot_be_picked() {
+        assert!(find(PERSON_DETECTION_MODEL.id).is_none());
+    }
+}
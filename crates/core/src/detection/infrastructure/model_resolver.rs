@@ -1,19 +1,53 @@
 use std::fs;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 
+use crate::detection::domain::confidence_calibration::ConfidenceCalibration;
+use crate::detection::infrastructure::model_registry::DETECTION_MODELS;
+use crate::shared::cancellation::{CancellationToken, Cancelled};
+use crate::shared::constants::{
+    EMBEDDING_MODEL_NAME, EMBEDDING_MODEL_URL, PERSON_MODEL_NAME, PERSON_MODEL_URL,
+    TEXT_DETECTION_MODEL_NAME, TEXT_DETECTION_MODEL_URL, TEXT_RECOGNITION_MODEL_NAME,
+    TEXT_RECOGNITION_MODEL_URL, WHISPER_MODEL_NAME, WHISPER_MODEL_URL, YOLO_MODEL_NAME,
+    YOLO_MODEL_URL,
+};
+
+/// Environment variable that overrides the model cache directory.
+pub const MODEL_DIR_ENV: &str = "FACEGUARD_MODEL_DIR";
+
+/// Environment variable that, when set to anything but `0`, allows
+/// downloading models that have no recorded SHA-256.
+pub const ALLOW_UNVERIFIED_ENV: &str = "FACEGUARD_ALLOW_UNVERIFIED_MODELS";
+
 #[derive(Error, Debug)]
 pub enum ModelResolveError {
     #[error("failed to create cache directory: {0}")]
     CacheDir(#[source] std::io::Error),
-    #[error("download failed for {url}: {source}")]
+    #[error("download failed for {url}: {source} (to install it offline, copy the file to {})", path.display())]
     Download {
         url: String,
+        /// Where the model is looked for, so it can be put there by hand.
+        path: PathBuf,
         #[source]
         source: reqwest::Error,
     },
+    #[error("{name} does not match its SHA-256 (expected {expected}, got {actual}); the download is corrupt or was tampered with")]
+    ChecksumMismatch {
+        name: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("no SHA-256 is recorded for {name}, so its download from {url} can't be verified (set {}=1 to download it anyway, or copy the file to {})", ALLOW_UNVERIFIED_ENV, path.display())]
+    Unverified {
+        name: String,
+        url: String,
+        /// Where the model is looked for, so it can be put there by hand.
+        path: PathBuf,
+    },
     #[error("failed to write model to {path}: {source}")]
     Write {
         path: PathBuf,
@@ -22,25 +56,166 @@ pub enum ModelResolveError {
     },
     #[error("could not determine cache directory")]
     NoCacheDir,
+    #[error("{0}")]
+    Cancelled(#[from] Cancelled),
 }
 
 /// Progress callback: `(bytes_downloaded, total_bytes)`.
 /// `total_bytes` is 0 if the server didn't provide Content-Length.
 pub type ProgressFn = Box<dyn Fn(u64, u64) + Send>;
 
+/// A downloadable model: its cache file name, source URL, and how its
+/// confidence scores map onto the shared sensitivity scale.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ModelSpec {
+    pub name: &'static str,
+    pub url: &'static str,
+    pub calibration: ConfidenceCalibration,
+    /// Lowercase hex SHA-256 of the file at `url`, checked after every
+    /// download. `None` until the release's digest is recorded here; such
+    /// models are only downloaded when [`unverified_allowed`].
+    pub sha256: Option<&'static str>,
+}
+
+/// Every model the app may need, in the order a first job uses them.
+///
+/// The sensitivity scale is defined by the bundled YOLO face model, so its
+/// calibration is the identity. Alternative detectors added here should
+/// declare a mapping measured against it. The person detector finds a
+/// different thing, so there is nothing to measure it against; its scores
+/// are used as they are.
+pub const REGISTERED_MODELS: &[ModelSpec] = &[
+    ModelSpec {
+        name: YOLO_MODEL_NAME,
+        url: YOLO_MODEL_URL,
+        calibration: ConfidenceCalibration::Identity,
+        sha256: None,
+    },
+    ModelSpec {
+        name: PERSON_MODEL_NAME,
+        url: PERSON_MODEL_URL,
+        calibration: ConfidenceCalibration::Identity,
+        sha256: None,
+    },
+    ModelSpec {
+        name: EMBEDDING_MODEL_NAME,
+        url: EMBEDDING_MODEL_URL,
+        calibration: ConfidenceCalibration::Identity,
+        sha256: None,
+    },
+    ModelSpec {
+        name: WHISPER_MODEL_NAME,
+        url: WHISPER_MODEL_URL,
+        calibration: ConfidenceCalibration::Identity,
+        sha256: None,
+    },
+    ModelSpec {
+        name: TEXT_DETECTION_MODEL_NAME,
+        url: TEXT_DETECTION_MODEL_URL,
+        calibration: ConfidenceCalibration::Identity,
+        sha256: None,
+    },
+    ModelSpec {
+        name: TEXT_RECOGNITION_MODEL_NAME,
+        url: TEXT_RECOGNITION_MODEL_URL,
+        calibration: ConfidenceCalibration::Identity,
+        sha256: None,
+    },
+];
+
+/// Calibration for a registered model, or the identity for unknown names.
+pub fn calibration_for(name: &str) -> ConfidenceCalibration {
+    REGISTERED_MODELS
+        .iter()
+        .find(|m| m.name == name)
+        .map_or(ConfidenceCalibration::Identity, |m| m.calibration)
+}
+
+/// Expected SHA-256 of a registered or selectable model, by file name.
+pub fn sha256_for(name: &str) -> Option<&'static str> {
+    REGISTERED_MODELS
+        .iter()
+        .chain(DETECTION_MODELS.iter().map(|model| &model.spec))
+        .find(|m| m.name == name)
+        .and_then(|m| m.sha256)
+}
+
+/// Prefetch progress: `(model index, bytes_downloaded, total_bytes)`.
+pub type PrefetchProgressFn = Arc<dyn Fn(usize, u64, u64) + Send + Sync>;
+
 /// Resolve a model file by name, checking cache locations before downloading.
 ///
 /// Resolution order:
-/// 1. User cache directory (platform-specific)
+/// 1. User cache directory (see [`model_cache_dir`])
 /// 2. Bundled path (for development / pre-packaged installs)
-/// 3. Download from URL to cache
+/// 3. Download from URL to cache, checked against the model's SHA-256
+///    (see [`sha256_for`]); a model without one is refused unless
+///    [`unverified_allowed`]
+///
+/// Files already in the cache or bundled folder are used as they are, so a
+/// folder provisioned for an offline install needs no network.
 pub fn resolve(
     name: &str,
     url: &str,
     bundled_dir: Option<&Path>,
     progress: Option<ProgressFn>,
 ) -> Result<PathBuf, ModelResolveError> {
-    let cache_dir = model_cache_dir()?;
+    resolve_cancellable(name, url, bundled_dir, progress, &CancellationToken::new())
+}
+
+/// Like [`resolve`], but a download stops once `cancelled` is cancelled,
+/// leaving no partial file behind.
+pub fn resolve_cancellable(
+    name: &str,
+    url: &str,
+    bundled_dir: Option<&Path>,
+    progress: Option<ProgressFn>,
+    cancelled: &CancellationToken,
+) -> Result<PathBuf, ModelResolveError> {
+    resolve_in(
+        &model_cache_dir()?,
+        name,
+        url,
+        sha256_for(name),
+        unverified_allowed(),
+        bundled_dir,
+        progress,
+        cancelled,
+    )
+}
+
+/// Like [`resolve`], but with `model_dir` in place of the cache directory,
+/// for a folder the user picked (e.g. an offline install's).
+pub fn resolve_in_dir(
+    model_dir: &Path,
+    name: &str,
+    url: &str,
+    bundled_dir: Option<&Path>,
+    progress: Option<ProgressFn>,
+) -> Result<PathBuf, ModelResolveError> {
+    resolve_in(
+        model_dir,
+        name,
+        url,
+        sha256_for(name),
+        unverified_allowed(),
+        bundled_dir,
+        progress,
+        &CancellationToken::new(),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn resolve_in(
+    cache_dir: &Path,
+    name: &str,
+    url: &str,
+    sha256: Option<&str>,
+    allow_unverified: bool,
+    bundled_dir: Option<&Path>,
+    progress: Option<ProgressFn>,
+    cancelled: &CancellationToken,
+) -> Result<PathBuf, ModelResolveError> {
     let cached_path = cache_dir.join(name);
     if cached_path.exists() {
         return Ok(cached_path);
@@ -52,17 +227,102 @@ pub fn resolve(
             return Ok(bundled_path);
         }
     }
-    fs::create_dir_all(&cache_dir).map_err(ModelResolveError::CacheDir)?;
-    download(url, &cached_path, progress)?;
+    require_sha256(name, url, &cached_path, sha256, allow_unverified)?;
+    fs::create_dir_all(cache_dir).map_err(ModelResolveError::CacheDir)?;
+    download(url, &cached_path, sha256, progress, cancelled)?;
     Ok(cached_path)
 }
 
-/// Platform-specific model cache directory.
+/// Downloads every registered model that is not already cached, so the
+/// first job does not stop for a download. Returns the resolved paths in
+/// `REGISTERED_MODELS` order. Stops at the next chunk once `cancelled` is
+/// cancelled; models already downloaded stay cached.
+pub fn prefetch_all(
+    progress: Option<PrefetchProgressFn>,
+    cancelled: &CancellationToken,
+) -> Result<Vec<PathBuf>, ModelResolveError> {
+    prefetch_into(
+        &model_cache_dir()?,
+        REGISTERED_MODELS,
+        unverified_allowed(),
+        progress,
+        cancelled,
+    )
+}
+
+fn prefetch_into(
+    cache_dir: &Path,
+    models: &[ModelSpec],
+    allow_unverified: bool,
+    progress: Option<PrefetchProgressFn>,
+    cancelled: &CancellationToken,
+) -> Result<Vec<PathBuf>, ModelResolveError> {
+    models
+        .iter()
+        .enumerate()
+        .map(|(index, model)| {
+            let on_progress = progress.clone().map(|cb| -> ProgressFn {
+                Box::new(move |downloaded, total| cb(index, downloaded, total))
+            });
+            resolve_in(
+                cache_dir,
+                model.name,
+                model.url,
+                model.sha256,
+                allow_unverified,
+                None,
+                on_progress,
+                cancelled,
+            )
+        })
+        .collect()
+}
+
+/// Whether models without a recorded SHA-256 may be downloaded, as opted
+/// into through `$FACEGUARD_ALLOW_UNVERIFIED_MODELS`.
+pub fn unverified_allowed() -> bool {
+    std::env::var_os(ALLOW_UNVERIFIED_ENV).is_some_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Errors unless a download of `name` to `dest` can be checked against
+/// `sha256`, or unverified downloads are allowed, in which case one
+/// without a digest is logged.
+pub(crate) fn require_sha256(
+    name: &str,
+    url: &str,
+    dest: &Path,
+    sha256: Option<&str>,
+    allow_unverified: bool,
+) -> Result<(), ModelResolveError> {
+    if sha256.is_some() {
+        return Ok(());
+    }
+    if !allow_unverified {
+        return Err(ModelResolveError::Unverified {
+            name: name.to_string(),
+            url: url.to_string(),
+            path: dest.to_path_buf(),
+        });
+    }
+    log::warn!("No SHA-256 is recorded for {name}; its download from {url} is not verified");
+    Ok(())
+}
+
+/// Whether `name` is already in the model cache.
+pub fn is_cached(name: &str) -> bool {
+    model_cache_dir().is_ok_and(|dir| dir.join(name).exists())
+}
+
+/// Model cache directory: `$FACEGUARD_MODEL_DIR` when set, otherwise a
+/// platform-specific default.
 ///
 /// - macOS: `~/Library/Application Support/FaceGuard/models/`
 /// - Linux: `$XDG_CACHE_HOME/FaceGuard/models/` or `~/.cache/FaceGuard/models/`
 /// - Windows: `%LOCALAPPDATA%/FaceGuard/models/`
 pub fn model_cache_dir() -> Result<PathBuf, ModelResolveError> {
+    if let Some(dir) = std::env::var_os(MODEL_DIR_ENV).filter(|d| !d.is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
     #[cfg(target_os = "macos")]
     {
         dirs::data_dir()
@@ -77,10 +337,18 @@ pub fn model_cache_dir() -> Result<PathBuf, ModelResolveError> {
     }
 }
 
-fn download(url: &str, dest: &Path, progress: Option<ProgressFn>) -> Result<(), ModelResolveError> {
+/// Downloads `url` to `dest` through a `.part` file, which is only renamed
+/// into place once complete and, when `sha256` is given, matching it.
+pub(crate) fn download(
+    url: &str,
+    dest: &Path,
+    sha256: Option<&str>,
+    progress: Option<ProgressFn>,
+    cancelled: &CancellationToken,
+) -> Result<(), ModelResolveError> {
     let temp_path = dest.with_extension("part");
 
-    let result = download_inner(url, dest, &temp_path, progress);
+    let result = download_inner(url, dest, &temp_path, sha256, progress, cancelled);
 
     // Clean up .part file on any error
     if result.is_err() {
@@ -94,12 +362,16 @@ fn download_inner(
     url: &str,
     dest: &Path,
     temp_path: &Path,
+    sha256: Option<&str>,
     progress: Option<ProgressFn>,
+    cancelled: &CancellationToken,
 ) -> Result<(), ModelResolveError> {
+    cancelled.check()?;
     let response = reqwest::blocking::get(url)
         .and_then(|r| r.error_for_status())
         .map_err(|e| ModelResolveError::Download {
             url: url.to_string(),
+            path: dest.to_path_buf(),
             source: e,
         })?;
 
@@ -115,8 +387,10 @@ fn download_inner(
     // This avoids loading large models (100MB+) entirely into RAM and
     // provides accurate progress reporting during the actual download.
     let mut reader = response;
+    let mut hasher = Sha256::new();
     let mut buf = vec![0u8; 1024 * 1024]; // 1MB buffer
     loop {
+        cancelled.check()?;
         let n = reader
             .read(&mut buf)
             .map_err(|e| ModelResolveError::Write {
@@ -131,6 +405,7 @@ fn download_inner(
                 path: temp_path.to_path_buf(),
                 source: e,
             })?;
+        hasher.update(&buf[..n]);
         downloaded += n as u64;
         if let Some(ref cb) = progress {
             cb(downloaded, total);
@@ -142,6 +417,9 @@ fn download_inner(
         source: e,
     })?;
     drop(file);
+    if let Some(expected) = sha256 {
+        check_sha256(dest, expected, &hasher.finalize())?;
+    }
 
     fs::rename(temp_path, dest).map_err(|e| ModelResolveError::Write {
         path: dest.to_path_buf(),
@@ -151,9 +429,30 @@ fn download_inner(
     Ok(())
 }
 
+/// Errors unless `digest` is the SHA-256 `expected` for the model at `path`.
+fn check_sha256(path: &Path, expected: &str, digest: &[u8]) -> Result<(), ModelResolveError> {
+    let actual = to_hex(digest);
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        return Ok(());
+    }
+    Err(ModelResolveError::ChecksumMismatch {
+        name: path.file_name().map_or_else(
+            || path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        ),
+        expected: expected.trim().to_ascii_lowercase(),
+        actual,
+    })
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shared::cancellation::CancelReason;
     use tempfile::TempDir;
 
     #[test]
@@ -204,6 +503,232 @@ mod tests {
         assert!(path.to_string_lossy().contains("models"));
     }
 
+    #[test]
+    fn test_prefetch_returns_cached_models_without_downloading() {
+        let tmp = TempDir::new().unwrap();
+        let models = [
+            ModelSpec {
+                name: "a.onnx",
+                url: "http://invalid.nonexistent.example.com/a.onnx",
+                calibration: ConfidenceCalibration::Identity,
+                sha256: None,
+            },
+            ModelSpec {
+                name: "b.bin",
+                url: "http://invalid.nonexistent.example.com/b.bin",
+                calibration: ConfidenceCalibration::Identity,
+                sha256: None,
+            },
+        ];
+        for model in &models {
+            fs::write(tmp.path().join(model.name), b"cached").unwrap();
+        }
+
+        let paths =
+            prefetch_into(tmp.path(), &models, false, None, &CancellationToken::new()).unwrap();
+        assert_eq!(
+            paths,
+            vec![tmp.path().join("a.onnx"), tmp.path().join("b.bin")]
+        );
+    }
+
+    #[test]
+    fn test_prefetch_stops_at_first_failed_download() {
+        let tmp = TempDir::new().unwrap();
+        let models = [ModelSpec {
+            name: "missing.onnx",
+            url: "http://invalid.nonexistent.example.com/missing.onnx",
+            calibration: ConfidenceCalibration::Identity,
+            sha256: None,
+        }];
+        assert!(prefetch_into(tmp.path(), &models, true, None, &CancellationToken::new()).is_err());
+        assert!(!tmp.path().join("missing.onnx").exists());
+    }
+
+    #[test]
+    fn test_registered_models_have_unique_names() {
+        let names: std::collections::HashSet<_> =
+            REGISTERED_MODELS.iter().map(|m| m.name).collect();
+        assert_eq!(names.len(), REGISTERED_MODELS.len());
+    }
+
+    #[test]
+    fn test_calibration_for_unknown_model_is_identity() {
+        assert_eq!(
+            calibration_for("not-a-model.onnx"),
+            ConfidenceCalibration::Identity
+        );
+    }
+
+    #[test]
+    fn test_reference_detector_is_uncalibrated() {
+        assert_eq!(
+            calibration_for(YOLO_MODEL_NAME),
+            ConfidenceCalibration::Identity
+        );
+    }
+
+    /// SHA-256 of `abc`, from FIPS 180-2.
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    #[test]
+    fn test_check_sha256_accepts_matching_digest() {
+        let digest = Sha256::digest(b"abc");
+        let path = Path::new("models/a.onnx");
+        assert!(check_sha256(path, ABC_SHA256, &digest).is_ok());
+        assert!(check_sha256(path, &ABC_SHA256.to_uppercase(), &digest).is_ok());
+    }
+
+    #[test]
+    fn test_check_sha256_rejects_other_file() {
+        let digest = Sha256::digest(b"abd");
+        let err = check_sha256(Path::new("models/a.onnx"), ABC_SHA256, &digest).unwrap_err();
+        match err {
+            ModelResolveError::ChecksumMismatch {
+                name,
+                expected,
+                actual,
+            } => {
+                assert_eq!(name, "a.onnx");
+                assert_eq!(expected, ABC_SHA256);
+                assert_ne!(actual, ABC_SHA256);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    /// Serves `body` to one HTTP request on a local port and returns its URL.
+    fn serve_once(body: &'static [u8]) -> String {
+        use std::io::BufRead;
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/model.onnx", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut request = std::io::BufReader::new(stream);
+            let mut line = String::new();
+            while request.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            let mut stream = request.into_inner();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            )
+            .unwrap();
+            stream.write_all(body).unwrap();
+        });
+        url
+    }
+
+    #[test]
+    fn test_resolve_rejects_download_with_other_digest() {
+        let tmp = TempDir::new().unwrap();
+        let url = serve_once(b"abd");
+
+        let err = resolve_in(
+            tmp.path(),
+            "model.onnx",
+            &url,
+            Some(ABC_SHA256),
+            false,
+            None,
+            None,
+            &CancellationToken::new(),
+        )
+        .unwrap_err();
+
+        assert!(
+            matches!(err, ModelResolveError::ChecksumMismatch { .. }),
+            "{err}"
+        );
+        assert!(!tmp.path().join("model.onnx").exists());
+        assert!(!tmp.path().join("model.part").exists());
+    }
+
+    #[test]
+    fn test_resolve_keeps_download_with_matching_digest() {
+        let tmp = TempDir::new().unwrap();
+        let url = serve_once(b"abc");
+
+        let path = resolve_in(
+            tmp.path(),
+            "model.onnx",
+            &url,
+            Some(ABC_SHA256),
+            false,
+            None,
+            None,
+            &CancellationToken::new(),
+        )
+        .unwrap();
+
+        assert_eq!(fs::read(path).unwrap(), b"abc");
+    }
+
+    #[test]
+    fn test_resolve_refuses_model_without_digest() {
+        let tmp = TempDir::new().unwrap();
+
+        let err = resolve_in(
+            tmp.path(),
+            "model.onnx",
+            "http://invalid.nonexistent.example.com/model.onnx",
+            None,
+            false,
+            None,
+            None,
+            &CancellationToken::new(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, ModelResolveError::Unverified { .. }), "{err}");
+        assert!(!tmp.path().join("model.onnx").exists());
+    }
+
+    #[test]
+    fn test_resolve_uses_cached_model_without_digest() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("model.onnx"), b"provisioned").unwrap();
+
+        let path = resolve_in(
+            tmp.path(),
+            "model.onnx",
+            "http://invalid.nonexistent.example.com/model.onnx",
+            None,
+            false,
+            None,
+            None,
+            &CancellationToken::new(),
+        )
+        .unwrap();
+
+        assert_eq!(path, tmp.path().join("model.onnx"));
+    }
+
+    #[test]
+    fn test_sha256_for_unknown_model_is_none() {
+        assert_eq!(sha256_for("not-a-model.onnx"), None);
+    }
+
+    #[test]
+    fn test_failed_download_names_where_to_copy_the_model() {
+        let tmp = TempDir::new().unwrap();
+        let dest = tmp.path().join("model.onnx");
+        let err = download(
+            "http://invalid.nonexistent.example.com/model",
+            &dest,
+            None,
+            None,
+            &CancellationToken::new(),
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains(&dest.display().to_string()),
+            "{err}"
+        );
+    }
+
     #[test]
     fn test_download_to_file() {
         // Skip in CI — requires network access
@@ -220,9 +745,11 @@ mod tests {
         let result = download(
             "https://www.google.com/robots.txt",
             &dest,
+            None,
             Some(Box::new(move |_downloaded, _total| {
                 flag.store(true, std::sync::atomic::Ordering::Relaxed);
             })),
+            &CancellationToken::new(),
         );
         assert!(result.is_ok(), "download failed: {:?}", result.err());
         assert!(dest.exists());
@@ -234,7 +761,13 @@ mod tests {
     fn test_download_invalid_url_returns_error() {
         let tmp = TempDir::new().unwrap();
         let dest = tmp.path().join("model.onnx");
-        let result = download("http://invalid.nonexistent.example.com/model", &dest, None);
+        let result = download(
+            "http://invalid.nonexistent.example.com/model",
+            &dest,
+            None,
+            None,
+            &CancellationToken::new(),
+        );
         assert!(result.is_err());
     }
 
@@ -242,9 +775,55 @@ mod tests {
     fn test_download_atomic_no_partial_on_failure() {
         let tmp = TempDir::new().unwrap();
         let dest = tmp.path().join("model.onnx");
-        let _ = download("http://invalid.nonexistent.example.com/model", &dest, None);
+        let _ = download(
+            "http://invalid.nonexistent.example.com/model",
+            &dest,
+            None,
+            None,
+            &CancellationToken::new(),
+        );
         // Neither the dest nor the .part file should exist after failure
         assert!(!dest.exists());
         assert!(!dest.with_extension("part").exists());
     }
+
+    #[test]
+    fn test_cancelled_download_returns_reason() {
+        let tmp = TempDir::new().unwrap();
+        let dest = tmp.path().join("model.onnx");
+        let token = CancellationToken::new();
+        token.cancel(CancelReason::Shutdown);
+
+        let err = download(
+            "http://invalid.nonexistent.example.com/model",
+            &dest,
+            None,
+            None,
+            &token,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ModelResolveError::Cancelled(Cancelled(CancelReason::Shutdown))
+        ));
+        assert_eq!(Cancelled::reason_of(&err), Some(CancelReason::Shutdown));
+        assert!(!dest.with_extension("part").exists());
+    }
+
+    #[test]
+    fn test_cancelled_prefetch_keeps_cached_models() {
+        let tmp = TempDir::new().unwrap();
+        let models = [ModelSpec {
+            name: "a.onnx",
+            url: "http://invalid.nonexistent.example.com/a.onnx",
+            calibration: ConfidenceCalibration::Identity,
+            sha256: None,
+        }];
+        fs::write(tmp.path().join("a.onnx"), b"cached").unwrap();
+        let token = CancellationToken::new();
+        token.cancel(CancelReason::User);
+
+        let paths = prefetch_into(tmp.path(), &models, None, &token).unwrap();
+        assert_eq!(paths, vec![tmp.path().join("a.onnx")]);
+    }
 }
@@ -0,0 +1,585 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::detection::infrastructure::model_resolver::{
+    self, ModelResolveError, ProgressFn, REGISTERED_MODELS,
+};
+use crate::shared::cancellation::CancellationToken;
+use crate::shared::constants::MODEL_MANIFEST_URL;
+use crate::shared::schema::{Schema, SchemaError};
+
+/// Layout of the published manifest listing the newest model releases.
+pub const MANIFEST_SCHEMA: Schema = Schema::new("Model manifest", 1, &[]);
+
+/// Layout of the record of updates installed into the model cache.
+const INSTALLED_SCHEMA: Schema = Schema::new("Installed models", 1, &[]);
+
+/// Record of installed model versions, beside the models themselves.
+const INSTALLED_FILE: &str = "installed.json";
+
+/// Version of every model that shipped with the app, before any update.
+const BUILT_IN_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum ModelUpdateError {
+    #[error("Could not check for model updates at {url}: {source}")]
+    Fetch {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("Model manifest is not valid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Schema(#[from] SchemaError),
+    #[error("Malformed model manifest: {0}")]
+    Malformed(String),
+    #[error("{name} v{version} needs FaceGuard {required} or newer")]
+    NeedsAppUpdate {
+        name: String,
+        version: u32,
+        required: String,
+    },
+    #[error("Downloaded {name} is {actual} bytes, expected {expected}")]
+    SizeMismatch {
+        name: String,
+        expected: u64,
+        actual: u64,
+    },
+    #[error("Could not record the installed model version: {0}")]
+    Record(#[source] std::io::Error),
+    #[error(transparent)]
+    Resolve(#[from] ModelResolveError),
+}
+
+/// The newest release of one model, as listed in the manifest.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModelRelease {
+    /// File name of the registered model it replaces.
+    pub name: String,
+    pub version: u32,
+    pub url: String,
+    pub size_bytes: u64,
+    /// Hex SHA-256 of the file at `url`, checked before it is swapped in.
+    pub sha256: Option<String>,
+    /// Accuracy on the model's benchmark (WIDER FACE hard mAP for the face
+    /// detector), from 0 to 1.
+    pub accuracy: Option<f64>,
+    pub changelog: String,
+    /// Oldest app version that can run the release.
+    pub min_app_version: Option<String>,
+}
+
+/// What is in the model cache for one model.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstalledModel {
+    pub version: u32,
+    pub size_bytes: Option<u64>,
+    pub accuracy: Option<f64>,
+}
+
+/// A release newer than the installed model, with what changes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModelUpdate {
+    pub installed: InstalledModel,
+    pub release: ModelRelease,
+    /// Set when the release needs a newer app; such updates are listed but
+    /// not installed.
+    pub needs_app_update: bool,
+}
+
+impl ModelUpdate {
+    /// Change in accuracy, in percentage points, when both ends are known.
+    pub fn accuracy_change(&self) -> Option<f64> {
+        Some((self.release.accuracy? - self.installed.accuracy?) * 100.0)
+    }
+
+    /// One line for listings, e.g. `yolo.onnx v1 → v2: accuracy 81.2% →
+    /// 84.5% (+3.3 points), size 10.5 MB → 11.2 MB`.
+    pub fn summary(&self) -> String {
+        let accuracy = match self.accuracy_change() {
+            Some(change) => format!(
+                "{} \u{2192} {} ({change:+.1} points)",
+                percent(self.installed.accuracy),
+                percent(self.release.accuracy)
+            ),
+            None => format!(
+                "{} \u{2192} {}",
+                percent(self.installed.accuracy),
+                percent(self.release.accuracy)
+            ),
+        };
+        format!(
+            "{} v{} \u{2192} v{}: accuracy {accuracy}, size {} \u{2192} {}",
+            self.release.name,
+            self.installed.version,
+            self.release.version,
+            megabytes(self.installed.size_bytes),
+            megabytes(Some(self.release.size_bytes))
+        )
+    }
+}
+
+fn percent(accuracy: Option<f64>) -> String {
+    accuracy.map_or("unknown".into(), |a| format!("{:.1}%", a * 100.0))
+}
+
+fn megabytes(bytes: Option<u64>) -> String {
+    bytes.map_or("unknown".into(), |b| {
+        format!("{:.1} MB", b as f64 / 1_000_000.0)
+    })
+}
+
+/// Fetches the manifest and lists the releases newer than the cached
+/// models. Only runs when the user asks; nothing checks automatically.
+pub fn check_for_updates() -> Result<Vec<ModelUpdate>, ModelUpdateError> {
+    check_for_updates_in(&model_resolver::model_cache_dir()?)
+}
+
+/// Like [`check_for_updates`], for the models in `model_dir`.
+pub fn check_for_updates_in(model_dir: &Path) -> Result<Vec<ModelUpdate>, ModelUpdateError> {
+    let fetch_error = |source| ModelUpdateError::Fetch {
+        url: MODEL_MANIFEST_URL.to_string(),
+        source,
+    };
+    let text = reqwest::blocking::get(MODEL_MANIFEST_URL)
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.text())
+        .map_err(fetch_error)?;
+    Ok(pending_updates(
+        &parse_manifest(&text)?,
+        &installed_models(model_dir),
+        env!("CARGO_PKG_VERSION"),
+    ))
+}
+
+/// Reads the releases listed in a manifest.
+pub fn parse_manifest(text: &str) -> Result<Vec<ModelRelease>, ModelUpdateError> {
+    let document = MANIFEST_SCHEMA.upgrade(serde_json::from_str(text)?)?;
+    let malformed = |what: &str| ModelUpdateError::Malformed(what.to_string());
+    document["models"]
+        .as_array()
+        .ok_or_else(|| malformed("`models` is not a list"))?
+        .iter()
+        .map(|entry| {
+            let field = |key: &str| {
+                entry[key]
+                    .as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| malformed(&format!("a model has no `{key}`")))
+            };
+            Ok(ModelRelease {
+                name: field("name")?,
+                version: entry["version"]
+                    .as_u64()
+                    .ok_or_else(|| malformed("a model has no `version`"))?
+                    as u32,
+                url: field("url")?,
+                size_bytes: entry["size_bytes"].as_u64().unwrap_or(0),
+                sha256: entry["sha256"].as_str().map(str::to_string),
+                accuracy: entry["accuracy"].as_f64(),
+                changelog: entry["changelog"].as_str().unwrap_or("").trim().to_string(),
+                min_app_version: entry["min_app_version"].as_str().map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+/// Releases of registered models newer than what is installed.
+///
+/// A release is only offered with a changelog, so nobody swaps the model
+/// their anonymization depends on without being told what changed.
+pub fn pending_updates(
+    releases: &[ModelRelease],
+    installed: &BTreeMap<String, InstalledModel>,
+    app_version: &str,
+) -> Vec<ModelUpdate> {
+    releases
+        .iter()
+        .filter(|release| REGISTERED_MODELS.iter().any(|m| m.name == release.name))
+        .filter_map(|release| {
+            let current = installed
+                .get(&release.name)
+                .cloned()
+                .unwrap_or_else(|| built_in(None));
+            if release.version <= current.version {
+                return None;
+            }
+            if release.changelog.is_empty() {
+                log::warn!(
+                    "Ignoring {} v{}: the release has no changelog",
+                    release.name,
+                    release.version
+                );
+                return None;
+            }
+            let needs_app_update = release
+                .min_app_version
+                .as_deref()
+                .is_some_and(|required| !version_at_least(app_version, required));
+            Some(ModelUpdate {
+                installed: current,
+                release: release.clone(),
+                needs_app_update,
+            })
+        })
+        .collect()
+}
+
+/// Downloads `update` over the cached model and records its version.
+/// The old model stays in place until the new one has fully arrived.
+/// A release without a `sha256` is refused unless
+/// [`model_resolver::unverified_allowed`].
+pub fn install_update(
+    update: &ModelUpdate,
+    progress: Option<ProgressFn>,
+    cancelled: &CancellationToken,
+) -> Result<PathBuf, ModelUpdateError> {
+    install_update_in(
+        &model_resolver::model_cache_dir()?,
+        update,
+        progress,
+        cancelled,
+    )
+}
+
+/// Like [`install_update`], into `cache_dir`.
+pub fn install_update_in(
+    cache_dir: &Path,
+    update: &ModelUpdate,
+    progress: Option<ProgressFn>,
+    cancelled: &CancellationToken,
+) -> Result<PathBuf, ModelUpdateError> {
+    let release = &update.release;
+    if update.needs_app_update {
+        return Err(ModelUpdateError::NeedsAppUpdate {
+            name: release.name.clone(),
+            version: release.version,
+            required: release.min_app_version.clone().unwrap_or_default(),
+        });
+    }
+    let staged = cache_dir.join(format!("{}.v{}", release.name, release.version));
+    model_resolver::require_sha256(
+        &release.name,
+        &release.url,
+        &cache_dir.join(&release.name),
+        release.sha256.as_deref(),
+        model_resolver::unverified_allowed(),
+    )?;
+    fs::create_dir_all(cache_dir).map_err(ModelResolveError::CacheDir)?;
+    model_resolver::download(
+        &release.url,
+        &staged,
+        release.sha256.as_deref(),
+        progress,
+        cancelled,
+    )?;
+
+    let actual = fs::metadata(&staged).map(|m| m.len()).unwrap_or(0);
+    if release.size_bytes > 0 && actual != release.size_bytes {
+        let _ = fs::remove_file(&staged);
+        return Err(ModelUpdateError::SizeMismatch {
+            name: release.name.clone(),
+            expected: release.size_bytes,
+            actual,
+        });
+    }
+    let path = cache_dir.join(&release.name);
+    fs::rename(&staged, &path).map_err(|source| ModelResolveError::Write {
+        path: path.clone(),
+        source,
+    })?;
+
+    let mut installed = installed_models(cache_dir);
+    installed.insert(
+        release.name.clone(),
+        InstalledModel {
+            version: release.version,
+            size_bytes: Some(actual),
+            accuracy: release.accuracy,
+        },
+    );
+    write_installed(cache_dir, &installed).map_err(ModelUpdateError::Record)?;
+    Ok(path)
+}
+
+/// Installed version of `name`: 1 until an update has been installed.
+pub fn installed_version(name: &str) -> u32 {
+    model_resolver::model_cache_dir()
+        .map_or(BUILT_IN_VERSION, |dir| installed_version_in(&dir, name))
+}
+
+/// Like [`installed_version`], for the models in `model_dir`.
+pub fn installed_version_in(model_dir: &Path, name: &str) -> u32 {
+    installed_models(model_dir)
+        .remove(name)
+        .map_or(BUILT_IN_VERSION, |m| m.version)
+}
+
+/// `name`, tagged with its version once updated, for cache keys that must
+/// change when the model does.
+pub fn model_tag(name: &str) -> String {
+    tagged(name, installed_version(name))
+}
+
+/// Like [`model_tag`], for the models in `model_dir`.
+pub fn model_tag_in(model_dir: &Path, name: &str) -> String {
+    tagged(name, installed_version_in(model_dir, name))
+}
+
+fn tagged(name: &str, version: u32) -> String {
+    match version {
+        BUILT_IN_VERSION => name.to_string(),
+        version => format!("{name}@v{version}"),
+    }
+}
+
+/// Installed models by name. Models without a record are the built-in
+/// version, sized from the cached file when there is one.
+fn installed_models(cache_dir: &Path) -> BTreeMap<String, InstalledModel> {
+    let mut installed: BTreeMap<String, InstalledModel> = REGISTERED_MODELS
+        .iter()
+        .map(|m| {
+            let size = fs::metadata(cache_dir.join(m.name)).ok().map(|f| f.len());
+            (m.name.to_string(), built_in(size))
+        })
+        .collect();
+    let record = fs::read_to_string(cache_dir.join(INSTALLED_FILE))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .and_then(|document| match INSTALLED_SCHEMA.upgrade(document) {
+            Ok(document) => Some(document),
+            Err(e) => {
+                log::warn!("Ignoring installed model record: {e}");
+                None
+            }
+        });
+    if let Some(Value::Object(models)) = record.map(|mut d| d["models"].take()) {
+        for (name, entry) in models {
+            let Some(version) = entry["version"].as_u64() else {
+                continue;
+            };
+            installed.insert(
+                name,
+                InstalledModel {
+                    version: version as u32,
+                    size_bytes: entry["size_bytes"].as_u64(),
+                    accuracy: entry["accuracy"].as_f64(),
+                },
+            );
+        }
+    }
+    installed
+}
+
+fn write_installed(
+    cache_dir: &Path,
+    installed: &BTreeMap<String, InstalledModel>,
+) -> std::io::Result<()> {
+    let models: serde_json::Map<String, Value> = installed
+        .iter()
+        .filter(|(_, m)| m.version != BUILT_IN_VERSION)
+        .map(|(name, m)| {
+            let entry = json!({
+                "version": m.version,
+                "size_bytes": m.size_bytes,
+                "accuracy": m.accuracy,
+            });
+            (name.clone(), entry)
+        })
+        .collect();
+    let document = json!({ "version": INSTALLED_SCHEMA.version(), "models": models });
+    let text = serde_json::to_string_pretty(&document).map_err(std::io::Error::other)?;
+    fs::write(cache_dir.join(INSTALLED_FILE), text)
+}
+
+fn built_in(size_bytes: Option<u64>) -> InstalledModel {
+    InstalledModel {
+        version: BUILT_IN_VERSION,
+        size_bytes,
+        accuracy: None,
+    }
+}
+
+/// Whether dotted version `current` is at or after `required`. Missing or
+/// non-numeric parts count as 0.
+fn version_at_least(current: &str, required: &str) -> bool {
+    let parts = |v: &str| -> Vec<u64> {
+        v.trim_start_matches('v')
+            .split('.')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect()
+    };
+    let (current, required) = (parts(current), parts(required));
+    let len = current.len().max(required.len());
+    let at = |v: &[u64], i: usize| v.get(i).copied().unwrap_or(0);
+    (0..len)
+        .map(|i| at(&current, i).cmp(&at(&required, i)))
+        .find(|order| order.is_ne())
+        .map_or(true, |order| order.is_gt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::constants::{WHISPER_MODEL_NAME, YOLO_MODEL_NAME};
+    use tempfile::TempDir;
+
+    fn release(name: &str, version: u32, changelog: &str) -> ModelRelease {
+        ModelRelease {
+            name: name.to_string(),
+            version,
+            url: "http://invalid.nonexistent.example.com/model.onnx".to_string(),
+            size_bytes: 11_200_000,
+            sha256: None,
+            accuracy: Some(0.845),
+            changelog: changelog.to_string(),
+            min_app_version: None,
+        }
+    }
+
+    fn no_installs() -> BTreeMap<String, InstalledModel> {
+        BTreeMap::new()
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let text = format!(
+            r#"{{"version": 1, "models": [{{
+                "name": "{YOLO_MODEL_NAME}", "version": 2,
+                "url": "https://example.com/yolo.onnx", "size_bytes": 11200000,
+                "sha256": "ab12", "accuracy": 0.845, "changelog": " Better on small faces. ",
+                "min_app_version": "0.5.0"
+            }}]}}"#
+        );
+        let releases = parse_manifest(&text).unwrap();
+        assert_eq!(releases.len(), 1);
+        assert_eq!(releases[0].version, 2);
+        assert_eq!(releases[0].accuracy, Some(0.845));
+        assert_eq!(releases[0].sha256.as_deref(), Some("ab12"));
+        assert_eq!(releases[0].changelog, "Better on small faces.");
+        assert_eq!(releases[0].min_app_version.as_deref(), Some("0.5.0"));
+    }
+
+    #[test]
+    fn test_parse_manifest_rejects_newer_layout_and_missing_fields() {
+        assert!(matches!(
+            parse_manifest(r#"{"version": 99, "models": []}"#),
+            Err(ModelUpdateError::Schema(_))
+        ));
+        assert!(matches!(
+            parse_manifest(r#"{"version": 1, "models": [{"name": "a"}]}"#),
+            Err(ModelUpdateError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn test_only_newer_releases_are_offered() {
+        let mut installed = no_installs();
+        installed.insert(
+            WHISPER_MODEL_NAME.to_string(),
+            InstalledModel {
+                version: 3,
+                size_bytes: None,
+                accuracy: None,
+            },
+        );
+        let releases = [
+            release(YOLO_MODEL_NAME, 2, "Better on small faces."),
+            release(WHISPER_MODEL_NAME, 3, "Same as before."),
+            release("unregistered.onnx", 9, "Not ours."),
+        ];
+        let updates = pending_updates(&releases, &installed, "0.4.0");
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].release.name, YOLO_MODEL_NAME);
+        assert_eq!(updates[0].installed.version, 1);
+    }
+
+    #[test]
+    fn test_releases_without_changelog_are_withheld() {
+        let updates = pending_updates(
+            &[release(YOLO_MODEL_NAME, 2, "  ")],
+            &no_installs(),
+            "0.4.0",
+        );
+        assert!(updates.is_empty());
+    }
+
+    #[test]
+    fn test_release_for_newer_app_is_flagged() {
+        let mut newer = release(YOLO_MODEL_NAME, 2, "Needs a new runtime.");
+        newer.min_app_version = Some("0.10.0".to_string());
+        let updates = pending_updates(&[newer], &no_installs(), "0.4.0");
+        assert!(updates[0].needs_app_update);
+
+        let tmp = TempDir::new().unwrap();
+        let err = install_update_in(tmp.path(), &updates[0], None, &CancellationToken::new());
+        assert!(matches!(err, Err(ModelUpdateError::NeedsAppUpdate { .. })));
+    }
+
+    #[test]
+    fn test_summary_shows_accuracy_and_size_changes() {
+        let update = ModelUpdate {
+            installed: InstalledModel {
+                version: 1,
+                size_bytes: Some(10_500_000),
+                accuracy: Some(0.812),
+            },
+            release: release("yolo.onnx", 2, "Better."),
+            needs_app_update: false,
+        };
+        assert_eq!(
+            update.summary(),
+            "yolo.onnx v1 \u{2192} v2: accuracy 81.2% \u{2192} 84.5% (+3.3 points), \
+             size 10.5 MB \u{2192} 11.2 MB"
+        );
+    }
+
+    #[test]
+    fn test_installed_record_roundtrip() {
+        let tmp = TempDir::new().unwrap();
+        let mut installed = installed_models(tmp.path());
+        assert_eq!(installed[YOLO_MODEL_NAME].version, 1);
+
+        installed.insert(
+            YOLO_MODEL_NAME.to_string(),
+            InstalledModel {
+                version: 2,
+                size_bytes: Some(42),
+                accuracy: Some(0.9),
+            },
+        );
+        write_installed(tmp.path(), &installed).unwrap();
+        let reread = installed_models(tmp.path());
+        assert_eq!(reread[YOLO_MODEL_NAME], installed[YOLO_MODEL_NAME]);
+        assert_eq!(reread[WHISPER_MODEL_NAME].version, 1);
+    }
+
+    #[test]
+    fn test_failed_download_keeps_the_old_model() {
+        let tmp = TempDir::new().unwrap();
+        let model = tmp.path().join(YOLO_MODEL_NAME);
+        fs::write(&model, b"old model").unwrap();
+        let mut newer = release(YOLO_MODEL_NAME, 2, "Better.");
+        newer.sha256 = Some("ab12".to_string());
+        let updates = pending_updates(&[newer], &installed_models(tmp.path()), "0.4.0");
+
+        let result = install_update_in(tmp.path(), &updates[0], None, &CancellationToken::new());
+        assert!(result.is_err());
+        assert_eq!(fs::read(&model).unwrap(), b"old model");
+        assert_eq!(installed_models(tmp.path())[YOLO_MODEL_NAME].version, 1);
+    }
+
+    #[test]
+    fn test_version_at_least() {
+        assert!(version_at_least("0.4.0", "0.4.0"));
+        assert!(version_at_least("0.10.0", "0.9.3"));
+        assert!(version_at_least("v1.2", "1.1.9"));
+        assert!(!version_at_least("0.4.0", "0.4.1"));
+        assert!(!version_at_least("0.4", "0.10"));
+    }
+}
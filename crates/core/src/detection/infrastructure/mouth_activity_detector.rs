@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::detection::domain::face_detector::{DetectorState, FaceDetector};
+use crate::detection::domain::mouth_activity::{FacePatch, MouthActivity};
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// Shared per-track mouth activity log.
+pub type MouthActivityLog = Arc<Mutex<MouthActivity>>;
+
+/// Decorator that measures mouth movement of every tracked face it passes
+/// through, for matching faces to speech.
+///
+/// Activity is recorded for a track on each frame that directly follows one
+/// where the same track was seen. Like `RecordingFaceDetector`, the log is
+/// shared through an `Arc<Mutex<_>>` the caller reads once the pipeline has
+/// finished.
+pub struct MouthActivityDetector {
+    inner: Box<dyn FaceDetector>,
+    previous: HashMap<u32, (usize, FacePatch)>,
+    log: MouthActivityLog,
+}
+
+impl MouthActivityDetector {
+    pub fn new(inner: Box<dyn FaceDetector>) -> Self {
+        Self {
+            inner,
+            previous: HashMap::new(),
+            log: Arc::new(Mutex::new(MouthActivity::new())),
+        }
+    }
+
+    /// Returns a handle to the log that stays valid after the detector is moved.
+    pub fn log(&self) -> MouthActivityLog {
+        Arc::clone(&self.log)
+    }
+}
+
+impl FaceDetector for MouthActivityDetector {
+    fn detect(&mut self, frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+        let regions = self.inner.detect(frame)?;
+        let index = frame.index();
+        let mut seen = HashMap::new();
+        let mut log = self.log.lock().map_err(|_| "Mouth activity log poisoned")?;
+        for region in &regions {
+            let (Some(track_id), Some(patch)) = (region.track_id, FacePatch::sample(frame, region))
+            else {
+                continue;
+            };
+            if let Some((last, previous)) = self.previous.get(&track_id) {
+                if last + 1 == index {
+                    log.entry(track_id)
+                        .or_default()
+                        .push((index, patch.activity_since(previous)));
+                }
+            }
+            seen.insert(track_id, (index, patch));
+        }
+        // Tracks missing from this frame cannot be compared next frame.
+        self.previous = seen;
+        Ok(regions)
+    }
+
+    fn save_state(&self) -> Result<DetectorState, Box<dyn std::error::Error>> {
+        self.inner.save_state()
+    }
+
+    fn restore_state(&mut self, state: &DetectorState) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.restore_state(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StubDetector {
+        regions: Vec<Region>,
+    }
+
+    impl FaceDetector for StubDetector {
+        fn detect(&mut self, _frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+            Ok(self.regions.clone())
+        }
+    }
+
+    fn region(track_id: u32) -> Region {
+        Region {
+            x: 0,
+            y: 0,
+            width: 40,
+            height: 40,
+            track_id: Some(track_id),
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    fn frame(index: usize, value: u8) -> Frame {
+        Frame::new(vec![value; 40 * 40 * 3], 40, 40, 3, index)
+    }
+
+    #[test]
+    fn test_passes_through_inner_regions() {
+        let mut detector = MouthActivityDetector::new(Box::new(StubDetector {
+            regions: vec![region(1), region(2)],
+        }));
+        assert_eq!(detector.detect(&frame(0, 0)).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_records_activity_from_second_consecutive_frame() {
+        let mut detector = MouthActivityDetector::new(Box::new(StubDetector {
+            regions: vec![region(1)],
+        }));
+        let log = detector.log();
+
+        for i in 0..3 {
+            detector.detect(&frame(i, 100)).unwrap();
+        }
+
+        let log = log.lock().unwrap();
+        assert_eq!(log[&1], vec![(1, 0.0), (2, 0.0)]);
+    }
+
+    #[test]
+    fn test_gap_restarts_comparison() {
+        let mut detector = MouthActivityDetector::new(Box::new(StubDetector {
+            regions: vec![region(1)],
+        }));
+        let log = detector.log();
+
+        detector.detect(&frame(0, 100)).unwrap();
+        detector.detect(&frame(5, 100)).unwrap();
+        detector.detect(&frame(6, 100)).unwrap();
+
+        let log = log.lock().unwrap();
+        assert_eq!(log[&1], vec![(6, 0.0)]);
+    }
+
+    #[test]
+    fn test_untracked_regions_are_skipped() {
+        let untracked = Region {
+            track_id: None,
+            ..region(1)
+        };
+        let mut detector = MouthActivityDetector::new(Box::new(StubDetector {
+            regions: vec![untracked],
+        }));
+        let log = detector.log();
+
+        detector.detect(&frame(0, 100)).unwrap();
+        detector.detect(&frame(1, 100)).unwrap();
+
+        assert!(log.lock().unwrap().is_empty());
+    }
+}
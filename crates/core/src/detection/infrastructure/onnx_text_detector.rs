@@ -0,0 +1,371 @@
+/// Text detector using ONNX Runtime.
+///
+/// Pipeline: resize to multiples of 32 → DBNet probability map → connected
+/// components → expanded boxes → optional CRNN recognition of each box.
+/// Models follow the PaddleOCR export layout (BGR input, ImageNet
+/// normalization for detection, CTC scores out of recognition).
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::detection::domain::text_detector::{TextBox, TextDetector};
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+use super::onnx_yolo_detector::{OnnxYoloDetector, SharedSession};
+
+/// Longest side the detection input is scaled down to. Larger frames lose
+/// small text; smaller ones cost more time per frame.
+pub const DEFAULT_MAX_SIDE: u32 = 960;
+
+/// Characters of the English recognition model, in class order after the
+/// CTC blank (class 0). Classes past the end read as a space.
+pub const DEFAULT_CHARSET: &str = "0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~!\"#$%&'()*+,-./";
+
+const STRIDE: u32 = 32;
+const MEAN: [f32; 3] = [0.485, 0.456, 0.406];
+const STD: [f32; 3] = [0.229, 0.224, 0.225];
+/// Probability a map pixel needs to count as text.
+const PIXEL_THRESH: f32 = 0.3;
+/// Mean probability a component needs to become a box.
+const BOX_THRESH: f32 = 0.6;
+/// DBNet predicts shrunk text kernels; boxes grow back by
+/// `area * ratio / perimeter` on every side.
+const UNCLIP_RATIO: f64 = 1.5;
+const MIN_BOX_SIDE: usize = 3;
+const REC_HEIGHT: u32 = 48;
+const REC_MAX_WIDTH: u32 = 320;
+
+pub struct OnnxTextDetector {
+    detection: SharedSession,
+    recognition: Option<SharedSession>,
+    charset: Vec<char>,
+    max_side: u32,
+}
+
+impl OnnxTextDetector {
+    pub fn new(model_path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let session = OnnxYoloDetector::build_session(model_path)?;
+        Ok(Self::from_shared_session(Arc::new(Mutex::new(session))))
+    }
+
+    pub fn from_shared_session(session: SharedSession) -> Self {
+        Self {
+            detection: session,
+            recognition: None,
+            charset: DEFAULT_CHARSET.chars().collect(),
+            max_side: DEFAULT_MAX_SIDE,
+        }
+    }
+
+    /// Reads each box with a recognition model, filling `TextBox::text`.
+    /// Without one, boxes are found but not read.
+    pub fn with_recognizer(mut self, session: SharedSession) -> Self {
+        self.recognition = Some(session);
+        self
+    }
+
+    /// Characters of a recognition model other than the default English one.
+    pub fn with_charset(mut self, charset: &str) -> Self {
+        self.charset = charset.chars().collect();
+        self
+    }
+
+    pub fn with_max_side(mut self, max_side: u32) -> Self {
+        self.max_side = max_side.max(STRIDE);
+        self
+    }
+
+    fn probability_map(
+        &self,
+        frame: &Frame,
+    ) -> Result<(Vec<f32>, usize, usize, f64, f64), Box<dyn std::error::Error>> {
+        let (input, scale_x, scale_y) = detection_input(frame, self.max_side);
+        let input_value = ort::value::TensorRef::from_array_view(input.view())?;
+        let mut session = self
+            .detection
+            .lock()
+            .map_err(|e| format!("Session lock poisoned: {e}"))?;
+        let outputs = session.run(ort::inputs![input_value])?;
+        if outputs.len() == 0 {
+            return Err("Text detection model produced no outputs".into());
+        }
+        let tensor = outputs[0].try_extract_array::<f32>()?;
+        let shape = tensor.shape();
+        if shape.len() != 4 {
+            return Err(format!("Unexpected text detection output shape: {shape:?}").into());
+        }
+        let (h, w) = (shape[2], shape[3]);
+        let data = tensor.as_slice().ok_or("Cannot get tensor slice")?;
+        Ok((data[..w * h].to_vec(), w, h, scale_x, scale_y))
+    }
+
+    fn read(
+        &self,
+        session: &SharedSession,
+        frame: &Frame,
+        region: &Region,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let input = recognition_input(frame, region);
+        let input_value = ort::value::TensorRef::from_array_view(input.view())?;
+        let mut session = session
+            .lock()
+            .map_err(|e| format!("Session lock poisoned: {e}"))?;
+        let outputs = session.run(ort::inputs![input_value])?;
+        if outputs.len() == 0 {
+            return Err("Text recognition model produced no outputs".into());
+        }
+        let tensor = outputs[0].try_extract_array::<f32>()?;
+        let shape = tensor.shape();
+        if shape.len() != 3 {
+            return Err(format!("Unexpected text recognition output shape: {shape:?}").into());
+        }
+        let data = tensor.as_slice().ok_or("Cannot get tensor slice")?;
+        Ok(ctc_decode(data, shape[1], shape[2], &self.charset))
+    }
+}
+
+impl TextDetector for OnnxTextDetector {
+    fn detect_text(&mut self, frame: &Frame) -> Result<Vec<TextBox>, Box<dyn std::error::Error>> {
+        let (map, w, h, scale_x, scale_y) = self.probability_map(frame)?;
+        let mut found = Vec::new();
+        for (x1, y1, x2, y2) in boxes_from_probability_map(&map, w, h) {
+            let Some(region) = clipped_region(
+                x1 * scale_x,
+                y1 * scale_y,
+                x2 * scale_x,
+                y2 * scale_y,
+                frame.width(),
+                frame.height(),
+            ) else {
+                continue;
+            };
+            let text = match self.recognition {
+                Some(ref session) => Some(self.read(session, frame, &region)?),
+                None => None,
+            };
+            found.push(TextBox { region, text });
+        }
+        Ok(found)
+    }
+}
+
+/// Detection input scaled so the longest side fits `max_side` (never
+/// enlarged) and both sides are multiples of 32, plus the factors that map
+/// input coordinates back to the frame.
+fn detection_input(frame: &Frame, max_side: u32) -> (ndarray::Array4<f32>, f64, f64) {
+    let (fw, fh) = (frame.width(), frame.height());
+    let scale = (max_side as f64 / fw.max(fh) as f64).min(1.0);
+    let round = |v: u32| {
+        (((v as f64 * scale) / STRIDE as f64).round() as u32 * STRIDE).max(STRIDE) as usize
+    };
+    let (w, h) = (round(fw), round(fh));
+
+    let src = frame.as_ndarray();
+    let mut input = ndarray::Array4::<f32>::zeros((1, 3, h, w));
+    for y in 0..h {
+        let sy = (y * fh as usize / h).min(fh as usize - 1);
+        for x in 0..w {
+            let sx = (x * fw as usize / w).min(fw as usize - 1);
+            for c in 0..3 {
+                // BGR channel order.
+                let v = src[[sy, sx, 2 - c]] as f32 / 255.0;
+                input[[0, c, y, x]] = (v - MEAN[c]) / STD[c];
+            }
+        }
+    }
+    (input, fw as f64 / w as f64, fh as f64 / h as f64)
+}
+
+/// Boxes `(x1, y1, x2, y2)` in map coordinates around each 4-connected
+/// blob of text pixels, grown back from DBNet's shrunk kernels. Blobs with
+/// a low mean probability or a side under 3 pixels are dropped.
+fn boxes_from_probability_map(map: &[f32], w: usize, h: usize) -> Vec<(f64, f64, f64, f64)> {
+    let mut visited = vec![false; w * h];
+    let mut boxes = Vec::new();
+    let mut stack = Vec::new();
+    for start in 0..w * h {
+        if visited[start] || map[start] <= PIXEL_THRESH {
+            continue;
+        }
+        visited[start] = true;
+        stack.push(start);
+        let (mut x0, mut y0, mut x1, mut y1) = (w, h, 0, 0);
+        let (mut sum, mut count) = (0.0f64, 0usize);
+        while let Some(i) = stack.pop() {
+            let (x, y) = (i % w, i / w);
+            x0 = x0.min(x);
+            y0 = y0.min(y);
+            x1 = x1.max(x);
+            y1 = y1.max(y);
+            sum += map[i] as f64;
+            count += 1;
+            let neighbours = [
+                (x > 0).then(|| i - 1),
+                (x + 1 < w).then(|| i + 1),
+                (y > 0).then(|| i - w),
+                (y + 1 < h).then(|| i + w),
+            ];
+            for n in neighbours.into_iter().flatten() {
+                if !visited[n] && map[n] > PIXEL_THRESH {
+                    visited[n] = true;
+                    stack.push(n);
+                }
+            }
+        }
+        let (bw, bh) = (x1 - x0 + 1, y1 - y0 + 1);
+        if bw.min(bh) < MIN_BOX_SIDE || sum / (count as f64) < BOX_THRESH as f64 {
+            continue;
+        }
+        let (bw, bh) = (bw as f64, bh as f64);
+        let grow = bw * bh * UNCLIP_RATIO / (2.0 * (bw + bh));
+        boxes.push((
+            x0 as f64 - grow,
+            y0 as f64 - grow,
+            (x1 + 1) as f64 + grow,
+            (y1 + 1) as f64 + grow,
+        ));
+    }
+    boxes
+}
+
+fn clipped_region(x1: f64, y1: f64, x2: f64, y2: f64, fw: u32, fh: u32) -> Option<Region> {
+    let x = (x1.floor().max(0.0) as i32).min(fw as i32);
+    let y = (y1.floor().max(0.0) as i32).min(fh as i32);
+    let right = (x2.ceil() as i32).min(fw as i32);
+    let bottom = (y2.ceil() as i32).min(fh as i32);
+    if right <= x || bottom <= y {
+        return None;
+    }
+    Some(Region {
+        x,
+        y,
+        width: right - x,
+        height: bottom - y,
+        track_id: None,
+        full_width: None,
+        full_height: None,
+        unclamped_x: None,
+        unclamped_y: None,
+    })
+}
+
+/// The region scaled to the recognizer's 48-pixel height, keeping its
+/// aspect up to 320 pixels wide, BGR and normalized to [-1, 1].
+fn recognition_input(frame: &Frame, region: &Region) -> ndarray::Array4<f32> {
+    let (rw, rh) = (region.width as f64, region.height as f64);
+    let w =
+        ((REC_HEIGHT as f64 * rw / rh).round() as u32).clamp(STRIDE / 4, REC_MAX_WIDTH) as usize;
+    let h = REC_HEIGHT as usize;
+    let src = frame.as_ndarray();
+    let mut input = ndarray::Array4::<f32>::zeros((1, 3, h, w));
+    for y in 0..h {
+        let sy = region.y as usize + y * region.height as usize / h;
+        for x in 0..w {
+            let sx = region.x as usize + x * region.width as usize / w;
+            for c in 0..3 {
+                let v = src[[sy, sx, 2 - c]] as f32 / 255.0;
+                input[[0, c, y, x]] = (v - 0.5) / 0.5;
+            }
+        }
+    }
+    input
+}
+
+/// Greedy CTC decoding of `steps x classes` scores: the best class per
+/// step, repeats collapsed, blanks (class 0) dropped.
+fn ctc_decode(scores: &[f32], steps: usize, classes: usize, charset: &[char]) -> String {
+    let mut text = String::new();
+    let mut previous = 0;
+    for step in scores.chunks(classes).take(steps) {
+        let best = step
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map_or(0, |(i, _)| i);
+        if best != 0 && best != previous {
+            text.push(charset.get(best - 1).copied().unwrap_or(' '));
+        }
+        previous = best;
+    }
+    text.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map_with(w: usize, h: usize, blobs: &[(usize, usize, usize, usize, f32)]) -> Vec<f32> {
+        let mut map = vec![0.0; w * h];
+        for &(x0, y0, bw, bh, p) in blobs {
+            for y in y0..y0 + bh {
+                for x in x0..x0 + bw {
+                    map[y * w + x] = p;
+                }
+            }
+        }
+        map
+    }
+
+    #[test]
+    fn test_each_blob_becomes_a_grown_box() {
+        let map = map_with(64, 32, &[(4, 4, 20, 6, 0.9), (40, 20, 10, 4, 0.8)]);
+        let boxes = boxes_from_probability_map(&map, 64, 32);
+        assert_eq!(boxes.len(), 2);
+
+        // 20x6 kernel: grows by 120 * 1.5 / 52 on every side.
+        let grow = 120.0 * 1.5 / 52.0;
+        let (x1, y1, x2, y2) = boxes[0];
+        approx::assert_abs_diff_eq!(x1, 4.0 - grow, epsilon = 1e-9);
+        approx::assert_abs_diff_eq!(y1, 4.0 - grow, epsilon = 1e-9);
+        approx::assert_abs_diff_eq!(x2, 24.0 + grow, epsilon = 1e-9);
+        approx::assert_abs_diff_eq!(y2, 10.0 + grow, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_faint_and_thin_blobs_are_dropped() {
+        let map = map_with(64, 32, &[(4, 4, 20, 6, 0.4), (40, 20, 20, 2, 0.9)]);
+        assert!(boxes_from_probability_map(&map, 64, 32).is_empty());
+    }
+
+    #[test]
+    fn test_detection_input_is_stride_aligned_and_never_enlarged() {
+        let frame = Frame::new(vec![0; 1920 * 1080 * 3], 1920, 1080, 3, 0);
+        let (input, sx, sy) = detection_input(&frame, 960);
+        assert_eq!(input.shape(), &[1, 3, 544, 960]);
+        approx::assert_abs_diff_eq!(sx, 2.0, epsilon = 1e-9);
+        approx::assert_abs_diff_eq!(sy, 1080.0 / 544.0, epsilon = 1e-9);
+
+        let small = Frame::new(vec![0; 100 * 50 * 3], 100, 50, 3, 0);
+        let (input, _, _) = detection_input(&small, 960);
+        assert_eq!(input.shape(), &[1, 3, 64, 96]);
+    }
+
+    #[test]
+    fn test_clipped_region_stays_in_frame() {
+        let region = clipped_region(-3.5, 10.2, 700.0, 20.7, 640, 480).unwrap();
+        assert_eq!(
+            (region.x, region.y, region.width, region.height),
+            (0, 10, 640, 11)
+        );
+        assert!(clipped_region(650.0, 0.0, 700.0, 10.0, 640, 480).is_none());
+    }
+
+    #[test]
+    fn test_ctc_decode_collapses_repeats_and_blanks() {
+        let charset: Vec<char> = "ab".chars().collect();
+        // Steps: a, a, blank, a, b, past-the-end (space), b
+        let best = [1, 1, 0, 1, 2, 3, 2];
+        let classes = 4;
+        let mut scores = vec![0.0f32; best.len() * classes];
+        for (step, &class) in best.iter().enumerate() {
+            scores[step * classes + class] = 1.0;
+        }
+        assert_eq!(ctc_decode(&scores, best.len(), classes, &charset), "aab b");
+    }
+
+    #[test]
+    fn test_default_charset_matches_english_model() {
+        assert_eq!(DEFAULT_CHARSET.chars().count(), 94);
+        assert_eq!(DEFAULT_CHARSET.chars().nth(10), Some(':'));
+    }
+}
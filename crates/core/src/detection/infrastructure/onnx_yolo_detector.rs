@@ -1,18 +1,35 @@
 /// YOLO face detector using ONNX Runtime.
 ///
 /// Pipeline: letterbox preprocess → ONNX inference → NMS → ByteTrack → region building.
-/// The detect() method orchestrates these stages for each frame.
+/// The detect() method orchestrates these stages for each frame. Built with
+/// [`OnnxYoloDetector::persons_from_shared_session`], the same pipeline runs
+/// a COCO person model and builds body regions instead. With
+/// [`OnnxYoloDetector::with_model_format`], it runs other registered face
+/// models, such as SCRFD. With [`OnnxYoloDetector::with_zones`], it only
+/// looks inside the given detection zones.
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
-use crate::detection::domain::face_detector::FaceDetector;
+use crate::detection::domain::confidence_calibration::ConfidenceCalibration;
+use crate::detection::domain::detection_zone::DetectionZone;
+use crate::detection::domain::detector_backend::DetectorBackend;
+use crate::detection::domain::face_detector::{DetectorState, FaceDetector};
 use crate::detection::domain::face_landmarks::FaceLandmarks;
-use crate::detection::domain::face_region_builder::FaceRegionBuilder;
+use crate::detection::domain::face_observation::{FaceObservation, FaceObservations};
+use crate::detection::domain::face_region_builder::{DetectionQuality, FaceRegionBuilder};
+use crate::detection::domain::person_region_builder::PersonRegionBuilder;
 use crate::shared::frame::Frame;
+use crate::shared::letterbox::{Crop, Letterbox};
+use crate::shared::pixel_converter::PixelConverter;
 use crate::shared::region::Region;
 
 use super::bytetrack_tracker::{ByteTracker, Detection as TrackerDetection};
+use super::detector_state::{
+    builder_from_json, builder_to_json, tracker_from_json, tracker_to_json,
+};
 use super::math::bbox_iou;
+use super::model_registry::{InputSpec, ModelFormat, OutputLayout};
 
 const DEFAULT_INPUT_SIZE: u32 = 640;
 pub const DEFAULT_CONFIDENCE: f64 = 0.25;
@@ -20,13 +37,34 @@ const NMS_IOU_THRESH: f64 = 0.45;
 const NUM_KEYPOINT_VALUES: usize = 15;
 const KEYPOINT_CONF_THRESH: f64 = 0.5;
 
+/// Shared observation log, filled on the detection thread and read by the
+/// caller once the scan has finished.
+pub type ObservationLog = Arc<Mutex<FaceObservations>>;
+
+/// A built ONNX session that several detectors can run on in turn, e.g. one
+/// per file of a batch.
+pub type SharedSession = Arc<Mutex<ort::session::Session>>;
+
+/// How tracked boxes become regions: faces steer by landmarks, people by
+/// their box alone.
+enum RegionBuilding {
+    Faces(FaceRegionBuilder),
+    People(PersonRegionBuilder),
+}
+
 pub struct OnnxYoloDetector {
-    session: Arc<Mutex<ort::session::Session>>,
-    region_builder: FaceRegionBuilder,
+    session: SharedSession,
+    region_builder: RegionBuilding,
     tracker: ByteTracker,
+    tracking: bool,
     confidence: f64,
+    calibration: ConfidenceCalibration,
+    format: ModelFormat,
     input_size: u32,
     letterbox_buf: ndarray::Array4<f32>,
+    converter: Option<Arc<dyn PixelConverter>>,
+    observations: Option<ObservationLog>,
+    zones: Vec<DetectionZone>,
 }
 
 /// Extract the model input resolution from an ONNX session, falling back to 640.
@@ -60,17 +98,32 @@ impl OnnxYoloDetector {
         let s = input_size as usize;
         Ok(Self {
             session: Arc::new(Mutex::new(session)),
-            region_builder,
+            region_builder: RegionBuilding::Faces(region_builder),
             tracker,
+            tracking: true,
             confidence,
+            calibration: ConfidenceCalibration::Identity,
+            format: ModelFormat::YOLO,
             input_size,
             letterbox_buf: ndarray::Array4::<f32>::zeros((1, 3, s, s)),
+            converter: None,
+            observations: None,
+            zones: Vec::new(),
         })
     }
 
     /// Build an ONNX session ahead of time so detector construction is instant.
     pub fn build_session(
         model_path: &Path,
+    ) -> Result<ort::session::Session, Box<dyn std::error::Error>> {
+        Self::build_session_on(model_path, DetectorBackend::Auto)
+    }
+
+    /// Like [`build_session`](Self::build_session), running the model on
+    /// `backend`'s execution provider.
+    pub fn build_session_on(
+        model_path: &Path,
+        backend: DetectorBackend,
     ) -> Result<ort::session::Session, Box<dyn std::error::Error>> {
         let intra_threads = std::thread::available_parallelism()
             .map(|n| n.get())
@@ -79,28 +132,115 @@ impl OnnxYoloDetector {
             .with_optimization_level(ort::session::builder::GraphOptimizationLevel::Level3)?
             .with_inter_threads(1)?
             .with_intra_threads(intra_threads)?
-            .with_execution_providers(super::execution_provider::preferred_execution_providers())?
+            .with_execution_providers(super::execution_provider::execution_providers(backend))?
             .commit_from_file(model_path)?;
         Ok(session)
     }
 
     pub fn from_shared_session(
-        session: Arc<Mutex<ort::session::Session>>,
+        session: SharedSession,
         input_size: u32,
         region_builder: FaceRegionBuilder,
         tracker: ByteTracker,
         confidence: f64,
+    ) -> Self {
+        Self::with_region_building(
+            session,
+            input_size,
+            RegionBuilding::Faces(region_builder),
+            tracker,
+            confidence,
+        )
+    }
+
+    /// Detector for a COCO-trained YOLO model, keeping only class 0
+    /// ("person") and building whole-body regions.
+    pub fn persons_from_shared_session(
+        session: SharedSession,
+        input_size: u32,
+        region_builder: PersonRegionBuilder,
+        tracker: ByteTracker,
+        confidence: f64,
+    ) -> Self {
+        Self::with_region_building(
+            session,
+            input_size,
+            RegionBuilding::People(region_builder),
+            tracker,
+            confidence,
+        )
+    }
+
+    fn with_region_building(
+        session: SharedSession,
+        input_size: u32,
+        region_builder: RegionBuilding,
+        tracker: ByteTracker,
+        confidence: f64,
     ) -> Self {
         let s = input_size as usize;
         Self {
             session,
             region_builder,
             tracker,
+            tracking: true,
             confidence,
+            calibration: ConfidenceCalibration::Identity,
+            format: ModelFormat::YOLO,
             input_size,
             letterbox_buf: ndarray::Array4::<f32>::zeros((1, 3, s, s)),
+            converter: None,
+            observations: None,
+            zones: Vec::new(),
         }
     }
+
+    /// Interpret `confidence` on the calibrated sensitivity scale of the
+    /// loaded model rather than as a raw score.
+    pub fn with_calibration(mut self, calibration: ConfidenceCalibration) -> Self {
+        self.calibration = calibration;
+        self
+    }
+
+    /// Feeds and decodes the model by `format` instead of as a YOLO model.
+    /// Pass the input size from the format, when it has one, to the
+    /// constructor.
+    pub fn with_model_format(mut self, format: ModelFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Letterboxes frames into the model input with `converter`, such as a
+    /// GPU, instead of on the CPU.
+    pub fn with_pixel_converter(mut self, converter: Arc<dyn PixelConverter>) -> Self {
+        self.converter = Some(converter);
+        self
+    }
+
+    /// Records each tracked face's score and landmarks into `log`, keyed by
+    /// frame index and track ID.
+    pub fn with_observation_log(mut self, log: ObservationLog) -> Self {
+        self.observations = Some(log);
+        self
+    }
+
+    /// Reports every detection that passes the confidence threshold and
+    /// NMS as it is, without a track ID, instead of following faces with
+    /// the tracker, which only starts tracks from detections scoring 0.5
+    /// or more. Each frame is then judged on its own, as when measuring the
+    /// model on unrelated still images.
+    pub fn without_tracking(mut self) -> Self {
+        self.tracking = false;
+        self
+    }
+
+    /// Only looks inside `zones`: the model sees the rectangle around them,
+    /// and faces centered outside all of them are dropped. No zones is the
+    /// whole frame.
+    pub fn with_zones(mut self, zones: Vec<DetectionZone>) -> Self {
+        self.zones = zones;
+        self
+    }
 }
 
 impl FaceDetector for OnnxYoloDetector {
@@ -108,16 +248,56 @@ impl FaceDetector for OnnxYoloDetector {
         let fw = frame.width();
         let fh = frame.height();
 
-        let (scale, pad_x, pad_y) = letterbox_into(frame, self.input_size, &mut self.letterbox_buf);
-        let lb = LetterboxParams {
-            scale,
-            pad_x,
-            pad_y,
+        let lb = letterbox_into(
+            frame,
+            DetectionZone::bounds(&self.zones, fw, fh),
+            self.input_size,
+            &mut self.letterbox_buf,
+            self.converter.as_deref(),
+        );
+        normalize(&mut self.letterbox_buf, &self.format.input);
+        let mut filtered = self.run_inference_and_nms(&lb)?;
+        if !self.zones.is_empty() {
+            filtered.retain(|d| {
+                let (cx, cy) = ((d.x1 + d.x2) / 2.0, (d.y1 + d.y2) / 2.0);
+                self.zones.iter().any(|zone| zone.contains(cx, cy, fw, fh))
+            });
+        }
+        let tracks = if self.tracking {
+            self.track(&filtered)
+        } else {
+            untracked(&filtered)
         };
-        let filtered = self.run_inference_and_nms(&lb)?;
-        let tracks = self.track(&filtered);
+        if let Some(ref log) = self.observations {
+            let observed = observe(&tracks, &filtered);
+            log.lock()
+                .map_err(|_| "Observation log poisoned")?
+                .insert(frame.index(), observed);
+        }
         Ok(self.build_regions(&tracks, &filtered, fw, fh))
     }
+
+    fn save_state(&self) -> Result<DetectorState, Box<dyn std::error::Error>> {
+        let regions = match self.region_builder {
+            RegionBuilding::Faces(ref builder) => builder.snapshot(),
+            RegionBuilding::People(ref builder) => builder.snapshot(),
+        };
+        Ok(serde_json::json!({
+            "tracker": tracker_to_json(&self.tracker.snapshot()),
+            "regions": builder_to_json(&regions),
+        }))
+    }
+
+    fn restore_state(&mut self, state: &DetectorState) -> Result<(), Box<dyn std::error::Error>> {
+        let tracker = tracker_from_json(&state["tracker"])?;
+        let regions = builder_from_json(&state["regions"])?;
+        self.tracker.restore(tracker);
+        match self.region_builder {
+            RegionBuilding::Faces(ref mut builder) => builder.restore(regions),
+            RegionBuilding::People(ref mut builder) => builder.restore(regions),
+        }
+        Ok(())
+    }
 }
 
 impl OnnxYoloDetector {
@@ -126,7 +306,7 @@ impl OnnxYoloDetector {
         lb: &LetterboxParams,
     ) -> Result<Vec<RawDetection>, Box<dyn std::error::Error>> {
         let input_value = ort::value::TensorRef::from_array_view(self.letterbox_buf.view())?;
-        let confidence = self.confidence;
+        let confidence = self.calibration.raw_threshold(self.confidence);
 
         let mut session = self
             .session
@@ -137,12 +317,29 @@ impl OnnxYoloDetector {
             return Err("YOLO model produced no outputs".into());
         }
 
+        if self.format.output == OutputLayout::Scrfd {
+            let tensors = (0..outputs.len())
+                .map(|i| outputs[i].try_extract_array::<f32>())
+                .collect::<Result<Vec<_>, _>>()?;
+            let maps = tensors
+                .iter()
+                .map(|tensor| tensor.as_slice().ok_or("Cannot get tensor slice"))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut raw_dets = parse_scrfd_detections(&maps, self.input_size, confidence, lb)?;
+            return Ok(nms(&mut raw_dets, NMS_IOU_THRESH));
+        }
+
         let tensor = outputs[0].try_extract_array::<f32>()?;
         let shape = tensor.shape();
         let data = tensor.as_slice().ok_or("Cannot get tensor slice")?;
 
         let (num_dets, num_feats, transposed) = parse_output_shape(shape)?;
-        let mut raw_dets = parse_detections(data, num_dets, num_feats, transposed, confidence, lb);
+        // COCO outputs have 80 class scores where a pose model has
+        // keypoints; column 4, the first class, is "person".
+        let keypoints = matches!(self.region_builder, RegionBuilding::Faces(_));
+        let mut raw_dets = parse_detections(
+            data, num_dets, num_feats, transposed, confidence, keypoints, lb,
+        );
         Ok(nms(&mut raw_dets, NMS_IOU_THRESH))
     }
 
@@ -164,27 +361,72 @@ impl OnnxYoloDetector {
         frame_w: u32,
         frame_h: u32,
     ) -> Vec<Region> {
+        let tracking = self.tracking;
         tracks
             .iter()
             .map(|track| {
-                let landmarks = track
-                    .det_index
-                    .and_then(|i| filtered.get(i))
-                    .and_then(|d| d.keypoints)
-                    .map(FaceLandmarks::new);
-
-                self.region_builder.build(
-                    (track.bbox[0], track.bbox[1], track.bbox[2], track.bbox[3]),
+                let bbox = (track.bbox[0], track.bbox[1], track.bbox[2], track.bbox[3]);
+                let track_id = tracking.then_some(track.id);
+                let builder = match self.region_builder {
+                    RegionBuilding::Faces(ref mut builder) => builder,
+                    RegionBuilding::People(ref mut builder) => {
+                        return builder.build(bbox, frame_w, frame_h, track_id);
+                    }
+                };
+                let detection = track.det_index.and_then(|i| filtered.get(i));
+                let landmarks = detection.and_then(|d| d.keypoints).map(FaceLandmarks::new);
+                let quality = DetectionQuality {
+                    confidence: detection.map_or(1.0, |d| d.confidence),
+                    reacquired: track.reacquired,
+                };
+
+                builder.build_with_quality(
+                    bbox,
                     frame_w,
                     frame_h,
                     landmarks.as_ref(),
-                    Some(track.id),
+                    track_id,
+                    quality,
                 )
             })
             .collect()
     }
 }
 
+/// Every detection as a track of its own, numbered from 1 within this
+/// frame only, for [`OnnxYoloDetector::without_tracking`].
+fn untracked(filtered: &[RawDetection]) -> Vec<super::bytetrack_tracker::Track> {
+    filtered
+        .iter()
+        .enumerate()
+        .map(|(i, d)| super::bytetrack_tracker::Track {
+            id: i as u32 + 1,
+            bbox: [d.x1, d.y1, d.x2, d.y2],
+            det_index: Some(i),
+            reacquired: false,
+        })
+        .collect()
+}
+
+/// Score and landmarks of the detection matched to each track. Tracks
+/// coasting on prediction alone have no detection and are left out.
+fn observe(
+    tracks: &[super::bytetrack_tracker::Track],
+    filtered: &[RawDetection],
+) -> HashMap<u32, FaceObservation> {
+    tracks
+        .iter()
+        .filter_map(|track| {
+            let detection = filtered.get(track.det_index?)?;
+            let observation = FaceObservation {
+                confidence: detection.confidence,
+                landmarks: detection.keypoints.map(FaceLandmarks::new),
+            };
+            Some((track.id, observation))
+        })
+        .collect()
+}
+
 fn parse_output_shape(shape: &[usize]) -> Result<(usize, usize, bool), Box<dyn std::error::Error>> {
     if shape.len() != 3 {
         return Err(format!("Unexpected YOLO output shape: {shape:?}").into());
@@ -203,15 +445,18 @@ struct LetterboxParams {
     scale: f64,
     pad_x: u32,
     pad_y: u32,
+    /// Top left of the letterboxed crop in the frame.
+    origin_x: u32,
+    origin_y: u32,
 }
 
 impl LetterboxParams {
     fn to_frame_x(&self, x: f64) -> f64 {
-        (x - self.pad_x as f64) / self.scale
+        self.origin_x as f64 + (x - self.pad_x as f64) / self.scale
     }
 
     fn to_frame_y(&self, y: f64) -> f64 {
-        (y - self.pad_y as f64) / self.scale
+        self.origin_y as f64 + (y - self.pad_y as f64) / self.scale
     }
 }
 
@@ -222,6 +467,7 @@ fn parse_detections(
     num_feats: usize,
     transposed: bool,
     confidence: f64,
+    keypoints: bool,
     lb: &LetterboxParams,
 ) -> Vec<RawDetection> {
     let feat = |det_idx: usize, feat_idx: usize| -> f32 {
@@ -252,15 +498,17 @@ fn parse_detections(
         let x2 = lb.to_frame_x(cx + w / 2.0);
         let y2 = lb.to_frame_y(cy + h / 2.0);
 
-        let keypoints = parse_keypoints(&feat, i, num_feats, lb);
-
         dets.push(RawDetection {
             x1,
             y1,
             x2,
             y2,
             confidence: conf,
-            keypoints,
+            keypoints: if keypoints {
+                parse_keypoints(&feat, i, num_feats, lb)
+            } else {
+                None
+            },
         });
     }
     dets
@@ -288,50 +536,116 @@ fn parse_keypoints(
     Some(pts)
 }
 
-/// Letterbox-resize into a pre-allocated NCHW tensor.
+/// Strides of SCRFD's feature maps, in output order.
+const SCRFD_STRIDES: [usize; 3] = [8, 16, 32];
+
+/// Decodes SCRFD's per-stride maps: scores, then box distances, then
+/// landmark distances, each for strides 8, 16, and 32. Every cell of a
+/// stride's grid has the same number of anchors, centered on the cell's
+/// top-left corner. Models exported without landmarks (six maps) yield
+/// detections without keypoints.
+fn parse_scrfd_detections(
+    maps: &[&[f32]],
+    input_size: u32,
+    confidence: f64,
+    lb: &LetterboxParams,
+) -> Result<Vec<RawDetection>, Box<dyn std::error::Error>> {
+    let levels = SCRFD_STRIDES.len();
+    let has_keypoints = match maps.len() {
+        9 => true,
+        6 => false,
+        n => return Err(format!("Expected 6 or 9 SCRFD outputs, got {n}").into()),
+    };
+    let mut dets = Vec::new();
+    for (level, &stride) in SCRFD_STRIDES.iter().enumerate() {
+        let scores = maps[level];
+        let boxes = maps[level + levels];
+        let side = (input_size as usize).div_ceil(stride);
+        let cells = side * side;
+        if cells == 0 || scores.len() % cells != 0 || boxes.len() != scores.len() * 4 {
+            return Err(
+                format!("SCRFD stride {stride} maps don't match a {input_size} input").into(),
+            );
+        }
+        let anchors = scores.len() / cells;
+        let keypoints = has_keypoints
+            .then(|| maps[level + 2 * levels])
+            .filter(|kps| kps.len() == scores.len() * 10);
+        for (i, &score) in scores.iter().enumerate() {
+            let score = score as f64;
+            if score < confidence {
+                continue;
+            }
+            let cell = i / anchors;
+            let cx = ((cell % side) * stride) as f64;
+            let cy = ((cell / side) * stride) as f64;
+            let s = stride as f64;
+            let d = &boxes[i * 4..i * 4 + 4];
+            dets.push(RawDetection {
+                x1: lb.to_frame_x(cx - d[0] as f64 * s),
+                y1: lb.to_frame_y(cy - d[1] as f64 * s),
+                x2: lb.to_frame_x(cx + d[2] as f64 * s),
+                y2: lb.to_frame_y(cy + d[3] as f64 * s),
+                confidence: score,
+                keypoints: keypoints.map(|kps| {
+                    let k = &kps[i * 10..i * 10 + 10];
+                    std::array::from_fn(|p| {
+                        (
+                            lb.to_frame_x(cx + k[2 * p] as f64 * s),
+                            lb.to_frame_y(cy + k[2 * p + 1] as f64 * s),
+                        )
+                    })
+                }),
+            });
+        }
+    }
+    Ok(dets)
+}
+
+/// Rescales a letterboxed tensor from 0–1 to what `input` expects.
+fn normalize(buf: &mut ndarray::Array4<f32>, input: &InputSpec) {
+    let yolo = ModelFormat::YOLO.input;
+    if (input.mean, input.std) == (yolo.mean, yolo.std) || input.std == 0.0 {
+        return;
+    }
+    let (mean, std) = (input.mean, input.std);
+    buf.mapv_inplace(|v| (v * 255.0 - mean) / std);
+}
+
+/// Letterboxes `crop` of the frame, or all of it, into the pre-allocated
+/// NCHW tensor `buf`, on `converter` when given.
 fn letterbox_into(
     frame: &Frame,
+    crop: Option<Crop>,
     target_size: u32,
     buf: &mut ndarray::Array4<f32>,
-) -> (f64, u32, u32) {
-    let fw = frame.width() as f64;
-    let fh = frame.height() as f64;
-    let target = target_size as f64;
-
-    let scale = (target / fw).min(target / fh);
-    let new_w = (fw * scale).round() as u32;
-    let new_h = (fh * scale).round() as u32;
-    let pad_x = (target_size - new_w) / 2;
-    let pad_y = (target_size - new_h) / 2;
-
-    // 114/255 gray is the YOLO letterbox convention
-    buf.fill(114.0f32 / 255.0);
-
-    let src = frame.as_ndarray();
-    let src_h = frame.height() as usize;
-    let src_w = frame.width() as usize;
-
-    for y in 0..new_h as usize {
-        let src_y = ((y as f64 / scale) as usize).min(src_h - 1);
-        for x in 0..new_w as usize {
-            let src_x = ((x as f64 / scale) as usize).min(src_w - 1);
-            let ty = pad_y as usize + y;
-            let tx = pad_x as usize + x;
-            for c in 0..3 {
-                buf[[0, c, ty, tx]] = src[[src_y, src_x, c]] as f32 / 255.0;
-            }
-        }
+    converter: Option<&dyn PixelConverter>,
+) -> LetterboxParams {
+    let letterbox = match crop {
+        Some(crop) => Letterbox::of_crop(crop, target_size),
+        None => Letterbox::new(frame.width(), frame.height(), target_size),
+    };
+    let out = buf.as_slice_mut().expect("letterbox buffer is contiguous");
+    match converter {
+        Some(converter) => converter.letterbox(frame, &letterbox, out),
+        None => letterbox.fill(frame, out),
+    }
+    let origin = crop.map_or((0, 0), |crop| (crop.x, crop.y));
+    LetterboxParams {
+        scale: letterbox.scale,
+        pad_x: letterbox.pad_x,
+        pad_y: letterbox.pad_y,
+        origin_x: origin.0,
+        origin_y: origin.1,
     }
-
-    (scale, pad_x, pad_y)
 }
 
 #[cfg(test)]
 fn letterbox(frame: &Frame, target_size: u32) -> (ndarray::Array4<f32>, f64, u32, u32) {
     let s = target_size as usize;
     let mut buf = ndarray::Array4::<f32>::zeros((1, 3, s, s));
-    let (scale, pad_x, pad_y) = letterbox_into(frame, target_size, &mut buf);
-    (buf, scale, pad_x, pad_y)
+    let lb = letterbox_into(frame, None, target_size, &mut buf, None);
+    (buf, lb.scale, lb.pad_x, lb.pad_y)
 }
 
 #[derive(Clone, Debug)]
@@ -378,6 +692,7 @@ fn nms(dets: &mut [RawDetection], iou_thresh: f64) -> Vec<RawDetection> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::detection::infrastructure::bytetrack_tracker::Track;
 
     #[test]
     fn test_letterbox_preserves_aspect_ratio() {
@@ -420,6 +735,195 @@ mod tests {
         assert!((tensor[[0, 0, 0, 0]] - pad_val).abs() < 0.01);
     }
 
+    #[test]
+    fn test_cropped_letterbox_maps_back_to_frame() {
+        // A bright 100x100 doorway at (300, 100) of a dark frame.
+        let mut frame = Frame::new(vec![0u8; 640 * 480 * 3], 640, 480, 3, 0);
+        for y in 100..200 {
+            let row = (y * 640 + 300) * 3;
+            frame.data_mut()[row..row + 300].fill(255);
+        }
+        let crop = Crop {
+            x: 300,
+            y: 100,
+            width: 100,
+            height: 100,
+        };
+        let mut buf = ndarray::Array4::<f32>::zeros((1, 3, 320, 320));
+        let lb = letterbox_into(&frame, Some(crop), 320, &mut buf, None);
+
+        assert!((lb.scale - 3.2).abs() < 1e-9);
+        assert_eq!(buf[[0, 0, 0, 0]], 1.0);
+        assert_eq!(buf[[0, 0, 319, 319]], 1.0);
+        assert!((lb.to_frame_x(160.0) - 350.0).abs() < 1e-9);
+        assert!((lb.to_frame_y(0.0) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_untracked_keeps_detections_the_tracker_would_not_start() {
+        let filtered = vec![RawDetection {
+            x1: 10.0,
+            y1: 20.0,
+            x2: 60.0,
+            y2: 80.0,
+            confidence: 0.3,
+            keypoints: None,
+        }];
+        let mut tracker = ByteTracker::new(30);
+        assert!(tracker
+            .update(&[TrackerDetection {
+                bbox: [10.0, 20.0, 60.0, 80.0],
+                score: 0.3,
+            }])
+            .is_empty());
+
+        let tracks = untracked(&filtered);
+
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].bbox, [10.0, 20.0, 60.0, 80.0]);
+        assert_eq!(tracks[0].det_index, Some(0));
+    }
+
+    #[test]
+    fn test_observe_skips_coasting_tracks() {
+        let filtered = vec![RawDetection {
+            x1: 0.0,
+            y1: 0.0,
+            x2: 100.0,
+            y2: 100.0,
+            confidence: 0.7,
+            keypoints: Some([(30.0, 40.0); 5]),
+        }];
+        let tracks = vec![
+            Track {
+                id: 4,
+                bbox: [0.0, 0.0, 100.0, 100.0],
+                det_index: Some(0),
+                reacquired: false,
+            },
+            Track {
+                id: 5,
+                bbox: [200.0, 0.0, 300.0, 100.0],
+                det_index: None,
+                reacquired: false,
+            },
+        ];
+
+        let observed = observe(&tracks, &filtered);
+
+        assert_eq!(observed.len(), 1);
+        assert!((observed[&4].confidence - 0.7).abs() < 1e-9);
+        assert_eq!(
+            observed[&4].landmarks.as_ref().unwrap().points()[0],
+            (30.0, 40.0)
+        );
+    }
+
+    #[test]
+    fn test_person_detections_skip_keypoints() {
+        // One COCO detection: box, then 80 class scores (person first).
+        let mut data = vec![0.1f32; 84];
+        data[..5].copy_from_slice(&[320.0, 320.0, 100.0, 200.0, 0.9]);
+        let lb = LetterboxParams {
+            scale: 1.0,
+            pad_x: 0,
+            pad_y: 0,
+            origin_x: 0,
+            origin_y: 0,
+        };
+
+        let people = parse_detections(&data, 1, 84, false, 0.5, false, &lb);
+        let with_keypoints = parse_detections(&data, 1, 84, false, 0.5, true, &lb);
+
+        assert_eq!(people.len(), 1);
+        assert!((people[0].confidence - 0.9).abs() < 1e-6);
+        assert!((people[0].y1 - 220.0).abs() < 1e-6);
+        assert!(people[0].keypoints.is_none());
+        assert!(with_keypoints[0].keypoints.is_some());
+    }
+
+    /// SCRFD maps for a 32 px input with two anchors per cell, all zero.
+    fn scrfd_maps() -> Vec<Vec<f32>> {
+        let scores = [32, 8, 2];
+        let mut maps: Vec<Vec<f32>> = scores.iter().map(|&n| vec![0.0; n]).collect();
+        maps.extend(scores.iter().map(|&n| vec![0.0; n * 4]));
+        maps.extend(scores.iter().map(|&n| vec![0.0; n * 10]));
+        maps
+    }
+
+    #[test]
+    fn test_scrfd_decodes_distances_from_anchor_centers() {
+        let mut maps = scrfd_maps();
+        // Stride 8, cell (x 1, y 2), first anchor: centered at (8, 16).
+        let i = (2 * 4 + 1) * 2;
+        maps[0][i] = 0.9;
+        maps[3][i * 4..i * 4 + 4].copy_from_slice(&[1.0, 1.0, 2.0, 1.0]);
+        maps[6][i * 10] = 0.5;
+        let slices: Vec<&[f32]> = maps.iter().map(Vec::as_slice).collect();
+        let lb = LetterboxParams {
+            scale: 1.0,
+            pad_x: 0,
+            pad_y: 0,
+            origin_x: 0,
+            origin_y: 0,
+        };
+
+        let dets = parse_scrfd_detections(&slices, 32, 0.5, &lb).unwrap();
+
+        assert_eq!(dets.len(), 1);
+        let d = &dets[0];
+        assert_eq!((d.x1, d.y1, d.x2, d.y2), (0.0, 8.0, 24.0, 24.0));
+        assert_eq!(d.keypoints.unwrap()[0], (12.0, 16.0));
+        assert_eq!(d.keypoints.unwrap()[1], (8.0, 16.0));
+    }
+
+    #[test]
+    fn test_scrfd_without_landmarks_has_no_keypoints() {
+        let mut maps = scrfd_maps();
+        maps.truncate(6);
+        maps[2][1] = 0.8;
+        let slices: Vec<&[f32]> = maps.iter().map(Vec::as_slice).collect();
+        let lb = LetterboxParams {
+            scale: 0.5,
+            pad_x: 0,
+            pad_y: 0,
+            origin_x: 0,
+            origin_y: 0,
+        };
+
+        let dets = parse_scrfd_detections(&slices, 32, 0.5, &lb).unwrap();
+
+        assert_eq!(dets.len(), 1);
+        assert!(dets[0].keypoints.is_none());
+    }
+
+    #[test]
+    fn test_scrfd_rejects_maps_of_another_size() {
+        let maps = scrfd_maps();
+        let slices: Vec<&[f32]> = maps.iter().map(Vec::as_slice).collect();
+        let lb = LetterboxParams {
+            scale: 1.0,
+            pad_x: 0,
+            pad_y: 0,
+            origin_x: 0,
+            origin_y: 0,
+        };
+        assert!(parse_scrfd_detections(&slices, 640, 0.5, &lb).is_err());
+    }
+
+    #[test]
+    fn test_scrfd_input_is_centered_on_mid_gray() {
+        let mut buf = ndarray::Array4::<f32>::from_elem((1, 3, 1, 2), 1.0);
+        buf[[0, 0, 0, 1]] = 0.0;
+        normalize(&mut buf, &ModelFormat::SCRFD.input);
+        assert_eq!(buf[[0, 0, 0, 0]], 127.5 / 128.0);
+        assert_eq!(buf[[0, 0, 0, 1]], -127.5 / 128.0);
+
+        let mut yolo = ndarray::Array4::<f32>::from_elem((1, 3, 1, 1), 0.5);
+        normalize(&mut yolo, &ModelFormat::YOLO.input);
+        assert_eq!(yolo[[0, 0, 0, 0]], 0.5);
+    }
+
     #[test]
     fn test_nms_suppresses_overlapping() {
         let mut dets = vec![
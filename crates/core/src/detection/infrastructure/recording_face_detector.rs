@@ -0,0 +1,142 @@
+use std::sync::{Arc, Mutex};
+
+use crate::detection::domain::face_detector::{DetectorState, FaceDetector};
+use crate::detection::infrastructure::disk_backed_detections::DiskBackedDetections;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// Shared per-frame detection log, keyed by frame index. Older frames spill
+/// to disk on long recordings.
+pub type DetectionLog = Arc<Mutex<DiskBackedDetections>>;
+
+/// Decorator that records every detection result by frame index.
+///
+/// The detector is moved onto the pipeline's detection thread, so the log is
+/// shared through an `Arc<Mutex<_>>` the caller keeps a handle to and reads
+/// once the pipeline has finished.
+pub struct RecordingFaceDetector {
+    inner: Box<dyn FaceDetector>,
+    log: DetectionLog,
+}
+
+impl RecordingFaceDetector {
+    pub fn new(inner: Box<dyn FaceDetector>) -> Self {
+        Self {
+            inner,
+            log: Arc::new(Mutex::new(DiskBackedDetections::new())),
+        }
+    }
+
+    /// Records into `log`, for a custom memory limit or spill folder. Call
+    /// before taking a handle with `log()`.
+    pub fn with_log(mut self, log: DiskBackedDetections) -> Self {
+        self.log = Arc::new(Mutex::new(log));
+        self
+    }
+
+    /// Returns a handle to the log that stays valid after the detector is moved.
+    pub fn log(&self) -> DetectionLog {
+        Arc::clone(&self.log)
+    }
+}
+
+impl FaceDetector for RecordingFaceDetector {
+    fn detect(&mut self, frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+        let regions = self.inner.detect(frame)?;
+        self.log
+            .lock()
+            .map_err(|_| "Detection log poisoned")?
+            .insert(frame.index(), regions.clone())?;
+        Ok(regions)
+    }
+
+    fn save_state(&self) -> Result<DetectorState, Box<dyn std::error::Error>> {
+        self.inner.save_state()
+    }
+
+    fn restore_state(&mut self, state: &DetectorState) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.restore_state(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StubDetector {
+        per_frame: usize,
+    }
+
+    impl FaceDetector for StubDetector {
+        fn detect(&mut self, frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+            Ok((0..self.per_frame)
+                .map(|i| Region {
+                    x: frame.index() as i32,
+                    y: 0,
+                    width: 10,
+                    height: 10,
+                    track_id: Some(i as u32 + 1),
+                    full_width: None,
+                    full_height: None,
+                    unclamped_x: None,
+                    unclamped_y: None,
+                })
+                .collect())
+        }
+    }
+
+    fn frame(index: usize) -> Frame {
+        Frame::new(vec![0u8; 10 * 10 * 3], 10, 10, 3, index)
+    }
+
+    #[test]
+    fn test_passes_through_inner_regions() {
+        let mut detector = RecordingFaceDetector::new(Box::new(StubDetector { per_frame: 2 }));
+        let regions = detector.detect(&frame(0)).unwrap();
+        assert_eq!(regions.len(), 2);
+    }
+
+    #[test]
+    fn test_records_regions_by_frame_index() {
+        let mut detector = RecordingFaceDetector::new(Box::new(StubDetector { per_frame: 1 }));
+        let log = detector.log();
+
+        detector.detect(&frame(3)).unwrap();
+        detector.detect(&frame(7)).unwrap();
+
+        let mut log = log.lock().unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log.get(3).unwrap().unwrap()[0].x, 3);
+        assert_eq!(log.get(7).unwrap().unwrap()[0].x, 7);
+    }
+
+    #[test]
+    fn test_records_empty_frames() {
+        let mut detector = RecordingFaceDetector::new(Box::new(StubDetector { per_frame: 0 }));
+        let log = detector.log();
+
+        detector.detect(&frame(0)).unwrap();
+
+        assert_eq!(log.lock().unwrap().get(0).unwrap(), Some(vec![]));
+    }
+
+    #[test]
+    fn test_long_recordings_spill_to_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let spill = DiskBackedDetections::new()
+            .with_memory_frames(2)
+            .with_spill_dir(dir.path());
+        let mut detector =
+            RecordingFaceDetector::new(Box::new(StubDetector { per_frame: 1 })).with_log(spill);
+        let log = detector.log();
+
+        for i in 0..10 {
+            detector.detect(&frame(i)).unwrap();
+        }
+
+        let mut log = log.lock().unwrap();
+        assert!(log.has_spilled());
+        assert_eq!(log.len(), 10);
+        assert_eq!(log.get(4).unwrap().unwrap()[0].x, 4);
+    }
+}
@@ -1,9 +1,13 @@
 use std::collections::HashMap;
 
-use crate::detection::domain::face_detector::FaceDetector;
+use serde_json::{json, Value};
+
+use crate::detection::domain::face_detector::{DetectorState, FaceDetector};
 use crate::shared::frame::Frame;
 use crate::shared::region::Region;
 
+use super::detector_state::{as_u32, floats, regions_from_json, regions_to_json, INVALID};
+
 /// Decorator that runs detection every N frames, reusing results in between.
 ///
 /// On skipped frames, region positions are linearly extrapolated from the
@@ -100,6 +104,61 @@ impl FaceDetector for SkipFrameDetector {
             Ok(self.extrapolate(&self.last_regions, self.frames_since_detect))
         }
     }
+
+    fn save_state(&self) -> Result<DetectorState, Box<dyn std::error::Error>> {
+        let mut tracks: Vec<u32> = self
+            .prev_pos
+            .keys()
+            .chain(self.velocity.keys())
+            .copied()
+            .collect();
+        tracks.sort_unstable();
+        tracks.dedup();
+        let tracks: Vec<Value> = tracks
+            .into_iter()
+            .map(|id| {
+                json!({
+                    "id": id,
+                    "position": self.prev_pos.get(&id).map(|&(x, y)| [x, y]),
+                    "velocity": self.velocity.get(&id).map(|&(dx, dy)| [dx, dy]),
+                })
+            })
+            .collect();
+        Ok(json!({
+            "frame_count": self.frame_count,
+            "frames_since_detect": self.frames_since_detect,
+            "last_regions": regions_to_json(&self.last_regions),
+            "tracks": tracks,
+            "inner": self.inner.save_state()?,
+        }))
+    }
+
+    fn restore_state(&mut self, state: &DetectorState) -> Result<(), Box<dyn std::error::Error>> {
+        let mut prev_pos = HashMap::new();
+        let mut velocity = HashMap::new();
+        for track in state["tracks"].as_array().ok_or(INVALID)? {
+            let id = as_u32(&track["id"])?;
+            if !track["position"].is_null() {
+                let [x, y] = floats(&track["position"])?;
+                prev_pos.insert(id, (x as i32, y as i32));
+            }
+            if !track["velocity"].is_null() {
+                let [dx, dy] = floats(&track["velocity"])?;
+                velocity.insert(id, (dx, dy));
+            }
+        }
+        let frame_count = state["frame_count"].as_u64().ok_or(INVALID)? as usize;
+        let frames_since_detect = state["frames_since_detect"].as_u64().ok_or(INVALID)? as usize;
+        let last_regions = regions_from_json(&state["last_regions"])?;
+        self.inner.restore_state(&state["inner"])?;
+
+        self.frame_count = frame_count;
+        self.frames_since_detect = frames_since_detect;
+        self.last_regions = last_regions;
+        self.prev_pos = prev_pos;
+        self.velocity = velocity;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -184,6 +243,25 @@ mod tests {
         assert!(r1.is_empty());
     }
 
+    #[test]
+    fn test_restored_detector_keeps_skipping_and_extrapolating() {
+        let results = vec![vec![region(1, 10, 20)], vec![region(1, 30, 20)]];
+        let mut detector =
+            SkipFrameDetector::new(Box::new(FakeDetector::new(results.clone())), 2).unwrap();
+        for i in 0..3 {
+            detector.detect(&frame(i)).unwrap();
+        }
+        let state = detector.save_state().unwrap();
+        let mut restored = SkipFrameDetector::new(Box::new(FakeDetector::new(results)), 2).unwrap();
+        restored.restore_state(&state).unwrap();
+
+        // Frame 3 is skipped and extrapolated from frames 0 and 2.
+        assert_eq!(
+            restored.detect(&frame(3)).unwrap(),
+            detector.detect(&frame(3)).unwrap()
+        );
+    }
+
     #[test]
     fn test_skip_interval_0_errors() {
         let inner = FakeDetector::new(vec![vec![]]);
@@ -0,0 +1,15 @@
+use std::path::PathBuf;
+
+use crate::shared::region::Region;
+
+/// An image of a benchmark dataset with its hand-labeled faces.
+///
+/// `ignored` holds faces the labels mark as not worth judging (too small
+/// or flagged invalid): finding one is not a false positive, and missing
+/// one is not a miss.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnnotatedImage {
+    pub path: PathBuf,
+    pub faces: Vec<Region>,
+    pub ignored: Vec<Region>,
+}
@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use crate::evaluation::domain::annotated_image::AnnotatedImage;
+use crate::evaluation::domain::detection_score::DetectionScore;
+use crate::evaluation::domain::latency_summary::LatencySummary;
+use crate::shared::region::Region;
+
+/// A detector's results over a dataset: how well it found the labeled
+/// faces and how long each image took.
+#[derive(Clone, Debug)]
+pub struct BenchmarkReport {
+    match_iou: f64,
+    score: DetectionScore,
+    latencies: Vec<Duration>,
+}
+
+impl BenchmarkReport {
+    pub fn new(match_iou: f64) -> Self {
+        Self {
+            match_iou,
+            score: DetectionScore::default(),
+            latencies: Vec::new(),
+        }
+    }
+
+    /// Adds one image: what the detector found in it and how long that
+    /// took.
+    pub fn record(&mut self, image: &AnnotatedImage, detected: &[Region], elapsed: Duration) {
+        self.score += DetectionScore::of(&image.faces, &image.ignored, detected, self.match_iou);
+        self.latencies.push(elapsed);
+    }
+
+    pub fn match_iou(&self) -> f64 {
+        self.match_iou
+    }
+
+    pub fn images(&self) -> usize {
+        self.latencies.len()
+    }
+
+    pub fn score(&self) -> DetectionScore {
+        self.score
+    }
+
+    pub fn latency(&self) -> Option<LatencySummary> {
+        LatencySummary::of(&self.latencies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn region(x: i32, y: i32, w: i32, h: i32) -> Region {
+        Region {
+            x,
+            y,
+            width: w,
+            height: h,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    #[test]
+    fn test_sums_images() {
+        let image = AnnotatedImage {
+            path: PathBuf::from("a.jpg"),
+            faces: vec![region(0, 0, 50, 50)],
+            ignored: vec![],
+        };
+        let mut report = BenchmarkReport::new(0.5);
+
+        report.record(&image, &[region(0, 0, 50, 50)], Duration::from_millis(10));
+        report.record(&image, &[], Duration::from_millis(30));
+
+        assert_eq!(report.images(), 2);
+        assert_eq!(
+            report.score(),
+            DetectionScore {
+                true_positives: 1,
+                false_positives: 0,
+                missed: 1
+            }
+        );
+        assert_eq!(report.latency().unwrap().mean, Duration::from_millis(20));
+    }
+}
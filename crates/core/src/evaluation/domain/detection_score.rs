@@ -0,0 +1,176 @@
+use std::ops::AddAssign;
+
+use crate::shared::region::Region;
+
+/// A detection counts as a labeled face when it overlaps it this much, the
+/// WIDER FACE and PASCAL VOC convention.
+pub const DEFAULT_MATCH_IOU: f64 = 0.5;
+
+/// Detections judged against labeled faces, summed over any number of
+/// images.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DetectionScore {
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub missed: usize,
+}
+
+impl DetectionScore {
+    /// Scores one image. Each detection takes the unmatched face it
+    /// overlaps most, if that reaches `match_iou`; a face matches at most
+    /// one detection, so a duplicate box is a false positive. Detections
+    /// left over that overlap an `ignored` face count for nothing.
+    pub fn of(faces: &[Region], ignored: &[Region], detected: &[Region], match_iou: f64) -> Self {
+        let mut matched = vec![false; faces.len()];
+        let mut false_positives = 0;
+        for region in detected {
+            let best = faces
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !matched[*i])
+                .map(|(i, face)| (i, face.iou(region)))
+                .filter(|(_, iou)| *iou >= match_iou)
+                .max_by(|(_, a), (_, b)| a.total_cmp(b));
+            match best {
+                Some((i, _)) => matched[i] = true,
+                None if ignored.iter().any(|face| face.iou(region) >= match_iou) => {}
+                None => false_positives += 1,
+            }
+        }
+        let found = matched.iter().filter(|m| **m).count();
+        Self {
+            true_positives: found,
+            false_positives,
+            missed: faces.len() - found,
+        }
+    }
+
+    /// Share of detections that are faces, or `None` without detections.
+    pub fn precision(&self) -> Option<f64> {
+        ratio(
+            self.true_positives,
+            self.true_positives + self.false_positives,
+        )
+    }
+
+    /// Share of labeled faces found, or `None` without labeled faces.
+    pub fn recall(&self) -> Option<f64> {
+        ratio(self.true_positives, self.true_positives + self.missed)
+    }
+
+    /// Harmonic mean of precision and recall, for ranking settings by one
+    /// number.
+    pub fn f1(&self) -> Option<f64> {
+        let (p, r) = (self.precision()?, self.recall()?);
+        Some(if p + r > 0.0 {
+            2.0 * p * r / (p + r)
+        } else {
+            0.0
+        })
+    }
+}
+
+impl AddAssign for DetectionScore {
+    fn add_assign(&mut self, other: Self) {
+        self.true_positives += other.true_positives;
+        self.false_positives += other.false_positives;
+        self.missed += other.missed;
+    }
+}
+
+fn ratio(part: usize, whole: usize) -> Option<f64> {
+    (whole > 0).then(|| part as f64 / whole as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn region(x: i32, y: i32, w: i32, h: i32) -> Region {
+        Region {
+            x,
+            y,
+            width: w,
+            height: h,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    #[test]
+    fn test_counts_hits_misses_and_false_alarms() {
+        let faces = [region(0, 0, 100, 100), region(300, 0, 100, 100)];
+        let detected = [region(5, 5, 100, 100), region(600, 600, 50, 50)];
+
+        let score = DetectionScore::of(&faces, &[], &detected, DEFAULT_MATCH_IOU);
+
+        assert_eq!(
+            score,
+            DetectionScore {
+                true_positives: 1,
+                false_positives: 1,
+                missed: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_duplicate_box_is_a_false_positive() {
+        let faces = [region(0, 0, 100, 100)];
+        let detected = [region(0, 0, 100, 100), region(2, 2, 100, 100)];
+
+        let score = DetectionScore::of(&faces, &[], &detected, DEFAULT_MATCH_IOU);
+
+        assert_eq!((score.true_positives, score.false_positives), (1, 1));
+    }
+
+    #[test]
+    fn test_loose_box_does_not_match() {
+        let faces = [region(0, 0, 100, 100)];
+        let detected = [region(0, 0, 300, 300)];
+
+        let score = DetectionScore::of(&faces, &[], &detected, DEFAULT_MATCH_IOU);
+
+        assert_eq!((score.true_positives, score.missed), (0, 1));
+    }
+
+    #[test]
+    fn test_ignored_faces_count_for_nothing() {
+        let ignored = [region(0, 0, 8, 8)];
+        let detected = [region(0, 0, 8, 8)];
+
+        let score = DetectionScore::of(&[], &ignored, &detected, DEFAULT_MATCH_IOU);
+
+        assert_eq!(score, DetectionScore::default());
+    }
+
+    #[test]
+    fn test_rates_over_summed_images() {
+        let mut total = DetectionScore {
+            true_positives: 3,
+            false_positives: 1,
+            missed: 0,
+        };
+        total += DetectionScore {
+            true_positives: 1,
+            false_positives: 0,
+            missed: 4,
+        };
+
+        assert_relative_eq!(total.precision().unwrap(), 0.8);
+        assert_relative_eq!(total.recall().unwrap(), 0.5);
+        assert_relative_eq!(total.f1().unwrap(), 2.0 * 0.8 * 0.5 / 1.3);
+    }
+
+    #[test]
+    fn test_rates_are_undefined_without_data() {
+        let score = DetectionScore::default();
+        assert_eq!(score.precision(), None);
+        assert_eq!(score.recall(), None);
+        assert_eq!(score.f1(), None);
+    }
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+/// How long detection took per image.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LatencySummary {
+    pub mean: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+impl LatencySummary {
+    /// Summarizes `samples`, or `None` without any. Percentiles take the
+    /// nearest sample, never a value between two.
+    pub fn of(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let percentile = |p: f64| sorted[((sorted.len() - 1) as f64 * p).round() as usize];
+        let total: Duration = sorted.iter().sum();
+        Some(Self {
+            mean: total / sorted.len() as u32,
+            median: percentile(0.5),
+            p95: percentile(0.95),
+            max: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(value: u64) -> Duration {
+        Duration::from_millis(value)
+    }
+
+    #[test]
+    fn test_summary_of_samples() {
+        let samples: Vec<Duration> = (1..=20).rev().map(ms).collect();
+
+        let summary = LatencySummary::of(&samples).unwrap();
+
+        assert_eq!(summary.mean, Duration::from_micros(10_500));
+        assert_eq!(summary.median, ms(11));
+        assert_eq!(summary.p95, ms(19));
+        assert_eq!(summary.max, ms(20));
+    }
+
+    #[test]
+    fn test_single_sample() {
+        let summary = LatencySummary::of(&[ms(7)]).unwrap();
+        assert_eq!((summary.median, summary.p95), (ms(7), ms(7)));
+    }
+
+    #[test]
+    fn test_no_samples() {
+        assert_eq!(LatencySummary::of(&[]), None);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod annotated_image;
+pub mod benchmark_report;
+pub mod detection_score;
+pub mod latency_summary;
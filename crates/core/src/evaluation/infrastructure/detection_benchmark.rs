@@ -0,0 +1,132 @@
+//! Runs a detector over a labeled dataset and scores it.
+use std::time::Instant;
+
+use crate::detection::domain::face_detector::FaceDetector;
+use crate::evaluation::domain::annotated_image::AnnotatedImage;
+use crate::evaluation::domain::benchmark_report::BenchmarkReport;
+use crate::video::domain::video_reader::VideoReader;
+use crate::video::infrastructure::image_file_reader::ImageFileReader;
+
+/// Detects faces in each of `images`, in order, and scores the detections
+/// against the labels at `match_iou`.
+///
+/// Images go through the one `detector` as consecutive frames, so a
+/// `SkipFrameDetector` skips and extrapolates across them as it would in
+/// a video; a detector built with `OnnxYoloDetector::without_tracking`
+/// judges each image on its own. Only `detect` is timed, not decoding. `on_progress` gets the
+/// number of images done and the total after each image.
+pub fn run(
+    detector: &mut dyn FaceDetector,
+    images: &[AnnotatedImage],
+    match_iou: f64,
+    on_progress: Option<&dyn Fn(usize, usize)>,
+) -> Result<BenchmarkReport, Box<dyn std::error::Error>> {
+    let mut report = BenchmarkReport::new(match_iou);
+    for (index, image) in images.iter().enumerate() {
+        let mut reader = ImageFileReader::new();
+        reader
+            .open(&image.path)
+            .map_err(|e| format!("Could not open {}: {e}", image.path.display()))?;
+        let frame = reader
+            .frames()
+            .next()
+            .ok_or_else(|| format!("No image data in {}", image.path.display()))??;
+        let started = Instant::now();
+        let detected = detector.detect(&frame)?;
+        report.record(image, &detected, started.elapsed());
+        if let Some(on_progress) = on_progress {
+            on_progress(index + 1, images.len());
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::domain::detection_score::{DetectionScore, DEFAULT_MATCH_IOU};
+    use crate::shared::frame::Frame;
+    use crate::shared::region::Region;
+    use std::cell::Cell;
+    use std::path::{Path, PathBuf};
+
+    fn region(x: i32, y: i32, w: i32, h: i32) -> Region {
+        Region {
+            x,
+            y,
+            width: w,
+            height: h,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    /// Finds one face at the same place in every frame.
+    struct FixedDetector;
+
+    impl FaceDetector for FixedDetector {
+        fn detect(&mut self, _frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+            Ok(vec![region(10, 10, 20, 20)])
+        }
+    }
+
+    fn write_image(dir: &Path, name: &str) -> PathBuf {
+        let path = dir.join(name);
+        image::RgbImage::new(64, 48).save(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_scores_every_image() {
+        let dir = tempfile::tempdir().unwrap();
+        let images = [
+            AnnotatedImage {
+                path: write_image(dir.path(), "a.png"),
+                faces: vec![region(10, 10, 20, 20)],
+                ignored: vec![],
+            },
+            AnnotatedImage {
+                path: write_image(dir.path(), "b.png"),
+                faces: vec![region(40, 20, 10, 10)],
+                ignored: vec![],
+            },
+        ];
+        let done = Cell::new(0);
+
+        let report = run(
+            &mut FixedDetector,
+            &images,
+            DEFAULT_MATCH_IOU,
+            Some(&|current, _| done.set(current)),
+        )
+        .unwrap();
+
+        assert_eq!(report.images(), 2);
+        assert_eq!(
+            report.score(),
+            DetectionScore {
+                true_positives: 1,
+                false_positives: 1,
+                missed: 1
+            }
+        );
+        assert!(report.latency().is_some());
+        assert_eq!(done.get(), 2);
+    }
+
+    #[test]
+    fn test_unreadable_image_names_it() {
+        let images = [AnnotatedImage {
+            path: PathBuf::from("/nonexistent/face.jpg"),
+            faces: vec![],
+            ignored: vec![],
+        }];
+
+        let err = run(&mut FixedDetector, &images, DEFAULT_MATCH_IOU, None).unwrap_err();
+
+        assert!(err.to_string().contains("face.jpg"), "{err}");
+    }
+}
@@ -0,0 +1,2 @@
+pub mod detection_benchmark;
+pub mod widerface_dataset;
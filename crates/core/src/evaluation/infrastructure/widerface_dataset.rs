@@ -0,0 +1,253 @@
+//! Benchmark datasets labeled in the WIDER FACE ground-truth format, such
+//! as a subset of its validation split.
+//!
+//! A dataset is a folder with one `.txt` label file (e.g. WIDER FACE's
+//! `wider_face_val_bbx_gt.txt`) and the images it lists, under `images/`
+//! when that folder exists and next to the label file otherwise. Each
+//! image in the label file is its path, its face count, and a line per
+//! face: `x y w h blur expression illumination invalid occlusion pose`.
+//! Only the box and the `invalid` flag are read. An image with no faces
+//! still has one all-zero face line, which is skipped.
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::evaluation::domain::annotated_image::AnnotatedImage;
+use crate::shared::region::Region;
+
+const IMAGES_DIR: &str = "images";
+
+/// Position of the `invalid` flag on a face line.
+const INVALID_FIELD: usize = 7;
+
+#[derive(Error, Debug)]
+pub enum DatasetError {
+    #[error("Could not read dataset {path}: {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Dataset {path} has no .txt label file")]
+    NoLabels { path: PathBuf },
+    #[error("Dataset {path} has more than one .txt label file; keep only one")]
+    AmbiguousLabels { path: PathBuf },
+    #[error("{path}, line {line}: {message}")]
+    Invalid {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    #[error("Image {path} listed in the labels is missing")]
+    MissingImage { path: PathBuf },
+}
+
+/// Loads the labeled images of the dataset folder `dir`. Faces narrower or
+/// shorter than `min_face_size` pixels are ignored rather than judged, like
+/// those the labels flag invalid.
+pub fn load(dir: &Path, min_face_size: u32) -> Result<Vec<AnnotatedImage>, DatasetError> {
+    let labels = label_file(dir)?;
+    let text = fs::read_to_string(&labels).map_err(|source| DatasetError::Read {
+        path: labels.clone(),
+        source,
+    })?;
+    let image_root = match dir.join(IMAGES_DIR) {
+        images if images.is_dir() => images,
+        _ => dir.to_path_buf(),
+    };
+    let images = parse(&text, &image_root, min_face_size).map_err(|(line, message)| {
+        DatasetError::Invalid {
+            path: labels.clone(),
+            line,
+            message,
+        }
+    })?;
+    if let Some(missing) = images.iter().find(|image| !image.path.is_file()) {
+        return Err(DatasetError::MissingImage {
+            path: missing.path.clone(),
+        });
+    }
+    Ok(images)
+}
+
+fn label_file(dir: &Path) -> Result<PathBuf, DatasetError> {
+    let read_error = |source| DatasetError::Read {
+        path: dir.to_path_buf(),
+        source,
+    };
+    let mut labels = Vec::new();
+    for entry in fs::read_dir(dir).map_err(read_error)? {
+        let path = entry.map_err(read_error)?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "txt") {
+            labels.push(path);
+        }
+    }
+    match labels.len() {
+        0 => Err(DatasetError::NoLabels {
+            path: dir.to_path_buf(),
+        }),
+        1 => Ok(labels.remove(0)),
+        _ => Err(DatasetError::AmbiguousLabels {
+            path: dir.to_path_buf(),
+        }),
+    }
+}
+
+/// Parses label text; errors carry the 1-based line number.
+fn parse(
+    text: &str,
+    image_root: &Path,
+    min_face_size: u32,
+) -> Result<Vec<AnnotatedImage>, (usize, String)> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+    let mut images = Vec::new();
+    while let Some((name_line, name)) = lines.next() {
+        let (count_line, count) = lines
+            .next()
+            .ok_or_else(|| (name_line, format!("'{name}' has no face count")))?;
+        let count: usize = count
+            .parse()
+            .map_err(|_| (count_line, format!("'{count}' is not a face count")))?;
+        let mut image = AnnotatedImage {
+            path: image_root.join(name),
+            faces: Vec::new(),
+            ignored: Vec::new(),
+        };
+        // WIDER FACE writes one placeholder line for an image without faces.
+        for _ in 0..count.max(1) {
+            let (number, line) = lines
+                .next()
+                .ok_or_else(|| (count_line, format!("'{name}' has fewer than {count} faces")))?;
+            let fields: Vec<i64> = line
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .map_err(|_| (number, format!("'{line}' is not a face line")))?;
+            let [x, y, w, h, ..] = fields[..] else {
+                return Err((number, format!("'{line}' is not a face line")));
+            };
+            if count == 0 || w <= 0 || h <= 0 {
+                continue;
+            }
+            let region = Region {
+                x: x as i32,
+                y: y as i32,
+                width: w as i32,
+                height: h as i32,
+                track_id: None,
+                full_width: None,
+                full_height: None,
+                unclamped_x: None,
+                unclamped_y: None,
+            };
+            let invalid = fields.get(INVALID_FIELD).is_some_and(|flag| *flag != 0);
+            if invalid || w.min(h) < min_face_size as i64 {
+                image.ignored.push(region);
+            } else {
+                image.faces.push(region);
+            }
+        }
+        images.push(image);
+    }
+    Ok(images)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const LABELS: &str = "\
+0--Parade/a.jpg
+2
+449 330 122 149 0 0 0 0 0 0
+10 10 8 9 2 0 0 0 0 0
+1--Handshaking/b.jpg
+0
+0 0 0 0 0 0 0 0 0 0
+1--Handshaking/c.jpg
+1
+100 100 40 40 0 0 0 1 0 0
+";
+
+    #[test]
+    fn test_parse_reads_faces_per_image() {
+        let images = parse(LABELS, Path::new("/data"), 0).unwrap();
+
+        assert_eq!(images.len(), 3);
+        assert_eq!(images[0].path, Path::new("/data/0--Parade/a.jpg"));
+        assert_eq!(images[0].faces.len(), 2);
+        assert_eq!((images[0].faces[0].x, images[0].faces[0].width), (449, 122));
+        assert!(images[1].faces.is_empty() && images[1].ignored.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_and_small_faces_are_ignored() {
+        let images = parse(LABELS, Path::new("/data"), 10).unwrap();
+
+        assert_eq!(images[0].faces.len(), 1);
+        assert_eq!(images[0].ignored.len(), 1);
+        assert!(images[2].faces.is_empty());
+        assert_eq!(images[2].ignored.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_rejects_short_face_list() {
+        let err = parse("a.jpg\n2\n1 1 5 5\n", Path::new("."), 0).unwrap_err();
+        assert_eq!(err.0, 2);
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_count() {
+        let err = parse("a.jpg\ntwo\n", Path::new("."), 0).unwrap_err();
+        assert_eq!(err.0, 2);
+    }
+
+    #[test]
+    fn test_load_resolves_images_folder() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("images/0--Parade")).unwrap();
+        fs::write(dir.path().join("images/0--Parade/a.jpg"), b"").unwrap();
+        fs::write(
+            dir.path().join("labels.txt"),
+            "0--Parade/a.jpg\n1\n1 2 30 40 0 0 0 0 0 0\n",
+        )
+        .unwrap();
+
+        let images = load(dir.path(), 0).unwrap();
+
+        assert_eq!(images[0].path, dir.path().join("images/0--Parade/a.jpg"));
+        assert_eq!(images[0].faces.len(), 1);
+    }
+
+    #[test]
+    fn test_load_reports_missing_image() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("labels.txt"), "a.jpg\n0\n0 0 0 0\n").unwrap();
+
+        assert!(matches!(
+            load(dir.path(), 0),
+            Err(DatasetError::MissingImage { .. })
+        ));
+    }
+
+    #[test]
+    fn test_load_needs_one_label_file() {
+        let dir = TempDir::new().unwrap();
+        assert!(matches!(
+            load(dir.path(), 0),
+            Err(DatasetError::NoLabels { .. })
+        ));
+        fs::write(dir.path().join("a.txt"), "").unwrap();
+        fs::write(dir.path().join("b.txt"), "").unwrap();
+        assert!(matches!(
+            load(dir.path(), 0),
+            Err(DatasetError::AmbiguousLabels { .. })
+        ));
+    }
+}
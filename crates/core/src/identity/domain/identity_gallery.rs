@@ -0,0 +1,118 @@
+/// Similarity an enrolled reference must reach for a face to count as that
+/// person. Stricter than grouping's threshold, since a false match leaves a
+/// stranger's face unblurred.
+pub const DEFAULT_MATCH_THRESHOLD: f64 = 0.5;
+
+/// A named person with one embedding per enrolled reference photo.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Identity {
+    pub name: String,
+    pub embeddings: Vec<Vec<f32>>,
+}
+
+/// Enrolled identities, matched by the best cosine similarity between a
+/// face's embedding and any of a person's references.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IdentityGallery {
+    identities: Vec<Identity>,
+}
+
+impl IdentityGallery {
+    pub fn new(identities: Vec<Identity>) -> Self {
+        Self { identities }
+    }
+
+    pub fn identities(&self) -> &[Identity] {
+        &self.identities
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.identities.is_empty()
+    }
+
+    /// Adds reference embeddings to `name`, creating the identity if needed.
+    pub fn enroll(&mut self, name: &str, embeddings: Vec<Vec<f32>>) {
+        match self.identities.iter_mut().find(|i| i.name == name) {
+            Some(identity) => identity.embeddings.extend(embeddings),
+            None => self.identities.push(Identity {
+                name: name.to_string(),
+                embeddings,
+            }),
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.identities.iter().any(|i| i.name == name)
+    }
+
+    /// Removes `name`; returns whether it was enrolled.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.identities.len();
+        self.identities.retain(|i| i.name != name);
+        self.identities.len() != before
+    }
+
+    /// The enrolled identity most similar to `embedding` and its
+    /// similarity, if that reaches `threshold`.
+    pub fn best_match(&self, embedding: &[f32], threshold: f64) -> Option<(&str, f64)> {
+        self.identities
+            .iter()
+            .flat_map(|identity| {
+                identity.embeddings.iter().map(move |reference| {
+                    (identity.name.as_str(), similarity(embedding, reference))
+                })
+            })
+            .filter(|&(_, score)| score >= threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+/// Dot product of L2-normalized vectors equals cosine similarity.
+fn similarity(a: &[f32], b: &[f32]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| *x as f64 * *y as f64).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gallery() -> IdentityGallery {
+        let mut gallery = IdentityGallery::default();
+        gallery.enroll("alice", vec![vec![1.0, 0.0, 0.0]]);
+        gallery.enroll("bob", vec![vec![0.0, 1.0, 0.0]]);
+        gallery.enroll("alice", vec![vec![0.6, 0.0, 0.8]]);
+        gallery
+    }
+
+    #[test]
+    fn test_enroll_appends_to_existing_identity() {
+        let gallery = gallery();
+        assert_eq!(gallery.identities().len(), 2);
+        assert_eq!(gallery.identities()[0].embeddings.len(), 2);
+    }
+
+    #[test]
+    fn test_best_match_uses_closest_reference() {
+        let (name, score) = gallery().best_match(&[0.0, 0.0, 1.0], 0.5).unwrap();
+        assert_eq!(name, "alice");
+        approx::assert_abs_diff_eq!(score, 0.8, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_no_match_below_threshold() {
+        assert_eq!(gallery().best_match(&[0.0, 0.6, 0.8], 0.7), None);
+        assert!(IdentityGallery::default()
+            .best_match(&[1.0, 0.0, 0.0], 0.0)
+            .is_none());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut gallery = gallery();
+        assert!(gallery.remove("bob"));
+        assert!(!gallery.remove("bob"));
+        assert!(!gallery.contains("bob"));
+        assert!(gallery.contains("alice"));
+        assert_eq!(gallery.identities().len(), 1);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod identity_gallery;
+pub mod track_identities;
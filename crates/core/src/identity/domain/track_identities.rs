@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use crate::detection::domain::face_embedder::FaceEmbedder;
+use crate::identity::domain::identity_gallery::{IdentityGallery, DEFAULT_MATCH_THRESHOLD};
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// Frames between two identity checks of the same track. A track keeps
+/// the last check's result in between.
+pub const DEFAULT_RECHECK_FRAMES: usize = 15;
+
+/// Matches detected faces against a gallery, remembering the result per
+/// track.
+///
+/// Each region is cropped and embedded, then compared with the gallery.
+/// Tracked regions are checked when their track first appears and again
+/// every `recheck` frames, so a track that turns out to be someone else
+/// (or an ID the tracker hands to a new face) is noticed within that
+/// window. Regions without a track ID are checked on every frame. A face
+/// that can't be cropped matches nobody.
+pub struct TrackIdentities {
+    embedder: Box<dyn FaceEmbedder>,
+    gallery: IdentityGallery,
+    threshold: f64,
+    recheck: usize,
+    /// Per track: frame of the last check and the identity it matched.
+    checks: HashMap<u32, (usize, Option<String>)>,
+}
+
+impl TrackIdentities {
+    pub fn new(embedder: Box<dyn FaceEmbedder>, gallery: IdentityGallery) -> Self {
+        Self {
+            embedder,
+            gallery,
+            threshold: DEFAULT_MATCH_THRESHOLD,
+            recheck: DEFAULT_RECHECK_FRAMES,
+            checks: HashMap::new(),
+        }
+    }
+
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Frames between checks of a track; at least 1 (every frame).
+    pub fn with_recheck(mut self, frames: usize) -> Self {
+        self.recheck = frames.max(1);
+        self
+    }
+
+    /// The enrolled identity `region` shows, if any.
+    pub fn identify(
+        &mut self,
+        frame: &Frame,
+        region: &Region,
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let index = frame.index();
+        if let Some(&(checked, ref name)) = region.track_id.and_then(|id| self.checks.get(&id)) {
+            if (checked..checked + self.recheck).contains(&index) {
+                return Ok(name.clone());
+            }
+        }
+        let name = self.best_match(frame, region)?;
+        if let Some(id) = region.track_id {
+            let previous = self.checks.insert(id, (index, name.clone()));
+            let was = previous.and_then(|(_, name)| name);
+            match (&was, &name) {
+                (None, Some(name)) => log::info!("Track {id} matches enrolled {name}"),
+                (Some(was), None) => log::info!("Track {id} no longer matches enrolled {was}"),
+                (Some(was), Some(name)) if was != name => {
+                    log::info!("Track {id} now matches enrolled {name} instead of {was}")
+                }
+                _ => {}
+            }
+        }
+        Ok(name)
+    }
+
+    fn best_match(
+        &self,
+        frame: &Frame,
+        region: &Region,
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let crop = frame.square_crop(region);
+        if crop.width() == 0 || crop.height() == 0 {
+            return Ok(None);
+        }
+        let embedding = self
+            .embedder
+            .embed(crop.data(), crop.width(), crop.height())?;
+        Ok(self
+            .gallery
+            .best_match(&embedding, self.threshold)
+            .map(|(name, _)| name.to_string()))
+    }
+}
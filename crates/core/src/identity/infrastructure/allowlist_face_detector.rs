@@ -0,0 +1,155 @@
+use crate::detection::domain::face_detector::{DetectorState, FaceDetector};
+use crate::detection::domain::face_embedder::FaceEmbedder;
+use crate::identity::domain::identity_gallery::IdentityGallery;
+use crate::identity::domain::track_identities::TrackIdentities;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// Decorator that drops faces matching an enrolled identity, so everyone
+/// else is blurred.
+///
+/// Matching is done per track (see [`TrackIdentities`]), so a track that
+/// turns out to be someone else is blurred again within the recheck
+/// window. A face that can't be cropped is kept, and so blurred.
+pub struct AllowlistFaceDetector {
+    inner: Box<dyn FaceDetector>,
+    identities: TrackIdentities,
+}
+
+impl AllowlistFaceDetector {
+    pub fn new(
+        inner: Box<dyn FaceDetector>,
+        embedder: Box<dyn FaceEmbedder>,
+        gallery: IdentityGallery,
+    ) -> Self {
+        Self {
+            inner,
+            identities: TrackIdentities::new(embedder, gallery),
+        }
+    }
+
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.identities = self.identities.with_threshold(threshold);
+        self
+    }
+
+    /// Frames between checks of a track; at least 1 (every frame).
+    pub fn with_recheck(mut self, frames: usize) -> Self {
+        self.identities = self.identities.with_recheck(frames);
+        self
+    }
+}
+
+impl FaceDetector for AllowlistFaceDetector {
+    fn detect(&mut self, frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+        let regions = self.inner.detect(frame)?;
+        let mut kept = Vec::with_capacity(regions.len());
+        for region in regions {
+            if self.identities.identify(frame, &region)?.is_none() {
+                kept.push(region);
+            }
+        }
+        Ok(kept)
+    }
+
+    fn save_state(&self) -> Result<DetectorState, Box<dyn std::error::Error>> {
+        self.inner.save_state()
+    }
+
+    fn restore_state(&mut self, state: &DetectorState) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.restore_state(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Two faces per frame: track 1 on the left, track 2 on the right.
+    struct TwoFaces;
+
+    impl FaceDetector for TwoFaces {
+        fn detect(&mut self, _frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+            Ok([0, 10]
+                .into_iter()
+                .enumerate()
+                .map(|(i, x)| Region {
+                    x,
+                    y: 0,
+                    width: 10,
+                    height: 10,
+                    track_id: Some(i as u32 + 1),
+                    full_width: None,
+                    full_height: None,
+                    unclamped_x: None,
+                    unclamped_y: None,
+                })
+                .collect())
+        }
+    }
+
+    /// Embeds a crop by whether it shows any red, counting calls.
+    struct RedEmbedder(Arc<Mutex<usize>>);
+
+    impl FaceEmbedder for RedEmbedder {
+        fn embed(
+            &self,
+            rgb_data: &[u8],
+            _width: u32,
+            _height: u32,
+        ) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+            *self.0.lock().unwrap() += 1;
+            let red = rgb_data.iter().step_by(3).any(|&r| r > 128);
+            Ok(if red { vec![1.0, 0.0] } else { vec![0.0, 1.0] })
+        }
+    }
+
+    /// Left half red, right half black.
+    fn frame(index: usize) -> Frame {
+        let mut data = vec![0u8; 20 * 10 * 3];
+        for y in 0..10 {
+            for x in 0..10 {
+                data[(y * 20 + x) * 3] = 255;
+            }
+        }
+        Frame::new(data, 20, 10, 3, index)
+    }
+
+    fn detector(calls: Arc<Mutex<usize>>) -> AllowlistFaceDetector {
+        let mut gallery = IdentityGallery::default();
+        gallery.enroll("alice", vec![vec![1.0, 0.0]]);
+        AllowlistFaceDetector::new(Box::new(TwoFaces), Box::new(RedEmbedder(calls)), gallery)
+    }
+
+    #[test]
+    fn test_enrolled_face_is_dropped() {
+        let mut detector = detector(Arc::default());
+        let regions = detector.detect(&frame(0)).unwrap();
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].track_id, Some(2));
+    }
+
+    #[test]
+    fn test_tracks_are_rechecked_on_interval() {
+        let calls = Arc::new(Mutex::new(0));
+        let mut detector = detector(calls.clone()).with_recheck(3);
+
+        for i in 0..6 {
+            detector.detect(&frame(i)).unwrap();
+        }
+
+        // Both tracks checked on frames 0 and 3.
+        assert_eq!(*calls.lock().unwrap(), 4);
+    }
+
+    #[test]
+    fn test_empty_gallery_keeps_everyone() {
+        let mut detector = AllowlistFaceDetector::new(
+            Box::new(TwoFaces),
+            Box::new(RedEmbedder(Arc::default())),
+            IdentityGallery::default(),
+        );
+        assert_eq!(detector.detect(&frame(0)).unwrap().len(), 2);
+    }
+}
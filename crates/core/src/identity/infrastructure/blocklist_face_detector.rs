@@ -0,0 +1,193 @@
+use std::collections::HashSet;
+
+use crate::detection::domain::face_detector::{DetectorState, FaceDetector};
+use crate::detection::domain::face_embedder::FaceEmbedder;
+use crate::identity::domain::identity_gallery::IdentityGallery;
+use crate::identity::domain::track_identities::TrackIdentities;
+use crate::shared::forced_tracks::ForcedTracks;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// Decorator that marks the tracks of blocked people as always blurred.
+///
+/// Regions pass through unchanged; a track whose face matches one of the
+/// `blocked` identities (see [`TrackIdentities`]) goes into
+/// [`ForcedTracks`], which the pipeline blurs whatever the track selection
+/// or time rules say. Faces are matched against the whole gallery, so a
+/// face closer to someone else enrolled is not taken for a blocked person.
+///
+/// Forcing is sticky: a track is never released, even if a later check
+/// no longer matches, since leaving a blocked person visible is the worse
+/// mistake. Regions without a track ID can't be forced.
+pub struct BlocklistFaceDetector {
+    inner: Box<dyn FaceDetector>,
+    identities: TrackIdentities,
+    blocked: HashSet<String>,
+    forced: ForcedTracks,
+}
+
+impl BlocklistFaceDetector {
+    pub fn new(
+        inner: Box<dyn FaceDetector>,
+        embedder: Box<dyn FaceEmbedder>,
+        gallery: IdentityGallery,
+        blocked: impl IntoIterator<Item = String>,
+    ) -> Self {
+        Self {
+            inner,
+            identities: TrackIdentities::new(embedder, gallery),
+            blocked: blocked.into_iter().collect(),
+            forced: ForcedTracks::new(),
+        }
+    }
+
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.identities = self.identities.with_threshold(threshold);
+        self
+    }
+
+    /// Frames between checks of a track; at least 1 (every frame).
+    pub fn with_recheck(mut self, frames: usize) -> Self {
+        self.identities = self.identities.with_recheck(frames);
+        self
+    }
+
+    /// The set this detector fills, to hand to the pipeline.
+    pub fn forced_tracks(&self) -> ForcedTracks {
+        self.forced.clone()
+    }
+}
+
+impl FaceDetector for BlocklistFaceDetector {
+    fn detect(&mut self, frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+        let regions = self.inner.detect(frame)?;
+        for region in &regions {
+            let Some(id) = region.track_id else { continue };
+            if self.forced.contains(id) {
+                continue;
+            }
+            if let Some(name) = self.identities.identify(frame, region)? {
+                if self.blocked.contains(&name) && self.forced.insert(id) {
+                    log::info!("Track {id} is blocked {name}; always blurring it");
+                }
+            }
+        }
+        Ok(regions)
+    }
+
+    fn save_state(&self) -> Result<DetectorState, Box<dyn std::error::Error>> {
+        Ok(serde_json::json!({
+            "forced": self.forced.ids(),
+            "inner": self.inner.save_state()?,
+        }))
+    }
+
+    /// Tracks forced before the interruption stay forced, even if the
+    /// person is not recognized again.
+    fn restore_state(&mut self, state: &DetectorState) -> Result<(), Box<dyn std::error::Error>> {
+        let forced = state["forced"]
+            .as_array()
+            .ok_or("The resume checkpoint has an invalid detector state")?;
+        for id in forced.iter().filter_map(|id| id.as_u64()) {
+            self.forced.insert(id as u32);
+        }
+        self.inner.restore_state(&state["inner"])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Two faces per frame: track 1 on the left, track 2 on the right.
+    struct TwoFaces;
+
+    impl FaceDetector for TwoFaces {
+        fn detect(&mut self, _frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+            Ok([0, 10]
+                .into_iter()
+                .enumerate()
+                .map(|(i, x)| Region {
+                    x,
+                    y: 0,
+                    width: 10,
+                    height: 10,
+                    track_id: Some(i as u32 + 1),
+                    full_width: None,
+                    full_height: None,
+                    unclamped_x: None,
+                    unclamped_y: None,
+                })
+                .collect())
+        }
+    }
+
+    /// Embeds a crop by whether it shows any red, counting calls.
+    struct RedEmbedder(Arc<Mutex<usize>>);
+
+    impl FaceEmbedder for RedEmbedder {
+        fn embed(
+            &self,
+            rgb_data: &[u8],
+            _width: u32,
+            _height: u32,
+        ) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+            *self.0.lock().unwrap() += 1;
+            let red = rgb_data.iter().step_by(3).any(|&r| r > 128);
+            Ok(if red { vec![1.0, 0.0] } else { vec![0.0, 1.0] })
+        }
+    }
+
+    /// Left half red, right half black.
+    fn frame(index: usize) -> Frame {
+        let mut data = vec![0u8; 20 * 10 * 3];
+        for y in 0..10 {
+            for x in 0..10 {
+                data[(y * 20 + x) * 3] = 255;
+            }
+        }
+        Frame::new(data, 20, 10, 3, index)
+    }
+
+    fn gallery() -> IdentityGallery {
+        let mut gallery = IdentityGallery::default();
+        gallery.enroll("alice", vec![vec![1.0, 0.0]]);
+        gallery.enroll("bob", vec![vec![0.0, 1.0]]);
+        gallery
+    }
+
+    #[test]
+    fn test_blocked_track_is_forced_and_regions_pass_through() {
+        let mut detector = BlocklistFaceDetector::new(
+            Box::new(TwoFaces),
+            Box::new(RedEmbedder(Arc::default())),
+            gallery(),
+            ["alice".to_string()],
+        );
+        let forced = detector.forced_tracks();
+
+        assert_eq!(detector.detect(&frame(0)).unwrap().len(), 2);
+        assert!(forced.contains(1));
+        assert!(!forced.contains(2));
+    }
+
+    #[test]
+    fn test_forced_tracks_are_not_checked_again() {
+        let calls = Arc::new(Mutex::new(0));
+        let mut detector = BlocklistFaceDetector::new(
+            Box::new(TwoFaces),
+            Box::new(RedEmbedder(calls.clone())),
+            gallery(),
+            ["alice".to_string()],
+        )
+        .with_recheck(1);
+
+        for i in 0..3 {
+            detector.detect(&frame(i)).unwrap();
+        }
+
+        // Track 1 once, track 2 on every frame.
+        assert_eq!(*calls.lock().unwrap(), 4);
+    }
+}
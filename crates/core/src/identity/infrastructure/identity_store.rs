@@ -0,0 +1,187 @@
+//! Persistent identity allowlist: enrolled people and their reference
+//! embeddings, shared by every job on this machine.
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::identity::domain::identity_gallery::{Identity, IdentityGallery};
+use crate::shared::schema::{Schema, SchemaError};
+
+pub const IDENTITIES_FILE_NAME: &str = "identities.json";
+
+const IDENTITIES_SCHEMA: Schema = Schema::new("Identities", 1, &[]);
+
+#[derive(Error, Debug)]
+pub enum IdentityStoreError {
+    #[error("Could not read identities from {path}: {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Could not write identities to {path}: {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Identities file {path} is not valid: {message}")]
+    Invalid { path: PathBuf, message: String },
+    #[error(transparent)]
+    Schema(#[from] SchemaError),
+    #[error("Identities in {path} were enrolled with {found}, not {expected}; enroll them again")]
+    ModelMismatch {
+        path: PathBuf,
+        found: String,
+        expected: String,
+    },
+}
+
+/// `identities.json` under the platform data folder, where enrollments
+/// outlive caches.
+pub fn default_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("FaceGuard").join(IDENTITIES_FILE_NAME))
+}
+
+/// Loads the gallery at `path`, enrolled with embedding model `model`.
+/// A missing file is an empty gallery. Embeddings from another model are
+/// not comparable, so a file written with one is an error.
+pub fn load(path: &Path, model: &str) -> Result<IdentityGallery, IdentityStoreError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(IdentityGallery::default()),
+        Err(source) => {
+            return Err(IdentityStoreError::Read {
+                path: path.to_path_buf(),
+                source,
+            })
+        }
+    };
+    let invalid = |message: String| IdentityStoreError::Invalid {
+        path: path.to_path_buf(),
+        message,
+    };
+    let document: Value = serde_json::from_str(&text).map_err(|e| invalid(e.to_string()))?;
+    let document = IDENTITIES_SCHEMA.upgrade(document)?;
+    let found = document["model"].as_str().unwrap_or_default();
+    if found != model {
+        return Err(IdentityStoreError::ModelMismatch {
+            path: path.to_path_buf(),
+            found: found.to_string(),
+            expected: model.to_string(),
+        });
+    }
+    let entries = document["identities"]
+        .as_array()
+        .ok_or_else(|| invalid("no identities list".to_string()))?;
+    let mut identities = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let name = entry["name"]
+            .as_str()
+            .ok_or_else(|| invalid("an identity has no name".to_string()))?;
+        let embeddings = entry["embeddings"]
+            .as_array()
+            .ok_or_else(|| invalid(format!("{name} has no embeddings")))?
+            .iter()
+            .map(|e| {
+                e.as_array()
+                    .map(|v| {
+                        v.iter()
+                            .filter_map(Value::as_f64)
+                            .map(|x| x as f32)
+                            .collect()
+                    })
+                    .ok_or_else(|| invalid(format!("{name} has a malformed embedding")))
+            })
+            .collect::<Result<_, _>>()?;
+        identities.push(Identity {
+            name: name.to_string(),
+            embeddings,
+        });
+    }
+    Ok(IdentityGallery::new(identities))
+}
+
+/// Writes `gallery` to `path` (temp file + rename, so a crash never leaves
+/// half a file), creating the folder if needed.
+pub fn save(path: &Path, model: &str, gallery: &IdentityGallery) -> Result<(), IdentityStoreError> {
+    let write_error = |source| IdentityStoreError::Write {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(write_error)?;
+    }
+    let identities: Vec<Value> = gallery
+        .identities()
+        .iter()
+        .map(|i| json!({ "name": i.name, "embeddings": i.embeddings }))
+        .collect();
+    let document = json!({
+        "version": IDENTITIES_SCHEMA.version(),
+        "model": model,
+        "identities": identities,
+    });
+    let text = serde_json::to_string(&document).map_err(|e| write_error(e.into()))?;
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, text).map_err(write_error)?;
+    fs::rename(&temp, path).map_err(write_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: &str = "w600k_r50.onnx";
+
+    #[test]
+    fn test_missing_file_is_empty_gallery() {
+        let dir = tempfile::tempdir().unwrap();
+        let gallery = load(&dir.path().join(IDENTITIES_FILE_NAME), MODEL).unwrap();
+        assert!(gallery.is_empty());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join(IDENTITIES_FILE_NAME);
+        let mut gallery = IdentityGallery::default();
+        gallery.enroll("alice", vec![vec![0.6, 0.8], vec![1.0, 0.0]]);
+        gallery.enroll("bob", vec![vec![0.0, 1.0]]);
+
+        save(&path, MODEL, &gallery).unwrap();
+
+        assert_eq!(load(&path, MODEL).unwrap(), gallery);
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+
+    #[test]
+    fn test_other_model_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(IDENTITIES_FILE_NAME);
+        save(&path, "other.onnx", &IdentityGallery::default()).unwrap();
+
+        assert!(matches!(
+            load(&path, MODEL),
+            Err(IdentityStoreError::ModelMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(IDENTITIES_FILE_NAME);
+        fs::write(
+            &path,
+            r#"{"version": 99, "model": "w600k_r50.onnx", "identities": []}"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            load(&path, MODEL),
+            Err(IdentityStoreError::Schema(SchemaError::Newer { .. }))
+        ));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod allowlist_face_detector;
+pub mod blocklist_face_detector;
+pub mod identity_store;
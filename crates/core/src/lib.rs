@@ -1,6 +1,9 @@
 pub mod audio;
 pub mod blurring;
 pub mod detection;
+pub mod evaluation;
+pub mod identity;
 pub mod pipeline;
+pub mod restoration;
 pub mod shared;
 pub mod video;
@@ -0,0 +1,450 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use argon2::{Algorithm, Argon2, Params, Version};
+use hmac::{Hmac, Mac};
+use serde_json::{json, Map, Value};
+use sha2::Sha256;
+use thiserror::Error;
+
+use crate::pipeline::report_inspection::spans;
+use crate::shared::constants::{MAX_M_COST_KIB, MAX_P_COST, MAX_T_COST};
+use crate::shared::region::Region;
+use crate::shared::schema::{Schema, SchemaError};
+use crate::shared::track_time_rules::TimeRange;
+
+/// Bumped when a field is renamed or removed, so reviewers' tools can tell
+/// ledger layouts apart.
+pub const LEDGER_VERSION: u32 = 1;
+
+pub const LEDGER_SCHEMA: Schema = Schema::new("Anonymization ledger", LEDGER_VERSION, &[]);
+
+/// Names the signing construction in the ledger, so it can change later
+/// without old signatures being misread.
+const SIGNATURE_SCHEME: &str = "argon2id-hmac-sha256";
+const SALT_LEN: usize = 16;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Error)]
+pub enum LedgerError {
+    #[error("Ledger is not valid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Schema(#[from] SchemaError),
+    #[error("Ledger passphrase must not be empty")]
+    EmptyPassphrase,
+    #[error("Ledger is not signed")]
+    Unsigned,
+    #[error("Ledger signature does not match: wrong passphrase, or the ledger was modified")]
+    BadSignature,
+    #[error("Malformed ledger: {0}")]
+    Malformed(String),
+    #[error("Could not derive a key from the passphrase: {0}")]
+    KeyDerivation(String),
+}
+
+/// One identity track and what was decided about it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LedgerTrack {
+    pub track_id: u32,
+    /// Name the operator gave the track; empty when unlabelled.
+    pub label: String,
+    pub blurred: bool,
+    /// Where the track appears, in seconds from the start.
+    pub appearances: Vec<TimeRange>,
+}
+
+/// Record of every anonymization decision in an export, for legal and
+/// compliance review without watching the footage.
+///
+/// Built from the detection scan and the job's settings. Written as JSON,
+/// optionally signed with a passphrase: the key is derived with Argon2id
+/// and a random salt, and an HMAC-SHA256 of the rest of the document is
+/// stored with the salt. Anyone holding the passphrase can then check that
+/// no decision was edited after export. The HMAC is a symmetric MAC, not a
+/// public-key signature, so anyone who can check it can also forge it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnonymizationLedger {
+    pub app_version: String,
+    /// Who ran the export; empty when not given.
+    pub operator: String,
+    /// Seconds since the Unix epoch.
+    pub created_at: u64,
+    pub source: String,
+    pub output: String,
+    /// Settings that shaped the decisions, such as the blur shape and
+    /// detection confidence, by name.
+    pub settings: BTreeMap<String, String>,
+    /// In track ID order.
+    pub tracks: Vec<LedgerTrack>,
+}
+
+impl AnonymizationLedger {
+    /// An empty ledger for exporting `source` to `output`, stamped with this
+    /// build's version and the current time.
+    pub fn new(source: &Path, output: &Path, operator: &str) -> Self {
+        Self {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            operator: operator.to_string(),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            source: source.display().to_string(),
+            output: output.display().to_string(),
+            settings: BTreeMap::new(),
+            tracks: Vec::new(),
+        }
+    }
+
+    pub fn with_setting(mut self, name: &str, value: impl ToString) -> Self {
+        self.settings.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Lists every track in `detections`, with its label from `labels` and
+    /// whether it is in `blurred`. Appearances bridge gaps of up to
+    /// `SPAN_GAP_FRAMES`, and each runs to the end of its last frame.
+    pub fn with_tracks(
+        mut self,
+        detections: &HashMap<usize, Vec<Region>>,
+        fps: f64,
+        labels: &HashMap<u32, String>,
+        blurred: &HashSet<u32>,
+    ) -> Self {
+        let mut frames: BTreeMap<u32, BTreeSet<usize>> = BTreeMap::new();
+        for (&frame, regions) in detections {
+            for id in regions.iter().filter_map(|r| r.track_id) {
+                frames.entry(id).or_default().insert(frame);
+            }
+        }
+        let seconds = |frame: usize| if fps > 0.0 { frame as f64 / fps } else { 0.0 };
+        self.tracks = frames
+            .into_iter()
+            .map(|(track_id, frames)| {
+                let frames: Vec<usize> = frames.into_iter().collect();
+                LedgerTrack {
+                    track_id,
+                    label: labels.get(&track_id).cloned().unwrap_or_default(),
+                    blurred: blurred.contains(&track_id),
+                    appearances: spans(&frames)
+                        .into_iter()
+                        .map(|span| TimeRange::new(seconds(span.start), seconds(span.end + 1)))
+                        .collect(),
+                }
+            })
+            .collect();
+        self
+    }
+
+    /// The ledger as pretty JSON, signed when `passphrase` is given.
+    pub fn to_json(&self, passphrase: Option<&str>) -> Result<String, LedgerError> {
+        let mut document = self.document();
+        if let Some(passphrase) = passphrase {
+            let mut salt = [0u8; SALT_LEN];
+            OsRng.fill_bytes(&mut salt);
+            let params = Params::default();
+            let tag = mac(&document, passphrase, &params, &salt)?
+                .finalize()
+                .into_bytes();
+            document.insert(
+                "signature".into(),
+                json!({
+                    "scheme": SIGNATURE_SCHEME,
+                    "m_cost": params.m_cost(),
+                    "t_cost": params.t_cost(),
+                    "p_cost": params.p_cost(),
+                    "salt": to_hex(&salt),
+                    "tag": to_hex(&tag),
+                }),
+            );
+        }
+        Ok(serde_json::to_string_pretty(&Value::Object(document))? + "\n")
+    }
+
+    /// Reads a ledger written by [`to_json`](Self::to_json), ignoring any
+    /// signature.
+    pub fn from_json(json: &str) -> Result<Self, LedgerError> {
+        let document = LEDGER_SCHEMA.upgrade(serde_json::from_str(json)?)?;
+        Self::from_document(&document)
+    }
+
+    /// Reads a signed ledger, failing unless it was signed with
+    /// `passphrase` and is unchanged since.
+    pub fn verify(json: &str, passphrase: &str) -> Result<Self, LedgerError> {
+        let Value::Object(mut document) = serde_json::from_str(json)? else {
+            return Err(LedgerError::Malformed("not a JSON object".into()));
+        };
+        let signature = document.remove("signature").ok_or(LedgerError::Unsigned)?;
+        if signature["scheme"].as_str() != Some(SIGNATURE_SCHEME) {
+            return Err(LedgerError::Malformed("unknown signature scheme".into()));
+        }
+        let cost = |name: &str, max: u32| {
+            let value = signature[name]
+                .as_u64()
+                .ok_or_else(|| LedgerError::Malformed(format!("signature has no `{name}`")))?;
+            u32::try_from(value)
+                .ok()
+                .filter(|&v| v <= max)
+                .ok_or_else(|| {
+                    LedgerError::Malformed(format!("signature `{name}` {value} is out of range"))
+                })
+        };
+        let params = Params::new(
+            cost("m_cost", MAX_M_COST_KIB)?,
+            cost("t_cost", MAX_T_COST)?,
+            cost("p_cost", MAX_P_COST)?,
+            None,
+        )
+        .map_err(|e| LedgerError::KeyDerivation(e.to_string()))?;
+        let hex = |name: &str| {
+            signature[name]
+                .as_str()
+                .and_then(from_hex)
+                .ok_or_else(|| LedgerError::Malformed(format!("signature `{name}` is not hex")))
+        };
+        let salt = hex("salt")?;
+        mac(&document, passphrase, &params, &salt)?
+            .verify_slice(&hex("tag")?)
+            .map_err(|_| LedgerError::BadSignature)?;
+        Self::from_document(&LEDGER_SCHEMA.upgrade(Value::Object(document))?)
+    }
+
+    fn document(&self) -> Map<String, Value> {
+        let tracks: Vec<Value> = self
+            .tracks
+            .iter()
+            .map(|track| {
+                json!({
+                    "track_id": track.track_id,
+                    "label": track.label,
+                    "blurred": track.blurred,
+                    "appearances": track
+                        .appearances
+                        .iter()
+                        .map(|range| json!({"start": range.start, "end": range.end}))
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        let Value::Object(document) = json!({
+            "version": LEDGER_VERSION,
+            "app_version": self.app_version,
+            "operator": self.operator,
+            "created_at": self.created_at,
+            "source": self.source,
+            "output": self.output,
+            "settings": self.settings,
+            "tracks": tracks,
+        }) else {
+            unreachable!("a JSON object literal is an object");
+        };
+        document
+    }
+
+    fn from_document(document: &Value) -> Result<Self, LedgerError> {
+        let text = |value: &Value| value.as_str().unwrap_or_default().to_string();
+        let tracks = document["tracks"]
+            .as_array()
+            .ok_or_else(|| LedgerError::Malformed("`tracks` is not a list".into()))?
+            .iter()
+            .map(|track| {
+                let track_id = track["track_id"]
+                    .as_u64()
+                    .ok_or_else(|| LedgerError::Malformed("track without `track_id`".into()))?;
+                let appearances = track["appearances"]
+                    .as_array()
+                    .map(Vec::as_slice)
+                    .unwrap_or(&[])
+                    .iter()
+                    .map(|range| {
+                        TimeRange::new(
+                            range["start"].as_f64().unwrap_or_default(),
+                            range["end"].as_f64().unwrap_or_default(),
+                        )
+                    })
+                    .collect();
+                Ok(LedgerTrack {
+                    track_id: track_id as u32,
+                    label: text(&track["label"]),
+                    blurred: track["blurred"].as_bool().unwrap_or_default(),
+                    appearances,
+                })
+            })
+            .collect::<Result<_, LedgerError>>()?;
+        let settings = document["settings"]
+            .as_object()
+            .map(|settings| {
+                settings
+                    .iter()
+                    .map(|(name, value)| (name.clone(), text(value)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            app_version: text(&document["app_version"]),
+            operator: text(&document["operator"]),
+            created_at: document["created_at"].as_u64().unwrap_or_default(),
+            source: text(&document["source"]),
+            output: text(&document["output"]),
+            settings,
+            tracks,
+        })
+    }
+}
+
+/// HMAC-SHA256 of the compact JSON of `document`, keyed with the Argon2id
+/// hash of `passphrase`. `verify_slice` on it compares in constant time.
+fn mac(
+    document: &Map<String, Value>,
+    passphrase: &str,
+    params: &Params,
+    salt: &[u8],
+) -> Result<HmacSha256, LedgerError> {
+    if passphrase.is_empty() {
+        return Err(LedgerError::EmptyPassphrase);
+    }
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params.clone())
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| LedgerError::KeyDerivation(e.to_string()))?;
+    let mut mac =
+        HmacSha256::new_from_slice(&key).map_err(|e| LedgerError::KeyDerivation(e.to_string()))?;
+    mac.update(&serde_json::to_vec(document)?);
+    Ok(mac)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn region(track_id: u32) -> Region {
+        Region {
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 10,
+            track_id: Some(track_id),
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    fn ledger() -> AnonymizationLedger {
+        let mut detections = HashMap::new();
+        for frame in [0, 1, 2, 3, 40, 41] {
+            detections.insert(frame, vec![region(2)]);
+        }
+        detections.insert(10, vec![region(1), region(2)]);
+        let labels = HashMap::from([(2, "Presenter".to_string())]);
+        let blurred = HashSet::from([1]);
+        AnonymizationLedger::new(Path::new("in.mp4"), Path::new("out.mp4"), "J. Smith")
+            .with_setting("blur_shape", "ellipse")
+            .with_tracks(&detections, 10.0, &labels, &blurred)
+    }
+
+    #[test]
+    fn test_tracks_list_decisions_and_appearances() {
+        let ledger = ledger();
+
+        assert_eq!(ledger.tracks.len(), 2);
+        assert_eq!(ledger.tracks[0].track_id, 1);
+        assert!(ledger.tracks[0].blurred);
+        assert_eq!(ledger.tracks[0].appearances, vec![TimeRange::new(1.0, 1.1)]);
+        let presenter = &ledger.tracks[1];
+        assert_eq!(presenter.label, "Presenter");
+        assert!(!presenter.blurred);
+        assert_eq!(
+            presenter.appearances,
+            vec![
+                TimeRange::new(0.0, 0.4),
+                TimeRange::new(1.0, 1.1),
+                TimeRange::new(4.0, 4.2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unsigned_round_trip() {
+        let ledger = ledger();
+        let json = ledger.to_json(None).unwrap();
+
+        assert_eq!(AnonymizationLedger::from_json(&json).unwrap(), ledger);
+        assert!(matches!(
+            AnonymizationLedger::verify(&json, "secret"),
+            Err(LedgerError::Unsigned)
+        ));
+    }
+
+    #[test]
+    fn test_signed_ledger_verifies() {
+        let ledger = ledger();
+        let json = ledger.to_json(Some("secret")).unwrap();
+
+        assert_eq!(
+            AnonymizationLedger::verify(&json, "secret").unwrap(),
+            ledger
+        );
+        assert!(matches!(
+            AnonymizationLedger::verify(&json, "wrong"),
+            Err(LedgerError::BadSignature)
+        ));
+    }
+
+    #[test]
+    fn test_edited_ledger_fails_verification() {
+        let json = ledger().to_json(Some("secret")).unwrap();
+        let edited = json.replace("\"blurred\": false", "\"blurred\": true");
+        assert_ne!(edited, json);
+
+        assert!(matches!(
+            AnonymizationLedger::verify(&edited, "secret"),
+            Err(LedgerError::BadSignature)
+        ));
+    }
+
+    #[rstest]
+    #[case("m_cost", u64::from(MAX_M_COST_KIB) + 1)]
+    #[case("t_cost", u64::from(MAX_T_COST) + 1)]
+    #[case("p_cost", u64::from(MAX_P_COST) + 1)]
+    #[case("m_cost", u64::from(u32::MAX) + 1)]
+    fn test_out_of_range_cost_is_malformed(#[case] name: &str, #[case] value: u64) {
+        let json = ledger().to_json(Some("secret")).unwrap();
+        let mut document: Value = serde_json::from_str(&json).unwrap();
+        document["signature"][name] = json!(value);
+
+        assert!(matches!(
+            AnonymizationLedger::verify(&document.to_string(), "secret"),
+            Err(LedgerError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        let bytes = [0u8, 15, 16, 255];
+        assert_eq!(to_hex(&bytes), "000f10ff");
+        assert_eq!(from_hex("000f10ff").unwrap(), bytes);
+        assert!(from_hex("abc").is_none());
+    }
+}
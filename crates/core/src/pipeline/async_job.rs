@@ -0,0 +1,283 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::shared::cancellation::{CancelReason, CancellationToken, Cancelled};
+use crate::shared::job_error::{JobError, JobErrorKind};
+
+/// Progress events queued before new ones are dropped. Progress is only a
+/// hint, so a slow consumer must never hold up the job.
+const EVENT_CAPACITY: usize = 64;
+
+/// The progress callback the use cases take: called with the current and
+/// total frame, returning `false` to stop.
+pub type ProgressCallback = Box<dyn Fn(usize, usize) -> bool + Send>;
+
+/// Something that happened while an [`AsyncJob`] ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobEvent {
+    Progress { current: usize, total: usize },
+}
+
+/// How an [`AsyncJob`] failed, with the parts a server needs to answer
+/// with: whether it was cancelled and which end of the job broke.
+#[derive(Error, Debug)]
+pub enum AsyncJobError {
+    #[error(transparent)]
+    Cancelled(#[from] Cancelled),
+    #[error("{message}")]
+    Failed {
+        kind: Option<JobErrorKind>,
+        message: String,
+    },
+    #[error("The job stopped unexpectedly")]
+    Panicked,
+}
+
+impl AsyncJobError {
+    fn from_job(error: &(dyn std::error::Error + 'static)) -> Self {
+        match Cancelled::reason_of(error) {
+            Some(reason) => Self::Cancelled(Cancelled(reason)),
+            None => Self::Failed {
+                kind: JobError::kind_of(error),
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+/// Progress of an [`AsyncJob`] as a stream of [`JobEvent`]s, which ends
+/// when the job does.
+pub struct JobEvents {
+    rx: mpsc::Receiver<JobEvent>,
+}
+
+impl JobEvents {
+    /// The next event, or `None` once the job has finished.
+    pub async fn next(&mut self) -> Option<JobEvent> {
+        self.rx.recv().await
+    }
+}
+
+impl futures_core::Stream for JobEvents {
+    type Item = JobEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<JobEvent>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// A blocking job running on the tokio blocking pool, awaited for its
+/// result.
+///
+/// Dropping it cancels the job, so a request handler that gives up on a
+/// job (a client disconnecting, a `select!` timing out) stops the work
+/// too. Use [`detach`](Self::detach) to let a job finish unobserved.
+pub struct AsyncJob<T> {
+    handle: JoinHandle<Result<T, AsyncJobError>>,
+    cancelled: CancellationToken,
+    events: Option<JobEvents>,
+    detached: bool,
+}
+
+impl<T> AsyncJob<T> {
+    /// The job's progress events. Only the first call gets them.
+    pub fn events(&mut self) -> Option<JobEvents> {
+        self.events.take()
+    }
+
+    /// Stops the job; awaiting it then gives [`AsyncJobError::Cancelled`]
+    /// unless it had already finished.
+    pub fn cancel(&self, reason: CancelReason) {
+        self.cancelled.cancel(reason);
+    }
+
+    /// Lets the job run to the end without being awaited.
+    pub fn detach(mut self) {
+        self.detached = true;
+    }
+}
+
+impl<T> Future for AsyncJob<T> {
+    type Output = Result<T, AsyncJobError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.handle)
+            .poll(cx)
+            .map(|joined| joined.unwrap_or(Err(AsyncJobError::Panicked)))
+    }
+}
+
+impl<T> Drop for AsyncJob<T> {
+    fn drop(&mut self) {
+        if !self.detached {
+            self.cancelled.cancel(CancelReason::Superseded);
+        }
+    }
+}
+
+/// Runs `job` on the tokio blocking pool instead of a thread of its own,
+/// for servers and queue workers that drive many jobs from async code.
+///
+/// `job` builds and runs a use case as the CLI does, handing it the
+/// progress callback and cancellation token it is given. The token is a
+/// child of `cancelled`, when given, so one token can stop many jobs.
+/// Must be called from within a tokio runtime.
+pub fn process_async<T, F>(job: F, cancelled: Option<&CancellationToken>) -> AsyncJob<T>
+where
+    T: Send + 'static,
+    F: FnOnce(ProgressCallback, CancellationToken) -> Result<T, Box<dyn std::error::Error>>
+        + Send
+        + 'static,
+{
+    let token = cancelled.map_or_else(CancellationToken::new, CancellationToken::child);
+    let (tx, rx) = mpsc::channel(EVENT_CAPACITY);
+
+    let job_token = token.clone();
+    let handle = tokio::task::spawn_blocking(move || {
+        let progress_token = job_token.clone();
+        let on_progress: ProgressCallback = Box::new(move |current, total| {
+            // A full queue drops the event; a closed one has no listener.
+            let _ = tx.try_send(JobEvent::Progress { current, total });
+            !progress_token.is_cancelled()
+        });
+        job(on_progress, job_token.clone()).map_err(|e| match job_token.reason() {
+            // Use cases stopped by their callback report the user as the
+            // reason; the token knows the real one.
+            Some(reason) if Cancelled::reason_of(&*e).is_some() => {
+                AsyncJobError::Cancelled(Cancelled(reason))
+            }
+            _ => AsyncJobError::from_job(&*e),
+        })
+    });
+
+    AsyncJob {
+        handle,
+        cancelled: token,
+        events: Some(JobEvents { rx }),
+        detached: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc as std_mpsc;
+    use std::time::Duration;
+
+    /// Reports progress until cancelled, then stops like a use case.
+    fn run_until_cancelled(
+        on_progress: ProgressCallback,
+        cancelled: CancellationToken,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for frame in 0.. {
+            cancelled.check()?;
+            if !on_progress(frame, 0) {
+                return Err(Box::new(Cancelled(CancelReason::User)));
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolves_with_job_result_and_streams_progress() {
+        let mut job = process_async(
+            |on_progress, _| {
+                for frame in 1..=3 {
+                    on_progress(frame, 3);
+                }
+                Ok(42)
+            },
+            None,
+        );
+        let mut events = job.events().unwrap();
+
+        assert_eq!(job.await.unwrap(), 42);
+        let mut seen = Vec::new();
+        while let Some(event) = events.next().await {
+            seen.push(event);
+        }
+        assert_eq!(
+            seen.last(),
+            Some(&JobEvent::Progress {
+                current: 3,
+                total: 3
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_events_are_taken_once() {
+        let mut job = process_async(|_, _| Ok(()), None);
+        assert!(job.events().is_some());
+        assert!(job.events().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stops_job_with_reason() {
+        let job = process_async(run_until_cancelled, None);
+
+        job.cancel(CancelReason::Shutdown);
+
+        match job.await {
+            Err(AsyncJobError::Cancelled(Cancelled(reason))) => {
+                assert_eq!(reason, CancelReason::Shutdown)
+            }
+            other => panic!("expected cancellation, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parent_token_cancels_job() {
+        let parent = CancellationToken::new();
+        let job = process_async(run_until_cancelled, Some(&parent));
+
+        parent.cancel(CancelReason::User);
+
+        assert!(matches!(job.await, Err(AsyncJobError::Cancelled(_))));
+    }
+
+    #[tokio::test]
+    async fn test_dropping_job_cancels_it() {
+        let (done_tx, done_rx) = std_mpsc::channel();
+        let job = process_async(
+            move |on_progress, cancelled| {
+                let result = run_until_cancelled(on_progress, cancelled);
+                done_tx.send(result.is_err()).unwrap();
+                Ok(())
+            },
+            None,
+        );
+
+        drop(job);
+
+        let stopped = tokio::task::spawn_blocking(move || done_rx.recv().unwrap())
+            .await
+            .unwrap();
+        assert!(stopped);
+    }
+
+    #[tokio::test]
+    async fn test_failure_keeps_job_error_kind() {
+        let job = process_async::<(), _>(|_, _| Err(JobError::input("No such file").into()), None);
+
+        match job.await {
+            Err(AsyncJobError::Failed { kind, message }) => {
+                assert_eq!(kind, Some(JobErrorKind::Input));
+                assert_eq!(message, "No such file");
+            }
+            other => panic!("expected failure, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_panic_is_reported() {
+        let job = process_async::<(), _>(|_, _| panic!("boom"), None);
+        assert!(matches!(job.await, Err(AsyncJobError::Panicked)));
+    }
+}
@@ -1,16 +1,19 @@
 use std::collections::HashSet;
-use std::path::Path;
-use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
 
 use crate::blurring::domain::frame_blurrer::FrameBlurrer;
 use crate::detection::domain::face_detector::FaceDetector;
 use crate::detection::domain::region_merger::RegionMerger;
+use crate::shared::cancellation::CancellationToken;
+use crate::shared::forced_tracks::ForcedTracks;
+use crate::shared::track_time_rules::TrackTimeRules;
 use crate::shared::video_metadata::VideoMetadata;
+use crate::shared::warnings::Warnings;
+use crate::video::domain::decode_error_policy::DecodeErrorPolicy;
 use crate::video::domain::video_reader::VideoReader;
 use crate::video::domain::video_writer::VideoWriter;
 
-use super::pipeline_executor::{PipelineConfig, PipelineExecutor};
+use super::pipeline_executor::{PipelineConfig, PipelineExecutor, ReferenceOutput};
 
 const DEFAULT_LOOKAHEAD: usize = 5;
 
@@ -29,8 +32,15 @@ pub struct BlurFacesUseCase {
     lookahead: usize,
     blur_ids: Option<HashSet<u32>>,
     exclude_ids: Option<HashSet<u32>>,
+    time_rules: TrackTimeRules,
+    forced_tracks: ForcedTracks,
+    min_track_frames: usize,
+    reference: Option<ReferenceOutput>,
+    decode_errors: DecodeErrorPolicy,
+    reuse_unchanged_frames: bool,
+    warnings: Warnings,
     on_progress: Option<Box<dyn Fn(usize, usize) -> bool + Send>>,
-    cancelled: Arc<AtomicBool>,
+    cancelled: CancellationToken,
 }
 
 impl BlurFacesUseCase {
@@ -46,7 +56,7 @@ impl BlurFacesUseCase {
         blur_ids: Option<HashSet<u32>>,
         exclude_ids: Option<HashSet<u32>>,
         on_progress: Option<Box<dyn Fn(usize, usize) -> bool + Send>>,
-        cancelled: Option<Arc<AtomicBool>>,
+        cancelled: Option<CancellationToken>,
     ) -> Self {
         Self {
             reader: Some(reader),
@@ -58,11 +68,71 @@ impl BlurFacesUseCase {
             lookahead: lookahead.unwrap_or(DEFAULT_LOOKAHEAD),
             blur_ids,
             exclude_ids,
+            time_rules: TrackTimeRules::new(),
+            forced_tracks: ForcedTracks::new(),
+            min_track_frames: 0,
+            reference: None,
+            decode_errors: DecodeErrorPolicy::default(),
+            reuse_unchanged_frames: false,
+            warnings: Warnings::new(),
             on_progress,
-            cancelled: cancelled.unwrap_or_else(|| Arc::new(AtomicBool::new(false))),
+            cancelled: cancelled.unwrap_or_default(),
         }
     }
 
+    /// Restricts when individual tracks are blurred (see [`TrackTimeRules`]).
+    pub fn with_time_rules(mut self, rules: TrackTimeRules) -> Self {
+        self.time_rules = rules;
+        self
+    }
+
+    /// Blurs the tracks in `forced` even where the ID sets or time rules
+    /// would leave them visible. The set may still be filling while the
+    /// pipeline runs (see
+    /// [`BlocklistFaceDetector`](crate::identity::infrastructure::blocklist_face_detector::BlocklistFaceDetector)).
+    pub fn with_forced_tracks(mut self, forced: ForcedTracks) -> Self {
+        self.forced_tracks = forced;
+        self
+    }
+
+    /// Leaves tracks seen on fewer than `frames` frames unblurred, as likely
+    /// false positives. Off by default; decided within the lookahead window
+    /// (see [`TrackDurationFilter`]).
+    ///
+    /// [`TrackDurationFilter`]: crate::detection::domain::track_duration_filter::TrackDurationFilter
+    pub fn with_min_track_frames(mut self, frames: usize) -> Self {
+        self.min_track_frames = frames;
+        self
+    }
+
+    /// Also writes the unblurred frames to `path`, producing a reference
+    /// copy that stays frame-aligned with the blurred output.
+    pub fn with_reference_output(mut self, writer: Box<dyn VideoWriter>, path: PathBuf) -> Self {
+        self.reference = Some(ReferenceOutput { writer, path });
+        self
+    }
+
+    /// How frames the reader fails to produce are handled. Tolerated
+    /// errors are counted and reported to the warnings collector.
+    pub fn with_decode_error_policy(mut self, policy: DecodeErrorPolicy) -> Self {
+        self.decode_errors = policy;
+        self
+    }
+
+    /// Reuses the previous output frame whenever a frame is pixel-identical
+    /// to its predecessor outside the blurred regions, skipping detection
+    /// and blurring. Pays off for slideshows and screen recordings, where
+    /// most frames repeat; costs a frame comparison otherwise.
+    pub fn with_reuse_unchanged_frames(mut self, reuse: bool) -> Self {
+        self.reuse_unchanged_frames = reuse;
+        self
+    }
+
+    pub fn with_warnings(mut self, warnings: Warnings) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
     pub fn execute(
         &mut self,
         metadata: &VideoMetadata,
@@ -72,6 +142,13 @@ impl BlurFacesUseCase {
             lookahead: self.lookahead,
             blur_ids: self.blur_ids.take(),
             exclude_ids: self.exclude_ids.take(),
+            time_rules: std::mem::take(&mut self.time_rules),
+            forced_tracks: self.forced_tracks.clone(),
+            min_track_frames: self.min_track_frames,
+            reference: self.reference.take(),
+            decode_errors: self.decode_errors,
+            reuse_unchanged_frames: self.reuse_unchanged_frames,
+            warnings: self.warnings.clone(),
             on_progress: self.on_progress.take(),
             cancelled: self.cancelled.clone(),
         };
@@ -94,12 +171,13 @@ mod tests {
     use super::*;
     use crate::detection::domain::region_merger::RegionMerger;
     use crate::pipeline::infrastructure::threaded_pipeline_executor::ThreadedPipelineExecutor;
+    use crate::shared::cancellation::{CancelReason, Cancelled};
+    use crate::shared::color_space::ColorSpace;
     use crate::shared::frame::Frame;
     use crate::shared::region::Region;
     use crate::shared::video_metadata::VideoMetadata;
     use std::collections::HashMap;
-    use std::sync::atomic::Ordering;
-    use std::sync::Mutex;
+    use std::sync::{Arc, Mutex};
 
     // --- Stubs ---
 
@@ -133,6 +211,37 @@ mod tests {
         }
     }
 
+    /// Yields good frames with read errors at `errors_at` positions in the
+    /// stream; good frames keep sequential indices, as a real reader would.
+    struct FlakyReader {
+        frames: usize,
+        errors_at: Vec<usize>,
+    }
+
+    impl VideoReader for FlakyReader {
+        fn open(&mut self, _path: &Path) -> Result<VideoMetadata, Box<dyn std::error::Error>> {
+            Ok(metadata(100, 100))
+        }
+
+        fn frames(
+            &mut self,
+        ) -> Box<dyn Iterator<Item = Result<Frame, Box<dyn std::error::Error>>> + '_> {
+            let total = self.frames + self.errors_at.len();
+            let mut next_index = 0;
+            let errors_at = self.errors_at.clone();
+            Box::new((0..total).map(move |pos| {
+                if errors_at.contains(&pos) {
+                    Err("corrupt packet".into())
+                } else {
+                    next_index += 1;
+                    Ok(make_frame(next_index - 1))
+                }
+            }))
+        }
+
+        fn close(&mut self) {}
+    }
+
     struct StubWriter {
         written: Arc<Mutex<Vec<Frame>>>,
         closed: Arc<Mutex<bool>>,
@@ -208,6 +317,19 @@ mod tests {
         }
     }
 
+    struct ZeroingBlurrer;
+
+    impl FrameBlurrer for ZeroingBlurrer {
+        fn blur(
+            &self,
+            frame: &mut Frame,
+            _regions: &[Region],
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            frame.data_mut().fill(0);
+            Ok(())
+        }
+    }
+
     struct FailingDetector;
 
     impl FaceDetector for FailingDetector {
@@ -235,6 +357,7 @@ mod tests {
             codec: String::new(),
             source_path: None,
             rotation: 0,
+            color: ColorSpace::default(),
         }
     }
 
@@ -261,6 +384,7 @@ mod tests {
             codec: String::new(),
             source_path: None,
             rotation: 0,
+            color: ColorSpace::default(),
         }
     }
 
@@ -555,6 +679,147 @@ mod tests {
         assert_eq!(calls[0].1[0].track_id, Some(1));
     }
 
+    #[test]
+    fn test_min_track_frames_skips_brief_tracks() {
+        let blurrer = PassthroughBlurrer::new();
+        let calls = blurrer.calls.clone();
+
+        // Track 1 is on frames 0-5, track 2 only on frame 2.
+        let mut det_results: HashMap<usize, Vec<Region>> = (0..6)
+            .map(|i| (i, vec![region_at(40, 40, Some(1))]))
+            .collect();
+        det_results
+            .get_mut(&2)
+            .unwrap()
+            .push(region_at(45, 45, Some(2)));
+
+        let mut uc = BlurFacesUseCase::new(
+            Box::new(StubReader::new(make_frames(8))),
+            Box::new(StubWriter::new()),
+            Box::new(StubDetector {
+                results: det_results,
+            }),
+            Box::new(blurrer),
+            RegionMerger::new(),
+            default_executor(),
+            Some(3),
+            None,
+            None,
+            None,
+            None,
+        )
+        .with_min_track_frames(3);
+
+        uc.execute(&meta_with_count(8), Path::new("/tmp/out.mp4"))
+            .unwrap();
+
+        let calls = calls.lock().unwrap();
+        let frames_with = |track: u32| -> Vec<usize> {
+            calls
+                .iter()
+                .filter(|(_, regions)| regions.iter().any(|r| r.track_id == Some(track)))
+                .map(|(index, _)| *index)
+                .collect()
+        };
+        assert_eq!(frames_with(1), vec![0, 1, 2, 3, 4, 5]);
+        assert!(frames_with(2).is_empty());
+    }
+
+    #[test]
+    fn test_time_rules_limit_track_to_range() {
+        use crate::shared::track_time_rules::{TimeRange, TrackTimeRules};
+
+        let blurrer = PassthroughBlurrer::new();
+        let calls = blurrer.calls.clone();
+
+        let det_results: HashMap<usize, Vec<Region>> = (0..4)
+            .map(|i| {
+                (
+                    i,
+                    vec![region_at(10, 10, Some(1)), region_at(50, 50, Some(2))],
+                )
+            })
+            .collect();
+        let mut rules = TrackTimeRules::new();
+        // At 30 fps this covers frames 2 and 3 only.
+        rules.blur_during(1, TimeRange::new(0.05, 0.12));
+
+        let mut uc = BlurFacesUseCase::new(
+            Box::new(StubReader::new(make_frames(4))),
+            Box::new(StubWriter::new()),
+            Box::new(StubDetector {
+                results: det_results,
+            }),
+            Box::new(blurrer),
+            RegionMerger::new(),
+            default_executor(),
+            Some(0),
+            None,
+            None,
+            None,
+            None,
+        )
+        .with_time_rules(rules);
+
+        uc.execute(&meta_with_count(4), Path::new("/tmp/out.mp4"))
+            .unwrap();
+
+        let calls = calls.lock().unwrap();
+        let track_1_frames: Vec<usize> = calls
+            .iter()
+            .filter(|(_, regions)| regions.iter().any(|r| r.track_id == Some(1)))
+            .map(|(index, _)| *index)
+            .collect();
+        assert_eq!(track_1_frames, vec![2, 3]);
+        assert!(calls
+            .iter()
+            .all(|(_, regions)| regions.iter().any(|r| r.track_id == Some(2))));
+    }
+
+    #[test]
+    fn test_forced_tracks_override_exclusion() {
+        let blurrer = PassthroughBlurrer::new();
+        let calls = blurrer.calls.clone();
+
+        let det_results: HashMap<usize, Vec<Region>> = (0..2)
+            .map(|i| {
+                (
+                    i,
+                    vec![region_at(10, 10, Some(1)), region_at(50, 50, Some(2))],
+                )
+            })
+            .collect();
+        let forced = ForcedTracks::new();
+        forced.insert(1);
+
+        let mut uc = BlurFacesUseCase::new(
+            Box::new(StubReader::new(make_frames(2))),
+            Box::new(StubWriter::new()),
+            Box::new(StubDetector {
+                results: det_results,
+            }),
+            Box::new(blurrer),
+            RegionMerger::new(),
+            default_executor(),
+            Some(0),
+            None,
+            Some(HashSet::from([1, 2])),
+            None,
+            None,
+        )
+        .with_forced_tracks(forced);
+
+        uc.execute(&meta_with_count(2), Path::new("/tmp/out.mp4"))
+            .unwrap();
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.len(), 2);
+        for (_, regions) in calls.iter() {
+            let ids: Vec<_> = regions.iter().map(|r| r.track_id).collect();
+            assert_eq!(ids, vec![Some(1)]);
+        }
+    }
+
     #[test]
     fn test_cancel_via_on_progress() {
         let mut uc = BlurFacesUseCase::new(
@@ -573,8 +838,10 @@ mod tests {
             None,
         );
 
-        let result = uc.execute(&meta_with_count(10), Path::new("/tmp/out.mp4"));
-        assert!(result.is_err());
+        let error = uc
+            .execute(&meta_with_count(10), Path::new("/tmp/out.mp4"))
+            .unwrap_err();
+        assert_eq!(Cancelled::reason_of(&*error), Some(CancelReason::User));
     }
 
     #[test]
@@ -612,8 +879,8 @@ mod tests {
     }
 
     #[test]
-    fn test_cancellation_via_atomic_bool() {
-        let cancelled = Arc::new(AtomicBool::new(false));
+    fn test_cancellation_via_token() {
+        let cancelled = CancellationToken::new();
         let cancelled_clone = cancelled.clone();
 
         let writer = StubWriter::new();
@@ -639,16 +906,18 @@ mod tests {
                 let mut c = count_clone.lock().unwrap();
                 *c += 1;
                 if *c >= 3 {
-                    cancelled_clone.store(true, Ordering::Relaxed);
+                    cancelled_clone.cancel(CancelReason::User);
                 }
                 true
             })),
             Some(cancelled),
         );
 
-        uc.execute(&meta_with_count(10), Path::new("/tmp/out.mp4"))
-            .unwrap();
+        let error = uc
+            .execute(&meta_with_count(10), Path::new("/tmp/out.mp4"))
+            .unwrap_err();
 
+        assert_eq!(Cancelled::reason_of(&*error), Some(CancelReason::User));
         // Should have stopped early
         assert!(written.lock().unwrap().len() < 10);
     }
@@ -677,4 +946,205 @@ mod tests {
         let result = uc.execute(&meta_with_count(3), Path::new("/tmp/out.mp4"));
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_reference_output_gets_unblurred_frames() {
+        let writer = StubWriter::new();
+        let written = writer.written.clone();
+        let reference = StubWriter::new();
+        let reference_written = reference.written.clone();
+        let reference_closed = reference.closed.clone();
+
+        let mut uc = BlurFacesUseCase::new(
+            Box::new(StubReader::new(make_frames(6))),
+            Box::new(writer),
+            Box::new(StubDetector {
+                results: HashMap::new(),
+            }),
+            Box::new(ZeroingBlurrer),
+            RegionMerger::new(),
+            default_executor(),
+            Some(2),
+            None,
+            None,
+            None,
+            None,
+        )
+        .with_reference_output(Box::new(reference), PathBuf::from("/tmp/ref.mp4"));
+
+        uc.execute(&meta_with_count(6), Path::new("/tmp/out.mp4"))
+            .unwrap();
+
+        let written = written.lock().unwrap();
+        let reference_written = reference_written.lock().unwrap();
+        assert_eq!(written.len(), 6);
+        assert_eq!(reference_written.len(), 6);
+        for (blurred, original) in written.iter().zip(reference_written.iter()) {
+            assert_eq!(blurred.index(), original.index());
+            assert!(blurred.data().iter().all(|&b| b == 0));
+            assert!(original.data().iter().all(|&b| b == 128));
+        }
+        assert!(*reference_closed.lock().unwrap());
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn run_flaky(
+        policy: DecodeErrorPolicy,
+        errors_at: Vec<usize>,
+    ) -> (Result<(), Box<dyn std::error::Error>>, Vec<usize>, Warnings) {
+        let writer = StubWriter::new();
+        let written = writer.written.clone();
+        let warnings = Warnings::new();
+        let mut uc = BlurFacesUseCase::new(
+            Box::new(FlakyReader {
+                frames: 4,
+                errors_at,
+            }),
+            Box::new(writer),
+            Box::new(StubDetector {
+                results: HashMap::new(),
+            }),
+            Box::new(PassthroughBlurrer::new()),
+            RegionMerger::new(),
+            default_executor(),
+            Some(1),
+            None,
+            None,
+            None,
+            None,
+        )
+        .with_decode_error_policy(policy)
+        .with_warnings(warnings.clone());
+
+        let result = uc.execute(&meta_with_count(4), Path::new("/tmp/out.mp4"));
+        let indices = written.lock().unwrap().iter().map(|f| f.index()).collect();
+        (result, indices, warnings)
+    }
+
+    #[test]
+    fn test_fail_policy_aborts_on_read_error() {
+        use crate::shared::job_error::{JobError, JobErrorKind};
+
+        let (result, _, warnings) = run_flaky(DecodeErrorPolicy::Fail, vec![2]);
+        let error = result.unwrap_err();
+        assert_eq!(JobError::kind_of(&*error), Some(JobErrorKind::Input));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_skip_policy_drops_bad_frames() {
+        let (result, indices, warnings) = run_flaky(DecodeErrorPolicy::SkipFrame, vec![2]);
+        assert!(result.is_ok());
+        assert_eq!(indices, vec![0, 1, 2, 3]);
+        let reported = warnings.snapshot();
+        assert_eq!(reported.len(), 1);
+        assert!(reported[0].message.starts_with("1 frame(s)"));
+    }
+
+    #[test]
+    fn test_duplicate_policy_keeps_timing() {
+        let (result, indices, warnings) =
+            run_flaky(DecodeErrorPolicy::DuplicatePrevious, vec![2, 3]);
+        assert!(result.is_ok());
+        assert_eq!(indices, vec![0, 1, 2, 3, 4, 5]);
+        assert!(warnings.snapshot()[0].message.starts_with("2 frame(s)"));
+    }
+
+    #[test]
+    fn test_duplicate_policy_skips_leading_errors() {
+        let (result, indices, _) = run_flaky(DecodeErrorPolicy::DuplicatePrevious, vec![0]);
+        assert!(result.is_ok());
+        assert_eq!(indices, vec![0, 1, 2, 3]);
+    }
+
+    // --- Unchanged frame reuse ---
+
+    /// Always finds the same face and counts how often it is asked.
+    struct CountingDetector {
+        calls: Arc<Mutex<usize>>,
+    }
+
+    impl FaceDetector for CountingDetector {
+        fn detect(&mut self, _frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+            *self.calls.lock().unwrap() += 1;
+            Ok(vec![region_at(10, 10, Some(1))])
+        }
+    }
+
+    /// Copies of frame 0 with one pixel changed from `changed_from` on.
+    fn frames_changing_at(count: usize, changed_from: usize, x: usize, y: usize) -> Vec<Frame> {
+        (0..count)
+            .map(|i| {
+                let mut frame = make_frame(i);
+                if i >= changed_from {
+                    frame.data_mut()[(y * 100 + x) * 3] = 0;
+                }
+                frame
+            })
+            .collect()
+    }
+
+    /// Returns (detect calls, blurred frame indices, written frame indices).
+    fn run_reuse(frames: Vec<Frame>, reuse: bool) -> (usize, Vec<usize>, Vec<usize>) {
+        let count = frames.len();
+        let detect_calls = Arc::new(Mutex::new(0));
+        let writer = StubWriter::new();
+        let written = writer.written.clone();
+        let blurrer = PassthroughBlurrer::new();
+        let blur_calls = blurrer.calls.clone();
+
+        let mut uc = BlurFacesUseCase::new(
+            Box::new(StubReader::new(frames)),
+            Box::new(writer),
+            Box::new(CountingDetector {
+                calls: detect_calls.clone(),
+            }),
+            Box::new(blurrer),
+            RegionMerger::new(),
+            default_executor(),
+            Some(2),
+            None,
+            None,
+            None,
+            None,
+        )
+        .with_reuse_unchanged_frames(reuse);
+
+        uc.execute(&meta_with_count(count), Path::new("/tmp/out.mp4"))
+            .unwrap();
+        let detected = *detect_calls.lock().unwrap();
+        let blurred = blur_calls.lock().unwrap().iter().map(|(i, _)| *i).collect();
+        let written = written.lock().unwrap().iter().map(|f| f.index()).collect();
+        (detected, blurred, written)
+    }
+
+    #[test]
+    fn test_reuse_skips_identical_frames() {
+        let (detected, blurred, written) = run_reuse(make_frames(5), true);
+        assert_eq!(detected, 1);
+        assert_eq!(blurred, vec![0]);
+        assert_eq!(written, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_reuse_ignores_changes_inside_regions() {
+        let (detected, blurred, _) = run_reuse(frames_changing_at(5, 2, 15, 15), true);
+        assert_eq!(detected, 1);
+        assert_eq!(blurred, vec![0]);
+    }
+
+    #[test]
+    fn test_reuse_reprocesses_changes_outside_regions() {
+        let (detected, blurred, written) = run_reuse(frames_changing_at(5, 2, 90, 90), true);
+        assert_eq!(detected, 2);
+        assert_eq!(blurred, vec![0, 2]);
+        assert_eq!(written, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_reuse_is_off_by_default() {
+        let (detected, blurred, _) = run_reuse(make_frames(5), false);
+        assert_eq!(detected, 5);
+        assert_eq!(blurred, vec![0, 1, 2, 3, 4]);
+    }
 }
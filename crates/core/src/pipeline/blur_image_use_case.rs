@@ -3,6 +3,8 @@ use std::path::Path;
 
 use crate::blurring::domain::frame_blurrer::FrameBlurrer;
 use crate::detection::domain::face_detector::FaceDetector;
+use crate::shared::forced_tracks::ForcedTracks;
+use crate::shared::job_error::JobError;
 use crate::shared::region::Region;
 use crate::video::domain::image_writer::ImageWriter;
 use crate::video::domain::video_reader::VideoReader;
@@ -15,6 +17,7 @@ pub struct BlurImageUseCase {
     blurrer: Box<dyn FrameBlurrer>,
     blur_ids: Option<HashSet<u32>>,
     exclude_ids: Option<HashSet<u32>>,
+    forced_tracks: ForcedTracks,
 }
 
 impl BlurImageUseCase {
@@ -33,24 +36,42 @@ impl BlurImageUseCase {
             blurrer,
             blur_ids,
             exclude_ids,
+            forced_tracks: ForcedTracks::new(),
         }
     }
 
+    /// Blurs the tracks in `forced` even if the ID sets leave them out.
+    pub fn with_forced_tracks(mut self, forced: ForcedTracks) -> Self {
+        self.forced_tracks = forced;
+        self
+    }
+
     pub fn execute(
         &mut self,
         input_path: &Path,
         output_path: &Path,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let _metadata = self.reader.open(input_path)?;
-
-        let mut frame = self.reader.frames().next().ok_or("No frames in image")??;
+        let _metadata = self
+            .reader
+            .open(input_path)
+            .map_err(|e| JobError::input(e.to_string()))?;
+
+        let mut frame = self
+            .reader
+            .frames()
+            .next()
+            .ok_or_else(|| JobError::input("No frames in image"))?
+            .map_err(|e| JobError::input(e.to_string()))?;
         self.reader.close();
 
         let regions = self.detector.detect(&frame)?;
         let filtered = Region::filter(&regions, self.blur_ids.as_ref(), self.exclude_ids.as_ref());
+        let filtered = self.forced_tracks.restore(&regions, filtered);
 
         self.blurrer.blur(&mut frame, &filtered)?;
-        self.image_writer.write(output_path, &frame, None)?;
+        self.image_writer
+            .write(output_path, &frame, None)
+            .map_err(|e| JobError::output(e.to_string()))?;
 
         Ok(())
     }
@@ -59,6 +80,7 @@ impl BlurImageUseCase {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shared::color_space::ColorSpace;
     use crate::shared::frame::Frame;
     use crate::shared::region::Region;
     use crate::shared::video_metadata::VideoMetadata;
@@ -86,6 +108,7 @@ mod tests {
                 codec: String::new(),
                 source_path: None,
                 rotation: 0,
+                color: ColorSpace::default(),
             })
         }
 
@@ -0,0 +1,164 @@
+/// Named bundle of the detection and blur knobs that trade speed against
+/// protection, so users pick one option instead of tuning eight.
+///
+/// `Balanced` is the frontends' defaults. Every value can still be
+/// overridden on its own after a preset is applied.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BlurPreset {
+    /// Detects every 4th frame with a short lookahead and a lighter blur.
+    /// For quick drafts of footage with few, slow-moving faces.
+    Fast,
+    #[default]
+    Balanced,
+    /// Detects every frame at a lower threshold, keeps even one-frame
+    /// tracks, blurs wider and harder, and checks the output for leaks.
+    /// Several times slower than `Balanced`.
+    MaximumProtection,
+}
+
+/// The values a [`BlurPreset`] sets.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PresetSettings {
+    /// Run detection every Nth frame (1 = every frame).
+    pub detection_interval: usize,
+    /// Frames buffered ahead so a face is blurred before it is detected.
+    pub lookahead: usize,
+    /// Gaussian kernel size (odd).
+    pub blur_strength: usize,
+    /// How far the blur extends beyond the face (0.0-1.0).
+    pub padding: f64,
+    /// Growth of regions while detection is uncertain (0.0-1.0).
+    pub uncertainty_growth: f64,
+    /// Detection confidence threshold (0.0-1.0).
+    pub confidence: f64,
+    /// Shortest track kept; shorter ones are treated as false positives.
+    pub min_track_frames: usize,
+    /// Re-run detection on a sample of blurred frames to catch leaks.
+    pub verify_blur: bool,
+}
+
+impl BlurPreset {
+    pub const ALL: [BlurPreset; 3] = [Self::Fast, Self::Balanced, Self::MaximumProtection];
+
+    /// Parses `fast`, `balanced`, or `maximum` (also `max` and
+    /// `maximum-protection`).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "fast" => Some(Self::Fast),
+            "balanced" => Some(Self::Balanced),
+            "maximum" | "max" | "maximum-protection" => Some(Self::MaximumProtection),
+            _ => None,
+        }
+    }
+
+    /// Name accepted by [`BlurPreset::parse`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Fast => "fast",
+            Self::Balanced => "balanced",
+            Self::MaximumProtection => "maximum",
+        }
+    }
+
+    /// Display name for menus and messages.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Fast => "Fast",
+            Self::Balanced => "Balanced",
+            Self::MaximumProtection => "Maximum protection",
+        }
+    }
+
+    pub fn settings(&self) -> PresetSettings {
+        match self {
+            Self::Fast => PresetSettings {
+                detection_interval: 4,
+                lookahead: 5,
+                blur_strength: 151,
+                padding: 0.4,
+                uncertainty_growth: 0.25,
+                confidence: 0.5,
+                min_track_frames: 3,
+                verify_blur: false,
+            },
+            Self::Balanced => PresetSettings {
+                detection_interval: 2,
+                lookahead: 10,
+                blur_strength: 201,
+                padding: 0.4,
+                uncertainty_growth: 0.25,
+                confidence: 0.5,
+                min_track_frames: 3,
+                verify_blur: false,
+            },
+            Self::MaximumProtection => PresetSettings {
+                detection_interval: 1,
+                lookahead: 30,
+                blur_strength: 301,
+                padding: 0.5,
+                uncertainty_growth: 0.4,
+                confidence: 0.35,
+                min_track_frames: 1,
+                verify_blur: true,
+            },
+        }
+    }
+
+    /// The preset whose values are exactly `settings`, if any. Frontends
+    /// use it to show "Custom" once a single value was overridden.
+    pub fn matching(settings: &PresetSettings) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.settings() == *settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("fast", Some(BlurPreset::Fast))]
+    #[case("Balanced", Some(BlurPreset::Balanced))]
+    #[case("maximum", Some(BlurPreset::MaximumProtection))]
+    #[case("max", Some(BlurPreset::MaximumProtection))]
+    #[case(" maximum-protection ", Some(BlurPreset::MaximumProtection))]
+    #[case("paranoid", None)]
+    fn test_parse(#[case] input: &str, #[case] expected: Option<BlurPreset>) {
+        assert_eq!(BlurPreset::parse(input), expected);
+    }
+
+    #[test]
+    fn test_name_round_trips() {
+        for preset in BlurPreset::ALL {
+            assert_eq!(BlurPreset::parse(preset.name()), Some(preset));
+        }
+    }
+
+    #[test]
+    fn test_presets_order_from_fast_to_thorough() {
+        let fast = BlurPreset::Fast.settings();
+        let balanced = BlurPreset::Balanced.settings();
+        let max = BlurPreset::MaximumProtection.settings();
+        assert!(fast.detection_interval > balanced.detection_interval);
+        assert!(balanced.detection_interval > max.detection_interval);
+        assert!(fast.lookahead < balanced.lookahead && balanced.lookahead < max.lookahead);
+        assert!(max.confidence < balanced.confidence);
+        assert!(max.verify_blur && !balanced.verify_blur);
+    }
+
+    #[test]
+    fn test_blur_strengths_are_odd() {
+        for preset in BlurPreset::ALL {
+            assert_eq!(preset.settings().blur_strength % 2, 1, "{}", preset.label());
+        }
+    }
+
+    #[test]
+    fn test_matching_detects_overrides() {
+        let mut settings = BlurPreset::Fast.settings();
+        assert_eq!(BlurPreset::matching(&settings), Some(BlurPreset::Fast));
+
+        settings.lookahead += 1;
+        assert_eq!(BlurPreset::matching(&settings), None);
+    }
+}
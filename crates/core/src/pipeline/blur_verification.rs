@@ -0,0 +1,260 @@
+use std::sync::{Arc, Mutex};
+
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
+use crate::detection::domain::face_detector::FaceDetector;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// Share of frames with faces that are checked by default.
+pub const DEFAULT_VERIFICATION_RATE: f64 = 0.015;
+
+/// A face still detectable after blurring counts as a leak when at least
+/// this much of it lies inside a blurred region.
+const LEAK_COVERAGE: f64 = 0.5;
+
+/// A face found on a blurred frame inside a region that was blurred.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlurLeak {
+    pub frame_index: usize,
+    /// The face as detected on the blurred frame.
+    pub face: Region,
+}
+
+/// Running totals of a [`BlurVerifyingBlurrer`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlurVerificationReport {
+    pub frames_checked: usize,
+    pub leaks: Vec<BlurLeak>,
+}
+
+impl BlurVerificationReport {
+    pub fn has_leaks(&self) -> bool {
+        !self.leaks.is_empty()
+    }
+}
+
+/// Decorator that re-runs face detection on a random sample of frames
+/// after the inner blurrer has run, and reports faces that are still
+/// detectable inside a blurred region.
+///
+/// An inline safety net against blurrer bugs and region math errors: a
+/// sample of 1-2% costs a small fraction of a full QA pass. Only frames
+/// with regions are sampled. Sampling is a hash of the frame index and a
+/// seed, so a run checks the same frames when repeated. The detector
+/// should be untracked and unsmoothed, since it sees scattered frames.
+pub struct BlurVerifyingBlurrer {
+    inner: Box<dyn FrameBlurrer>,
+    detector: Mutex<Box<dyn FaceDetector>>,
+    rate: f64,
+    seed: u64,
+    report: Arc<Mutex<BlurVerificationReport>>,
+}
+
+impl BlurVerifyingBlurrer {
+    pub fn new(inner: Box<dyn FrameBlurrer>, detector: Box<dyn FaceDetector>) -> Self {
+        Self {
+            inner,
+            detector: Mutex::new(detector),
+            rate: DEFAULT_VERIFICATION_RATE,
+            seed: 0,
+            report: Arc::new(Mutex::new(BlurVerificationReport::default())),
+        }
+    }
+
+    /// Share of frames with regions to check, clamped to 0.0-1.0.
+    pub fn with_rate(mut self, rate: f64) -> Self {
+        self.rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Picks a different sample of frames.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Shared handle to the totals, readable after the blurrer is moved
+    /// into a pipeline.
+    pub fn report(&self) -> Arc<Mutex<BlurVerificationReport>> {
+        self.report.clone()
+    }
+
+    fn is_sampled(&self, frame_index: usize) -> bool {
+        let hash = splitmix64(self.seed ^ frame_index as u64);
+        let unit = (hash >> 11) as f64 / (1u64 << 53) as f64;
+        self.rate > 0.0 && unit <= self.rate
+    }
+}
+
+impl FrameBlurrer for BlurVerifyingBlurrer {
+    fn blur(
+        &self,
+        frame: &mut Frame,
+        regions: &[Region],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.blur(frame, regions)?;
+        if regions.is_empty() || !self.is_sampled(frame.index()) {
+            return Ok(());
+        }
+
+        let faces = self
+            .detector
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .detect(frame)?;
+        let mut report = self.report.lock().unwrap_or_else(|e| e.into_inner());
+        report.frames_checked += 1;
+        for face in faces {
+            if regions.iter().any(|r| coverage(&face, r) >= LEAK_COVERAGE) {
+                log::warn!(
+                    "Face still detectable after blurring on frame {} at ({}, {}) {}x{}",
+                    frame.index(),
+                    face.x,
+                    face.y,
+                    face.width,
+                    face.height
+                );
+                report.leaks.push(BlurLeak {
+                    frame_index: frame.index(),
+                    face,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fraction of `face` that lies inside `region`.
+fn coverage(face: &Region, region: &Region) -> f64 {
+    let area = face.width as f64 * face.height as f64;
+    if area <= 0.0 {
+        return 0.0;
+    }
+    let w = (face.x + face.width).min(region.x + region.width) - face.x.max(region.x);
+    let h = (face.y + face.height).min(region.y + region.height) - face.y.max(region.y);
+    if w <= 0 || h <= 0 {
+        return 0.0;
+    }
+    (w as f64 * h as f64) / area
+}
+
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(x: i32, y: i32, w: i32, h: i32) -> Region {
+        Region {
+            x,
+            y,
+            width: w,
+            height: h,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    /// Leaves the frame untouched, as a broken blurrer would.
+    struct NoopBlurrer;
+
+    impl FrameBlurrer for NoopBlurrer {
+        fn blur(
+            &self,
+            _frame: &mut Frame,
+            _regions: &[Region],
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+    }
+
+    /// Always finds the same faces.
+    struct FixedDetector {
+        faces: Vec<Region>,
+        calls: Arc<Mutex<usize>>,
+    }
+
+    impl FaceDetector for FixedDetector {
+        fn detect(&mut self, _frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+            *self.calls.lock().unwrap() += 1;
+            Ok(self.faces.clone())
+        }
+    }
+
+    fn verifier(faces: Vec<Region>, rate: f64) -> (BlurVerifyingBlurrer, Arc<Mutex<usize>>) {
+        let calls = Arc::new(Mutex::new(0));
+        let detector = FixedDetector {
+            faces,
+            calls: calls.clone(),
+        };
+        let blurrer =
+            BlurVerifyingBlurrer::new(Box::new(NoopBlurrer), Box::new(detector)).with_rate(rate);
+        (blurrer, calls)
+    }
+
+    fn frame(index: usize) -> Frame {
+        Frame::new(vec![0; 64 * 64 * 3], 64, 64, 3, index)
+    }
+
+    #[test]
+    fn test_face_inside_blurred_region_is_a_leak() {
+        let (blurrer, _) = verifier(vec![region(12, 12, 16, 16), region(45, 45, 10, 10)], 1.0);
+        let report = blurrer.report();
+        blurrer
+            .blur(&mut frame(7), &[region(10, 10, 20, 20)])
+            .unwrap();
+
+        let report = report.lock().unwrap();
+        assert_eq!(report.frames_checked, 1);
+        assert_eq!(
+            report.leaks,
+            vec![BlurLeak {
+                frame_index: 7,
+                face: region(12, 12, 16, 16),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_frames_without_regions_are_not_checked() {
+        let (blurrer, calls) = verifier(vec![region(0, 0, 10, 10)], 1.0);
+        blurrer.blur(&mut frame(0), &[]).unwrap();
+        assert_eq!(*calls.lock().unwrap(), 0);
+        assert!(!blurrer.report().lock().unwrap().has_leaks());
+    }
+
+    #[test]
+    fn test_sample_rate_is_roughly_respected() {
+        let (blurrer, calls) = verifier(Vec::new(), DEFAULT_VERIFICATION_RATE);
+        for i in 0..10_000 {
+            blurrer.blur(&mut frame(i), &[region(0, 0, 8, 8)]).unwrap();
+        }
+        let checked = *calls.lock().unwrap();
+        assert!((100..=200).contains(&checked), "checked {checked}");
+        assert_eq!(blurrer.report().lock().unwrap().frames_checked, checked);
+    }
+
+    #[test]
+    fn test_zero_rate_checks_nothing() {
+        let (blurrer, calls) = verifier(Vec::new(), 0.0);
+        for i in 0..1000 {
+            blurrer.blur(&mut frame(i), &[region(0, 0, 8, 8)]).unwrap();
+        }
+        assert_eq!(*calls.lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_coverage() {
+        assert_eq!(coverage(&region(0, 0, 10, 10), &region(5, 0, 10, 10)), 0.5);
+        assert_eq!(coverage(&region(0, 0, 10, 10), &region(20, 0, 10, 10)), 0.0);
+        assert_eq!(coverage(&region(2, 2, 4, 4), &region(0, 0, 10, 10)), 1.0);
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::detection::domain::face_observation::{FaceObservation, FaceObservations};
+use crate::shared::region::Region;
+use crate::shared::schema::Schema;
+use crate::shared::video_metadata::VideoMetadata;
+
+/// Bumped when a field is renamed or removed, so consumers can tell
+/// report layouts apart.
+pub const REPORT_VERSION: u32 = 1;
+
+/// Report layout history. A version bump adds a migration here, so
+/// `CachedFaceDetector::from_json_file` keeps replaying older reports.
+pub const REPORT_SCHEMA: Schema = Schema::new("Detections", REPORT_VERSION, &[]);
+
+/// Formats a detection scan as JSON, for auditing what the detector sees
+/// and feeding it into other tools.
+///
+/// Only frames with at least one face are listed, in frame order; gaps in
+/// the `frame` numbers are frames without faces. Each face carries its blur
+/// region in frame pixels and its track ID. `confidence` (the raw model
+/// score) and `landmarks` (left eye, right eye, nose, left and right mouth
+/// corner; `null` when not visible) come from `observations` and are
+/// `null` on frames the model skipped or when the track coasted without a
+/// matched detection.
+pub fn format_detection_report(
+    detections: &HashMap<usize, Vec<Region>>,
+    observations: &FaceObservations,
+    metadata: &VideoMetadata,
+) -> String {
+    let fps = if metadata.fps.is_finite() {
+        metadata.fps
+    } else {
+        0.0
+    };
+    let mut frames: Vec<usize> = detections
+        .iter()
+        .filter(|(_, regions)| !regions.is_empty())
+        .map(|(&frame, _)| frame)
+        .collect();
+    frames.sort_unstable();
+
+    let mut out = String::new();
+    let _ = writeln!(out, "{{");
+    let _ = writeln!(out, "  \"version\": {REPORT_VERSION},");
+    let _ = writeln!(out, "  \"width\": {},", metadata.width);
+    let _ = writeln!(out, "  \"height\": {},", metadata.height);
+    let _ = writeln!(out, "  \"fps\": {fps},");
+    let _ = writeln!(out, "  \"total_frames\": {},", metadata.total_frames);
+    out.push_str("  \"frames\": [");
+    for (i, &frame) in frames.iter().enumerate() {
+        out.push_str(if i == 0 { "\n" } else { ",\n" });
+        let observed = observations.get(&frame);
+        format_frame(&mut out, frame, fps, &detections[&frame], observed);
+    }
+    if !frames.is_empty() {
+        out.push_str("\n  ");
+    }
+    out.push_str("]\n}\n");
+    out
+}
+
+fn format_frame(
+    out: &mut String,
+    frame: usize,
+    fps: f64,
+    regions: &[Region],
+    observed: Option<&HashMap<u32, FaceObservation>>,
+) {
+    let timestamp = if fps > 0.0 { frame as f64 / fps } else { 0.0 };
+    let _ = write!(
+        out,
+        "    {{\"frame\": {frame}, \"timestamp\": {timestamp:.3}, \"faces\": ["
+    );
+    for (i, region) in regions.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        let observation = region
+            .track_id
+            .and_then(|id| observed.and_then(|faces| faces.get(&id)));
+        format_face(out, region, observation);
+    }
+    out.push_str("]}");
+}
+
+fn format_face(out: &mut String, region: &Region, observation: Option<&FaceObservation>) {
+    let _ = write!(
+        out,
+        "{{\"track_id\": {}, \"x\": {}, \"y\": {}, \"width\": {}, \"height\": {}, \
+         \"confidence\": {}, \"landmarks\": {}}}",
+        region
+            .track_id
+            .map_or_else(|| "null".to_string(), |id| id.to_string()),
+        region.x,
+        region.y,
+        region.width,
+        region.height,
+        observation.map_or_else(|| "null".to_string(), |o| format!("{:.4}", o.confidence)),
+        observation.and_then(|o| o.landmarks.as_ref()).map_or_else(
+            || "null".to_string(),
+            |landmarks| {
+                let points: Vec<String> = landmarks
+                    .points()
+                    .iter()
+                    .map(|&(x, y)| {
+                        if x > 0.0 {
+                            format!("[{x:.1}, {y:.1}]")
+                        } else {
+                            "null".to_string()
+                        }
+                    })
+                    .collect();
+                format!("[{}]", points.join(", "))
+            }
+        ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detection::domain::face_detector::FaceDetector;
+    use crate::detection::domain::face_landmarks::FaceLandmarks;
+    use crate::detection::infrastructure::cached_face_detector::CachedFaceDetector;
+    use crate::shared::color_space::ColorSpace;
+    use crate::shared::frame::Frame;
+
+    fn region(x: i32, track_id: Option<u32>) -> Region {
+        Region {
+            x,
+            y: 20,
+            width: 30,
+            height: 40,
+            track_id,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    fn metadata(fps: f64) -> VideoMetadata {
+        VideoMetadata {
+            width: 640,
+            height: 480,
+            fps,
+            total_frames: 3,
+            codec: String::new(),
+            source_path: None,
+            rotation: 0,
+            color: ColorSpace::default(),
+        }
+    }
+
+    #[test]
+    fn test_empty_scan_has_no_frames() {
+        let report =
+            format_detection_report(&HashMap::new(), &FaceObservations::new(), &metadata(25.0));
+        assert_eq!(
+            report,
+            "{\n  \"version\": 1,\n  \"width\": 640,\n  \"height\": 480,\n  \"fps\": 25,\n  \
+             \"total_frames\": 3,\n  \"frames\": []\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_faces_carry_observations() {
+        let detections = HashMap::from([
+            (0, vec![region(10, Some(1))]),
+            (1, vec![]),
+            (2, vec![region(12, Some(1)), region(300, None)]),
+        ]);
+        let observations = FaceObservations::from([(
+            2,
+            HashMap::from([(
+                1,
+                FaceObservation {
+                    confidence: 0.87654,
+                    landmarks: Some(FaceLandmarks::new([
+                        (15.0, 30.0),
+                        (25.0, 30.0),
+                        (20.0, 38.26),
+                        (0.0, 0.0),
+                        (24.0, 45.0),
+                    ])),
+                },
+            )]),
+        )]);
+
+        let report = format_detection_report(&detections, &observations, &metadata(25.0));
+
+        assert!(report.contains(
+            "    {\"frame\": 0, \"timestamp\": 0.000, \"faces\": [{\"track_id\": 1, \"x\": 10, \
+             \"y\": 20, \"width\": 30, \"height\": 40, \"confidence\": null, \"landmarks\": null}]},\n"
+        ));
+        assert!(!report.contains("\"frame\": 1,"));
+        assert!(report.contains(
+            "{\"frame\": 2, \"timestamp\": 0.080, \"faces\": [{\"track_id\": 1, \"x\": 12, \
+             \"y\": 20, \"width\": 30, \"height\": 40, \"confidence\": 0.8765, \"landmarks\": \
+             [[15.0, 30.0], [25.0, 30.0], [20.0, 38.3], null, [24.0, 45.0]]}, {\"track_id\": null, \
+             \"x\": 300, \"y\": 20, \"width\": 30, \"height\": 40, \"confidence\": null, \
+             \"landmarks\": null}]}\n  ]\n}\n"
+        ));
+    }
+
+    #[test]
+    fn test_images_report_zero_timestamps() {
+        let detections = HashMap::from([(0, vec![region(10, Some(1))])]);
+        let report = format_detection_report(&detections, &FaceObservations::new(), &metadata(0.0));
+        assert!(report.contains("\"fps\": 0,"));
+        assert!(report.contains("\"timestamp\": 0.000"));
+    }
+
+    #[test]
+    fn test_report_replays_through_cached_detector() {
+        let detections = HashMap::from([
+            (0, vec![region(10, Some(1))]),
+            (2, vec![region(12, Some(1)), region(300, None)]),
+        ]);
+        let report =
+            format_detection_report(&detections, &FaceObservations::new(), &metadata(25.0));
+
+        let mut detector = CachedFaceDetector::from_json(&report).unwrap();
+
+        for frame in 0..3 {
+            let replayed = detector
+                .detect(&Frame::new(vec![0u8; 3], 1, 1, 3, frame))
+                .unwrap();
+            assert_eq!(
+                replayed,
+                detections.get(&frame).cloned().unwrap_or_default()
+            );
+        }
+    }
+}
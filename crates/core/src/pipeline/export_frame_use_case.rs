@@ -0,0 +1,701 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
+use crate::detection::domain::face_detector::FaceDetector;
+use crate::detection::domain::region_merger::RegionMerger;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+use crate::shared::track_time_rules::TrackTimeRules;
+use crate::shared::video_metadata::VideoMetadata;
+use crate::video::domain::image_writer::ImageWriter;
+use crate::video::domain::video_reader::VideoReader;
+
+/// Detection and blurring for [`ExportFrameUseCase::with_blur`].
+struct FrameBlur {
+    detector: Box<dyn FaceDetector>,
+    blurrer: Box<dyn FrameBlurrer>,
+    merger: RegionMerger,
+    lookahead: usize,
+}
+
+/// A frame as decoded and as it comes out of the blur pipeline.
+pub struct RenderedFrame {
+    pub original: Frame,
+    pub processed: Frame,
+    /// Regions blurred on `processed`, in frame pixels, for outlining them
+    /// in a debug view. Empty when not blurring.
+    pub regions: Vec<Region>,
+    /// Clockwise turn, in degrees, the source's metadata asks players to
+    /// show the frame with.
+    pub rotation: i32,
+}
+
+impl RenderedFrame {
+    /// The frames and regions turned by `rotation`, as a player would
+    /// show them.
+    pub fn upright(self) -> Self {
+        if self.rotation == 0 {
+            return self;
+        }
+        let (w, h) = (self.original.width(), self.original.height());
+        Self {
+            original: self.original.rotated(self.rotation),
+            processed: self.processed.rotated(self.rotation),
+            regions: self
+                .regions
+                .iter()
+                .map(|r| r.rotated(self.rotation, w, h))
+                .collect(),
+            rotation: 0,
+        }
+    }
+}
+
+/// Renders one frame of a video, either as decoded or with faces blurred,
+/// without running the whole pipeline.
+///
+/// Seeks straight to the frame instead of decoding from the start. When
+/// blurring, faces are detected on the frame and the `lookahead` frames
+/// after it, filtered, and merged like the video pipeline does, so a face
+/// entering the shot is covered as it would be in the export.
+///
+/// A fresh detector numbers tracks from the seek point, so track filters
+/// only make sense with one that replays a full scan, such as
+/// `CachedFaceDetector`.
+pub struct ExportFrameUseCase {
+    reader: Box<dyn VideoReader>,
+    image_writer: Box<dyn ImageWriter>,
+    blur: Option<FrameBlur>,
+    blur_ids: Option<HashSet<u32>>,
+    exclude_ids: Option<HashSet<u32>>,
+    time_rules: TrackTimeRules,
+}
+
+impl ExportFrameUseCase {
+    pub fn new(reader: Box<dyn VideoReader>, image_writer: Box<dyn ImageWriter>) -> Self {
+        Self {
+            reader,
+            image_writer,
+            blur: None,
+            blur_ids: None,
+            exclude_ids: None,
+            time_rules: TrackTimeRules::default(),
+        }
+    }
+
+    /// Blurs the faces found on the frame before writing it.
+    pub fn with_blur(
+        mut self,
+        detector: Box<dyn FaceDetector>,
+        blurrer: Box<dyn FrameBlurrer>,
+        lookahead: usize,
+    ) -> Self {
+        self.blur = Some(FrameBlur {
+            detector,
+            blurrer,
+            merger: RegionMerger::new(),
+            lookahead,
+        });
+        self
+    }
+
+    /// Blur only `blur_ids`, or everything except `exclude_ids`, as in
+    /// `BlurFacesUseCase`.
+    pub fn with_track_filter(
+        mut self,
+        blur_ids: Option<HashSet<u32>>,
+        exclude_ids: Option<HashSet<u32>>,
+    ) -> Self {
+        self.blur_ids = blur_ids;
+        self.exclude_ids = exclude_ids;
+        self
+    }
+
+    /// Per-track time windows, applied after the ID sets.
+    pub fn with_time_rules(mut self, rules: TrackTimeRules) -> Self {
+        self.time_rules = rules;
+        self
+    }
+
+    /// Writes the frame shown at `seconds` into the video to `output_path`
+    /// and returns its index.
+    pub fn execute(
+        &mut self,
+        input_path: &Path,
+        seconds: f64,
+        output_path: &Path,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let metadata = self.reader.open(input_path)?;
+        if metadata.fps <= 0.0 {
+            return Err("Input has no frame rate, so timestamps can't be located".into());
+        }
+        if seconds < 0.0 {
+            return Err(format!("Timestamp {seconds}s is negative").into());
+        }
+        let index = (seconds * metadata.fps).floor() as usize;
+        if metadata.total_frames > 0 && index >= metadata.total_frames {
+            return Err(format!(
+                "Timestamp {seconds}s is past the end of the video ({:.2}s)",
+                metadata.total_frames as f64 / metadata.fps
+            )
+            .into());
+        }
+
+        let rendered = self.render_opened(&metadata, index)?;
+        self.image_writer
+            .write(output_path, &rendered.processed, None)?;
+        Ok(index)
+    }
+
+    /// Renders frame `frame_index` in memory, for before/after comparisons.
+    /// Frame 0 needs no seek, so single images work too.
+    pub fn render(
+        &mut self,
+        input_path: &Path,
+        frame_index: usize,
+    ) -> Result<RenderedFrame, Box<dyn std::error::Error>> {
+        let metadata = self.reader.open(input_path)?;
+        if metadata.total_frames > 0 && frame_index >= metadata.total_frames {
+            return Err(format!(
+                "Frame {frame_index} is past the end of the video ({} frames)",
+                metadata.total_frames
+            )
+            .into());
+        }
+        self.render_opened(&metadata, frame_index)
+    }
+
+    fn render_opened(
+        &mut self,
+        metadata: &VideoMetadata,
+        index: usize,
+    ) -> Result<RenderedFrame, Box<dyn std::error::Error>> {
+        if index > 0 {
+            self.reader.seek(index)?;
+        }
+        let lookahead = self.blur.as_ref().map_or(0, |b| b.lookahead);
+        let frames: Vec<Frame> = self
+            .reader
+            .frames()
+            .take(1 + lookahead)
+            .collect::<Result<_, _>>()?;
+        self.reader.close();
+
+        let mut frames = frames.into_iter();
+        let original = frames
+            .next()
+            .ok_or_else(|| format!("No frame at index {index}"))?;
+        let mut processed = original.clone();
+        let mut blurred_regions = Vec::new();
+
+        if let Some(blur) = &mut self.blur {
+            let mut detect = |frame: &Frame| -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+                let regions = blur.detector.detect(frame)?;
+                let selected =
+                    Region::filter(&regions, self.blur_ids.as_ref(), self.exclude_ids.as_ref());
+                Ok(self
+                    .time_rules
+                    .filter(&selected, frame.index(), metadata.fps))
+            };
+            let current = detect(&original)?;
+            let future = frames.map(|f| detect(&f)).collect::<Result<Vec<_>, _>>()?;
+            let future: Vec<&[Region]> = future.iter().map(Vec::as_slice).collect();
+            let regions = blur
+                .merger
+                .merge(&current, &future, original.width(), original.height());
+            blur.blurrer.blur(&mut processed, &regions)?;
+            blurred_regions = regions;
+        }
+
+        Ok(RenderedFrame {
+            original,
+            processed,
+            regions: blurred_regions,
+            rotation: metadata.rotation,
+        })
+    }
+}
+
+/// The frame with the most faces in a preview scan, earliest first on ties,
+/// as a representative sample for comparing blur settings. `None` when no
+/// face was found.
+pub fn representative_frame(detections: &HashMap<usize, Vec<Region>>) -> Option<usize> {
+    detections
+        .iter()
+        .filter(|(_, regions)| !regions.is_empty())
+        .max_by(|(a_index, a), (b_index, b)| a.len().cmp(&b.len()).then(b_index.cmp(a_index)))
+        .map(|(&index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::color_space::ColorSpace;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+
+    // --- Stubs ---
+
+    /// Frames filled with their own index, with seeking.
+    struct StubVideoReader {
+        total: usize,
+        fps: f64,
+        position: usize,
+        seekable: bool,
+    }
+
+    impl StubVideoReader {
+        fn new(total: usize, fps: f64) -> Self {
+            Self {
+                total,
+                fps,
+                position: 0,
+                seekable: true,
+            }
+        }
+    }
+
+    impl VideoReader for StubVideoReader {
+        fn open(&mut self, _path: &Path) -> Result<VideoMetadata, Box<dyn std::error::Error>> {
+            Ok(VideoMetadata {
+                width: 100,
+                height: 100,
+                fps: self.fps,
+                total_frames: self.total,
+                codec: String::new(),
+                source_path: None,
+                rotation: 0,
+                color: ColorSpace::default(),
+            })
+        }
+
+        fn frames(
+            &mut self,
+        ) -> Box<dyn Iterator<Item = Result<Frame, Box<dyn std::error::Error>>> + '_> {
+            Box::new(
+                (self.position..self.total)
+                    .map(|i| Ok(Frame::new(vec![i as u8; 100 * 100 * 3], 100, 100, 3, i))),
+            )
+        }
+
+        fn seek(&mut self, frame_index: usize) -> Result<(), Box<dyn std::error::Error>> {
+            if !self.seekable {
+                return Err("not seekable".into());
+            }
+            self.position = frame_index;
+            Ok(())
+        }
+
+        fn close(&mut self) {}
+    }
+
+    struct StubImageWriter {
+        written: Arc<Mutex<Vec<(PathBuf, Frame)>>>,
+    }
+
+    impl ImageWriter for StubImageWriter {
+        fn write(
+            &self,
+            path: &Path,
+            frame: &Frame,
+            _size: Option<(u32, u32)>,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            self.written
+                .lock()
+                .unwrap()
+                .push((path.to_path_buf(), frame.clone()));
+            Ok(())
+        }
+    }
+
+    /// Finds one face, with track ID 1, on each frame listed.
+    struct StubDetector {
+        faces_on: Vec<usize>,
+    }
+
+    impl FaceDetector for StubDetector {
+        fn detect(&mut self, frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+            if self.faces_on.contains(&frame.index()) {
+                Ok(vec![region(1)])
+            } else {
+                Ok(vec![])
+            }
+        }
+    }
+
+    /// Finds the bright square `write_phone_video` draws, in stored pixels.
+    struct CornerDetector;
+
+    impl FaceDetector for CornerDetector {
+        fn detect(&mut self, _frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+            Ok(vec![Region {
+                x: 0,
+                y: 0,
+                width: 48,
+                height: 48,
+                track_id: Some(1),
+                full_width: None,
+                full_height: None,
+                unclamped_x: None,
+                unclamped_y: None,
+            }])
+        }
+    }
+
+    struct RecordingBlurrer {
+        calls: Arc<Mutex<Vec<Vec<Region>>>>,
+    }
+
+    impl FrameBlurrer for RecordingBlurrer {
+        fn blur(
+            &self,
+            _frame: &mut Frame,
+            regions: &[Region],
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            self.calls.lock().unwrap().push(regions.to_vec());
+            Ok(())
+        }
+    }
+
+    // --- Helpers ---
+
+    fn region(track_id: u32) -> Region {
+        Region {
+            x: 40,
+            y: 40,
+            width: 20,
+            height: 20,
+            track_id: Some(track_id),
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    type Written = Arc<Mutex<Vec<(PathBuf, Frame)>>>;
+
+    fn writer() -> (Box<dyn ImageWriter>, Written) {
+        let written = Arc::new(Mutex::new(Vec::new()));
+        (
+            Box::new(StubImageWriter {
+                written: written.clone(),
+            }),
+            written,
+        )
+    }
+
+    // --- Tests ---
+
+    #[test]
+    fn test_writes_frame_at_timestamp() {
+        let (image_writer, written) = writer();
+        let mut uc =
+            ExportFrameUseCase::new(Box::new(StubVideoReader::new(50, 10.0)), image_writer);
+
+        let index = uc
+            .execute(Path::new("in.mp4"), 2.35, Path::new("out.png"))
+            .unwrap();
+
+        assert_eq!(index, 23);
+        let written = written.lock().unwrap();
+        assert_eq!(written.len(), 1);
+        assert_eq!(written[0].0, PathBuf::from("out.png"));
+        assert_eq!(written[0].1.index(), 23);
+        assert_eq!(written[0].1.data()[0], 23);
+    }
+
+    #[test]
+    fn test_blur_uses_detections_on_frame() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let (image_writer, _) = writer();
+        let mut uc =
+            ExportFrameUseCase::new(Box::new(StubVideoReader::new(50, 10.0)), image_writer)
+                .with_blur(
+                    Box::new(StubDetector { faces_on: vec![10] }),
+                    Box::new(RecordingBlurrer {
+                        calls: calls.clone(),
+                    }),
+                    0,
+                );
+
+        uc.execute(Path::new("in.mp4"), 1.0, Path::new("out.png"))
+            .unwrap();
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].len(), 1);
+        assert_eq!(calls[0][0].track_id, Some(1));
+    }
+
+    #[test]
+    fn test_blur_includes_lookahead_faces() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let (image_writer, _) = writer();
+        let mut uc =
+            ExportFrameUseCase::new(Box::new(StubVideoReader::new(50, 10.0)), image_writer)
+                .with_blur(
+                    Box::new(StubDetector { faces_on: vec![12] }),
+                    Box::new(RecordingBlurrer {
+                        calls: calls.clone(),
+                    }),
+                    3,
+                );
+
+        uc.execute(Path::new("in.mp4"), 1.0, Path::new("out.png"))
+            .unwrap();
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls[0].len(), 1, "face two frames ahead should be blurred");
+    }
+
+    #[test]
+    fn test_lookahead_stops_at_end_of_video() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let (image_writer, written) = writer();
+        let mut uc =
+            ExportFrameUseCase::new(Box::new(StubVideoReader::new(50, 10.0)), image_writer)
+                .with_blur(
+                    Box::new(StubDetector { faces_on: vec![] }),
+                    Box::new(RecordingBlurrer {
+                        calls: calls.clone(),
+                    }),
+                    10,
+                );
+
+        let index = uc
+            .execute(Path::new("in.mp4"), 4.95, Path::new("out.png"))
+            .unwrap();
+
+        assert_eq!(index, 49);
+        assert_eq!(written.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_timestamp_past_end_is_error() {
+        let (image_writer, written) = writer();
+        let mut uc =
+            ExportFrameUseCase::new(Box::new(StubVideoReader::new(50, 10.0)), image_writer);
+
+        let result = uc.execute(Path::new("in.mp4"), 5.0, Path::new("out.png"));
+
+        assert!(result.is_err());
+        assert!(written.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unseekable_reader_is_error() {
+        let (image_writer, _) = writer();
+        let mut reader = StubVideoReader::new(50, 10.0);
+        reader.seekable = false;
+        let mut uc = ExportFrameUseCase::new(Box::new(reader), image_writer);
+
+        assert!(uc
+            .execute(Path::new("in.mp4"), 1.0, Path::new("out.png"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_input_without_frame_rate_is_error() {
+        let (image_writer, _) = writer();
+        let mut uc = ExportFrameUseCase::new(Box::new(StubVideoReader::new(1, 0.0)), image_writer);
+
+        assert!(uc
+            .execute(Path::new("in.png"), 0.0, Path::new("out.png"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_render_returns_original_and_processed() {
+        let (image_writer, written) = writer();
+        let mut uc =
+            ExportFrameUseCase::new(Box::new(StubVideoReader::new(50, 10.0)), image_writer);
+
+        let rendered = uc.render(Path::new("in.mp4"), 30).unwrap();
+
+        assert_eq!(rendered.original.index(), 30);
+        assert_eq!(rendered.processed.data(), rendered.original.data());
+        assert!(rendered.regions.is_empty());
+        assert!(written.lock().unwrap().is_empty(), "render writes nothing");
+    }
+
+    #[test]
+    fn test_render_returns_blurred_regions() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let (image_writer, _) = writer();
+        let mut uc =
+            ExportFrameUseCase::new(Box::new(StubVideoReader::new(50, 10.0)), image_writer)
+                .with_blur(
+                    Box::new(StubDetector { faces_on: vec![5] }),
+                    Box::new(RecordingBlurrer {
+                        calls: calls.clone(),
+                    }),
+                    0,
+                );
+
+        let rendered = uc.render(Path::new("in.mp4"), 5).unwrap();
+
+        assert_eq!(rendered.regions, calls.lock().unwrap()[0]);
+        assert_eq!(rendered.regions.len(), 1);
+    }
+
+    #[test]
+    fn test_render_first_frame_needs_no_seek() {
+        let (image_writer, _) = writer();
+        let mut reader = StubVideoReader::new(1, 0.0);
+        reader.seekable = false;
+        let mut uc = ExportFrameUseCase::new(Box::new(reader), image_writer);
+
+        let rendered = uc.render(Path::new("in.png"), 0).unwrap();
+
+        assert_eq!(rendered.original.index(), 0);
+    }
+
+    #[test]
+    fn test_render_applies_track_filter() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let (image_writer, _) = writer();
+        let mut uc =
+            ExportFrameUseCase::new(Box::new(StubVideoReader::new(50, 10.0)), image_writer)
+                .with_blur(
+                    Box::new(StubDetector { faces_on: vec![5] }),
+                    Box::new(RecordingBlurrer {
+                        calls: calls.clone(),
+                    }),
+                    0,
+                )
+                .with_track_filter(None, Some(HashSet::from([1])));
+
+        uc.render(Path::new("in.mp4"), 5).unwrap();
+
+        assert!(calls.lock().unwrap()[0].is_empty());
+    }
+
+    #[test]
+    fn test_render_past_end_is_error() {
+        let (image_writer, _) = writer();
+        let mut uc =
+            ExportFrameUseCase::new(Box::new(StubVideoReader::new(50, 10.0)), image_writer);
+
+        assert!(uc.render(Path::new("in.mp4"), 50).is_err());
+    }
+
+    #[test]
+    fn test_upright_turns_frames_and_regions() {
+        let face = Region {
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 20,
+            track_id: Some(1),
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        };
+        let rendered = RenderedFrame {
+            original: Frame::new(vec![0; 40 * 30 * 3], 40, 30, 3, 0),
+            processed: Frame::new(vec![0; 40 * 30 * 3], 40, 30, 3, 0),
+            regions: vec![face],
+            rotation: 90,
+        };
+
+        let upright = rendered.upright();
+
+        assert_eq!(
+            (upright.original.width(), upright.original.height()),
+            (30, 40)
+        );
+        assert_eq!(
+            (upright.processed.width(), upright.processed.height()),
+            (30, 40)
+        );
+        let r = &upright.regions[0];
+        assert_eq!((r.x, r.y, r.width, r.height), (10, 0, 20, 10));
+        assert_eq!(upright.rotation, 0);
+    }
+
+    /// Writes a one-frame 160x120 video, black with a bright square in the
+    /// top-left corner, carrying the display matrix of a phone held upright.
+    fn write_phone_video(path: &Path) {
+        use crate::video::domain::video_writer::VideoWriter;
+        use crate::video::infrastructure::ffmpeg_writer::FfmpegWriter;
+
+        let mut data = vec![0u8; 160 * 120 * 3];
+        for y in 0..48 {
+            data[y * 160 * 3..(y * 160 + 48) * 3].fill(255);
+        }
+        let metadata = VideoMetadata {
+            width: 160,
+            height: 120,
+            fps: 30.0,
+            total_frames: 1,
+            codec: String::new(),
+            source_path: None,
+            rotation: 0,
+            color: ColorSpace::default(),
+        };
+        let mut writer = FfmpegWriter::new();
+        writer.set_skip_audio_passthrough(true);
+        writer.open(path, &metadata).unwrap();
+        writer.write(&Frame::new(data, 160, 120, 3, 0)).unwrap();
+        writer.close().unwrap();
+
+        let mut bytes = std::fs::read(path).unwrap();
+        let tkhd = bytes.windows(4).position(|w| w == b"tkhd").unwrap();
+        let matrix: [i32; 9] = [0, 65536, 0, -65536, 0, 0, 0, 0, 0x40000000];
+        for (i, value) in matrix.iter().enumerate() {
+            let at = tkhd + 4 + 40 + i * 4;
+            bytes[at..at + 4].copy_from_slice(&value.to_be_bytes());
+        }
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn test_upright_shows_phone_video_as_players_do() {
+        use crate::video::infrastructure::ffmpeg_reader::FfmpegReader;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("phone.mp4");
+        write_phone_video(&path);
+        let (image_writer, _) = writer();
+        let mut uc = ExportFrameUseCase::new(Box::new(FfmpegReader::new()), image_writer)
+            .with_blur(
+                Box::new(CornerDetector),
+                Box::new(RecordingBlurrer {
+                    calls: Arc::new(Mutex::new(Vec::new())),
+                }),
+                0,
+            );
+
+        let upright = uc.render(&path, 0).unwrap().upright();
+
+        let frame = &upright.original;
+        assert_eq!((frame.width(), frame.height()), (120, 160));
+        let luma = |x: usize, y: usize| frame.data()[(y * 120 + x) * 3];
+        assert!(luma(96, 24) > 200, "top right is {}", luma(96, 24));
+        assert!(luma(24, 24) < 50, "top left is {}", luma(24, 24));
+        let r = &upright.regions[0];
+        assert_eq!((r.x, r.y, r.width, r.height), (72, 0, 48, 48));
+    }
+
+    #[test]
+    fn test_representative_frame_has_most_faces() {
+        let detections = HashMap::from([
+            (0, vec![region(1)]),
+            (4, vec![region(1), region(2)]),
+            (2, vec![region(1), region(2)]),
+            (6, vec![]),
+        ]);
+
+        assert_eq!(representative_frame(&detections), Some(2));
+    }
+
+    #[test]
+    fn test_representative_frame_none_without_faces() {
+        let detections = HashMap::from([(0, vec![]), (1, vec![])]);
+
+        assert_eq!(representative_frame(&detections), None);
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+
+use crate::detection::infrastructure::cache_key_params::{CacheKeyParam, CacheKeyParams};
+use crate::shared::schema::{Schema, SchemaError};
+use crate::shared::track_time_rules::{TimeRange, TrackTimeRules};
+
+pub const SELECTION_VERSION: u32 = 1;
+
+pub const SELECTION_SCHEMA: Schema = Schema::new("Face selection", SELECTION_VERSION, &[]);
+
+#[derive(Debug, Error)]
+pub enum SelectionError {
+    #[error("Could not access face selection {path}: {source}")]
+    Io { path: PathBuf, source: io::Error },
+    #[error("Face selection is not valid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Schema(#[from] SchemaError),
+    #[error("Malformed face selection: {0}")]
+    Malformed(&'static str),
+}
+
+/// One track of a [`FaceSelection`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SelectedTrack {
+    pub track_id: u32,
+    pub label: String,
+    pub blurred: bool,
+    /// When not empty, the track is only blurred inside these ranges.
+    pub blur_ranges: Vec<TimeRange>,
+}
+
+/// Which faces of a scan to blur, saved so the choice made in one place
+/// can be rendered in another: picked in the desktop app, exported by the
+/// CLI on a machine with more cores.
+///
+/// Track IDs only mean something for the scan that assigned them, so the
+/// selection carries that scan's detection settings (`scan`). The same
+/// input detected with the same settings gets the same IDs; a renderer
+/// should check [`changed_settings`](Self::changed_settings) before
+/// trusting them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FaceSelection {
+    /// File name of the scanned input.
+    pub source: String,
+    pub scan: CacheKeyParams,
+    pub tracks: Vec<SelectedTrack>,
+}
+
+impl FaceSelection {
+    pub fn load(path: &Path) -> Result<Self, SelectionError> {
+        let json = fs::read_to_string(path).map_err(|source| SelectionError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_json(&json)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SelectionError> {
+        fs::write(path, self.to_json()).map_err(|source| SelectionError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// The tracks to blur.
+    pub fn blur_ids(&self) -> HashSet<u32> {
+        self.tracks
+            .iter()
+            .filter(|track| track.blurred)
+            .map(|track| track.track_id)
+            .collect()
+    }
+
+    /// Each blurred track's ranges as time rules.
+    pub fn time_rules(&self) -> TrackTimeRules {
+        let mut rules = TrackTimeRules::new();
+        for track in self.tracks.iter().filter(|track| track.blurred) {
+            for &range in &track.blur_ranges {
+                rules.blur_during(track.track_id, range);
+            }
+        }
+        rules
+    }
+
+    /// Settings of the scan that `params` differ in, for a renderer about
+    /// to detect with `params`. Settings the scan didn't record are left
+    /// out.
+    pub fn changed_settings(&self, params: &CacheKeyParams) -> Vec<CacheKeyParam> {
+        self.scan
+            .changed(params)
+            .into_iter()
+            .filter(|&param| self.scan.contains(param))
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        let scan: Map<String, Value> = self
+            .scan
+            .iter()
+            .map(|(param, value)| (param.name().to_string(), json!(value)))
+            .collect();
+        let tracks: Vec<Value> = self
+            .tracks
+            .iter()
+            .map(|track| {
+                json!({
+                    "track_id": track.track_id,
+                    "label": track.label,
+                    "blurred": track.blurred,
+                    "blur_ranges": track
+                        .blur_ranges
+                        .iter()
+                        .map(|range| json!([range.start, range.end]))
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        serde_json::to_string_pretty(&json!({
+            "version": SELECTION_SCHEMA.version(),
+            "source": self.source,
+            "scan": scan,
+            "tracks": tracks,
+        }))
+        .unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, SelectionError> {
+        let document = SELECTION_SCHEMA.upgrade(serde_json::from_str(json)?)?;
+        let scan = document["scan"]
+            .as_object()
+            .ok_or(SelectionError::Malformed("no scan settings"))?
+            .iter()
+            // Settings this build doesn't know can't be compared; drop them.
+            .filter_map(|(name, value)| Some((CacheKeyParam::from_name(name)?, value.as_str()?)))
+            .fold(CacheKeyParams::new(), |params, (param, value)| {
+                params.with(param, value)
+            });
+        let tracks = document["tracks"]
+            .as_array()
+            .ok_or(SelectionError::Malformed("no track list"))?
+            .iter()
+            .map(track_from_json)
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            source: document["source"].as_str().unwrap_or_default().to_string(),
+            scan,
+            tracks,
+        })
+    }
+}
+
+fn track_from_json(track: &Value) -> Result<SelectedTrack, SelectionError> {
+    let track_id = track["track_id"]
+        .as_u64()
+        .and_then(|id| u32::try_from(id).ok())
+        .ok_or(SelectionError::Malformed("a track has no ID"))?;
+    let blur_ranges = match track.get("blur_ranges") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(ranges) => ranges
+            .as_array()
+            .ok_or(SelectionError::Malformed("blur ranges are not a list"))?
+            .iter()
+            .map(|range| match range.as_array().map(Vec::as_slice) {
+                Some([start, end]) => Some(TimeRange::new(start.as_f64()?, end.as_f64()?)),
+                _ => None,
+            })
+            .collect::<Option<_>>()
+            .ok_or(SelectionError::Malformed(
+                "a blur range is not [start, end]",
+            ))?,
+    };
+    Ok(SelectedTrack {
+        track_id,
+        label: track["label"].as_str().unwrap_or_default().to_string(),
+        blurred: track["blurred"]
+            .as_bool()
+            .ok_or(SelectionError::Malformed("a track has no blurred flag"))?,
+        blur_ranges,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(confidence: &str) -> CacheKeyParams {
+        CacheKeyParams::new()
+            .with(CacheKeyParam::Model, "yolo11n-pose_widerface.onnx")
+            .with(CacheKeyParam::Confidence, confidence)
+    }
+
+    fn selection() -> FaceSelection {
+        FaceSelection {
+            source: "talk.mp4".into(),
+            scan: scan("0.5"),
+            tracks: vec![
+                SelectedTrack {
+                    track_id: 1,
+                    label: "Host".into(),
+                    blurred: false,
+                    blur_ranges: Vec::new(),
+                },
+                SelectedTrack {
+                    track_id: 4,
+                    label: "Guest".into(),
+                    blurred: true,
+                    blur_ranges: vec![TimeRange::new(10.0, 20.5)],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_round_trips_through_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("selection.json");
+
+        selection().save(&path).unwrap();
+
+        assert_eq!(FaceSelection::load(&path).unwrap(), selection());
+    }
+
+    #[test]
+    fn test_blurs_selected_tracks_in_their_ranges() {
+        let selection = selection();
+        assert_eq!(selection.blur_ids(), HashSet::from([4]));
+        let rules = selection.time_rules();
+        assert!(rules.allows(Some(4), 15.0));
+        assert!(!rules.allows(Some(4), 25.0));
+    }
+
+    #[test]
+    fn test_changed_settings_ignore_settings_scan_did_not_record() {
+        let params = scan("0.4").with(CacheKeyParam::BakeRotation, false);
+        assert_eq!(
+            selection().changed_settings(&params),
+            vec![CacheKeyParam::Confidence]
+        );
+        assert!(selection()
+            .changed_settings(&scan("0.5").with(CacheKeyParam::BakeRotation, false))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_unknown_scan_settings_are_skipped() {
+        let json = json!({
+            "version": SELECTION_VERSION,
+            "scan": { "confidence": "0.5", "sharpness": "2" },
+            "tracks": [],
+        });
+        let selection = FaceSelection::from_json(&json.to_string()).unwrap();
+        assert_eq!(
+            selection.scan,
+            CacheKeyParams::new().with(CacheKeyParam::Confidence, "0.5")
+        );
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let json = json!({ "version": SELECTION_VERSION + 1, "scan": {}, "tracks": [] });
+        assert!(matches!(
+            FaceSelection::from_json(&json.to_string()),
+            Err(SelectionError::Schema(_))
+        ));
+    }
+
+    #[test]
+    fn test_track_without_id_is_error() {
+        let json =
+            json!({ "version": SELECTION_VERSION, "scan": {}, "tracks": [{ "blurred": true }] });
+        assert!(matches!(
+            FaceSelection::from_json(&json.to_string()),
+            Err(SelectionError::Malformed(_))
+        ));
+    }
+}
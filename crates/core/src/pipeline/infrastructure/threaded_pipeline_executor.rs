@@ -1,14 +1,20 @@
 use std::collections::VecDeque;
 use std::path::Path;
-use std::sync::atomic::Ordering;
 
 use crate::blurring::domain::frame_blurrer::FrameBlurrer;
 use crate::detection::domain::face_detector::FaceDetector;
 use crate::detection::domain::region_merger::RegionMerger;
+use crate::detection::domain::track_duration_filter::TrackDurationFilter;
 use crate::pipeline::pipeline_executor::{PipelineConfig, PipelineExecutor};
+use crate::shared::cancellation::{CancelReason, CancellationToken, Cancelled};
+use crate::shared::forced_tracks::ForcedTracks;
 use crate::shared::frame::Frame;
+use crate::shared::job_error::JobError;
 use crate::shared::region::Region;
+use crate::shared::track_time_rules::TrackTimeRules;
 use crate::shared::video_metadata::VideoMetadata;
+use crate::shared::warnings::{WarningKind, Warnings};
+use crate::video::domain::decode_error_policy::DecodeErrorPolicy;
 use crate::video::domain::video_reader::VideoReader;
 use crate::video::domain::video_writer::VideoWriter;
 
@@ -16,10 +22,38 @@ const DEFAULT_CHANNEL_CAPACITY: usize = 8;
 
 type SendError = Box<dyn std::error::Error + Send + Sync>;
 
+/// A blurred frame plus, when a reference output is open, its original.
+type WriteItem = (Frame, Option<Frame>);
+
+type Writers = (Box<dyn VideoWriter>, Option<Box<dyn VideoWriter>>);
+
+/// Last blurred frame and the regions it was blurred with.
+type PreviousOutput = Option<(Frame, Vec<Region>)>;
+
+/// A frame and its selected regions, as handed from detection to the main
+/// loop.
+struct DetectedFrame {
+    frame: Frame,
+    regions: Vec<Region>,
+    /// Pixel-identical to the previous frame outside that frame's selected
+    /// regions, so its detections were reused instead of recomputed.
+    unchanged: bool,
+}
+
 /// Executes the blur pipeline with dedicated threads for I/O and detection.
 ///
 /// Layout: `reader → detect → main [buffer/merge/blur] → writer`
 ///
+/// With a reference output configured, the writer thread also writes each
+/// frame's unblurred copy to the reference writer.
+///
+/// With `reuse_unchanged_frames`, the detect thread compares each frame to
+/// the previous one outside the regions selected for blurring. A match
+/// reuses the previous detections, and if the merged regions also match,
+/// the main loop writes the previous blurred frame again instead of
+/// blurring. Nothing outside a blurred region changed, so no new face can
+/// have appeared; only blurred content inside the regions can be stale.
+///
 /// Detection and I/O run concurrently so they overlap, improving throughput
 /// when detection is the bottleneck.
 pub struct ThreadedPipelineExecutor {
@@ -50,30 +84,55 @@ impl PipelineExecutor for ThreadedPipelineExecutor {
         merger: RegionMerger,
         metadata: &VideoMetadata,
         output_path: &Path,
-        config: PipelineConfig,
+        mut config: PipelineConfig,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let frame_w = metadata.width;
         let frame_h = metadata.height;
         let total_frames = metadata.total_frames;
         let cap = self.channel_capacity;
 
-        writer.open(output_path, metadata)?;
+        writer
+            .open(output_path, metadata)
+            .map_err(|e| JobError::output(e.to_string()))?;
+        let reference_writer = match config.reference.take() {
+            Some(mut reference) => {
+                reference
+                    .writer
+                    .open(&reference.path, metadata)
+                    .map_err(|e| JobError::output(e.to_string()))?;
+                Some(reference.writer)
+            }
+            None => None,
+        };
+        let keep_original = reference_writer.is_some();
 
         let (frame_tx, frame_rx) = crossbeam_channel::bounded::<Result<Frame, SendError>>(cap);
         let (detected_tx, detected_rx) =
-            crossbeam_channel::bounded::<Result<(Frame, Vec<Region>), SendError>>(cap);
-        let (write_tx, write_rx) = crossbeam_channel::bounded::<Frame>(cap);
+            crossbeam_channel::bounded::<Result<DetectedFrame, SendError>>(cap);
+        let (write_tx, write_rx) = crossbeam_channel::bounded::<WriteItem>(cap);
 
-        let reader_handle = spawn_reader(reader, frame_tx, config.cancelled.clone());
+        let reader_handle = spawn_reader(
+            reader,
+            frame_tx,
+            config.cancelled.clone(),
+            config.decode_errors,
+            config.warnings.clone(),
+        );
         let detect_handle = spawn_detector(
             detector,
             frame_rx,
             detected_tx,
             config.cancelled.clone(),
-            config.blur_ids.clone(),
-            config.exclude_ids.clone(),
+            RegionFilter {
+                blur_ids: config.blur_ids.clone(),
+                exclude_ids: config.exclude_ids.clone(),
+                time_rules: config.time_rules.clone(),
+                forced: config.forced_tracks.clone(),
+                fps: metadata.fps,
+            },
+            config.reuse_unchanged_frames,
         );
-        let writer_handle = spawn_writer(writer, write_rx);
+        let writer_handle = spawn_writer(writer, reference_writer, write_rx);
 
         let main_error = run_main_loop(
             detected_rx,
@@ -83,6 +142,7 @@ impl PipelineExecutor for ThreadedPipelineExecutor {
             frame_w,
             frame_h,
             total_frames,
+            keep_original,
             &config,
         );
 
@@ -92,49 +152,134 @@ impl PipelineExecutor for ThreadedPipelineExecutor {
     }
 }
 
+/// Reads frames, applying `policy` to frames the reader fails to produce.
+///
+/// Substituted frames take the next index and later frames are shifted to
+/// follow it, so indices stay sequential for detection caches and time
+/// rules.
 fn spawn_reader(
     mut reader: Box<dyn VideoReader>,
     frame_tx: crossbeam_channel::Sender<Result<Frame, SendError>>,
-    cancelled: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    cancelled: CancellationToken,
+    policy: DecodeErrorPolicy,
+    warnings: Warnings,
 ) -> std::thread::JoinHandle<Box<dyn VideoReader>> {
     std::thread::spawn(move || {
+        let mut previous: Option<Frame> = None;
+        let mut inserted: usize = 0;
+        let mut tolerated: usize = 0;
         for frame_result in reader.frames() {
-            if cancelled.load(Ordering::Relaxed) {
+            if cancelled.is_cancelled() {
                 break;
             }
-            let mapped = frame_result.map_err(|e| -> SendError { e.to_string().into() });
-            if frame_tx.send(mapped).is_err() {
+            let item = match (frame_result, policy) {
+                (Ok(frame), _) => {
+                    let frame = if inserted > 0 {
+                        let index = frame.index() + inserted;
+                        frame.with_index(index)
+                    } else {
+                        frame
+                    };
+                    if policy == DecodeErrorPolicy::DuplicatePrevious {
+                        previous = Some(frame.clone());
+                    }
+                    Ok(frame)
+                }
+                (Err(e), DecodeErrorPolicy::Fail) => {
+                    Err(Box::new(JobError::input(e.to_string())) as SendError)
+                }
+                (Err(e), _) => {
+                    log::warn!("Frame read failed: {e}");
+                    tolerated += 1;
+                    match previous.as_ref() {
+                        Some(last) => {
+                            inserted += 1;
+                            let repeat = last.clone().with_index(last.index() + 1);
+                            previous = Some(repeat.clone());
+                            Ok(repeat)
+                        }
+                        None => continue,
+                    }
+                }
+            };
+            if frame_tx.send(item).is_err() {
                 break;
             }
         }
+        if tolerated > 0 {
+            warnings.push(
+                WarningKind::Decode,
+                format!(
+                    "{tolerated} frame(s) could not be read and {}",
+                    policy.outcome()
+                ),
+            );
+        }
         reader.close();
         reader
     })
 }
 
+/// Track selection applied in the detect thread: ID sets first, then
+/// per-track time windows, then forced tracks are added back.
+struct RegionFilter {
+    blur_ids: Option<std::collections::HashSet<u32>>,
+    exclude_ids: Option<std::collections::HashSet<u32>>,
+    time_rules: TrackTimeRules,
+    forced: ForcedTracks,
+    fps: f64,
+}
+
+impl RegionFilter {
+    fn apply(&self, regions: &[Region], frame_index: usize) -> Vec<Region> {
+        let selected = Region::filter(regions, self.blur_ids.as_ref(), self.exclude_ids.as_ref());
+        let selected = self.time_rules.filter(&selected, frame_index, self.fps);
+        self.forced.restore(regions, selected)
+    }
+}
+
 fn spawn_detector(
     mut detector: Box<dyn FaceDetector>,
     frame_rx: crossbeam_channel::Receiver<Result<Frame, SendError>>,
-    detected_tx: crossbeam_channel::Sender<Result<(Frame, Vec<Region>), SendError>>,
-    cancelled: std::sync::Arc<std::sync::atomic::AtomicBool>,
-    blur_ids: Option<std::collections::HashSet<u32>>,
-    exclude_ids: Option<std::collections::HashSet<u32>>,
+    detected_tx: crossbeam_channel::Sender<Result<DetectedFrame, SendError>>,
+    cancelled: CancellationToken,
+    filter: RegionFilter,
+    reuse_unchanged: bool,
 ) -> std::thread::JoinHandle<Box<dyn FaceDetector>> {
     std::thread::spawn(move || {
+        // Previous frame with its raw detections and selected regions.
+        let mut previous: Option<(Frame, Vec<Region>, Vec<Region>)> = None;
         for frame_result in frame_rx {
-            if cancelled.load(Ordering::Relaxed) {
+            if cancelled.is_cancelled() {
                 break;
             }
 
             let result = match frame_result {
-                Ok(frame) => match detector.detect(&frame) {
-                    Ok(regions) => {
-                        let filtered =
-                            Region::filter(&regions, blur_ids.as_ref(), exclude_ids.as_ref());
-                        Ok((frame, filtered))
+                Ok(frame) => {
+                    let reusable = previous
+                        .as_ref()
+                        .filter(|(last, _, selected)| frame.matches_outside(last, selected))
+                        .map(|(_, detected, _)| detected.clone());
+                    let unchanged = reusable.is_some();
+                    let detected = match reusable {
+                        Some(detected) => Ok(detected),
+                        None => detector.detect(&frame),
+                    };
+                    match detected {
+                        Ok(detected) => {
+                            let regions = filter.apply(&detected, frame.index());
+                            if reuse_unchanged {
+                                previous = Some((frame.clone(), detected, regions.clone()));
+                            }
+                            Ok(DetectedFrame {
+                                frame,
+                                regions,
+                                unchanged,
+                            })
+                        }
+                        Err(e) => Err(e.to_string().into()),
                     }
-                    Err(e) => Err(e.to_string().into()),
-                },
+                }
                 Err(e) => Err(e),
             };
 
@@ -148,15 +293,20 @@ fn spawn_detector(
 
 fn spawn_writer(
     mut writer: Box<dyn VideoWriter>,
-    write_rx: crossbeam_channel::Receiver<Frame>,
-) -> std::thread::JoinHandle<Result<Box<dyn VideoWriter>, SendError>> {
+    mut reference_writer: Option<Box<dyn VideoWriter>>,
+    write_rx: crossbeam_channel::Receiver<WriteItem>,
+) -> std::thread::JoinHandle<Result<Writers, SendError>> {
     std::thread::spawn(move || {
-        for frame in write_rx {
-            writer
-                .write(&frame)
-                .map_err(|e| -> SendError { e.to_string().into() })?;
+        let to_send = |e: Box<dyn std::error::Error>| -> SendError {
+            Box::new(JobError::output(e.to_string()))
+        };
+        for (frame, original) in write_rx {
+            writer.write(&frame).map_err(to_send)?;
+            if let (Some(reference), Some(original)) = (reference_writer.as_mut(), original) {
+                reference.write(&original).map_err(to_send)?;
+            }
         }
-        Ok(writer)
+        Ok((writer, reference_writer))
     })
 }
 
@@ -164,29 +314,32 @@ fn spawn_writer(
 /// merge regions, blur, and send to writer.
 #[allow(clippy::too_many_arguments)]
 fn run_main_loop(
-    detected_rx: crossbeam_channel::Receiver<Result<(Frame, Vec<Region>), SendError>>,
-    write_tx: &crossbeam_channel::Sender<Frame>,
+    detected_rx: crossbeam_channel::Receiver<Result<DetectedFrame, SendError>>,
+    write_tx: &crossbeam_channel::Sender<WriteItem>,
     merger: &RegionMerger,
     blurrer: &dyn FrameBlurrer,
     frame_w: u32,
     frame_h: u32,
     total_frames: usize,
+    keep_original: bool,
     config: &PipelineConfig,
 ) -> Option<Box<dyn std::error::Error>> {
-    let mut buffer: VecDeque<(Frame, Vec<Region>)> = VecDeque::new();
+    let mut buffer: VecDeque<DetectedFrame> = VecDeque::new();
     let mut frames_processed: usize = 0;
+    let mut previous_output: PreviousOutput = None;
+    let mut durations = TrackDurationFilter::new(config.min_track_frames);
 
     for detected_result in detected_rx {
-        if config.cancelled.load(Ordering::Relaxed) {
-            break;
+        if let Err(e) = config.cancelled.check() {
+            return Some(Box::new(e));
         }
 
-        let (frame, filtered) = match detected_result {
-            Ok(pair) => pair,
-            Err(e) => return Some(e.to_string().into()),
+        let detected = match detected_result {
+            Ok(detected) => detected,
+            Err(e) => return Some(e as Box<dyn std::error::Error>),
         };
 
-        buffer.push_back((frame, filtered));
+        buffer.push_back(detected);
 
         if buffer.len() > config.lookahead {
             if let Err(e) = flush_oldest(
@@ -197,7 +350,10 @@ fn run_main_loop(
                 frame_h,
                 write_tx,
                 &mut frames_processed,
+                &mut previous_output,
+                &mut durations,
                 total_frames,
+                keep_original,
                 config,
             ) {
                 return Some(e);
@@ -206,8 +362,8 @@ fn run_main_loop(
     }
 
     while !buffer.is_empty() {
-        if config.cancelled.load(Ordering::Relaxed) {
-            break;
+        if let Err(e) = config.cancelled.check() {
+            return Some(Box::new(e));
         }
         if let Err(e) = flush_oldest(
             &mut buffer,
@@ -217,21 +373,30 @@ fn run_main_loop(
             frame_h,
             write_tx,
             &mut frames_processed,
+            &mut previous_output,
+            &mut durations,
             total_frames,
+            keep_original,
             config,
         ) {
             return Some(e);
         }
     }
 
-    None
+    // The reader stops early on cancellation, which ends the loops above
+    // without an error.
+    config
+        .cancelled
+        .check()
+        .err()
+        .map(|e| Box::new(e) as Box<dyn std::error::Error>)
 }
 
 /// Joins all pipeline threads and coalesces the first error encountered.
 fn join_threads(
     reader_handle: std::thread::JoinHandle<Box<dyn VideoReader>>,
     detect_handle: std::thread::JoinHandle<Box<dyn FaceDetector>>,
-    writer_handle: std::thread::JoinHandle<Result<Box<dyn VideoWriter>, SendError>>,
+    writer_handle: std::thread::JoinHandle<Result<Writers, SendError>>,
     mut first_error: Option<Box<dyn std::error::Error>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     fn set_if_none(slot: &mut Option<Box<dyn std::error::Error>>, err: Box<dyn std::error::Error>) {
@@ -250,12 +415,17 @@ fn join_threads(
     }
 
     match writer_handle.join() {
-        Ok(Ok(mut w)) => {
+        Ok(Ok((mut w, reference))) => {
             if let Err(e) = w.close() {
-                set_if_none(&mut first_error, e);
+                set_if_none(&mut first_error, Box::new(JobError::output(e.to_string())));
+            }
+            if let Some(mut r) = reference {
+                if let Err(e) = r.close() {
+                    set_if_none(&mut first_error, Box::new(JobError::output(e.to_string())));
+                }
             }
         }
-        Ok(Err(e)) => set_if_none(&mut first_error, e.to_string().into()),
+        Ok(Err(e)) => set_if_none(&mut first_error, e),
         Err(_) => set_if_none(&mut first_error, "Writer thread panicked".into()),
     }
 
@@ -267,35 +437,74 @@ fn join_threads(
 
 #[allow(clippy::too_many_arguments)]
 fn flush_oldest(
-    buffer: &mut VecDeque<(Frame, Vec<Region>)>,
+    buffer: &mut VecDeque<DetectedFrame>,
     merger: &RegionMerger,
     blurrer: &dyn FrameBlurrer,
     frame_w: u32,
     frame_h: u32,
-    write_tx: &crossbeam_channel::Sender<Frame>,
+    write_tx: &crossbeam_channel::Sender<WriteItem>,
     frames_processed: &mut usize,
+    previous_output: &mut PreviousOutput,
+    durations: &mut TrackDurationFilter,
     total_frames: usize,
+    keep_original: bool,
     config: &PipelineConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let (mut frame, own_regions) = buffer.pop_front().unwrap();
+    let DetectedFrame {
+        mut frame,
+        regions: own_regions,
+        unchanged,
+    } = buffer.pop_front().unwrap();
 
     let lookahead_regions: Vec<&[Region]> = buffer
         .iter()
-        .map(|(_, regions)| regions.as_slice())
+        .map(|detected| detected.regions.as_slice())
+        .collect();
+
+    let window: Vec<&[Region]> = std::iter::once(own_regions.as_slice())
+        .chain(lookahead_regions.iter().copied())
         .collect();
+    let short = durations.short_tracks(&window);
+    durations.record(&own_regions);
+    let kept_lookahead: Vec<Vec<Region>>;
+    let (own_regions, lookahead_regions) = if short.is_empty() {
+        (own_regions, lookahead_regions)
+    } else {
+        kept_lookahead = lookahead_regions
+            .iter()
+            .map(|regions| Region::filter(regions, None, Some(&short)))
+            .collect();
+        (
+            Region::filter(&own_regions, None, Some(&short)),
+            kept_lookahead.iter().map(Vec::as_slice).collect(),
+        )
+    };
 
     let merged = merger.merge(&own_regions, &lookahead_regions, frame_w, frame_h);
-    blurrer.blur(&mut frame, &merged)?;
+    let original = keep_original.then(|| frame.clone());
+    match previous_output.take() {
+        Some((last, last_regions)) if unchanged && last_regions == merged => {
+            let reused = last.with_index(frame.index());
+            frame = match frame.timestamp() {
+                Some(t) => reused.with_timestamp(t),
+                None => reused,
+            };
+        }
+        _ => blurrer.blur(&mut frame, &merged)?,
+    }
+    if config.reuse_unchanged_frames {
+        *previous_output = Some((frame.clone(), merged));
+    }
 
     write_tx
-        .send(frame)
+        .send((frame, original))
         .map_err(|_| "Writer channel closed unexpectedly")?;
 
     *frames_processed += 1;
 
     if let Some(ref callback) = config.on_progress {
         if !callback(*frames_processed, total_frames) {
-            return Err("Cancelled".into());
+            return Err(Box::new(Cancelled(CancelReason::User)));
         }
     }
 
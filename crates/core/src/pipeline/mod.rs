@@ -1,7 +1,21 @@
+pub mod anonymization_ledger;
+#[cfg(feature = "async")]
+pub mod async_job;
 pub mod blur_faces_use_case;
 pub mod blur_image_use_case;
+pub mod blur_preset;
+pub mod blur_verification;
+pub mod detection_report;
+pub mod export_frame_use_case;
+pub mod face_selection;
 pub mod infrastructure;
+pub mod parallel_segments_use_case;
 pub mod pipeline_executor;
 pub mod pipeline_logger;
 pub mod preview_faces_use_case;
+pub mod preview_frame_use_case;
 pub mod process_audio_use_case;
+pub mod report_inspection;
+pub mod restore_faces_use_case;
+pub mod resume_checkpoint;
+pub mod speech_association;
@@ -0,0 +1,602 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
+use crate::detection::domain::face_detector::FaceDetector;
+use crate::detection::domain::region_merger::RegionMerger;
+use crate::shared::cancellation::{CancelReason, CancellationToken, Cancelled};
+use crate::shared::constants::TRACKER_MAX_LOST;
+use crate::shared::forced_tracks::ForcedTracks;
+use crate::shared::frame::Frame;
+use crate::shared::job_error::{JobError, JobErrorKind};
+use crate::shared::track_time_rules::{TimeRange, TrackTimeRules};
+use crate::shared::video_metadata::VideoMetadata;
+use crate::shared::warnings::Warnings;
+use crate::video::domain::decode_error_policy::DecodeErrorPolicy;
+use crate::video::domain::video_reader::VideoReader;
+use crate::video::domain::video_writer::VideoWriter;
+use crate::video::infrastructure::parted_writer::{join_parts, part_path, PartWriterFactory};
+
+use super::infrastructure::threaded_pipeline_executor::ThreadedPipelineExecutor;
+use super::pipeline_executor::{PipelineConfig, PipelineExecutor};
+
+const DEFAULT_LOOKAHEAD: usize = 5;
+
+/// Segments shorter than this aren't worth a seek and a cold tracker.
+pub const MIN_SEGMENT_FRAMES: usize = 300;
+
+/// The components one segment is processed with. Each segment runs on
+/// its own threads, so none of them are shared.
+pub struct SegmentWorker {
+    pub reader: Box<dyn VideoReader>,
+    pub detector: Box<dyn FaceDetector>,
+    pub blurrer: Box<dyn FrameBlurrer>,
+}
+
+/// Splits `total_frames` into at most `count` contiguous ranges of at
+/// least `min_frames` frames each, as even as possible. An unknown length
+/// (0) is one open-ended range.
+pub fn segment_ranges(total_frames: usize, count: usize, min_frames: usize) -> Vec<Range<usize>> {
+    if total_frames == 0 {
+        return vec![0..usize::MAX];
+    }
+    let count = count
+        .min(total_frames / min_frames.max(1))
+        .clamp(1, total_frames);
+    (0..count)
+        .map(|i| i * total_frames / count..(i + 1) * total_frames / count)
+        .collect()
+}
+
+/// Blurs a long video as several segments at once and joins them into
+/// the output without re-encoding.
+///
+/// `BlurFacesUseCase` overlaps reading, detection, blurring, and encoding
+/// of one frame stream, which leaves cores idle once one stage is the
+/// bottleneck. Here every [`SegmentWorker`] runs that whole pipeline on
+/// its own stretch of the video, seeking its reader to the start, and
+/// writes a part file beside the output (see [`part_path`]). Each segment
+/// reads `lookahead` frames past its end so faces about to appear are
+/// blurred early as usual; those frames are then dropped. Each segment
+/// after the first also starts `TRACKER_MAX_LOST` + `lookahead` frames
+/// early, so its tracker already follows the faces on screen at the
+/// boundary, including ones only detected with low confidence; those
+/// frames are dropped too. The parts are joined, with the source audio,
+/// once every segment has finished, and deleted.
+///
+/// Track IDs still restart in each segment and don't identify a face
+/// across the video. Track selection is therefore not offered, time rules
+/// only apply to every face at once, and every track is blurred however
+/// briefly it is seen (no `min_track_frames`).
+pub struct ParallelSegmentsUseCase {
+    workers: Vec<SegmentWorker>,
+    new_writer: PartWriterFactory,
+    lookahead: usize,
+    time_rules: TrackTimeRules,
+    decode_errors: DecodeErrorPolicy,
+    reuse_unchanged_frames: bool,
+    skip_audio_passthrough: bool,
+    warnings: Warnings,
+    on_progress: Option<Box<dyn Fn(usize, usize) -> bool + Send>>,
+    cancelled: CancellationToken,
+}
+
+impl ParallelSegmentsUseCase {
+    /// One segment per worker, unless the video is too short to give each
+    /// [`MIN_SEGMENT_FRAMES`]; `new_writer` builds the writer for each
+    /// part.
+    pub fn new(
+        workers: Vec<SegmentWorker>,
+        new_writer: PartWriterFactory,
+        lookahead: Option<usize>,
+        on_progress: Option<Box<dyn Fn(usize, usize) -> bool + Send>>,
+        cancelled: Option<CancellationToken>,
+    ) -> Self {
+        Self {
+            workers,
+            new_writer,
+            lookahead: lookahead.unwrap_or(DEFAULT_LOOKAHEAD),
+            time_rules: TrackTimeRules::new(),
+            decode_errors: DecodeErrorPolicy::default(),
+            reuse_unchanged_frames: false,
+            skip_audio_passthrough: false,
+            warnings: Warnings::new(),
+            on_progress,
+            cancelled: cancelled.unwrap_or_default(),
+        }
+    }
+
+    /// Blurs every face during `ranges`, whatever else applies (see
+    /// [`TrackTimeRules::blur_all_during`]). Per-track rules aren't offered,
+    /// since track IDs restart in each segment.
+    pub fn with_blur_all_during(mut self, ranges: Vec<TimeRange>) -> Self {
+        for range in ranges {
+            self.time_rules.blur_all_during(range);
+        }
+        self
+    }
+
+    pub fn with_decode_error_policy(mut self, policy: DecodeErrorPolicy) -> Self {
+        self.decode_errors = policy;
+        self
+    }
+
+    pub fn with_reuse_unchanged_frames(mut self, reuse: bool) -> Self {
+        self.reuse_unchanged_frames = reuse;
+        self
+    }
+
+    pub fn with_warnings(mut self, warnings: Warnings) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    /// Leaves the source audio out of the joined output, for callers that
+    /// mux processed audio afterwards.
+    pub fn set_skip_audio_passthrough(&mut self, skip: bool) {
+        self.skip_audio_passthrough = skip;
+    }
+
+    pub fn execute(
+        &mut self,
+        input_path: &Path,
+        output_path: &Path,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut workers = std::mem::take(&mut self.workers);
+        if workers.is_empty() {
+            return Err("Pipeline already executed".into());
+        }
+        let metadata = workers[0]
+            .reader
+            .open(input_path)
+            .map_err(|e| JobError::input(e.to_string()))?;
+        let ranges = segment_ranges(metadata.total_frames, workers.len(), MIN_SEGMENT_FRAMES);
+        workers.truncate(ranges.len());
+        let parts: Vec<PathBuf> = (0..ranges.len())
+            .map(|i| part_path(output_path, i))
+            .collect();
+        log::info!("Processing {} segments in parallel", ranges.len());
+
+        // Parts get the audio once, when they are joined.
+        let part_metadata = VideoMetadata {
+            source_path: None,
+            ..metadata.clone()
+        };
+        let cancelled = self.cancelled.child();
+        let progress = SegmentProgress {
+            done: Arc::new(ranges.iter().map(|_| AtomicUsize::new(0)).collect()),
+            total: metadata.total_frames,
+            on_progress: Arc::new(Mutex::new(self.on_progress.take())),
+        };
+
+        let results: Vec<Result<(), SegmentFailure>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = workers
+                .into_iter()
+                .zip(&ranges)
+                .zip(&parts)
+                .enumerate()
+                .map(|(i, ((worker, range), part))| {
+                    let warmup = if i > 0 {
+                        warmup_frames(self.lookahead).min(range.start)
+                    } else {
+                        0
+                    };
+                    let writer = Box::new(SegmentWriter {
+                        inner: (self.new_writer)(),
+                        range: range.clone(),
+                    });
+                    let config = PipelineConfig {
+                        lookahead: self.lookahead,
+                        blur_ids: None,
+                        exclude_ids: None,
+                        time_rules: self.time_rules.clone(),
+                        forced_tracks: ForcedTracks::new(),
+                        // A track cut by a boundary would be counted in each
+                        // segment separately and could stay unblurred.
+                        min_track_frames: 0,
+                        reference: None,
+                        decode_errors: self.decode_errors,
+                        reuse_unchanged_frames: self.reuse_unchanged_frames,
+                        warnings: self.warnings.clone(),
+                        on_progress: Some(progress.for_segment(i, warmup, range.len())),
+                        cancelled: cancelled.clone(),
+                    };
+                    let (metadata, cancelled) = (&part_metadata, cancelled.clone());
+                    scope.spawn(move || {
+                        let result = run_segment(
+                            worker,
+                            writer,
+                            input_path,
+                            i > 0,
+                            range.start - warmup..range.end,
+                            part,
+                            metadata,
+                            config,
+                        )
+                        .map_err(|e| SegmentFailure::from_error(&*e));
+                        if result.is_err() {
+                            // One failed segment fails the job; stop the rest.
+                            cancelled.cancel(CancelReason::User);
+                        }
+                        result
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap_or(Err(SegmentFailure::Panicked)))
+                .collect()
+        });
+
+        // Segments stopped because another failed are collateral; report
+        // the failure that caused it.
+        let failure = results
+            .iter()
+            .filter_map(|r| r.as_ref().err())
+            .min_by_key(|failure| matches!(failure, SegmentFailure::Cancelled(_)));
+        let joined = match failure {
+            Some(failure) => Err(failure.to_error(&self.cancelled)),
+            None => join_parts(
+                &parts,
+                output_path,
+                &metadata,
+                self.skip_audio_passthrough,
+                &self.warnings,
+            )
+            .map_err(|e| JobError::output(e.to_string()).into()),
+        };
+        for part in parts.iter().filter(|part| part.exists()) {
+            if let Err(e) = std::fs::remove_file(part) {
+                log::warn!("Could not delete part {}: {e}", part.display());
+            }
+        }
+        joined
+    }
+}
+
+/// Frames a segment reads before its start: as long as the tracker keeps
+/// a lost face, plus the lookahead, so the faces it carries over and the
+/// regions merged ahead of them are as they would be in one pass.
+fn warmup_frames(lookahead: usize) -> usize {
+    TRACKER_MAX_LOST + lookahead
+}
+
+/// Runs the pipeline over `range` of the input, which includes any warm-up
+/// frames, writing `part`. `open` is false for the first worker, whose
+/// reader was opened for the metadata.
+#[allow(clippy::too_many_arguments)]
+fn run_segment(
+    mut worker: SegmentWorker,
+    writer: Box<dyn VideoWriter>,
+    input_path: &Path,
+    open: bool,
+    range: Range<usize>,
+    part: &Path,
+    metadata: &VideoMetadata,
+    config: PipelineConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if open {
+        worker
+            .reader
+            .open(input_path)
+            .map_err(|e| JobError::input(e.to_string()))?;
+    }
+    if range.start > 0 {
+        worker.reader.seek(range.start)?;
+    }
+    let reader = Box::new(SegmentReader {
+        inner: worker.reader,
+        end: range.end.saturating_add(config.lookahead),
+    });
+    ThreadedPipelineExecutor::new().execute(
+        reader,
+        writer,
+        worker.detector,
+        worker.blurrer,
+        RegionMerger::new(),
+        metadata,
+        part,
+        config,
+    )
+}
+
+/// Sums the frames every segment has finished for the caller's progress
+/// callback.
+struct SegmentProgress {
+    done: Arc<Vec<AtomicUsize>>,
+    total: usize,
+    on_progress: Arc<Mutex<Option<Box<dyn Fn(usize, usize) -> bool + Send>>>>,
+}
+
+impl SegmentProgress {
+    /// The callback for a segment of `frames` frames, read after `warmup`
+    /// frames. Frames read before its start or past its end belong to
+    /// another segment and aren't counted.
+    fn for_segment(
+        &self,
+        segment: usize,
+        warmup: usize,
+        frames: usize,
+    ) -> Box<dyn Fn(usize, usize) -> bool + Send> {
+        let (done, total, on_progress) = (self.done.clone(), self.total, self.on_progress.clone());
+        Box::new(move |current, _| {
+            let current = current.saturating_sub(warmup).min(frames);
+            done[segment].store(current, Ordering::Relaxed);
+            let sum = done.iter().map(|d| d.load(Ordering::Relaxed)).sum();
+            match *on_progress.lock().unwrap_or_else(|e| e.into_inner()) {
+                Some(ref callback) => callback(sum, total),
+                None => true,
+            }
+        })
+    }
+}
+
+/// How a segment's thread ended, in a form that can cross threads.
+enum SegmentFailure {
+    Cancelled(CancelReason),
+    Failed {
+        kind: Option<JobErrorKind>,
+        message: String,
+    },
+    Panicked,
+}
+
+impl SegmentFailure {
+    fn from_error(error: &(dyn std::error::Error + 'static)) -> Self {
+        match Cancelled::reason_of(error) {
+            Some(reason) => Self::Cancelled(reason),
+            None => Self::Failed {
+                kind: JobError::kind_of(error),
+                message: error.to_string(),
+            },
+        }
+    }
+
+    /// The job's error. A cancelled segment takes the reason from the
+    /// caller's token, since a failed sibling cancels with its own.
+    fn to_error(&self, cancelled: &CancellationToken) -> Box<dyn std::error::Error> {
+        match self {
+            Self::Cancelled(reason) => Box::new(Cancelled(cancelled.reason().unwrap_or(*reason))),
+            Self::Failed {
+                kind: Some(JobErrorKind::Input),
+                message,
+            } => Box::new(JobError::input(message.clone())),
+            Self::Failed {
+                kind: Some(JobErrorKind::Output),
+                message,
+            } => Box::new(JobError::output(message.clone())),
+            Self::Failed {
+                kind: None,
+                message,
+            } => message.clone().into(),
+            Self::Panicked => "A segment stopped unexpectedly".into(),
+        }
+    }
+}
+
+/// Stops reading at `end`, past which another segment takes over.
+struct SegmentReader {
+    inner: Box<dyn VideoReader>,
+    end: usize,
+}
+
+impl VideoReader for SegmentReader {
+    fn open(&mut self, path: &Path) -> Result<VideoMetadata, Box<dyn std::error::Error>> {
+        self.inner.open(path)
+    }
+
+    fn frames(
+        &mut self,
+    ) -> Box<dyn Iterator<Item = Result<Frame, Box<dyn std::error::Error>>> + '_> {
+        let end = self.end;
+        Box::new(
+            self.inner
+                .frames()
+                .take_while(move |frame| frame.as_ref().map_or(true, |f| f.index() < end)),
+        )
+    }
+
+    fn seek(&mut self, frame_index: usize) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.seek(frame_index)
+    }
+
+    fn close(&mut self) {
+        self.inner.close();
+    }
+}
+
+/// Drops the warm-up frames read before the segment's start and the
+/// lookahead frames read past its end.
+struct SegmentWriter {
+    inner: Box<dyn VideoWriter>,
+    range: Range<usize>,
+}
+
+impl VideoWriter for SegmentWriter {
+    fn open(
+        &mut self,
+        path: &Path,
+        metadata: &VideoMetadata,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.open(path, metadata)
+    }
+
+    fn write(&mut self, frame: &Frame) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.range.contains(&frame.index()) {
+            return Ok(());
+        }
+        self.inner.write(frame)
+    }
+
+    fn close(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.close()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::color_space::ColorSpace;
+    use crate::shared::region::Region;
+    use crate::video::infrastructure::ffmpeg_reader::FfmpegReader;
+    use crate::video::infrastructure::ffmpeg_writer::FfmpegWriter;
+
+    // --- Stubs ---
+
+    /// Logs the frames it is given and finds no faces.
+    struct LoggingDetector {
+        seen: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl FaceDetector for LoggingDetector {
+        fn detect(&mut self, frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+            self.seen.lock().unwrap().push(frame.index());
+            Ok(Vec::new())
+        }
+    }
+
+    struct FailingDetector;
+
+    impl FaceDetector for FailingDetector {
+        fn detect(&mut self, _frame: &Frame) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+            Err("detector error".into())
+        }
+    }
+
+    struct PassthroughBlurrer;
+
+    impl FrameBlurrer for PassthroughBlurrer {
+        fn blur(
+            &self,
+            _frame: &mut Frame,
+            _regions: &[Region],
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+    }
+
+    // --- Helpers ---
+
+    const FRAMES: usize = 2 * MIN_SEGMENT_FRAMES;
+
+    /// A `FRAMES`-frame video at 30 fps.
+    fn write_source(path: &Path) {
+        let metadata = VideoMetadata {
+            width: 64,
+            height: 48,
+            fps: 30.0,
+            total_frames: 0,
+            codec: String::new(),
+            source_path: None,
+            rotation: 0,
+            color: ColorSpace::default(),
+        };
+        let mut writer = FfmpegWriter::new();
+        writer.open(path, &metadata).unwrap();
+        for i in 0..FRAMES {
+            let frame = Frame::new(vec![(i % 256) as u8; 64 * 48 * 3], 64, 48, 3, i)
+                .with_timestamp(i as f64 / 30.0);
+            writer.write(&frame).unwrap();
+        }
+        writer.close().unwrap();
+    }
+
+    fn worker(detector: Box<dyn FaceDetector>) -> SegmentWorker {
+        SegmentWorker {
+            reader: Box::new(FfmpegReader::new()),
+            detector,
+            blurrer: Box::new(PassthroughBlurrer),
+        }
+    }
+
+    fn use_case(workers: Vec<SegmentWorker>) -> ParallelSegmentsUseCase {
+        ParallelSegmentsUseCase::new(
+            workers,
+            Box::new(|| Box::new(FfmpegWriter::new())),
+            Some(5),
+            None,
+            None,
+        )
+    }
+
+    fn frame_count(path: &Path) -> usize {
+        let mut reader = FfmpegReader::new();
+        reader.open(path).unwrap();
+        reader.frames().map(|f| f.unwrap()).count()
+    }
+
+    // --- Tests ---
+
+    #[test]
+    fn test_segment_ranges_split_evenly() {
+        assert_eq!(
+            segment_ranges(1000, 3, 100),
+            vec![0..333, 333..666, 666..1000]
+        );
+    }
+
+    #[test]
+    fn test_short_video_gets_fewer_segments() {
+        assert_eq!(segment_ranges(250, 4, 100), vec![0..125, 125..250]);
+        assert_eq!(segment_ranges(50, 4, 100), vec![0..50]);
+    }
+
+    #[test]
+    fn test_unknown_length_is_one_segment() {
+        assert_eq!(segment_ranges(0, 4, 100), vec![0..usize::MAX]);
+    }
+
+    #[test]
+    fn test_joins_segments_into_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let (input, output) = (dir.path().join("in.mp4"), dir.path().join("out.mp4"));
+        write_source(&input);
+        let (first, second) = (Arc::default(), Arc::default());
+        let mut uc = use_case(vec![
+            worker(Box::new(LoggingDetector {
+                seen: Arc::clone(&first),
+            })),
+            worker(Box::new(LoggingDetector {
+                seen: Arc::clone(&second),
+            })),
+        ]);
+
+        uc.execute(&input, &output).unwrap();
+
+        assert_eq!(frame_count(&output), FRAMES);
+        assert!(!part_path(&output, 0).exists(), "parts should be deleted");
+        let first = first.lock().unwrap();
+        let second = second.lock().unwrap();
+        assert_eq!(
+            second.first(),
+            Some(&(MIN_SEGMENT_FRAMES - warmup_frames(5))),
+            "the second segment should warm its tracker up before its start"
+        );
+        assert_eq!(
+            first.last(),
+            Some(&(MIN_SEGMENT_FRAMES + 4)),
+            "the first segment should read the lookahead past its end"
+        );
+    }
+
+    #[test]
+    fn test_failed_segment_fails_job() {
+        let dir = tempfile::tempdir().unwrap();
+        let (input, output) = (dir.path().join("in.mp4"), dir.path().join("out.mp4"));
+        write_source(&input);
+        let mut uc = use_case(vec![
+            worker(Box::new(LoggingDetector {
+                seen: Arc::default(),
+            })),
+            worker(Box::new(FailingDetector)),
+        ]);
+
+        let error = uc.execute(&input, &output).unwrap_err();
+
+        assert!(error.to_string().contains("detector error"));
+        assert!(!output.exists());
+        assert!(!part_path(&output, 0).exists(), "parts should be deleted");
+    }
+}
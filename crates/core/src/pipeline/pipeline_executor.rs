@@ -1,22 +1,50 @@
 use std::collections::HashSet;
-use std::path::Path;
-use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
 
 use crate::blurring::domain::frame_blurrer::FrameBlurrer;
 use crate::detection::domain::face_detector::FaceDetector;
 use crate::detection::domain::region_merger::RegionMerger;
+use crate::shared::cancellation::CancellationToken;
+use crate::shared::forced_tracks::ForcedTracks;
+use crate::shared::track_time_rules::TrackTimeRules;
 use crate::shared::video_metadata::VideoMetadata;
+use crate::shared::warnings::Warnings;
+use crate::video::domain::decode_error_policy::DecodeErrorPolicy;
 use crate::video::domain::video_reader::VideoReader;
 use crate::video::domain::video_writer::VideoWriter;
 
+/// Second sink that receives every frame before blurring, so an internal
+/// reference copy shares the public output's trims and frame timing.
+pub struct ReferenceOutput {
+    pub writer: Box<dyn VideoWriter>,
+    pub path: PathBuf,
+}
+
 /// Configuration for a pipeline execution run.
 pub struct PipelineConfig {
     pub lookahead: usize,
     pub blur_ids: Option<HashSet<u32>>,
     pub exclude_ids: Option<HashSet<u32>>,
+    pub time_rules: TrackTimeRules,
+    /// Tracks blurred even where the ID sets and time rules would leave
+    /// them visible.
+    pub forced_tracks: ForcedTracks,
+    /// Tracks seen on fewer frames are not blurred (see
+    /// `TrackDurationFilter`); 0 or 1 blurs every track.
+    pub min_track_frames: usize,
+    pub reference: Option<ReferenceOutput>,
+    /// Applied to frames the reader fails to produce.
+    pub decode_errors: DecodeErrorPolicy,
+    /// Skip detection and blurring for frames that are pixel-identical to
+    /// the previous one outside its blurred regions, writing the previous
+    /// output again instead.
+    pub reuse_unchanged_frames: bool,
+    pub warnings: Warnings,
     pub on_progress: Option<Box<dyn Fn(usize, usize) -> bool + Send>>,
-    pub cancelled: Arc<AtomicBool>,
+    /// Stops the run with a [`Cancelled`] error.
+    ///
+    /// [`Cancelled`]: crate::shared::cancellation::Cancelled
+    pub cancelled: CancellationToken,
 }
 
 /// Abstracts how the read → detect → blur → write pipeline is executed.
@@ -1,8 +1,13 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
 use crate::detection::domain::face_detector::FaceDetector;
+use crate::detection::domain::face_grouper::FaceGrouper;
+use crate::detection::domain::track_duration_filter::short_tracks_in;
+use crate::shared::cancellation::{CancelReason, CancellationToken, Cancelled};
 use crate::shared::frame::Frame;
+use crate::shared::job_error::JobError;
 use crate::shared::region::Region;
 use crate::shared::video_metadata::VideoMetadata;
 use crate::video::domain::image_writer::ImageWriter;
@@ -10,22 +15,44 @@ use crate::video::domain::video_reader::VideoReader;
 
 const PREVIEW_SIZE: u32 = 256;
 
+/// Frames waiting for crop scoring before detection blocks.
+const CROP_CHANNEL_CAPACITY: usize = 8;
+
+/// Fewest frames between two ranked crops of one track (1 s at 30 fps), so
+/// they show different moments rather than near-identical neighbors.
+pub const RANKED_CROP_MIN_GAP: usize = 30;
+
 pub type PreviewResult = (HashMap<u32, PathBuf>, HashMap<usize, Vec<Region>>);
 
 /// Best crop found so far per track ID: maps track_id → (area, cropped frame).
 type BestCrops = HashMap<u32, (u32, Frame)>;
 
+/// Largest crops per track ID, largest first: (area, frame index, crop).
+type RankedCrops = HashMap<u32, Vec<(u32, usize, Frame)>>;
+
 type DetectionCache = HashMap<usize, Vec<Region>>;
 
 /// Scans a video for faces and saves the best crop of each tracked identity.
 ///
 /// Selects the largest detection per track ID (by area), giving downstream
 /// grouping and the UI the clearest possible thumbnail.
+///
+/// Decoding and detection run on the calling thread; crops are scored on a
+/// second thread, and the thumbnails are encoded on a worker pool once the
+/// scan is done. Progress follows detection.
 pub struct PreviewFacesUseCase {
     reader: Box<dyn VideoReader>,
     detector: Box<dyn FaceDetector>,
     image_writer: Box<dyn ImageWriter>,
     on_progress: Option<Box<dyn Fn(usize, usize) -> bool + Send>>,
+    crop_blurrer: Option<Box<dyn FrameBlurrer>>,
+    sharp_crops: HashMap<u32, Frame>,
+    grouper: Option<Box<dyn FaceGrouper>>,
+    groups: Vec<Vec<u32>>,
+    min_track_frames: usize,
+    ranked_crop_count: usize,
+    ranked_crops: RankedCrops,
+    cancelled: CancellationToken,
 }
 
 impl PreviewFacesUseCase {
@@ -40,73 +67,274 @@ impl PreviewFacesUseCase {
             detector,
             image_writer,
             on_progress,
+            crop_blurrer: None,
+            sharp_crops: HashMap::new(),
+            grouper: None,
+            groups: Vec::new(),
+            min_track_frames: 0,
+            ranked_crop_count: 0,
+            ranked_crops: HashMap::new(),
+            cancelled: CancellationToken::new(),
         }
     }
 
+    /// Stops the scan with a [`Cancelled`] error once `token` is cancelled,
+    /// checked before each frame.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancelled = token;
+        self
+    }
+
+    /// Blurs each thumbnail with `blurrer` before it is written, so no
+    /// recognizable face is stored on disk. The sharp crops are kept in
+    /// memory for display; collect them with [`take_sharp_crops`].
+    ///
+    /// [`take_sharp_crops`]: Self::take_sharp_crops
+    pub fn with_crop_blurrer(mut self, blurrer: Box<dyn FrameBlurrer>) -> Self {
+        self.crop_blurrer = Some(blurrer);
+        self
+    }
+
+    /// Unblurred crops by track ID from the last run with a crop blurrer,
+    /// at full crop resolution. Empty otherwise.
+    pub fn take_sharp_crops(&mut self) -> HashMap<u32, Frame> {
+        std::mem::take(&mut self.sharp_crops)
+    }
+
+    /// Groups the best crops by identity before they are saved (and before
+    /// any crop blurring). Collect the result with [`take_groups`].
+    ///
+    /// [`take_groups`]: Self::take_groups
+    pub fn with_grouper(mut self, grouper: Box<dyn FaceGrouper>) -> Self {
+        self.grouper = Some(grouper);
+        self
+    }
+
+    /// Drops tracks seen on fewer than `frames` frames from the crops, so
+    /// likely false positives get no thumbnail. The detection cache keeps
+    /// them: it holds raw detector output, and the blur applies the same
+    /// filter when replaying it.
+    pub fn with_min_track_frames(mut self, frames: usize) -> Self {
+        self.min_track_frames = frames;
+        self
+    }
+
+    /// Also keeps up to `count` unblurred crops per track in memory, largest
+    /// first and at least [`RANKED_CROP_MIN_GAP`] frames apart, for showing
+    /// a track or group as a collage. Collect them with
+    /// [`take_ranked_crops`].
+    ///
+    /// [`take_ranked_crops`]: Self::take_ranked_crops
+    pub fn with_ranked_crops(mut self, count: usize) -> Self {
+        self.ranked_crop_count = count;
+        self
+    }
+
+    /// Ranked crops by track ID from the last run with
+    /// [`with_ranked_crops`], best first, at full crop resolution. Empty
+    /// otherwise.
+    ///
+    /// [`with_ranked_crops`]: Self::with_ranked_crops
+    pub fn take_ranked_crops(&mut self) -> HashMap<u32, Vec<Frame>> {
+        std::mem::take(&mut self.ranked_crops)
+            .into_iter()
+            .map(|(track_id, crops)| {
+                let crops = crops.into_iter().map(|(_, _, crop)| crop).collect();
+                (track_id, crops)
+            })
+            .collect()
+    }
+
+    /// Track ID groups from the last run with a grouper. Empty otherwise.
+    pub fn take_groups(&mut self) -> Vec<Vec<u32>> {
+        std::mem::take(&mut self.groups)
+    }
+
     /// Scans all frames, saves 256x256 thumbnails, and returns a detection cache.
     ///
     /// Returns `(crops_by_id, detection_cache)` where the detection cache maps
-    /// frame indices to regions for reuse in the blur pass.
+    /// frame indices to regions for reuse in the blur pass. Crops are turned
+    /// upright by `metadata.rotation`; the cache stays in decoded frame
+    /// pixels, like the frames the blur pass reads.
     pub fn execute(
         &mut self,
         metadata: &VideoMetadata,
         output_dir: &Path,
     ) -> Result<PreviewResult, Box<dyn std::error::Error>> {
-        let (best_crops, detection_cache) = self.scan_frames(metadata.total_frames)?;
+        let (mut best_crops, detection_cache) =
+            self.scan_frames(metadata.total_frames, Some(metadata.rotation))?;
+        let short = short_tracks_in(&detection_cache, self.min_track_frames);
+        best_crops.retain(|track_id, _| !short.contains(track_id));
+        self.ranked_crops
+            .retain(|track_id, _| !short.contains(track_id));
+        if let Some(ref grouper) = self.grouper {
+            self.groups = group_crops(grouper.as_ref(), &best_crops)?;
+        }
         let saved_paths = self.save_crops(best_crops, output_dir)?;
         Ok((saved_paths, detection_cache))
     }
 
+    /// Runs detection only and returns the detection cache. No crops are
+    /// kept or written, so a report of what the detector sees costs no
+    /// more than the detection itself.
+    pub fn scan(
+        &mut self,
+        metadata: &VideoMetadata,
+    ) -> Result<HashMap<usize, Vec<Region>>, Box<dyn std::error::Error>> {
+        let (_, detection_cache) = self.scan_frames(metadata.total_frames, None)?;
+        Ok(detection_cache)
+    }
+
+    /// Detects faces on every frame, keeping crops turned clockwise by
+    /// `crop_rotation` when it is given.
     fn scan_frames(
         &mut self,
         total_frames: usize,
+        crop_rotation: Option<i32>,
     ) -> Result<(BestCrops, DetectionCache), Box<dyn std::error::Error>> {
-        let mut best_crops: BestCrops = HashMap::new();
         let mut detection_cache: DetectionCache = HashMap::new();
+        let keep_crops = crop_rotation.is_some();
+        let rotation = crop_rotation.unwrap_or(0);
+        let ranked_count = if keep_crops {
+            self.ranked_crop_count
+        } else {
+            0
+        };
 
         let reader = &mut self.reader;
         let detector = &mut self.detector;
         let on_progress = &self.on_progress;
-
-        for result in reader.frames() {
-            let frame = result?;
-            let regions = detector.detect(&frame)?;
-
-            detection_cache.insert(frame.index(), regions.clone());
-            update_best_crops(&mut best_crops, &frame, &regions);
-
-            if let Some(ref callback) = on_progress {
-                if !callback(frame.index() + 1, total_frames) {
-                    return Err("Cancelled".into());
+        let cancelled = &self.cancelled;
+        let ranked_crops = &mut self.ranked_crops;
+        ranked_crops.clear();
+
+        let (crop_tx, crop_rx) =
+            crossbeam_channel::bounded::<(Frame, Vec<Region>)>(CROP_CHANNEL_CAPACITY);
+        let (scanned, best_crops) = std::thread::scope(|scope| {
+            // Crops are scored and copied in frame order on their own
+            // thread, while the next frames are decoded and detected.
+            let scorer = scope.spawn(move || {
+                let mut best_crops: BestCrops = HashMap::new();
+                for (frame, regions) in crop_rx {
+                    update_best_crops(&mut best_crops, &frame, &regions, rotation);
+                    if ranked_count > 0 {
+                        update_ranked_crops(ranked_crops, &frame, &regions, ranked_count, rotation);
+                    }
                 }
-            }
-        }
+                best_crops
+            });
+            let scanned = (|| -> Result<(), Box<dyn std::error::Error>> {
+                for result in reader.frames() {
+                    cancelled.check()?;
+                    let frame = result?;
+                    let regions = detector.detect(&frame)?;
+                    let index = frame.index();
+
+                    if keep_crops && regions.iter().any(|r| r.track_id.is_some()) {
+                        crop_tx
+                            .send((frame, regions.clone()))
+                            .map_err(|_| "Preview crop thread stopped")?;
+                    }
+                    detection_cache.insert(index, regions);
+
+                    if let Some(ref callback) = on_progress {
+                        if !callback(index + 1, total_frames) {
+                            return Err(Box::new(Cancelled(CancelReason::User)));
+                        }
+                    }
+                }
+                Ok(())
+            })();
+            drop(crop_tx);
+            (scanned, scorer.join())
+        });
+        scanned?;
+        let best_crops = best_crops.map_err(|_| "Preview crop thread panicked")?;
 
         self.reader.close();
         Ok((best_crops, detection_cache))
     }
 
     fn save_crops(
-        &self,
-        mut best_crops: BestCrops,
+        &mut self,
+        best_crops: BestCrops,
         output_dir: &Path,
     ) -> Result<HashMap<u32, PathBuf>, Box<dyn std::error::Error>> {
-        let mut sorted_ids: Vec<u32> = best_crops.keys().copied().collect();
-        sorted_ids.sort();
-
-        let mut saved = HashMap::new();
-        for track_id in sorted_ids {
-            let (_, crop) = best_crops.remove(&track_id).unwrap();
-            let path = output_dir.join(format!("{track_id}.jpg"));
-            self.image_writer
-                .write(&path, &crop, Some((PREVIEW_SIZE, PREVIEW_SIZE)))?;
-            saved.insert(track_id, path);
+        let mut thumbnails: Vec<(u32, PathBuf, Frame)> = best_crops
+            .into_iter()
+            .map(|(track_id, (_, crop))| {
+                let path = output_dir.join(format!("{track_id}.jpg"));
+                (track_id, path, crop)
+            })
+            .collect();
+        thumbnails.sort_by_key(|&(track_id, ..)| track_id);
+
+        if let Some(ref blurrer) = self.crop_blurrer {
+            for (track_id, _, crop) in &mut thumbnails {
+                self.sharp_crops.insert(*track_id, crop.clone());
+                let whole = whole_frame_region(crop);
+                blurrer.blur(crop, &[whole])?;
+            }
+        }
+        write_thumbnails(self.image_writer.as_ref(), &thumbnails)?;
+        Ok(thumbnails
+            .into_iter()
+            .map(|(track_id, path, _)| (track_id, path))
+            .collect())
+    }
+}
+
+/// Resizes and encodes the thumbnails on up to one thread per core.
+fn write_thumbnails(
+    writer: &dyn ImageWriter,
+    thumbnails: &[(u32, PathBuf, Frame)],
+) -> Result<(), Box<dyn std::error::Error>> {
+    if thumbnails.is_empty() {
+        return Ok(());
+    }
+    let workers = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(thumbnails.len());
+    let chunk_size = thumbnails.len().div_ceil(workers);
+    std::thread::scope(|scope| -> Result<(), Box<dyn std::error::Error>> {
+        let handles: Vec<_> = thumbnails
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    for (_, path, crop) in chunk {
+                        writer
+                            .write(path, crop, Some((PREVIEW_SIZE, PREVIEW_SIZE)))
+                            .map_err(|e| JobError::output(e.to_string()))?;
+                    }
+                    Ok::<(), JobError>(())
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle
+                .join()
+                .map_err(|_| "Thumbnail encoding thread panicked")??;
         }
-        Ok(saved)
+        Ok(())
+    })
+}
+
+fn group_crops(
+    grouper: &dyn FaceGrouper,
+    best_crops: &BestCrops,
+) -> Result<Vec<Vec<u32>>, Box<dyn std::error::Error>> {
+    if best_crops.is_empty() {
+        return Ok(Vec::new());
     }
+    let mut crops: Vec<(u32, &[u8], u32, u32)> = best_crops
+        .iter()
+        .map(|(&id, (_, crop))| (id, crop.data(), crop.width(), crop.height()))
+        .collect();
+    crops.sort_by_key(|&(id, ..)| id);
+    grouper.group(&crops)
 }
 
-fn update_best_crops(best: &mut BestCrops, frame: &Frame, regions: &[Region]) {
+fn update_best_crops(best: &mut BestCrops, frame: &Frame, regions: &[Region], rotation: i32) {
     for r in regions {
         let Some(track_id) = r.track_id else {
             continue;
@@ -114,45 +342,65 @@ fn update_best_crops(best: &mut BestCrops, frame: &Frame, regions: &[Region]) {
         let area = r.width as u32 * r.height as u32;
         let is_largest = best.get(&track_id).map_or(true, |(prev, _)| area > *prev);
         if is_largest {
-            best.insert(track_id, (area, square_crop(frame, r)));
+            best.insert(track_id, (area, frame.square_crop(r).rotated(rotation)));
         }
     }
 }
 
-fn square_crop(frame: &Frame, region: &Region) -> Frame {
-    let fw = frame.width() as i32;
-    let fh = frame.height() as i32;
-
-    let cx = region.x + region.width / 2;
-    let cy = region.y + region.height / 2;
-    let half = region.width.max(region.height) / 2;
-
-    let x1 = (cx - half).max(0) as usize;
-    let y1 = (cy - half).max(0) as usize;
-    let x2 = (cx + half).min(fw) as usize;
-    let y2 = (cy + half).min(fh) as usize;
-
-    let crop_w = x2 - x1;
-    let crop_h = y2 - y1;
-    let channels = frame.channels() as usize;
-
-    let src = frame.as_ndarray();
-    let mut data = Vec::with_capacity(crop_w * crop_h * channels);
-
-    for row in y1..y2 {
-        for col in x1..x2 {
-            for c in 0..channels {
-                data.push(src[[row, col, c]]);
+/// Keeps the `count` largest crops per track that are at least
+/// `RANKED_CROP_MIN_GAP` frames apart. A crop near an already ranked one
+/// replaces it only if larger, so each ranked crop stands for one moment.
+fn update_ranked_crops(
+    ranked: &mut RankedCrops,
+    frame: &Frame,
+    regions: &[Region],
+    count: usize,
+    rotation: i32,
+) {
+    for r in regions {
+        let Some(track_id) = r.track_id else {
+            continue;
+        };
+        let area = r.width as u32 * r.height as u32;
+        let crops = ranked.entry(track_id).or_default();
+        let nearby = crops
+            .iter()
+            .position(|&(_, index, _)| frame.index().abs_diff(index) < RANKED_CROP_MIN_GAP);
+        match nearby {
+            Some(pos) if area <= crops[pos].0 => continue,
+            Some(pos) => {
+                crops.remove(pos);
             }
+            None if crops.len() >= count && crops.last().is_some_and(|c| area <= c.0) => continue,
+            None => {}
         }
+        let at = crops.partition_point(|&(ranked_area, ..)| ranked_area >= area);
+        crops.insert(
+            at,
+            (area, frame.index(), frame.square_crop(r).rotated(rotation)),
+        );
+        crops.truncate(count);
     }
+}
 
-    Frame::new(data, crop_w as u32, crop_h as u32, channels as u8, 0)
+fn whole_frame_region(frame: &Frame) -> Region {
+    Region {
+        x: 0,
+        y: 0,
+        width: frame.width() as i32,
+        height: frame.height() as i32,
+        track_id: None,
+        full_width: None,
+        full_height: None,
+        unclamped_x: None,
+        unclamped_y: None,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shared::color_space::ColorSpace;
     use std::sync::{Arc, Mutex};
 
     // --- Stubs ---
@@ -223,6 +471,33 @@ mod tests {
         }
     }
 
+    struct ZeroingBlurrer;
+
+    impl FrameBlurrer for ZeroingBlurrer {
+        fn blur(
+            &self,
+            frame: &mut Frame,
+            regions: &[Region],
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            assert_eq!(regions.len(), 1);
+            assert_eq!(regions[0].width, frame.width() as i32);
+            frame.data_mut().fill(0);
+            Ok(())
+        }
+    }
+
+    /// Puts every track in one group, in the order the crops arrive.
+    struct OneGroupGrouper;
+
+    impl FaceGrouper for OneGroupGrouper {
+        fn group(
+            &self,
+            crops: &[(u32, &[u8], u32, u32)],
+        ) -> Result<Vec<Vec<u32>>, Box<dyn std::error::Error>> {
+            Ok(vec![crops.iter().map(|&(id, ..)| id).collect()])
+        }
+    }
+
     // --- Helpers ---
 
     fn make_frame(index: usize, w: u32, h: u32) -> Frame {
@@ -238,6 +513,7 @@ mod tests {
             codec: String::new(),
             source_path: None,
             rotation: 0,
+            color: ColorSpace::default(),
         }
     }
 
@@ -325,6 +601,40 @@ mod tests {
         assert_eq!(written[0].1.height(), 30);
     }
 
+    #[test]
+    fn test_crops_are_turned_upright_by_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let img_writer = StubImageWriter::new();
+        let written = img_writer.written.clone();
+
+        // Marks the top-left pixel of the face.
+        let mut frame = Frame::new(vec![0; 100 * 100 * 3], 100, 100, 3, 0);
+        frame.data_mut()[(10 * 100 + 10) * 3] = 255;
+        let face = region(10, 10, 20, 20, Some(1));
+
+        let mut uc = PreviewFacesUseCase::new(
+            Box::new(StubReader::new(vec![frame])),
+            Box::new(StubDetector {
+                results: HashMap::from([(0, vec![face.clone()])]),
+            }),
+            Box::new(img_writer),
+            None,
+        );
+        let rotated = VideoMetadata {
+            rotation: 90,
+            ..metadata(100, 100, 1)
+        };
+
+        let (_, cache) = uc.execute(&rotated, dir.path()).unwrap();
+
+        // Turned clockwise, the mark ends up top right.
+        let written = written.lock().unwrap();
+        let crop = written[0].1.data();
+        assert_eq!(crop[0], 0);
+        assert_eq!(crop[19 * 3], 255);
+        assert_eq!(cache[&0], vec![face], "cache stays in decoded pixels");
+    }
+
     #[test]
     fn test_detection_cache_populated() {
         let dir = tempfile::tempdir().unwrap();
@@ -352,6 +662,119 @@ mod tests {
         assert_eq!(cache[&1].len(), 0);
     }
 
+    #[test]
+    fn test_min_track_frames_drops_brief_tracks() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut det_results = HashMap::new();
+        det_results.insert(
+            0,
+            vec![
+                region(10, 10, 20, 20, Some(1)),
+                region(50, 50, 20, 20, Some(2)),
+            ],
+        );
+        det_results.insert(1, vec![region(10, 10, 20, 20, Some(1))]);
+        det_results.insert(2, vec![region(10, 10, 20, 20, Some(1))]);
+
+        let mut uc = PreviewFacesUseCase::new(
+            Box::new(StubReader::new(
+                (0..3).map(|i| make_frame(i, 100, 100)).collect(),
+            )),
+            Box::new(StubDetector {
+                results: det_results,
+            }),
+            Box::new(StubImageWriter::new()),
+            None,
+        )
+        .with_min_track_frames(3);
+
+        let (crops, cache) = uc.execute(&metadata(100, 100, 3), dir.path()).unwrap();
+
+        assert_eq!(crops.keys().collect::<Vec<_>>(), vec![&1]);
+        assert_eq!(cache[&0].len(), 2);
+    }
+
+    #[test]
+    fn test_ranked_crops_are_largest_first_and_spaced_apart() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // Track 1: a small face at frame 0, a larger one at frame 1 (same
+        // moment), then medium and small faces later on.
+        let mut det_results = HashMap::new();
+        det_results.insert(0, vec![region(10, 10, 10, 10, Some(1))]);
+        det_results.insert(1, vec![region(10, 10, 40, 40, Some(1))]);
+        det_results.insert(40, vec![region(10, 10, 30, 30, Some(1))]);
+        det_results.insert(80, vec![region(10, 10, 20, 20, Some(1))]);
+        det_results.insert(120, vec![region(10, 10, 16, 16, Some(1))]);
+
+        let mut uc = PreviewFacesUseCase::new(
+            Box::new(StubReader::new(
+                (0..121).map(|i| make_frame(i, 100, 100)).collect(),
+            )),
+            Box::new(StubDetector {
+                results: det_results,
+            }),
+            Box::new(StubImageWriter::new()),
+            None,
+        )
+        .with_ranked_crops(3);
+
+        uc.execute(&metadata(100, 100, 121), dir.path()).unwrap();
+        let ranked = uc.take_ranked_crops();
+
+        let sizes: Vec<u32> = ranked[&1].iter().map(|crop| crop.width()).collect();
+        assert_eq!(sizes, vec![40, 30, 20]);
+        assert!(uc.take_ranked_crops().is_empty());
+    }
+
+    #[test]
+    fn test_no_ranked_crops_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut det_results = HashMap::new();
+        det_results.insert(0, vec![region(10, 10, 20, 20, Some(1))]);
+
+        let mut uc = PreviewFacesUseCase::new(
+            Box::new(StubReader::new(vec![make_frame(0, 100, 100)])),
+            Box::new(StubDetector {
+                results: det_results,
+            }),
+            Box::new(StubImageWriter::new()),
+            None,
+        );
+
+        uc.execute(&metadata(100, 100, 1), dir.path()).unwrap();
+        assert!(uc.take_ranked_crops().is_empty());
+    }
+
+    #[test]
+    fn test_scan_returns_detections_without_writing_crops() {
+        let img_writer = StubImageWriter::new();
+        let written = img_writer.written.clone();
+
+        let mut det_results = HashMap::new();
+        det_results.insert(0, vec![region(10, 10, 20, 20, Some(1))]);
+        det_results.insert(1, vec![region(12, 10, 20, 20, Some(1))]);
+
+        let mut uc = PreviewFacesUseCase::new(
+            Box::new(StubReader::new(
+                (0..2).map(|i| make_frame(i, 100, 100)).collect(),
+            )),
+            Box::new(StubDetector {
+                results: det_results,
+            }),
+            Box::new(img_writer),
+            None,
+        );
+
+        let cache = uc.scan(&metadata(100, 100, 2)).unwrap();
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache[&1][0].x, 12);
+        assert!(written.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn test_none_track_id_skipped() {
         let dir = tempfile::tempdir().unwrap();
@@ -425,6 +848,42 @@ mod tests {
         assert_eq!(written[0].0, dir.path().join("42.jpg"));
     }
 
+    #[test]
+    fn test_every_track_is_written_by_the_encoding_pool() {
+        let dir = tempfile::tempdir().unwrap();
+        let img_writer = StubImageWriter::new();
+        let written = img_writer.written.clone();
+
+        let mut det_results = HashMap::new();
+        let regions = (0..20)
+            .map(|i| region(i * 5, 10, 4, 4, Some(i as u32)))
+            .collect();
+        det_results.insert(0, regions);
+
+        let mut uc = PreviewFacesUseCase::new(
+            Box::new(StubReader::new(vec![make_frame(0, 100, 100)])),
+            Box::new(StubDetector {
+                results: det_results,
+            }),
+            Box::new(img_writer),
+            None,
+        );
+
+        let (crops, _) = uc.execute(&metadata(100, 100, 1), dir.path()).unwrap();
+
+        assert_eq!(crops.len(), 20);
+        let mut paths: Vec<PathBuf> = written
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(path, ..)| path.clone())
+            .collect();
+        paths.sort();
+        let mut expected: Vec<PathBuf> = crops.into_values().collect();
+        expected.sort();
+        assert_eq!(paths, expected);
+    }
+
     #[test]
     fn test_cancel_via_progress() {
         let dir = tempfile::tempdir().unwrap();
@@ -446,8 +905,31 @@ mod tests {
             Some(Box::new(|current, _total| current < 2)), // cancel after 2
         );
 
-        let result = uc.execute(&metadata(100, 100, 3), dir.path());
-        assert!(result.is_err());
+        let error = uc.execute(&metadata(100, 100, 3), dir.path()).unwrap_err();
+        assert_eq!(Cancelled::reason_of(&*error), Some(CancelReason::User));
+    }
+
+    #[test]
+    fn test_cancel_via_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let token = CancellationToken::new();
+        token.cancel(CancelReason::Superseded);
+
+        let mut uc = PreviewFacesUseCase::new(
+            Box::new(StubReader::new(vec![make_frame(0, 100, 100)])),
+            Box::new(StubDetector {
+                results: HashMap::new(),
+            }),
+            Box::new(StubImageWriter::new()),
+            None,
+        )
+        .with_cancellation(token.child());
+
+        let error = uc.execute(&metadata(100, 100, 3), dir.path()).unwrap_err();
+        assert_eq!(
+            Cancelled::reason_of(&*error),
+            Some(CancelReason::Superseded)
+        );
     }
 
     #[test]
@@ -473,35 +955,94 @@ mod tests {
     }
 
     #[test]
-    fn test_square_crop_basic() {
-        // 10x10 frame, region at (2,2) size 4x4
-        let frame = make_frame(0, 10, 10);
-        let r = region(2, 2, 4, 4, Some(1));
-        let crop = square_crop(&frame, &r);
-        // cx=4, cy=4, half=2 → x1=2, y1=2, x2=6, y2=6 → 4x4
-        assert_eq!(crop.width(), 4);
-        assert_eq!(crop.height(), 4);
+    fn test_crop_blurrer_anonymizes_saved_thumbnails() {
+        let dir = tempfile::tempdir().unwrap();
+        let img_writer = StubImageWriter::new();
+        let written = img_writer.written.clone();
+
+        let mut det_results = HashMap::new();
+        det_results.insert(0, vec![region(10, 10, 20, 20, Some(1))]);
+
+        let mut uc = PreviewFacesUseCase::new(
+            Box::new(StubReader::new(vec![make_frame(0, 100, 100)])),
+            Box::new(StubDetector {
+                results: det_results,
+            }),
+            Box::new(img_writer),
+            None,
+        )
+        .with_crop_blurrer(Box::new(ZeroingBlurrer));
+
+        uc.execute(&metadata(100, 100, 1), dir.path()).unwrap();
+
+        let written = written.lock().unwrap();
+        assert!(written[0].1.data().iter().all(|&b| b == 0));
+        let sharp = uc.take_sharp_crops();
+        assert_eq!(sharp.len(), 1);
+        assert!(sharp[&1].data().iter().all(|&b| b == 128));
     }
 
     #[test]
-    fn test_square_crop_clamps_to_frame() {
-        // 10x10 frame, region near edge
-        let frame = make_frame(0, 10, 10);
-        let r = region(7, 7, 6, 6, Some(1));
-        let crop = square_crop(&frame, &r);
-        // cx=10, cy=10, half=3 → x1=7, y1=7, x2=10, y2=10 → 3x3
-        assert_eq!(crop.width(), 3);
-        assert_eq!(crop.height(), 3);
+    fn test_no_sharp_crops_without_blurrer() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut det_results = HashMap::new();
+        det_results.insert(0, vec![region(10, 10, 20, 20, Some(1))]);
+
+        let mut uc = PreviewFacesUseCase::new(
+            Box::new(StubReader::new(vec![make_frame(0, 100, 100)])),
+            Box::new(StubDetector {
+                results: det_results,
+            }),
+            Box::new(StubImageWriter::new()),
+            None,
+        );
+
+        uc.execute(&metadata(100, 100, 1), dir.path()).unwrap();
+        assert!(uc.take_sharp_crops().is_empty());
     }
 
     #[test]
-    fn test_square_crop_rectangular_region_uses_max_dim() {
-        // 100x100 frame, tall region 10x30
-        let frame = make_frame(0, 100, 100);
-        let r = region(40, 35, 10, 30, Some(1));
-        let crop = square_crop(&frame, &r);
-        // cx=45, cy=50, half=15 → x1=30, y1=35, x2=60, y2=65 → 30x30
-        assert_eq!(crop.width(), 30);
-        assert_eq!(crop.height(), 30);
+    fn test_grouper_sees_every_track_sorted() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut det_results = HashMap::new();
+        det_results.insert(
+            0,
+            vec![
+                region(50, 50, 20, 20, Some(7)),
+                region(10, 10, 20, 20, Some(2)),
+            ],
+        );
+
+        let mut uc = PreviewFacesUseCase::new(
+            Box::new(StubReader::new(vec![make_frame(0, 100, 100)])),
+            Box::new(StubDetector {
+                results: det_results,
+            }),
+            Box::new(StubImageWriter::new()),
+            None,
+        )
+        .with_grouper(Box::new(OneGroupGrouper));
+
+        uc.execute(&metadata(100, 100, 1), dir.path()).unwrap();
+        assert_eq!(uc.take_groups(), vec![vec![2, 7]]);
+    }
+
+    #[test]
+    fn test_no_groups_without_grouper() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut det_results = HashMap::new();
+        det_results.insert(0, vec![region(10, 10, 20, 20, Some(1))]);
+
+        let mut uc = PreviewFacesUseCase::new(
+            Box::new(StubReader::new(vec![make_frame(0, 100, 100)])),
+            Box::new(StubDetector {
+                results: det_results,
+            }),
+            Box::new(StubImageWriter::new()),
+            None,
+        );
+
+        uc.execute(&metadata(100, 100, 1), dir.path()).unwrap();
+        assert!(uc.take_groups().is_empty());
     }
 }
@@ -0,0 +1,289 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
+use crate::detection::domain::region_merger::RegionMerger;
+use crate::pipeline::export_frame_use_case::RenderedFrame;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+use crate::shared::track_time_rules::TrackTimeRules;
+use crate::video::domain::video_reader::VideoReader;
+
+/// Blurs one frame with the detections of an earlier scan, so blur
+/// settings can be tried on a frame without exporting the video.
+///
+/// Nothing is detected: the regions for the frame and the `lookahead`
+/// frames after it come from the scan, are filtered by track and time,
+/// and are merged like the video pipeline does. Rendering is cheap enough
+/// to redo on every change to the strength, shape, or selection.
+///
+/// The scan sized its regions with its own padding. `with_padding`
+/// resizes them to another padding, so a padding change can be previewed
+/// before re-scanning; it approximates the re-scan, which also re-smooths
+/// the tracks.
+pub struct PreviewFrameUseCase {
+    reader: Box<dyn VideoReader>,
+    detections: Arc<HashMap<usize, Vec<Region>>>,
+    blurrer: Box<dyn FrameBlurrer>,
+    merger: RegionMerger,
+    lookahead: usize,
+    blur_ids: Option<HashSet<u32>>,
+    time_rules: TrackTimeRules,
+    region_scale: f64,
+}
+
+impl PreviewFrameUseCase {
+    pub fn new(
+        reader: Box<dyn VideoReader>,
+        detections: Arc<HashMap<usize, Vec<Region>>>,
+        blurrer: Box<dyn FrameBlurrer>,
+        lookahead: usize,
+    ) -> Self {
+        Self {
+            reader,
+            detections,
+            blurrer,
+            merger: RegionMerger::new(),
+            lookahead,
+            blur_ids: None,
+            time_rules: TrackTimeRules::default(),
+            region_scale: 1.0,
+        }
+    }
+
+    /// Blur only these tracks; `None` blurs every track.
+    pub fn with_blur_ids(mut self, blur_ids: Option<HashSet<u32>>) -> Self {
+        self.blur_ids = blur_ids;
+        self
+    }
+
+    /// Per-track time windows, applied after the ID set.
+    pub fn with_time_rules(mut self, rules: TrackTimeRules) -> Self {
+        self.time_rules = rules;
+        self
+    }
+
+    /// Resizes regions the scan built with `scan_padding` to `padding`,
+    /// both as fractions of the face size added around it.
+    pub fn with_padding(mut self, scan_padding: f64, padding: f64) -> Self {
+        self.region_scale = (1.0 + padding) / (1.0 + scan_padding);
+        self
+    }
+
+    /// Renders frame `frame_index` as decoded and as blurred. Frame 0
+    /// needs no seek, so single images work too.
+    pub fn render(
+        &mut self,
+        input_path: &Path,
+        frame_index: usize,
+    ) -> Result<RenderedFrame, Box<dyn std::error::Error>> {
+        let metadata = self.reader.open(input_path)?;
+        if metadata.total_frames > 0 && frame_index >= metadata.total_frames {
+            return Err(format!(
+                "Frame {frame_index} is past the end of the video ({} frames)",
+                metadata.total_frames
+            )
+            .into());
+        }
+        if frame_index > 0 {
+            self.reader.seek(frame_index)?;
+        }
+        let frames: Vec<Frame> = self
+            .reader
+            .frames()
+            .take(1 + self.lookahead)
+            .collect::<Result<_, _>>()?;
+        self.reader.close();
+
+        let mut frames = frames.into_iter();
+        let original = frames
+            .next()
+            .ok_or_else(|| format!("No frame at index {frame_index}"))?;
+        let regions_on = |frame: &Frame| -> Vec<Region> {
+            let cached = self
+                .detections
+                .get(&frame.index())
+                .map_or(&[][..], Vec::as_slice);
+            let selected = Region::filter(cached, self.blur_ids.as_ref(), None);
+            let timed = self
+                .time_rules
+                .filter(&selected, frame.index(), metadata.fps);
+            if self.region_scale == 1.0 {
+                return timed;
+            }
+            timed
+                .iter()
+                .map(|r| r.scaled(self.region_scale, frame.width(), frame.height()))
+                .collect()
+        };
+        let current = regions_on(&original);
+        let future: Vec<Vec<Region>> = frames.map(|f| regions_on(&f)).collect();
+        let future: Vec<&[Region]> = future.iter().map(Vec::as_slice).collect();
+        let regions = self
+            .merger
+            .merge(&current, &future, original.width(), original.height());
+
+        let mut processed = original.clone();
+        self.blurrer.blur(&mut processed, &regions)?;
+        Ok(RenderedFrame {
+            original,
+            processed,
+            regions,
+            rotation: metadata.rotation,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::color_space::ColorSpace;
+    use crate::shared::video_metadata::VideoMetadata;
+    use std::sync::Mutex;
+
+    // --- Stubs ---
+
+    /// Frames filled with their own index, with seeking.
+    struct StubVideoReader {
+        total: usize,
+        position: usize,
+    }
+
+    impl VideoReader for StubVideoReader {
+        fn open(&mut self, _path: &Path) -> Result<VideoMetadata, Box<dyn std::error::Error>> {
+            Ok(VideoMetadata {
+                width: 100,
+                height: 100,
+                fps: 10.0,
+                total_frames: self.total,
+                codec: String::new(),
+                source_path: None,
+                rotation: 0,
+                color: ColorSpace::default(),
+            })
+        }
+
+        fn frames(
+            &mut self,
+        ) -> Box<dyn Iterator<Item = Result<Frame, Box<dyn std::error::Error>>> + '_> {
+            Box::new(
+                (self.position..self.total)
+                    .map(|i| Ok(Frame::new(vec![i as u8; 100 * 100 * 3], 100, 100, 3, i))),
+            )
+        }
+
+        fn seek(&mut self, frame_index: usize) -> Result<(), Box<dyn std::error::Error>> {
+            self.position = frame_index;
+            Ok(())
+        }
+
+        fn close(&mut self) {}
+    }
+
+    struct RecordingBlurrer {
+        calls: Arc<Mutex<Vec<Vec<Region>>>>,
+    }
+
+    impl FrameBlurrer for RecordingBlurrer {
+        fn blur(
+            &self,
+            _frame: &mut Frame,
+            regions: &[Region],
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            self.calls.lock().unwrap().push(regions.to_vec());
+            Ok(())
+        }
+    }
+
+    // --- Helpers ---
+
+    fn region(track_id: u32) -> Region {
+        Region {
+            x: 40,
+            y: 40,
+            width: 20,
+            height: 20,
+            track_id: Some(track_id),
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    type Calls = Arc<Mutex<Vec<Vec<Region>>>>;
+
+    fn use_case(
+        detections: HashMap<usize, Vec<Region>>,
+        lookahead: usize,
+    ) -> (PreviewFrameUseCase, Calls) {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let uc = PreviewFrameUseCase::new(
+            Box::new(StubVideoReader {
+                total: 50,
+                position: 0,
+            }),
+            Arc::new(detections),
+            Box::new(RecordingBlurrer {
+                calls: calls.clone(),
+            }),
+            lookahead,
+        );
+        (uc, calls)
+    }
+
+    // --- Tests ---
+
+    #[test]
+    fn test_blurs_cached_regions_of_frame() {
+        let (mut uc, calls) = use_case(HashMap::from([(5, vec![region(1)])]), 0);
+
+        let rendered = uc.render(Path::new("in.mp4"), 5).unwrap();
+
+        assert_eq!(rendered.original.index(), 5);
+        assert_eq!(rendered.regions, vec![region(1)]);
+        assert_eq!(calls.lock().unwrap()[0], rendered.regions);
+    }
+
+    #[test]
+    fn test_includes_lookahead_regions() {
+        let (mut uc, _) = use_case(HashMap::from([(7, vec![region(1)])]), 3);
+
+        let rendered = uc.render(Path::new("in.mp4"), 5).unwrap();
+
+        assert_eq!(
+            rendered.regions.len(),
+            1,
+            "face two frames ahead should be blurred"
+        );
+    }
+
+    #[test]
+    fn test_blurs_only_selected_tracks() {
+        let (uc, _) = use_case(HashMap::from([(5, vec![region(1), region(2)])]), 0);
+        let mut uc = uc.with_blur_ids(Some(HashSet::from([2])));
+
+        let rendered = uc.render(Path::new("in.mp4"), 5).unwrap();
+
+        assert_eq!(rendered.regions.len(), 1);
+        assert_eq!(rendered.regions[0].track_id, Some(2));
+    }
+
+    #[test]
+    fn test_padding_resizes_regions() {
+        let (uc, _) = use_case(HashMap::from([(5, vec![region(1)])]), 0);
+        let mut uc = uc.with_padding(0.0, 1.0);
+
+        let rendered = uc.render(Path::new("in.mp4"), 5).unwrap();
+
+        let r = &rendered.regions[0];
+        assert_eq!((r.x, r.y, r.width, r.height), (30, 30, 40, 40));
+    }
+
+    #[test]
+    fn test_frame_past_end_is_error() {
+        let (mut uc, _) = use_case(HashMap::new(), 0);
+        assert!(uc.render(Path::new("in.mp4"), 50).is_err());
+    }
+}
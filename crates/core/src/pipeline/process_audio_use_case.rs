@@ -1,22 +1,69 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use crate::audio::domain::audio_failure_policy::AudioFailurePolicy;
+use crate::audio::domain::audio_segment::AudioSegment;
 use crate::audio::domain::audio_transformer::AudioTransformer;
+use crate::audio::domain::bleep_style::{BleepStyle, DEFAULT_BLEEP_FADE};
+use crate::audio::domain::censor_region::CensorRegion;
+use crate::audio::domain::entity_recognizer::EntityRecognizer;
+use crate::audio::domain::keyword_hit::KeywordHit;
+use crate::audio::domain::keyword_matcher::KeywordMatcher;
 use crate::audio::domain::speech_recognizer::SpeechRecognizer;
-use crate::audio::domain::word_censor::{
-    BleepMode, WordCensor, DEFAULT_BLEEP_FREQUENCY, DEFAULT_BLEEP_PADDING,
-};
+use crate::audio::domain::transcript::TranscriptWord;
+use crate::audio::domain::word_censor::{WordCensor, DEFAULT_BLEEP_PADDING};
+use crate::shared::cancellation::{CancellationToken, Cancelled};
+use crate::shared::time_map::TimeMap;
+use crate::shared::track_time_rules::TimeRange;
+use crate::shared::warnings::{WarningKind, Warnings};
 use crate::video::domain::audio_reader::AudioReader;
 use crate::video::domain::audio_writer::AudioWriter;
 
+/// Source audio with the keyword hits found in it: the result of
+/// [`ProcessAudioUseCase::recognize`], which
+/// [`ProcessAudioUseCase::apply`] turns into the output audio. In between,
+/// hits can be reviewed and false positives dropped.
+pub struct AudioRecognition {
+    source_path: PathBuf,
+    /// `None` when the source has no audio track.
+    audio: Option<AudioSegment>,
+    hits: Vec<KeywordHit>,
+}
+
+impl AudioRecognition {
+    /// Keyword hits to bleep, in transcript order.
+    pub fn hits(&self) -> &[KeywordHit] {
+        &self.hits
+    }
+
+    /// Replaces the hits to bleep, such as with the ones a user kept.
+    pub fn set_hits(&mut self, hits: Vec<KeywordHit>) {
+        self.hits = hits;
+    }
+}
+
 pub struct ProcessAudioUseCase {
     reader: Box<dyn AudioReader>,
     writer: Box<dyn AudioWriter>,
     recognizer: Option<Box<dyn SpeechRecognizer>>,
     transformer: Option<Box<dyn AudioTransformer>>,
     keywords: Vec<String>,
-    bleep_mode: BleepMode,
+    matcher: KeywordMatcher,
+    entities: Option<Box<dyn EntityRecognizer>>,
+    bleep_style: BleepStyle,
+    bleep_fade: f64,
+    time_ranges: Vec<TimeRange>,
+    bleep_ranges: Vec<TimeRange>,
+    time_map: TimeMap,
+    extra_outputs: Vec<PathBuf>,
+    failure_policy: AudioFailurePolicy,
+    warnings: Warnings,
+    cancelled: CancellationToken,
 }
 
+/// Crossfade at the edges of a disguised range, long enough to avoid a
+/// click but short enough not to leak a recognisable syllable.
+const RANGE_FADE_SECONDS: f64 = 0.01;
+
 impl ProcessAudioUseCase {
     pub fn new(
         reader: Box<dyn AudioReader>,
@@ -24,7 +71,7 @@ impl ProcessAudioUseCase {
         recognizer: Option<Box<dyn SpeechRecognizer>>,
         transformer: Option<Box<dyn AudioTransformer>>,
         keywords: Vec<String>,
-        bleep_mode: BleepMode,
+        bleep_style: BleepStyle,
     ) -> Self {
         Self {
             reader,
@@ -32,54 +79,346 @@ impl ProcessAudioUseCase {
             recognizer,
             transformer,
             keywords,
-            bleep_mode,
+            matcher: KeywordMatcher::default(),
+            entities: None,
+            bleep_style,
+            bleep_fade: DEFAULT_BLEEP_FADE,
+            time_ranges: Vec::new(),
+            bleep_ranges: Vec::new(),
+            time_map: TimeMap::new(),
+            extra_outputs: Vec::new(),
+            failure_policy: AudioFailurePolicy::default(),
+            warnings: Warnings::new(),
+            cancelled: CancellationToken::new(),
         }
     }
 
+    /// Stops between steps with a [`Cancelled`] error once `token` is
+    /// cancelled. A cancelled run never falls back to the original or
+    /// silent audio.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancelled = token;
+        self
+    }
+
+    /// Decides which transcribed words count as a keyword, e.g. to catch
+    /// other spellings of a name. Exact (case-insensitive) by default.
+    pub fn with_keyword_matcher(mut self, matcher: KeywordMatcher) -> Self {
+        self.matcher = matcher;
+        self
+    }
+
+    /// Also bleeps the names, numbers, or places `entities` finds in the
+    /// transcript, alongside the keywords. Needs a speech recognizer.
+    pub fn with_entity_recognizer(mut self, entities: Box<dyn EntityRecognizer>) -> Self {
+        self.entities = Some(entities);
+        self
+    }
+
+    /// Crossfades `seconds` into and out of each bleep instead of
+    /// cutting to it, so the edges don't click. Zero cuts hard.
+    pub fn with_bleep_fade(mut self, seconds: f64) -> Self {
+        self.bleep_fade = seconds;
+        self
+    }
+
+    /// Bleeps these ranges outright, independent of keyword matches.
+    pub fn with_bleep_ranges(mut self, ranges: Vec<TimeRange>) -> Self {
+        self.bleep_ranges = ranges;
+        self
+    }
+
+    /// Lays the output audio on the output video's timeline: cuts are
+    /// removed, and bleeps are placed at the output time of the words
+    /// they cover. Pass the map the video's cut-range reader and writer
+    /// got, so the two stay in sync.
+    pub fn with_time_map(mut self, time_map: TimeMap) -> Self {
+        self.time_map = time_map;
+        self
+    }
+
+    /// Also muxes the processed audio into these videos, such as extra
+    /// renditions of the same output, without reprocessing it.
+    pub fn with_extra_outputs(mut self, paths: Vec<PathBuf>) -> Self {
+        self.extra_outputs = paths;
+        self
+    }
+
+    /// Decides what happens to the output when processing fails.
+    pub fn with_failure_policy(mut self, policy: AudioFailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    /// Collects the warning raised when a failure policy falls back.
+    pub fn with_warnings(mut self, warnings: Warnings) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    /// Limits voice disguise and keyword bleeps to these ranges. With no
+    /// ranges (the default) the whole track is processed.
+    pub fn with_time_ranges(mut self, ranges: Vec<TimeRange>) -> Self {
+        self.time_ranges = ranges;
+        self
+    }
+
+    /// Processes the source audio and muxes it into the output videos,
+    /// bleeping every keyword hit. Failures are handled by the failure
+    /// policy; with a fallback the run still succeeds and the outcome is
+    /// recorded as a warning.
     pub fn run(
         &self,
         source_path: &Path,
         output_path: &Path,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        // 1. Read audio from source
-        let mut audio = match self.reader.read_audio(source_path, 16000)? {
-            Some(a) => a,
-            None => return Ok(()), // No audio track — skip
+        match self.recognize(source_path) {
+            Ok(recognition) => self.apply(recognition, output_path),
+            Err(error) => self.recover(source_path, output_path, error),
+        }
+    }
+
+    /// Reads the source audio and finds the keyword and entity hits in it,
+    /// on the original voice and within the time ranges. Pass a failure to
+    /// [`recover`](Self::recover), which applies the failure policy.
+    pub fn recognize(
+        &self,
+        source_path: &Path,
+    ) -> Result<AudioRecognition, Box<dyn std::error::Error>> {
+        let audio = self.reader.read_audio(source_path, 16000)?;
+        self.cancelled.check()?;
+
+        let wanted = !self.keywords.is_empty() || self.entities.is_some();
+        let hits = match (&audio, &self.recognizer) {
+            (Some(audio), Some(recognizer)) if wanted => {
+                let transcript = recognizer.transcribe(audio)?;
+                self.within_ranges(self.find_hits(&transcript)?)
+            }
+            _ => Vec::new(),
         };
+        self.cancelled.check()?;
 
-        // 2. Transcribe keywords on the original audio (before voice transform)
-        let censor_regions = if !self.keywords.is_empty() {
-            if let Some(ref recognizer) = self.recognizer {
-                let transcript = recognizer.transcribe(&audio)?;
-                WordCensor::find_censor_regions(&transcript, &self.keywords, DEFAULT_BLEEP_PADDING)
-            } else {
-                Vec::new()
+        Ok(AudioRecognition {
+            source_path: source_path.to_path_buf(),
+            audio,
+            hits,
+        })
+    }
+
+    /// Disguises, bleeps, and cuts recognized audio and muxes it into the
+    /// output videos. Failures are handled by the failure policy.
+    pub fn apply(
+        &self,
+        recognition: AudioRecognition,
+        output_path: &Path,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let source_path = recognition.source_path.clone();
+        match self
+            .process(recognition)
+            .and_then(|audio| self.write_all(output_path, audio.as_ref()))
+        {
+            Ok(()) => Ok(()),
+            Err(error) => self.recover(&source_path, output_path, error),
+        }
+    }
+
+    /// Applies the failure policy to `error` from [`recognize`] or
+    /// [`apply`]: returns it, or falls back to the original or silent audio
+    /// and records a warning. Cancellations are always returned.
+    ///
+    /// [`recognize`]: Self::recognize
+    /// [`apply`]: Self::apply
+    pub fn recover(
+        &self,
+        source_path: &Path,
+        output_path: &Path,
+        error: Box<dyn std::error::Error>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if Cancelled::reason_of(&*error).is_some() {
+            return Err(error);
+        }
+        match self.failure_policy {
+            AudioFailurePolicy::Fail => Err(error),
+            AudioFailurePolicy::OriginalAudio => {
+                let fallback = self
+                    .read_cut(source_path)
+                    .and_then(|audio| self.write_all(output_path, audio.as_ref()));
+                match fallback {
+                    Ok(()) => self.warnings.push(
+                        WarningKind::Audio,
+                        format!(
+                            "Audio processing failed ({error}). The output contains the ORIGINAL audio: voices are not disguised and nothing is bleeped"
+                        ),
+                    ),
+                    Err(e) => self.warnings.push(
+                        WarningKind::Audio,
+                        format!(
+                            "Audio processing failed ({error}) and the original audio could not be used either ({e}). The output has no audio"
+                        ),
+                    ),
+                }
+                Ok(())
             }
-        } else {
-            Vec::new()
+            AudioFailurePolicy::Silent => {
+                let fallback = self.read_cut(source_path).and_then(|audio| {
+                    let silence = audio.map(|mut a| {
+                        a.samples_mut().fill(0.0);
+                        a
+                    });
+                    self.write_all(output_path, silence.as_ref())
+                });
+                let outcome = match fallback {
+                    Ok(()) => "The output has a silent audio track".to_string(),
+                    Err(e) => format!("The output has no audio ({e})"),
+                };
+                self.warnings.push(
+                    WarningKind::Audio,
+                    format!("Audio processing failed ({error}). {outcome}"),
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Disguises, bleeps, and cuts the recognized audio. `None` when the
+    /// source has no audio track.
+    fn process(
+        &self,
+        recognition: AudioRecognition,
+    ) -> Result<Option<AudioSegment>, Box<dyn std::error::Error>> {
+        let Some(mut audio) = recognition.audio else {
+            return Ok(None);
         };
+        let mut censor_regions: Vec<CensorRegion> = recognition
+            .hits
+            .iter()
+            .map(|hit| hit.censor_region(DEFAULT_BLEEP_PADDING))
+            .collect();
 
-        // 3. Voice transform (if enabled) — must happen before bleeping,
-        //    otherwise PSOLA overlap-add corrupts the bleep tones
+        // 1. Voice transform (if enabled) — must happen before bleeping,
+        //    otherwise PSOLA overlap-add corrupts the bleep tones.
+        //    With time ranges, the whole track is transformed on a copy so
+        //    each range starts with warmed-up state, then only the ranges
+        //    are spliced back.
         if let Some(ref transformer) = self.transformer {
-            transformer.transform(&mut audio)?;
+            if self.time_ranges.is_empty() {
+                transformer.transform(&mut audio)?;
+            } else {
+                let mut disguised = audio.clone();
+                transformer.transform(&mut disguised)?;
+                for range in &self.time_ranges {
+                    audio.blend_range(&disguised, range.start, range.end, RANGE_FADE_SECONDS);
+                }
+            }
         }
 
-        // 4. Apply bleeps after voice transform so they cleanly overwrite
+        self.cancelled.check()?;
+
+        // 2. Cut ranges, so the audio follows the output timeline.
+        self.remove_cuts(&mut audio);
+
+        // 3. Apply bleeps after voice transform so they cleanly overwrite,
+        //    each moved from source time to where it plays in the output.
+        censor_regions.extend(self.bleep_ranges.iter().map(|r| CensorRegion {
+            start_time: r.start,
+            end_time: r.end,
+            padding: 0.0,
+        }));
+        let censor_regions: Vec<CensorRegion> = censor_regions
+            .iter()
+            .filter_map(|region| {
+                let source = TimeRange::new(region.effective_start(), region.effective_end());
+                let output = self.time_map.range_to_output(source)?;
+                Some(CensorRegion {
+                    start_time: output.start,
+                    end_time: output.end,
+                    padding: 0.0,
+                })
+            })
+            .collect();
         if !censor_regions.is_empty() {
             WordCensor::apply_bleep(
                 &mut audio,
                 &censor_regions,
-                DEFAULT_BLEEP_FREQUENCY,
-                self.bleep_mode,
+                &self.bleep_style,
+                self.bleep_fade,
             );
         }
 
-        // 5. Write processed audio to output
-        self.writer.write_audio(output_path, &audio)?;
+        Ok(Some(audio))
+    }
 
+    /// The source audio with only the cut ranges removed.
+    fn read_cut(
+        &self,
+        source_path: &Path,
+    ) -> Result<Option<AudioSegment>, Box<dyn std::error::Error>> {
+        let mut audio = match self.reader.read_audio(source_path, 16000)? {
+            Some(a) => a,
+            None => return Ok(None),
+        };
+        self.remove_cuts(&mut audio);
+        Ok(Some(audio))
+    }
+
+    fn remove_cuts(&self, audio: &mut AudioSegment) {
+        // Latest first, so earlier offsets stay valid.
+        for range in self.time_map.cuts().iter().rev() {
+            audio.remove_range(range.start, range.end);
+        }
+    }
+
+    fn write_all(
+        &self,
+        output_path: &Path,
+        audio: Option<&AudioSegment>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(audio) = audio else {
+            return Ok(());
+        };
+        self.writer.write_audio(output_path, audio)?;
+        for path in &self.extra_outputs {
+            self.writer.write_audio(path, audio)?;
+        }
         Ok(())
     }
+
+    /// Keyword hits and the words of found entities, in transcript order. A
+    /// word that is both is listed once, as the keyword hit.
+    fn find_hits(
+        &self,
+        transcript: &[TranscriptWord],
+    ) -> Result<Vec<KeywordHit>, Box<dyn std::error::Error>> {
+        let mut hits = WordCensor::find_hits(transcript, &self.keywords, &self.matcher);
+        if let Some(entities) = &self.entities {
+            for entity in entities.find_entities(transcript)? {
+                for hit in entity.hits() {
+                    if !hits.iter().any(|h| h.word == hit.word) {
+                        hits.push(hit);
+                    }
+                }
+            }
+            hits.sort_by(|a, b| a.word.start_time.total_cmp(&b.word.start_time));
+        }
+        Ok(hits)
+    }
+
+    /// Drops hits whose bleep would not overlap any configured range.
+    /// Overlapping hits are kept whole so a word straddling the edge is
+    /// still fully bleeped.
+    fn within_ranges(&self, hits: Vec<KeywordHit>) -> Vec<KeywordHit> {
+        if self.time_ranges.is_empty() {
+            return hits;
+        }
+        hits.into_iter()
+            .filter(|hit| {
+                let r = hit.censor_region(DEFAULT_BLEEP_PADDING);
+                self.time_ranges.iter().any(|range| {
+                    r.effective_start() <= range.end && r.effective_end() >= range.start
+                })
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -88,7 +427,7 @@ mod tests {
     use crate::audio::domain::audio_segment::AudioSegment;
     use crate::audio::domain::audio_transformer::AudioTransformer;
     use crate::audio::domain::speech_recognizer::SpeechRecognizer;
-    use crate::audio::domain::transcript::TranscriptWord;
+    use crate::audio::domain::spoken_entity::{PiiCategory, SpokenEntity};
     use crate::video::domain::audio_reader::AudioReader;
     use crate::video::domain::audio_writer::AudioWriter;
     use std::path::Path;
@@ -175,7 +514,7 @@ mod tests {
             None,
             None,
             vec![],
-            BleepMode::Tone,
+            BleepStyle::default(),
         );
         uc.run(Path::new("in.mp4"), Path::new("out.mp4")).unwrap();
         assert!(written.lock().unwrap().is_none());
@@ -203,7 +542,7 @@ mod tests {
             Some(Box::new(recognizer)),
             None,
             vec!["secret".to_string()],
-            BleepMode::Tone,
+            BleepStyle::default(),
         );
         uc.run(Path::new("in.mp4"), Path::new("out.mp4")).unwrap();
 
@@ -219,6 +558,130 @@ mod tests {
         assert!(energy > 0.0);
     }
 
+    #[test]
+    fn test_phonetic_matcher_finds_spelling_variants() {
+        use crate::audio::domain::keyword_matcher::PhoneticStrictness;
+
+        let recognizer = StubRecognizer {
+            words: vec![TranscriptWord {
+                word: "Mohammed".to_string(),
+                start_time: 0.5,
+                end_time: 0.8,
+                confidence: 0.95,
+            }],
+        };
+        let uc = ProcessAudioUseCase::new(
+            Box::new(StubAudioReader {
+                segment: Some(silent_audio()),
+            }),
+            Box::new(StubAudioWriter {
+                written: Arc::new(Mutex::new(None)),
+            }),
+            Some(Box::new(recognizer)),
+            None,
+            vec!["Muhammad".to_string()],
+            BleepStyle::default(),
+        )
+        .with_keyword_matcher(KeywordMatcher::Phonetic(PhoneticStrictness::Normal));
+
+        let recognition = uc.recognize(Path::new("in.mp4")).unwrap();
+
+        assert_eq!(recognition.hits().len(), 1);
+        assert_eq!(recognition.hits()[0].keyword, "Muhammad");
+    }
+
+    #[test]
+    fn test_reviewed_hits_replace_recognized_ones() {
+        let writer = StubAudioWriter {
+            written: Arc::new(Mutex::new(None)),
+        };
+        let written = writer.written.clone();
+        let word = |start: f64, end: f64| TranscriptWord {
+            word: "secret".to_string(),
+            start_time: start,
+            end_time: end,
+            confidence: 0.95,
+        };
+        let uc = ProcessAudioUseCase::new(
+            Box::new(StubAudioReader {
+                segment: Some(silent_audio()),
+            }),
+            Box::new(writer),
+            Some(Box::new(StubRecognizer {
+                words: vec![word(0.1, 0.2), word(0.7, 0.8)],
+            })),
+            None,
+            vec!["secret".to_string()],
+            BleepStyle::default(),
+        );
+
+        let mut recognition = uc.recognize(Path::new("in.mp4")).unwrap();
+        assert_eq!(recognition.hits().len(), 2);
+        let kept = recognition.hits()[1..].to_vec();
+        recognition.set_hits(kept);
+        uc.apply(recognition, Path::new("out.mp4")).unwrap();
+
+        let written = written.lock().unwrap();
+        let seg = written.as_ref().unwrap();
+        assert_eq!(energy(seg, 0.1, 0.2), 0.0);
+        assert!(energy(seg, 0.7, 0.8) > 0.0);
+    }
+
+    /// Finds every word at or after `from` seconds as a name.
+    struct StubEntities {
+        from: f64,
+    }
+
+    impl EntityRecognizer for StubEntities {
+        fn find_entities(
+            &self,
+            transcript: &[TranscriptWord],
+        ) -> Result<Vec<SpokenEntity>, Box<dyn std::error::Error>> {
+            Ok(vec![SpokenEntity {
+                category: PiiCategory::Name,
+                words: transcript
+                    .iter()
+                    .filter(|w| w.start_time >= self.from)
+                    .cloned()
+                    .collect(),
+            }])
+        }
+    }
+
+    #[test]
+    fn test_entities_are_bleeped_with_keywords() {
+        let word = |text: &str, start: f64| TranscriptWord {
+            word: text.to_string(),
+            start_time: start,
+            end_time: start + 0.1,
+            confidence: 0.95,
+        };
+        let uc = ProcessAudioUseCase::new(
+            Box::new(StubAudioReader {
+                segment: Some(silent_audio()),
+            }),
+            Box::new(StubAudioWriter {
+                written: Arc::new(Mutex::new(None)),
+            }),
+            Some(Box::new(StubRecognizer {
+                words: vec![word("hello", 0.1), word("secret", 0.4), word("Jane", 0.7)],
+            })),
+            None,
+            vec!["secret".to_string()],
+            BleepStyle::default(),
+        )
+        .with_entity_recognizer(Box::new(StubEntities { from: 0.4 }));
+
+        let recognition = uc.recognize(Path::new("in.mp4")).unwrap();
+
+        let found: Vec<(&str, &str)> = recognition
+            .hits()
+            .iter()
+            .map(|hit| (hit.keyword.as_str(), hit.word.word.as_str()))
+            .collect();
+        assert_eq!(found, vec![("secret", "secret"), ("name", "Jane")]);
+    }
+
     #[test]
     fn test_voice_transform_applied() {
         let writer = StubAudioWriter {
@@ -236,9 +699,516 @@ mod tests {
             None,
             Some(Box::new(transformer)),
             vec![],
-            BleepMode::Tone,
+            BleepStyle::default(),
         );
         uc.run(Path::new("in.mp4"), Path::new("out.mp4")).unwrap();
         assert!(*called.lock().unwrap());
     }
+
+    struct ConstantTransformer;
+
+    impl AudioTransformer for ConstantTransformer {
+        fn transform(&self, audio: &mut AudioSegment) -> Result<(), Box<dyn std::error::Error>> {
+            audio.samples_mut().fill(0.5);
+            Ok(())
+        }
+    }
+
+    fn energy(seg: &AudioSegment, start: f64, end: f64) -> f64 {
+        seg.samples()[seg.sample_index_at_time(start)..seg.sample_index_at_time(end)]
+            .iter()
+            .map(|s| (*s as f64).powi(2))
+            .sum()
+    }
+
+    #[test]
+    fn test_time_ranges_limit_voice_transform() {
+        let writer = StubAudioWriter {
+            written: Arc::new(Mutex::new(None)),
+        };
+        let written = writer.written.clone();
+        let uc = ProcessAudioUseCase::new(
+            Box::new(StubAudioReader {
+                segment: Some(silent_audio()),
+            }),
+            Box::new(writer),
+            None,
+            Some(Box::new(ConstantTransformer)),
+            vec![],
+            BleepStyle::default(),
+        )
+        .with_time_ranges(vec![TimeRange::new(0.25, 0.5)]);
+        uc.run(Path::new("in.mp4"), Path::new("out.mp4")).unwrap();
+
+        let written = written.lock().unwrap();
+        let seg = written.as_ref().unwrap();
+        assert_eq!(energy(seg, 0.0, 0.24), 0.0);
+        assert!(energy(seg, 0.3, 0.45) > 0.0);
+        assert_eq!(energy(seg, 0.51, 1.0), 0.0);
+    }
+
+    #[test]
+    fn test_time_ranges_skip_keywords_outside_range() {
+        let writer = StubAudioWriter {
+            written: Arc::new(Mutex::new(None)),
+        };
+        let written = writer.written.clone();
+        let word = |start: f64, end: f64| TranscriptWord {
+            word: "secret".to_string(),
+            start_time: start,
+            end_time: end,
+            confidence: 0.95,
+        };
+        let recognizer = StubRecognizer {
+            words: vec![word(0.1, 0.2), word(0.7, 0.8)],
+        };
+        let uc = ProcessAudioUseCase::new(
+            Box::new(StubAudioReader {
+                segment: Some(silent_audio()),
+            }),
+            Box::new(writer),
+            Some(Box::new(recognizer)),
+            None,
+            vec!["secret".to_string()],
+            BleepStyle::default(),
+        )
+        .with_time_ranges(vec![TimeRange::new(0.6, 1.0)]);
+        uc.run(Path::new("in.mp4"), Path::new("out.mp4")).unwrap();
+
+        let written = written.lock().unwrap();
+        let seg = written.as_ref().unwrap();
+        assert_eq!(energy(seg, 0.1, 0.2), 0.0);
+        assert!(energy(seg, 0.7, 0.8) > 0.0);
+    }
+
+    #[test]
+    fn test_bleep_ranges_bleep_without_keywords() {
+        let writer = StubAudioWriter {
+            written: Arc::new(Mutex::new(None)),
+        };
+        let written = writer.written.clone();
+        let uc = ProcessAudioUseCase::new(
+            Box::new(StubAudioReader {
+                segment: Some(silent_audio()),
+            }),
+            Box::new(writer),
+            None,
+            None,
+            vec![],
+            BleepStyle::default(),
+        )
+        .with_bleep_ranges(vec![TimeRange::new(0.2, 0.4)]);
+        uc.run(Path::new("in.mp4"), Path::new("out.mp4")).unwrap();
+
+        let written = written.lock().unwrap();
+        let seg = written.as_ref().unwrap();
+        assert!(energy(seg, 0.2, 0.4) > 0.0);
+        assert_eq!(energy(seg, 0.5, 1.0), 0.0);
+    }
+
+    #[test]
+    fn test_cut_ranges_shorten_audio() {
+        let writer = StubAudioWriter {
+            written: Arc::new(Mutex::new(None)),
+        };
+        let written = writer.written.clone();
+        let uc = ProcessAudioUseCase::new(
+            Box::new(StubAudioReader {
+                segment: Some(silent_audio()),
+            }),
+            Box::new(writer),
+            None,
+            None,
+            vec![],
+            BleepStyle::default(),
+        )
+        .with_time_map(TimeMap::new().with_cuts(&[
+            TimeRange::new(0.5, 0.75),
+            TimeRange::new(0.0, 0.25),
+            TimeRange::new(0.6, 0.7),
+        ]));
+        uc.run(Path::new("in.mp4"), Path::new("out.mp4")).unwrap();
+
+        let written = written.lock().unwrap();
+        assert_eq!(written.as_ref().unwrap().duration(), 0.5);
+    }
+
+    /// Mono audio at a low rate, so half-hour fixtures stay small. Still
+    /// above twice the bleep tone's frequency.
+    fn long_silent_audio(seconds: f64) -> AudioSegment {
+        AudioSegment::new(vec![0.0; (seconds * 3000.0) as usize], 3000, 1)
+    }
+
+    #[test]
+    fn test_bleeps_stay_on_output_timeline_over_long_recording() {
+        // 31 minutes with a 4 s cut every 5 minutes, and a word near the end.
+        let cuts: Vec<TimeRange> = (1..=6)
+            .map(|m| TimeRange::new(m as f64 * 300.0, m as f64 * 300.0 + 4.0))
+            .collect();
+        let time_map = TimeMap::new().with_frame_rate(29.97).with_cuts(&cuts);
+        let writer = StubAudioWriter {
+            written: Arc::new(Mutex::new(None)),
+        };
+        let written = writer.written.clone();
+        let recognizer = StubRecognizer {
+            words: vec![TranscriptWord {
+                word: "secret".to_string(),
+                start_time: 1850.0,
+                end_time: 1850.5,
+                confidence: 0.95,
+            }],
+        };
+        let uc = ProcessAudioUseCase::new(
+            Box::new(StubAudioReader {
+                segment: Some(long_silent_audio(1860.0)),
+            }),
+            Box::new(writer),
+            Some(Box::new(recognizer)),
+            None,
+            vec!["secret".to_string()],
+            BleepStyle::default(),
+        )
+        .with_time_map(time_map.clone());
+        uc.run(Path::new("in.mp4"), Path::new("out.mp4")).unwrap();
+
+        let written = written.lock().unwrap();
+        let seg = written.as_ref().unwrap();
+        assert!((seg.duration() - 1836.0).abs() < 1e-3);
+        let start = time_map.to_output(1850.0 - DEFAULT_BLEEP_PADDING);
+        let end = time_map.to_output(1850.5 + DEFAULT_BLEEP_PADDING);
+        let rate = seg.sample_rate() as f64;
+        let first = seg.samples().iter().position(|s| *s != 0.0).unwrap() as f64 / rate;
+        let last = seg.samples().iter().rposition(|s| *s != 0.0).unwrap() as f64 / rate;
+        let frame = 1.0 / 29.97;
+        assert!(
+            (first - start).abs() < frame,
+            "bleep starts at {first}, video at {start}"
+        );
+        assert!(
+            (last - end).abs() < frame,
+            "bleep ends at {last}, video at {end}"
+        );
+    }
+
+    #[test]
+    fn test_bleep_across_cut_is_trimmed_to_output() {
+        let writer = StubAudioWriter {
+            written: Arc::new(Mutex::new(None)),
+        };
+        let written = writer.written.clone();
+        let uc = ProcessAudioUseCase::new(
+            Box::new(StubAudioReader {
+                segment: Some(silent_audio()),
+            }),
+            Box::new(writer),
+            None,
+            None,
+            vec![],
+            BleepStyle::default(),
+        )
+        .with_bleep_ranges(vec![TimeRange::new(0.3, 0.5), TimeRange::new(0.6, 0.7)])
+        .with_time_map(TimeMap::new().with_cuts(&[TimeRange::new(0.4, 0.8)]));
+        uc.run(Path::new("in.mp4"), Path::new("out.mp4")).unwrap();
+
+        let written = written.lock().unwrap();
+        let seg = written.as_ref().unwrap();
+        assert!(energy(seg, 0.3, 0.4) > 0.0);
+        assert_eq!(energy(seg, 0.0, 0.29), 0.0);
+        assert_eq!(energy(seg, 0.41, 0.6), 0.0);
+    }
+
+    struct PathRecordingWriter {
+        paths: Arc<Mutex<Vec<PathBuf>>>,
+    }
+
+    impl AudioWriter for PathRecordingWriter {
+        fn write_audio(
+            &self,
+            path: &Path,
+            _: &AudioSegment,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            self.paths.lock().unwrap().push(path.to_path_buf());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_extra_outputs_receive_same_audio() {
+        let paths = Arc::new(Mutex::new(Vec::new()));
+        let uc = ProcessAudioUseCase::new(
+            Box::new(StubAudioReader {
+                segment: Some(silent_audio()),
+            }),
+            Box::new(PathRecordingWriter {
+                paths: paths.clone(),
+            }),
+            None,
+            None,
+            vec![],
+            BleepStyle::default(),
+        )
+        .with_extra_outputs(vec![PathBuf::from("out_720p.mp4")]);
+        uc.run(Path::new("in.mp4"), Path::new("out.mp4")).unwrap();
+
+        assert_eq!(
+            *paths.lock().unwrap(),
+            vec![PathBuf::from("out.mp4"), PathBuf::from("out_720p.mp4")]
+        );
+    }
+
+    struct FailingTransformer;
+
+    impl AudioTransformer for FailingTransformer {
+        fn transform(&self, audio: &mut AudioSegment) -> Result<(), Box<dyn std::error::Error>> {
+            audio.samples_mut().fill(0.9);
+            Err("transform failed".into())
+        }
+    }
+
+    struct FailingAudioReader;
+
+    impl AudioReader for FailingAudioReader {
+        fn read_audio(
+            &self,
+            _: &Path,
+            _: u32,
+        ) -> Result<Option<AudioSegment>, Box<dyn std::error::Error>> {
+            Err("unsupported codec".into())
+        }
+
+        fn audio_metadata(
+            &self,
+            _: &Path,
+        ) -> Result<Option<(u32, u16)>, Box<dyn std::error::Error>> {
+            Err("unsupported codec".into())
+        }
+    }
+
+    fn failing_use_case(
+        policy: AudioFailurePolicy,
+        warnings: Warnings,
+    ) -> (ProcessAudioUseCase, Arc<Mutex<Option<AudioSegment>>>) {
+        let writer = StubAudioWriter {
+            written: Arc::new(Mutex::new(None)),
+        };
+        let written = writer.written.clone();
+        let uc = ProcessAudioUseCase::new(
+            Box::new(StubAudioReader {
+                segment: Some(AudioSegment::new(vec![0.25; 16000], 16000, 1)),
+            }),
+            Box::new(writer),
+            None,
+            Some(Box::new(FailingTransformer)),
+            vec![],
+            BleepStyle::default(),
+        )
+        .with_failure_policy(policy)
+        .with_warnings(warnings);
+        (uc, written)
+    }
+
+    #[test]
+    fn test_failure_policy_fail_returns_error() {
+        let warnings = Warnings::new();
+        let (uc, written) = failing_use_case(AudioFailurePolicy::Fail, warnings.clone());
+        assert!(uc.run(Path::new("in.mp4"), Path::new("out.mp4")).is_err());
+        assert!(written.lock().unwrap().is_none());
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_failure_policy_original_writes_unprocessed_audio() {
+        let warnings = Warnings::new();
+        let (uc, written) = failing_use_case(AudioFailurePolicy::OriginalAudio, warnings.clone());
+        uc.run(Path::new("in.mp4"), Path::new("out.mp4")).unwrap();
+
+        let written = written.lock().unwrap();
+        assert!(written
+            .as_ref()
+            .unwrap()
+            .samples()
+            .iter()
+            .all(|&s| s == 0.25));
+        let warnings = warnings.snapshot();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, WarningKind::Audio);
+        assert!(warnings[0].message.contains("ORIGINAL audio"));
+    }
+
+    #[test]
+    fn test_failure_policy_silent_writes_silence() {
+        let warnings = Warnings::new();
+        let (uc, written) = failing_use_case(AudioFailurePolicy::Silent, warnings.clone());
+        uc.run(Path::new("in.mp4"), Path::new("out.mp4")).unwrap();
+
+        let written = written.lock().unwrap();
+        let seg = written.as_ref().unwrap();
+        assert_eq!(seg.samples().len(), 16000);
+        assert!(seg.samples().iter().all(|&s| s == 0.0));
+        assert!(warnings.snapshot()[0]
+            .message
+            .contains("silent audio track"));
+    }
+
+    #[test]
+    fn test_cancelled_run_skips_fallback() {
+        use crate::shared::cancellation::CancelReason;
+
+        let warnings = Warnings::new();
+        let (uc, written) = failing_use_case(AudioFailurePolicy::OriginalAudio, warnings.clone());
+        let token = CancellationToken::new();
+        token.cancel(CancelReason::User);
+        let uc = uc.with_cancellation(token);
+
+        let error = uc
+            .run(Path::new("in.mp4"), Path::new("out.mp4"))
+            .unwrap_err();
+
+        assert_eq!(Cancelled::reason_of(&*error), Some(CancelReason::User));
+        assert!(written.lock().unwrap().is_none());
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_failure_policy_silent_without_readable_audio_leaves_no_track() {
+        let warnings = Warnings::new();
+        let writer = StubAudioWriter {
+            written: Arc::new(Mutex::new(None)),
+        };
+        let written = writer.written.clone();
+        let uc = ProcessAudioUseCase::new(
+            Box::new(FailingAudioReader),
+            Box::new(writer),
+            None,
+            None,
+            vec![],
+            BleepStyle::default(),
+        )
+        .with_failure_policy(AudioFailurePolicy::Silent)
+        .with_warnings(warnings.clone());
+        uc.run(Path::new("in.mp4"), Path::new("out.mp4")).unwrap();
+
+        assert!(written.lock().unwrap().is_none());
+        let message = &warnings.snapshot()[0].message;
+        assert!(message.contains("unsupported codec"));
+        assert!(message.contains("no audio"));
+    }
+
+    /// Four seconds at 10 fps; frame `i` is `i * 5 + 20` bright, so it can
+    /// be told apart after encoding.
+    struct NumberedFrameReader;
+
+    const NUMBERED_FPS: f64 = 10.0;
+    const NUMBERED_FRAMES: usize = 40;
+
+    fn brightness_of(index: usize) -> u8 {
+        (index * 5 + 20) as u8
+    }
+
+    impl crate::video::domain::video_reader::VideoReader for NumberedFrameReader {
+        fn open(
+            &mut self,
+            _: &Path,
+        ) -> Result<crate::shared::video_metadata::VideoMetadata, Box<dyn std::error::Error>>
+        {
+            Ok(crate::shared::video_metadata::VideoMetadata {
+                width: 64,
+                height: 48,
+                fps: NUMBERED_FPS,
+                total_frames: NUMBERED_FRAMES,
+                codec: String::new(),
+                source_path: None,
+                rotation: 0,
+                color: Default::default(),
+            })
+        }
+
+        fn frames(
+            &mut self,
+        ) -> Box<
+            dyn Iterator<Item = Result<crate::shared::frame::Frame, Box<dyn std::error::Error>>>
+                + '_,
+        > {
+            Box::new((0..NUMBERED_FRAMES).map(|i| {
+                let data = vec![brightness_of(i); 64 * 48 * 3];
+                Ok(crate::shared::frame::Frame::new(data, 64, 48, 3, i)
+                    .with_timestamp(i as f64 / NUMBERED_FPS))
+            }))
+        }
+
+        fn close(&mut self) {}
+    }
+
+    #[test]
+    fn test_audio_and_video_agree_on_output_times_after_cuts() {
+        use crate::video::domain::video_reader::VideoReader;
+        use crate::video::domain::video_writer::VideoWriter;
+        use crate::video::infrastructure::cut_range_reader::CutRangeReader;
+        use crate::video::infrastructure::ffmpeg_reader::FfmpegReader;
+        use crate::video::infrastructure::ffmpeg_writer::FfmpegWriter;
+
+        let time_map = TimeMap::new()
+            .with_frame_rate(NUMBERED_FPS)
+            .with_cuts(&[TimeRange::new(0.5, 1.0), TimeRange::new(1.5, 2.0)]);
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("out.mp4");
+
+        // Video: the shared map drops the cuts on read and places frames
+        // on write.
+        let mut reader = CutRangeReader::new(Box::new(NumberedFrameReader), time_map.clone());
+        let metadata = reader.open(Path::new("in.mp4")).unwrap();
+        let mut writer = FfmpegWriter::new().with_time_map(time_map.clone());
+        writer.open(&output, &metadata).unwrap();
+        for frame in reader.frames() {
+            writer.write(&frame.unwrap()).unwrap();
+        }
+        writer.close().unwrap();
+
+        // Audio: a bleep over source frame 25, on the same map.
+        let audio_writer = StubAudioWriter {
+            written: Arc::new(Mutex::new(None)),
+        };
+        let written = audio_writer.written.clone();
+        ProcessAudioUseCase::new(
+            Box::new(StubAudioReader {
+                segment: Some(AudioSegment::new(vec![0.0; 4 * 8000], 8000, 1)),
+            }),
+            Box::new(audio_writer),
+            None,
+            None,
+            vec![],
+            BleepStyle::default(),
+        )
+        .with_bleep_ranges(vec![TimeRange::new(2.5, 2.6)])
+        .with_time_map(time_map)
+        .run(Path::new("in.mp4"), &output)
+        .unwrap();
+        let written = written.lock().unwrap();
+        let audio = written.as_ref().unwrap();
+        let bleep_start = audio.samples().iter().position(|s| *s != 0.0).unwrap() as f64
+            / audio.sample_rate() as f64;
+
+        let mut decoded = FfmpegReader::new();
+        decoded.open(&output).unwrap();
+        let shown_at = decoded
+            .frames()
+            .map(|f| f.unwrap())
+            .find(|frame| {
+                let mean =
+                    frame.data().iter().map(|&b| b as f64).sum::<f64>() / frame.data().len() as f64;
+                (mean - brightness_of(25) as f64).abs() < 2.5
+            })
+            .and_then(|frame| frame.timestamp())
+            .unwrap();
+
+        assert!(
+            (shown_at - 1.5).abs() < 1e-3,
+            "frame 25 shown at {shown_at}"
+        );
+        assert!(
+            (bleep_start - shown_at).abs() < 1.0 / NUMBERED_FPS,
+            "bleep starts at {bleep_start}, frame 25 shows at {shown_at}"
+        );
+        assert!((audio.duration() - 3.0).abs() < 1e-3);
+    }
 }
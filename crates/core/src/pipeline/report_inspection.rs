@@ -0,0 +1,489 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::detection::infrastructure::detection_csv::CSV_HEADER;
+use crate::pipeline::detection_report::REPORT_SCHEMA;
+use crate::shared::schema::SchemaError;
+
+/// Appearances of a track separated by at most this many frames are shown
+/// as one span, so a detection dropping out for a frame or two doesn't
+/// split the timeline.
+pub const SPAN_GAP_FRAMES: usize = 3;
+
+#[derive(Debug, Error)]
+pub enum InspectError {
+    #[error("Could not read report: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Report is not valid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Schema(#[from] SchemaError),
+    #[error("Not a FaceGuard detection report, detection CSV, or anonymization report")]
+    Unrecognized,
+    #[error("Malformed report: {0}")]
+    Malformed(String),
+}
+
+/// Which file an inspection was read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportKind {
+    /// JSON from `format_detection_report` (CLI `--report`).
+    Detections,
+    /// Per-frame CSV from `format_detection_csv` (CLI `--detections-csv`).
+    DetectionCsv,
+    /// The desktop app's `<output>.report.json`: tracks, whether each was
+    /// blurred, and the job's warnings.
+    Anonymization,
+}
+
+impl ReportKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReportKind::Detections => "Detection report",
+            ReportKind::DetectionCsv => "Detection CSV",
+            ReportKind::Anonymization => "Anonymization report",
+        }
+    }
+}
+
+/// Consecutive frames, both ends included.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// What a report says about one face track.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrackSummary {
+    /// `None` collects faces that were never given a track.
+    pub track_id: Option<u32>,
+    pub label: String,
+    pub note: String,
+    /// Whether the track was blurred; only anonymization reports say.
+    pub blurred: Option<bool>,
+    /// Time ranges the track was limited to, as entered.
+    pub blur_ranges: String,
+    /// Frames the track appears on.
+    pub frames: usize,
+    /// Mean model score over the frames that have one.
+    pub mean_confidence: Option<f64>,
+    /// Where the track appears, in frame order.
+    pub spans: Vec<FrameSpan>,
+}
+
+impl TrackSummary {
+    /// Splits `total_frames` into `buckets` equal slices and marks those the
+    /// track appears in, for drawing a timeline strip.
+    pub fn occupancy(&self, total_frames: usize, buckets: usize) -> Vec<bool> {
+        let mut marks = vec![false; buckets];
+        if total_frames == 0 || buckets == 0 {
+            return marks;
+        }
+        for span in &self.spans {
+            let first = span.start * buckets / total_frames;
+            let last = span.end * buckets / total_frames;
+            for mark in &mut marks[first.min(buckets - 1)..=last.min(buckets - 1)] {
+                *mark = true;
+            }
+        }
+        marks
+    }
+}
+
+/// A non-fatal problem recorded in an anonymization report.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InspectedWarning {
+    pub kind: String,
+    pub message: String,
+}
+
+/// Read-only summary of a report or detection export, for reviewers who
+/// audit outputs without processing media themselves.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReportInspection {
+    pub kind: ReportKind,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// 0 when the report doesn't say.
+    pub fps: f64,
+    /// 0 when the report doesn't say.
+    pub total_frames: usize,
+    pub frames_with_faces: usize,
+    pub source: Option<String>,
+    pub output: Option<String>,
+    pub tracks: Vec<TrackSummary>,
+    pub warnings: Vec<InspectedWarning>,
+}
+
+impl ReportInspection {
+    /// Share of frames with at least one face, when the frame count is known.
+    pub fn coverage(&self) -> Option<f64> {
+        (self.total_frames > 0).then(|| self.frames_with_faces as f64 / self.total_frames as f64)
+    }
+
+    /// Seconds from the start at `frame`, when the frame rate is known.
+    pub fn seconds(&self, frame: usize) -> Option<f64> {
+        (self.fps > 0.0).then(|| frame as f64 / self.fps)
+    }
+
+    /// Tracks the report marks as left visible.
+    pub fn unblurred_tracks(&self) -> impl Iterator<Item = &TrackSummary> {
+        self.tracks.iter().filter(|t| t.blurred == Some(false))
+    }
+}
+
+/// Reads a detection report, detection CSV, or anonymization report,
+/// telling them apart by content rather than name.
+pub fn inspect_report(path: &Path) -> Result<ReportInspection, InspectError> {
+    let content = fs::read_to_string(path)?;
+    let trimmed = content.trim_start();
+    if trimmed.starts_with(CSV_HEADER) {
+        return inspect_detection_csv(trimmed);
+    }
+    if !trimmed.starts_with('{') {
+        return Err(InspectError::Unrecognized);
+    }
+    let document: Value = serde_json::from_str(trimmed)?;
+    if document.get("frames").is_some() {
+        inspect_detections(REPORT_SCHEMA.upgrade(document)?)
+    } else if document.get("tracks").is_some() {
+        inspect_anonymization(&document)
+    } else {
+        Err(InspectError::Unrecognized)
+    }
+}
+
+/// Per-track appearances, gathered before they are summarized.
+#[derive(Default)]
+struct Appearances {
+    frames: Vec<usize>,
+    confidence_sum: f64,
+    confidence_count: usize,
+}
+
+fn inspect_detections(document: Value) -> Result<ReportInspection, InspectError> {
+    let malformed = |what: &str| InspectError::Malformed(what.to_string());
+    let frames = document["frames"]
+        .as_array()
+        .ok_or_else(|| malformed("`frames` is not a list"))?;
+
+    let mut tracks: BTreeMap<Option<u32>, Appearances> = BTreeMap::new();
+    let mut frames_with_faces = 0;
+    for entry in frames {
+        let frame = entry["frame"]
+            .as_u64()
+            .ok_or_else(|| malformed("a frame has no `frame` number"))?
+            as usize;
+        let faces = entry["faces"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+        if !faces.is_empty() {
+            frames_with_faces += 1;
+        }
+        for face in faces {
+            let track_id = face["track_id"].as_u64().map(|id| id as u32);
+            let appearances = tracks.entry(track_id).or_default();
+            if appearances.frames.last() != Some(&frame) {
+                appearances.frames.push(frame);
+            }
+            if let Some(confidence) = face["confidence"].as_f64() {
+                appearances.confidence_sum += confidence;
+                appearances.confidence_count += 1;
+            }
+        }
+    }
+
+    Ok(ReportInspection {
+        kind: ReportKind::Detections,
+        width: document["width"].as_u64().map(|w| w as u32),
+        height: document["height"].as_u64().map(|h| h as u32),
+        fps: document["fps"].as_f64().unwrap_or(0.0),
+        total_frames: document["total_frames"].as_u64().unwrap_or(0) as usize,
+        frames_with_faces,
+        source: None,
+        output: None,
+        tracks: summarize(tracks),
+        warnings: Vec::new(),
+    })
+}
+
+fn inspect_detection_csv(content: &str) -> Result<ReportInspection, InspectError> {
+    let mut tracks: BTreeMap<Option<u32>, Appearances> = BTreeMap::new();
+    let mut frames_with_faces = 0;
+    let mut total_frames = 0;
+    let mut last_timestamp = None;
+    for (number, line) in content.lines().enumerate().skip(1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let malformed = || InspectError::Malformed(format!("CSV line {}", number + 1));
+        let mut fields = line.split(',');
+        let frame: usize = fields
+            .next()
+            .and_then(|f| f.trim().parse().ok())
+            .ok_or_else(malformed)?;
+        let timestamp: Option<f64> = fields.next().and_then(|t| t.trim().parse().ok());
+        let faces: usize = fields
+            .next()
+            .and_then(|f| f.trim().parse().ok())
+            .ok_or_else(malformed)?;
+        total_frames = total_frames.max(frame + 1);
+        if frame > 0 {
+            last_timestamp = timestamp.map(|t| (frame, t));
+        }
+        if faces == 0 {
+            continue;
+        }
+        frames_with_faces += 1;
+        let ids: Vec<u32> = fields
+            .next()
+            .unwrap_or("")
+            .split(';')
+            .filter_map(|id| id.trim().parse().ok())
+            .collect();
+        for &id in &ids {
+            tracks.entry(Some(id)).or_default().frames.push(frame);
+        }
+        if ids.len() < faces {
+            tracks.entry(None).or_default().frames.push(frame);
+        }
+    }
+
+    let fps = match last_timestamp {
+        Some((frame, seconds)) if seconds > 0.0 => frame as f64 / seconds,
+        _ => 0.0,
+    };
+    Ok(ReportInspection {
+        kind: ReportKind::DetectionCsv,
+        width: None,
+        height: None,
+        fps,
+        total_frames,
+        frames_with_faces,
+        source: None,
+        output: None,
+        tracks: summarize(tracks),
+        warnings: Vec::new(),
+    })
+}
+
+fn inspect_anonymization(document: &Value) -> Result<ReportInspection, InspectError> {
+    let text = |value: &Value| value.as_str().unwrap_or_default().to_string();
+    let tracks = document["tracks"]
+        .as_array()
+        .ok_or_else(|| InspectError::Malformed("`tracks` is not a list".into()))?
+        .iter()
+        .map(|track| TrackSummary {
+            track_id: track["track_id"].as_u64().map(|id| id as u32),
+            label: text(&track["label"]),
+            note: text(&track["note"]),
+            blurred: track["blurred"].as_bool(),
+            blur_ranges: text(&track["blur_ranges"]),
+            ..TrackSummary::default()
+        })
+        .collect();
+    let warnings = document["warnings"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+        .iter()
+        .map(|warning| InspectedWarning {
+            kind: text(&warning["kind"]),
+            message: text(&warning["message"]),
+        })
+        .collect();
+
+    Ok(ReportInspection {
+        kind: ReportKind::Anonymization,
+        width: None,
+        height: None,
+        fps: 0.0,
+        total_frames: 0,
+        frames_with_faces: 0,
+        source: document["source"].as_str().map(str::to_string),
+        output: document["output"].as_str().map(str::to_string),
+        tracks,
+        warnings,
+    })
+}
+
+fn summarize(tracks: BTreeMap<Option<u32>, Appearances>) -> Vec<TrackSummary> {
+    let mut summaries: Vec<TrackSummary> = tracks
+        .into_iter()
+        .map(|(track_id, mut appearances)| {
+            appearances.frames.sort_unstable();
+            appearances.frames.dedup();
+            TrackSummary {
+                track_id,
+                frames: appearances.frames.len(),
+                mean_confidence: (appearances.confidence_count > 0)
+                    .then(|| appearances.confidence_sum / appearances.confidence_count as f64),
+                spans: spans(&appearances.frames),
+                ..TrackSummary::default()
+            }
+        })
+        .collect();
+    // Tracked faces first, by ID; untracked faces last.
+    summaries.sort_by_key(|t| (t.track_id.is_none(), t.track_id));
+    summaries
+}
+
+/// Groups sorted frame numbers into spans, bridging gaps of up to
+/// `SPAN_GAP_FRAMES`.
+pub(crate) fn spans(frames: &[usize]) -> Vec<FrameSpan> {
+    let mut spans: Vec<FrameSpan> = Vec::new();
+    for &frame in frames {
+        match spans.last_mut() {
+            Some(span) if frame <= span.end + SPAN_GAP_FRAMES + 1 => span.end = frame,
+            _ => spans.push(FrameSpan {
+                start: frame,
+                end: frame,
+            }),
+        }
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) -> std::path::PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    const DETECTIONS: &str = r#"{
+  "version": 1,
+  "width": 1920,
+  "height": 1080,
+  "fps": 10.0,
+  "total_frames": 100,
+  "frames": [
+    {"frame": 0, "timestamp": 0.000, "faces": [{"track_id": 2, "x": 1, "y": 1, "width": 9, "height": 9, "confidence": 0.8, "landmarks": null}]},
+    {"frame": 1, "timestamp": 0.100, "faces": [{"track_id": 2, "x": 1, "y": 1, "width": 9, "height": 9, "confidence": 0.6, "landmarks": null}, {"track_id": null, "x": 50, "y": 1, "width": 9, "height": 9, "confidence": null, "landmarks": null}]},
+    {"frame": 3, "timestamp": 0.300, "faces": [{"track_id": 2, "x": 1, "y": 1, "width": 9, "height": 9, "confidence": null, "landmarks": null}]},
+    {"frame": 40, "timestamp": 4.000, "faces": [{"track_id": 2, "x": 1, "y": 1, "width": 9, "height": 9, "confidence": null, "landmarks": null}, {"track_id": 1, "x": 50, "y": 1, "width": 9, "height": 9, "confidence": 0.9, "landmarks": null}]}
+  ]
+}
+"#;
+
+    #[test]
+    fn test_detection_report_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        let inspection = inspect_report(&write(dir.path(), "d.json", DETECTIONS)).unwrap();
+
+        assert_eq!(inspection.kind, ReportKind::Detections);
+        assert_eq!(
+            (inspection.width, inspection.height),
+            (Some(1920), Some(1080))
+        );
+        assert_eq!(inspection.frames_with_faces, 4);
+        assert_eq!(inspection.coverage(), Some(0.04));
+        assert_eq!(inspection.seconds(40), Some(4.0));
+
+        let ids: Vec<Option<u32>> = inspection.tracks.iter().map(|t| t.track_id).collect();
+        assert_eq!(ids, vec![Some(1), Some(2), None]);
+        let track = &inspection.tracks[1];
+        assert_eq!(track.frames, 4);
+        assert!((track.mean_confidence.unwrap() - 0.7).abs() < 1e-9);
+        assert_eq!(
+            track.spans,
+            vec![
+                FrameSpan { start: 0, end: 3 },
+                FrameSpan { start: 40, end: 40 }
+            ]
+        );
+        assert_eq!(inspection.tracks[2].mean_confidence, None);
+    }
+
+    #[test]
+    fn test_newer_detection_report_is_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let newer = DETECTIONS.replace("\"version\": 1", "\"version\": 99");
+        let result = inspect_report(&write(dir.path(), "d.json", &newer));
+        assert!(matches!(result, Err(InspectError::Schema(_))));
+    }
+
+    #[test]
+    fn test_detection_csv_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv = format!("{CSV_HEADER}\n0,0.000,1,4\n1,0.040,2,4;5\n2,0.080,0,\n3,0.120,1,\n");
+        let inspection = inspect_report(&write(dir.path(), "d.csv", &csv)).unwrap();
+
+        assert_eq!(inspection.kind, ReportKind::DetectionCsv);
+        assert_eq!(inspection.total_frames, 4);
+        assert_eq!(inspection.frames_with_faces, 3);
+        assert!((inspection.fps - 25.0).abs() < 1e-9);
+        let frames: Vec<(Option<u32>, usize)> = inspection
+            .tracks
+            .iter()
+            .map(|t| (t.track_id, t.frames))
+            .collect();
+        assert_eq!(frames, vec![(Some(4), 2), (Some(5), 1), (None, 1)]);
+    }
+
+    #[test]
+    fn test_anonymization_report_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = r#"{
+  "source": "/in/talk.mp4",
+  "output": "/out/talk_blurred.mp4",
+  "tracks": [
+    {"track_id": 1, "label": "Host", "note": "", "blur_ranges": "", "blurred": false},
+    {"track_id": 2, "label": "", "note": "audience", "blur_ranges": "0-10", "blurred": true}
+  ],
+  "warnings": [{"kind": "audio", "message": "Audio kept as original"}]
+}"#;
+        let inspection = inspect_report(&write(dir.path(), "r.json", report)).unwrap();
+
+        assert_eq!(inspection.kind, ReportKind::Anonymization);
+        assert_eq!(inspection.source.as_deref(), Some("/in/talk.mp4"));
+        assert_eq!(inspection.tracks.len(), 2);
+        let visible: Vec<&str> = inspection
+            .unblurred_tracks()
+            .map(|t| t.label.as_str())
+            .collect();
+        assert_eq!(visible, vec!["Host"]);
+        assert_eq!(
+            inspection.warnings,
+            vec![InspectedWarning {
+                kind: "audio".into(),
+                message: "Audio kept as original".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_other_files_are_unrecognized() {
+        let dir = tempfile::tempdir().unwrap();
+        for content in ["hello", "{\"name\": 1}", "a,b\n1,2\n"] {
+            let result = inspect_report(&write(dir.path(), "x", content));
+            assert!(
+                matches!(result, Err(InspectError::Unrecognized)),
+                "{content}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_occupancy() {
+        let track = TrackSummary {
+            spans: vec![
+                FrameSpan { start: 0, end: 9 },
+                FrameSpan { start: 95, end: 99 },
+            ],
+            ..TrackSummary::default()
+        };
+        let marks = track.occupancy(100, 10);
+        assert_eq!(
+            marks,
+            vec![true, false, false, false, false, false, false, false, false, true]
+        );
+        assert!(track.occupancy(0, 10).iter().all(|&m| !m));
+    }
+}
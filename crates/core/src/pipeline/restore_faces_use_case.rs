@@ -0,0 +1,196 @@
+use std::path::Path;
+
+use crate::restoration::infrastructure::restoration_sidecar::RestorationSidecarReader;
+use crate::video::domain::video_reader::VideoReader;
+use crate::video::domain::video_writer::VideoWriter;
+
+/// Puts the original faces back into an anonymized video from its
+/// encrypted restoration sidecar.
+///
+/// Every frame is re-encoded; frames the sidecar holds patches for get
+/// their original pixels written back first. The video must be the one the
+/// sidecar was written with, frame for frame, so a sidecar that outlasts
+/// the video or a patch that doesn't fit the frame is an error.
+pub struct RestoreFacesUseCase {
+    reader: Box<dyn VideoReader>,
+    writer: Box<dyn VideoWriter>,
+    sidecar: RestorationSidecarReader,
+}
+
+impl RestoreFacesUseCase {
+    pub fn new(
+        reader: Box<dyn VideoReader>,
+        writer: Box<dyn VideoWriter>,
+        sidecar: RestorationSidecarReader,
+    ) -> Self {
+        Self {
+            reader,
+            writer,
+            sidecar,
+        }
+    }
+
+    /// Returns the number of frames that had faces restored.
+    pub fn execute(
+        &mut self,
+        input_path: &Path,
+        output_path: &Path,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let metadata = self.reader.open(input_path)?;
+        self.writer.open(output_path, &metadata)?;
+
+        let mut pending = self.sidecar.next_frame()?;
+        let mut restored = 0;
+        for frame in self.reader.frames() {
+            let mut frame = frame?;
+            if let Some((index, patches)) = &pending {
+                if *index < frame.index() {
+                    return Err(format!(
+                        "Restoration sidecar has frame {index} out of order; it does not belong to this video"
+                    )
+                    .into());
+                }
+                if *index == frame.index() {
+                    for patch in patches {
+                        patch.apply(&mut frame)?;
+                    }
+                    restored += 1;
+                    pending = self.sidecar.next_frame()?;
+                }
+            }
+            self.writer.write(&frame)?;
+        }
+        self.reader.close();
+        self.writer.close()?;
+
+        if let Some((index, _)) = pending {
+            return Err(format!(
+                "Restoration sidecar has faces for frame {index}, past the end of the video"
+            )
+            .into());
+        }
+        Ok(restored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::restoration::domain::restoration_patch::RestorationPatch;
+    use crate::restoration::infrastructure::restoration_sidecar::RestorationSidecarWriter;
+    use crate::shared::color_space::ColorSpace;
+    use crate::shared::frame::Frame;
+    use crate::shared::video_metadata::VideoMetadata;
+    use std::sync::{Arc, Mutex};
+
+    struct StubReader {
+        frames: Vec<Frame>,
+    }
+
+    impl VideoReader for StubReader {
+        fn open(&mut self, _path: &Path) -> Result<VideoMetadata, Box<dyn std::error::Error>> {
+            Ok(VideoMetadata {
+                width: 8,
+                height: 8,
+                fps: 30.0,
+                total_frames: self.frames.len(),
+                codec: "h264".into(),
+                source_path: None,
+                rotation: 0,
+                color: ColorSpace::default(),
+            })
+        }
+
+        fn frames(
+            &mut self,
+        ) -> Box<dyn Iterator<Item = Result<Frame, Box<dyn std::error::Error>>> + '_> {
+            Box::new(self.frames.drain(..).map(Ok))
+        }
+
+        fn close(&mut self) {}
+    }
+
+    struct StubWriter {
+        written: Arc<Mutex<Vec<Frame>>>,
+    }
+
+    impl VideoWriter for StubWriter {
+        fn open(
+            &mut self,
+            _path: &Path,
+            _metadata: &VideoMetadata,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+
+        fn write(&mut self, frame: &Frame) -> Result<(), Box<dyn std::error::Error>> {
+            self.written.lock().unwrap().push(frame.clone());
+            Ok(())
+        }
+
+        fn close(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+    }
+
+    fn gray_frames(count: usize) -> Vec<Frame> {
+        (0..count)
+            .map(|i| Frame::new(vec![0; 8 * 8 * 3], 8, 8, 3, i))
+            .collect()
+    }
+
+    fn white_patch() -> RestorationPatch {
+        RestorationPatch {
+            x: 2,
+            y: 2,
+            width: 2,
+            height: 2,
+            pixels: vec![255; 12],
+        }
+    }
+
+    fn run(
+        frames: usize,
+        records: &[usize],
+    ) -> (Result<usize, Box<dyn std::error::Error>>, Vec<Frame>) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("faces.fgrestore");
+        let mut writer = RestorationSidecarWriter::create(&path, "secret").unwrap();
+        for &index in records {
+            writer.write_frame(index, &[white_patch()]).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let mut use_case = RestoreFacesUseCase::new(
+            Box::new(StubReader {
+                frames: gray_frames(frames),
+            }),
+            Box::new(StubWriter {
+                written: written.clone(),
+            }),
+            RestorationSidecarReader::open(&path, "secret").unwrap(),
+        );
+        let result = use_case.execute(Path::new("in.mp4"), Path::new("out.mp4"));
+        let frames = written.lock().unwrap().clone();
+        (result, frames)
+    }
+
+    #[test]
+    fn test_restores_recorded_frames_only() {
+        let (result, frames) = run(4, &[1, 3]);
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(frames.len(), 4);
+        let restored: Vec<bool> = frames
+            .iter()
+            .map(|f| f.data()[(2 * 8 + 2) * 3] == 255)
+            .collect();
+        assert_eq!(restored, vec![false, true, false, true]);
+    }
+
+    #[test]
+    fn test_sidecar_longer_than_video_is_an_error() {
+        let (result, _) = run(2, &[1, 5]);
+        assert!(result.unwrap_err().to_string().contains("frame 5"));
+    }
+}
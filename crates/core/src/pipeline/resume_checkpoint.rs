@@ -0,0 +1,188 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::detection::domain::face_detector::DetectorState;
+use crate::shared::schema::{Schema, SchemaError};
+
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+pub const CHECKPOINT_SCHEMA: Schema = Schema::new("Resume checkpoint", CHECKPOINT_VERSION, &[]);
+
+/// Frames encoded between checkpoints by default: a minute at 30 fps, so
+/// an interruption loses at most about a minute of work.
+pub const DEFAULT_CHECKPOINT_FRAMES: usize = 1800;
+
+#[derive(Debug, Error)]
+pub enum CheckpointError {
+    #[error("Could not access resume checkpoint {path}: {source}")]
+    Io { path: PathBuf, source: io::Error },
+    #[error("Resume checkpoint is not valid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Schema(#[from] SchemaError),
+    #[error("Malformed resume checkpoint: {0}")]
+    Malformed(&'static str),
+}
+
+/// How far an interrupted export got, saved next to its output so the
+/// export can pick up from there.
+///
+/// The video is encoded in parts; once a part is finished the checkpoint
+/// lists it with the first frame still to encode and the detector's state
+/// after the frame before it (tracks, smoothing). Resuming seeks to
+/// `next_frame`, restores the detector, and encodes the remaining parts.
+/// `settings` identifies the input and options, so a checkpoint is only
+/// resumed by the same export.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResumeCheckpoint {
+    pub settings: String,
+    pub next_frame: usize,
+    pub parts: Vec<PathBuf>,
+    pub detector: DetectorState,
+}
+
+impl ResumeCheckpoint {
+    /// `out.mp4` → `out.mp4.resume.json`, next to the output.
+    pub fn path_for(output: &Path) -> PathBuf {
+        let mut name = output.file_name().unwrap_or_default().to_os_string();
+        name.push(".resume.json");
+        output.with_file_name(name)
+    }
+
+    /// The checkpoint at `path`, or `None` when there is none.
+    pub fn load(path: &Path) -> Result<Option<Self>, CheckpointError> {
+        match fs::read_to_string(path) {
+            Ok(json) => Self::from_json(&json).map(Some),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(CheckpointError::Io {
+                path: path.to_path_buf(),
+                source,
+            }),
+        }
+    }
+
+    /// Writes the checkpoint to `path` (temp file + rename, so an
+    /// interruption while saving keeps the previous checkpoint).
+    pub fn save(&self, path: &Path) -> Result<(), CheckpointError> {
+        let io_error = |source| CheckpointError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, self.to_json()).map_err(io_error)?;
+        fs::rename(&temp, path).map_err(io_error)
+    }
+
+    /// Deletes the checkpoint at `path`, if any, once the export is done.
+    pub fn remove(path: &Path) -> Result<(), CheckpointError> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(CheckpointError::Io {
+                path: path.to_path_buf(),
+                source: e,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let parts: Vec<String> = self
+            .parts
+            .iter()
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect();
+        json!({
+            "version": CHECKPOINT_SCHEMA.version(),
+            "settings": self.settings,
+            "next_frame": self.next_frame,
+            "parts": parts,
+            "detector": self.detector,
+        })
+        .to_string()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, CheckpointError> {
+        let document = CHECKPOINT_SCHEMA.upgrade(serde_json::from_str(json)?)?;
+        let parts = document["parts"]
+            .as_array()
+            .ok_or(CheckpointError::Malformed("no parts list"))?
+            .iter()
+            .map(|p| p.as_str().map(PathBuf::from))
+            .collect::<Option<_>>()
+            .ok_or(CheckpointError::Malformed("a part is not a path"))?;
+        Ok(Self {
+            settings: document["settings"]
+                .as_str()
+                .ok_or(CheckpointError::Malformed("no settings"))?
+                .to_string(),
+            next_frame: document["next_frame"]
+                .as_u64()
+                .ok_or(CheckpointError::Malformed("no next frame"))?
+                as usize,
+            parts,
+            detector: document.get("detector").cloned().unwrap_or(Value::Null),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint() -> ResumeCheckpoint {
+        ResumeCheckpoint {
+            settings: "abc-123".into(),
+            next_frame: 3600,
+            parts: vec!["out.part000.mp4".into(), "out.part001.mp4".into()],
+            detector: json!({ "tracker": { "next_id": 4 } }),
+        }
+    }
+
+    #[test]
+    fn test_path_is_next_to_output() {
+        assert_eq!(
+            ResumeCheckpoint::path_for(Path::new("/videos/out.mp4")),
+            PathBuf::from("/videos/out.mp4.resume.json")
+        );
+    }
+
+    #[test]
+    fn test_round_trips_through_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.mp4.resume.json");
+
+        checkpoint().save(&path).unwrap();
+
+        assert_eq!(ResumeCheckpoint::load(&path).unwrap(), Some(checkpoint()));
+    }
+
+    #[test]
+    fn test_missing_file_is_none() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("none.resume.json");
+
+        assert_eq!(ResumeCheckpoint::load(&path).unwrap(), None);
+        ResumeCheckpoint::remove(&path).unwrap();
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let json = json!({ "version": CHECKPOINT_VERSION + 1, "settings": "", "next_frame": 0, "parts": [] });
+        assert!(matches!(
+            ResumeCheckpoint::from_json(&json.to_string()),
+            Err(CheckpointError::Schema(_))
+        ));
+    }
+
+    #[test]
+    fn test_malformed_checkpoint_is_error() {
+        let json = json!({ "version": CHECKPOINT_VERSION, "settings": "" });
+        assert!(matches!(
+            ResumeCheckpoint::from_json(&json.to_string()),
+            Err(CheckpointError::Malformed(_))
+        ));
+    }
+}
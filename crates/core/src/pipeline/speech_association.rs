@@ -0,0 +1,185 @@
+use std::collections::HashSet;
+
+use crate::audio::domain::transcript::TranscriptWord;
+use crate::detection::domain::mouth_activity::MouthActivity;
+use crate::shared::track_time_rules::TimeRange;
+
+/// Longest pause between words that still counts as one utterance.
+pub const DEFAULT_MAX_WORD_GAP: f64 = 0.6;
+
+/// Share of an utterance's frames a face must be visible in to be matched.
+const MIN_COVERAGE: f64 = 0.5;
+/// Mean activity below which a face is not considered to be talking.
+const MIN_ACTIVITY: f64 = 0.01;
+/// How far the most active face must lead the runner-up to be matched.
+const DOMINANCE: f64 = 1.5;
+
+/// Groups transcript words into utterances, splitting wherever the pause
+/// between two words exceeds `max_gap` seconds.
+pub fn speech_segments(words: &[TranscriptWord], max_gap: f64) -> Vec<TimeRange> {
+    let mut segments: Vec<TimeRange> = Vec::new();
+    for word in words {
+        match segments.last_mut() {
+            Some(last) if word.start_time - last.end <= max_gap => {
+                last.end = last.end.max(word.end_time);
+            }
+            _ => segments.push(TimeRange::new(word.start_time, word.end_time)),
+        }
+    }
+    segments
+}
+
+/// Experimental audiovisual matching of speech to on-screen faces.
+///
+/// Each utterance goes to the tracked face whose mouth moved most while it
+/// was spoken, provided that face was visible for at least half of it,
+/// moved noticeably, and clearly out-moved every other face. Utterances
+/// without a clear match (an off-screen speaker, two people talking over
+/// each other) stay unmatched. Selecting faces for blurring can then select
+/// their speech for voice disguise through `ranges_for`.
+///
+/// Mouth movement is a weak cue on its own: chewing, smiling, and motion
+/// blur all register. It is meant to be combined with speaker diarization
+/// before frontends offer it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SpeechAssociation {
+    segments: Vec<(TimeRange, Option<u32>)>,
+}
+
+impl SpeechAssociation {
+    /// Matches `segments` to tracks using mouth `activity` sampled at `fps`.
+    pub fn associate(segments: &[TimeRange], activity: &MouthActivity, fps: f64) -> Self {
+        let segments = segments
+            .iter()
+            .map(|&segment| (segment, speaker(segment, activity, fps)))
+            .collect();
+        Self { segments }
+    }
+
+    /// Every utterance with the track it was matched to, if any.
+    pub fn segments(&self) -> &[(TimeRange, Option<u32>)] {
+        &self.segments
+    }
+
+    /// Utterances matched to any of `tracks`, in time order, for
+    /// `ProcessAudioUseCase::with_time_ranges`.
+    pub fn ranges_for(&self, tracks: &HashSet<u32>) -> Vec<TimeRange> {
+        self.segments
+            .iter()
+            .filter(|(_, track)| track.is_some_and(|t| tracks.contains(&t)))
+            .map(|(range, _)| *range)
+            .collect()
+    }
+}
+
+fn speaker(segment: TimeRange, activity: &MouthActivity, fps: f64) -> Option<u32> {
+    if fps <= 0.0 {
+        return None;
+    }
+    let first = (segment.start * fps).ceil() as usize;
+    let last = ((segment.end * fps).floor() as usize).max(first);
+    let frames = (last - first + 1) as f64;
+
+    let mut scores: Vec<(u32, f64)> = activity
+        .iter()
+        .filter_map(|(&track, samples)| {
+            let values: Vec<f64> = samples
+                .iter()
+                .filter(|(frame, _)| (first..=last).contains(frame))
+                .map(|&(_, value)| value)
+                .collect();
+            (values.len() as f64 / frames >= MIN_COVERAGE)
+                .then(|| (track, values.iter().sum::<f64>() / values.len() as f64))
+        })
+        .collect();
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    match scores.as_slice() {
+        [] => None,
+        [(track, best), rest @ ..] => {
+            let runner_up = rest.first().map_or(0.0, |&(_, score)| score);
+            (*best >= MIN_ACTIVITY && *best >= runner_up * DOMINANCE).then_some(*track)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(start: f64, end: f64) -> TranscriptWord {
+        TranscriptWord {
+            word: "word".into(),
+            start_time: start,
+            end_time: end,
+            confidence: 0.9,
+        }
+    }
+
+    /// Constant activity `value` on every frame in `frames`.
+    fn samples(frames: std::ops::Range<usize>, value: f64) -> Vec<(usize, f64)> {
+        frames.map(|f| (f, value)).collect()
+    }
+
+    #[test]
+    fn test_speech_segments_split_on_pauses() {
+        let words = [
+            word(0.0, 0.4),
+            word(0.5, 0.9),
+            word(2.0, 2.3),
+            word(2.4, 2.8),
+        ];
+        assert_eq!(
+            speech_segments(&words, DEFAULT_MAX_WORD_GAP),
+            vec![TimeRange::new(0.0, 0.9), TimeRange::new(2.0, 2.8)]
+        );
+        assert!(speech_segments(&[], DEFAULT_MAX_WORD_GAP).is_empty());
+    }
+
+    #[test]
+    fn test_most_active_face_gets_the_segment() {
+        let activity = MouthActivity::from([(1, samples(0..30, 0.08)), (2, samples(0..30, 0.01))]);
+        let association =
+            SpeechAssociation::associate(&[TimeRange::new(0.0, 0.9)], &activity, 30.0);
+        assert_eq!(association.segments()[0].1, Some(1));
+    }
+
+    #[test]
+    fn test_close_scores_stay_unmatched() {
+        let activity = MouthActivity::from([(1, samples(0..30, 0.05)), (2, samples(0..30, 0.04))]);
+        let association =
+            SpeechAssociation::associate(&[TimeRange::new(0.0, 0.9)], &activity, 30.0);
+        assert_eq!(association.segments()[0].1, None);
+    }
+
+    #[test]
+    fn test_still_faces_stay_unmatched() {
+        let activity = MouthActivity::from([(1, samples(0..30, 0.001))]);
+        let association =
+            SpeechAssociation::associate(&[TimeRange::new(0.0, 0.9)], &activity, 30.0);
+        assert_eq!(association.segments()[0].1, None);
+    }
+
+    #[test]
+    fn test_briefly_visible_face_is_not_matched() {
+        // Visible for 5 of 28 frames.
+        let activity = MouthActivity::from([(1, samples(0..5, 0.2))]);
+        let association =
+            SpeechAssociation::associate(&[TimeRange::new(0.0, 0.9)], &activity, 30.0);
+        assert_eq!(association.segments()[0].1, None);
+    }
+
+    #[test]
+    fn test_ranges_for_selected_tracks() {
+        let activity = MouthActivity::from([(1, samples(0..30, 0.08)), (2, samples(60..90, 0.08))]);
+        let segments = [TimeRange::new(0.0, 0.9), TimeRange::new(2.0, 2.9)];
+        let association = SpeechAssociation::associate(&segments, &activity, 30.0);
+
+        assert_eq!(
+            association.ranges_for(&HashSet::from([2])),
+            vec![TimeRange::new(2.0, 2.9)]
+        );
+        assert_eq!(association.ranges_for(&HashSet::from([1, 2])).len(), 2);
+        assert!(association.ranges_for(&HashSet::from([3])).is_empty());
+    }
+}
@@ -0,0 +1 @@
+pub mod restoration_patch;
@@ -0,0 +1,128 @@
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// Original pixels of one anonymized region, kept so an authorized person
+/// can put the face back later.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RestorationPatch {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Row-major RGB, `width * height * 3` bytes.
+    pub pixels: Vec<u8>,
+}
+
+impl RestorationPatch {
+    /// Copies the visible part of `region` out of an RGB `frame`, before it
+    /// is blurred. `None` when the region lies entirely off the frame.
+    pub fn capture(frame: &Frame, region: &Region) -> Option<Self> {
+        let fw = frame.width() as i32;
+        let fh = frame.height() as i32;
+        let x0 = region.x.clamp(0, fw);
+        let y0 = region.y.clamp(0, fh);
+        let x1 = (region.x + region.width).clamp(0, fw);
+        let y1 = (region.y + region.height).clamp(0, fh);
+        if x1 <= x0 || y1 <= y0 {
+            return None;
+        }
+
+        let channels = frame.channels() as usize;
+        let (w, h) = ((x1 - x0) as usize, (y1 - y0) as usize);
+        let mut pixels = Vec::with_capacity(w * h * 3);
+        for row in y0 as usize..y1 as usize {
+            let start = (row * fw as usize + x0 as usize) * channels;
+            for pixel in frame.data()[start..start + w * channels].chunks_exact(channels) {
+                pixels.extend_from_slice(&pixel[..3]);
+            }
+        }
+        Some(Self {
+            x: x0 as u32,
+            y: y0 as u32,
+            width: w as u32,
+            height: h as u32,
+            pixels,
+        })
+    }
+
+    /// Writes the original pixels back into `frame`. Fails when the patch
+    /// doesn't fit, which means the sidecar belongs to another video.
+    pub fn apply(&self, frame: &mut Frame) -> Result<(), String> {
+        let fw = frame.width() as usize;
+        let (x, y) = (self.x as usize, self.y as usize);
+        let (w, h) = (self.width as usize, self.height as usize);
+        if x + w > fw || y + h > frame.height() as usize || self.pixels.len() != w * h * 3 {
+            return Err(format!(
+                "A {w}x{h} patch at ({x}, {y}) does not fit a {fw}x{} frame",
+                frame.height()
+            ));
+        }
+
+        let channels = frame.channels() as usize;
+        let data = frame.data_mut();
+        for row in 0..h {
+            let start = ((y + row) * fw + x) * channels;
+            let src = &self.pixels[row * w * 3..(row + 1) * w * 3];
+            for (pixel, rgb) in data[start..start + w * channels]
+                .chunks_exact_mut(channels)
+                .zip(src.chunks_exact(3))
+            {
+                pixel[..3].copy_from_slice(rgb);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(x: i32, y: i32, w: i32, h: i32) -> Region {
+        Region {
+            x,
+            y,
+            width: w,
+            height: h,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    fn gradient_frame(width: u32, height: u32) -> Frame {
+        let data = (0..width * height * 3).map(|i| (i % 251) as u8).collect();
+        Frame::new(data, width, height, 3, 0)
+    }
+
+    #[test]
+    fn test_capture_then_apply_restores_pixels() {
+        let original = gradient_frame(40, 30);
+        let patch = RestorationPatch::capture(&original, &region(5, 6, 10, 8)).unwrap();
+        let mut blurred = Frame::new(vec![0; 40 * 30 * 3], 40, 30, 3, 0);
+        patch.apply(&mut blurred).unwrap();
+
+        let at = |f: &Frame, x: usize, y: usize| f.data()[(y * 40 + x) * 3 + 1];
+        assert_eq!(at(&blurred, 5, 6), at(&original, 5, 6));
+        assert_eq!(at(&blurred, 14, 13), at(&original, 14, 13));
+        assert_eq!(at(&blurred, 15, 13), 0);
+    }
+
+    #[test]
+    fn test_capture_clamps_to_frame() {
+        let frame = gradient_frame(40, 30);
+        let patch = RestorationPatch::capture(&frame, &region(-5, 25, 10, 10)).unwrap();
+        assert_eq!((patch.x, patch.y, patch.width, patch.height), (0, 25, 5, 5));
+        assert!(RestorationPatch::capture(&frame, &region(50, 0, 10, 10)).is_none());
+    }
+
+    #[test]
+    fn test_patch_outside_frame_is_rejected() {
+        let frame = gradient_frame(40, 30);
+        let patch = RestorationPatch::capture(&frame, &region(30, 20, 10, 10)).unwrap();
+        let mut small = gradient_frame(20, 20);
+        assert!(patch.apply(&mut small).is_err());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod restoration_recording_blurrer;
+pub mod restoration_sidecar;
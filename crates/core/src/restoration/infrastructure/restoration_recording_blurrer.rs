@@ -0,0 +1,114 @@
+use std::sync::{Arc, Mutex};
+
+use crate::blurring::domain::frame_blurrer::FrameBlurrer;
+use crate::restoration::domain::restoration_patch::RestorationPatch;
+use crate::restoration::infrastructure::restoration_sidecar::RestorationSidecarWriter;
+use crate::shared::frame::Frame;
+use crate::shared::region::Region;
+
+/// Decorator that saves the original pixels of every region to an
+/// encrypted sidecar before the inner blurrer anonymizes them.
+///
+/// Frames without regions are not recorded. Patches are keyed by the
+/// frame's index, so the sidecar only lines up with an output that keeps
+/// every frame of the input in order.
+pub struct RestorationRecordingBlurrer {
+    inner: Box<dyn FrameBlurrer>,
+    sidecar: Arc<Mutex<RestorationSidecarWriter>>,
+}
+
+impl RestorationRecordingBlurrer {
+    pub fn new(inner: Box<dyn FrameBlurrer>, sidecar: RestorationSidecarWriter) -> Self {
+        Self {
+            inner,
+            sidecar: Arc::new(Mutex::new(sidecar)),
+        }
+    }
+
+    /// Shared handle to the writer, for calling `finish` after the blurrer
+    /// is moved into a pipeline.
+    pub fn sidecar(&self) -> Arc<Mutex<RestorationSidecarWriter>> {
+        self.sidecar.clone()
+    }
+}
+
+impl FrameBlurrer for RestorationRecordingBlurrer {
+    fn blur(
+        &self,
+        frame: &mut Frame,
+        regions: &[Region],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if !regions.is_empty() {
+            let patches: Vec<RestorationPatch> = regions
+                .iter()
+                .filter_map(|r| RestorationPatch::capture(frame, r))
+                .collect();
+            self.sidecar
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .write_frame(frame.index(), &patches)?;
+        }
+        self.inner.blur(frame, regions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blurring::infrastructure::cpu_rectangular_blurrer::CpuRectangularBlurrer;
+    use crate::restoration::infrastructure::restoration_sidecar::RestorationSidecarReader;
+
+    fn region(x: i32, y: i32, w: i32, h: i32) -> Region {
+        Region {
+            x,
+            y,
+            width: w,
+            height: h,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    fn noise_frame(index: usize) -> Frame {
+        let data = (0..32u32 * 32 * 3)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8)
+            .collect();
+        Frame::new(data, 32, 32, 3, index)
+    }
+
+    #[test]
+    fn test_recorded_patches_restore_the_blurred_frame() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("faces.fgrestore");
+        let writer = RestorationSidecarWriter::create(&path, "secret").unwrap();
+        let blurrer =
+            RestorationRecordingBlurrer::new(Box::new(CpuRectangularBlurrer::exact(9)), writer);
+        let sidecar = blurrer.sidecar();
+
+        let original = noise_frame(4);
+        let mut blurred = original.clone();
+        blurrer
+            .blur(
+                &mut blurred,
+                &[region(2, 2, 10, 10), region(20, 20, 20, 20)],
+            )
+            .unwrap();
+        let mut untouched = noise_frame(5);
+        blurrer.blur(&mut untouched, &[]).unwrap();
+        assert_ne!(blurred.data(), original.data());
+        assert_eq!(sidecar.lock().unwrap().finish().unwrap(), 1);
+
+        let mut reader = RestorationSidecarReader::open(&path, "secret").unwrap();
+        let (index, patches) = reader.next_frame().unwrap().unwrap();
+        assert_eq!(index, 4);
+        assert_eq!(patches.len(), 2);
+        for patch in &patches {
+            patch.apply(&mut blurred).unwrap();
+        }
+        assert_eq!(blurred.data(), original.data());
+        assert!(reader.next_frame().unwrap().is_none());
+    }
+}
@@ -0,0 +1,529 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use image::codecs::png::PngEncoder;
+use image::{ExtendedColorType, ImageEncoder, ImageFormat};
+use thiserror::Error;
+
+use crate::restoration::domain::restoration_patch::RestorationPatch;
+use crate::shared::constants::{MAX_M_COST_KIB, MAX_P_COST, MAX_T_COST};
+
+const MAGIC: &[u8; 9] = b"FGRESTORE";
+/// Version 1 files had no end record, so truncation went unnoticed.
+const FORMAT_VERSION: u8 = 2;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 1 + 3 * 4 + SALT_LEN;
+/// Records larger than this are treated as corruption rather than read.
+const MAX_RECORD_LEN: u32 = 1 << 30;
+
+const CHECK_RECORD: u8 = 0;
+const FRAME_RECORD: u8 = 1;
+const END_RECORD: u8 = 2;
+const CHECK_TEXT: &[u8] = b"faceguard restoration";
+
+#[derive(Debug, Error)]
+pub enum RestorationError {
+    #[error("Restoration sidecar I/O failed: {0}")]
+    Io(#[from] io::Error),
+    #[error("Restoration passphrase must not be empty")]
+    EmptyPassphrase,
+    #[error("Wrong passphrase for this restoration sidecar")]
+    WrongPassphrase,
+    #[error("Restoration sidecar is corrupt or was modified: {0}")]
+    Corrupt(String),
+    #[error("Could not derive a key from the passphrase: {0}")]
+    KeyDerivation(String),
+    #[error("Could not encode a restoration patch: {0}")]
+    Encode(String),
+}
+
+/// Writes the original pixels of anonymized regions to an encrypted file,
+/// so a person holding the passphrase can restore the faces later.
+///
+/// The key is derived from the passphrase with Argon2id and a random salt,
+/// both stored in the header. Each frame is one record, sealed with
+/// AES-256-GCM under a fresh random nonce; the header and the record's
+/// position are authenticated with it, so records can't be swapped,
+/// reordered, or edited undetected. Patches are stored as lossless PNG.
+/// The first record only holds a known check value, so a wrong passphrase
+/// is caught before any frame is read. [`finish`](Self::finish) seals an
+/// end record with the frame count, so a file cut short is caught too.
+pub struct RestorationSidecarWriter {
+    out: BufWriter<File>,
+    sealer: Sealer,
+    frames: usize,
+    finished: bool,
+}
+
+/// Reads a sidecar written by [`RestorationSidecarWriter`], frame by frame
+/// in the order they were written.
+pub struct RestorationSidecarReader {
+    input: BufReader<File>,
+    sealer: Sealer,
+    frames: usize,
+    ended: bool,
+}
+
+/// Cipher plus the authenticated data shared by every record.
+struct Sealer {
+    cipher: Aes256Gcm,
+    header: Vec<u8>,
+    sequence: u64,
+}
+
+impl RestorationSidecarWriter {
+    pub fn create(path: &Path, passphrase: &str) -> Result<Self, RestorationError> {
+        let params = Params::default();
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        header.push(FORMAT_VERSION);
+        for cost in [params.m_cost(), params.t_cost(), params.p_cost()] {
+            header.extend_from_slice(&cost.to_le_bytes());
+        }
+        header.extend_from_slice(&salt);
+
+        let mut sealer = Sealer::new(passphrase, params, &salt, header)?;
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(&sealer.header)?;
+        let mut check = vec![CHECK_RECORD];
+        check.extend_from_slice(CHECK_TEXT);
+        sealer.write_record(&mut out, &check)?;
+        Ok(Self {
+            out,
+            sealer,
+            frames: 0,
+            finished: false,
+        })
+    }
+
+    /// Appends the original pixels of one frame's anonymized regions.
+    pub fn write_frame(
+        &mut self,
+        frame_index: usize,
+        patches: &[RestorationPatch],
+    ) -> Result<(), RestorationError> {
+        if self.finished {
+            return Err(RestorationError::Encode(
+                "the sidecar is already finished".into(),
+            ));
+        }
+        let mut record = vec![FRAME_RECORD];
+        record.extend_from_slice(&(frame_index as u64).to_le_bytes());
+        record.extend_from_slice(&(patches.len() as u32).to_le_bytes());
+        for patch in patches {
+            let mut png = Vec::new();
+            PngEncoder::new(&mut png)
+                .write_image(
+                    &patch.pixels,
+                    patch.width,
+                    patch.height,
+                    ExtendedColorType::Rgb8,
+                )
+                .map_err(|e| RestorationError::Encode(e.to_string()))?;
+            for value in [patch.x, patch.y, png.len() as u32] {
+                record.extend_from_slice(&value.to_le_bytes());
+            }
+            record.extend_from_slice(&png);
+        }
+        self.sealer.write_record(&mut self.out, &record)?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Seals the end record, flushes the file to disk, and returns the
+    /// number of frames written. Frames can't be added afterwards; calling
+    /// it again only returns the count.
+    pub fn finish(&mut self) -> Result<usize, RestorationError> {
+        if self.finished {
+            return Ok(self.frames);
+        }
+        let mut end = vec![END_RECORD];
+        end.extend_from_slice(&(self.frames as u64).to_le_bytes());
+        self.sealer.write_record(&mut self.out, &end)?;
+        self.finished = true;
+        self.out.flush()?;
+        self.out.get_ref().sync_all()?;
+        Ok(self.frames)
+    }
+}
+
+impl RestorationSidecarReader {
+    /// Opens `path`, failing with `WrongPassphrase` when `passphrase` isn't
+    /// the one it was written with.
+    pub fn open(path: &Path, passphrase: &str) -> Result<Self, RestorationError> {
+        let mut input = BufReader::new(File::open(path)?);
+        let mut header = vec![0u8; HEADER_LEN];
+        input
+            .read_exact(&mut header)
+            .map_err(|_| RestorationError::Corrupt("header is truncated".into()))?;
+        if &header[..MAGIC.len()] != MAGIC {
+            return Err(RestorationError::Corrupt(
+                "not a FaceGuard restoration sidecar".into(),
+            ));
+        }
+        let version = header[MAGIC.len()];
+        if version != FORMAT_VERSION {
+            return Err(RestorationError::Corrupt(format!(
+                "format version {version} is not supported by this build"
+            )));
+        }
+        let cost = |i: usize| {
+            let start = MAGIC.len() + 1 + i * 4;
+            u32::from_le_bytes(header[start..start + 4].try_into().unwrap())
+        };
+        let (m_cost, t_cost, p_cost) = (cost(0), cost(1), cost(2));
+        if m_cost > MAX_M_COST_KIB || t_cost > MAX_T_COST || p_cost > MAX_P_COST {
+            return Err(RestorationError::Corrupt(format!(
+                "key parameters m={m_cost} t={t_cost} p={p_cost} are out of range"
+            )));
+        }
+        let params = Params::new(m_cost, t_cost, p_cost, Some(32))
+            .map_err(|e| RestorationError::Corrupt(format!("key parameters: {e}")))?;
+        let salt = header[HEADER_LEN - SALT_LEN..].to_vec();
+
+        let mut sealer = Sealer::new(passphrase, params, &salt, header)?;
+        let check = match sealer.read_record(&mut input) {
+            Ok(Some(check)) => check,
+            Ok(None) => return Err(RestorationError::Corrupt("check record is missing".into())),
+            Err(RestorationError::Corrupt(_)) => return Err(RestorationError::WrongPassphrase),
+            Err(e) => return Err(e),
+        };
+        if check.first() != Some(&CHECK_RECORD) || &check[1..] != CHECK_TEXT {
+            return Err(RestorationError::Corrupt("check record is wrong".into()));
+        }
+        Ok(Self {
+            input,
+            sealer,
+            frames: 0,
+            ended: false,
+        })
+    }
+
+    /// The next stored frame, or `None` after the last one. Fails when the
+    /// file ends without the writer's end record, or the record's frame
+    /// count disagrees with the frames read.
+    pub fn next_frame(
+        &mut self,
+    ) -> Result<Option<(usize, Vec<RestorationPatch>)>, RestorationError> {
+        if self.ended {
+            return Ok(None);
+        }
+        let Some(record) = self.sealer.read_record(&mut self.input)? else {
+            return Err(RestorationError::Corrupt(
+                "the file ends without its end record; it was cut short".into(),
+            ));
+        };
+        let mut cursor = record.as_slice();
+        let u32_at = |bytes: &[u8]| u32::from_le_bytes(bytes.try_into().unwrap());
+
+        match take(&mut cursor, 1)?[0] {
+            FRAME_RECORD => {}
+            END_RECORD => {
+                let frames = u64::from_le_bytes(take(&mut cursor, 8)?.try_into().unwrap());
+                if frames != self.frames as u64 {
+                    return Err(RestorationError::Corrupt(format!(
+                        "end record counts {frames} frames but {} were read",
+                        self.frames
+                    )));
+                }
+                if self.sealer.read_record(&mut self.input)?.is_some() {
+                    return Err(RestorationError::Corrupt(
+                        "records follow the end record".into(),
+                    ));
+                }
+                self.ended = true;
+                return Ok(None);
+            }
+            _ => return Err(RestorationError::Corrupt("unexpected record kind".into())),
+        }
+        let frame_index = u64::from_le_bytes(take(&mut cursor, 8)?.try_into().unwrap()) as usize;
+        let count = u32_at(take(&mut cursor, 4)?);
+        let mut patches = Vec::new();
+        for _ in 0..count {
+            let x = u32_at(take(&mut cursor, 4)?);
+            let y = u32_at(take(&mut cursor, 4)?);
+            let len = u32_at(take(&mut cursor, 4)?) as usize;
+            let png = take(&mut cursor, len)?;
+            let rgb = image::load_from_memory_with_format(png, ImageFormat::Png)
+                .map_err(|e| RestorationError::Corrupt(format!("patch image: {e}")))?
+                .to_rgb8();
+            patches.push(RestorationPatch {
+                x,
+                y,
+                width: rgb.width(),
+                height: rgb.height(),
+                pixels: rgb.into_raw(),
+            });
+        }
+        self.frames += 1;
+        Ok(Some((frame_index, patches)))
+    }
+}
+
+impl Sealer {
+    fn new(
+        passphrase: &str,
+        params: Params,
+        salt: &[u8],
+        header: Vec<u8>,
+    ) -> Result<Self, RestorationError> {
+        if passphrase.is_empty() {
+            return Err(RestorationError::EmptyPassphrase);
+        }
+        let mut key = [0u8; 32];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| RestorationError::KeyDerivation(e.to_string()))?;
+        let cipher = Aes256Gcm::new_from_slice(&key)
+            .map_err(|e| RestorationError::KeyDerivation(e.to_string()))?;
+        Ok(Self {
+            cipher,
+            header,
+            sequence: 0,
+        })
+    }
+
+    /// Header plus record number, so each record is bound to its place.
+    fn associated_data(&self) -> Vec<u8> {
+        let mut aad = self.header.clone();
+        aad.extend_from_slice(&self.sequence.to_le_bytes());
+        aad
+    }
+
+    fn write_record(
+        &mut self,
+        out: &mut impl Write,
+        plaintext: &[u8],
+    ) -> Result<(), RestorationError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let aad = self.associated_data();
+        let sealed = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| RestorationError::Encode("encryption failed".into()))?;
+        out.write_all(&nonce)?;
+        out.write_all(&(sealed.len() as u32).to_le_bytes())?;
+        out.write_all(&sealed)?;
+        self.sequence += 1;
+        Ok(())
+    }
+
+    /// `None` at a clean end of file.
+    fn read_record(&mut self, input: &mut impl Read) -> Result<Option<Vec<u8>>, RestorationError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        if !read_exact_or_eof(input, &mut nonce)? {
+            return Ok(None);
+        }
+        let mut len = [0u8; 4];
+        input
+            .read_exact(&mut len)
+            .map_err(|_| RestorationError::Corrupt("record is truncated".into()))?;
+        let len = u32::from_le_bytes(len);
+        if len > MAX_RECORD_LEN {
+            return Err(RestorationError::Corrupt(format!("record of {len} bytes")));
+        }
+        let mut sealed = vec![0u8; len as usize];
+        input
+            .read_exact(&mut sealed)
+            .map_err(|_| RestorationError::Corrupt("record is truncated".into()))?;
+
+        let aad = self.associated_data();
+        let plaintext = self
+            .cipher
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &sealed,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| {
+                RestorationError::Corrupt(format!("record {} fails authentication", self.sequence))
+            })?;
+        self.sequence += 1;
+        Ok(Some(plaintext))
+    }
+}
+
+/// Splits the next `n` bytes off a record being parsed.
+fn take<'a>(cursor: &mut &'a [u8], n: usize) -> Result<&'a [u8], RestorationError> {
+    if cursor.len() < n {
+        return Err(RestorationError::Corrupt("record is truncated".into()));
+    }
+    let (head, rest) = cursor.split_at(n);
+    *cursor = rest;
+    Ok(head)
+}
+
+/// Fills `buf`, or returns `false` if the input ends before its first byte.
+fn read_exact_or_eof(input: &mut impl Read, buf: &mut [u8]) -> Result<bool, RestorationError> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match input.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(RestorationError::Corrupt("record is truncated".into())),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn patch(x: u32, seed: u8) -> RestorationPatch {
+        RestorationPatch {
+            x,
+            y: 3,
+            width: 4,
+            height: 2,
+            pixels: (0..24).map(|i| seed.wrapping_add(i * 7)).collect(),
+        }
+    }
+
+    fn write_sample(path: &Path) {
+        let mut writer = RestorationSidecarWriter::create(path, "correct horse").unwrap();
+        writer
+            .write_frame(0, &[patch(1, 10), patch(9, 50)])
+            .unwrap();
+        writer.write_frame(5, &[]).unwrap();
+        writer.write_frame(7, &[patch(2, 90)]).unwrap();
+        assert_eq!(writer.finish().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("faces.fgrestore");
+        write_sample(&path);
+
+        let mut reader = RestorationSidecarReader::open(&path, "correct horse").unwrap();
+        assert_eq!(
+            reader.next_frame().unwrap(),
+            Some((0, vec![patch(1, 10), patch(9, 50)]))
+        );
+        assert_eq!(reader.next_frame().unwrap(), Some((5, vec![])));
+        assert_eq!(reader.next_frame().unwrap(), Some((7, vec![patch(2, 90)])));
+        assert_eq!(reader.next_frame().unwrap(), None);
+    }
+
+    #[test]
+    fn test_pixels_are_not_stored_in_the_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("faces.fgrestore");
+        write_sample(&path);
+        let bytes = fs::read(&path).unwrap();
+        assert!(!bytes.windows(4).any(|w| w == b"\x89PNG"));
+    }
+
+    #[test]
+    fn test_wrong_passphrase_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("faces.fgrestore");
+        write_sample(&path);
+        assert!(matches!(
+            RestorationSidecarReader::open(&path, "wrong"),
+            Err(RestorationError::WrongPassphrase)
+        ));
+        assert!(matches!(
+            RestorationSidecarWriter::create(&path, ""),
+            Err(RestorationError::EmptyPassphrase)
+        ));
+    }
+
+    #[test]
+    fn test_tampered_record_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("faces.fgrestore");
+        write_sample(&path);
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        fs::write(&path, bytes).unwrap();
+
+        let mut reader = RestorationSidecarReader::open(&path, "correct horse").unwrap();
+        for _ in 0..3 {
+            reader.next_frame().unwrap();
+        }
+        assert!(matches!(
+            reader.next_frame(),
+            Err(RestorationError::Corrupt(_))
+        ));
+    }
+
+    #[test]
+    fn test_file_without_end_record_is_truncated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("faces.fgrestore");
+        {
+            let mut writer = RestorationSidecarWriter::create(&path, "correct horse").unwrap();
+            writer.write_frame(0, &[patch(1, 10)]).unwrap();
+            writer.write_frame(4, &[patch(2, 20)]).unwrap();
+        }
+
+        let mut reader = RestorationSidecarReader::open(&path, "correct horse").unwrap();
+        reader.next_frame().unwrap();
+        reader.next_frame().unwrap();
+        let err = reader.next_frame().unwrap_err();
+        assert!(matches!(err, RestorationError::Corrupt(_)));
+        assert!(err.to_string().contains("cut short"), "{err}");
+    }
+
+    #[test]
+    fn test_frames_after_finish_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("faces.fgrestore");
+        let mut writer = RestorationSidecarWriter::create(&path, "correct horse").unwrap();
+        writer.write_frame(0, &[]).unwrap();
+        assert_eq!(writer.finish().unwrap(), 1);
+
+        assert!(writer.write_frame(1, &[]).is_err());
+        assert_eq!(writer.finish().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_excessive_key_costs_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("faces.fgrestore");
+        write_sample(&path);
+        let mut bytes = fs::read(&path).unwrap();
+        let m_cost = MAGIC.len() + 1;
+        bytes[m_cost..m_cost + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        fs::write(&path, bytes).unwrap();
+
+        let err = RestorationSidecarReader::open(&path, "correct horse")
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("out of range"), "{err}");
+    }
+
+    #[test]
+    fn test_other_files_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        fs::write(&path, "not a sidecar at all, just some text").unwrap();
+        assert!(matches!(
+            RestorationSidecarReader::open(&path, "correct horse"),
+            Err(RestorationError::Corrupt(_))
+        ));
+    }
+}
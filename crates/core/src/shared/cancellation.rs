@@ -0,0 +1,185 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+use thiserror::Error;
+
+/// Why a job was cancelled, carried into the error it stops with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelReason {
+    /// The user asked to stop, or a progress callback returned `false`.
+    User,
+    /// A newer job replaced this one, e.g. the input or settings changed
+    /// while it was running.
+    Superseded,
+    /// The application is shutting down.
+    Shutdown,
+}
+
+impl CancelReason {
+    fn code(self) -> u8 {
+        match self {
+            CancelReason::User => 1,
+            CancelReason::Superseded => 2,
+            CancelReason::Shutdown => 3,
+        }
+    }
+
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(CancelReason::User),
+            2 => Some(CancelReason::Superseded),
+            3 => Some(CancelReason::Shutdown),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CancelReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CancelReason::User => "by the user",
+            CancelReason::Superseded => "by a newer job",
+            CancelReason::Shutdown => "because the app is closing",
+        })
+    }
+}
+
+/// The error a job stops with once its token is cancelled.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("Cancelled {0}")]
+pub struct Cancelled(pub CancelReason);
+
+impl Cancelled {
+    /// The reason, if `error` or one of its sources is a cancellation
+    /// rather than a failure.
+    pub fn reason_of(error: &(dyn std::error::Error + 'static)) -> Option<CancelReason> {
+        let mut current = Some(error);
+        while let Some(e) = current {
+            if let Some(cancelled) = e.downcast_ref::<Cancelled>() {
+                return Some(cancelled.0);
+            }
+            current = e.source();
+        }
+        None
+    }
+}
+
+/// A cancellation flag shared between whoever may stop a job and the
+/// threads doing the work.
+///
+/// Clones share one flag. A [`child`](Self::child) token is also cancelled
+/// when its parent is, but cancelling the child leaves the parent running,
+/// so one stage of a job can be stopped on its own. The first reason given
+/// sticks.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// 0 while running, otherwise the code of the [`CancelReason`].
+    reason: AtomicU8,
+    parent: Option<CancellationToken>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A token cancelled along with this one.
+    pub fn child(&self) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                reason: AtomicU8::new(0),
+                parent: Some(self.clone()),
+            }),
+        }
+    }
+
+    /// Cancels this token and its children. Has no effect when already
+    /// cancelled.
+    pub fn cancel(&self, reason: CancelReason) {
+        let _ = self.inner.reason.compare_exchange(
+            0,
+            reason.code(),
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Why this token or one of its ancestors was cancelled, if it was.
+    pub fn reason(&self) -> Option<CancelReason> {
+        CancelReason::from_code(self.inner.reason.load(Ordering::Relaxed))
+            .or_else(|| self.inner.parent.as_ref().and_then(Self::reason))
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.reason().is_some()
+    }
+
+    /// `Err(Cancelled)` once cancelled, for use with `?` between steps.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        match self.reason() {
+            Some(reason) => Err(Cancelled(reason)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_token_is_not_cancelled() {
+        let token = CancellationToken::new();
+        assert!(!token.is_cancelled());
+        assert_eq!(token.check(), Ok(()));
+    }
+
+    #[test]
+    fn test_clones_share_the_flag() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        clone.cancel(CancelReason::User);
+        assert_eq!(token.reason(), Some(CancelReason::User));
+    }
+
+    #[test]
+    fn test_first_reason_sticks() {
+        let token = CancellationToken::new();
+        token.cancel(CancelReason::Superseded);
+        token.cancel(CancelReason::Shutdown);
+        assert_eq!(token.check(), Err(Cancelled(CancelReason::Superseded)));
+    }
+
+    #[test]
+    fn test_child_follows_parent() {
+        let parent = CancellationToken::new();
+        let child = parent.child();
+        parent.cancel(CancelReason::Shutdown);
+        assert_eq!(child.reason(), Some(CancelReason::Shutdown));
+    }
+
+    #[test]
+    fn test_cancelling_child_leaves_parent_running() {
+        let parent = CancellationToken::new();
+        let child = parent.child();
+        child.cancel(CancelReason::User);
+        assert!(child.is_cancelled());
+        assert!(!parent.is_cancelled());
+    }
+
+    #[test]
+    fn test_reason_of_boxed_error() {
+        let error: Box<dyn std::error::Error> = Box::new(Cancelled(CancelReason::User));
+        assert_eq!(Cancelled::reason_of(&*error), Some(CancelReason::User));
+        assert_eq!(error.to_string(), "Cancelled by the user");
+
+        let other: Box<dyn std::error::Error> = "Decode failed".into();
+        assert_eq!(Cancelled::reason_of(&*other), None);
+    }
+}
@@ -0,0 +1,67 @@
+/// YUV↔RGB conversion matrix of a video stream.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorMatrix {
+    /// SD video (ITU-R BT.601).
+    Bt601,
+    /// HD video (ITU-R BT.709).
+    #[default]
+    Bt709,
+}
+
+/// Whether luma spans 16–235 (limited, usual for video) or 0–255 (full).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorRange {
+    #[default]
+    Limited,
+    Full,
+}
+
+/// How a stream's YUV samples map to RGB.
+///
+/// The reader converts with the source's matrix and range, and the writer
+/// converts back with the same ones and tags the output with them, so a
+/// frame that passes through unblurred keeps its colors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ColorSpace {
+    pub matrix: ColorMatrix,
+    pub range: ColorRange,
+}
+
+impl ColorSpace {
+    pub fn new(matrix: ColorMatrix, range: ColorRange) -> Self {
+        Self { matrix, range }
+    }
+
+    /// What players assume for untagged video: BT.709 for HD sizes and
+    /// BT.601 for SD, limited range.
+    pub fn assumed_for(width: u32, height: u32) -> Self {
+        let matrix = if width >= 1280 || height > 576 {
+            ColorMatrix::Bt709
+        } else {
+            ColorMatrix::Bt601
+        };
+        Self::new(matrix, ColorRange::Limited)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(640, 480, ColorMatrix::Bt601)]
+    #[case(720, 576, ColorMatrix::Bt601)]
+    #[case(1280, 720, ColorMatrix::Bt709)]
+    #[case(1920, 1080, ColorMatrix::Bt709)]
+    #[case(1080, 1920, ColorMatrix::Bt709)]
+    fn test_assumed_matrix_by_size(
+        #[case] width: u32,
+        #[case] height: u32,
+        #[case] expected: ColorMatrix,
+    ) {
+        let color = ColorSpace::assumed_for(width, height);
+        assert_eq!(color.matrix, expected);
+        assert_eq!(color.range, ColorRange::Limited);
+    }
+}
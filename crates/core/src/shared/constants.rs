@@ -2,16 +2,65 @@ pub const YOLO_MODEL_NAME: &str = "yolo11n-pose_widerface.onnx";
 pub const YOLO_MODEL_URL: &str =
     "https://github.com/neutrinographics/faceguard-ml-models/releases/download/models-v1/yolo11n-pose_widerface.onnx";
 
+/// Alternative face detectors, selectable with the CLI's `--model`. The
+/// YOLOv8 models share the bundled model's output layout; SCRFD decodes
+/// per-stride score, box, and landmark maps.
+pub const YOLOV8N_FACE_MODEL_NAME: &str = "yolov8n-face.onnx";
+pub const YOLOV8N_FACE_MODEL_URL: &str =
+    "https://github.com/neutrinographics/faceguard-ml-models/releases/download/models-v1/yolov8n-face.onnx";
+pub const YOLOV8M_FACE_MODEL_NAME: &str = "yolov8m-face.onnx";
+pub const YOLOV8M_FACE_MODEL_URL: &str =
+    "https://github.com/neutrinographics/faceguard-ml-models/releases/download/models-v1/yolov8m-face.onnx";
+pub const SCRFD_2_5G_MODEL_NAME: &str = "scrfd_2.5g_bnkps.onnx";
+pub const SCRFD_2_5G_MODEL_URL: &str =
+    "https://github.com/neutrinographics/faceguard-ml-models/releases/download/models-v1/scrfd_2.5g_bnkps.onnx";
+pub const SCRFD_10G_MODEL_NAME: &str = "scrfd_10g_bnkps.onnx";
+pub const SCRFD_10G_MODEL_URL: &str =
+    "https://github.com/neutrinographics/faceguard-ml-models/releases/download/models-v1/scrfd_10g_bnkps.onnx";
+
+/// COCO-trained YOLO11n; class 0 is "person". Used for `BlurTarget::Person`.
+pub const PERSON_MODEL_NAME: &str = "yolo11n.onnx";
+pub const PERSON_MODEL_URL: &str =
+    "https://github.com/neutrinographics/faceguard-ml-models/releases/download/models-v1/yolo11n.onnx";
+
 pub const EMBEDDING_MODEL_NAME: &str = "w600k_r50.onnx";
 pub const EMBEDDING_MODEL_URL: &str =
     "https://github.com/neutrinographics/faceguard-ml-models/releases/download/models-v1/w600k_r50.onnx";
 
+/// DBNet text detector (PaddleOCR PP-OCRv4), for `--redact-text`.
+pub const TEXT_DETECTION_MODEL_NAME: &str = "ppocrv4_det.onnx";
+pub const TEXT_DETECTION_MODEL_URL: &str =
+    "https://github.com/neutrinographics/faceguard-ml-models/releases/download/models-v1/ppocrv4_det.onnx";
+
+/// CRNN text recognizer (PaddleOCR PP-OCRv4, English), used when
+/// `--redact-text` has keywords or patterns to read for.
+pub const TEXT_RECOGNITION_MODEL_NAME: &str = "ppocrv4_rec_en.onnx";
+pub const TEXT_RECOGNITION_MODEL_URL: &str =
+    "https://github.com/neutrinographics/faceguard-ml-models/releases/download/models-v1/ppocrv4_rec_en.onnx";
+
+/// Newest release of each model, fetched only when the user checks for
+/// model updates.
+pub const MODEL_MANIFEST_URL: &str =
+    "https://github.com/neutrinographics/faceguard-ml-models/releases/latest/download/manifest.json";
+
 /// Max frames a track can be lost before removal (~1 second at 30 fps).
 pub const TRACKER_MAX_LOST: usize = 30;
 
+/// Mosaic blocks across a pixelated face, whatever its size on screen.
+pub const PIXELATE_BLOCKS: usize = 8;
+
 pub const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "bmp", "tiff", "tif", "webp"];
+pub const VIDEO_EXTENSIONS: &[&str] = &["mp4", "avi", "mov", "mkv", "m4v", "webm"];
 
-pub const WHISPER_MODEL_NAME: &str = "ggml-tiny.en.bin";
+pub const WHISPER_MODEL_NAME: &str = "ggml-tiny.bin";
 pub const WHISPER_MODEL_URL: &str =
-    "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.en.bin";
+    "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin";
 pub const WHISPER_SAMPLE_RATE: u32 = 16000;
+
+/// Argon2 costs above these are treated as corruption when read back from
+/// a restoration sidecar or ledger signature, so a crafted file can't make
+/// opening it take unbounded memory or time. Files written with the
+/// default costs are far below them.
+pub const MAX_M_COST_KIB: u32 = 1 << 20;
+pub const MAX_T_COST: u32 = 16;
+pub const MAX_P_COST: u32 = 16;
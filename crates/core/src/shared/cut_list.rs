@@ -0,0 +1,287 @@
+use std::path::Path;
+
+use crate::shared::track_time_rules::{parse_timestamp, TimeRange};
+
+/// What to do with the media inside a cut-list range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RedactionAction {
+    /// Blur every detected face.
+    BlurAll,
+    /// Replace the audio with a bleep.
+    Bleep,
+    /// Remove the range from both video and audio.
+    Cut,
+}
+
+impl RedactionAction {
+    /// Recognises `blur-all`, `bleep`, and `cut` (plus common spellings
+    /// such as `blur` or `blur_all`), case-insensitively.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().replace(['_', ' '], "-").as_str() {
+            "blur-all" | "blur" => Some(Self::BlurAll),
+            "bleep" | "mute" => Some(Self::Bleep),
+            "cut" | "remove" => Some(Self::Cut),
+            _ => None,
+        }
+    }
+
+    /// Finds an action keyword anywhere in free text, as editors put it in
+    /// EDL comments or marker names.
+    fn find_in(text: &str) -> Option<Self> {
+        let lower = text.to_lowercase();
+        if lower.contains("bleep") || lower.contains("mute") {
+            Some(Self::Bleep)
+        } else if lower.contains("cut") || lower.contains("remove") {
+            Some(Self::Cut)
+        } else if lower.contains("blur") {
+            Some(Self::BlurAll)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CutListEntry {
+    pub range: TimeRange,
+    pub action: RedactionAction,
+}
+
+/// Redaction instructions handed off from an editing tool: time ranges in
+/// source time, each with a [`RedactionAction`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CutList {
+    pub entries: Vec<CutListEntry>,
+}
+
+impl CutList {
+    /// Loads a `.edl` (CMX 3600) or CSV cut list, chosen by extension.
+    /// `fps` converts EDL frame-based timecodes to seconds.
+    pub fn load(path: &Path, fps: f64) -> Result<Self, Box<dyn std::error::Error>> {
+        let text = std::fs::read_to_string(path)?;
+        let is_edl = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("edl"));
+        let list = if is_edl {
+            Self::parse_edl(&text, fps)?
+        } else {
+            Self::parse_csv(&text)?
+        };
+        Ok(list)
+    }
+
+    /// Parses `start,end,action` rows. Times use `SS`, `MM:SS`, or
+    /// `HH:MM:SS`; a header row and blank or `#` lines are skipped.
+    pub fn parse_csv(text: &str) -> Result<Self, String> {
+        let mut entries = Vec::new();
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if fields[0].eq_ignore_ascii_case("start") {
+                continue;
+            }
+            let [start, end, action] = fields[..] else {
+                return Err(format!("Line {}: expected start,end,action", line_no + 1));
+            };
+            let at_line = |e: String| format!("Line {}: {e}", line_no + 1);
+            let range = TimeRange::parse(&format!("{start}-{end}")).map_err(at_line)?;
+            let action = RedactionAction::parse(action)
+                .ok_or_else(|| at_line(format!("Unknown action '{action}'")))?;
+            entries.push(CutListEntry { range, action });
+        }
+        Ok(Self { entries })
+    }
+
+    /// Parses a CMX 3600 EDL. Each event's source in/out becomes a range.
+    /// The action comes from a keyword in the event's comment or marker
+    /// lines; without one, video events blur and audio-only events bleep.
+    pub fn parse_edl(text: &str, fps: f64) -> Result<Self, String> {
+        struct Event {
+            range: TimeRange,
+            audio_only: bool,
+            action: Option<RedactionAction>,
+        }
+
+        let mut events: Vec<Event> = Vec::new();
+        for line in text.lines() {
+            let trimmed = line.trim();
+            let fields: Vec<&str> = trimmed.split_whitespace().collect();
+            let is_event = fields.len() >= 8
+                && fields[0].chars().all(|c| c.is_ascii_digit())
+                && fields.iter().rev().take(4).all(|f| is_timecode(f));
+
+            if is_event {
+                let n = fields.len();
+                let start = parse_timecode(fields[n - 4], fps)?;
+                let end = parse_timecode(fields[n - 3], fps)?;
+                if end < start {
+                    return Err(format!("EDL event '{}' ends before it starts", fields[0]));
+                }
+                events.push(Event {
+                    range: TimeRange::new(start, end),
+                    audio_only: fields[2].to_uppercase().starts_with('A'),
+                    action: None,
+                });
+            } else if let Some(event) = events.last_mut() {
+                // Comment, locator, and marker lines describe the preceding
+                // event. Clip-name lines are skipped so a file called
+                // "cutaway.mov" is not read as an instruction.
+                if event.action.is_none() && is_note_line(trimmed) {
+                    event.action = RedactionAction::find_in(trimmed);
+                }
+            }
+        }
+
+        let entries = events
+            .into_iter()
+            .map(|e| CutListEntry {
+                range: e.range,
+                action: e.action.unwrap_or(if e.audio_only {
+                    RedactionAction::Bleep
+                } else {
+                    RedactionAction::BlurAll
+                }),
+            })
+            .collect();
+        Ok(Self { entries })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Ranges carrying `action`, in file order.
+    pub fn ranges(&self, action: RedactionAction) -> Vec<TimeRange> {
+        self.entries
+            .iter()
+            .filter(|e| e.action == action)
+            .map(|e| e.range)
+            .collect()
+    }
+}
+
+fn is_note_line(line: &str) -> bool {
+    let upper = line.to_uppercase();
+    upper.starts_with("* COMMENT") || upper.starts_with("* LOC") || line.contains("|M:")
+}
+
+fn is_timecode(s: &str) -> bool {
+    let parts: Vec<&str> = s.split([':', ';']).collect();
+    parts.len() == 4
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Converts `HH:MM:SS:FF` (or drop-frame `HH:MM:SS;FF`) to seconds. Drop
+/// frame is treated as non-drop, which is within a frame for short edits.
+fn parse_timecode(tc: &str, fps: f64) -> Result<f64, String> {
+    let parts: Vec<&str> = tc.split([':', ';']).collect();
+    let [h, m, s, f] = parts[..] else {
+        return Err(format!("Invalid timecode '{tc}'"));
+    };
+    let seconds = parse_timestamp(&format!("{h}:{m}:{s}"))?;
+    let frames: f64 = f.parse().map_err(|_| format!("Invalid timecode '{tc}'"))?;
+    let fps = if fps > 0.0 { fps } else { 30.0 };
+    Ok(seconds + frames / fps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("blur-all", RedactionAction::BlurAll)]
+    #[case("BLUR_ALL", RedactionAction::BlurAll)]
+    #[case("blur", RedactionAction::BlurAll)]
+    #[case("Bleep", RedactionAction::Bleep)]
+    #[case("cut", RedactionAction::Cut)]
+    fn test_parse_action(#[case] input: &str, #[case] expected: RedactionAction) {
+        assert_eq!(RedactionAction::parse(input), Some(expected));
+    }
+
+    #[test]
+    fn test_parse_action_rejects_unknown() {
+        assert_eq!(RedactionAction::parse("sparkle"), None);
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let list = CutList::parse_csv(
+            "start,end,action\n\
+             00:10,00:15,blur-all\n\
+             # comment\n\
+             \n\
+             1:00,1:02.5,bleep\n\
+             90,95,cut\n",
+        )
+        .unwrap();
+        assert_eq!(
+            list.entries,
+            vec![
+                CutListEntry {
+                    range: TimeRange::new(10.0, 15.0),
+                    action: RedactionAction::BlurAll,
+                },
+                CutListEntry {
+                    range: TimeRange::new(60.0, 62.5),
+                    action: RedactionAction::Bleep,
+                },
+                CutListEntry {
+                    range: TimeRange::new(90.0, 95.0),
+                    action: RedactionAction::Cut,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_csv_reports_line_numbers() {
+        let err = CutList::parse_csv("0,1,blur\n2,3,sparkle\n").unwrap_err();
+        assert!(err.starts_with("Line 2:"), "{err}");
+        let err = CutList::parse_csv("0,1\n").unwrap_err();
+        assert!(err.starts_with("Line 1:"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_edl_uses_source_timecodes_and_comments() {
+        let edl = "TITLE: Redactions\n\
+                   FCM: NON-DROP FRAME\n\
+                   \n\
+                   001  AX       V     C        00:00:10:00 00:00:15:00 01:00:00:00 01:00:05:00\n\
+                   * FROM CLIP NAME: cutaway.mov\n\
+                   * COMMENT: CUT\n\
+                   002  AX       A     C        00:01:00:15 00:01:02:00 01:00:05:00 01:00:06:15\n\
+                   003  AX       V     C        00:02:00:00 00:02:01:00 01:00:06:15 01:00:07:15\n\
+                   * FROM CLIP NAME: interview_cut_02.mov\n\
+                   |C:ResolveColorBlue |M:bleep answer |D:25\n\
+                   004  AX       V     C        00:03:00:00 00:03:04:00 01:00:07:15 01:00:11:15\n";
+        let list = CutList::parse_edl(edl, 30.0).unwrap();
+        let actions: Vec<RedactionAction> = list.entries.iter().map(|e| e.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                RedactionAction::Cut,
+                RedactionAction::Bleep,
+                RedactionAction::Bleep,
+                RedactionAction::BlurAll,
+            ]
+        );
+        assert_eq!(list.entries[0].range, TimeRange::new(10.0, 15.0));
+        assert_eq!(list.entries[1].range, TimeRange::new(60.5, 62.0));
+    }
+
+    #[test]
+    fn test_ranges_filters_by_action() {
+        let list = CutList::parse_csv("0,1,cut\n2,3,bleep\n4,5,cut\n").unwrap();
+        assert_eq!(
+            list.ranges(RedactionAction::Cut),
+            vec![TimeRange::new(0.0, 1.0), TimeRange::new(4.0, 5.0)]
+        );
+        assert!(list.ranges(RedactionAction::BlurAll).is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use crate::shared::region::Region;
+
+/// Track IDs that are blurred whatever the track selection says.
+///
+/// Filled by a detector decorator as it recognizes people, and read where
+/// the selection is applied. Cloning is cheap and every clone sees the
+/// same set, like [`Warnings`](super::warnings::Warnings).
+#[derive(Clone, Debug, Default)]
+pub struct ForcedTracks {
+    ids: Arc<Mutex<HashSet<u32>>>,
+}
+
+impl ForcedTracks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forces `id`; returns whether it was not forced already.
+    pub fn insert(&self, id: u32) -> bool {
+        self.ids
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id)
+    }
+
+    pub fn contains(&self, id: u32) -> bool {
+        self.ids
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(&id)
+    }
+
+    /// The forced IDs, in order.
+    pub fn ids(&self) -> Vec<u32> {
+        let ids = self.ids.lock().unwrap_or_else(|e| e.into_inner());
+        let mut ids: Vec<u32> = ids.iter().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// `selected` plus every region of `detected` on a forced track that
+    /// the selection dropped.
+    pub fn restore(&self, detected: &[Region], mut selected: Vec<Region>) -> Vec<Region> {
+        let ids = self.ids.lock().unwrap_or_else(|e| e.into_inner());
+        if ids.is_empty() {
+            return selected;
+        }
+        let missing: Vec<Region> = detected
+            .iter()
+            .filter(|r| r.track_id.is_some_and(|id| ids.contains(&id)))
+            .filter(|r| !selected.iter().any(|s| s.track_id == r.track_id))
+            .cloned()
+            .collect();
+        selected.extend(missing);
+        selected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(track_id: Option<u32>) -> Region {
+        Region {
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 10,
+            track_id,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    #[test]
+    fn test_restores_dropped_forced_tracks() {
+        let forced = ForcedTracks::new();
+        forced.insert(2);
+        let detected = vec![region(Some(1)), region(Some(2)), region(Some(3))];
+
+        let restored = forced.restore(&detected, vec![region(Some(1))]);
+
+        let ids: Vec<_> = restored.iter().map(|r| r.track_id).collect();
+        assert_eq!(ids, vec![Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn test_selected_forced_tracks_are_not_duplicated() {
+        let forced = ForcedTracks::new();
+        forced.insert(1);
+        let detected = vec![region(Some(1))];
+
+        assert_eq!(forced.restore(&detected, detected.clone()).len(), 1);
+    }
+
+    #[test]
+    fn test_clones_share_the_set() {
+        let forced = ForcedTracks::new();
+        assert!(forced.clone().insert(4));
+        assert!(!forced.insert(4));
+        assert!(forced.contains(4));
+    }
+}
@@ -1,9 +1,14 @@
 use ndarray::{ArrayView3, ArrayViewMut3};
 
+use crate::shared::region::Region;
+
 /// A single video/image frame: contiguous RGB bytes in row-major order.
 ///
 /// Format conversion happens at I/O boundaries only; the domain layer
 /// treats pixel data as opaque.
+///
+/// Decoded video frames carry their presentation time, so writers can keep
+/// the source timing of variable frame rate recordings.
 #[derive(Clone, Debug)]
 pub struct Frame {
     data: Vec<u8>,
@@ -11,6 +16,7 @@ pub struct Frame {
     height: u32,
     channels: u8,
     index: usize,
+    timestamp: Option<f64>,
 }
 
 impl Frame {
@@ -26,6 +32,7 @@ impl Frame {
             height,
             channels,
             index,
+            timestamp: None,
         }
     }
 
@@ -53,6 +60,24 @@ impl Frame {
         self.index
     }
 
+    /// Renumbers the frame, for writers that resample the frame sequence.
+    pub fn with_index(mut self, index: usize) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Presentation time in seconds from the start of the stream, when the
+    /// source provides one.
+    pub fn timestamp(&self) -> Option<f64> {
+        self.timestamp
+    }
+
+    /// Stamps the frame with its presentation time in seconds.
+    pub fn with_timestamp(mut self, seconds: f64) -> Self {
+        self.timestamp = Some(seconds);
+        self
+    }
+
     pub fn as_ndarray(&self) -> ArrayView3<'_, u8> {
         ArrayView3::from_shape(self.shape(), &self.data)
             .expect("Frame data length must match dimensions")
@@ -63,6 +88,116 @@ impl Frame {
             .expect("Frame data length must match dimensions")
     }
 
+    /// Returns a copy of this frame rotated clockwise by `degrees`.
+    ///
+    /// Only multiples of 90 are supported; any other value (including 0)
+    /// returns an unrotated copy. Quarter turns swap width and height.
+    pub fn rotated(&self, degrees: i32) -> Frame {
+        let w = self.width as usize;
+        let h = self.height as usize;
+        let c = self.channels as usize;
+        let turns = degrees.rem_euclid(360);
+        if !matches!(turns, 90 | 180 | 270) {
+            return self.clone();
+        }
+
+        let (out_w, out_h) = if turns == 180 { (w, h) } else { (h, w) };
+        let mut data = vec![0u8; self.data.len()];
+        for y in 0..h {
+            for x in 0..w {
+                let (nx, ny) = match turns {
+                    90 => (h - 1 - y, x),
+                    180 => (w - 1 - x, h - 1 - y),
+                    _ => (y, w - 1 - x),
+                };
+                let src = (y * w + x) * c;
+                let dst = (ny * out_w + nx) * c;
+                data[dst..dst + c].copy_from_slice(&self.data[src..src + c]);
+            }
+        }
+        Frame {
+            timestamp: self.timestamp,
+            ..Frame::new(data, out_w as u32, out_h as u32, self.channels, self.index)
+        }
+    }
+
+    /// Square crop centered on `region`, as wide as its larger side and
+    /// clipped to the self. Used for face thumbnails and embeddings.
+    pub fn square_crop(&self, region: &Region) -> Frame {
+        let fw = self.width() as i32;
+        let fh = self.height() as i32;
+
+        let cx = region.x + region.width / 2;
+        let cy = region.y + region.height / 2;
+        let half = region.width.max(region.height) / 2;
+
+        let x1 = (cx - half).max(0) as usize;
+        let y1 = (cy - half).max(0) as usize;
+        let x2 = (cx + half).min(fw) as usize;
+        let y2 = (cy + half).min(fh) as usize;
+
+        let crop_w = x2 - x1;
+        let crop_h = y2 - y1;
+        let channels = self.channels() as usize;
+
+        let src = self.as_ndarray();
+        let mut data = Vec::with_capacity(crop_w * crop_h * channels);
+
+        for row in y1..y2 {
+            for col in x1..x2 {
+                for c in 0..channels {
+                    data.push(src[[row, col, c]]);
+                }
+            }
+        }
+
+        Frame::new(data, crop_w as u32, crop_h as u32, channels as u8, 0)
+    }
+
+    /// Whether `other` has the same pixels as this frame everywhere outside
+    /// the (clamped) rectangles of `regions`. Frames of different shapes
+    /// never match.
+    pub fn matches_outside(&self, other: &Frame, regions: &[Region]) -> bool {
+        if self.shape() != other.shape() {
+            return false;
+        }
+        let w = self.width as i32;
+        let c = self.channels as usize;
+        let row_len = self.width as usize * c;
+        let mut holes: Vec<(usize, usize)> = Vec::with_capacity(regions.len());
+        for y in 0..self.height as i32 {
+            holes.clear();
+            holes.extend(
+                regions
+                    .iter()
+                    .filter(|r| y >= r.y && y < r.y + r.height)
+                    .map(|r| {
+                        (
+                            r.x.clamp(0, w) as usize,
+                            (r.x + r.width).clamp(0, w) as usize,
+                        )
+                    }),
+            );
+            holes.sort_unstable();
+            let row = y as usize * row_len;
+            let mut x = 0;
+            for &(start, end) in &holes {
+                if start > x {
+                    let span = row + x * c..row + start * c;
+                    if self.data[span.clone()] != other.data[span] {
+                        return false;
+                    }
+                }
+                x = x.max(end);
+            }
+            let span = row + x * c..row + row_len;
+            if self.data[span.clone()] != other.data[span] {
+                return false;
+            }
+        }
+        true
+    }
+
     fn shape(&self) -> (usize, usize, usize) {
         (
             self.height as usize,
@@ -142,4 +277,156 @@ mod tests {
         }
         assert_eq!(frame.as_ndarray()[[0, 1, 2]], 128);
     }
+
+    /// 3x2 frame whose pixel values encode their position: (row * 3 + col).
+    fn numbered_frame() -> Frame {
+        let data: Vec<u8> = (0..6u8).flat_map(|v| [v, v, v]).collect();
+        Frame::new(data, 3, 2, 3, 7)
+    }
+
+    fn reds(frame: &Frame) -> Vec<u8> {
+        frame.data().chunks(3).map(|p| p[0]).collect()
+    }
+
+    #[test]
+    fn test_rotated_90_swaps_dimensions_clockwise() {
+        let rotated = numbered_frame().rotated(90);
+        assert_eq!((rotated.width(), rotated.height()), (2, 3));
+        // 0 1 2      3 0
+        // 3 4 5  ->  4 1
+        //            5 2
+        assert_eq!(reds(&rotated), vec![3, 0, 4, 1, 5, 2]);
+        assert_eq!(rotated.index(), 7);
+    }
+
+    #[test]
+    fn test_rotated_180_reverses_pixels() {
+        let rotated = numbered_frame().rotated(180);
+        assert_eq!((rotated.width(), rotated.height()), (3, 2));
+        assert_eq!(reds(&rotated), vec![5, 4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_timestamp_survives_renumbering_and_rotation() {
+        let frame = numbered_frame();
+        assert_eq!(frame.timestamp(), None);
+        let stamped = frame.with_timestamp(1.25).with_index(3);
+        assert_eq!(stamped.timestamp(), Some(1.25));
+        assert_eq!(stamped.rotated(90).timestamp(), Some(1.25));
+    }
+
+    #[test]
+    fn test_rotated_270_swaps_dimensions_counterclockwise() {
+        let rotated = numbered_frame().rotated(270);
+        assert_eq!((rotated.width(), rotated.height()), (2, 3));
+        assert_eq!(reds(&rotated), vec![2, 5, 1, 4, 0, 3]);
+    }
+
+    #[test]
+    fn test_rotated_zero_and_odd_angles_are_identity() {
+        let frame = numbered_frame();
+        assert_eq!(frame.rotated(0).data(), frame.data());
+        assert_eq!(frame.rotated(45).data(), frame.data());
+        assert_eq!(frame.rotated(-270).data(), frame.rotated(90).data());
+    }
+
+    fn square(x: i32, y: i32, size: i32) -> Region {
+        Region {
+            x,
+            y,
+            width: size,
+            height: size,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    fn with_pixel(frame: &Frame, x: usize, y: usize, value: u8) -> Frame {
+        let mut changed = frame.clone();
+        let i = (y * frame.width() as usize + x) * 3;
+        changed.data_mut()[i] = value;
+        changed
+    }
+
+    #[test]
+    fn test_matches_outside_ignores_changes_inside_regions() {
+        let frame = Frame::new(vec![10; 8 * 8 * 3], 8, 8, 3, 0);
+        let changed = with_pixel(&frame, 3, 3, 200);
+        assert!(frame.matches_outside(&frame.clone(), &[]));
+        assert!(!frame.matches_outside(&changed, &[]));
+        assert!(frame.matches_outside(&changed, &[square(2, 2, 3)]));
+    }
+
+    #[test]
+    fn test_matches_outside_detects_changes_beside_regions() {
+        let frame = Frame::new(vec![10; 8 * 8 * 3], 8, 8, 3, 0);
+        let regions = [square(2, 2, 3), square(4, 2, 2)];
+        assert!(!frame.matches_outside(&with_pixel(&frame, 6, 3, 200), &regions));
+        assert!(!frame.matches_outside(&with_pixel(&frame, 3, 5, 200), &regions));
+        assert!(frame.matches_outside(&with_pixel(&frame, 5, 3, 200), &regions));
+    }
+
+    #[test]
+    fn test_matches_outside_clamps_regions_past_edges() {
+        let frame = Frame::new(vec![10; 8 * 8 * 3], 8, 8, 3, 0);
+        let changed = with_pixel(&frame, 7, 0, 200);
+        assert!(frame.matches_outside(&changed, &[square(5, -3, 10)]));
+    }
+
+    #[test]
+    fn test_matches_outside_rejects_different_sizes() {
+        let a = Frame::new(vec![10; 4 * 4 * 3], 4, 4, 3, 0);
+        let b = Frame::new(vec![10; 8 * 2 * 3], 8, 2, 3, 0);
+        assert!(!a.matches_outside(&b, &[]));
+    }
+
+    fn crop_region(x: i32, y: i32, width: i32, height: i32) -> Region {
+        Region {
+            x,
+            y,
+            width,
+            height,
+            track_id: None,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    #[test]
+    fn test_square_crop_basic() {
+        // 10x10 frame, region at (2,2) size 4x4
+        let frame = Frame::new(vec![0; 10 * 10 * 3], 10, 10, 3, 0);
+        let r = crop_region(2, 2, 4, 4);
+        let crop = frame.square_crop(&r);
+        // cx=4, cy=4, half=2 → x1=2, y1=2, x2=6, y2=6 → 4x4
+        assert_eq!(crop.width(), 4);
+        assert_eq!(crop.height(), 4);
+    }
+
+    #[test]
+    fn test_square_crop_clamps_to_frame() {
+        // 10x10 frame, region near edge
+        let frame = Frame::new(vec![0; 10 * 10 * 3], 10, 10, 3, 0);
+        let r = crop_region(7, 7, 6, 6);
+        let crop = frame.square_crop(&r);
+        // cx=10, cy=10, half=3 → x1=7, y1=7, x2=10, y2=10 → 3x3
+        assert_eq!(crop.width(), 3);
+        assert_eq!(crop.height(), 3);
+    }
+
+    #[test]
+    fn test_square_crop_rectangular_region_uses_max_dim() {
+        // 100x100 frame, tall region 10x30
+        let frame = Frame::new(vec![0; 100 * 100 * 3], 100, 100, 3, 0);
+        let r = crop_region(40, 35, 10, 30);
+        let crop = frame.square_crop(&r);
+        // cx=45, cy=50, half=15 → x1=30, y1=35, x2=60, y2=65 → 30x30
+        assert_eq!(crop.width(), 30);
+        assert_eq!(crop.height(), 30);
+    }
 }
@@ -0,0 +1,69 @@
+use thiserror::Error;
+
+/// Which end of a job failed: reading the input or writing the output.
+/// Failures without a [`JobError`] happened in between, while processing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobErrorKind {
+    /// The input could not be opened or decoded.
+    Input,
+    /// The output could not be created, encoded, or finalized.
+    Output,
+}
+
+/// An error tagged with the end of the job it came from, so callers can
+/// report input and output problems differently (e.g. as exit codes)
+/// without parsing messages. Displays as the underlying error.
+#[derive(Error, Debug)]
+#[error("{source}")]
+pub struct JobError {
+    pub kind: JobErrorKind,
+    source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl JobError {
+    pub fn input(source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self {
+            kind: JobErrorKind::Input,
+            source: source.into(),
+        }
+    }
+
+    pub fn output(source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self {
+            kind: JobErrorKind::Output,
+            source: source.into(),
+        }
+    }
+
+    /// The kind, if `error` or one of its sources is a [`JobError`].
+    pub fn kind_of(error: &(dyn std::error::Error + 'static)) -> Option<JobErrorKind> {
+        let mut current = Some(error);
+        while let Some(e) = current {
+            if let Some(job) = e.downcast_ref::<JobError>() {
+                return Some(job.kind);
+            }
+            current = e.source();
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_displays_as_the_source() {
+        let error = JobError::output("Disk full");
+        assert_eq!(error.to_string(), "Disk full");
+    }
+
+    #[test]
+    fn test_kind_of_finds_tag_through_boxing() {
+        let error: Box<dyn std::error::Error> = Box::new(JobError::input("No such file"));
+        assert_eq!(JobError::kind_of(&*error), Some(JobErrorKind::Input));
+
+        let untagged: Box<dyn std::error::Error> = "Inference failed".into();
+        assert_eq!(JobError::kind_of(&*untagged), None);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::shared::frame::Frame;
+
+/// Value of the padding around a letterboxed frame: 114/255 gray, the YOLO
+/// convention.
+pub const LETTERBOX_FILL: f32 = 114.0 / 255.0;
+
+/// A rectangle of a frame, in pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Crop {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Where a frame, or a [`Crop`] of it, lands in a square model input:
+/// scaled to fit, centered, and padded.
+///
+/// Scaling samples the nearest source pixel. [`source_x`](Self::source_x)
+/// and [`source_y`](Self::source_y) give the mapping, so every
+/// implementation of the resize picks the same pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Letterbox {
+    /// Side of the square input.
+    pub size: u32,
+    /// Input pixels per frame pixel.
+    pub scale: f64,
+    /// Size of the scaled frame inside the input.
+    pub width: u32,
+    pub height: u32,
+    /// Offset of the scaled frame from the input's top left.
+    pub pad_x: u32,
+    pub pad_y: u32,
+    crop: Crop,
+}
+
+impl Letterbox {
+    pub fn new(frame_width: u32, frame_height: u32, size: u32) -> Self {
+        Self::of_crop(
+            Crop {
+                x: 0,
+                y: 0,
+                width: frame_width,
+                height: frame_height,
+            },
+            size,
+        )
+    }
+
+    /// Letterboxes only `crop` of the frame; source pixels are still
+    /// frame coordinates.
+    pub fn of_crop(crop: Crop, size: u32) -> Self {
+        let (fw, fh) = (crop.width as f64, crop.height as f64);
+        let target = size as f64;
+        let scale = (target / fw).min(target / fh);
+        let width = (fw * scale).round() as u32;
+        let height = (fh * scale).round() as u32;
+        Self {
+            size,
+            scale,
+            width,
+            height,
+            pad_x: (size - width) / 2,
+            pad_y: (size - height) / 2,
+            crop,
+        }
+    }
+
+    /// Frame column that column `x` of the scaled frame samples.
+    pub fn source_x(&self, x: u32) -> u32 {
+        self.crop.x + ((x as f64 / self.scale) as u32).min(self.crop.width - 1)
+    }
+
+    /// Frame row that row `y` of the scaled frame samples.
+    pub fn source_y(&self, y: u32) -> u32 {
+        self.crop.y + ((y as f64 / self.scale) as u32).min(self.crop.height - 1)
+    }
+
+    /// Writes `frame` letterboxed into `out`, a planar RGB tensor of
+    /// `3 * size * size` values in 0–1.
+    pub fn fill(&self, frame: &Frame, out: &mut [f32]) {
+        out.fill(LETTERBOX_FILL);
+        let size = self.size as usize;
+        let plane = size * size;
+        let channels = frame.channels() as usize;
+        let row_bytes = frame.width() as usize * channels;
+        let data = frame.data();
+        let columns: Vec<usize> = (0..self.width)
+            .map(|x| self.source_x(x) as usize * channels)
+            .collect();
+        for y in 0..self.height {
+            let source_row = self.source_y(y) as usize * row_bytes;
+            let target_row = (self.pad_y + y) as usize * size + self.pad_x as usize;
+            for (x, &column) in columns.iter().enumerate() {
+                let pixel = &data[source_row + column..];
+                for c in 0..3 {
+                    out[c * plane + target_row + x] = pixel[c] as f32 / 255.0;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wide_frame_is_padded_top_and_bottom() {
+        let letterbox = Letterbox::new(1920, 1080, 640);
+        assert_eq!((letterbox.width, letterbox.height), (640, 360));
+        assert_eq!((letterbox.pad_x, letterbox.pad_y), (0, 140));
+    }
+
+    #[test]
+    fn test_source_pixels_stay_in_frame() {
+        let letterbox = Letterbox::new(3, 7, 640);
+        assert_eq!(letterbox.source_x(letterbox.width - 1), 2);
+        assert_eq!(letterbox.source_y(letterbox.height - 1), 6);
+    }
+
+    #[test]
+    fn test_fill_pads_with_gray_around_frame() {
+        let frame = Frame::new(vec![255; 4 * 2 * 3], 4, 2, 3, 0);
+        let letterbox = Letterbox::new(4, 2, 8);
+        let mut out = vec![0.0; 3 * 8 * 8];
+
+        letterbox.fill(&frame, &mut out);
+
+        assert_eq!(out[0], LETTERBOX_FILL);
+        assert_eq!(out[(letterbox.pad_y as usize) * 8], 1.0);
+        assert_eq!(out[2 * 64 + 7 * 8 + 7], LETTERBOX_FILL);
+    }
+
+    #[test]
+    fn test_crop_samples_only_its_pixels() {
+        let crop = Crop {
+            x: 100,
+            y: 50,
+            width: 200,
+            height: 100,
+        };
+        let letterbox = Letterbox::of_crop(crop, 400);
+        assert_eq!((letterbox.width, letterbox.height), (400, 200));
+        assert_eq!(letterbox.source_x(0), 100);
+        assert_eq!(letterbox.source_x(letterbox.width - 1), 299);
+        assert_eq!(letterbox.source_y(letterbox.height - 1), 149);
+    }
+}
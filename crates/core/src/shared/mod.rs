@@ -1,4 +1,17 @@
+pub mod cancellation;
+pub mod color_space;
 pub mod constants;
+pub mod cut_list;
+pub mod forced_tracks;
 pub mod frame;
+pub mod job_error;
+pub mod letterbox;
+pub mod pixel_converter;
 pub mod region;
+pub mod schema;
+pub mod time_map;
+pub mod track_id_selection;
+pub mod track_time_rules;
 pub mod video_metadata;
+pub mod warnings;
+pub mod yuv420;
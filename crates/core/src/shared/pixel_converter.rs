@@ -0,0 +1,22 @@
+use crate::shared::color_space::ColorSpace;
+use crate::shared::frame::Frame;
+use crate::shared::letterbox::Letterbox;
+use crate::shared::yuv420::Yuv420;
+
+/// Per-pixel format work handed to an accelerator: the YUV↔RGB conversion
+/// around decoding and encoding, and the letterbox resize in front of
+/// detection.
+///
+/// Implemented by the blurring slice's `GpuContext`. Readers, writers,
+/// and detectors without one do the same work on the CPU, with swscale or
+/// [`Letterbox::fill`].
+pub trait PixelConverter: Send + Sync {
+    /// Packed RGB24 pixels of `yuv`, decoded with `color`.
+    fn yuv420_to_rgb(&self, yuv: &Yuv420, color: ColorSpace) -> Vec<u8>;
+
+    /// Packed RGB24 pixels encoded with `color`.
+    fn rgb_to_yuv420(&self, rgb: &[u8], width: u32, height: u32, color: ColorSpace) -> Yuv420;
+
+    /// Like [`Letterbox::fill`].
+    fn letterbox(&self, frame: &Frame, letterbox: &Letterbox, out: &mut [f32]);
+}
@@ -93,6 +93,67 @@ impl Region {
         (fw as f64 / 2.0, fh as f64 / 2.0)
     }
 
+    /// The region with its full extent scaled by `factor` about its
+    /// center, clamped to a `frame_w`×`frame_h` frame like a freshly
+    /// built region.
+    pub fn scaled(&self, factor: f64, frame_w: u32, frame_h: u32) -> Region {
+        let (fw, fh, ux, uy) = self.unclamped_geometry();
+        let cx = ux as f64 + fw as f64 / 2.0;
+        let cy = uy as f64 + fh as f64 / 2.0;
+        let half_w = fw as f64 * factor / 2.0;
+        let half_h = fh as f64 * factor / 2.0;
+
+        let ux = (cx - half_w) as i32;
+        let uy = (cy - half_h) as i32;
+        let x = ux.max(0);
+        let y = uy.max(0);
+        Region {
+            x,
+            y,
+            width: ((cx + half_w).min(frame_w as f64) as i32 - x).max(0),
+            height: ((cy + half_h).min(frame_h as f64) as i32 - y).max(0),
+            track_id: self.track_id,
+            full_width: Some((half_w * 2.0) as i32),
+            full_height: Some((half_h * 2.0) as i32),
+            unclamped_x: Some(ux),
+            unclamped_y: Some(uy),
+        }
+    }
+
+    /// The region on a `frame_w`×`frame_h` frame turned clockwise by
+    /// `degrees`, to match [`Frame::rotated`](crate::shared::frame::Frame::rotated).
+    /// Other angles than quarter turns leave it as is.
+    pub fn rotated(&self, degrees: i32, frame_w: u32, frame_h: u32) -> Region {
+        let (fw, fh) = (frame_w as i32, frame_h as i32);
+        let turn = |x: i32, y: i32, w: i32, h: i32| match degrees.rem_euclid(360) {
+            90 => (fh - (y + h), x, h, w),
+            180 => (fw - (x + w), fh - (y + h), w, h),
+            270 => (y, fw - (x + w), h, w),
+            _ => (x, y, w, h),
+        };
+        let (x, y, width, height) = turn(self.x, self.y, self.width, self.height);
+        let mut rotated = Region {
+            x,
+            y,
+            width,
+            height,
+            track_id: self.track_id,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        };
+        if self.full_width.is_some() || self.unclamped_x.is_some() {
+            let (ux, uy, full_w, full_h) = self.unclamped_geometry();
+            let (ux, uy, full_w, full_h) = turn(ux, uy, full_w, full_h);
+            rotated.full_width = Some(full_w);
+            rotated.full_height = Some(full_h);
+            rotated.unclamped_x = Some(ux);
+            rotated.unclamped_y = Some(uy);
+        }
+        rotated
+    }
+
     fn unclamped_geometry(&self) -> (i32, i32, i32, i32) {
         (
             self.full_width.unwrap_or(self.width),
@@ -186,6 +247,66 @@ mod tests {
 
     // ── Deduplication ────────────────────────────────────────────────
 
+    #[test]
+    fn test_scaled_grows_about_center() {
+        let scaled = region(40, 40, 20, 10).scaled(2.0, 100, 100);
+        assert_eq!(
+            (scaled.x, scaled.y, scaled.width, scaled.height),
+            (30, 35, 40, 20)
+        );
+    }
+
+    #[test]
+    fn test_scaled_clamps_to_frame_and_keeps_full_extent() {
+        let r = region_with_unclamped(0, 0, 10, 20, -10, 0, 20, 20);
+
+        let scaled = r.scaled(2.0, 100, 100);
+
+        assert_eq!(
+            (scaled.x, scaled.y, scaled.width, scaled.height),
+            (0, 0, 20, 30)
+        );
+        assert_eq!(scaled.full_width, Some(40));
+        assert_eq!(scaled.unclamped_x, Some(-20));
+        assert_eq!(scaled.ellipse_axes(), (20.0, 20.0));
+    }
+
+    #[rstest]
+    #[case(90, (70, 10, 20, 30))]
+    #[case(180, (60, 70, 30, 20))]
+    #[case(270, (10, 60, 20, 30))]
+    #[case(0, (10, 10, 30, 20))]
+    fn test_rotated_turns_clockwise(#[case] degrees: i32, #[case] expected: (i32, i32, i32, i32)) {
+        // A 100×100 frame, so both axes keep their length.
+        let rotated = region(10, 10, 30, 20).rotated(degrees, 100, 100);
+        assert_eq!(
+            (rotated.x, rotated.y, rotated.width, rotated.height),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_rotated_quarter_turn_swaps_frame_axes() {
+        // 200 wide, 100 tall: the region's right edge is 40 from the frame's.
+        let rotated = region(150, 0, 10, 20).rotated(270, 200, 100);
+        assert_eq!(
+            (rotated.x, rotated.y, rotated.width, rotated.height),
+            (0, 40, 20, 10)
+        );
+    }
+
+    #[test]
+    fn test_rotated_keeps_full_extent() {
+        let r = region_with_unclamped(0, 0, 10, 20, -10, 0, 20, 20);
+
+        let rotated = r.rotated(90, 100, 100);
+
+        assert_eq!((rotated.x, rotated.y), (80, 0));
+        assert_eq!(rotated.unclamped_x, Some(80));
+        assert_eq!(rotated.unclamped_y, Some(-10));
+        assert_eq!(rotated.ellipse_axes(), (10.0, 10.0));
+    }
+
     #[test]
     fn test_deduplicate_empty() {
         let result = Region::deduplicate(&[], DEFAULT_IOU_THRESHOLD);
@@ -0,0 +1,204 @@
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+/// Upgrades a document by one version, in place.
+pub type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+/// Version history of a JSON file format, so files saved by older builds
+/// keep loading as fields are added.
+///
+/// Each document carries a top-level `version`. `migrations[i]` upgrades
+/// version `oldest() + i` to the next, and `upgrade` chains them up to
+/// `version`. New optional fields don't need a migration, since readers
+/// default them; renames, removals, and changed meanings do.
+pub struct Schema {
+    name: &'static str,
+    version: u32,
+    migrations: &'static [Migration],
+    unversioned: Option<u32>,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum SchemaError {
+    #[error("{name} is not a JSON object")]
+    NotAnObject { name: &'static str },
+    #[error("{name} has no version")]
+    MissingVersion { name: &'static str },
+    #[error("{name} version {found} is newer than this build reads (up to {supported})")]
+    Newer {
+        name: &'static str,
+        found: u64,
+        supported: u32,
+    },
+    #[error("{name} version {found} is older than this build reads (from {oldest})")]
+    TooOld {
+        name: &'static str,
+        found: u64,
+        oldest: u32,
+    },
+    #[error("Could not upgrade {name} from version {from}: {message}")]
+    Migration {
+        name: &'static str,
+        from: u32,
+        message: String,
+    },
+}
+
+impl Schema {
+    /// `name` appears in error messages, e.g. "Detections".
+    pub const fn new(name: &'static str, version: u32, migrations: &'static [Migration]) -> Self {
+        Self {
+            name,
+            version,
+            migrations,
+            unversioned: None,
+        }
+    }
+
+    /// Reads documents without a `version` as `version`, for formats saved
+    /// before they were versioned. Without this they are rejected.
+    pub const fn with_unversioned(mut self, version: u32) -> Self {
+        self.unversioned = Some(version);
+        self
+    }
+
+    /// Version written by this build.
+    pub const fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Oldest version `upgrade` can migrate from.
+    pub const fn oldest(&self) -> u32 {
+        self.version - self.migrations.len() as u32
+    }
+
+    /// Migrates `document` to the current version and stamps it with it.
+    pub fn upgrade(&self, mut document: Value) -> Result<Value, SchemaError> {
+        let name = self.name;
+        let object = document
+            .as_object_mut()
+            .ok_or(SchemaError::NotAnObject { name })?;
+        let found = match object.get("version") {
+            Some(version) => version
+                .as_u64()
+                .ok_or(SchemaError::MissingVersion { name })?,
+            None => self
+                .unversioned
+                .ok_or(SchemaError::MissingVersion { name })? as u64,
+        };
+        if found > self.version as u64 {
+            return Err(SchemaError::Newer {
+                name,
+                found,
+                supported: self.version,
+            });
+        }
+        if found < self.oldest() as u64 {
+            return Err(SchemaError::TooOld {
+                name,
+                found,
+                oldest: self.oldest(),
+            });
+        }
+
+        for from in found as u32..self.version {
+            let migrate = self.migrations[(from - self.oldest()) as usize];
+            migrate(object).map_err(|message| SchemaError::Migration {
+                name,
+                from,
+                message,
+            })?;
+        }
+        object.insert("version".into(), self.version.into());
+        Ok(document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 0 to 1 renames `w` to `width`, 1 to 2 rejects odd widths, and 2 to 3
+    /// only added optional fields.
+    const SHAPES: Schema = Schema::new("Shapes", 3, &[rename_w, fail_on_odd, no_op]);
+
+    fn rename_w(doc: &mut Map<String, Value>) -> Result<(), String> {
+        if let Some(w) = doc.remove("w") {
+            doc.insert("width".into(), w);
+        }
+        Ok(())
+    }
+
+    fn fail_on_odd(doc: &mut Map<String, Value>) -> Result<(), String> {
+        match doc.get("width").and_then(Value::as_u64) {
+            Some(width) if width % 2 == 1 => Err("odd width".into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn no_op(_: &mut Map<String, Value>) -> Result<(), String> {
+        Ok(())
+    }
+
+    #[test]
+    fn test_current_version_is_unchanged() {
+        let doc = json!({"version": 3, "width": 4});
+        assert_eq!(SHAPES.upgrade(doc.clone()).unwrap(), doc);
+    }
+
+    #[test]
+    fn test_migrations_run_in_order() {
+        let upgraded = SHAPES.upgrade(json!({"version": 0, "w": 4})).unwrap();
+        assert_eq!(upgraded, json!({"version": 3, "width": 4}));
+    }
+
+    #[test]
+    fn test_migration_failure_names_the_version() {
+        let err = SHAPES.upgrade(json!({"version": 0, "w": 3})).unwrap_err();
+        assert_eq!(
+            err,
+            SchemaError::Migration {
+                name: "Shapes",
+                from: 1,
+                message: "odd width".into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let err = SHAPES.upgrade(json!({"version": 4})).unwrap_err();
+        assert!(matches!(err, SchemaError::Newer { found: 4, .. }));
+    }
+
+    #[test]
+    fn test_too_old_version_is_rejected() {
+        const RECENT: Schema = Schema::new("Shapes", 3, &[no_op]);
+        assert_eq!(RECENT.oldest(), 2);
+        let err = RECENT.upgrade(json!({"version": 1})).unwrap_err();
+        assert!(matches!(err, SchemaError::TooOld { oldest: 2, .. }));
+    }
+
+    #[test]
+    fn test_missing_version() {
+        assert!(matches!(
+            SHAPES.upgrade(json!({"w": 4})),
+            Err(SchemaError::MissingVersion { .. })
+        ));
+        const LEGACY: Schema =
+            Schema::new("Shapes", 3, &[rename_w, fail_on_odd, no_op]).with_unversioned(0);
+        assert_eq!(
+            LEGACY.upgrade(json!({"w": 4})).unwrap(),
+            json!({"version": 3, "width": 4})
+        );
+    }
+
+    #[test]
+    fn test_non_object_is_rejected() {
+        assert!(matches!(
+            SHAPES.upgrade(json!([1, 2])),
+            Err(SchemaError::NotAnObject { .. })
+        ));
+    }
+}
@@ -0,0 +1,186 @@
+use crate::shared::track_time_rules::TimeRange;
+
+/// Frame rate assumed for frames without a timestamp when the source
+/// doesn't report a usable one, matching the encoder's fallback.
+const FALLBACK_FPS: f64 = 30.0;
+
+/// Where a moment of the source lands on the output timeline.
+///
+/// Cut ranges are removed and everything after them is pulled back, and
+/// frames without a timestamp are spaced at the source frame rate. Video
+/// writers, cut-range readers, and audio processing all place time
+/// through it, so a bleep computed in source audio time plays over the
+/// same frames it was computed for, however long the recording.
+///
+/// Cuts are half-open (`start <= t < end`), like the frames a cut-range
+/// reader drops.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TimeMap {
+    fps: f64,
+    /// Sorted, with overlaps merged.
+    cuts: Vec<TimeRange>,
+}
+
+impl TimeMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spaces frames without a timestamp at `fps`, the rate the source
+    /// reports. The exact rate is kept: a rate rounded for the encoder
+    /// (29.97 to `30000/1001`, 12.3456 to `12346/1000`) would drift the
+    /// frames against the audio over a long recording.
+    pub fn with_frame_rate(mut self, fps: f64) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    /// Removes these ranges from the output.
+    pub fn with_cuts(mut self, cuts: &[TimeRange]) -> Self {
+        self.cuts = merge_ranges(cuts);
+        self
+    }
+
+    /// The cut ranges, sorted and merged.
+    pub fn cuts(&self) -> &[TimeRange] {
+        &self.cuts
+    }
+
+    /// The frame rate untimed frames are spaced at, or `None` when none
+    /// was given or it isn't usable.
+    pub fn frame_rate(&self) -> Option<f64> {
+        (self.fps.is_finite() && self.fps.round() > 0.0).then_some(self.fps)
+    }
+
+    pub fn is_cut(&self, seconds: f64) -> bool {
+        self.cuts
+            .iter()
+            .any(|cut| seconds >= cut.start && seconds < cut.end)
+    }
+
+    /// Seconds of cuts before `seconds`.
+    pub fn cut_before(&self, seconds: f64) -> f64 {
+        self.cuts
+            .iter()
+            .take_while(|cut| cut.start < seconds)
+            .map(|cut| cut.end.min(seconds) - cut.start)
+            .sum()
+    }
+
+    /// Output time of source time `seconds`. A moment inside a cut maps to
+    /// where the cut was.
+    pub fn to_output(&self, seconds: f64) -> f64 {
+        seconds - self.cut_before(seconds)
+    }
+
+    /// `range` on the output timeline, or `None` when it was cut entirely.
+    pub fn range_to_output(&self, range: TimeRange) -> Option<TimeRange> {
+        let output = TimeRange::new(self.to_output(range.start), self.to_output(range.end));
+        (output.end > output.start).then_some(output)
+    }
+
+    /// Output time of the `count`th frame written without a timestamp.
+    /// Such frames follow one another, so cuts are already behind them.
+    pub fn frame_time(&self, count: usize) -> f64 {
+        count as f64 / self.frame_rate().unwrap_or(FALLBACK_FPS)
+    }
+}
+
+/// Sorts ranges and merges any that overlap, so each second counts once.
+pub fn merge_ranges(ranges: &[TimeRange]) -> Vec<TimeRange> {
+    let mut sorted = ranges.to_vec();
+    sorted.sort_by(|a, b| a.start.total_cmp(&b.start));
+    let mut merged: Vec<TimeRange> = Vec::with_capacity(sorted.len());
+    for range in sorted {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rstest::rstest;
+
+    #[test]
+    fn test_cut_before_merges_overlapping_cuts() {
+        let map = TimeMap::new().with_cuts(&[TimeRange::new(1.0, 2.0), TimeRange::new(1.5, 3.0)]);
+        assert_eq!(map.cut_before(0.5), 0.0);
+        assert_eq!(map.cut_before(2.5), 1.5);
+        assert_eq!(map.cut_before(4.0), 2.0);
+    }
+
+    #[test]
+    fn test_cuts_are_half_open() {
+        let map = TimeMap::new().with_cuts(&[TimeRange::new(1.0, 2.0)]);
+        assert!(!map.is_cut(0.99));
+        assert!(map.is_cut(1.0));
+        assert!(!map.is_cut(2.0));
+    }
+
+    #[test]
+    fn test_time_inside_cut_maps_to_cut_start() {
+        let map = TimeMap::new().with_cuts(&[TimeRange::new(10.0, 20.0)]);
+        assert_eq!(map.to_output(5.0), 5.0);
+        assert_eq!(map.to_output(15.0), 10.0);
+        assert_eq!(map.to_output(25.0), 15.0);
+    }
+
+    #[test]
+    fn test_trim_cut_to_infinity() {
+        let map = TimeMap::new().with_cuts(&TimeRange::new(60.0, 120.0).trim_cuts());
+        assert_eq!(map.to_output(90.0), 30.0);
+        assert_eq!(map.to_output(3600.0), 60.0);
+    }
+
+    #[test]
+    fn test_range_to_output() {
+        let map = TimeMap::new().with_cuts(&[TimeRange::new(10.0, 20.0)]);
+        assert_eq!(
+            map.range_to_output(TimeRange::new(8.0, 12.0)),
+            Some(TimeRange::new(8.0, 10.0))
+        );
+        assert_eq!(
+            map.range_to_output(TimeRange::new(18.0, 22.0)),
+            Some(TimeRange::new(10.0, 12.0))
+        );
+        assert_eq!(map.range_to_output(TimeRange::new(12.0, 18.0)), None);
+    }
+
+    #[test]
+    fn test_merge_ranges_joins_overlaps() {
+        let merged = merge_ranges(&[
+            TimeRange::new(5.0, 6.0),
+            TimeRange::new(0.0, 2.0),
+            TimeRange::new(1.0, 3.0),
+        ]);
+        assert_eq!(
+            merged,
+            vec![TimeRange::new(0.0, 3.0), TimeRange::new(5.0, 6.0)]
+        );
+    }
+
+    #[rstest]
+    #[case(0.0)]
+    #[case(f64::NAN)]
+    fn test_invalid_frame_rate_falls_back_to_30(#[case] fps: f64) {
+        let map = TimeMap::new().with_frame_rate(fps);
+        assert_eq!(map.frame_rate(), None);
+        assert_eq!(map.frame_time(30), 1.0);
+    }
+
+    #[test]
+    fn test_many_cuts_over_long_recording() {
+        // Two hours with a 3 s cut at the start of every minute.
+        let cuts: Vec<TimeRange> = (0..120)
+            .map(|m| TimeRange::new(m as f64 * 60.0, m as f64 * 60.0 + 3.0))
+            .collect();
+        let map = TimeMap::new().with_cuts(&cuts);
+        let source = 119.0 * 60.0 + 30.0;
+        assert_relative_eq!(map.to_output(source), 119.0 * 57.0 + 27.0, epsilon = 1e-9);
+    }
+}
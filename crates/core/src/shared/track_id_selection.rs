@@ -0,0 +1,266 @@
+use std::collections::{BTreeSet, HashSet};
+
+/// Largest range that may be expanded without a list of known track IDs,
+/// so a typo like `1-4000000000` fails instead of exhausting memory.
+pub const MAX_UNBOUNDED_RANGE: u32 = 100_000;
+
+/// One comma-separated item of a track ID list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrackIdTerm {
+    All,
+    None,
+    Id(u32),
+    /// Inclusive `START-END`.
+    Range(u32, u32),
+    /// 1-based group label from the grouping output (`group:N`).
+    Group(usize),
+}
+
+/// Which tracks a list selects once ranges and groups are expanded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrackIdSelection {
+    All,
+    Ids(HashSet<u32>),
+}
+
+/// A parsed track ID list such as `1,3-9,group:2`, or `all`/`none`.
+///
+/// Parsing only checks syntax. Expanding group labels needs the grouping
+/// output, and checking IDs needs the tracks that were actually detected;
+/// both come from [`resolve`].
+///
+/// [`resolve`]: TrackIdSpec::resolve
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrackIdSpec {
+    terms: Vec<TrackIdTerm>,
+}
+
+impl TrackIdSpec {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let terms = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(parse_term)
+            .collect::<Result<Vec<_>, _>>()?;
+        if terms.is_empty() {
+            return Err("Track ID list is empty (use 'none' to select no tracks)".into());
+        }
+        Ok(Self { terms })
+    }
+
+    pub fn terms(&self) -> &[TrackIdTerm] {
+        &self.terms
+    }
+
+    pub fn uses_groups(&self) -> bool {
+        self.terms
+            .iter()
+            .any(|t| matches!(t, TrackIdTerm::Group(_)))
+    }
+
+    /// Expands the list against `groups` (as numbered by the grouping
+    /// output) and, when given, the `known` track IDs.
+    ///
+    /// With `known`, an explicit ID that was never detected is an error, as
+    /// is a range with no detected track in it; ranges only keep the IDs
+    /// that exist. `all` wins over everything else and `none` adds
+    /// nothing.
+    pub fn resolve(
+        &self,
+        groups: &[Vec<u32>],
+        known: Option<&HashSet<u32>>,
+    ) -> Result<TrackIdSelection, String> {
+        if self.terms.contains(&TrackIdTerm::All) {
+            return Ok(TrackIdSelection::All);
+        }
+        let mut ids = HashSet::new();
+        let mut unknown = BTreeSet::new();
+        for term in &self.terms {
+            match *term {
+                TrackIdTerm::All | TrackIdTerm::None => {}
+                TrackIdTerm::Id(id) => {
+                    if known.is_some_and(|k| !k.contains(&id)) {
+                        unknown.insert(id);
+                    }
+                    ids.insert(id);
+                }
+                TrackIdTerm::Range(start, end) => match known {
+                    Some(k) => {
+                        if !k.iter().any(|id| (start..=end).contains(id)) {
+                            return Err(format!("No detected tracks in range {start}-{end}"));
+                        }
+                        ids.extend(k.iter().filter(|id| (start..=end).contains(*id)));
+                    }
+                    None if end - start < MAX_UNBOUNDED_RANGE => ids.extend(start..=end),
+                    None => {
+                        return Err(format!(
+                            "Track ID range {start}-{end} is too large \
+                             (at most {MAX_UNBOUNDED_RANGE} IDs without a preview folder)"
+                        ))
+                    }
+                },
+                TrackIdTerm::Group(n) => {
+                    if groups.is_empty() {
+                        return Err(format!(
+                            "group:{n} needs the grouping output from a preview run"
+                        ));
+                    }
+                    let group = n
+                        .checked_sub(1)
+                        .and_then(|i| groups.get(i))
+                        .ok_or_else(|| {
+                            format!(
+                                "group:{n} does not exist (there are {} groups)",
+                                groups.len()
+                            )
+                        })?;
+                    ids.extend(group.iter().copied());
+                }
+            }
+        }
+        if !unknown.is_empty() {
+            let list: Vec<String> = unknown.iter().map(u32::to_string).collect();
+            return Err(format!("Track IDs not detected: {}", list.join(", ")));
+        }
+        Ok(TrackIdSelection::Ids(ids))
+    }
+}
+
+fn parse_term(term: &str) -> Result<TrackIdTerm, String> {
+    let lower = term.to_lowercase();
+    if lower == "all" {
+        return Ok(TrackIdTerm::All);
+    }
+    if lower == "none" {
+        return Ok(TrackIdTerm::None);
+    }
+    if let Some(n) = lower.strip_prefix("group:") {
+        return n
+            .trim()
+            .parse()
+            .ok()
+            .filter(|&n| n > 0)
+            .map(TrackIdTerm::Group)
+            .ok_or_else(|| format!("Invalid group label '{term}' (expected group:N, N from 1)"));
+    }
+    if let Some((start, end)) = term.split_once('-') {
+        let invalid = || format!("Invalid track ID range '{term}' (expected START-END)");
+        let start: u32 = start.trim().parse().map_err(|_| invalid())?;
+        let end: u32 = end.trim().parse().map_err(|_| invalid())?;
+        if end < start {
+            return Err(format!("Track ID range '{term}' ends before it starts"));
+        }
+        return Ok(TrackIdTerm::Range(start, end));
+    }
+    term.parse()
+        .map(TrackIdTerm::Id)
+        .map_err(|_| format!("Invalid track ID '{term}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn ids(list: &[u32]) -> TrackIdSelection {
+        TrackIdSelection::Ids(list.iter().copied().collect())
+    }
+
+    #[rstest]
+    #[case("3", vec![TrackIdTerm::Id(3)])]
+    #[case("3-9", vec![TrackIdTerm::Range(3, 9)])]
+    #[case(" group:2 ", vec![TrackIdTerm::Group(2)])]
+    #[case("ALL", vec![TrackIdTerm::All])]
+    #[case("none", vec![TrackIdTerm::None])]
+    #[case("1, 4-5,group:1", vec![TrackIdTerm::Id(1), TrackIdTerm::Range(4, 5), TrackIdTerm::Group(1)])]
+    fn test_parse(#[case] spec: &str, #[case] expected: Vec<TrackIdTerm>) {
+        assert_eq!(
+            TrackIdSpec::parse(spec).unwrap().terms(),
+            expected.as_slice()
+        );
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("x")]
+    #[case("9-3")]
+    #[case("group:0")]
+    #[case("group:x")]
+    #[case("-3")]
+    fn test_parse_rejects(#[case] spec: &str) {
+        assert!(TrackIdSpec::parse(spec).is_err());
+    }
+
+    #[test]
+    fn test_range_expands_without_known_ids() {
+        let spec = TrackIdSpec::parse("1,3-5").unwrap();
+        assert_eq!(spec.resolve(&[], None).unwrap(), ids(&[1, 3, 4, 5]));
+    }
+
+    #[test]
+    fn test_range_keeps_only_known_ids() {
+        let known: HashSet<u32> = [3, 5, 20].into_iter().collect();
+        let spec = TrackIdSpec::parse("3-9").unwrap();
+        assert_eq!(spec.resolve(&[], Some(&known)).unwrap(), ids(&[3, 5]));
+    }
+
+    #[test]
+    fn test_range_matching_nothing_is_an_error_with_known_ids() {
+        let known: HashSet<u32> = [1].into_iter().collect();
+        let spec = TrackIdSpec::parse("3-9").unwrap();
+        assert!(spec.resolve(&[], Some(&known)).is_err());
+    }
+
+    #[test]
+    fn test_huge_range_without_known_ids_is_rejected() {
+        let spec = TrackIdSpec::parse("1-4000000000").unwrap();
+        assert!(spec.resolve(&[], None).is_err());
+    }
+
+    #[test]
+    fn test_groups_expand_by_label() {
+        let groups = vec![vec![1, 4], vec![2, 7]];
+        let spec = TrackIdSpec::parse("group:2,9").unwrap();
+        assert_eq!(spec.resolve(&groups, None).unwrap(), ids(&[2, 7, 9]));
+    }
+
+    #[test]
+    fn test_missing_group_is_an_error() {
+        let spec = TrackIdSpec::parse("group:3").unwrap();
+        assert!(spec.resolve(&[vec![1]], None).is_err());
+        assert!(spec.resolve(&[], None).is_err());
+    }
+
+    #[test]
+    fn test_unknown_ids_are_listed() {
+        let known: HashSet<u32> = [1, 2].into_iter().collect();
+        let spec = TrackIdSpec::parse("2,8,5").unwrap();
+        let err = spec.resolve(&[], Some(&known)).unwrap_err();
+        assert_eq!(err, "Track IDs not detected: 5, 8");
+    }
+
+    #[test]
+    fn test_all_and_none() {
+        assert_eq!(
+            TrackIdSpec::parse("1,all")
+                .unwrap()
+                .resolve(&[], None)
+                .unwrap(),
+            TrackIdSelection::All
+        );
+        assert_eq!(
+            TrackIdSpec::parse("none")
+                .unwrap()
+                .resolve(&[], None)
+                .unwrap(),
+            ids(&[])
+        );
+    }
+
+    #[test]
+    fn test_uses_groups() {
+        assert!(TrackIdSpec::parse("1,group:1").unwrap().uses_groups());
+        assert!(!TrackIdSpec::parse("1-3").unwrap().uses_groups());
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+
+use crate::shared::region::Region;
+
+/// A closed time window in seconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeRange {
+    pub start: f64,
+    pub end: f64,
+}
+
+impl TimeRange {
+    pub fn new(start: f64, end: f64) -> Self {
+        Self { start, end }
+    }
+
+    pub fn contains(&self, seconds: f64) -> bool {
+        seconds >= self.start && seconds <= self.end
+    }
+
+    /// Parses `START-END`, where each side is `SS`, `MM:SS`, or `HH:MM:SS`
+    /// with optional fractional seconds (e.g. `02:10-05:30.5`).
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (start, end) = spec
+            .split_once('-')
+            .ok_or_else(|| format!("Time range '{spec}' must be START-END"))?;
+        let start = parse_timestamp(start.trim())?;
+        let end = parse_timestamp(end.trim())?;
+        if end < start {
+            return Err(format!("Time range '{spec}' ends before it starts"));
+        }
+        Ok(Self::new(start, end))
+    }
+
+    /// Cut ranges that remove everything outside this range, trimming a
+    /// recording to it. The last cut runs to infinity, so the recording's
+    /// length is not needed.
+    pub fn trim_cuts(&self) -> Vec<TimeRange> {
+        let mut cuts = Vec::with_capacity(2);
+        if self.start > 0.0 {
+            cuts.push(Self::new(0.0, self.start));
+        }
+        cuts.push(Self::new(self.end, f64::INFINITY));
+        cuts
+    }
+
+    /// Parses a comma-separated list of ranges. Blank input yields none.
+    pub fn parse_list(spec: &str) -> Result<Vec<Self>, String> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(Self::parse)
+            .collect()
+    }
+}
+
+/// Parses `SS`, `MM:SS`, or `HH:MM:SS` (fractional seconds allowed) to seconds.
+pub fn parse_timestamp(s: &str) -> Result<f64, String> {
+    let invalid = || format!("Invalid timestamp '{s}' (expected SS, MM:SS, or HH:MM:SS)");
+    let parts: Vec<&str> = s.split(':').collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return Err(invalid());
+    }
+    let mut seconds = 0.0;
+    for (i, part) in parts.iter().enumerate() {
+        let value: f64 = part.parse().map_err(|_| invalid())?;
+        let is_last = i == parts.len() - 1;
+        if value < 0.0 || (!is_last && value.fract() != 0.0) {
+            return Err(invalid());
+        }
+        seconds = seconds * 60.0 + value;
+    }
+    Ok(seconds)
+}
+
+/// Per-track time windows layered on top of the blur/exclude ID sets.
+///
+/// A track with `blur_during` ranges is only blurred inside them; a track
+/// with `show_during` ranges is left visible inside them. Tracks without
+/// rules, and regions without a track ID, pass through untouched.
+///
+/// `blur_all_during` windows apply to every region: once any are set,
+/// nothing is blurred outside them.
+#[derive(Clone, Debug, Default)]
+pub struct TrackTimeRules {
+    blur_during: HashMap<u32, Vec<TimeRange>>,
+    show_during: HashMap<u32, Vec<TimeRange>>,
+    blur_all_during: Vec<TimeRange>,
+}
+
+impl TrackTimeRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn blur_during(&mut self, track_id: u32, range: TimeRange) {
+        self.blur_during.entry(track_id).or_default().push(range);
+    }
+
+    pub fn show_during(&mut self, track_id: u32, range: TimeRange) {
+        self.show_during.entry(track_id).or_default().push(range);
+    }
+
+    pub fn blur_all_during(&mut self, range: TimeRange) {
+        self.blur_all_during.push(range);
+    }
+
+    /// Parses a `TRACK@START-END` rule (e.g. `7@02:10-05:30`).
+    pub fn parse_rule(spec: &str) -> Result<(u32, TimeRange), String> {
+        let (track, range) = spec
+            .split_once('@')
+            .ok_or_else(|| format!("Track range '{spec}' must be TRACK@START-END"))?;
+        let track_id = track
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid track ID in '{spec}'"))?;
+        Ok((track_id, TimeRange::parse(range)?))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blur_during.is_empty()
+            && self.show_during.is_empty()
+            && self.blur_all_during.is_empty()
+    }
+
+    /// Whether a region on `track_id` should be blurred at `seconds`.
+    pub fn allows(&self, track_id: Option<u32>, seconds: f64) -> bool {
+        if !self.blur_all_during.is_empty()
+            && !self.blur_all_during.iter().any(|r| r.contains(seconds))
+        {
+            return false;
+        }
+        let Some(id) = track_id else {
+            return true;
+        };
+        let in_any = |ranges: Option<&Vec<TimeRange>>| {
+            ranges.is_some_and(|rs| rs.iter().any(|r| r.contains(seconds)))
+        };
+        let blurred = !self.blur_during.contains_key(&id) || in_any(self.blur_during.get(&id));
+        blurred && !in_any(self.show_during.get(&id))
+    }
+
+    /// Keeps the regions that should be blurred on frame `frame_index`.
+    pub fn filter(&self, regions: &[Region], frame_index: usize, fps: f64) -> Vec<Region> {
+        if self.is_empty() {
+            return regions.to_vec();
+        }
+        let seconds = if fps > 0.0 {
+            frame_index as f64 / fps
+        } else {
+            0.0
+        };
+        regions
+            .iter()
+            .filter(|r| self.allows(r.track_id, seconds))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn tracked(track_id: Option<u32>) -> Region {
+        Region {
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 10,
+            track_id,
+            full_width: None,
+            full_height: None,
+            unclamped_x: None,
+            unclamped_y: None,
+        }
+    }
+
+    #[rstest]
+    #[case("45", 45.0)]
+    #[case("02:10", 130.0)]
+    #[case("1:02:10", 3730.0)]
+    #[case("00:05.5", 5.5)]
+    fn test_parse_timestamp(#[case] input: &str, #[case] expected: f64) {
+        assert_eq!(parse_timestamp(input).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("abc")]
+    #[case("1:2:3:4")]
+    #[case("1.5:00")]
+    #[case("-5")]
+    fn test_parse_timestamp_rejects_invalid(#[case] input: &str) {
+        assert!(parse_timestamp(input).is_err());
+    }
+
+    #[test]
+    fn test_parse_range() {
+        let range = TimeRange::parse("02:10-05:30").unwrap();
+        assert_eq!(range, TimeRange::new(130.0, 330.0));
+    }
+
+    #[test]
+    fn test_parse_range_rejects_reversed() {
+        assert!(TimeRange::parse("05:30-02:10").is_err());
+    }
+
+    #[test]
+    fn test_trim_cuts_keep_only_the_range() {
+        let cuts = TimeRange::new(10.0, 20.0).trim_cuts();
+        assert_eq!(
+            cuts,
+            vec![
+                TimeRange::new(0.0, 10.0),
+                TimeRange::new(20.0, f64::INFINITY)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trim_cuts_from_start_only_cut_the_tail() {
+        let cuts = TimeRange::new(0.0, 20.0).trim_cuts();
+        assert_eq!(cuts, vec![TimeRange::new(20.0, f64::INFINITY)]);
+    }
+
+    #[test]
+    fn test_parse_list_skips_blanks() {
+        let ranges = TimeRange::parse_list(" 0-10, ,20-30 ").unwrap();
+        assert_eq!(ranges.len(), 2);
+        assert!(TimeRange::parse_list("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let (id, range) = TrackTimeRules::parse_rule("7@02:10-05:30").unwrap();
+        assert_eq!(id, 7);
+        assert_eq!(range, TimeRange::new(130.0, 330.0));
+        assert!(TrackTimeRules::parse_rule("02:10-05:30").is_err());
+        assert!(TrackTimeRules::parse_rule("x@0-1").is_err());
+    }
+
+    #[test]
+    fn test_empty_rules_allow_everything() {
+        let rules = TrackTimeRules::new();
+        assert!(rules.allows(Some(1), 0.0));
+        assert!(rules.allows(None, 100.0));
+    }
+
+    #[test]
+    fn test_blur_during_limits_track_to_range() {
+        let mut rules = TrackTimeRules::new();
+        rules.blur_during(7, TimeRange::new(130.0, 330.0));
+        assert!(!rules.allows(Some(7), 100.0));
+        assert!(rules.allows(Some(7), 200.0));
+        assert!(!rules.allows(Some(7), 400.0));
+        assert!(rules.allows(Some(8), 100.0));
+    }
+
+    #[test]
+    fn test_show_during_unblurs_track_in_range() {
+        let mut rules = TrackTimeRules::new();
+        rules.show_during(3, TimeRange::new(10.0, 20.0));
+        assert!(rules.allows(Some(3), 5.0));
+        assert!(!rules.allows(Some(3), 15.0));
+    }
+
+    #[test]
+    fn test_multiple_ranges_per_track() {
+        let mut rules = TrackTimeRules::new();
+        rules.blur_during(1, TimeRange::new(0.0, 10.0));
+        rules.blur_during(1, TimeRange::new(20.0, 30.0));
+        assert!(rules.allows(Some(1), 5.0));
+        assert!(!rules.allows(Some(1), 15.0));
+        assert!(rules.allows(Some(1), 25.0));
+    }
+
+    #[test]
+    fn test_blur_all_during_limits_every_region() {
+        let mut rules = TrackTimeRules::new();
+        rules.blur_all_during(TimeRange::new(10.0, 20.0));
+        assert!(!rules.allows(Some(1), 5.0));
+        assert!(!rules.allows(None, 5.0));
+        assert!(rules.allows(Some(1), 15.0));
+        assert!(rules.allows(None, 15.0));
+    }
+
+    #[test]
+    fn test_blur_all_during_combines_with_show_during() {
+        let mut rules = TrackTimeRules::new();
+        rules.blur_all_during(TimeRange::new(10.0, 20.0));
+        rules.show_during(1, TimeRange::new(12.0, 14.0));
+        assert!(!rules.allows(Some(1), 13.0));
+        assert!(rules.allows(Some(2), 13.0));
+    }
+
+    #[test]
+    fn test_untracked_regions_always_pass() {
+        let mut rules = TrackTimeRules::new();
+        rules.show_during(1, TimeRange::new(0.0, 100.0));
+        assert!(rules.allows(None, 50.0));
+    }
+
+    #[test]
+    fn test_filter_uses_frame_timestamp() {
+        let mut rules = TrackTimeRules::new();
+        rules.blur_during(1, TimeRange::new(2.0, 4.0));
+        let regions = vec![tracked(Some(1)), tracked(Some(2))];
+
+        let at_1s = rules.filter(&regions, 30, 30.0);
+        assert_eq!(at_1s.len(), 1);
+        assert_eq!(at_1s[0].track_id, Some(2));
+
+        let at_3s = rules.filter(&regions, 90, 30.0);
+        assert_eq!(at_3s.len(), 2);
+    }
+}
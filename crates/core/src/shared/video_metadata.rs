@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use crate::shared::color_space::ColorSpace;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct VideoMetadata {
     pub width: u32,
@@ -8,7 +10,11 @@ pub struct VideoMetadata {
     pub total_frames: usize,
     pub codec: String,
     pub source_path: Option<PathBuf>,
+    /// Clockwise turn, in degrees (0, 90, 180, or 270), that shows frames
+    /// upright, as players apply it from the container's display matrix.
     pub rotation: i32,
+    /// YUV matrix and range of the source, reused when encoding.
+    pub color: ColorSpace,
 }
 
 #[cfg(test)]
@@ -25,6 +31,7 @@ mod tests {
             codec: "h264".to_string(),
             source_path: Some(PathBuf::from("/tmp/test.mp4")),
             rotation: 0,
+            color: ColorSpace::default(),
         };
         assert_eq!(meta.width, 1920);
         assert_eq!(meta.height, 1080);
@@ -44,6 +51,7 @@ mod tests {
             codec: "vp9".to_string(),
             source_path: None,
             rotation: 0,
+            color: ColorSpace::default(),
         };
         let cloned = meta.clone();
         assert_eq!(meta, cloned);
@@ -60,6 +68,7 @@ mod tests {
             codec: "png".to_string(),
             source_path: None,
             rotation: 0,
+            color: ColorSpace::default(),
         };
         assert_eq!(meta.total_frames, 1);
         assert_eq!(meta.fps, 0.0);
@@ -0,0 +1,121 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Category of a non-fatal problem, so frontends can group or filter
+/// warnings without parsing their text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WarningKind {
+    /// The source audio could not be copied or processed into the output.
+    Audio,
+    /// Rotation metadata was unusable and was ignored or rounded.
+    Rotation,
+    /// Some packets or frames failed to decode.
+    Decode,
+}
+
+impl WarningKind {
+    /// Stable identifier for reports and machine-readable output.
+    pub fn code(&self) -> &'static str {
+        match self {
+            WarningKind::Audio => "audio",
+            WarningKind::Rotation => "rotation",
+            WarningKind::Decode => "decode",
+        }
+    }
+}
+
+/// A problem that did not stop the job but that the user should know about
+/// before trusting the output.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Shared collector for [`Warning`]s raised anywhere in a run.
+///
+/// Cloning is cheap and every clone appends to the same list, so readers,
+/// writers, and pipeline threads can each hold one and the frontend reads
+/// the result once the job finishes. Identical warnings are kept once.
+#[derive(Clone, Default)]
+pub struct Warnings {
+    items: Arc<Mutex<Vec<Warning>>>,
+}
+
+impl Warnings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&self, kind: WarningKind, message: impl Into<String>) {
+        let warning = Warning {
+            kind,
+            message: message.into(),
+        };
+        log::warn!("{warning}");
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        if !items.contains(&warning) {
+            items.push(warning);
+        }
+    }
+
+    /// All warnings so far, in the order they were first raised.
+    pub fn snapshot(&self) -> Vec<Warning> {
+        self.items.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_one_list() {
+        let warnings = Warnings::new();
+        let clone = warnings.clone();
+        clone.push(WarningKind::Audio, "audio stream could not be copied");
+        assert_eq!(
+            warnings.snapshot(),
+            vec![Warning {
+                kind: WarningKind::Audio,
+                message: "audio stream could not be copied".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_duplicates_are_kept_once() {
+        let warnings = Warnings::new();
+        warnings.push(WarningKind::Decode, "3 packets could not be decoded");
+        warnings.push(WarningKind::Decode, "3 packets could not be decoded");
+        warnings.push(WarningKind::Rotation, "rotation rounded");
+        assert_eq!(warnings.snapshot().len(), 2);
+    }
+
+    #[test]
+    fn test_preserves_order() {
+        let warnings = Warnings::new();
+        warnings.push(WarningKind::Rotation, "first");
+        warnings.push(WarningKind::Audio, "second");
+        let messages: Vec<_> = warnings.snapshot().into_iter().map(|w| w.message).collect();
+        assert_eq!(messages, vec!["first", "second"]);
+    }
+
+    #[test]
+    fn test_new_is_empty() {
+        assert!(Warnings::new().is_empty());
+    }
+}
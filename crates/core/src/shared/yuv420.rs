@@ -0,0 +1,212 @@
+use crate::shared::color_space::{ColorMatrix, ColorRange, ColorSpace};
+
+/// A frame as planar YUV 4:2:0: full-size luma and one chroma sample per
+/// 2×2 block, the layout nearly every video codec encodes.
+///
+/// Planes are tightly packed. Odd sizes round the chroma planes up, so
+/// the last column or row of blocks covers a single pixel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Yuv420 {
+    pub width: u32,
+    pub height: u32,
+    pub y: Vec<u8>,
+    pub u: Vec<u8>,
+    pub v: Vec<u8>,
+}
+
+impl Yuv420 {
+    pub fn chroma_width(&self) -> u32 {
+        self.width.div_ceil(2)
+    }
+
+    pub fn chroma_height(&self) -> u32 {
+        self.height.div_ceil(2)
+    }
+
+    /// Converts packed RGB24 pixels, each chroma sample taken from the
+    /// mean color of its block.
+    pub fn from_rgb(rgb: &[u8], width: u32, height: u32, color: ColorSpace) -> Self {
+        let coefficients = YuvCoefficients::of(color);
+        let (w, h) = (width as usize, height as usize);
+        let pixel = |x: usize, y: usize| {
+            let i = (y * w + x) * 3;
+            [rgb[i] as f32, rgb[i + 1] as f32, rgb[i + 2] as f32]
+        };
+        let mut luma = Vec::with_capacity(w * h);
+        for y in 0..h {
+            for x in 0..w {
+                luma.push(coefficients.luma(pixel(x, y)));
+            }
+        }
+        let (cw, ch) = (w.div_ceil(2), h.div_ceil(2));
+        let mut u = Vec::with_capacity(cw * ch);
+        let mut v = Vec::with_capacity(cw * ch);
+        for by in 0..ch {
+            for bx in 0..cw {
+                let mut sum = [0.0f32; 3];
+                let mut count = 0.0f32;
+                for y in 2 * by..(2 * by + 2).min(h) {
+                    for x in 2 * bx..(2 * bx + 2).min(w) {
+                        let p = pixel(x, y);
+                        sum = [sum[0] + p[0], sum[1] + p[1], sum[2] + p[2]];
+                        count += 1.0;
+                    }
+                }
+                let mean = [sum[0] / count, sum[1] / count, sum[2] / count];
+                let (cb, cr) = coefficients.chroma(mean);
+                u.push(cb);
+                v.push(cr);
+            }
+        }
+        Self {
+            width,
+            height,
+            y: luma,
+            u,
+            v,
+        }
+    }
+
+    /// Converts to packed RGB24, each pixel using its block's chroma.
+    pub fn to_rgb(&self, color: ColorSpace) -> Vec<u8> {
+        let coefficients = YuvCoefficients::of(color);
+        let (w, h) = (self.width as usize, self.height as usize);
+        let cw = self.chroma_width() as usize;
+        let mut rgb = Vec::with_capacity(w * h * 3);
+        for y in 0..h {
+            for x in 0..w {
+                let c = (y / 2) * cw + x / 2;
+                rgb.extend_from_slice(&coefficients.to_rgb(
+                    self.y[y * w + x],
+                    self.u[c],
+                    self.v[c],
+                ));
+            }
+        }
+        rgb
+    }
+}
+
+/// The constants of a color space's YUV↔RGB conversion, as the GPU
+/// shaders take them.
+///
+/// Math is in `f32` and rounds half up, like the shaders, so a GPU and
+/// the CPU agree to within a level.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct YuvCoefficients {
+    /// Red and blue weights of luma; green's is the rest.
+    pub kr: f32,
+    pub kb: f32,
+    /// Code value of black.
+    pub luma_offset: f32,
+    /// Code values from black to white.
+    pub luma_scale: f32,
+    /// Code values spanned by each chroma axis.
+    pub chroma_scale: f32,
+}
+
+impl YuvCoefficients {
+    pub fn of(color: ColorSpace) -> Self {
+        let (kr, kb) = match color.matrix {
+            ColorMatrix::Bt601 => (0.299, 0.114),
+            ColorMatrix::Bt709 => (0.2126, 0.0722),
+        };
+        let (luma_offset, luma_scale, chroma_scale) = match color.range {
+            ColorRange::Limited => (16.0, 219.0, 224.0),
+            ColorRange::Full => (0.0, 255.0, 255.0),
+        };
+        Self {
+            kr,
+            kb,
+            luma_offset,
+            luma_scale,
+            chroma_scale,
+        }
+    }
+
+    fn kg(&self) -> f32 {
+        1.0 - self.kr - self.kb
+    }
+
+    /// Luma code value of an RGB color with channels in 0–255.
+    pub fn luma(&self, rgb: [f32; 3]) -> u8 {
+        let y = self.kr * rgb[0] + self.kg() * rgb[1] + self.kb * rgb[2];
+        to_code(self.luma_offset + y * self.luma_scale / 255.0)
+    }
+
+    /// Cb and Cr code values of an RGB color with channels in 0–255.
+    pub fn chroma(&self, rgb: [f32; 3]) -> (u8, u8) {
+        let y = self.kr * rgb[0] + self.kg() * rgb[1] + self.kb * rgb[2];
+        let cb = (rgb[2] - y) / (2.0 * (1.0 - self.kb));
+        let cr = (rgb[0] - y) / (2.0 * (1.0 - self.kr));
+        let scale = self.chroma_scale / 255.0;
+        (to_code(128.0 + cb * scale), to_code(128.0 + cr * scale))
+    }
+
+    pub fn to_rgb(&self, y: u8, u: u8, v: u8) -> [u8; 3] {
+        let luma = (y as f32 - self.luma_offset) * 255.0 / self.luma_scale;
+        let cb = (u as f32 - 128.0) * 255.0 / self.chroma_scale;
+        let cr = (v as f32 - 128.0) * 255.0 / self.chroma_scale;
+        let r = luma + 2.0 * (1.0 - self.kr) * cr;
+        let b = luma + 2.0 * (1.0 - self.kb) * cb;
+        let g = (luma - self.kr * r - self.kb * b) / self.kg();
+        [to_code(r), to_code(g), to_code(b)]
+    }
+}
+
+fn to_code(value: f32) -> u8 {
+    (value + 0.5).floor().clamp(0.0, 255.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn solid(value: [u8; 3], width: u32, height: u32) -> Vec<u8> {
+        value.repeat((width * height) as usize)
+    }
+
+    #[test]
+    fn test_limited_range_black_and_white() {
+        let color = ColorSpace::new(ColorMatrix::Bt709, ColorRange::Limited);
+        let black = Yuv420::from_rgb(&solid([0, 0, 0], 2, 2), 2, 2, color);
+        let white = Yuv420::from_rgb(&solid([255, 255, 255], 2, 2), 2, 2, color);
+        assert_eq!((black.y[0], black.u[0], black.v[0]), (16, 128, 128));
+        assert_eq!((white.y[0], white.u[0], white.v[0]), (235, 128, 128));
+    }
+
+    #[test]
+    fn test_full_range_spans_all_codes() {
+        let color = ColorSpace::new(ColorMatrix::Bt601, ColorRange::Full);
+        let black = Yuv420::from_rgb(&solid([0, 0, 0], 2, 2), 2, 2, color);
+        let white = Yuv420::from_rgb(&solid([255, 255, 255], 2, 2), 2, 2, color);
+        assert_eq!(black.y[0], 0);
+        assert_eq!(white.y[0], 255);
+    }
+
+    #[rstest]
+    #[case(ColorMatrix::Bt601, ColorRange::Limited)]
+    #[case(ColorMatrix::Bt709, ColorRange::Limited)]
+    #[case(ColorMatrix::Bt709, ColorRange::Full)]
+    fn test_solid_colors_round_trip(#[case] matrix: ColorMatrix, #[case] range: ColorRange) {
+        let color = ColorSpace::new(matrix, range);
+        for value in [[200, 30, 90], [12, 180, 240], [128, 128, 128]] {
+            let yuv = Yuv420::from_rgb(&solid(value, 4, 4), 4, 4, color);
+            for pixel in yuv.to_rgb(color).chunks(3) {
+                for (got, want) in pixel.iter().zip(value) {
+                    assert!(got.abs_diff(want) <= 2, "{pixel:?} vs {value:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_odd_sizes_round_chroma_planes_up() {
+        let color = ColorSpace::default();
+        let yuv = Yuv420::from_rgb(&solid([50, 100, 150], 5, 3), 5, 3, color);
+        assert_eq!((yuv.chroma_width(), yuv.chroma_height()), (3, 2));
+        assert_eq!((yuv.y.len(), yuv.u.len(), yuv.v.len()), (15, 6, 6));
+        assert_eq!(yuv.to_rgb(color).len(), 45);
+    }
+}
@@ -0,0 +1,80 @@
+/// What to do when a frame cannot be decoded.
+///
+/// Damaged field recordings often have a handful of corrupt packets; the
+/// lenient policies, opted into per job, let the rest of the file still be
+/// anonymized. No policy
+/// ever emits undecoded or partially decoded pixels, so a bad frame never
+/// reaches the output unblurred.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DecodeErrorPolicy {
+    /// Abort the job on the first decode error.
+    #[default]
+    Fail,
+    /// Drop the frame. The output gets shorter by one frame per error,
+    /// which can drift passthrough audio slightly.
+    SkipFrame,
+    /// Repeat the last good frame in its place, keeping frame timing and
+    /// audio sync. Falls back to skipping when no frame has decoded yet.
+    DuplicatePrevious,
+}
+
+impl DecodeErrorPolicy {
+    /// Parses `fail`, `skip`, or `duplicate`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "fail" => Some(Self::Fail),
+            "skip" | "skip-frame" => Some(Self::SkipFrame),
+            "duplicate" | "duplicate-previous" => Some(Self::DuplicatePrevious),
+            _ => None,
+        }
+    }
+
+    /// Name accepted by [`parse`](Self::parse), for cache keys and messages.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Fail => "fail",
+            Self::SkipFrame => "skip",
+            Self::DuplicatePrevious => "duplicate",
+        }
+    }
+
+    /// Past-tense description of what happened to the affected frames,
+    /// for warning messages.
+    pub fn outcome(&self) -> &'static str {
+        match self {
+            Self::Fail => "stopped the job",
+            Self::SkipFrame => "were skipped",
+            Self::DuplicatePrevious => "were replaced by the previous frame",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("fail", Some(DecodeErrorPolicy::Fail))]
+    #[case("skip", Some(DecodeErrorPolicy::SkipFrame))]
+    #[case("skip-frame", Some(DecodeErrorPolicy::SkipFrame))]
+    #[case("Duplicate", Some(DecodeErrorPolicy::DuplicatePrevious))]
+    #[case("duplicate-previous", Some(DecodeErrorPolicy::DuplicatePrevious))]
+    #[case("ignore", None)]
+    fn test_parse(#[case] input: &str, #[case] expected: Option<DecodeErrorPolicy>) {
+        assert_eq!(DecodeErrorPolicy::parse(input), expected);
+    }
+
+    #[rstest]
+    #[case(DecodeErrorPolicy::Fail)]
+    #[case(DecodeErrorPolicy::SkipFrame)]
+    #[case(DecodeErrorPolicy::DuplicatePrevious)]
+    fn test_name_parses_back(#[case] policy: DecodeErrorPolicy) {
+        assert_eq!(DecodeErrorPolicy::parse(policy.name()), Some(policy));
+    }
+
+    #[test]
+    fn test_default_fails() {
+        assert_eq!(DecodeErrorPolicy::default(), DecodeErrorPolicy::Fail);
+    }
+}
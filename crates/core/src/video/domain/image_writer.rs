@@ -3,7 +3,9 @@ use std::path::Path;
 use crate::shared::frame::Frame;
 
 /// Writes a single frame to an image file, with optional resizing for thumbnails.
-pub trait ImageWriter: Send {
+///
+/// `Sync` so preview thumbnails can be encoded on several threads at once.
+pub trait ImageWriter: Send + Sync {
     fn write(
         &self,
         path: &Path,
@@ -1,5 +1,8 @@
 pub mod audio_reader;
 pub mod audio_writer;
+pub mod decode_error_policy;
 pub mod image_writer;
+pub mod source_metadata;
+pub mod video_codec;
 pub mod video_reader;
 pub mod video_writer;
@@ -0,0 +1,129 @@
+/// Kind of identifying information a metadata field carries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MetadataCategory {
+    /// GPS coordinates or a place name.
+    Location,
+    /// Camera or phone make, model, lens, or serial number.
+    Device,
+    /// When the file was recorded or last edited.
+    CreationTime,
+    /// Recording or editing software and encoders.
+    Software,
+    /// Anything else, such as titles, comments, or container brands.
+    Other,
+}
+
+impl MetadataCategory {
+    /// Classifies a tag by its key, across the naming schemes of MP4,
+    /// QuickTime (`com.apple.quicktime.*`), Matroska, and EXIF.
+    pub fn of(key: &str) -> Self {
+        let key = key.to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|word| key.contains(word));
+        if has(&["gps", "location", "xyz", "latitude", "longitude"]) {
+            Self::Location
+        } else if has(&["date", "time"]) {
+            Self::CreationTime
+        } else if has(&[
+            "make",
+            "model",
+            "manufacturer",
+            "lens",
+            "serial",
+            "camera",
+            "device",
+        ]) {
+            Self::Device
+        } else if has(&[
+            "encoder",
+            "software",
+            "handler_name",
+            "vendor_id",
+            "writing_app",
+        ]) {
+            Self::Software
+        } else {
+            Self::Other
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Location => "Location",
+            Self::Device => "Device",
+            Self::CreationTime => "Creation time",
+            Self::Software => "Software",
+            Self::Other => "Other",
+        }
+    }
+}
+
+/// One metadata tag found in a source file.
+///
+/// Exports never carry these over: writers start from an empty container
+/// and write only the streams and their own encoder tag. Listing them shows
+/// users exactly what an export removes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MetadataField {
+    /// Where the tag was found: `container`, `video stream 0`, or `image`.
+    pub scope: String,
+    pub key: String,
+    pub value: String,
+    pub category: MetadataCategory,
+}
+
+impl MetadataField {
+    pub fn new(scope: impl Into<String>, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let key = key.into();
+        Self {
+            scope: scope.into(),
+            category: MetadataCategory::of(&key),
+            key,
+            value: value.into(),
+        }
+    }
+}
+
+/// Fields sorted with the most identifying categories first, keeping the
+/// found order within a category.
+pub fn sort_by_category(fields: &mut [MetadataField]) {
+    fields.sort_by_key(|field| field.category);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("location", MetadataCategory::Location)]
+    #[case("com.apple.quicktime.location.ISO6709", MetadataCategory::Location)]
+    #[case("GPSLatitude", MetadataCategory::Location)]
+    #[case("GPSTimeStamp", MetadataCategory::Location)]
+    #[case("creation_time", MetadataCategory::CreationTime)]
+    #[case("com.apple.quicktime.creationdate", MetadataCategory::CreationTime)]
+    #[case("DateTimeOriginal", MetadataCategory::CreationTime)]
+    #[case("com.apple.quicktime.make", MetadataCategory::Device)]
+    #[case("Model", MetadataCategory::Device)]
+    #[case("LensModel", MetadataCategory::Device)]
+    #[case("encoder", MetadataCategory::Software)]
+    #[case("com.apple.quicktime.software", MetadataCategory::Software)]
+    #[case("handler_name", MetadataCategory::Software)]
+    #[case("major_brand", MetadataCategory::Other)]
+    #[case("title", MetadataCategory::Other)]
+    fn test_category_of(#[case] key: &str, #[case] expected: MetadataCategory) {
+        assert_eq!(MetadataCategory::of(key), expected);
+    }
+
+    #[test]
+    fn test_sort_puts_location_first_and_keeps_order() {
+        let mut fields = vec![
+            MetadataField::new("container", "encoder", "Lavf60"),
+            MetadataField::new("container", "title", "a"),
+            MetadataField::new("container", "location", "+48.8+002.2/"),
+            MetadataField::new("container", "comment", "b"),
+        ];
+        sort_by_category(&mut fields);
+        let keys: Vec<&str> = fields.iter().map(|f| f.key.as_str()).collect();
+        assert_eq!(keys, ["location", "encoder", "title", "comment"]);
+    }
+}
@@ -0,0 +1,132 @@
+use std::path::Path;
+
+/// Video codec of an encoded output.
+///
+/// Not every container carries every codec: WebM takes only VP9 and AV1,
+/// and the MPEG-TS segments of an HLS playlist only H.264 and HEVC.
+/// [`VideoCodec::check_container`] rejects combinations before any
+/// encoding starts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum VideoCodec {
+    /// Plays everywhere. Falls back to MPEG-4 Part 2 when the ffmpeg build
+    /// has no H.264 encoder.
+    #[default]
+    H264,
+    /// About half the size of H.264 at the same quality; slower to encode.
+    Hevc,
+    /// Royalty-free, for WebM and browsers.
+    Vp9,
+    /// Smallest files; slowest to encode.
+    Av1,
+}
+
+impl VideoCodec {
+    pub const ALL: [VideoCodec; 4] = [Self::H264, Self::Hevc, Self::Vp9, Self::Av1];
+
+    /// Parses `h264`, `hevc`, `vp9`, or `av1`. `avc` and `h265` are
+    /// accepted as aliases.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "h264" | "h.264" | "avc" => Some(Self::H264),
+            "hevc" | "h265" | "h.265" => Some(Self::Hevc),
+            "vp9" => Some(Self::Vp9),
+            "av1" => Some(Self::Av1),
+            _ => None,
+        }
+    }
+
+    /// Name accepted by [`VideoCodec::parse`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::H264 => "h264",
+            Self::Hevc => "hevc",
+            Self::Vp9 => "vp9",
+            Self::Av1 => "av1",
+        }
+    }
+
+    /// Display name for menus and messages.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::H264 => "H.264",
+            Self::Hevc => "HEVC (H.265)",
+            Self::Vp9 => "VP9",
+            Self::Av1 => "AV1",
+        }
+    }
+
+    /// Output extensions whose container can carry this codec.
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Self::H264 => &["mp4", "m4v", "mov", "mkv", "avi", "m3u8", "mpd"],
+            Self::Hevc => &["mp4", "m4v", "mov", "mkv", "m3u8", "mpd"],
+            Self::Vp9 | Self::Av1 => &["mp4", "m4v", "mkv", "webm", "mpd"],
+        }
+    }
+
+    /// Whether the container picked by `path`'s extension can carry this
+    /// codec. Paths without an extension are left to ffmpeg.
+    pub fn check_container(&self, path: &Path) -> Result<(), String> {
+        let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+            return Ok(());
+        };
+        let ext = ext.to_lowercase();
+        if self.extensions().contains(&ext.as_str()) {
+            return Ok(());
+        }
+        Err(format!(
+            "{} can't be written to .{ext}; use .{}",
+            self.label(),
+            self.extensions().join(", .")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("h264", Some(VideoCodec::H264))]
+    #[case("AVC", Some(VideoCodec::H264))]
+    #[case("hevc", Some(VideoCodec::Hevc))]
+    #[case("h265", Some(VideoCodec::Hevc))]
+    #[case("vp9", Some(VideoCodec::Vp9))]
+    #[case(" av1 ", Some(VideoCodec::Av1))]
+    #[case("vp8", None)]
+    fn test_parse(#[case] input: &str, #[case] expected: Option<VideoCodec>) {
+        assert_eq!(VideoCodec::parse(input), expected);
+    }
+
+    #[test]
+    fn test_name_round_trips() {
+        for codec in VideoCodec::ALL {
+            assert_eq!(VideoCodec::parse(codec.name()), Some(codec));
+        }
+    }
+
+    #[rstest]
+    #[case(VideoCodec::H264, "out.mp4", true)]
+    #[case(VideoCodec::H264, "out.webm", false)]
+    #[case(VideoCodec::Hevc, "out.MOV", true)]
+    #[case(VideoCodec::Hevc, "out.avi", false)]
+    #[case(VideoCodec::Vp9, "out.webm", true)]
+    #[case(VideoCodec::Vp9, "out.mov", false)]
+    #[case(VideoCodec::Av1, "out.mkv", true)]
+    #[case(VideoCodec::Av1, "out.m3u8", false)]
+    #[case(VideoCodec::Av1, "out.mpd", true)]
+    #[case(VideoCodec::Vp9, "out", true)]
+    fn test_check_container(#[case] codec: VideoCodec, #[case] path: &str, #[case] ok: bool) {
+        assert_eq!(codec.check_container(Path::new(path)).is_ok(), ok);
+    }
+
+    #[test]
+    fn test_container_error_lists_alternatives() {
+        let err = VideoCodec::H264
+            .check_container(Path::new("clip.webm"))
+            .unwrap_err();
+        assert!(err.contains("H.264 can't be written to .webm"), "{err}");
+        assert!(err.contains(".mp4"), "{err}");
+    }
+}
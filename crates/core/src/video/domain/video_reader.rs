@@ -12,5 +12,13 @@ pub trait VideoReader: Send {
         &mut self,
     ) -> Box<dyn Iterator<Item = Result<Frame, Box<dyn std::error::Error>>> + '_>;
 
+    /// Positions the reader so the next `frames()` call starts at
+    /// `frame_index`, with that as the first frame's index. Readers that
+    /// can't seek return an error.
+    fn seek(&mut self, frame_index: usize) -> Result<(), Box<dyn std::error::Error>> {
+        let _ = frame_index;
+        Err("This reader does not support seeking".into())
+    }
+
     fn close(&mut self);
 }
@@ -0,0 +1,140 @@
+use std::path::Path;
+
+use crate::shared::frame::Frame;
+use crate::shared::time_map::TimeMap;
+use crate::shared::video_metadata::VideoMetadata;
+use crate::video::domain::video_reader::VideoReader;
+
+/// Decorator that drops every frame whose timestamp falls in one of a
+/// [`TimeMap`]'s cut ranges.
+///
+/// Ranges are half-open (`start <= t < end`) so the removed video matches
+/// the removed audio duration. Surviving frames keep their source index,
+/// which keeps frame times (and detection caches) in source time, while
+/// presentation timestamps are moved onto the output timeline, pulled back
+/// by the cut time before them, so the output plays without gaps. Give the
+/// writer and audio processing the same map so they agree on that
+/// timeline.
+pub struct CutRangeReader {
+    inner: Box<dyn VideoReader>,
+    time_map: TimeMap,
+    fps: f64,
+}
+
+impl CutRangeReader {
+    pub fn new(inner: Box<dyn VideoReader>, time_map: TimeMap) -> Self {
+        Self {
+            inner,
+            time_map,
+            fps: 0.0,
+        }
+    }
+}
+
+fn is_cut(time_map: &TimeMap, index: usize, fps: f64) -> bool {
+    fps > 0.0 && time_map.is_cut(index as f64 / fps)
+}
+
+impl VideoReader for CutRangeReader {
+    fn open(&mut self, path: &Path) -> Result<VideoMetadata, Box<dyn std::error::Error>> {
+        let mut metadata = self.inner.open(path)?;
+        self.fps = metadata.fps;
+        let removed = (0..metadata.total_frames)
+            .filter(|&i| is_cut(&self.time_map, i, self.fps))
+            .count();
+        metadata.total_frames -= removed;
+        Ok(metadata)
+    }
+
+    fn frames(
+        &mut self,
+    ) -> Box<dyn Iterator<Item = Result<Frame, Box<dyn std::error::Error>>> + '_> {
+        let time_map = &self.time_map;
+        let fps = self.fps;
+        Box::new(
+            self.inner
+                .frames()
+                .filter(move |result| match result {
+                    Ok(frame) => !is_cut(time_map, frame.index(), fps),
+                    Err(_) => true,
+                })
+                .map(move |result| {
+                    result.map(|frame| match frame.timestamp() {
+                        Some(t) => frame.with_timestamp(time_map.to_output(t)),
+                        None => frame,
+                    })
+                }),
+        )
+    }
+
+    fn close(&mut self) {
+        self.inner.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::color_space::ColorSpace;
+    use crate::shared::track_time_rules::TimeRange;
+
+    struct StubReader {
+        count: usize,
+    }
+
+    impl VideoReader for StubReader {
+        fn open(&mut self, _: &Path) -> Result<VideoMetadata, Box<dyn std::error::Error>> {
+            Ok(VideoMetadata {
+                width: 2,
+                height: 2,
+                fps: 10.0,
+                total_frames: self.count,
+                codec: String::new(),
+                source_path: None,
+                rotation: 0,
+                color: ColorSpace::default(),
+            })
+        }
+
+        fn frames(
+            &mut self,
+        ) -> Box<dyn Iterator<Item = Result<Frame, Box<dyn std::error::Error>>> + '_> {
+            Box::new((0..self.count).map(|i| Ok(Frame::new(vec![0; 12], 2, 2, 3, i))))
+        }
+
+        fn close(&mut self) {}
+    }
+
+    fn kept_indices(cuts: Vec<TimeRange>) -> (usize, Vec<usize>) {
+        let mut reader = CutRangeReader::new(
+            Box::new(StubReader { count: 20 }),
+            TimeMap::new().with_cuts(&cuts),
+        );
+        let meta = reader.open(Path::new("in.mp4")).unwrap();
+        let kept = reader.frames().map(|f| f.unwrap().index()).collect();
+        (meta.total_frames, kept)
+    }
+
+    #[test]
+    fn test_no_cuts_keeps_every_frame() {
+        let (total, kept) = kept_indices(vec![]);
+        assert_eq!(total, 20);
+        assert_eq!(kept, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_cut_range_is_half_open() {
+        // 10 fps: 0.5s..1.0s covers frames 5-9; frame 10 (1.0s) stays.
+        let (total, kept) = kept_indices(vec![TimeRange::new(0.5, 1.0)]);
+        assert_eq!(total, 15);
+        assert_eq!(kept[..6], [0, 1, 2, 3, 4, 10]);
+    }
+
+    #[test]
+    fn test_multiple_cuts() {
+        let (total, kept) = kept_indices(vec![TimeRange::new(0.0, 0.2), TimeRange::new(1.5, 5.0)]);
+        assert_eq!(total, 13);
+        assert_eq!(kept.first(), Some(&2));
+        assert_eq!(kept.last(), Some(&14));
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::shared::video_metadata::VideoMetadata;
+
+/// Headroom left free on top of the estimate: container overhead, logs,
+/// and whatever else writes to the volume during the export.
+pub const SPACE_MARGIN_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Bits per pixel of H.264 at the default CRF on typical camera footage.
+/// Busy or noisy footage runs higher, which the input's own size covers.
+const BITS_PER_PIXEL: f64 = 0.1;
+
+/// Expected size of one encoded video output: the larger of a bitrate
+/// estimate at the output resolution and the input's own size scaled to
+/// that resolution, since re-encoding rarely shrinks a file much.
+pub fn estimate_output_bytes(
+    metadata: &VideoMetadata,
+    input_bytes: u64,
+    output_height: Option<u32>,
+) -> u64 {
+    let scale = match output_height {
+        Some(height) if height > 0 && height < metadata.height => {
+            height as f64 / metadata.height as f64
+        }
+        _ => 1.0,
+    };
+    let pixels = metadata.total_frames as f64
+        * metadata.width as f64
+        * metadata.height as f64
+        * scale
+        * scale;
+    let encoded = pixels * BITS_PER_PIXEL / 8.0;
+    let scaled_input = input_bytes as f64 * scale * scale;
+    encoded.max(scaled_input).ceil() as u64
+}
+
+/// Free-space check run before an export, so a full disk fails the job up
+/// front with a clear message instead of as an ffmpeg write error partway
+/// through the encode.
+///
+/// Outputs are grouped by folder, and each folder's total plus the margin
+/// must fit in the space free on its volume. With `with_audio_remux`, the
+/// largest output in a folder counts twice, since processed audio is muxed
+/// into a temporary copy that replaces the output afterwards.
+#[derive(Debug, Clone, Default)]
+pub struct DiskSpaceCheck {
+    outputs: Vec<(PathBuf, u64)>,
+    audio_remux: bool,
+}
+
+impl DiskSpaceCheck {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_output(mut self, path: impl Into<PathBuf>, bytes: u64) -> Self {
+        self.outputs.push((path.into(), bytes));
+        self
+    }
+
+    pub fn with_audio_remux(mut self, audio_remux: bool) -> Self {
+        self.audio_remux = audio_remux;
+        self
+    }
+
+    /// Fails with a readable message when a folder lacks room. Folders whose
+    /// free space cannot be read are let through.
+    pub fn run(&self) -> Result<(), String> {
+        self.run_with(available_space)
+    }
+
+    fn run_with(&self, available: impl Fn(&Path) -> io::Result<u64>) -> Result<(), String> {
+        for (dir, needed) in self.needed_per_folder() {
+            match available(&dir) {
+                Ok(free) if free < needed => {
+                    return Err(format!(
+                        "Not enough disk space in {}: the export needs about {}, but only {} is free",
+                        dir.display(),
+                        format_bytes(needed),
+                        format_bytes(free)
+                    ));
+                }
+                Ok(_) => {}
+                Err(e) => log::debug!("Skipping disk space check for {}: {e}", dir.display()),
+            }
+        }
+        Ok(())
+    }
+
+    fn needed_per_folder(&self) -> BTreeMap<PathBuf, u64> {
+        let mut folders: BTreeMap<PathBuf, (u64, u64)> = BTreeMap::new();
+        for (path, bytes) in &self.outputs {
+            let (total, largest) = folders.entry(existing_folder(path)).or_default();
+            *total += bytes;
+            *largest = (*largest).max(*bytes);
+        }
+        folders
+            .into_iter()
+            .map(|(dir, (total, largest))| {
+                let remux = if self.audio_remux { largest } else { 0 };
+                (dir, total + remux + SPACE_MARGIN_BYTES)
+            })
+            .collect()
+    }
+}
+
+fn available_space(dir: &Path) -> io::Result<u64> {
+    fs4::available_space(dir)
+}
+
+/// The output's folder, or its nearest ancestor that exists yet.
+fn existing_folder(output: &Path) -> PathBuf {
+    let mut dir = match output.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    while !dir.exists() {
+        match dir.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => dir = parent.to_path_buf(),
+            _ => return PathBuf::from("."),
+        }
+    }
+    dir
+}
+
+/// Size in the largest fitting binary unit: "512 B", "3.4 MB", "1.2 GB".
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::color_space::ColorSpace;
+    use rstest::rstest;
+
+    const MB: u64 = 1024 * 1024;
+
+    fn metadata(frames: usize, width: u32, height: u32) -> VideoMetadata {
+        VideoMetadata {
+            width,
+            height,
+            fps: 30.0,
+            total_frames: frames,
+            codec: String::new(),
+            source_path: None,
+            rotation: 0,
+            color: ColorSpace::default(),
+        }
+    }
+
+    #[test]
+    fn test_estimate_uses_bitrate_for_small_inputs() {
+        // One minute of 1080p30: 3.7 Gpx at 0.1 bits per pixel.
+        let estimate = estimate_output_bytes(&metadata(1800, 1920, 1080), MB, None);
+        assert_eq!(estimate, 46_656_000);
+    }
+
+    #[test]
+    fn test_estimate_uses_input_size_when_larger() {
+        let estimate = estimate_output_bytes(&metadata(30, 1920, 1080), 500 * MB, None);
+        assert_eq!(estimate, 500 * MB);
+    }
+
+    #[test]
+    fn test_estimate_scales_with_output_height() {
+        let full = estimate_output_bytes(&metadata(1800, 1920, 1080), 0, None);
+        let half = estimate_output_bytes(&metadata(1800, 1920, 1080), 0, Some(540));
+        assert_eq!(half, full / 4);
+        assert_eq!(
+            estimate_output_bytes(&metadata(1800, 1920, 1080), 0, Some(2160)),
+            full
+        );
+    }
+
+    #[test]
+    fn test_check_passes_with_room() {
+        let dir = tempfile::tempdir().unwrap();
+        let check = DiskSpaceCheck::new().with_output(dir.path().join("out.mp4"), 100 * MB);
+        assert!(check.run_with(|_| Ok(200 * MB)).is_ok());
+    }
+
+    #[test]
+    fn test_check_fails_without_room() {
+        let dir = tempfile::tempdir().unwrap();
+        let check = DiskSpaceCheck::new().with_output(dir.path().join("out.mp4"), 100 * MB);
+        let err = check.run_with(|_| Ok(120 * MB)).unwrap_err();
+        assert!(err.contains("Not enough disk space"), "{err}");
+        assert!(err.contains("164.0 MB"), "{err}");
+        assert!(err.contains("120.0 MB"), "{err}");
+    }
+
+    #[test]
+    fn test_outputs_in_one_folder_add_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let check = DiskSpaceCheck::new()
+            .with_output(dir.path().join("out.mp4"), 100 * MB)
+            .with_output(dir.path().join("out_720p.mp4"), 50 * MB);
+        assert!(check.run_with(|_| Ok(200 * MB)).is_err());
+        assert!(check.run_with(|_| Ok(250 * MB)).is_ok());
+    }
+
+    #[test]
+    fn test_audio_remux_counts_largest_output_twice() {
+        let dir = tempfile::tempdir().unwrap();
+        let check = DiskSpaceCheck::new()
+            .with_output(dir.path().join("out.mp4"), 100 * MB)
+            .with_audio_remux(true);
+        assert!(check.run_with(|_| Ok(200 * MB)).is_err());
+        assert!(check.run_with(|_| Ok(300 * MB)).is_ok());
+    }
+
+    #[test]
+    fn test_unreadable_free_space_is_let_through() {
+        let check = DiskSpaceCheck::new().with_output("out.mp4", u64::MAX / 2);
+        assert!(check
+            .run_with(|_| Err(io::Error::new(io::ErrorKind::Unsupported, "no statvfs")))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_missing_output_folder_checks_nearest_existing_ancestor() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("not").join("yet").join("out.mp4");
+        assert_eq!(existing_folder(&output), dir.path());
+        assert_eq!(existing_folder(Path::new("out.mp4")), PathBuf::from("."));
+    }
+
+    #[rstest]
+    #[case(512, "512 B")]
+    #[case(1536, "1.5 KB")]
+    #[case(3 * MB + MB / 2, "3.5 MB")]
+    #[case(5 * 1024 * MB, "5.0 GB")]
+    fn test_format_bytes(#[case] bytes: u64, #[case] expected: &str) {
+        assert_eq!(format_bytes(bytes), expected);
+    }
+}
@@ -0,0 +1,166 @@
+use std::path::{Path, PathBuf};
+
+use crate::shared::frame::Frame;
+use crate::shared::video_metadata::VideoMetadata;
+use crate::video::domain::video_writer::VideoWriter;
+
+/// Sends every frame to a primary writer and any number of extra writers,
+/// each with its own output path.
+///
+/// Used to encode several renditions (e.g. 1080p/720p/480p) from one
+/// detect-and-blur pass: the extra writers usually differ only in output
+/// size or quality.
+pub struct FanOutWriter {
+    primary: Box<dyn VideoWriter>,
+    outputs: Vec<(Box<dyn VideoWriter>, PathBuf)>,
+}
+
+impl FanOutWriter {
+    pub fn new(primary: Box<dyn VideoWriter>) -> Self {
+        Self {
+            primary,
+            outputs: Vec::new(),
+        }
+    }
+
+    pub fn with_output(mut self, writer: Box<dyn VideoWriter>, path: PathBuf) -> Self {
+        self.outputs.push((writer, path));
+        self
+    }
+}
+
+impl VideoWriter for FanOutWriter {
+    fn open(
+        &mut self,
+        path: &Path,
+        metadata: &VideoMetadata,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.primary.open(path, metadata)?;
+        for (writer, path) in &mut self.outputs {
+            writer.open(path, metadata)?;
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, frame: &Frame) -> Result<(), Box<dyn std::error::Error>> {
+        self.primary.write(frame)?;
+        for (writer, _) in &mut self.outputs {
+            writer.write(frame)?;
+        }
+        Ok(())
+    }
+
+    /// Closes every writer even if one fails, returning the first error.
+    fn close(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut result = self.primary.close();
+        for (writer, _) in &mut self.outputs {
+            let closed = writer.close();
+            if result.is_ok() {
+                result = closed;
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::color_space::ColorSpace;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Log {
+        opened: Option<PathBuf>,
+        written: Vec<usize>,
+        closed: bool,
+    }
+
+    struct StubWriter {
+        log: Arc<Mutex<Log>>,
+        fail_close: bool,
+    }
+
+    impl StubWriter {
+        fn new(fail_close: bool) -> (Self, Arc<Mutex<Log>>) {
+            let log = Arc::new(Mutex::new(Log::default()));
+            (
+                Self {
+                    log: log.clone(),
+                    fail_close,
+                },
+                log,
+            )
+        }
+    }
+
+    impl VideoWriter for StubWriter {
+        fn open(
+            &mut self,
+            path: &Path,
+            _: &VideoMetadata,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            self.log.lock().unwrap().opened = Some(path.to_path_buf());
+            Ok(())
+        }
+
+        fn write(&mut self, frame: &Frame) -> Result<(), Box<dyn std::error::Error>> {
+            self.log.lock().unwrap().written.push(frame.index());
+            Ok(())
+        }
+
+        fn close(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+            self.log.lock().unwrap().closed = true;
+            if self.fail_close {
+                return Err("close failed".into());
+            }
+            Ok(())
+        }
+    }
+
+    fn metadata() -> VideoMetadata {
+        VideoMetadata {
+            width: 2,
+            height: 2,
+            fps: 30.0,
+            total_frames: 2,
+            codec: String::new(),
+            source_path: None,
+            rotation: 0,
+            color: ColorSpace::default(),
+        }
+    }
+
+    #[test]
+    fn test_fans_frames_out_to_every_writer() {
+        let (primary, primary_log) = StubWriter::new(false);
+        let (extra, extra_log) = StubWriter::new(false);
+        let mut writer = FanOutWriter::new(Box::new(primary))
+            .with_output(Box::new(extra), PathBuf::from("out_720p.mp4"));
+
+        writer.open(Path::new("out.mp4"), &metadata()).unwrap();
+        for i in 0..2 {
+            writer.write(&Frame::new(vec![0; 12], 2, 2, 3, i)).unwrap();
+        }
+        writer.close().unwrap();
+
+        for (log, path) in [(primary_log, "out.mp4"), (extra_log, "out_720p.mp4")] {
+            let log = log.lock().unwrap();
+            assert_eq!(log.opened.as_deref(), Some(Path::new(path)));
+            assert_eq!(log.written, vec![0, 1]);
+            assert!(log.closed);
+        }
+    }
+
+    #[test]
+    fn test_close_reaches_every_writer_after_failure() {
+        let (primary, _) = StubWriter::new(true);
+        let (extra, extra_log) = StubWriter::new(false);
+        let mut writer = FanOutWriter::new(Box::new(primary))
+            .with_output(Box::new(extra), PathBuf::from("out_480p.mp4"));
+
+        writer.open(Path::new("out.mp4"), &metadata()).unwrap();
+        assert!(writer.close().is_err());
+        assert!(extra_log.lock().unwrap().closed);
+    }
+}
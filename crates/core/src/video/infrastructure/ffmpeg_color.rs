@@ -0,0 +1,255 @@
+//! Color space plumbing shared by [`FfmpegReader`] and [`FfmpegWriter`].
+//!
+//! swscale defaults to BT.601 limited range for every YUV↔RGB conversion,
+//! which shifts the colors of BT.709 (HD) and full-range sources. These
+//! helpers read the source's tags, configure the scaler to match, and tag
+//! encoded output with the same values.
+//!
+//! [`FfmpegReader`]: super::ffmpeg_reader::FfmpegReader
+//! [`FfmpegWriter`]: super::ffmpeg_writer::FfmpegWriter
+
+use std::os::raw::c_int;
+
+use ffmpeg_next::ffi;
+use ffmpeg_next::format::Pixel;
+use ffmpeg_next::util::color;
+use ffmpeg_next::util::frame::video::Video;
+
+use crate::shared::color_space::{ColorMatrix, ColorRange, ColorSpace};
+use crate::shared::yuv420::Yuv420;
+
+/// Color space of a decoded stream. Untagged matrices fall back to
+/// [`ColorSpace::assumed_for`]; `yuvj*` formats are always full range.
+pub fn stream_color_space(
+    space: color::Space,
+    range: color::Range,
+    format: Pixel,
+    width: u32,
+    height: u32,
+) -> ColorSpace {
+    let assumed = ColorSpace::assumed_for(width, height);
+    let matrix = match space {
+        color::Space::BT709 => ColorMatrix::Bt709,
+        color::Space::BT470BG | color::Space::SMPTE170M | color::Space::FCC => ColorMatrix::Bt601,
+        _ => assumed.matrix,
+    };
+    let full = range == color::Range::JPEG
+        || matches!(
+            format,
+            Pixel::YUVJ420P | Pixel::YUVJ422P | Pixel::YUVJ444P | Pixel::YUVJ440P
+        );
+    let range = if full {
+        ColorRange::Full
+    } else {
+        ColorRange::Limited
+    };
+    ColorSpace::new(matrix, range)
+}
+
+/// Makes a YUV→RGB scaler decode with `source`'s matrix and range.
+pub fn configure_yuv_to_rgb(
+    scaler: &mut ffmpeg_next::software::scaling::Context,
+    source: ColorSpace,
+) {
+    set_colorspace_details(scaler, source, is_full(source), true);
+}
+
+/// Makes an RGB→YUV scaler encode with `target`'s matrix and range.
+pub fn configure_rgb_to_yuv(
+    scaler: &mut ffmpeg_next::software::scaling::Context,
+    target: ColorSpace,
+) {
+    set_colorspace_details(scaler, target, true, is_full(target));
+}
+
+/// Tags an encoder (and, through its parameters, the output stream) with
+/// `color` so players decode it the way it was encoded.
+pub fn tag_encoder(encoder: &mut ffmpeg_next::codec::encoder::video::Video, color: ColorSpace) {
+    let (space, primaries, trc) = match color.matrix {
+        ColorMatrix::Bt601 => (
+            ffi::AVColorSpace::AVCOL_SPC_SMPTE170M,
+            ffi::AVColorPrimaries::AVCOL_PRI_SMPTE170M,
+            ffi::AVColorTransferCharacteristic::AVCOL_TRC_SMPTE170M,
+        ),
+        ColorMatrix::Bt709 => (
+            ffi::AVColorSpace::AVCOL_SPC_BT709,
+            ffi::AVColorPrimaries::AVCOL_PRI_BT709,
+            ffi::AVColorTransferCharacteristic::AVCOL_TRC_BT709,
+        ),
+    };
+    let range = match color.range {
+        ColorRange::Limited => ffi::AVColorRange::AVCOL_RANGE_MPEG,
+        ColorRange::Full => ffi::AVColorRange::AVCOL_RANGE_JPEG,
+    };
+    unsafe {
+        let ctx = encoder.as_mut_ptr();
+        (*ctx).colorspace = space;
+        (*ctx).color_primaries = primaries;
+        (*ctx).color_trc = trc;
+        (*ctx).color_range = range;
+    }
+}
+
+/// The planes of a decoded 4:2:0 frame, without row padding, for a
+/// [`PixelConverter`](crate::shared::pixel_converter::PixelConverter).
+/// `None` for other pixel formats.
+pub fn yuv420_planes(frame: &Video) -> Option<Yuv420> {
+    if !matches!(frame.format(), Pixel::YUV420P | Pixel::YUVJ420P) {
+        return None;
+    }
+    let (width, height) = (frame.width(), frame.height());
+    let plane = |index: usize, w: u32, h: u32| {
+        let stride = frame.stride(index);
+        let data = frame.data(index);
+        let w = w as usize;
+        let mut packed = Vec::with_capacity(w * h as usize);
+        for row in 0..h as usize {
+            packed.extend_from_slice(&data[row * stride..row * stride + w]);
+        }
+        packed
+    };
+    let (cw, ch) = (width.div_ceil(2), height.div_ceil(2));
+    Some(Yuv420 {
+        width,
+        height,
+        y: plane(0, width, height),
+        u: plane(1, cw, ch),
+        v: plane(2, cw, ch),
+    })
+}
+
+/// A YUV420P frame holding `yuv`'s planes, respecting stride.
+pub fn yuv420_frame(yuv: &Yuv420) -> Video {
+    let mut frame = Video::new(Pixel::YUV420P, yuv.width, yuv.height);
+    let planes = [
+        (&yuv.y, yuv.width),
+        (&yuv.u, yuv.chroma_width()),
+        (&yuv.v, yuv.chroma_width()),
+    ];
+    for (index, (plane, w)) in planes.into_iter().enumerate() {
+        let stride = frame.stride(index);
+        let data = frame.data_mut(index);
+        let w = w as usize;
+        for (row, src) in plane.chunks(w).enumerate() {
+            data[row * stride..row * stride + w].copy_from_slice(src);
+        }
+    }
+    frame
+}
+
+fn is_full(color: ColorSpace) -> bool {
+    color.range == ColorRange::Full
+}
+
+fn set_colorspace_details(
+    scaler: &mut ffmpeg_next::software::scaling::Context,
+    color: ColorSpace,
+    src_full: bool,
+    dst_full: bool,
+) {
+    let standard = match color.matrix {
+        ColorMatrix::Bt601 => ffi::SWS_CS_ITU601,
+        ColorMatrix::Bt709 => ffi::SWS_CS_ITU709,
+    };
+    // The same table serves both sides: swscale ignores the RGB side's.
+    unsafe {
+        let coefficients = ffi::sws_getCoefficients(standard as c_int);
+        let result = ffi::sws_setColorspaceDetails(
+            scaler.as_mut_ptr(),
+            coefficients,
+            src_full as c_int,
+            coefficients,
+            dst_full as c_int,
+            0,
+            1 << 16,
+            1 << 16,
+        );
+        if result < 0 {
+            log::debug!("swscale rejected color space details for {color:?}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yuv420_planes_round_trip_through_frame() {
+        let yuv = Yuv420 {
+            width: 5,
+            height: 3,
+            y: (0..15).collect(),
+            u: (100..106).collect(),
+            v: (200..206).collect(),
+        };
+        assert_eq!(yuv420_planes(&yuv420_frame(&yuv)), Some(yuv));
+    }
+
+    #[test]
+    fn test_tagged_bt709_is_kept_for_sd() {
+        let color = stream_color_space(
+            color::Space::BT709,
+            color::Range::MPEG,
+            Pixel::YUV420P,
+            640,
+            480,
+        );
+        assert_eq!(
+            color,
+            ColorSpace::new(ColorMatrix::Bt709, ColorRange::Limited)
+        );
+    }
+
+    #[test]
+    fn test_tagged_bt601_is_kept_for_hd() {
+        let color = stream_color_space(
+            color::Space::SMPTE170M,
+            color::Range::Unspecified,
+            Pixel::YUV420P,
+            1920,
+            1080,
+        );
+        assert_eq!(color.matrix, ColorMatrix::Bt601);
+    }
+
+    #[test]
+    fn test_untagged_falls_back_to_size() {
+        let hd = stream_color_space(
+            color::Space::Unspecified,
+            color::Range::Unspecified,
+            Pixel::YUV420P,
+            1920,
+            1080,
+        );
+        let sd = stream_color_space(
+            color::Space::Unspecified,
+            color::Range::Unspecified,
+            Pixel::YUV420P,
+            640,
+            480,
+        );
+        assert_eq!(hd.matrix, ColorMatrix::Bt709);
+        assert_eq!(sd.matrix, ColorMatrix::Bt601);
+    }
+
+    #[test]
+    fn test_full_range_from_tag_or_yuvj_format() {
+        let tagged = stream_color_space(
+            color::Space::BT709,
+            color::Range::JPEG,
+            Pixel::YUV420P,
+            1920,
+            1080,
+        );
+        let yuvj = stream_color_space(
+            color::Space::Unspecified,
+            color::Range::Unspecified,
+            Pixel::YUVJ420P,
+            640,
+            480,
+        );
+        assert_eq!(tagged.range, ColorRange::Full);
+        assert_eq!(yuvj.range, ColorRange::Full);
+    }
+}
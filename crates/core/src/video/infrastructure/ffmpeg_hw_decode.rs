@@ -0,0 +1,119 @@
+//! Hardware decode plumbing for [`FfmpegReader`].
+//!
+//! Opens the platform's decode API as an ffmpeg hardware device and
+//! attaches it to a decoder when the codec supports it. libavcodec then
+//! picks the hardware pixel format on its own; decoded frames live in GPU
+//! memory and are downloaded with [`download`] before conversion to RGB.
+//! Every step falls back to software decode when the hardware says no.
+//!
+//! [`FfmpegReader`]: super::ffmpeg_reader::FfmpegReader
+
+use std::os::raw::c_int;
+use std::ptr;
+
+use ffmpeg_next::ffi;
+use ffmpeg_next::util::frame::video::Video;
+
+/// Device types to try, most capable first.
+#[cfg(target_os = "macos")]
+const DEVICE_TYPES: &[ffi::AVHWDeviceType] = &[ffi::AVHWDeviceType::AV_HWDEVICE_TYPE_VIDEOTOOLBOX];
+#[cfg(target_os = "windows")]
+const DEVICE_TYPES: &[ffi::AVHWDeviceType] = &[
+    ffi::AVHWDeviceType::AV_HWDEVICE_TYPE_D3D11VA,
+    ffi::AVHWDeviceType::AV_HWDEVICE_TYPE_DXVA2,
+];
+#[cfg(target_os = "linux")]
+const DEVICE_TYPES: &[ffi::AVHWDeviceType] = &[ffi::AVHWDeviceType::AV_HWDEVICE_TYPE_VAAPI];
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+const DEVICE_TYPES: &[ffi::AVHWDeviceType] = &[];
+
+/// An opened hardware decode device. Decoders hold their own reference,
+/// so it can be dropped while they are still in use.
+pub struct HwDevice {
+    context: *mut ffi::AVBufferRef,
+    kind: ffi::AVHWDeviceType,
+}
+
+impl HwDevice {
+    /// Opens the first decode API this platform offers, or `None` when
+    /// there is no usable device (no GPU, no driver, a headless session).
+    pub fn open() -> Option<Self> {
+        DEVICE_TYPES.iter().find_map(|&kind| {
+            let mut context = ptr::null_mut();
+            let result = unsafe {
+                ffi::av_hwdevice_ctx_create(&mut context, kind, ptr::null(), ptr::null_mut(), 0)
+            };
+            if result < 0 || context.is_null() {
+                log::debug!("Hardware decode device {} unavailable", device_name(kind));
+                return None;
+            }
+            Some(Self { context, kind })
+        })
+    }
+
+    /// Short name of the decode API, such as `vaapi`.
+    pub fn name(&self) -> &'static str {
+        device_name(self.kind)
+    }
+
+    /// Attaches the device to a decoder context that hasn't been opened
+    /// yet. Returns `false`, leaving the context untouched, when the codec
+    /// can't be decoded on this device.
+    pub fn attach(&self, context: &mut ffmpeg_next::codec::context::Context) -> bool {
+        let Some(codec) = ffmpeg_next::decoder::find(context.id()) else {
+            return false;
+        };
+        if !supports(codec.as_ptr(), self.kind) {
+            return false;
+        }
+        unsafe {
+            let raw = context.as_mut_ptr();
+            (*raw).hw_device_ctx = ffi::av_buffer_ref(self.context);
+            !(*raw).hw_device_ctx.is_null()
+        }
+    }
+}
+
+impl Drop for HwDevice {
+    fn drop(&mut self) {
+        unsafe { ffi::av_buffer_unref(&mut self.context) };
+    }
+}
+
+/// Copies a frame decoded into GPU memory back to system memory, keeping
+/// its timestamps. Software frames are returned as they are.
+pub fn download(frame: Video) -> Result<Video, ffmpeg_next::Error> {
+    unsafe {
+        if (*frame.as_ptr()).hw_frames_ctx.is_null() {
+            return Ok(frame);
+        }
+        let mut software = Video::empty();
+        let result = ffi::av_hwframe_transfer_data(software.as_mut_ptr(), frame.as_ptr(), 0);
+        if result < 0 {
+            return Err(ffmpeg_next::Error::from(result));
+        }
+        ffi::av_frame_copy_props(software.as_mut_ptr(), frame.as_ptr());
+        Ok(software)
+    }
+}
+
+/// Whether `codec` can decode through a device of type `kind`.
+fn supports(codec: *const ffi::AVCodec, kind: ffi::AVHWDeviceType) -> bool {
+    (0..)
+        .map(|i| unsafe { ffi::avcodec_get_hw_config(codec, i) })
+        .take_while(|config| !config.is_null())
+        .any(|config| unsafe {
+            (*config).device_type == kind
+                && (*config).methods & ffi::AV_CODEC_HW_CONFIG_METHOD_HW_DEVICE_CTX as c_int != 0
+        })
+}
+
+fn device_name(kind: ffi::AVHWDeviceType) -> &'static str {
+    match kind {
+        ffi::AVHWDeviceType::AV_HWDEVICE_TYPE_VIDEOTOOLBOX => "videotoolbox",
+        ffi::AVHWDeviceType::AV_HWDEVICE_TYPE_D3D11VA => "d3d11va",
+        ffi::AVHWDeviceType::AV_HWDEVICE_TYPE_DXVA2 => "dxva2",
+        ffi::AVHWDeviceType::AV_HWDEVICE_TYPE_VAAPI => "vaapi",
+        _ => "hardware",
+    }
+}
@@ -1,16 +1,57 @@
 use std::path::Path;
+use std::sync::Arc;
 
+use crate::shared::color_space::ColorSpace;
 use crate::shared::frame::Frame;
+use crate::shared::pixel_converter::PixelConverter;
 use crate::shared::video_metadata::VideoMetadata;
+use crate::shared::warnings::{WarningKind, Warnings};
+use crate::video::domain::decode_error_policy::DecodeErrorPolicy;
 use crate::video::domain::video_reader::VideoReader;
+use crate::video::infrastructure::ffmpeg_color::{
+    configure_yuv_to_rgb, stream_color_space, yuv420_planes,
+};
+use crate::video::infrastructure::ffmpeg_hw_decode::{download, HwDevice};
 
 /// Decodes video frames via ffmpeg-next (libavformat + libavcodec).
 ///
-/// Converts each decoded frame to RGB24 and wraps it in a [`Frame`].
+/// Converts each decoded frame to RGB24 with the stream's own matrix and
+/// range (see [`VideoMetadata::color`]) and wraps it in a [`Frame`].
+///
+/// With [`with_bake_rotation`](Self::with_bake_rotation), frames are turned
+/// upright as they are decoded and the reported metadata carries the
+/// rotated dimensions with `rotation: 0`, so downstream detection sees
+/// upright faces and writers emit no display matrix.
+///
+/// With [`with_hw_accel`](Self::with_hw_accel), decoding runs on the
+/// platform's video decode hardware where the codec allows it, falling
+/// back to software otherwise.
+///
+/// With [`with_pixel_converter`](Self::with_pixel_converter), 4:2:0 frames
+/// are converted to RGB by the converter instead of swscale.
+///
+/// [`seek`](VideoReader::seek) jumps to the nearest keyframe at or before
+/// the target and decodes forward, dropping frames until the target, so the
+/// first frame yielded is exactly the one requested.
+///
+/// Frames carry their presentation time relative to the stream start (see
+/// [`Frame::timestamp`]), so variable frame rate recordings keep their
+/// timing through the writer.
 pub struct FfmpegReader {
     input_ctx: Option<ffmpeg_next::format::context::Input>,
     video_stream_index: usize,
     metadata: Option<VideoMetadata>,
+    bake_rotation: bool,
+    baked_rotation: i32,
+    decode_errors: DecodeErrorPolicy,
+    warnings: Warnings,
+    /// Frame the next `frames()` call starts at, after a `seek`.
+    seek_target: Option<usize>,
+    hw_accel: bool,
+    /// Opened on the first `open` with hardware decode on; `None` when the
+    /// platform has no usable device.
+    hw_device: Option<HwDevice>,
+    converter: Option<Arc<dyn PixelConverter>>,
 }
 
 // Safety: FfmpegReader is only used from a single thread at a time.
@@ -23,8 +64,52 @@ impl FfmpegReader {
             input_ctx: None,
             video_stream_index: 0,
             metadata: None,
+            bake_rotation: false,
+            baked_rotation: 0,
+            decode_errors: DecodeErrorPolicy::default(),
+            warnings: Warnings::new(),
+            seek_target: None,
+            hw_accel: false,
+            hw_device: None,
+            converter: None,
         }
     }
+
+    /// Physically rotates decoded frames by the stream's rotation metadata.
+    pub fn with_bake_rotation(mut self, bake: bool) -> Self {
+        self.bake_rotation = bake;
+        self
+    }
+
+    /// How packets the decoder rejects are handled. Defaults to skipping
+    /// them.
+    pub fn with_decode_error_policy(mut self, policy: DecodeErrorPolicy) -> Self {
+        self.decode_errors = policy;
+        self
+    }
+
+    /// Decodes on the GPU's video engine (VideoToolbox on macOS,
+    /// D3D11VA or DXVA2 on Windows, VAAPI on Linux) when one is present and
+    /// supports the codec, and in software otherwise. Off by default.
+    pub fn with_hw_accel(mut self, hw_accel: bool) -> Self {
+        self.hw_accel = hw_accel;
+        self
+    }
+
+    /// Converts YUV 4:2:0 frames, the format of nearly all video, to RGB
+    /// with `converter`, such as a GPU. Other formats still go through
+    /// swscale.
+    pub fn with_pixel_converter(mut self, converter: Arc<dyn PixelConverter>) -> Self {
+        self.converter = Some(converter);
+        self
+    }
+
+    /// Reports unusable rotation metadata and undecodable packets to
+    /// `warnings`.
+    pub fn with_warnings(mut self, warnings: Warnings) -> Self {
+        self.warnings = warnings;
+        self
+    }
 }
 
 impl Default for FfmpegReader {
@@ -33,12 +118,15 @@ impl Default for FfmpegReader {
     }
 }
 
-impl VideoReader for FfmpegReader {
-    fn open(&mut self, path: &Path) -> Result<VideoMetadata, Box<dyn std::error::Error>> {
-        ffmpeg_next::init()?;
-
-        let ictx = ffmpeg_next::format::input(path)?;
-
+impl FfmpegReader {
+    /// Takes over an already opened input, such as a capture device, and
+    /// reads its best video stream. `source_path` becomes the metadata's
+    /// source path.
+    pub(crate) fn open_input(
+        &mut self,
+        ictx: ffmpeg_next::format::context::Input,
+        source_path: Option<&Path>,
+    ) -> Result<VideoMetadata, Box<dyn std::error::Error>> {
         let stream = ictx
             .streams()
             .best(ffmpeg_next::media::Type::Video)
@@ -55,18 +143,56 @@ impl VideoReader for FfmpegReader {
             0.0
         };
 
-        let metadata = VideoMetadata {
+        let mut metadata = VideoMetadata {
             width: decoder.width(),
             height: decoder.height(),
             fps,
-            total_frames: stream.frames() as usize,
+            total_frames: total_frames(stream.frames(), stream_duration(&ictx, &stream), fps),
             codec: decoder
                 .codec()
                 .map(|c| c.name().to_string())
                 .unwrap_or_default(),
-            source_path: Some(path.to_path_buf()),
-            rotation: extract_rotation(&stream),
+            source_path: source_path.map(Path::to_path_buf),
+            rotation: 0,
+            color: stream_color_space(
+                decoder.color_space(),
+                decoder.color_range(),
+                decoder.format(),
+                decoder.width(),
+                decoder.height(),
+            ),
         };
+        if let Some(angle) = stream_rotation_angle(&stream) {
+            metadata.rotation = normalize_rotation(angle);
+            if angle.rem_euclid(90) != 0 {
+                self.warnings.push(
+                    WarningKind::Rotation,
+                    format!(
+                        "Rotation metadata of {angle}\u{b0} is not a multiple of 90\u{b0}; \
+                         treated as {}\u{b0}",
+                        metadata.rotation
+                    ),
+                );
+            }
+        }
+
+        if self.hw_accel && self.hw_device.is_none() {
+            self.hw_device = HwDevice::open();
+            match &self.hw_device {
+                Some(device) => log::info!("Hardware decoding with {}", device.name()),
+                None => log::info!("No hardware decoder available; decoding in software"),
+            }
+        }
+
+        self.baked_rotation = 0;
+        self.seek_target = None;
+        if self.bake_rotation && metadata.rotation != 0 {
+            self.baked_rotation = metadata.rotation;
+            if metadata.rotation % 180 != 0 {
+                std::mem::swap(&mut metadata.width, &mut metadata.height);
+            }
+            metadata.rotation = 0;
+        }
 
         self.video_stream_index = video_stream_index;
         self.metadata = Some(metadata.clone());
@@ -74,6 +200,14 @@ impl VideoReader for FfmpegReader {
 
         Ok(metadata)
     }
+}
+
+impl VideoReader for FfmpegReader {
+    fn open(&mut self, path: &Path) -> Result<VideoMetadata, Box<dyn std::error::Error>> {
+        ffmpeg_next::init()?;
+        let ictx = ffmpeg_next::format::input(path)?;
+        self.open_input(ictx, Some(path))
+    }
 
     fn frames(
         &mut self,
@@ -86,40 +220,157 @@ impl VideoReader for FfmpegReader {
             .streams()
             .best(ffmpeg_next::media::Type::Video)
             .unwrap();
-        let codec_ctx =
-            ffmpeg_next::codec::context::Context::from_parameters(stream.parameters()).unwrap();
-        let decoder = codec_ctx.decoder().video().unwrap();
+        let decoder = match open_decoder(&stream, self.hw_device.as_ref()) {
+            Ok(decoder) => decoder,
+            Err(e) => return Box::new(std::iter::once(Err(e.into()))),
+        };
+        let time_base = f64::from(stream.time_base());
+        let start_time = stream_start_time(&stream);
+        let seek = self.seek_target.take().map(|frame| SeekTarget {
+            frame,
+            time_base,
+            start_time,
+            fps: self.metadata.as_ref().map_or(0.0, |m| m.fps),
+        });
 
         let width = decoder.width();
         let height = decoder.height();
-
-        let scaler = ffmpeg_next::software::scaling::Context::get(
-            decoder.format(),
-            width,
-            height,
-            ffmpeg_next::format::Pixel::RGB24,
-            width,
-            height,
-            ffmpeg_next::software::scaling::Flags::BILINEAR,
-        )
-        .unwrap();
+        let color = self.metadata.as_ref().map(|m| m.color).unwrap_or_default();
 
         Box::new(FfmpegFrameIter {
             ictx,
             decoder,
-            scaler,
+            scaler: None,
+            converter: self.converter.clone(),
+            color,
             width,
             height,
             video_stream_index: self.video_stream_index,
-            frame_index: 0,
+            rotation: self.baked_rotation,
+            time_base,
+            start_time,
+            frame_index: seek.as_ref().map_or(0, |s| s.frame),
+            seek,
             flushing: false,
             done: false,
+            policy: self.decode_errors,
+            previous: None,
+            decode_errors: 0,
+            warnings: self.warnings.clone(),
         })
     }
 
+    fn seek(&mut self, frame_index: usize) -> Result<(), Box<dyn std::error::Error>> {
+        let fps = self
+            .metadata
+            .as_ref()
+            .map(|m| m.fps)
+            .ok_or("FfmpegReader: not opened")?;
+        if fps <= 0.0 {
+            return Err("FfmpegReader: cannot seek a stream without a frame rate".into());
+        }
+        let ictx = self.input_ctx.as_mut().ok_or("FfmpegReader: not opened")?;
+        let stream = ictx
+            .stream(self.video_stream_index)
+            .ok_or("FfmpegReader: video stream missing")?;
+        let time_base = f64::from(stream.time_base());
+        let target = stream_start_time(&stream) as f64 * time_base + frame_index as f64 / fps;
+
+        // Stream -1 seeks in AV_TIME_BASE (microsecond) units. Capping the
+        // range at the target lands on the keyframe at or before it.
+        let micros = (target * 1_000_000.0).round() as i64;
+        ictx.seek(micros, ..micros)?;
+        self.seek_target = Some(frame_index);
+        Ok(())
+    }
+
     fn close(&mut self) {
         self.input_ctx = None;
         self.metadata = None;
+        self.baked_rotation = 0;
+        self.seek_target = None;
+    }
+}
+
+/// Opens a decoder for `stream`, on `device` when it can take the codec
+/// and in software otherwise.
+fn open_decoder(
+    stream: &ffmpeg_next::format::stream::Stream,
+    device: Option<&HwDevice>,
+) -> Result<ffmpeg_next::decoder::Video, ffmpeg_next::Error> {
+    if let Some(device) = device {
+        let mut context =
+            ffmpeg_next::codec::context::Context::from_parameters(stream.parameters())?;
+        if device.attach(&mut context) {
+            match context.decoder().video() {
+                Ok(decoder) => return Ok(decoder),
+                Err(e) => log::info!(
+                    "{} decoder failed to start ({e}); decoding in software",
+                    device.name()
+                ),
+            }
+        } else {
+            log::debug!(
+                "{} can't decode this codec; decoding in software",
+                device.name()
+            );
+        }
+    }
+    ffmpeg_next::codec::context::Context::from_parameters(stream.parameters())?
+        .decoder()
+        .video()
+}
+
+/// Where decoding resumes after a seek, and what is needed to tell which
+/// frame a decoded timestamp belongs to.
+struct SeekTarget {
+    frame: usize,
+    time_base: f64,
+    start_time: i64,
+    fps: f64,
+}
+
+impl SeekTarget {
+    /// Whether a frame decoded at `timestamp` comes before the target.
+    /// Frames without a timestamp are kept, since their position is unknown.
+    fn is_before(&self, timestamp: Option<i64>) -> bool {
+        let Some(ts) = timestamp else {
+            return false;
+        };
+        let index = ((ts - self.start_time) as f64 * self.time_base * self.fps).round();
+        index < self.frame as f64
+    }
+}
+
+/// Frame count from the container, or from the duration when the container
+/// doesn't store one, as for long MKV and MPEG-TS recordings.
+fn total_frames(frames: i64, duration: Option<f64>, fps: f64) -> usize {
+    if frames > 0 {
+        return usize::try_from(frames).unwrap_or(usize::MAX);
+    }
+    match duration {
+        Some(seconds) if fps > 0.0 => (seconds * fps).round() as usize,
+        _ => 0,
+    }
+}
+
+/// Duration in seconds of the stream, falling back to the container's.
+fn stream_duration(
+    ictx: &ffmpeg_next::format::context::Input,
+    stream: &ffmpeg_next::format::stream::Stream,
+) -> Option<f64> {
+    if stream.duration() > 0 {
+        return Some(stream.duration() as f64 * f64::from(stream.time_base()));
+    }
+    // The container's duration is in AV_TIME_BASE (microsecond) units.
+    (ictx.duration() > 0).then(|| ictx.duration() as f64 / 1_000_000.0)
+}
+
+/// The stream's first timestamp, or 0 when the container doesn't say.
+fn stream_start_time(stream: &ffmpeg_next::format::stream::Stream) -> i64 {
+    match stream.start_time() {
+        i64::MIN => 0,
+        start => start,
     }
 }
 
@@ -128,30 +379,147 @@ impl VideoReader for FfmpegReader {
 struct FfmpegFrameIter<'a> {
     ictx: &'a mut ffmpeg_next::format::context::Input,
     decoder: ffmpeg_next::decoder::Video,
-    scaler: ffmpeg_next::software::scaling::Context,
+    /// Built for the first frame's pixel format, which for hardware decode
+    /// is only known once a frame has been downloaded.
+    scaler: Option<ffmpeg_next::software::scaling::Context>,
+    converter: Option<Arc<dyn PixelConverter>>,
+    color: ColorSpace,
     width: u32,
     height: u32,
     video_stream_index: usize,
+    /// Clockwise degrees applied to each frame (0 when not baking).
+    rotation: i32,
+    time_base: f64,
+    start_time: i64,
     frame_index: usize,
+    /// Set after a seek until decoding reaches the target frame.
+    seek: Option<SeekTarget>,
     flushing: bool,
     done: bool,
+    policy: DecodeErrorPolicy,
+    /// Last good frame, kept only for [`DecodeErrorPolicy::DuplicatePrevious`].
+    previous: Option<Frame>,
+    /// Decode errors tolerated so far; reported once decoding ends.
+    decode_errors: usize,
+    warnings: Warnings,
 }
 
 impl FfmpegFrameIter<'_> {
+    fn finish(&mut self) {
+        self.done = true;
+        if self.decode_errors > 0 {
+            self.warnings.push(
+                WarningKind::Decode,
+                format!(
+                    "{} frame(s) had decode errors and {}",
+                    self.decode_errors,
+                    self.policy.outcome()
+                ),
+            );
+            self.decode_errors = 0;
+        }
+    }
+
+    /// Applies the decode error policy. Returns the item to yield, or
+    /// `None` to carry on decoding.
+    fn on_decode_error(
+        &mut self,
+        error: &dyn std::fmt::Display,
+    ) -> Option<Result<Frame, Box<dyn std::error::Error>>> {
+        if self.policy == DecodeErrorPolicy::Fail {
+            self.done = true;
+            return Some(Err(format!(
+                "Failed to decode video near frame {}: {error}",
+                self.frame_index
+            )
+            .into()));
+        }
+        self.decode_errors += 1;
+        let previous = self.previous.as_ref()?;
+        let frame = previous.clone().with_index(self.frame_index);
+        self.frame_index += 1;
+        Some(Ok(frame))
+    }
+
+    /// Packed RGB pixels of `decoded`, through the converter when it
+    /// takes the frame's format.
+    fn rgb_pixels(
+        &mut self,
+        decoded: &ffmpeg_next::util::frame::video::Video,
+    ) -> Result<Vec<u8>, ffmpeg_next::Error> {
+        if let Some(converter) = &self.converter {
+            if (decoded.width(), decoded.height()) == (self.width, self.height) {
+                if let Some(yuv) = yuv420_planes(decoded) {
+                    return Ok(converter.yuv420_to_rgb(&yuv, self.color));
+                }
+            }
+        }
+        let rgb_frame = self.to_rgb(decoded)?;
+        Ok(extract_rgb_pixels(&rgb_frame, self.width, self.height))
+    }
+
+    fn to_rgb(
+        &mut self,
+        decoded: &ffmpeg_next::util::frame::video::Video,
+    ) -> Result<ffmpeg_next::util::frame::video::Video, ffmpeg_next::Error> {
+        let stale = self
+            .scaler
+            .as_ref()
+            .map_or(true, |s| s.input().format != decoded.format());
+        if stale {
+            let mut scaler = ffmpeg_next::software::scaling::Context::get(
+                decoded.format(),
+                self.width,
+                self.height,
+                ffmpeg_next::format::Pixel::RGB24,
+                self.width,
+                self.height,
+                ffmpeg_next::software::scaling::Flags::BILINEAR,
+            )?;
+            configure_yuv_to_rgb(&mut scaler, self.color);
+            self.scaler = Some(scaler);
+        }
+        let mut rgb_frame = ffmpeg_next::util::frame::video::Video::empty();
+        if let Some(scaler) = self.scaler.as_mut() {
+            scaler.run(decoded, &mut rgb_frame)?;
+        }
+        Ok(rgb_frame)
+    }
+
     fn try_receive(&mut self) -> Option<Result<Frame, Box<dyn std::error::Error>>> {
-        let mut decoded = ffmpeg_next::util::frame::video::Video::empty();
-        if self.decoder.receive_frame(&mut decoded).is_ok() {
-            let mut rgb_frame = ffmpeg_next::util::frame::video::Video::empty();
-            if let Err(e) = self.scaler.run(&decoded, &mut rgb_frame) {
-                return Some(Err(Box::new(e)));
+        loop {
+            let mut decoded = ffmpeg_next::util::frame::video::Video::empty();
+            if self.decoder.receive_frame(&mut decoded).is_err() {
+                return None;
+            }
+            if let Some(seek) = &self.seek {
+                if seek.is_before(decoded.timestamp()) {
+                    continue;
+                }
+                self.seek = None;
             }
 
-            let pixels = extract_rgb_pixels(&rgb_frame, self.width, self.height);
-            let frame = Frame::new(pixels, self.width, self.height, 3, self.frame_index);
+            let timestamp = decoded.timestamp();
+            let pixels = match download(decoded).and_then(|d| self.rgb_pixels(&d)) {
+                Ok(pixels) => pixels,
+                Err(e) => match self.on_decode_error(&e) {
+                    Some(result) => return Some(result),
+                    None => continue,
+                },
+            };
+
+            let mut frame = Frame::new(pixels, self.width, self.height, 3, self.frame_index);
+            if let Some(ts) = timestamp {
+                frame = frame.with_timestamp((ts - self.start_time) as f64 * self.time_base);
+            }
+            if self.rotation != 0 {
+                frame = frame.rotated(self.rotation);
+            }
             self.frame_index += 1;
-            Some(Ok(frame))
-        } else {
-            None
+            if self.policy == DecodeErrorPolicy::DuplicatePrevious {
+                self.previous = Some(frame.clone());
+            }
+            return Some(Ok(frame));
         }
     }
 }
@@ -169,7 +537,7 @@ impl Iterator for FfmpegFrameIter<'_> {
         }
 
         if self.flushing {
-            self.done = true;
+            self.finish();
             return None;
         }
 
@@ -180,7 +548,7 @@ impl Iterator for FfmpegFrameIter<'_> {
                 if let Some(result) = self.try_receive() {
                     return Some(result);
                 }
-                self.done = true;
+                self.finish();
                 return None;
             };
 
@@ -188,8 +556,11 @@ impl Iterator for FfmpegFrameIter<'_> {
                 continue;
             }
 
-            if self.decoder.send_packet(&packet).is_err() {
-                continue;
+            if let Err(e) = self.decoder.send_packet(&packet) {
+                match self.on_decode_error(&e) {
+                    Some(result) => return Some(result),
+                    None => continue,
+                }
             }
 
             if let Some(result) = self.try_receive() {
@@ -199,39 +570,38 @@ impl Iterator for FfmpegFrameIter<'_> {
     }
 }
 
-/// Extracts the rotation angle from a video stream.
+/// Extracts the raw clockwise rotation angle in degrees from a video stream.
 ///
 /// Tries stream side data (DisplayMatrix) first, then falls back to the
-/// `"rotate"` metadata tag. Returns 0, 90, 180, or 270.
-fn extract_rotation(stream: &ffmpeg_next::format::stream::Stream) -> i32 {
+/// `"rotate"` metadata tag, which older muxers write in the same clockwise
+/// sense. Pass the result through [`normalize_rotation`] to get 0, 90, 180,
+/// or 270.
+fn stream_rotation_angle(stream: &ffmpeg_next::format::stream::Stream) -> Option<i32> {
     // Try DisplayMatrix side data first
     for side_data in stream.side_data() {
         if side_data.kind() == ffmpeg_next::codec::packet::side_data::Type::DisplayMatrix {
             let data = side_data.data();
             if let Some(angle) = parse_display_matrix(data) {
-                return normalize_rotation(angle);
+                return Some(angle);
             }
         }
     }
 
     // Fallback: check stream metadata for "rotate" tag
-    if let Some(rotate_str) = stream.metadata().get("rotate") {
-        if let Ok(angle) = rotate_str.parse::<i32>() {
-            return normalize_rotation(angle);
-        }
-    }
-
-    0
+    stream
+        .metadata()
+        .get("rotate")
+        .and_then(|rotate_str| rotate_str.parse::<i32>().ok())
 }
 
 /// Parses a 3x3 display matrix (9 x i32, 16.16 fixed-point) to extract
-/// the rotation angle in degrees.
+/// the clockwise turn, in degrees, that shows the frame upright.
 ///
 /// The matrix is stored as 9 consecutive i32 values in native byte order
-/// (as returned by the ffmpeg API). Rotation is derived from
-/// atan2(matrix[1], matrix[0]) which gives the angle of the first row vector.
-/// The result is negated because the display matrix represents the
-/// transformation needed to display correctly (inverse of actual rotation).
+/// (as returned by the ffmpeg API). ffmpeg's `av_display_rotation_get`
+/// returns -atan2(matrix[1], matrix[0]), the counter-clockwise angle, so
+/// the clockwise turn is atan2(matrix[1], matrix[0]) itself. A phone held
+/// upright records matrix[1] = 1, i.e. 90.
 fn parse_display_matrix(data: &[u8]) -> Option<i32> {
     if data.len() < 36 {
         return None;
@@ -241,7 +611,7 @@ fn parse_display_matrix(data: &[u8]) -> Option<i32> {
     let m10 = i32::from_ne_bytes(data[4..8].try_into().ok()?) as f64 / 65536.0;
 
     let angle_rad = m10.atan2(m00);
-    let angle_deg = -angle_rad.to_degrees().round() as i32;
+    let angle_deg = angle_rad.to_degrees().round() as i32;
 
     Some(angle_deg)
 }
@@ -283,9 +653,26 @@ fn extract_rgb_pixels(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shared::letterbox::Letterbox;
+    use crate::shared::yuv420::Yuv420;
     use std::path::PathBuf;
 
     fn create_test_video(path: &Path, num_frames: usize, width: u32, height: u32, fps: f64) {
+        create_test_video_with(path, num_frames, width, height, fps, |i, _, _| {
+            ((i * 40) % 256) as u8
+        });
+    }
+
+    /// Encodes frames whose gray level at each pixel is `pixel(frame,
+    /// column, row)`.
+    fn create_test_video_with(
+        path: &Path,
+        num_frames: usize,
+        width: u32,
+        height: u32,
+        fps: f64,
+        pixel: impl Fn(usize, usize, usize) -> u8,
+    ) {
         ffmpeg_next::init().unwrap();
 
         let mut octx = ffmpeg_next::format::output(path).unwrap();
@@ -341,9 +728,9 @@ mod tests {
             );
             let stride = rgb_frame.stride(0);
             let data = rgb_frame.data_mut(0);
-            let value = ((i * 40) % 256) as u8;
             for row in 0..height as usize {
                 for col in 0..width as usize {
+                    let value = pixel(i, col, row);
                     let offset = row * stride + col * 3;
                     data[offset] = value;
                     data[offset + 1] = value;
@@ -431,6 +818,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_frames_carry_presentation_timestamps() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = test_video_path(dir.path());
+        create_test_video(&path, 5, 160, 120, 30.0);
+
+        let mut reader = FfmpegReader::new();
+        reader.open(&path).unwrap();
+
+        let frames: Vec<_> = reader.frames().map(|f| f.unwrap()).collect();
+        for (i, frame) in frames.iter().enumerate() {
+            let timestamp = frame.timestamp().expect("decoded frames have a timestamp");
+            assert!((timestamp - i as f64 / 30.0).abs() < 1e-3);
+        }
+    }
+
     #[test]
     fn test_frames_are_3_channel() {
         let dir = tempfile::tempdir().unwrap();
@@ -445,6 +848,78 @@ mod tests {
         assert_eq!(frame.data().len(), (160 * 120 * 3) as usize);
     }
 
+    #[test]
+    fn test_hw_accel_decodes_same_frames() {
+        // Uses the hardware decoder where one is present and falls back to
+        // software elsewhere; either way the frames must match.
+        let dir = tempfile::tempdir().unwrap();
+        let path = test_video_path(dir.path());
+        create_test_video(&path, 5, 160, 120, 30.0);
+
+        let mut software = FfmpegReader::new();
+        software.open(&path).unwrap();
+        let expected: Vec<Frame> = software.frames().map(|f| f.unwrap()).collect();
+
+        let mut hardware = FfmpegReader::new().with_hw_accel(true);
+        hardware.open(&path).unwrap();
+        let frames: Vec<Frame> = hardware.frames().map(|f| f.unwrap()).collect();
+        assert_eq!(frames.len(), expected.len());
+        for (frame, expected) in frames.iter().zip(&expected) {
+            assert_eq!(frame.index(), expected.index());
+            let worst = frame
+                .data()
+                .iter()
+                .zip(expected.data())
+                .map(|(a, b)| a.abs_diff(*b))
+                .max()
+                .unwrap();
+            assert!(worst <= 4, "frame {} differs by {worst}", frame.index());
+        }
+    }
+
+    /// The CPU reference of a GPU converter.
+    struct CpuConverter;
+
+    impl PixelConverter for CpuConverter {
+        fn yuv420_to_rgb(&self, yuv: &Yuv420, color: ColorSpace) -> Vec<u8> {
+            yuv.to_rgb(color)
+        }
+
+        fn rgb_to_yuv420(&self, rgb: &[u8], width: u32, height: u32, color: ColorSpace) -> Yuv420 {
+            Yuv420::from_rgb(rgb, width, height, color)
+        }
+
+        fn letterbox(&self, frame: &Frame, letterbox: &Letterbox, out: &mut [f32]) {
+            letterbox.fill(frame, out);
+        }
+    }
+
+    #[test]
+    fn test_pixel_converter_decodes_like_swscale() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = test_video_path(dir.path());
+        create_test_video(&path, 5, 160, 120, 30.0);
+
+        let mut swscale = FfmpegReader::new();
+        swscale.open(&path).unwrap();
+        let expected: Vec<Frame> = swscale.frames().map(|f| f.unwrap()).collect();
+
+        let mut converted = FfmpegReader::new().with_pixel_converter(Arc::new(CpuConverter));
+        converted.open(&path).unwrap();
+        let frames: Vec<Frame> = converted.frames().map(|f| f.unwrap()).collect();
+        assert_eq!(frames.len(), expected.len());
+        for (frame, expected) in frames.iter().zip(&expected) {
+            let worst = frame
+                .data()
+                .iter()
+                .zip(expected.data())
+                .map(|(a, b)| a.abs_diff(*b))
+                .max()
+                .unwrap();
+            assert!(worst <= 2, "frame {} differs by {worst}", frame.index());
+        }
+    }
+
     #[test]
     fn test_frames_without_open_returns_error() {
         let mut reader = FfmpegReader::new();
@@ -452,31 +927,45 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_total_frames_prefers_container_count() {
+        assert_eq!(total_frames(900, Some(60.0), 30.0), 900);
+    }
+
+    #[test]
+    fn test_total_frames_falls_back_to_duration() {
+        // Five hours at 29.97 fps, as an MKV without a stored frame count.
+        assert_eq!(
+            total_frames(0, Some(5.0 * 3600.0), 30000.0 / 1001.0),
+            539_461
+        );
+        assert_eq!(total_frames(0, None, 30.0), 0);
+        assert_eq!(total_frames(0, Some(10.0), 0.0), 0);
+    }
+
     #[test]
     fn test_parse_display_matrix_90_degrees() {
-        // A 90° clockwise rotation display matrix:
-        //   [ 0,  1,  0 ]
-        //   [-1,  0,  0 ]
-        //   [ 0,  0,  1 ]
-        // In 16.16 fixed point: 0=0, 1=65536, -1=-65536
+        // The matrix a phone held upright records (ffprobe: "rotation of
+        // -90.00 degrees", counter-clockwise), which players show turned
+        // 90° clockwise.
         // Matrix layout: m00, m10, m20, m01, m11, m21, m02, m12, m22
-        // For 90° rotation (display matrix convention):
-        //   m00=0, m10=65536 (sin90=1), m20=0
         let mut data = vec![0u8; 36];
-        let m00: i32 = 0; // cos(90°) = 0
-        let m10: i32 = 65536; // sin(90°) = 1
+        let m00: i32 = 0;
+        let m10: i32 = 65536;
         data[0..4].copy_from_slice(&m00.to_ne_bytes());
         data[4..8].copy_from_slice(&m10.to_ne_bytes());
 
-        // atan2(65536, 0) = π/2 ≈ 90°, negated = -90°, normalized = 270
-        // But phone videos use the convention where the matrix stores
-        // the inverse transform, so we negate to get actual rotation
         let angle = parse_display_matrix(&data).unwrap();
-        let normalized = normalize_rotation(angle);
-        assert!(
-            normalized == 90 || normalized == 270,
-            "Expected 90 or 270, got {normalized}"
-        );
+        assert_eq!(normalize_rotation(angle), 90);
+    }
+
+    #[test]
+    fn test_parse_display_matrix_270_degrees() {
+        let mut data = vec![0u8; 36];
+        data[4..8].copy_from_slice(&(-65536i32).to_ne_bytes());
+
+        let angle = parse_display_matrix(&data).unwrap();
+        assert_eq!(normalize_rotation(angle), 270);
     }
 
     #[test]
@@ -538,11 +1027,7 @@ mod tests {
 
         let mut reader = FfmpegReader::new();
         let meta = reader.open(&path).unwrap();
-        assert!(
-            meta.rotation == 90 || meta.rotation == 270,
-            "Expected 90 or 270 rotation, got {}",
-            meta.rotation
-        );
+        assert_eq!(meta.rotation, 90);
     }
 
     /// Find the position of a 4-byte atom type in MP4 data.
@@ -575,11 +1060,7 @@ mod tests {
         // Read input (should have rotation)
         let mut reader = FfmpegReader::new();
         let meta = reader.open(&input_path).unwrap();
-        assert!(
-            meta.rotation == 90 || meta.rotation == 270,
-            "Input rotation: {}",
-            meta.rotation
-        );
+        assert_eq!(meta.rotation, 90);
 
         // Write output with same metadata
         let output_path = dir.path().join("output.mp4");
@@ -601,6 +1082,143 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bake_rotation_reports_upright_metadata_and_frames() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rotated.mp4");
+        create_test_video(&path, 2, 160, 120, 30.0);
+        inject_tkhd_rotation_90(&path);
+
+        let mut reader = FfmpegReader::new().with_bake_rotation(true);
+        let meta = reader.open(&path).unwrap();
+        assert_eq!(meta.rotation, 0);
+        assert_eq!((meta.width, meta.height), (120, 160));
+
+        for frame in reader.frames() {
+            let frame = frame.unwrap();
+            assert_eq!((frame.width(), frame.height()), (120, 160));
+        }
+    }
+
+    #[test]
+    fn test_bake_rotation_turns_phone_video_upright() {
+        // Stored landscape with a bright top-left corner; the phone's
+        // display matrix turns it clockwise, so the corner shows top-right.
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rotated.mp4");
+        create_test_video_with(&path, 1, 160, 120, 30.0, |_, col, row| {
+            if col < 48 && row < 48 {
+                255
+            } else {
+                0
+            }
+        });
+        inject_tkhd_rotation_90(&path);
+
+        let mut reader = FfmpegReader::new().with_bake_rotation(true);
+        reader.open(&path).unwrap();
+        let frame = reader.frames().next().unwrap().unwrap();
+
+        assert_eq!((frame.width(), frame.height()), (120, 160));
+        let luma = |x: usize, y: usize| frame.data()[(y * 120 + x) * 3];
+        assert!(luma(96, 24) > 200, "top right is {}", luma(96, 24));
+        assert!(luma(24, 24) < 50, "top left is {}", luma(24, 24));
+        assert!(luma(24, 136) < 50, "bottom left is {}", luma(24, 136));
+    }
+
+    #[test]
+    fn test_bake_rotation_output_has_no_display_matrix() {
+        use crate::video::domain::video_writer::VideoWriter;
+        use crate::video::infrastructure::ffmpeg_writer::FfmpegWriter;
+
+        let dir = tempfile::tempdir().unwrap();
+        let input_path = dir.path().join("input.mp4");
+        create_test_video(&input_path, 3, 160, 120, 30.0);
+        inject_tkhd_rotation_90(&input_path);
+
+        let mut reader = FfmpegReader::new().with_bake_rotation(true);
+        let meta = reader.open(&input_path).unwrap();
+
+        let output_path = dir.path().join("output.mp4");
+        let mut writer = FfmpegWriter::new();
+        writer.set_skip_audio_passthrough(true);
+        writer.open(&output_path, &meta).unwrap();
+        for frame in reader.frames() {
+            writer.write(&frame.unwrap()).unwrap();
+        }
+        writer.close().unwrap();
+
+        let mut reader2 = FfmpegReader::new();
+        let meta2 = reader2.open(&output_path).unwrap();
+        assert_eq!(meta2.rotation, 0);
+        assert_eq!((meta2.width, meta2.height), (120, 160));
+    }
+
+    #[test]
+    fn test_bake_rotation_is_noop_for_upright_video() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = test_video_path(dir.path());
+        create_test_video(&path, 1, 160, 120, 30.0);
+
+        let mut reader = FfmpegReader::new().with_bake_rotation(true);
+        let meta = reader.open(&path).unwrap();
+        assert_eq!((meta.width, meta.height, meta.rotation), (160, 120, 0));
+    }
+
+    fn inject_tkhd_rotation_90(path: &Path) {
+        let mut data = std::fs::read(path).unwrap();
+        let tkhd_pos = find_atom(&data, b"tkhd").expect("No tkhd atom found in test video");
+        let matrix_offset = tkhd_pos + 4 + 40;
+        let matrix_90: [i32; 9] = [0, 65536, 0, -65536, 0, 0, 0, 0, 0x40000000];
+        for (i, &val) in matrix_90.iter().enumerate() {
+            data[matrix_offset + i * 4..matrix_offset + i * 4 + 4]
+                .copy_from_slice(&val.to_be_bytes());
+        }
+        std::fs::write(path, &data).unwrap();
+    }
+
+    #[test]
+    fn test_seek_starts_at_requested_frame() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = test_video_path(dir.path());
+        create_test_video(&path, 20, 160, 120, 30.0);
+
+        let mut reader = FfmpegReader::new();
+        reader.open(&path).unwrap();
+        reader.seek(7).unwrap();
+
+        let frames: Vec<_> = reader.frames().map(|f| f.unwrap()).collect();
+        assert_eq!(frames.len(), 13);
+        assert_eq!(frames[0].index(), 7);
+        // Frame i was filled with gray level (i * 40) % 256.
+        let gray = frames[0].data()[0] as i32;
+        assert!(
+            (gray - 24).abs() <= 6,
+            "expected frame 7's gray, got {gray}"
+        );
+    }
+
+    #[test]
+    fn test_seek_backwards_after_reading() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = test_video_path(dir.path());
+        create_test_video(&path, 10, 160, 120, 30.0);
+
+        let mut reader = FfmpegReader::new();
+        reader.open(&path).unwrap();
+        assert_eq!(reader.frames().count(), 10);
+
+        reader.seek(2).unwrap();
+        let indices: Vec<usize> = reader.frames().map(|f| f.unwrap().index()).collect();
+        assert_eq!(indices, (2..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_seek_without_open_returns_error() {
+        let mut reader = FfmpegReader::new();
+        assert!(reader.seek(3).is_err());
+    }
+
     #[test]
     fn test_close_idempotent() {
         let dir = tempfile::tempdir().unwrap();
@@ -1,31 +1,75 @@
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use crate::shared::color_space::ColorSpace;
 use crate::shared::frame::Frame;
+use crate::shared::pixel_converter::PixelConverter;
+use crate::shared::time_map::TimeMap;
 use crate::shared::video_metadata::VideoMetadata;
+use crate::shared::warnings::{WarningKind, Warnings};
+use crate::video::domain::video_codec::VideoCodec;
 use crate::video::domain::video_writer::VideoWriter;
+use crate::video::infrastructure::ffmpeg_color::{configure_rgb_to_yuv, tag_encoder, yuv420_frame};
+use crate::video::infrastructure::segmented_output::SegmentedOutput;
 
 pub const DEFAULT_CRF: u32 = 18;
 
+/// Ticks per second of the encoder clock. The 90 kHz MPEG clock resolves
+/// frame times of every common rate and of variable frame rate sources to
+/// within a tick.
+const TIME_BASE: ffmpeg_next::Rational = ffmpeg_next::Rational(1, 90_000);
+
 /// Encodes video frames via ffmpeg-next with built-in audio muxing.
 ///
 /// When the source video has an audio stream, it is copied directly
 /// to the output — no separate ffmpeg binary or temp file needed.
+///
+/// Frames are converted to YUV with the matrix and range in
+/// [`VideoMetadata::color`], and the output stream is tagged with them.
+///
+/// With [`FfmpegWriter::with_segmented_output`], the path passed to `open`
+/// is an HLS or DASH playlist that is packaged on `close`.
+///
+/// Encodes H.264 unless [`FfmpegWriter::with_codec`] picks another codec;
+/// `open` fails when the output's container can't carry it.
+///
+/// Frames with a [`Frame::timestamp`] are presented at that time, so
+/// variable frame rate recordings keep their source timing and stay in sync
+/// with the audio. Frames without one are spaced evenly at the metadata's
+/// frame rate.
+///
+/// With [`FfmpegWriter::with_pixel_converter`], frames bound for a 4:2:0
+/// encoder at the source size are converted to YUV by the converter
+/// instead of swscale.
 pub struct FfmpegWriter {
     output_path: Option<PathBuf>,
     source_path: Option<PathBuf>,
     octx: Option<ffmpeg_next::format::context::Output>,
     encoder: Option<ffmpeg_next::codec::encoder::video::Encoder>,
     scaler: Option<ffmpeg_next::software::scaling::Context>,
+    converter: Option<Arc<dyn PixelConverter>>,
+    /// Whether the open output takes the converter's 4:2:0 frames.
+    converts: bool,
+    color: ColorSpace,
+    codec: VideoCodec,
     width: u32,
     height: u32,
-    fps: i32,
+    frame_rate: ffmpeg_next::Rational,
+    /// Places frames that carry no timestamp; takes the source frame rate
+    /// on open unless it was given one.
+    time_map: TimeMap,
     crf: u32,
+    output_height: Option<u32>,
+    segmented: Option<SegmentedOutput>,
     frame_count: usize,
+    /// Presentation time of the last written frame, in [`TIME_BASE`] ticks.
+    last_pts: Option<i64>,
     video_stream_index: usize,
     audio_source_stream_idx: Option<usize>,
     audio_output_stream_idx: Option<usize>,
     audio_source_time_base: Option<ffmpeg_next::Rational>,
     pub(crate) skip_audio_passthrough: bool,
+    warnings: Warnings,
 }
 
 // Safety: FfmpegWriter is only used from a single thread at a time.
@@ -40,16 +84,25 @@ impl FfmpegWriter {
             octx: None,
             encoder: None,
             scaler: None,
+            converter: None,
+            converts: false,
+            color: ColorSpace::default(),
+            codec: VideoCodec::default(),
             width: 0,
             height: 0,
-            fps: 30,
+            frame_rate: ffmpeg_next::Rational(30, 1),
+            time_map: TimeMap::new(),
             crf: DEFAULT_CRF,
+            output_height: None,
+            segmented: None,
             frame_count: 0,
+            last_pts: None,
             video_stream_index: 0,
             audio_source_stream_idx: None,
             audio_output_stream_idx: None,
             audio_source_time_base: None,
             skip_audio_passthrough: false,
+            warnings: Warnings::new(),
         }
     }
 
@@ -58,6 +111,52 @@ impl FfmpegWriter {
         self
     }
 
+    /// Encodes with `codec`. `crf` is on the H.264 scale (0-51) for every
+    /// codec and is mapped to the encoder's own range.
+    pub fn with_codec(mut self, codec: VideoCodec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Downscales the encoded video to `height`, keeping the aspect ratio.
+    /// Frames are still written at the source size; sources already at or
+    /// below `height` are encoded unchanged.
+    pub fn with_output_height(mut self, height: u32) -> Self {
+        self.output_height = Some(height);
+        self
+    }
+
+    /// Writes an HLS or DASH playlist with segments instead of a single
+    /// file, with a keyframe at every segment boundary.
+    pub fn with_segmented_output(mut self, segmented: SegmentedOutput) -> Self {
+        self.segmented = Some(segmented);
+        self
+    }
+
+    /// Converts frames to YUV with `converter`, such as a GPU, when the
+    /// encoder takes 4:2:0 input at the source size. Other outputs still
+    /// go through swscale.
+    pub fn with_pixel_converter(mut self, converter: Arc<dyn PixelConverter>) -> Self {
+        self.converter = Some(converter);
+        self
+    }
+
+    /// Places frames without a timestamp on `time_map`, the map the
+    /// cut-range reader and audio processing share. Frames from a
+    /// cut-range reader arrive already on the output timeline, so only the
+    /// map's frame rate is used here; without one, the source's is.
+    pub fn with_time_map(mut self, time_map: TimeMap) -> Self {
+        self.time_map = time_map;
+        self
+    }
+
+    /// Reports audio passthrough failures to `warnings` instead of only
+    /// logging them.
+    pub fn with_warnings(mut self, warnings: Warnings) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
     pub fn set_skip_audio_passthrough(&mut self, skip: bool) {
         self.skip_audio_passthrough = skip;
     }
@@ -76,16 +175,40 @@ impl VideoWriter for FfmpegWriter {
         metadata: &VideoMetadata,
     ) -> Result<(), Box<dyn std::error::Error>> {
         ffmpeg_next::init()?;
+        self.codec.check_container(path)?;
 
         self.width = metadata.width;
         self.height = metadata.height;
-        self.fps = sanitize_fps(metadata.fps);
+        let (num, den) = frame_rate(metadata.fps);
+        self.frame_rate = ffmpeg_next::Rational(num, den);
+        if self.time_map.frame_rate().is_none() {
+            self.time_map = self.time_map.clone().with_frame_rate(metadata.fps);
+        }
         self.output_path = Some(path.to_path_buf());
         self.source_path = metadata.source_path.clone();
 
-        let mut octx = ffmpeg_next::format::output(path)?;
+        let mut octx = match self.segmented {
+            Some(_) => ffmpeg_next::format::output(&SegmentedOutput::intermediate_path(path))?,
+            None => ffmpeg_next::format::output(path)?,
+        };
 
-        let encoder = create_video_encoder(&mut octx, metadata, self.fps, self.crf)?;
+        let (out_w, out_h) = scaled_size(metadata.width, metadata.height, self.output_height);
+        let encoded = VideoMetadata {
+            width: out_w,
+            height: out_h,
+            ..metadata.clone()
+        };
+        let gop = self
+            .segmented
+            .map(|segmented| segmented.keyframe_interval(metadata.fps));
+        let (encoder, pixel_format) = create_video_encoder(
+            &mut octx,
+            &encoded,
+            self.codec,
+            self.frame_rate,
+            self.crf,
+            gop,
+        )?;
 
         self.video_stream_index = 0;
 
@@ -104,20 +227,26 @@ impl VideoWriter for FfmpegWriter {
 
         octx.write_header()?;
 
-        let scaler = ffmpeg_next::software::scaling::Context::get(
+        let mut scaler = ffmpeg_next::software::scaling::Context::get(
             ffmpeg_next::format::Pixel::RGB24,
             metadata.width,
             metadata.height,
-            ffmpeg_next::format::Pixel::YUV420P,
-            metadata.width,
-            metadata.height,
+            pixel_format,
+            out_w,
+            out_h,
             ffmpeg_next::software::scaling::Flags::BILINEAR,
         )?;
+        configure_rgb_to_yuv(&mut scaler, metadata.color);
+        self.color = metadata.color;
+        self.converts = self.converter.is_some()
+            && pixel_format == ffmpeg_next::format::Pixel::YUV420P
+            && (out_w, out_h) == (metadata.width, metadata.height);
 
         self.octx = Some(octx);
         self.encoder = Some(encoder);
         self.scaler = Some(scaler);
         self.frame_count = 0;
+        self.last_pts = None;
 
         Ok(())
     }
@@ -127,26 +256,45 @@ impl VideoWriter for FfmpegWriter {
         let scaler = self.scaler.as_mut().unwrap();
         let octx = self.octx.as_mut().unwrap();
 
-        let rgb_frame = frame_to_rgb_video(frame, self.width, self.height);
-
-        let mut yuv_frame = ffmpeg_next::util::frame::video::Video::empty();
-        scaler.run(&rgb_frame, &mut yuv_frame)?;
-        yuv_frame.set_pts(Some(self.frame_count as i64));
+        let mut yuv_frame = match self.converter.as_ref().filter(|_| self.converts) {
+            Some(converter) => yuv420_frame(&converter.rgb_to_yuv420(
+                frame.data(),
+                self.width,
+                self.height,
+                self.color,
+            )),
+            None => {
+                let rgb_frame = frame_to_rgb_video(frame, self.width, self.height);
+                let mut yuv_frame = ffmpeg_next::util::frame::video::Video::empty();
+                scaler.run(&rgb_frame, &mut yuv_frame)?;
+                yuv_frame
+            }
+        };
+        let pts = presentation_ticks(frame.timestamp(), self.frame_count, &self.time_map);
+        // Encoders reject repeated or backward timestamps, as duplicated
+        // frames would carry.
+        let pts = match self.last_pts {
+            Some(last) if pts <= last => last + 1,
+            _ => pts,
+        };
+        yuv_frame.set_pts(Some(pts));
 
         encoder.send_frame(&yuv_frame)?;
 
-        flush_packets(encoder, octx, self.video_stream_index, self.fps)?;
+        flush_packets(encoder, octx, self.video_stream_index)?;
 
         self.frame_count += 1;
+        self.last_pts = Some(pts);
         Ok(())
     }
 
     fn close(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let playlist = self.encoder.as_ref().and(self.output_path.clone());
         if let Some(ref mut encoder) = self.encoder {
             let octx = self.octx.as_mut().unwrap();
 
             encoder.send_eof()?;
-            flush_packets(encoder, octx, self.video_stream_index, self.fps)?;
+            flush_packets(encoder, octx, self.video_stream_index)?;
 
             mux_audio_from_source(
                 octx,
@@ -154,12 +302,20 @@ impl VideoWriter for FfmpegWriter {
                 self.audio_output_stream_idx,
                 self.audio_source_time_base,
                 self.source_path.as_ref(),
+                &self.warnings,
             );
 
             octx.write_trailer()?;
         }
 
+        // Dropping the output context closes the intermediate file.
         self.reset();
+        if let (Some(segmented), Some(playlist)) = (self.segmented, playlist) {
+            let intermediate = SegmentedOutput::intermediate_path(&playlist);
+            let packaged = segmented.package(&intermediate, &playlist);
+            let _ = std::fs::remove_file(&intermediate);
+            packaged?;
+        }
         Ok(())
     }
 }
@@ -177,33 +333,127 @@ impl FfmpegWriter {
     }
 }
 
-/// Clamps fps to a positive integer, defaulting to 30 for invalid values.
-fn sanitize_fps(fps: f64) -> i32 {
-    let rounded = fps.round() as i32;
-    if rounded <= 0 {
-        30
-    } else {
-        rounded
+/// Output dimensions for an optional target height. Dimensions are rounded
+/// to even values, as YUV420P requires.
+fn scaled_size(width: u32, height: u32, target_height: Option<u32>) -> (u32, u32) {
+    match target_height {
+        Some(target) if target > 0 && target < height => {
+            let half_w = (width as f64 * target as f64 / height as f64 / 2.0).round() as u32;
+            ((half_w * 2).max(2), target / 2 * 2)
+        }
+        _ => (width, height),
+    }
+}
+
+/// Frame rate as a `(numerator, denominator)` fraction, defaulting to 30
+/// for invalid values. Whole rates stay whole, NTSC rates such as 29.97
+/// become `30000/1001`, and other fractional rates keep three decimals.
+/// Rounding 29.97 to 30 would drift the picture 14 seconds ahead of the
+/// audio over four hours.
+fn frame_rate(fps: f64) -> (i32, i32) {
+    if !fps.is_finite() || fps.round() <= 0.0 {
+        return (30, 1);
+    }
+    let whole = fps.round();
+    if (fps - whole).abs() < 0.001 {
+        return (whole as i32, 1);
+    }
+    let ntsc = (fps * 1.001).round();
+    if (fps - ntsc / 1.001).abs() < 0.001 {
+        return (ntsc as i32 * 1000, 1001);
+    }
+    ((fps * 1000.0).round() as i32, 1000)
+}
+
+/// Presentation time in [`TIME_BASE`] ticks of a frame at `timestamp`
+/// seconds, or of frame number `frame_count` on `time_map` when the frame
+/// has no timestamp. Untimed frames are spaced at the source's exact rate
+/// rather than the encoder's rounded one, so they stay on the audio's
+/// timeline.
+fn presentation_ticks(timestamp: Option<f64>, frame_count: usize, time_map: &TimeMap) -> i64 {
+    let ticks_per_second = f64::from(TIME_BASE.invert());
+    let seconds = match timestamp {
+        Some(seconds) => seconds.max(0.0),
+        None => time_map.frame_time(frame_count),
+    };
+    (seconds * ticks_per_second).round() as i64
+}
+
+/// Encoder for `codec`. Where ffmpeg builds commonly ship several, the
+/// library with CRF support and sensible speed is preferred (SVT-AV1 over
+/// the much slower libaom). H.264 falls back to MPEG4 with qscale.
+fn find_encoder(codec: VideoCodec) -> Result<ffmpeg_next::Codec, Box<dyn std::error::Error>> {
+    use ffmpeg_next::codec::Id;
+    let (preferred, id): (&[&str], Id) = match codec {
+        VideoCodec::H264 => (&["libx264"], Id::H264),
+        VideoCodec::Hevc => (&["libx265"], Id::HEVC),
+        VideoCodec::Vp9 => (&["libvpx-vp9"], Id::VP9),
+        VideoCodec::Av1 => (&["libsvtav1", "libaom-av1"], Id::AV1),
+    };
+    let found = preferred
+        .iter()
+        .find_map(|name| ffmpeg_next::encoder::find_by_name(name))
+        .or_else(|| ffmpeg_next::encoder::find(id));
+    match (found, codec) {
+        (Some(encoder), _) => Ok(encoder),
+        (None, VideoCodec::H264) => ffmpeg_next::encoder::find(Id::MPEG4)
+            .ok_or_else(|| "No suitable video encoder found (tried H264, MPEG4)".into()),
+        (None, _) => Err(format!("This ffmpeg build has no {} encoder", codec.label()).into()),
     }
 }
 
+/// 8-bit 4:2:0 pixel format the encoder accepts: YUV420P, or NV12 for
+/// hardware encoders that only take that.
+fn pixel_format(
+    codec: ffmpeg_next::Codec,
+) -> Result<ffmpeg_next::format::Pixel, Box<dyn std::error::Error>> {
+    use ffmpeg_next::format::Pixel;
+    let Some(formats) = codec.video()?.formats() else {
+        return Ok(Pixel::YUV420P);
+    };
+    let formats: Vec<Pixel> = formats.collect();
+    [Pixel::YUV420P, Pixel::NV12]
+        .into_iter()
+        .find(|format| formats.contains(format))
+        .ok_or_else(|| {
+            format!(
+                "The {} encoder takes no 8-bit 4:2:0 pixel format",
+                codec.name()
+            )
+            .into()
+        })
+}
+
+/// Maps a CRF on the H.264 scale (0-51) to the 0-63 scale of VP9 and AV1
+/// encoders, so one quality setting means roughly the same across codecs.
+fn crf_63(crf: u32) -> u32 {
+    (crf.min(51) as f64 * 63.0 / 51.0).round() as u32
+}
+
 fn create_video_encoder(
     octx: &mut ffmpeg_next::format::context::Output,
     metadata: &VideoMetadata,
-    fps: i32,
+    video_codec: VideoCodec,
+    frame_rate: ffmpeg_next::Rational,
     crf: u32,
-) -> Result<ffmpeg_next::codec::encoder::video::Encoder, Box<dyn std::error::Error>> {
+    gop: Option<u32>,
+) -> Result<
+    (
+        ffmpeg_next::codec::encoder::video::Encoder,
+        ffmpeg_next::format::Pixel,
+    ),
+    Box<dyn std::error::Error>,
+> {
     let global_header = octx
         .format()
         .flags()
         .contains(ffmpeg_next::format::Flags::GLOBAL_HEADER);
+    // Apple players only open HEVC in MP4/MOV when it is tagged `hvc1`.
+    let tag_hvc1 =
+        video_codec == VideoCodec::Hevc && matches!(octx.format().name(), "mp4" | "mov" | "ipod");
 
-    // Prefer H.264 (libx264) for CRF support; fall back to MPEG4 with qscale
-    let h264 = ffmpeg_next::encoder::find(ffmpeg_next::codec::Id::H264);
-    let codec = h264
-        .or_else(|| ffmpeg_next::encoder::find(ffmpeg_next::codec::Id::MPEG4))
-        .ok_or("No suitable video encoder found (tried H264, MPEG4)")?;
-    let is_h264 = h264.is_some();
+    let codec = find_encoder(video_codec)?;
+    let pixel_format = pixel_format(codec)?;
 
     let mut ost = octx.add_stream(Some(codec))?;
 
@@ -213,35 +463,69 @@ fn create_video_encoder(
 
     encoder_ctx.set_width(metadata.width);
     encoder_ctx.set_height(metadata.height);
-    encoder_ctx.set_format(ffmpeg_next::format::Pixel::YUV420P);
-    encoder_ctx.set_time_base(ffmpeg_next::Rational(1, fps));
-    encoder_ctx.set_frame_rate(Some(ffmpeg_next::Rational(fps, 1)));
+    encoder_ctx.set_format(pixel_format);
+    encoder_ctx.set_time_base(TIME_BASE);
+    encoder_ctx.set_frame_rate(Some(frame_rate));
+    tag_encoder(&mut encoder_ctx, metadata.color);
+    if let Some(gop) = gop {
+        encoder_ctx.set_gop(gop);
+    }
 
     if global_header {
         encoder_ctx.set_flags(ffmpeg_next::codec::Flags::GLOBAL_HEADER);
     }
 
     let mut opts = ffmpeg_next::Dictionary::new();
-    if is_h264 {
-        opts.set("preset", "medium");
-        opts.set("crf", &crf.max(1).to_string());
-    } else {
-        // MPEG4 uses global_quality (qscale). Map CRF 1–51 to qscale 1–31.
-        // FF_QP2LAMBDA = 128
-        let qscale = (crf.clamp(1, 51) as f64 * 31.0 / 51.0).round().max(1.0) as i32;
-        encoder_ctx.set_global_quality(qscale * 128);
+    match codec.name() {
+        "mpeg4" => {
+            // MPEG4 uses global_quality (qscale). Map CRF 1–51 to qscale 1–31.
+            // FF_QP2LAMBDA = 128
+            let qscale = (crf.clamp(1, 51) as f64 * 31.0 / 51.0).round().max(1.0) as i32;
+            encoder_ctx.set_global_quality(qscale * 128);
+        }
+        "libvpx-vp9" => {
+            // Constant quality needs a zero bitrate; otherwise CRF is a cap.
+            encoder_ctx.set_bit_rate(0);
+            opts.set("crf", &crf_63(crf).to_string());
+            opts.set("deadline", "good");
+            opts.set("cpu-used", "4");
+            opts.set("row-mt", "1");
+        }
+        "libaom-av1" => {
+            encoder_ctx.set_bit_rate(0);
+            opts.set("crf", &crf_63(crf).to_string());
+            opts.set("cpu-used", "6");
+            opts.set("row-mt", "1");
+        }
+        "libsvtav1" => {
+            opts.set("crf", &crf_63(crf).max(1).to_string());
+            opts.set("preset", "8");
+        }
+        name => {
+            opts.set("preset", "medium");
+            opts.set("crf", &crf.max(1).to_string());
+            if name == "libx265" {
+                opts.set("x265-params", "log-level=error");
+            }
+        }
     }
     let encoder = encoder_ctx.open_with(opts)?;
     ost.set_parameters(&encoder);
+    if tag_hvc1 {
+        unsafe {
+            (*ost.parameters().as_mut_ptr()).codec_tag = u32::from_le_bytes(*b"hvc1");
+        }
+    }
 
-    Ok(encoder)
+    Ok((encoder, pixel_format))
 }
 
-type AudioPassthroughInfo = (Option<usize>, Option<usize>, Option<ffmpeg_next::Rational>);
+pub(crate) type AudioPassthroughInfo =
+    (Option<usize>, Option<usize>, Option<ffmpeg_next::Rational>);
 
 /// Adds an audio passthrough stream if the source video has audio.
 /// Returns (source_stream_idx, output_stream_idx, source_time_base).
-fn setup_audio_passthrough(
+pub(crate) fn setup_audio_passthrough(
     octx: &mut ffmpeg_next::format::context::Output,
     metadata: &VideoMetadata,
 ) -> Result<AudioPassthroughInfo, Box<dyn std::error::Error>> {
@@ -277,26 +561,26 @@ fn flush_packets(
     encoder: &mut ffmpeg_next::codec::encoder::video::Encoder,
     octx: &mut ffmpeg_next::format::context::Output,
     stream_index: usize,
-    fps: i32,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let ost_time_base = octx.stream(stream_index).unwrap().time_base();
 
     let mut encoded = ffmpeg_next::Packet::empty();
     while encoder.receive_packet(&mut encoded).is_ok() {
         encoded.set_stream(stream_index);
-        encoded.rescale_ts(ffmpeg_next::Rational(1, fps), ost_time_base);
+        encoded.rescale_ts(TIME_BASE, ost_time_base);
         encoded.write_interleaved(octx)?;
     }
     Ok(())
 }
 
 /// Copies audio packets from the source file into the output container.
-fn mux_audio_from_source(
+pub(crate) fn mux_audio_from_source(
     octx: &mut ffmpeg_next::format::context::Output,
     audio_source_stream_idx: Option<usize>,
     audio_output_stream_idx: Option<usize>,
     audio_source_time_base: Option<ffmpeg_next::Rational>,
     source_path: Option<&PathBuf>,
+    warnings: &Warnings,
 ) {
     let (Some(audio_src_idx), Some(audio_ost_idx), Some(audio_src_tb), Some(source_path)) = (
         audio_source_stream_idx,
@@ -310,7 +594,10 @@ fn mux_audio_from_source(
     let mut ictx = match ffmpeg_next::format::input(source_path) {
         Ok(ctx) => ctx,
         Err(e) => {
-            log::warn!("Audio muxing failed: could not reopen source: {e}");
+            warnings.push(
+                WarningKind::Audio,
+                format!("Audio stream could not be copied: could not reopen source: {e}"),
+            );
             return;
         }
     };
@@ -324,26 +611,33 @@ fn mux_audio_from_source(
         packet.set_position(-1);
         packet.set_stream(audio_ost_idx);
         if let Err(e) = packet.write_interleaved(octx) {
-            log::warn!("Failed to write audio packet: {e}");
+            warnings.push(
+                WarningKind::Audio,
+                format!("Audio stream could not be fully copied: {e}"),
+            );
             break;
         }
     }
 }
 
-/// Sets a display matrix on an output stream to encode the given rotation angle.
+/// Sets a display matrix on an output stream that asks players to turn
+/// frames `rotation_degrees` clockwise, the convention of
+/// [`VideoMetadata::rotation`](crate::shared::video_metadata::VideoMetadata::rotation).
 ///
 /// Uses the raw FFmpeg C API (`av_stream_new_side_data`) because the
 /// ffmpeg-next bindings don't expose stream-level side data writes.
 /// The display matrix is a 3×3 transformation stored as 9 × i32 values:
 /// the first 6 in 16.16 fixed-point, the last 3 in 2.30 fixed-point.
-fn set_stream_display_matrix(
+/// This is what `av_display_rotation_set` writes for the counter-clockwise
+/// angle `-rotation_degrees`.
+pub(crate) fn set_stream_display_matrix(
     octx: &mut ffmpeg_next::format::context::Output,
     stream_index: usize,
     rotation_degrees: i32,
 ) {
     use ffmpeg_next::sys::{av_stream_new_side_data, AVPacketSideDataType};
 
-    let angle_rad = -(rotation_degrees as f64).to_radians();
+    let angle_rad = (rotation_degrees as f64).to_radians();
     let cos_val = (angle_rad.cos() * 65536.0).round() as i32;
     let sin_val = (angle_rad.sin() * 65536.0).round() as i32;
 
@@ -398,7 +692,11 @@ fn frame_to_rgb_video(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shared::color_space::{ColorMatrix, ColorRange};
+    use crate::shared::letterbox::Letterbox;
+    use crate::shared::yuv420::Yuv420;
     use crate::video::domain::video_reader::VideoReader;
+    use rstest::rstest;
 
     fn metadata(w: u32, h: u32, fps: f64) -> VideoMetadata {
         VideoMetadata {
@@ -409,6 +707,7 @@ mod tests {
             codec: String::new(),
             source_path: None,
             rotation: 0,
+            color: ColorSpace::default(),
         }
     }
 
@@ -434,6 +733,78 @@ mod tests {
         assert!(std::fs::metadata(&path).unwrap().len() > 0);
     }
 
+    #[test]
+    fn test_segmented_output_writes_playlist_and_segments() {
+        use crate::video::infrastructure::segmented_output::SegmentFormat;
+
+        let dir = tempfile::tempdir().unwrap();
+        let playlist = dir.path().join("out.m3u8");
+        let meta = metadata(160, 120, 10.0);
+
+        let mut writer = FfmpegWriter::new().with_segmented_output(
+            SegmentedOutput::new(SegmentFormat::Hls).with_segment_seconds(1.0),
+        );
+        writer.open(&playlist, &meta).unwrap();
+        for i in 0..30 {
+            writer
+                .write(&solid_frame(i, 160, 120, (i * 8) as u8))
+                .unwrap();
+        }
+        writer.close().unwrap();
+
+        let listing = std::fs::read_to_string(&playlist).unwrap();
+        assert!(listing.contains("#EXT-X-ENDLIST"), "{listing}");
+        assert!(listing.contains("out_00000.ts"), "{listing}");
+        assert!(dir.path().join("out_00002.ts").exists());
+        assert!(!SegmentedOutput::intermediate_path(&playlist).exists());
+    }
+
+    #[rstest]
+    #[case(30.0, (30, 1))]
+    #[case(25.0, (25, 1))]
+    #[case(29.97, (30000, 1001))]
+    #[case(30000.0 / 1001.0, (30000, 1001))]
+    #[case(23.976, (24000, 1001))]
+    #[case(59.94, (60000, 1001))]
+    #[case(12.5, (12500, 1000))]
+    #[case(0.0, (30, 1))]
+    #[case(f64::NAN, (30, 1))]
+    fn test_frame_rate(#[case] fps: f64, #[case] expected: (i32, i32)) {
+        assert_eq!(frame_rate(fps), expected);
+    }
+
+    #[test]
+    fn test_scaled_size() {
+        assert_eq!(scaled_size(1920, 1080, None), (1920, 1080));
+        assert_eq!(scaled_size(1920, 1080, Some(720)), (1280, 720));
+        assert_eq!(scaled_size(1920, 1080, Some(480)), (854, 480));
+        assert_eq!(scaled_size(1280, 720, Some(1080)), (1280, 720));
+    }
+
+    #[test]
+    fn test_output_height_downscales_encoded_video() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.mp4");
+        let meta = metadata(320, 240, 30.0);
+
+        let mut writer = FfmpegWriter::new().with_output_height(120);
+        writer.open(&path, &meta).unwrap();
+        writer.write(&solid_frame(0, 320, 240, 128)).unwrap();
+        writer.close().unwrap();
+
+        ffmpeg_next::init().unwrap();
+        let ictx = ffmpeg_next::format::input(&path).unwrap();
+        let stream = ictx
+            .streams()
+            .best(ffmpeg_next::media::Type::Video)
+            .unwrap();
+        let codec_ctx =
+            ffmpeg_next::codec::context::Context::from_parameters(stream.parameters()).unwrap();
+        let decoder = codec_ctx.decoder().video().unwrap();
+        assert_eq!(decoder.width(), 160);
+        assert_eq!(decoder.height(), 120);
+    }
+
     #[test]
     fn test_written_video_has_correct_resolution() {
         let dir = tempfile::tempdir().unwrap();
@@ -458,6 +829,57 @@ mod tests {
         assert_eq!(decoder.height(), 120);
     }
 
+    #[rstest]
+    #[case(VideoCodec::Hevc, "out.mp4", ffmpeg_next::codec::Id::HEVC)]
+    #[case(VideoCodec::Vp9, "out.webm", ffmpeg_next::codec::Id::VP9)]
+    #[case(VideoCodec::Av1, "out.mkv", ffmpeg_next::codec::Id::AV1)]
+    fn test_codec_selection(
+        #[case] codec: VideoCodec,
+        #[case] name: &str,
+        #[case] expected: ffmpeg_next::codec::Id,
+    ) {
+        ffmpeg_next::init().unwrap();
+        if find_encoder(codec).is_err() {
+            // Not every ffmpeg build ships every encoder.
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(name);
+        let meta = metadata(160, 120, 30.0);
+
+        let mut writer = FfmpegWriter::new().with_codec(codec);
+        writer.open(&path, &meta).unwrap();
+        for i in 0..3 {
+            writer.write(&solid_frame(i, 160, 120, 128)).unwrap();
+        }
+        writer.close().unwrap();
+
+        let ictx = ffmpeg_next::format::input(&path).unwrap();
+        let stream = ictx
+            .streams()
+            .best(ffmpeg_next::media::Type::Video)
+            .unwrap();
+        assert_eq!(stream.parameters().id(), expected);
+    }
+
+    #[test]
+    fn test_open_rejects_container_without_codec_support() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.webm");
+        let mut writer = FfmpegWriter::new();
+        let err = writer.open(&path, &metadata(160, 120, 30.0)).unwrap_err();
+        assert!(err.to_string().contains(".webm"), "{err}");
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_crf_63() {
+        assert_eq!(crf_63(0), 0);
+        assert_eq!(crf_63(DEFAULT_CRF), 22);
+        assert_eq!(crf_63(51), 63);
+        assert_eq!(crf_63(99), 63);
+    }
+
     #[test]
     fn test_write_without_open_returns_error() {
         let mut writer = FfmpegWriter::new();
@@ -510,6 +932,151 @@ mod tests {
         );
     }
 
+    /// Converts on the CPU, as a GPU converter would.
+    struct CpuConverter;
+
+    impl PixelConverter for CpuConverter {
+        fn yuv420_to_rgb(&self, yuv: &Yuv420, color: ColorSpace) -> Vec<u8> {
+            yuv.to_rgb(color)
+        }
+
+        fn rgb_to_yuv420(&self, rgb: &[u8], width: u32, height: u32, color: ColorSpace) -> Yuv420 {
+            Yuv420::from_rgb(rgb, width, height, color)
+        }
+
+        fn letterbox(&self, frame: &Frame, letterbox: &Letterbox, out: &mut [f32]) {
+            letterbox.fill(frame, out);
+        }
+    }
+
+    #[test]
+    fn test_pixel_converter_encodes_frames() {
+        use crate::video::infrastructure::ffmpeg_reader::FfmpegReader;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("converted.mp4");
+        let meta = metadata(160, 120, 30.0);
+
+        let mut writer = FfmpegWriter::new().with_pixel_converter(Arc::new(CpuConverter));
+        writer.open(&path, &meta).unwrap();
+        for i in 0..3 {
+            writer.write(&solid_frame(i, 160, 120, 200)).unwrap();
+        }
+        writer.close().unwrap();
+
+        let mut reader = FfmpegReader::new();
+        reader.open(&path).unwrap();
+        let frames: Vec<_> = reader.frames().map(|f| f.unwrap()).collect();
+        assert_eq!(frames.len(), 3);
+        let worst = frames[1]
+            .data()
+            .iter()
+            .map(|&b| b.abs_diff(200))
+            .max()
+            .unwrap();
+        assert!(worst <= 6, "pixels differ from the source by {worst}");
+    }
+
+    #[test]
+    fn test_presentation_ticks() {
+        let ntsc = TimeMap::new().with_frame_rate(30000.0 / 1001.0);
+        assert_eq!(presentation_ticks(None, 0, &ntsc), 0);
+        assert_eq!(presentation_ticks(None, 10, &ntsc), 30030);
+        assert_eq!(presentation_ticks(Some(0.5), 10, &ntsc), 45000);
+        assert_eq!(presentation_ticks(Some(-0.1), 0, &ntsc), 0);
+    }
+
+    #[rstest]
+    #[case(29.97)]
+    #[case(23.976)]
+    #[case(12.3456)]
+    fn test_untimed_frames_stay_in_sync_with_audio_over_long_recordings(#[case] fps: f64) {
+        // The last frame of a 45 minute recording plays when the source
+        // audio reaches it, not where the encoder's rounded rate puts it.
+        let time_map = TimeMap::new().with_frame_rate(fps);
+        let count = (45.0 * 60.0 * fps) as usize;
+        let audio_ticks = count as f64 / fps * f64::from(TIME_BASE.invert());
+        let ticks = presentation_ticks(None, count, &time_map);
+        assert!(
+            (ticks as f64 - audio_ticks).abs() <= 1.0,
+            "{ticks} vs {audio_ticks}"
+        );
+        let (num, den) = frame_rate(fps);
+        let rounded = count as f64 * den as f64 / num as f64 * f64::from(TIME_BASE.invert());
+        assert!((rounded - audio_ticks).abs() > 1.0);
+    }
+
+    #[test]
+    fn test_roundtrip_preserves_variable_frame_timing() {
+        use crate::video::infrastructure::ffmpeg_reader::FfmpegReader;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vfr.mkv");
+        let times = [0.0, 0.04, 0.2, 0.25, 0.6];
+
+        let mut writer = FfmpegWriter::new();
+        writer.open(&path, &metadata(160, 120, 30.0)).unwrap();
+        for (i, &t) in times.iter().enumerate() {
+            writer
+                .write(&solid_frame(i, 160, 120, 128).with_timestamp(t))
+                .unwrap();
+        }
+        writer.close().unwrap();
+
+        let mut reader = FfmpegReader::new();
+        reader.open(&path).unwrap();
+        let read: Vec<f64> = reader
+            .frames()
+            .map(|f| f.unwrap().timestamp().unwrap())
+            .collect();
+        assert_eq!(read.len(), times.len());
+        for (got, want) in read.iter().zip(times) {
+            assert!((got - want).abs() < 2e-3, "{got} vs {want}");
+        }
+    }
+
+    #[rstest]
+    #[case(ColorMatrix::Bt601, ColorRange::Limited)]
+    #[case(ColorMatrix::Bt709, ColorRange::Limited)]
+    #[case(ColorMatrix::Bt709, ColorRange::Full)]
+    fn test_roundtrip_preserves_color(#[case] matrix: ColorMatrix, #[case] range: ColorRange) {
+        use crate::video::infrastructure::ffmpeg_reader::FfmpegReader;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("color.mp4");
+        let meta = VideoMetadata {
+            color: ColorSpace::new(matrix, range),
+            ..metadata(160, 120, 30.0)
+        };
+        // Saturated enough that a BT.601/709 mix-up shifts it well past
+        // the tolerance.
+        let rgb = [200u8, 60, 30];
+        let data = rgb.repeat(160 * 120);
+
+        let mut writer = FfmpegWriter::new();
+        writer.open(&path, &meta).unwrap();
+        for i in 0..3 {
+            writer
+                .write(&Frame::new(data.clone(), 160, 120, 3, i))
+                .unwrap();
+        }
+        writer.close().unwrap();
+
+        let mut reader = FfmpegReader::new();
+        let read_meta = reader.open(&path).unwrap();
+        assert_eq!(read_meta.color, meta.color);
+
+        let frame = reader.frames().next().unwrap().unwrap();
+        let center = ((60 * 160 + 80) * 3) as usize;
+        let pixel = &frame.data()[center..center + 3];
+        for (got, want) in pixel.iter().zip(rgb) {
+            assert!(
+                (*got as i32 - want as i32).abs() <= 3,
+                "pixel {pixel:?} should be within 3 of {rgb:?}"
+            );
+        }
+    }
+
     #[test]
     fn test_skip_audio_passthrough_setter() {
         let mut writer = FfmpegWriter::new();
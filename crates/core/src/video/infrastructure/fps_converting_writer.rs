@@ -0,0 +1,275 @@
+use std::path::Path;
+
+use crate::shared::frame::Frame;
+use crate::shared::video_metadata::VideoMetadata;
+use crate::video::domain::video_writer::VideoWriter;
+
+/// How output frames are built when the frame rate changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FpsConversionMode {
+    /// Repeat or skip source frames, taking the latest one at or before
+    /// each output timestamp.
+    DropDuplicate,
+    /// Blend the two source frames surrounding each output timestamp,
+    /// weighted by distance. Smoother, at the cost of ghosting on motion.
+    Blend,
+}
+
+/// Decorator that resamples frames to a target frame rate before handing
+/// them to the inner writer.
+///
+/// Output frame `m` sits at `m / target_fps` seconds and is built from the
+/// source frames around that time, so the output keeps the source duration
+/// and stays in sync with passthrough or processed audio. Frames are counted
+/// as they arrive rather than by index, and output frames are stamped with
+/// their own time so the encoder spaces them evenly.
+pub struct FpsConvertingWriter {
+    inner: Box<dyn VideoWriter>,
+    target_fps: f64,
+    mode: FpsConversionMode,
+    source_fps: f64,
+    received: usize,
+    emitted: usize,
+    previous: Option<Frame>,
+}
+
+impl FpsConvertingWriter {
+    pub fn new(inner: Box<dyn VideoWriter>, target_fps: f64, mode: FpsConversionMode) -> Self {
+        Self {
+            inner,
+            target_fps,
+            mode,
+            source_fps: target_fps,
+            received: 0,
+            emitted: 0,
+            previous: None,
+        }
+    }
+
+    /// Position of output frame `m` on the source frame timeline.
+    fn source_position(&self, output_index: usize) -> f64 {
+        output_index as f64 * self.source_fps / self.target_fps
+    }
+
+    fn emit(&mut self, frame: Frame) -> Result<(), Box<dyn std::error::Error>> {
+        let timestamp = self.emitted as f64 / self.target_fps;
+        self.inner
+            .write(&frame.with_index(self.emitted).with_timestamp(timestamp))?;
+        self.emitted += 1;
+        Ok(())
+    }
+}
+
+impl VideoWriter for FpsConvertingWriter {
+    fn open(
+        &mut self,
+        path: &Path,
+        metadata: &VideoMetadata,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if self.target_fps <= 0.0 {
+            return Err(format!("Invalid output frame rate: {}", self.target_fps).into());
+        }
+        self.source_fps = if metadata.fps > 0.0 {
+            metadata.fps
+        } else {
+            self.target_fps
+        };
+        self.received = 0;
+        self.emitted = 0;
+        self.previous = None;
+
+        let duration = metadata.total_frames as f64 / self.source_fps;
+        let converted = VideoMetadata {
+            fps: self.target_fps,
+            total_frames: (duration * self.target_fps).round() as usize,
+            ..metadata.clone()
+        };
+        self.inner.open(path, &converted)
+    }
+
+    fn write(&mut self, frame: &Frame) -> Result<(), Box<dyn std::error::Error>> {
+        let n = self.received;
+        self.received += 1;
+
+        match self.mode {
+            FpsConversionMode::DropDuplicate => {
+                // Every output timestamp in [n, n + 1) shows this frame.
+                while self.source_position(self.emitted) < (n + 1) as f64 {
+                    self.emit(frame.clone())?;
+                }
+            }
+            FpsConversionMode::Blend => {
+                // Output timestamps in [n - 1, n) fall between the previous
+                // frame and this one.
+                if let Some(previous) = self.previous.take() {
+                    while self.source_position(self.emitted) < n as f64 {
+                        let weight = self.source_position(self.emitted) - (n - 1) as f64;
+                        self.emit(blend(&previous, frame, weight as f32))?;
+                    }
+                }
+                self.previous = Some(frame.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        // Blend mode holds the last frame for timestamps after it.
+        if let Some(last) = self.previous.take() {
+            while self.source_position(self.emitted) < self.received as f64 {
+                self.emit(last.clone())?;
+            }
+        }
+        self.inner.close()
+    }
+}
+
+/// Linear mix of two same-sized frames: `weight` 0 gives `a`, 1 gives `b`.
+fn blend(a: &Frame, b: &Frame, weight: f32) -> Frame {
+    if a.data().len() != b.data().len() {
+        return b.clone();
+    }
+    let w = weight.clamp(0.0, 1.0);
+    let data = a
+        .data()
+        .iter()
+        .zip(b.data())
+        .map(|(&x, &y)| (x as f32 * (1.0 - w) + y as f32 * w).round() as u8)
+        .collect();
+    Frame::new(data, b.width(), b.height(), b.channels(), b.index())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::color_space::ColorSpace;
+    use std::sync::{Arc, Mutex};
+
+    struct StubWriter {
+        opened: Arc<Mutex<Option<VideoMetadata>>>,
+        written: Arc<Mutex<Vec<Frame>>>,
+    }
+
+    fn metadata(fps: f64, total_frames: usize) -> VideoMetadata {
+        VideoMetadata {
+            width: 1,
+            height: 1,
+            fps,
+            total_frames,
+            codec: String::new(),
+            source_path: None,
+            rotation: 0,
+            color: ColorSpace::default(),
+        }
+    }
+
+    impl VideoWriter for StubWriter {
+        fn open(
+            &mut self,
+            _: &Path,
+            metadata: &VideoMetadata,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            *self.opened.lock().unwrap() = Some(metadata.clone());
+            Ok(())
+        }
+
+        fn write(&mut self, frame: &Frame) -> Result<(), Box<dyn std::error::Error>> {
+            self.written.lock().unwrap().push(frame.clone());
+            Ok(())
+        }
+
+        fn close(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+    }
+
+    /// Converts `count` one-pixel frames whose value is `index * 10`,
+    /// returning the output metadata and pixel values.
+    fn convert(
+        source_fps: f64,
+        target_fps: f64,
+        count: usize,
+        mode: FpsConversionMode,
+    ) -> (VideoMetadata, Vec<u8>) {
+        let opened = Arc::new(Mutex::new(None));
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let inner = StubWriter {
+            opened: opened.clone(),
+            written: written.clone(),
+        };
+        let mut writer = FpsConvertingWriter::new(Box::new(inner), target_fps, mode);
+        writer
+            .open(Path::new("out.mp4"), &metadata(source_fps, count))
+            .unwrap();
+        for i in 0..count {
+            let value = (i * 10) as u8;
+            writer
+                .write(&Frame::new(vec![value; 3], 1, 1, 3, i))
+                .unwrap();
+        }
+        writer.close().unwrap();
+
+        let written = written.lock().unwrap();
+        for (i, frame) in written.iter().enumerate() {
+            assert_eq!(frame.index(), i);
+            assert_eq!(frame.timestamp(), Some(i as f64 / target_fps));
+        }
+        let values = written.iter().map(|f| f.data()[0]).collect();
+        let opened = opened.lock().unwrap().clone().unwrap();
+        (opened, values)
+    }
+
+    #[test]
+    fn test_halving_drops_every_other_frame() {
+        let (meta, values) = convert(60.0, 30.0, 6, FpsConversionMode::DropDuplicate);
+        assert_eq!(meta.fps, 30.0);
+        assert_eq!(meta.total_frames, 3);
+        assert_eq!(values, vec![0, 20, 40]);
+    }
+
+    #[test]
+    fn test_doubling_duplicates_frames() {
+        let (meta, values) = convert(30.0, 60.0, 3, FpsConversionMode::DropDuplicate);
+        assert_eq!(meta.total_frames, 6);
+        assert_eq!(values, vec![0, 0, 10, 10, 20, 20]);
+    }
+
+    #[test]
+    fn test_non_integer_ratio_preserves_duration() {
+        // 50 fps → 30 fps over 2 seconds.
+        let (meta, values) = convert(50.0, 30.0, 100, FpsConversionMode::DropDuplicate);
+        assert_eq!(meta.total_frames, 60);
+        assert_eq!(values.len(), 60);
+    }
+
+    #[test]
+    fn test_blend_mixes_neighbouring_frames() {
+        // 30 → 20 fps: output frames land at source positions 0, 1.5, 3.
+        let (_, values) = convert(30.0, 20.0, 4, FpsConversionMode::Blend);
+        assert_eq!(values, vec![0, 15, 30]);
+    }
+
+    #[test]
+    fn test_blend_holds_last_frame_when_upsampling() {
+        let (_, values) = convert(30.0, 60.0, 2, FpsConversionMode::Blend);
+        assert_eq!(values, vec![0, 5, 10, 10]);
+    }
+
+    #[test]
+    fn test_same_rate_is_passthrough() {
+        let (_, values) = convert(30.0, 30.0, 3, FpsConversionMode::Blend);
+        assert_eq!(values, vec![0, 10, 20]);
+    }
+
+    #[test]
+    fn test_rejects_non_positive_target() {
+        let inner = StubWriter {
+            opened: Arc::new(Mutex::new(None)),
+            written: Arc::new(Mutex::new(Vec::new())),
+        };
+        let mut writer = FpsConvertingWriter::new(Box::new(inner), 0.0, FpsConversionMode::Blend);
+        assert!(writer
+            .open(Path::new("out.mp4"), &metadata(30.0, 1))
+            .is_err());
+    }
+}
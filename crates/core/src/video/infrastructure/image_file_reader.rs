@@ -1,5 +1,6 @@
 use std::path::Path;
 
+use crate::shared::color_space::ColorSpace;
 use crate::shared::frame::Frame;
 use crate::shared::video_metadata::VideoMetadata;
 use crate::video::domain::video_reader::VideoReader;
@@ -7,12 +8,17 @@ use crate::video::domain::video_reader::VideoReader;
 /// Adapts a single image file to the [`VideoReader`] interface.
 ///
 /// Treats the image as a one-frame video with `fps=0` and `total_frames=1`,
-/// allowing the pipeline to process images and videos uniformly.
+/// allowing the pipeline to process images and videos uniformly. Seeking
+/// to frame 0 rewinds it, so the image can be read again without decoding
+/// it twice.
 ///
 /// Uses ffmpeg for decoding, which is significantly faster than the pure-Rust
 /// `image` crate for large images (e.g. 4032x3024 JPEG).
 pub struct ImageFileReader {
     frame: Option<Frame>,
+    /// Whether `frames()` has yielded the image since it was opened or
+    /// rewound.
+    read: bool,
     metadata: Option<VideoMetadata>,
 }
 
@@ -24,6 +30,7 @@ impl ImageFileReader {
     pub fn new() -> Self {
         Self {
             frame: None,
+            read: false,
             metadata: None,
         }
     }
@@ -131,6 +138,7 @@ impl VideoReader for ImageFileReader {
             video_stream_index,
         )?;
         self.frame = Some(frame);
+        self.read = false;
 
         let metadata = VideoMetadata {
             width,
@@ -140,6 +148,7 @@ impl VideoReader for ImageFileReader {
             codec: String::new(),
             source_path: Some(path.to_path_buf()),
             rotation: 0,
+            color: ColorSpace::default(),
         };
         self.metadata = Some(metadata.clone());
         Ok(metadata)
@@ -151,11 +160,27 @@ impl VideoReader for ImageFileReader {
         if self.frame.is_none() {
             return Box::new(std::iter::once(Err("ImageFileReader: not opened".into())));
         }
-        Box::new(self.frame.take().into_iter().map(Ok))
+        if self.read {
+            return Box::new(std::iter::empty());
+        }
+        self.read = true;
+        Box::new(self.frame.clone().into_iter().map(Ok))
+    }
+
+    fn seek(&mut self, frame_index: usize) -> Result<(), Box<dyn std::error::Error>> {
+        if self.frame.is_none() {
+            return Err("ImageFileReader: not opened".into());
+        }
+        if frame_index > 0 {
+            return Err(format!("ImageFileReader: an image has no frame {frame_index}").into());
+        }
+        self.read = false;
+        Ok(())
     }
 
     fn close(&mut self) {
         self.frame = None;
+        self.read = false;
         self.metadata = None;
     }
 }
@@ -241,6 +266,47 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_frames_yields_image_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_test_image(dir.path(), 100, 80);
+        let mut reader = ImageFileReader::new();
+        reader.open(&path).unwrap();
+
+        assert_eq!(reader.frames().count(), 1);
+        assert_eq!(reader.frames().count(), 0);
+    }
+
+    #[test]
+    fn test_seek_to_start_rewinds() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_test_image(dir.path(), 100, 80);
+        let mut reader = ImageFileReader::new();
+        reader.open(&path).unwrap();
+        reader.frames().for_each(drop);
+
+        reader.seek(0).unwrap();
+
+        let frame = reader.frames().next().unwrap().unwrap();
+        assert_eq!(frame.index(), 0);
+        assert_eq!(frame.data()[0], 50);
+    }
+
+    #[test]
+    fn test_seek_past_image_returns_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_test_image(dir.path(), 100, 80);
+        let mut reader = ImageFileReader::new();
+        reader.open(&path).unwrap();
+        assert!(reader.seek(1).is_err());
+    }
+
+    #[test]
+    fn test_seek_without_open_returns_error() {
+        let mut reader = ImageFileReader::new();
+        assert!(reader.seek(0).is_err());
+    }
+
     #[test]
     fn test_close_idempotent() {
         let dir = tempfile::tempdir().unwrap();
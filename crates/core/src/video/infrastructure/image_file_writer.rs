@@ -1,14 +1,127 @@
+use std::io::Cursor;
 use std::path::Path;
 
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+
 use crate::shared::frame::Frame;
 use crate::video::domain::image_writer::ImageWriter;
 
+/// Matches the `image` crate's own JPEG default, so output is unchanged
+/// unless a quality is requested.
+pub const DEFAULT_JPEG_QUALITY: u8 = 75;
+
+/// Encodings the writer can produce, independent of the output file extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageOutputFormat {
+    Jpeg,
+    Png,
+    Webp,
+    Bmp,
+    Tiff,
+}
+
+impl ImageOutputFormat {
+    /// Parses a file extension or format name (case-insensitive).
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_lowercase().as_str() {
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "png" => Some(Self::Png),
+            "webp" => Some(Self::Webp),
+            "bmp" => Some(Self::Bmp),
+            "tif" | "tiff" => Some(Self::Tiff),
+            _ => None,
+        }
+    }
+
+    /// Canonical file extension for this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Jpeg => "jpg",
+            Self::Png => "png",
+            Self::Webp => "webp",
+            Self::Bmp => "bmp",
+            Self::Tiff => "tiff",
+        }
+    }
+
+    fn image_format(self) -> image::ImageFormat {
+        match self {
+            Self::Jpeg => image::ImageFormat::Jpeg,
+            Self::Png => image::ImageFormat::Png,
+            Self::Webp => image::ImageFormat::WebP,
+            Self::Bmp => image::ImageFormat::Bmp,
+            Self::Tiff => image::ImageFormat::Tiff,
+        }
+    }
+}
+
+/// PNG deflate effort. PNG is lossless, so this trades only speed for size.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PngCompression {
+    Fast,
+    #[default]
+    Default,
+    Best,
+}
+
+impl PngCompression {
+    fn compression_type(self) -> CompressionType {
+        match self {
+            Self::Fast => CompressionType::Fast,
+            Self::Default => CompressionType::Default,
+            Self::Best => CompressionType::Best,
+        }
+    }
+}
+
 /// Writes a single frame to an image file using the `image` crate.
-pub struct ImageFileWriter;
+///
+/// The encoding follows the output path's extension unless a format is set
+/// with `with_format`. JPEG quality and PNG compression only apply when the
+/// resolved format is JPEG or PNG respectively.
+///
+/// Output is encoded from the frame's pixels alone, so nothing from the
+/// source file (EXIF, embedded thumbnails or previews) is carried over. The
+/// encoded bytes are checked for metadata that could hold a second image
+/// before they reach disk, and the write fails if any is found.
+pub struct ImageFileWriter {
+    format: Option<ImageOutputFormat>,
+    jpeg_quality: u8,
+    png_compression: PngCompression,
+}
 
 impl ImageFileWriter {
     pub fn new() -> Self {
-        Self
+        Self {
+            format: None,
+            jpeg_quality: DEFAULT_JPEG_QUALITY,
+            png_compression: PngCompression::Default,
+        }
+    }
+
+    pub fn with_format(mut self, format: ImageOutputFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// JPEG quality from 1 (smallest) to 100 (best); out-of-range values are clamped.
+    pub fn with_jpeg_quality(mut self, quality: u8) -> Self {
+        self.jpeg_quality = quality.clamp(1, 100);
+        self
+    }
+
+    pub fn with_png_compression(mut self, compression: PngCompression) -> Self {
+        self.png_compression = compression;
+        self
+    }
+
+    fn resolve_format(&self, path: &Path) -> Option<ImageOutputFormat> {
+        self.format.or_else(|| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .and_then(ImageOutputFormat::from_extension)
+        })
     }
 }
 
@@ -38,14 +151,147 @@ impl ImageWriter for ImageFileWriter {
             img
         };
 
-        img.save(path)?;
+        let mut encoded = Cursor::new(Vec::new());
+        let format = self.resolve_format(path);
+        match format {
+            Some(ImageOutputFormat::Jpeg) => {
+                img.write_with_encoder(JpegEncoder::new_with_quality(
+                    &mut encoded,
+                    self.jpeg_quality,
+                ))?;
+            }
+            Some(ImageOutputFormat::Png) => {
+                img.write_with_encoder(PngEncoder::new_with_quality(
+                    &mut encoded,
+                    self.png_compression.compression_type(),
+                    FilterType::Adaptive,
+                ))?;
+            }
+            Some(format) => img.write_to(&mut encoded, format.image_format())?,
+            None => img.write_to(&mut encoded, image::ImageFormat::from_path(path)?)?,
+        }
+        let bytes = encoded.into_inner();
+        if let Some(what) = format.and_then(|f| embedded_preview(f, &bytes)) {
+            return Err(format!(
+                "Refusing to write {}: the encoded image carries {what}, which could show unblurred content",
+                path.display()
+            )
+            .into());
+        }
+        std::fs::write(path, bytes)?;
         Ok(())
     }
 }
 
+/// Describes the first place in an encoded image that could hold a second,
+/// unblurred picture: an EXIF block (thumbnails live there), a JFIF
+/// thumbnail, an embedded JPEG, text chunks, or an extra TIFF directory.
+/// Returns `None` for a file that holds only the main image.
+fn embedded_preview(format: ImageOutputFormat, bytes: &[u8]) -> Option<&'static str> {
+    match format {
+        ImageOutputFormat::Jpeg => jpeg_embedded_preview(bytes),
+        ImageOutputFormat::Png => png_embedded_preview(bytes),
+        ImageOutputFormat::Webp => webp_embedded_preview(bytes),
+        ImageOutputFormat::Tiff => tiff_embedded_preview(bytes),
+        ImageOutputFormat::Bmp => None,
+    }
+}
+
+fn jpeg_embedded_preview(bytes: &[u8]) -> Option<&'static str> {
+    // Byte stuffing keeps FF D8 out of entropy-coded data, so a second
+    // start-of-image marker can only be another JPEG.
+    if bytes.windows(2).filter(|w| *w == [0xFF, 0xD8]).count() > 1 {
+        return Some("an embedded JPEG");
+    }
+    let mut pos = 2;
+    while pos + 4 <= bytes.len() && bytes[pos] == 0xFF {
+        let marker = bytes[pos + 1];
+        let len = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+        match marker {
+            // Start of scan: only image data follows.
+            0xDA => break,
+            // A bare JFIF header is 16 bytes; anything longer has a thumbnail.
+            0xE0 if len > 16 => return Some("a JFIF thumbnail"),
+            0xE1..=0xEF => return Some("an APP segment (EXIF, XMP or similar)"),
+            _ => {}
+        }
+        pos += 2 + len;
+    }
+    None
+}
+
+fn png_embedded_preview(bytes: &[u8]) -> Option<&'static str> {
+    let mut pos = 8;
+    while pos + 8 <= bytes.len() {
+        let len = u32::from_be_bytes(bytes[pos..pos + 4].try_into().unwrap()) as usize;
+        match &bytes[pos + 4..pos + 8] {
+            b"eXIf" => return Some("an EXIF chunk"),
+            b"tEXt" | b"zTXt" | b"iTXt" => return Some("a text chunk"),
+            _ => {}
+        }
+        pos += 12 + len;
+    }
+    None
+}
+
+fn webp_embedded_preview(bytes: &[u8]) -> Option<&'static str> {
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let len = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into().unwrap()) as usize;
+        match &bytes[pos..pos + 4] {
+            b"EXIF" => return Some("an EXIF chunk"),
+            b"XMP " => return Some("an XMP chunk"),
+            _ => {}
+        }
+        pos += 8 + len + len % 2;
+    }
+    None
+}
+
+fn tiff_embedded_preview(bytes: &[u8]) -> Option<&'static str> {
+    const SUB_IFDS: u16 = 330;
+    const JPEG_THUMBNAIL: u16 = 513;
+    const EXIF_IFD: u16 = 34665;
+
+    let big_endian = bytes.get(..2)? == b"MM";
+    let u16_at = |pos: usize| {
+        let b: [u8; 2] = bytes.get(pos..pos + 2)?.try_into().ok()?;
+        Some(if big_endian {
+            u16::from_be_bytes(b)
+        } else {
+            u16::from_le_bytes(b)
+        })
+    };
+    let u32_at = |pos: usize| {
+        let b: [u8; 4] = bytes.get(pos..pos + 4)?.try_into().ok()?;
+        Some(if big_endian {
+            u32::from_be_bytes(b)
+        } else {
+            u32::from_le_bytes(b)
+        })
+    };
+
+    let ifd = u32_at(4)? as usize;
+    let entries = u16_at(ifd)? as usize;
+    for i in 0..entries {
+        match u16_at(ifd + 2 + i * 12)? {
+            SUB_IFDS => return Some("sub-images"),
+            JPEG_THUMBNAIL => return Some("a JPEG thumbnail"),
+            EXIF_IFD => return Some("an EXIF directory"),
+            _ => {}
+        }
+    }
+    // Thumbnails conventionally sit in a second directory after the first.
+    if u32_at(ifd + 2 + entries * 12)? != 0 {
+        return Some("a second image directory");
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     fn make_frame(width: u32, height: u32, r: u8, g: u8, b: u8) -> Frame {
         let mut data = Vec::with_capacity((width * height * 3) as usize);
@@ -97,6 +343,185 @@ mod tests {
         assert_eq!(img.height(), 64);
     }
 
+    fn noisy_frame(width: u32, height: u32) -> Frame {
+        let data = (0..width * height * 3)
+            .map(|i| (i.wrapping_mul(2654435761) >> 24) as u8)
+            .collect();
+        Frame::new(data, width, height, 3, 0)
+    }
+
+    #[test]
+    fn test_jpeg_quality_affects_file_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let low = dir.path().join("low.jpg");
+        let high = dir.path().join("high.jpg");
+        let frame = noisy_frame(64, 64);
+
+        ImageFileWriter::new()
+            .with_jpeg_quality(10)
+            .write(&low, &frame, None)
+            .unwrap();
+        ImageFileWriter::new()
+            .with_jpeg_quality(95)
+            .write(&high, &frame, None)
+            .unwrap();
+
+        let low_size = std::fs::metadata(&low).unwrap().len();
+        let high_size = std::fs::metadata(&high).unwrap().len();
+        assert!(low_size < high_size);
+    }
+
+    #[test]
+    fn test_format_override_ignores_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.jpg");
+        let frame = make_frame(16, 16, 10, 20, 30);
+
+        ImageFileWriter::new()
+            .with_format(ImageOutputFormat::Png)
+            .write(&path, &frame, None)
+            .unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(&bytes[..4], b"\x89PNG");
+    }
+
+    #[test]
+    fn test_png_compression_is_lossless() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("best.png");
+        let frame = make_frame(20, 20, 1, 2, 3);
+
+        ImageFileWriter::new()
+            .with_png_compression(PngCompression::Best)
+            .write(&path, &frame, None)
+            .unwrap();
+
+        let img = image::open(&path).unwrap().to_rgb8();
+        assert_eq!(img.get_pixel(5, 5).0, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_jpeg_quality_is_clamped() {
+        assert_eq!(ImageFileWriter::new().with_jpeg_quality(0).jpeg_quality, 1);
+        assert_eq!(
+            ImageFileWriter::new().with_jpeg_quality(200).jpeg_quality,
+            100
+        );
+    }
+
+    #[rstest]
+    #[case("jpg", Some(ImageOutputFormat::Jpeg))]
+    #[case("JPEG", Some(ImageOutputFormat::Jpeg))]
+    #[case("png", Some(ImageOutputFormat::Png))]
+    #[case("webp", Some(ImageOutputFormat::Webp))]
+    #[case("tif", Some(ImageOutputFormat::Tiff))]
+    #[case("gif", None)]
+    fn test_format_from_extension(#[case] ext: &str, #[case] expected: Option<ImageOutputFormat>) {
+        assert_eq!(ImageOutputFormat::from_extension(ext), expected);
+    }
+
+    /// A JPEG of `main` carrying `thumbnail` as an EXIF thumbnail, the way
+    /// cameras and phones save photos.
+    fn jpeg_with_exif_thumbnail(main: &Frame, thumbnail: &[u8]) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        image::RgbImage::from_raw(main.width(), main.height(), main.data().to_vec())
+            .unwrap()
+            .write_with_encoder(JpegEncoder::new(&mut encoded))
+            .unwrap();
+        let mut payload = b"Exif\0\0".to_vec();
+        payload.extend_from_slice(thumbnail);
+        let mut app1 = vec![0xFF, 0xE1];
+        app1.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+        app1.extend_from_slice(&payload);
+        encoded.splice(2..2, app1);
+        encoded
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|w| w == needle)
+    }
+
+    #[rstest]
+    #[case(ImageOutputFormat::Jpeg)]
+    #[case(ImageOutputFormat::Png)]
+    #[case(ImageOutputFormat::Webp)]
+    #[case(ImageOutputFormat::Bmp)]
+    #[case(ImageOutputFormat::Tiff)]
+    fn test_output_holds_only_the_main_image(#[case] format: ImageOutputFormat) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format!("out.{}", format.extension()));
+        ImageFileWriter::new()
+            .write(&path, &noisy_frame(48, 32), None)
+            .unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(embedded_preview(format, &bytes), None);
+        assert!(!contains(&bytes, b"Exif"));
+        if format != ImageOutputFormat::Jpeg {
+            assert!(!contains(&bytes, &[0xFF, 0xD8, 0xFF]));
+        }
+    }
+
+    #[test]
+    fn test_source_exif_thumbnail_does_not_survive() {
+        use crate::video::domain::video_reader::VideoReader;
+        use crate::video::infrastructure::image_file_reader::ImageFileReader;
+
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("photo.jpg");
+        let output = dir.path().join("blurred.jpg");
+        let mut thumbnail = Vec::new();
+        let small = noisy_frame(40, 40);
+        image::RgbImage::from_raw(40, 40, small.data().to_vec())
+            .unwrap()
+            .write_with_encoder(JpegEncoder::new(&mut thumbnail))
+            .unwrap();
+        let photo = jpeg_with_exif_thumbnail(&noisy_frame(64, 48), &thumbnail);
+        assert!(embedded_preview(ImageOutputFormat::Jpeg, &photo).is_some());
+        std::fs::write(&source, &photo).unwrap();
+
+        let mut reader = ImageFileReader::new();
+        reader.open(&source).unwrap();
+        let frame = reader.frames().next().unwrap().unwrap();
+        ImageFileWriter::new().write(&output, &frame, None).unwrap();
+
+        let bytes = std::fs::read(&output).unwrap();
+        assert_eq!(embedded_preview(ImageOutputFormat::Jpeg, &bytes), None);
+        assert!(!contains(&bytes, b"Exif"));
+        // The thumbnail's scan data is the unblurred picture itself.
+        assert!(!contains(&bytes, &thumbnail[thumbnail.len() - 64..]));
+    }
+
+    #[test]
+    fn test_detects_jfif_thumbnail_and_png_text() {
+        let mut jfif = vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x20];
+        jfif.extend_from_slice(&[0; 30]);
+        jfif.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]);
+        assert_eq!(jpeg_embedded_preview(&jfif), Some("a JFIF thumbnail"));
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png.extend_from_slice(&[0, 0, 0, 1]);
+        png.extend_from_slice(b"tEXtx\0\0\0\0");
+        assert_eq!(png_embedded_preview(&png), Some("a text chunk"));
+    }
+
+    #[test]
+    fn test_detects_second_tiff_directory() {
+        // Little-endian header, one directory at offset 8 with no entries,
+        // pointing to another at offset 14.
+        let mut tiff = b"II*\0".to_vec();
+        tiff.extend_from_slice(&8u32.to_le_bytes());
+        tiff.extend_from_slice(&0u16.to_le_bytes());
+        tiff.extend_from_slice(&14u32.to_le_bytes());
+        tiff.extend_from_slice(&0u16.to_le_bytes());
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        assert_eq!(
+            tiff_embedded_preview(&tiff),
+            Some("a second image directory")
+        );
+    }
+
     #[test]
     fn test_write_invalid_path_returns_error() {
         let frame = make_frame(10, 10, 0, 0, 0);
@@ -0,0 +1,144 @@
+use std::path::Path;
+
+use crate::video::domain::source_metadata::{sort_by_category, MetadataField};
+
+/// Packets read while looking for the first decodable frame.
+const MAX_PROBE_PACKETS: usize = 64;
+
+/// Lists every metadata tag in a video or image, read-only, sorted by
+/// category.
+///
+/// Reads the container and stream tags, then decodes the first video frame,
+/// since ffmpeg exposes an image's EXIF block (camera, GPS, capture time)
+/// on the decoded frame rather than the container.
+pub fn read_source_metadata(path: &Path) -> Result<Vec<MetadataField>, Box<dyn std::error::Error>> {
+    ffmpeg_next::init()?;
+    let mut ictx = ffmpeg_next::format::input(path)?;
+
+    let mut fields: Vec<MetadataField> = ictx
+        .metadata()
+        .iter()
+        .map(|(key, value)| MetadataField::new("container", key, value))
+        .collect();
+    for stream in ictx.streams() {
+        let scope = format!(
+            "{} stream {}",
+            medium_name(stream.parameters().medium()),
+            stream.index()
+        );
+        fields.extend(
+            stream
+                .metadata()
+                .iter()
+                .map(|(key, value)| MetadataField::new(scope.as_str(), key, value)),
+        );
+    }
+    fields.extend(
+        first_frame_tags(&mut ictx)
+            .into_iter()
+            .map(|(key, value)| MetadataField::new("image", key, value)),
+    );
+
+    sort_by_category(&mut fields);
+    Ok(fields)
+}
+
+fn medium_name(medium: ffmpeg_next::media::Type) -> &'static str {
+    match medium {
+        ffmpeg_next::media::Type::Video => "video",
+        ffmpeg_next::media::Type::Audio => "audio",
+        ffmpeg_next::media::Type::Subtitle => "subtitle",
+        ffmpeg_next::media::Type::Data => "data",
+        _ => "other",
+    }
+}
+
+/// Tags attached to the first decoded video frame. Decode failures yield
+/// no tags rather than an error, as the container tags are still useful.
+fn first_frame_tags(ictx: &mut ffmpeg_next::format::context::Input) -> Vec<(String, String)> {
+    let Some(stream) = ictx.streams().best(ffmpeg_next::media::Type::Video) else {
+        return Vec::new();
+    };
+    let stream_index = stream.index();
+    let Ok(mut decoder) =
+        ffmpeg_next::codec::context::Context::from_parameters(stream.parameters())
+            .and_then(|ctx| ctx.decoder().video())
+    else {
+        return Vec::new();
+    };
+
+    let mut frame = ffmpeg_next::util::frame::video::Video::empty();
+    let mut decoded = false;
+    for (stream, packet) in ictx.packets().take(MAX_PROBE_PACKETS) {
+        if stream.index() != stream_index || decoder.send_packet(&packet).is_err() {
+            continue;
+        }
+        if decoder.receive_frame(&mut frame).is_ok() {
+            decoded = true;
+            break;
+        }
+    }
+    if !decoded {
+        // Single-image files only emit their frame once the decoder is flushed.
+        decoded = decoder.send_eof().is_ok() && decoder.receive_frame(&mut frame).is_ok();
+    }
+    if !decoded {
+        return Vec::new();
+    }
+    frame
+        .metadata()
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::color_space::ColorSpace;
+    use crate::shared::frame::Frame;
+    use crate::shared::video_metadata::VideoMetadata;
+    use crate::video::domain::source_metadata::MetadataCategory;
+    use crate::video::domain::video_writer::VideoWriter;
+    use crate::video::infrastructure::ffmpeg_writer::FfmpegWriter;
+
+    #[test]
+    fn test_reads_encoder_tag_of_written_video() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.mp4");
+        let metadata = VideoMetadata {
+            width: 64,
+            height: 48,
+            fps: 10.0,
+            total_frames: 0,
+            codec: String::new(),
+            source_path: None,
+            rotation: 0,
+            color: ColorSpace::default(),
+        };
+        let mut writer = FfmpegWriter::new();
+        writer.open(&path, &metadata).unwrap();
+        for i in 0..3 {
+            writer
+                .write(&Frame::new(vec![90; 64 * 48 * 3], 64, 48, 3, i))
+                .unwrap();
+        }
+        writer.close().unwrap();
+
+        let fields = read_source_metadata(&path).unwrap();
+        assert!(
+            fields
+                .iter()
+                .any(|f| f.scope == "container" && f.category == MetadataCategory::Software),
+            "{fields:?}"
+        );
+        assert!(fields
+            .iter()
+            .all(|f| f.category != MetadataCategory::Location));
+    }
+
+    #[test]
+    fn test_missing_file_is_an_error() {
+        assert!(read_source_metadata(Path::new("/nonexistent/input.mp4")).is_err());
+    }
+}
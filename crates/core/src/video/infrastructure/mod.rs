@@ -1,6 +1,16 @@
+pub mod cut_range_reader;
+pub mod disk_space;
+pub mod fan_out_writer;
 pub mod ffmpeg_audio_reader;
 pub mod ffmpeg_audio_writer;
+pub mod ffmpeg_color;
+pub mod ffmpeg_hw_decode;
 pub mod ffmpeg_reader;
 pub mod ffmpeg_writer;
+pub mod fps_converting_writer;
 pub mod image_file_reader;
 pub mod image_file_writer;
+pub mod metadata_inspector;
+pub mod parted_writer;
+pub mod screen_capture_reader;
+pub mod segmented_output;
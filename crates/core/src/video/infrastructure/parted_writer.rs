@@ -0,0 +1,363 @@
+use std::path::{Path, PathBuf};
+
+use crate::shared::frame::Frame;
+use crate::shared::video_metadata::VideoMetadata;
+use crate::shared::warnings::Warnings;
+use crate::video::domain::video_writer::VideoWriter;
+use crate::video::infrastructure::ffmpeg_writer::{
+    mux_audio_from_source, set_stream_display_matrix, setup_audio_passthrough,
+};
+
+/// Builds the writer for each part.
+pub type PartWriterFactory = Box<dyn Fn() -> Box<dyn VideoWriter> + Send>;
+
+/// Called when a part is finished, with the first frame of the next part
+/// and every finished part so far.
+pub type OnPartFinished =
+    Box<dyn FnMut(usize, &[PathBuf]) -> Result<(), Box<dyn std::error::Error>> + Send>;
+
+/// `out.mp4`, 3 → `out.part003.mp4`, next to the output.
+pub fn part_path(output: &Path, part: usize) -> PathBuf {
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = output
+        .extension()
+        .map_or("mp4".into(), |e| e.to_string_lossy());
+    output.with_file_name(format!("{stem}.part{part:03}.{ext}"))
+}
+
+/// Decorator that encodes the video in parts of `part_frames` frames and
+/// joins them into the output on `close`, so an interrupted export keeps
+/// every finished part.
+///
+/// Parts start at frame indices that are multiples of `part_frames`, and
+/// each is encoded by a fresh writer from the factory, without audio.
+/// Joining copies the parts' packets without re-encoding, then copies the
+/// source audio as [`FfmpegWriter`](super::ffmpeg_writer::FfmpegWriter)
+/// does. Parts are deleted once joined.
+///
+/// [`with_finished_parts`](Self::with_finished_parts) continues an
+/// interrupted export: the parts it lists are joined first, and frames
+/// written start where they ended.
+pub struct PartedWriter {
+    new_part: PartWriterFactory,
+    part_frames: usize,
+    parts: Vec<PathBuf>,
+    current: Option<(Box<dyn VideoWriter>, PathBuf)>,
+    /// Index of the first frame after the current part.
+    part_end: usize,
+    output_path: Option<PathBuf>,
+    metadata: Option<VideoMetadata>,
+    on_part_finished: Option<OnPartFinished>,
+    skip_audio_passthrough: bool,
+    warnings: Warnings,
+}
+
+impl PartedWriter {
+    pub fn new(new_part: PartWriterFactory, part_frames: usize) -> Self {
+        Self {
+            new_part,
+            part_frames: part_frames.max(1),
+            parts: Vec::new(),
+            current: None,
+            part_end: 0,
+            output_path: None,
+            metadata: None,
+            on_part_finished: None,
+            skip_audio_passthrough: false,
+            warnings: Warnings::new(),
+        }
+    }
+
+    /// Parts an earlier, interrupted export finished, in order.
+    pub fn with_finished_parts(mut self, parts: Vec<PathBuf>) -> Self {
+        self.parts = parts;
+        self
+    }
+
+    pub fn with_on_part_finished(mut self, callback: OnPartFinished) -> Self {
+        self.on_part_finished = Some(callback);
+        self
+    }
+
+    /// Reports audio copy failures to `warnings` instead of only logging
+    /// them.
+    pub fn with_warnings(mut self, warnings: Warnings) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    pub fn set_skip_audio_passthrough(&mut self, skip: bool) {
+        self.skip_audio_passthrough = skip;
+    }
+
+    fn start_part(&mut self, frame_index: usize) -> Result<(), Box<dyn std::error::Error>> {
+        let output = self
+            .output_path
+            .as_ref()
+            .ok_or("PartedWriter: not opened")?;
+        let metadata = self.metadata.as_ref().ok_or("PartedWriter: not opened")?;
+        let path = part_path(output, self.parts.len());
+        // Audio is copied once, when the parts are joined.
+        let part_metadata = VideoMetadata {
+            source_path: None,
+            ..metadata.clone()
+        };
+        let mut writer = (self.new_part)();
+        writer.open(&path, &part_metadata)?;
+        self.part_end = (frame_index / self.part_frames + 1) * self.part_frames;
+        self.current = Some((writer, path));
+        Ok(())
+    }
+
+    fn finish_part(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some((mut writer, path)) = self.current.take() else {
+            return Ok(());
+        };
+        writer.close()?;
+        self.parts.push(path);
+        Ok(())
+    }
+}
+
+impl VideoWriter for PartedWriter {
+    fn open(
+        &mut self,
+        path: &Path,
+        metadata: &VideoMetadata,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.output_path = Some(path.to_path_buf());
+        self.metadata = Some(metadata.clone());
+        Ok(())
+    }
+
+    fn write(&mut self, frame: &Frame) -> Result<(), Box<dyn std::error::Error>> {
+        if self.current.is_some() && frame.index() >= self.part_end {
+            self.finish_part()?;
+            if let Some(ref mut callback) = self.on_part_finished {
+                callback(self.part_end, &self.parts)?;
+            }
+        }
+        if self.current.is_none() {
+            self.start_part(frame.index())?;
+        }
+        let (writer, _) = self.current.as_mut().unwrap();
+        writer.write(frame)
+    }
+
+    fn close(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.finish_part()?;
+        let (Some(output), Some(metadata)) = (self.output_path.take(), self.metadata.take()) else {
+            return Ok(());
+        };
+        if self.parts.is_empty() {
+            return Err("PartedWriter: no frames were written".into());
+        }
+        join_parts(
+            &self.parts,
+            &output,
+            &metadata,
+            self.skip_audio_passthrough,
+            &self.warnings,
+        )?;
+        for part in self.parts.drain(..) {
+            if let Err(e) = std::fs::remove_file(&part) {
+                log::warn!("Could not delete part {}: {e}", part.display());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Copies the video of `parts` one after another into `output`, then the
+/// source audio.
+///
+/// Parts stamped with source times follow on as they are. Parts whose
+/// times restart at zero are shifted to start where the previous part
+/// ended, so decode times keep increasing.
+pub fn join_parts(
+    parts: &[PathBuf],
+    output: &Path,
+    metadata: &VideoMetadata,
+    skip_audio: bool,
+    warnings: &Warnings,
+) -> Result<(), Box<dyn std::error::Error>> {
+    ffmpeg_next::init()?;
+    let mut octx = ffmpeg_next::format::output(output)?;
+    let video_index = {
+        let first = ffmpeg_next::format::input(&parts[0])?;
+        let ist = first
+            .streams()
+            .best(ffmpeg_next::media::Type::Video)
+            .ok_or_else(|| format!("{} has no video", parts[0].display()))?;
+        let mut ost = octx.add_stream(ffmpeg_next::encoder::find(ffmpeg_next::codec::Id::None))?;
+        ost.set_parameters(ist.parameters());
+        unsafe {
+            (*ost.parameters().as_mut_ptr()).codec_tag = 0;
+        }
+        ost.index()
+    };
+    let (audio_src, audio_ost, audio_tb) = if skip_audio {
+        (None, None, None)
+    } else {
+        setup_audio_passthrough(&mut octx, metadata)?
+    };
+    if metadata.rotation != 0 {
+        set_stream_display_matrix(&mut octx, video_index, metadata.rotation);
+    }
+    octx.write_header()?;
+
+    let out_tb = octx.stream(video_index).unwrap().time_base();
+    let mut last_dts: Option<i64> = None;
+    let mut last_duration: i64 = 1;
+    for part in parts {
+        let mut ictx = ffmpeg_next::format::input(part)?;
+        let (part_index, part_tb) = {
+            let stream = ictx
+                .streams()
+                .best(ffmpeg_next::media::Type::Video)
+                .ok_or_else(|| format!("{} has no video", part.display()))?;
+            (stream.index(), stream.time_base())
+        };
+        let mut offset: Option<i64> = None;
+        for (stream, mut packet) in ictx.packets() {
+            if stream.index() != part_index {
+                continue;
+            }
+            packet.rescale_ts(part_tb, out_tb);
+            let dts = packet.dts().or(packet.pts()).unwrap_or(0);
+            let offset = *offset.get_or_insert(match last_dts {
+                Some(last) if dts <= last => last + last_duration - dts,
+                _ => 0,
+            });
+            packet.set_pts(packet.pts().map(|pts| pts + offset));
+            packet.set_dts(packet.dts().map(|dts| dts + offset));
+            last_dts = Some(dts + offset);
+            last_duration = packet.duration().max(1);
+            packet.set_position(-1);
+            packet.set_stream(video_index);
+            packet.write_interleaved(&mut octx)?;
+        }
+    }
+
+    mux_audio_from_source(
+        &mut octx,
+        audio_src,
+        audio_ost,
+        audio_tb,
+        metadata.source_path.as_ref(),
+        warnings,
+    );
+    octx.write_trailer()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::color_space::ColorSpace;
+    use crate::video::domain::video_reader::VideoReader;
+    use crate::video::infrastructure::ffmpeg_reader::FfmpegReader;
+    use crate::video::infrastructure::ffmpeg_writer::FfmpegWriter;
+    use std::sync::{Arc, Mutex};
+
+    fn metadata() -> VideoMetadata {
+        VideoMetadata {
+            width: 160,
+            height: 120,
+            fps: 10.0,
+            total_frames: 0,
+            codec: String::new(),
+            source_path: None,
+            rotation: 0,
+            color: ColorSpace::default(),
+        }
+    }
+
+    fn frame(index: usize) -> Frame {
+        Frame::new(vec![(index * 8) as u8; 160 * 120 * 3], 160, 120, 3, index)
+            .with_timestamp(index as f64 / 10.0)
+    }
+
+    fn ffmpeg_parts(part_frames: usize) -> PartedWriter {
+        PartedWriter::new(Box::new(|| Box::new(FfmpegWriter::new())), part_frames)
+    }
+
+    type Finished = Arc<Mutex<Vec<(usize, Vec<PathBuf>)>>>;
+
+    fn recording(writer: PartedWriter) -> (PartedWriter, Finished) {
+        let finished: Finished = Arc::default();
+        let log = finished.clone();
+        let writer = writer.with_on_part_finished(Box::new(move |next, parts| {
+            log.lock().unwrap().push((next, parts.to_vec()));
+            Ok(())
+        }));
+        (writer, finished)
+    }
+
+    fn frame_count(path: &Path) -> usize {
+        let mut reader = FfmpegReader::new();
+        reader.open(path).unwrap();
+        reader.frames().map(|f| f.unwrap()).count()
+    }
+
+    #[test]
+    fn test_part_path() {
+        assert_eq!(
+            part_path(Path::new("/videos/talk.mp4"), 3),
+            PathBuf::from("/videos/talk.part003.mp4")
+        );
+    }
+
+    #[test]
+    fn test_joins_parts_into_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("out.mp4");
+        let (mut writer, finished) = recording(ffmpeg_parts(10));
+
+        writer.open(&output, &metadata()).unwrap();
+        for i in 0..25 {
+            writer.write(&frame(i)).unwrap();
+        }
+        writer.close().unwrap();
+
+        let finished = finished.lock().unwrap();
+        assert_eq!(
+            finished.iter().map(|(next, _)| *next).collect::<Vec<_>>(),
+            vec![10, 20]
+        );
+        assert_eq!(frame_count(&output), 25);
+        assert!(!part_path(&output, 0).exists(), "parts should be deleted");
+    }
+
+    #[test]
+    fn test_resumes_after_finished_parts() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("out.mp4");
+        let (mut interrupted, finished) = recording(ffmpeg_parts(10));
+        interrupted.open(&output, &metadata()).unwrap();
+        for i in 0..15 {
+            interrupted.write(&frame(i)).unwrap();
+        }
+        drop(interrupted);
+        let (next, parts) = finished.lock().unwrap()[0].clone();
+
+        let mut resumed = ffmpeg_parts(10).with_finished_parts(parts);
+        resumed.open(&output, &metadata()).unwrap();
+        for i in next..25 {
+            resumed.write(&frame(i)).unwrap();
+        }
+        resumed.close().unwrap();
+
+        assert_eq!(frame_count(&output), 25);
+    }
+
+    #[test]
+    fn test_close_without_frames_is_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = ffmpeg_parts(10);
+        writer
+            .open(&dir.path().join("out.mp4"), &metadata())
+            .unwrap();
+        assert!(writer.close().is_err());
+    }
+}
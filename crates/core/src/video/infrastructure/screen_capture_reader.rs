@@ -0,0 +1,189 @@
+use std::path::Path;
+
+use crate::shared::frame::Frame;
+use crate::shared::video_metadata::VideoMetadata;
+use crate::shared::warnings::Warnings;
+use crate::video::domain::video_reader::VideoReader;
+use crate::video::infrastructure::ffmpeg_reader::FfmpegReader;
+
+pub const DEFAULT_CAPTURE_FPS: u32 = 15;
+
+/// What to record.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CaptureTarget {
+    /// The main display.
+    Screen,
+    /// A single window: its title on Windows, its X11 window ID (decimal
+    /// or `0x` hex) on Linux. Not available on macOS.
+    Window(String),
+}
+
+/// Records the screen or a window through ffmpeg's capture devices
+/// (`x11grab` on Linux, `avfoundation` on macOS, `gdigrab` on Windows).
+///
+/// The path given to [`open`](VideoReader::open) is ignored. Frames keep
+/// coming until the iterator is dropped, so callers stop a recording with
+/// the pipeline's cancellation flag. Metadata reports `total_frames: 0`
+/// and no source path, since there is no file to copy audio from.
+pub struct ScreenCaptureReader {
+    target: CaptureTarget,
+    fps: u32,
+    inner: FfmpegReader,
+}
+
+impl ScreenCaptureReader {
+    pub fn new(target: CaptureTarget) -> Self {
+        Self {
+            target,
+            fps: DEFAULT_CAPTURE_FPS,
+            inner: FfmpegReader::new(),
+        }
+    }
+
+    pub fn with_fps(mut self, fps: u32) -> Self {
+        self.fps = fps.max(1);
+        self
+    }
+
+    pub fn with_warnings(mut self, warnings: Warnings) -> Self {
+        self.inner = self.inner.with_warnings(warnings);
+        self
+    }
+}
+
+impl VideoReader for ScreenCaptureReader {
+    fn open(&mut self, _path: &Path) -> Result<VideoMetadata, Box<dyn std::error::Error>> {
+        ffmpeg_next::init()?;
+        ffmpeg_next::device::register_all();
+
+        let device = capture_device(&self.target, self.fps)?;
+        let format = ffmpeg_next::device::input::video()
+            .find(|f| f.name() == device.format)
+            .ok_or_else(|| format!("Screen capture ({}) is not available", device.format))?;
+        let mut options = ffmpeg_next::Dictionary::new();
+        for (key, value) in &device.options {
+            options.set(key, value);
+        }
+        let ictx = match ffmpeg_next::format::open_with(
+            &device.url,
+            &ffmpeg_next::format::Format::Input(format),
+            options,
+        )? {
+            ffmpeg_next::format::context::Context::Input(ictx) => ictx,
+            ffmpeg_next::format::context::Context::Output(_) => {
+                return Err("Capture device opened as an output".into())
+            }
+        };
+
+        let mut metadata = self.inner.open_input(ictx, None)?;
+        // Devices report their clock rate rather than the capture rate.
+        metadata.fps = self.fps as f64;
+        Ok(metadata)
+    }
+
+    fn frames(
+        &mut self,
+    ) -> Box<dyn Iterator<Item = Result<Frame, Box<dyn std::error::Error>>> + '_> {
+        self.inner.frames()
+    }
+
+    fn close(&mut self) {
+        self.inner.close();
+    }
+}
+
+/// An ffmpeg input device with the URL and options that select a target.
+#[derive(Debug, PartialEq, Eq)]
+struct CaptureDevice {
+    format: &'static str,
+    url: String,
+    options: Vec<(&'static str, String)>,
+}
+
+#[cfg(target_os = "linux")]
+fn capture_device(target: &CaptureTarget, fps: u32) -> Result<CaptureDevice, String> {
+    let display = std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string());
+    let mut options = vec![("framerate", fps.to_string()), ("draw_mouse", "1".into())];
+    if let CaptureTarget::Window(id) = target {
+        let id = id.trim();
+        let parsed = match id.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => id.parse(),
+        };
+        let id = parsed.map_err(|_| format!("'{id}' is not an X11 window ID"))?;
+        options.push(("window_id", id.to_string()));
+    }
+    Ok(CaptureDevice {
+        format: "x11grab",
+        url: display,
+        options,
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn capture_device(target: &CaptureTarget, fps: u32) -> Result<CaptureDevice, String> {
+    if let CaptureTarget::Window(_) = target {
+        return Err("Window capture is not supported on macOS; record the screen".into());
+    }
+    Ok(CaptureDevice {
+        format: "avfoundation",
+        url: "Capture screen 0:none".into(),
+        options: vec![
+            ("framerate", fps.to_string()),
+            ("capture_cursor", "1".into()),
+        ],
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn capture_device(target: &CaptureTarget, fps: u32) -> Result<CaptureDevice, String> {
+    let url = match target {
+        CaptureTarget::Screen => "desktop".to_string(),
+        CaptureTarget::Window(title) => format!("title={}", title.trim()),
+    };
+    Ok(CaptureDevice {
+        format: "gdigrab",
+        url,
+        options: vec![("framerate", fps.to_string()), ("draw_mouse", "1".into())],
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn capture_device(_target: &CaptureTarget, _fps: u32) -> Result<CaptureDevice, String> {
+    Err("Screen capture is not supported on this platform".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    #[test]
+    fn test_screen_device_sets_framerate() {
+        let device = capture_device(&CaptureTarget::Screen, 24).unwrap();
+        assert!(device.options.contains(&("framerate", "24".to_string())));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_window_id_accepts_hex_and_decimal() {
+        let hex = capture_device(&CaptureTarget::Window("0x3a00007".into()), 15).unwrap();
+        let dec = capture_device(&CaptureTarget::Window("60817415".into()), 15).unwrap();
+        assert!(hex.options.contains(&("window_id", "60817415".to_string())));
+        assert_eq!(hex, dec);
+        assert!(capture_device(&CaptureTarget::Window("Firefox".into()), 15).is_err());
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn test_window_uses_title() {
+        let device = capture_device(&CaptureTarget::Window("Notepad".into()), 15).unwrap();
+        assert_eq!(device.url, "title=Notepad");
+    }
+
+    #[test]
+    fn test_fps_is_at_least_one() {
+        let reader = ScreenCaptureReader::new(CaptureTarget::Screen).with_fps(0);
+        assert_eq!(reader.fps, 1);
+    }
+}
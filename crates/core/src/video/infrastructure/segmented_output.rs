@@ -0,0 +1,203 @@
+use std::path::{Path, PathBuf};
+
+/// Segment length HLS players and CDNs are tuned for.
+pub const DEFAULT_SEGMENT_SECONDS: f64 = 6.0;
+
+/// Streaming package format, picked from the playlist extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SegmentFormat {
+    /// `.m3u8` playlist with MPEG-TS `.ts` segments.
+    Hls,
+    /// `.mpd` manifest with fragmented MP4 `.m4s` segments.
+    Dash,
+}
+
+impl SegmentFormat {
+    /// Format for a playlist path: `.m3u8` for HLS, `.mpd` for DASH.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "m3u8" => Some(Self::Hls),
+            "mpd" => Some(Self::Dash),
+            _ => None,
+        }
+    }
+
+    fn muxer(self) -> &'static str {
+        match self {
+            Self::Hls => "hls",
+            Self::Dash => "dash",
+        }
+    }
+}
+
+/// Segmented streaming output, so an anonymized recording can be put on a
+/// static web host as is.
+///
+/// Video is encoded into an intermediate MP4 next to the playlist first,
+/// then remuxed without re-encoding into the playlist and its segments,
+/// which are named after the playlist (`talk.m3u8` → `talk_00000.ts`).
+/// Packaging afterwards keeps the audio, which is copied at the end of the
+/// encode, interleaved with the video across segments.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SegmentedOutput {
+    pub format: SegmentFormat,
+    pub segment_seconds: f64,
+}
+
+impl SegmentedOutput {
+    pub fn new(format: SegmentFormat) -> Self {
+        Self {
+            format,
+            segment_seconds: DEFAULT_SEGMENT_SECONDS,
+        }
+    }
+
+    pub fn with_segment_seconds(mut self, seconds: f64) -> Self {
+        self.segment_seconds = seconds;
+        self
+    }
+
+    /// Keyframe interval in frames, so every segment can start on one.
+    pub fn keyframe_interval(&self, fps: f64) -> u32 {
+        (self.segment_seconds * fps).round().max(1.0) as u32
+    }
+
+    /// Where the video is encoded before packaging.
+    pub fn intermediate_path(playlist: &Path) -> PathBuf {
+        playlist.with_extension("tmp.mp4")
+    }
+
+    /// Remuxes the video and audio streams of `source` into `playlist` and
+    /// its segments.
+    pub fn package(
+        &self,
+        source: &Path,
+        playlist: &Path,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        ffmpeg_next::init()?;
+        let mut ictx = ffmpeg_next::format::input(source)?;
+        let mut octx = ffmpeg_next::format::output_as(playlist, self.format.muxer())?;
+
+        let mut stream_map = vec![None; ictx.nb_streams() as usize];
+        for ist in ictx.streams() {
+            let medium = ist.parameters().medium();
+            if medium != ffmpeg_next::media::Type::Video
+                && medium != ffmpeg_next::media::Type::Audio
+            {
+                continue;
+            }
+            let mut ost =
+                octx.add_stream(ffmpeg_next::encoder::find(ffmpeg_next::codec::Id::None))?;
+            ost.set_parameters(ist.parameters());
+            unsafe {
+                (*ost.parameters().as_mut_ptr()).codec_tag = 0;
+            }
+            stream_map[ist.index()] = Some(ost.index());
+        }
+
+        octx.write_header_with(self.muxer_options(playlist))?;
+
+        for (stream, mut packet) in ictx.packets() {
+            let Some(out_idx) = stream_map[stream.index()] else {
+                continue;
+            };
+            let out_tb = octx.stream(out_idx).unwrap().time_base();
+            packet.rescale_ts(stream.time_base(), out_tb);
+            packet.set_position(-1);
+            packet.set_stream(out_idx);
+            packet.write_interleaved(&mut octx)?;
+        }
+
+        octx.write_trailer()?;
+        Ok(())
+    }
+
+    fn muxer_options(&self, playlist: &Path) -> ffmpeg_next::Dictionary<'static> {
+        let stem = playlist.file_stem().unwrap_or_default().to_string_lossy();
+        let seconds = self.segment_seconds.to_string();
+        let mut opts = ffmpeg_next::Dictionary::new();
+        match self.format {
+            SegmentFormat::Hls => {
+                // Segment paths resolve against the working directory, not
+                // the playlist, so they are given in full.
+                let segments = playlist.with_file_name(format!("{stem}_%05d.ts"));
+                opts.set("hls_time", &seconds);
+                opts.set("hls_playlist_type", "vod");
+                opts.set("hls_segment_filename", &segments.to_string_lossy());
+            }
+            SegmentFormat::Dash => {
+                // DASH segment names resolve against the manifest's folder.
+                opts.set("seg_duration", &seconds);
+                opts.set("use_template", "1");
+                opts.set("use_timeline", "1");
+                opts.set(
+                    "init_seg_name",
+                    &format!("{stem}_init_$RepresentationID$.m4s"),
+                );
+                opts.set(
+                    "media_seg_name",
+                    &format!("{stem}_$RepresentationID$_$Number%05d$.m4s"),
+                );
+            }
+        }
+        opts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("out.m3u8", Some(SegmentFormat::Hls))]
+    #[case("OUT.M3U8", Some(SegmentFormat::Hls))]
+    #[case("share/out.mpd", Some(SegmentFormat::Dash))]
+    #[case("out.mp4", None)]
+    #[case("out", None)]
+    fn test_format_from_path(#[case] path: &str, #[case] expected: Option<SegmentFormat>) {
+        assert_eq!(SegmentFormat::from_path(Path::new(path)), expected);
+    }
+
+    #[rstest]
+    #[case(6.0, 30.0, 180)]
+    #[case(6.0, 29.97, 180)]
+    #[case(2.0, 25.0, 50)]
+    #[case(0.01, 24.0, 1)]
+    fn test_keyframe_interval(#[case] seconds: f64, #[case] fps: f64, #[case] expected: u32) {
+        let output = SegmentedOutput::new(SegmentFormat::Hls).with_segment_seconds(seconds);
+        assert_eq!(output.keyframe_interval(fps), expected);
+    }
+
+    #[test]
+    fn test_intermediate_path_sits_next_to_playlist() {
+        assert_eq!(
+            SegmentedOutput::intermediate_path(Path::new("share/talk.m3u8")),
+            PathBuf::from("share/talk.tmp.mp4")
+        );
+    }
+
+    #[test]
+    fn test_hls_segments_are_named_after_playlist() {
+        let output = SegmentedOutput::new(SegmentFormat::Hls).with_segment_seconds(4.0);
+        let opts = output.muxer_options(Path::new("share/talk.m3u8"));
+        assert_eq!(opts.get("hls_time"), Some("4"));
+        assert_eq!(opts.get("hls_playlist_type"), Some("vod"));
+        assert_eq!(
+            opts.get("hls_segment_filename"),
+            Some(Path::new("share").join("talk_%05d.ts").to_str().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_dash_segments_are_named_after_manifest() {
+        let output = SegmentedOutput::new(SegmentFormat::Dash);
+        let opts = output.muxer_options(Path::new("talk.mpd"));
+        assert_eq!(opts.get("seg_duration"), Some("6"));
+        assert_eq!(
+            opts.get("init_seg_name"),
+            Some("talk_init_$RepresentationID$.m4s")
+        );
+    }
+}
@@ -0,0 +1,206 @@
+//! Synthetic faces with known positions, drawn in code so the fixtures are
+//! bundled with the tests without shipping photos of real people.
+//!
+//! Each face is a shaded skin-tone oval with hair, brows, eyes, a nose
+//! shadow, and a mouth, in the proportions the detector was trained on.
+//! Scenes also contain face-sized distractors (plain ovals and boxes) that
+//! must not be detected.
+
+use faceguard_core::shared::frame::Frame;
+use faceguard_core::shared::region::Region;
+
+/// Ground truth for one drawn face: its box from brow to chin, as a face
+/// detector would draw it.
+#[derive(Clone, Copy, Debug)]
+pub struct FaceBox {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl FaceBox {
+    /// Intersection over union with a detected region.
+    pub fn iou(&self, region: &Region) -> f64 {
+        let (rx1, ry1) = (region.x as f64, region.y as f64);
+        let (rx2, ry2) = (rx1 + region.width as f64, ry1 + region.height as f64);
+        let ix = (self.x + self.width).min(rx2) - self.x.max(rx1);
+        let iy = (self.y + self.height).min(ry2) - self.y.max(ry1);
+        if ix <= 0.0 || iy <= 0.0 {
+            return 0.0;
+        }
+        let inter = ix * iy;
+        inter / (self.width * self.height + (rx2 - rx1) * (ry2 - ry1) - inter)
+    }
+}
+
+/// A frame and the faces drawn on it.
+pub struct Scene {
+    pub frame: Frame,
+    pub faces: Vec<FaceBox>,
+}
+
+const SKIN_TONES: [[f64; 3]; 4] = [
+    [236.0, 196.0, 164.0],
+    [198.0, 150.0, 110.0],
+    [141.0, 98.0, 66.0],
+    [92.0, 62.0, 42.0],
+];
+
+/// Scene `index` of a deterministic set: a few faces of varying size, tone,
+/// and position, plus distractors, over a gradient background. `shift`
+/// moves every face right by that many pixels, for clips.
+pub fn scene(index: usize, width: u32, height: u32, shift: f64) -> Scene {
+    let mut canvas = Canvas::new(width, height, index);
+    let mut faces = Vec::new();
+    let count = 1 + index % 3;
+    for slot in 0..count {
+        let size = (height as f64) * (0.18 + 0.06 * ((index + slot) % 3) as f64);
+        let x = (width as f64) * (0.12 + 0.3 * slot as f64) + shift;
+        let y = (height as f64) * (0.15 + 0.08 * ((index * 7 + slot) % 4) as f64);
+        let tone = SKIN_TONES[(index + slot * 3) % SKIN_TONES.len()];
+        faces.push(canvas.draw_face(x, y, size, tone));
+    }
+    // Distractors in the bottom strip, clear of the faces.
+    let distractor = height as f64 * 0.12;
+    let bottom = height as f64 - distractor * 1.3;
+    canvas.fill_ellipse(
+        width as f64 * 0.2,
+        bottom,
+        distractor * 0.4,
+        distractor * 0.5,
+        SKIN_TONES[index % SKIN_TONES.len()],
+    );
+    canvas.fill_rect(
+        width as f64 * 0.7,
+        bottom - distractor * 0.5,
+        distractor,
+        distractor,
+        [60.0, 90.0, 160.0],
+    );
+    Scene {
+        frame: Frame::new(canvas.data, width, height, 3, index),
+        faces,
+    }
+}
+
+struct Canvas {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32, seed: usize) -> Self {
+        let (w, h) = (width as usize, height as usize);
+        let mut data = vec![0u8; w * h * 3];
+        let hue = (seed * 37 % 100) as f64 / 100.0;
+        for y in 0..h {
+            for x in 0..w {
+                let t = (x + y) as f64 / (w + h) as f64;
+                let idx = (y * w + x) * 3;
+                data[idx] = (90.0 + 80.0 * t) as u8;
+                data[idx + 1] = (110.0 + 60.0 * hue) as u8;
+                data[idx + 2] = (140.0 - 60.0 * t) as u8;
+            }
+        }
+        Self {
+            data,
+            width,
+            height,
+        }
+    }
+
+    /// Draws a face whose brow-to-chin box is `size` tall with its top-left
+    /// corner at `(x, y)`, and returns that box.
+    fn draw_face(&mut self, x: f64, y: f64, size: f64, skin: [f64; 3]) -> FaceBox {
+        let w = size * 0.78;
+        let cx = x + w / 2.0;
+        let cy = y + size / 2.0;
+        let hair = [40.0, 28.0, 20.0];
+
+        // Hair behind and above the face, then the face itself, shaded
+        // darker toward the edges so it reads as a rounded surface.
+        self.fill_ellipse(cx, cy - size * 0.12, w * 0.56, size * 0.58, hair);
+        self.shade_ellipse(cx, cy, w / 2.0, size / 2.0, skin);
+
+        let eye_y = cy - size * 0.08;
+        let eye_dx = w * 0.2;
+        for side in [-1.0, 1.0] {
+            let ex = cx + side * eye_dx;
+            self.fill_rect(
+                ex - w * 0.12,
+                eye_y - size * 0.11,
+                w * 0.24,
+                size * 0.03,
+                hair,
+            );
+            self.fill_ellipse(ex, eye_y, w * 0.09, size * 0.04, [245.0, 245.0, 240.0]);
+            self.fill_ellipse(ex, eye_y, w * 0.04, size * 0.035, [50.0, 35.0, 25.0]);
+        }
+        let shadow = skin.map(|c| c * 0.7);
+        self.fill_ellipse(cx, cy + size * 0.08, w * 0.06, size * 0.1, shadow);
+        self.fill_ellipse(
+            cx,
+            cy + size * 0.26,
+            w * 0.16,
+            size * 0.04,
+            [150.0, 60.0, 60.0],
+        );
+
+        FaceBox {
+            x,
+            y,
+            width: w,
+            height: size,
+        }
+    }
+
+    fn set(&mut self, x: i64, y: i64, color: [f64; 3]) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return;
+        }
+        let idx = (y as usize * self.width as usize + x as usize) * 3;
+        for (dst, c) in self.data[idx..idx + 3].iter_mut().zip(color) {
+            *dst = c.clamp(0.0, 255.0) as u8;
+        }
+    }
+
+    fn fill_rect(&mut self, x: f64, y: f64, w: f64, h: f64, color: [f64; 3]) {
+        for py in y as i64..(y + h) as i64 {
+            for px in x as i64..(x + w) as i64 {
+                self.set(px, py, color);
+            }
+        }
+    }
+
+    fn fill_ellipse(&mut self, cx: f64, cy: f64, rx: f64, ry: f64, color: [f64; 3]) {
+        self.paint_ellipse(cx, cy, rx, ry, |_| color);
+    }
+
+    fn shade_ellipse(&mut self, cx: f64, cy: f64, rx: f64, ry: f64, color: [f64; 3]) {
+        self.paint_ellipse(cx, cy, rx, ry, |d| color.map(|c| c * (1.0 - 0.25 * d)));
+    }
+
+    /// Paints every pixel inside the ellipse with `color_at(d)`, where `d`
+    /// is the squared normalized distance from the center (0 to 1).
+    fn paint_ellipse(
+        &mut self,
+        cx: f64,
+        cy: f64,
+        rx: f64,
+        ry: f64,
+        color_at: impl Fn(f64) -> [f64; 3],
+    ) {
+        for py in (cy - ry) as i64..=(cy + ry) as i64 {
+            for px in (cx - rx) as i64..=(cx + rx) as i64 {
+                let dx = (px as f64 - cx) / rx;
+                let dy = (py as f64 - cy) / ry;
+                let d = dx * dx + dy * dy;
+                if d <= 1.0 {
+                    self.set(px, py, color_at(d));
+                }
+            }
+        }
+    }
+}
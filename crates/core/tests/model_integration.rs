@@ -0,0 +1,262 @@
+//! End-to-end checks against the real YOLO face model.
+//!
+//! Off by default: run with `cargo test -p faceguard-core --features
+//! integration-tests`. The model is resolved like the apps resolve it (the
+//! model cache, then a download), so only the first run needs a network.
+//! These catch regressions in letterboxing, output parsing, NMS, tracking,
+//! and region building that unit tests with hand-made tensors can miss.
+#![cfg(feature = "integration-tests")]
+
+mod fixtures;
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use faceguard_core::blurring::infrastructure::cpu_elliptical_blurrer::CpuEllipticalBlurrer;
+use faceguard_core::detection::domain::face_detector::FaceDetector;
+use faceguard_core::detection::domain::face_region_builder::FaceRegionBuilder;
+use faceguard_core::detection::domain::region_merger::RegionMerger;
+use faceguard_core::detection::infrastructure::bytetrack_tracker::ByteTracker;
+use faceguard_core::detection::infrastructure::model_resolver;
+use faceguard_core::detection::infrastructure::onnx_yolo_detector::OnnxYoloDetector;
+use faceguard_core::pipeline::blur_faces_use_case::BlurFacesUseCase;
+use faceguard_core::pipeline::infrastructure::threaded_pipeline_executor::ThreadedPipelineExecutor;
+use faceguard_core::shared::color_space::ColorSpace;
+use faceguard_core::shared::constants::{TRACKER_MAX_LOST, YOLO_MODEL_NAME, YOLO_MODEL_URL};
+use faceguard_core::shared::frame::Frame;
+use faceguard_core::shared::region::Region;
+use faceguard_core::shared::video_metadata::VideoMetadata;
+use faceguard_core::video::domain::video_reader::VideoReader;
+use faceguard_core::video::domain::video_writer::VideoWriter;
+use faceguard_core::video::infrastructure::ffmpeg_reader::FfmpegReader;
+use faceguard_core::video::infrastructure::ffmpeg_writer::FfmpegWriter;
+
+use fixtures::{scene, FaceBox};
+
+const WIDTH: u32 = 640;
+const HEIGHT: u32 = 360;
+const SCENES: usize = 24;
+
+/// A detection counts as a face when it overlaps one this much.
+const MATCH_IOU: f64 = 0.3;
+
+/// Quality floors on the synthetic set. They sit below what the bundled
+/// model scores, so they fail on real regressions rather than on noise;
+/// raise them when the model improves.
+const RECALL_FLOOR: f64 = 0.8;
+const PRECISION_FLOOR: f64 = 0.9;
+
+const CLIP_FRAMES: usize = 30;
+const CLIP_FPS: f64 = 15.0;
+const BLUR_KERNEL_SIZE: usize = 31;
+
+/// Resolved once per test run; tests run in parallel and share it.
+fn model_path() -> PathBuf {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    PATH.get_or_init(|| {
+        model_resolver::resolve(YOLO_MODEL_NAME, YOLO_MODEL_URL, None, None)
+            .expect("YOLO model should resolve (needs a network on the first run)")
+    })
+    .clone()
+}
+
+/// Raw boxes: no padding or smoothing, so regions can be compared with the
+/// drawn faces.
+fn raw_detector() -> OnnxYoloDetector {
+    OnnxYoloDetector::new(
+        &model_path(),
+        FaceRegionBuilder::new(0.0, 0.0, None),
+        ByteTracker::new(TRACKER_MAX_LOST),
+        0.5,
+    )
+    .expect("detector should build")
+    .with_calibration(model_resolver::calibration_for(YOLO_MODEL_NAME))
+}
+
+/// `(true positives, false positives, missed faces)` for one frame.
+fn score(faces: &[FaceBox], detected: &[Region]) -> (usize, usize, usize) {
+    let mut matched = vec![false; faces.len()];
+    let mut false_positives = 0;
+    for region in detected {
+        let best = faces
+            .iter()
+            .enumerate()
+            .filter(|(i, face)| !matched[*i] && face.iou(region) >= MATCH_IOU)
+            .max_by(|(_, a), (_, b)| a.iou(region).total_cmp(&b.iou(region)));
+        match best {
+            Some((i, _)) => matched[i] = true,
+            None => false_positives += 1,
+        }
+    }
+    let found = matched.iter().filter(|m| **m).count();
+    (found, false_positives, faces.len() - found)
+}
+
+#[test]
+fn detection_meets_recall_and_precision_floors() {
+    let mut true_positives = 0;
+    let mut false_positives = 0;
+    let mut missed = 0;
+    for index in 0..SCENES {
+        // A fresh detector per scene, so tracking never carries a box over.
+        let mut detector = raw_detector();
+        let scene = scene(index, WIDTH, HEIGHT, 0.0);
+        let detected = detector.detect(&scene.frame).expect("detection should run");
+        let (tp, fp, miss) = score(&scene.faces, &detected);
+        true_positives += tp;
+        false_positives += fp;
+        missed += miss;
+    }
+
+    let recall = true_positives as f64 / (true_positives + missed) as f64;
+    let precision = true_positives as f64 / (true_positives + false_positives).max(1) as f64;
+    eprintln!(
+        "recall {recall:.3} ({true_positives} of {}), precision {precision:.3} ({false_positives} false positives)",
+        true_positives + missed
+    );
+    assert!(
+        recall >= RECALL_FLOOR,
+        "recall {recall:.3} < {RECALL_FLOOR}"
+    );
+    assert!(
+        precision >= PRECISION_FLOOR,
+        "precision {precision:.3} < {PRECISION_FLOOR}"
+    );
+}
+
+#[test]
+fn tracking_keeps_one_id_per_moving_face() {
+    let mut detector = raw_detector();
+    let mut ids = std::collections::HashSet::new();
+    for frame in 0..CLIP_FRAMES {
+        let scene = scene(0, WIDTH, HEIGHT, frame as f64 * 3.0);
+        for region in detector.detect(&scene.frame).expect("detection should run") {
+            ids.extend(region.track_id);
+        }
+    }
+    // Scene 0 has one face sliding right; a tracker that loses it on the
+    // way hands out new IDs.
+    assert_eq!(ids.len(), 1, "track IDs seen: {ids:?}");
+}
+
+#[test]
+fn pipeline_blurs_faces_and_keeps_the_rest() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let source = dir.path().join("faces.mp4");
+    let output = dir.path().join("blurred.mp4");
+    let metadata = VideoMetadata {
+        width: WIDTH,
+        height: HEIGHT,
+        fps: CLIP_FPS,
+        total_frames: CLIP_FRAMES,
+        codec: "h264".to_string(),
+        source_path: None,
+        rotation: 0,
+        color: ColorSpace::default(),
+    };
+
+    let scenes: Vec<_> = (0..CLIP_FRAMES)
+        .map(|i| scene(1, WIDTH, HEIGHT, i as f64 * 2.0))
+        .collect();
+    let mut writer = FfmpegWriter::new();
+    writer.open(&source, &metadata).expect("open writer");
+    for (i, scene) in scenes.iter().enumerate() {
+        let frame = Frame::new(scene.frame.data().to_vec(), WIDTH, HEIGHT, 3, i);
+        writer.write(&frame).expect("write frame");
+    }
+    writer.close().expect("close writer");
+
+    let mut reader: Box<dyn VideoReader> = Box::new(FfmpegReader::new());
+    let opened = reader.open(&source).expect("open source");
+    let detector = OnnxYoloDetector::new(
+        &model_path(),
+        FaceRegionBuilder::new(0.4, 0.0, None),
+        ByteTracker::new(TRACKER_MAX_LOST),
+        0.5,
+    )
+    .expect("detector should build");
+    let mut use_case = BlurFacesUseCase::new(
+        reader,
+        Box::new(FfmpegWriter::new()),
+        Box::new(detector),
+        Box::new(CpuEllipticalBlurrer::new(BLUR_KERNEL_SIZE)),
+        RegionMerger::new(),
+        Box::new(ThreadedPipelineExecutor::new()),
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    use_case
+        .execute(&opened, &output)
+        .expect("pipeline should run");
+
+    let mut check = FfmpegReader::new();
+    let written = check.open(&output).expect("open output");
+    assert_eq!((written.width, written.height), (WIDTH, HEIGHT));
+    let frames: Vec<Frame> = check.frames().map(|f| f.expect("decode")).collect();
+    check.close();
+    assert_eq!(frames.len(), CLIP_FRAMES);
+
+    for (frame, scene) in frames.iter().zip(&scenes).skip(CLIP_FRAMES / 2).take(3) {
+        for face in &scene.faces {
+            let before = detail(&scene.frame, face);
+            let after = detail(frame, face);
+            assert!(
+                after < before * 0.5,
+                "frame {}: face at ({:.0}, {:.0}) not blurred (detail {before:.1} -> {after:.1})",
+                frame.index(),
+                face.x,
+                face.y
+            );
+        }
+        // The top-left corner holds no face; only compression touches it.
+        let corner = FaceBox {
+            x: 0.0,
+            y: 0.0,
+            width: 40.0,
+            height: 40.0,
+        };
+        let drift = mean_difference(&scene.frame, frame, &corner);
+        assert!(drift < 6.0, "background changed by {drift:.1} levels");
+    }
+}
+
+/// Mean absolute difference between horizontally adjacent pixels inside
+/// `area`: high for the drawn features, low once they are blurred.
+fn detail(frame: &Frame, area: &FaceBox) -> f64 {
+    let w = frame.width() as usize;
+    let data = frame.data();
+    let (x0, x1) = (area.x as usize, (area.x + area.width) as usize - 1);
+    let (y0, y1) = (area.y as usize, (area.y + area.height) as usize);
+    let mut total = 0.0;
+    let mut count = 0;
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let a = (y * w + x) * 3;
+            let b = a + 3;
+            total += (0..3)
+                .map(|c| (data[a + c] as f64 - data[b + c] as f64).abs())
+                .sum::<f64>();
+            count += 3;
+        }
+    }
+    total / count.max(1) as f64
+}
+
+fn mean_difference(a: &Frame, b: &Frame, area: &FaceBox) -> f64 {
+    let w = a.width() as usize;
+    let mut total = 0.0;
+    let mut count = 0;
+    for y in area.y as usize..(area.y + area.height) as usize {
+        for x in area.x as usize..(area.x + area.width) as usize {
+            for c in 0..3 {
+                let i = (y * w + x) * 3 + c;
+                total += (a.data()[i] as f64 - b.data()[i] as f64).abs();
+                count += 1;
+            }
+        }
+    }
+    total / count.max(1) as f64
+}
@@ -8,30 +8,72 @@ use crossbeam_channel::Receiver;
 use iced::widget::{
     button, column, container, mouse_area, operation, row, scrollable, text, Space,
 };
-use iced::{Color, Element, Length, Subscription, Task, Theme};
+use iced::{window, Color, Element, Length, Point, Size, Subscription, Task, Theme};
 use iced_anim::transition::Easing;
 use iced_anim::AnimationBuilder;
+use serde::{Deserialize, Serialize};
 
-use crate::settings::{Appearance, BlurShape, Settings};
+use crate::checklist::{Checklist, ChecklistPolicy};
+use crate::performance::{self, InputSize};
+use crate::platform::Outcome;
+use crate::project::Project;
+use crate::settings::{Appearance, BlurShape, CaptureSource, Settings, WindowPlacement};
+use crate::shortcuts::Command;
 use crate::tabs;
+use crate::tabs::main_tab::{KeywordReview, PreviewControls, TrimFields};
+use crate::tabs::onboarding::OnboardingState;
+use crate::tabs::settings_tab::SelectionGuard;
 use crate::theme;
+use crate::widgets::command_palette;
+use crate::widgets::compare_slider::CompareState;
+use crate::widgets::detection_timeline::{self, Coverage, TIMELINE_BUCKETS};
 use crate::widgets::faces_well::FacesWellState;
 use crate::workers::blur_worker::{self, BlurParams, WorkerMessage};
+use crate::workers::capture_worker::{self, CaptureMessage, CaptureParams};
+use crate::workers::compare_worker::{self, CompareMessage, CompareParams};
+use crate::workers::detection_cache;
 use crate::workers::model_cache::ModelCache;
+use crate::workers::model_update_worker::{self, ModelUpdateMessage};
+use crate::workers::prefetch_worker::{self, PrefetchMessage};
 use crate::workers::preview_worker::{self, PreviewMessage, PreviewParams};
+use faceguard_core::blurring::domain::weak_blur::{widest_face, WeakBlur};
 use faceguard_core::blurring::infrastructure::blurrer_factory;
 use faceguard_core::blurring::infrastructure::gpu_context::GpuContext;
+use faceguard_core::detection::infrastructure::cache_key_params::{CacheKeyParam, CacheKeyParams};
+use faceguard_core::detection::infrastructure::model_resolver;
+use faceguard_core::detection::infrastructure::model_updates::ModelUpdate;
+use faceguard_core::pipeline::anonymization_ledger::AnonymizationLedger;
+use faceguard_core::pipeline::blur_preset::BlurPreset;
+use faceguard_core::pipeline::export_frame_use_case::representative_frame;
+use faceguard_core::pipeline::report_inspection::{inspect_report, ReportInspection};
+use faceguard_core::shared::cancellation::{CancelReason, CancellationToken};
+use faceguard_core::shared::constants::IMAGE_EXTENSIONS;
 use faceguard_core::shared::region::Region;
+use faceguard_core::shared::track_time_rules::{parse_timestamp, TimeRange};
+use faceguard_core::shared::warnings::{Warning, Warnings};
+use faceguard_core::video::domain::source_metadata::MetadataField;
+use faceguard_core::video::domain::video_reader::VideoReader;
+use faceguard_core::video::infrastructure::ffmpeg_reader::FfmpegReader;
+use faceguard_core::video::infrastructure::metadata_inspector::read_source_metadata;
+use faceguard_core::video::infrastructure::screen_capture_reader::CaptureTarget;
 
+const APP_TITLE: &str = "FaceGuard \u{2014} Neutrino Graphics";
 const WEBSITE_URL: &str = "https://www.neutrinographics.com/";
 const SCROLL_ID: &str = "tab-scroll";
+const SYSTEM_SIGNALS_POLL_INTERVAL: Duration = Duration::from_secs(5);
+pub const DEFAULT_WINDOW_SIZE: Size = Size::new(620.0, 540.0);
+pub const MIN_WINDOW_SIZE: Size = Size::new(520.0, 440.0);
+/// Horizontal space around the tab content: padding plus scrollbar.
+const CONTENT_INSET: f32 = 72.0;
 
 pub const SUPPORTED_EXTENSIONS: &[&str] = &[
     "mp4", "avi", "mov", "mkv", "jpg", "jpeg", "png", "bmp", "tiff", "webp",
 ];
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Tab {
+    #[default]
     Blur,
     Settings,
     About,
@@ -52,6 +94,8 @@ impl Tab {
 #[derive(Debug, Clone)]
 pub enum ProcessingState {
     Idle,
+    /// Screen recording in progress, with the frames captured so far.
+    Recording(usize),
     Preparing,
     Downloading(u64, u64),
     Scanning(usize, usize),
@@ -61,6 +105,29 @@ pub enum ProcessingState {
     Error(String),
 }
 
+/// Where a user-started model update check stands.
+#[derive(Debug, Clone, Default)]
+pub enum ModelUpdateState {
+    #[default]
+    Idle,
+    Checking,
+    /// Updates found by the last check; empty when everything is current.
+    Available(Vec<ModelUpdate>),
+    /// Downloading the listed updates: `(update index, downloaded, total)`.
+    Installing(Vec<ModelUpdate>, usize, u64, u64),
+    Installed,
+    Failed(String),
+}
+
+/// How the app quits once a running export stops.
+#[derive(Debug, Clone, Copy)]
+enum PendingExit {
+    /// The export was cancelled; quit once its partial output is removed.
+    AfterCleanup,
+    /// The export carries on with the window minimized; quit when it ends.
+    WhenDone(window::Id),
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     TabSelected(Tab),
@@ -74,26 +141,97 @@ pub enum Message {
     CancelWork,
     WorkerTick,
     ShowInFolder,
+    DragOutput,
+    OutputDragEnded(Result<(), String>),
     StartOver,
+    ToggleKeywordHit(usize),
+    ConfirmKeywordReview,
+    ToggleChecklistItem(usize),
+    DismissChecklist,
+    DismissWarnings,
+    ShowMetadata,
+    CloseMetadata,
+    InspectReport,
+    ReportPicked(Option<PathBuf>),
+    ExportSelection,
+    SelectionExportPicked(Option<PathBuf>),
+    CloseInspection,
+    RunCommand(Command),
+    OpenCommandPalette,
+    CommandPaletteQueryChanged(String),
+    CommandPaletteSubmit,
+    CloseCommandPalette,
+    CaptureSourceChanged(CaptureSource),
+    CaptureWindowChanged(String),
+    TrimStartChanged(String),
+    TrimEndChanged(String),
+    StartRecording,
+    StopRecording,
+    CompareDragStarted,
+    CompareDragEnded,
+    ComparePointerMoved(f32),
+    /// The scrubber under the sample frame moved to this frame.
+    SampleFrameChanged(u32),
+    SampleFrameReleased,
     ToggleFace(u32),
     ToggleGroup(usize),
     GroupFacesToggled(bool),
+    EditTrack(Option<u32>),
+    TrackLabelChanged(u32, String),
+    TrackNoteChanged(u32, String),
+    TrackRangesChanged(u32, String),
+    PresetChanged(BlurPreset),
     BlurShapeChanged(BlurShape),
     ConfidenceChanged(u32),
     BlurStrengthChanged(u32),
+    BlurFeatherChanged(u32),
     BlurCoverageChanged(u32),
     CenterOffsetChanged(i32),
     LookaheadChanged(u32),
+    DetectionIntervalChanged(u32),
+    /// Apply the held detection settings change, clearing the scan.
+    ConfirmRescan,
+    /// Drop the held detection settings change, keeping the scan.
+    KeepSelection,
     RestoreDefaults,
     AppearanceChanged(Appearance),
     HighContrastChanged(bool),
+    ShowRegionsToggled(bool),
+    ShowDetectionsToggled(bool),
+    OverlayPaletteChanged(crate::settings::OverlayPalette),
     QualityChanged(u32),
+    VideoCodecChanged(crate::settings::VideoCodec),
+    ImageFormatChanged(crate::settings::ImageFormat),
+    ImageQualityChanged(u32),
+    PngCompressionChanged(crate::settings::PngCompression),
     FontScaleChanged(f32),
     AudioProcessingChanged(bool),
+    AnonymizeThumbnailsChanged(bool),
+    CacheDetectionsChanged(bool),
+    SkipBriefDetectionsChanged(bool),
+    DetectorHardwareChanged(crate::settings::DetectorHardware),
     BleepKeywordsChanged(String),
+    BleepPiiChanged(
+        faceguard_core::audio::domain::spoken_entity::PiiCategory,
+        bool,
+    ),
+    SpeechLanguageChanged(faceguard_core::audio::domain::speech_language::SpeechLanguage),
+    KeywordMatchingChanged(crate::settings::KeywordMatching),
     BleepSoundChanged(crate::settings::BleepSound),
+    AudioFallbackChanged(crate::settings::AudioFallback),
     VoiceDisguiseChanged(crate::settings::VoiceDisguise),
+    PostExportChecklistChanged(bool),
+    FinishSoundChanged(bool),
+    FinishAttentionChanged(bool),
+    WriteLedgerChanged(bool),
+    LedgerOperatorChanged(String),
+    LedgerPassphraseChanged(String),
     PollSystemTheme,
+    PollSystemSignals,
+    SystemSignals {
+        reduced_motion: bool,
+        power_saving: bool,
+    },
     FileDropped(PathBuf),
     TabHover(usize, bool),
     BrowseHover(bool),
@@ -102,6 +240,14 @@ pub enum Message {
     ChangeInputHover(bool),
     ChangeOutputHover(bool),
     ChooseFacesHover(bool),
+    ChooseModelDir,
+    ModelDirSelected(Option<PathBuf>),
+    DownloadModels,
+    SkipOnboarding,
+    CheckModelUpdates,
+    InstallModelUpdates,
+    DownloadModelsHover(bool),
+    SkipOnboardingHover(bool),
     CancelHover(bool),
     RescanHover(bool),
     FaceCardHover(u32, bool),
@@ -109,21 +255,81 @@ pub enum Message {
     BlurAnotherHover(bool),
     RestoreDefaultsHover(bool),
     WebsiteHover(bool),
+    WindowMoved(window::Id, Point),
+    WindowResized(Size),
+    WindowFocusChanged(window::Id, bool),
+    MonitorDetected(window::Id, Option<Size>),
+    WindowCloseRequested(window::Id),
+    CancelAndQuit,
+    QuitWhenDone,
+    KeepWindowOpen,
 }
 
 pub struct App {
     active_tab: Tab,
     pub settings: Settings,
+    /// Settings changes that would clear the current face selection, held
+    /// until the user confirms or drops them.
+    pending_changes: Vec<Message>,
     pub input_path: Option<PathBuf>,
     pub output_path: Option<PathBuf>,
+    /// Frame count and resolution of a video input, for time estimates.
+    input_size: Option<InputSize>,
+    /// In and out points typed for a video input. Blank ones keep the
+    /// start or end of the video.
+    trim_start: String,
+    trim_end: String,
     pub processing: ProcessingState,
     pub faces_well: FacesWellState,
+    /// Before/after sample frame on the review screen.
+    pub compare: Option<CompareState>,
+    compare_rx: Option<Receiver<CompareMessage>>,
+    /// Frame the sample shows; the one with the most faces until the user
+    /// scrubs to another.
+    sample_frame: Option<usize>,
+    /// Where the scan found faces along the video, under the scrubber.
+    timeline: Vec<Coverage>,
     detection_cache: Option<Arc<HashMap<usize, Vec<Region>>>>,
+    /// Width of the widest face in `detection_cache`, for the blur
+    /// strength check.
+    widest_face: u32,
     gpu_context: Option<Arc<GpuContext>>,
+    /// Parent of every background job's token; cancelled on close.
+    shutdown: CancellationToken,
     model_cache: Arc<ModelCache>,
+    onboarding: Option<OnboardingState>,
+    model_updates: ModelUpdateState,
+    model_update_rx: Option<Receiver<ModelUpdateMessage>>,
+    /// Non-fatal problems from the last blur job, until dismissed.
+    job_warnings: Vec<Warning>,
+    /// Keyword hits the running blur job waits on the user to review.
+    keyword_review: Option<KeywordReview>,
+    /// The organization's post-export checklist policy, read at startup.
+    checklist_policy: ChecklistPolicy,
+    /// Questions about the finished export, until answered or dismissed.
+    checklist: Option<Checklist>,
+    /// Signs the anonymization ledger. Kept for the session only, never
+    /// saved with the settings.
+    ledger_passphrase: String,
+    /// Metadata found in the input, while the metadata panel is open.
+    metadata_info: Option<Result<Vec<MetadataField>, String>>,
+    /// A report or detection export opened for review, while the
+    /// inspection panel is open. Independent of the loaded input.
+    report_inspection: Option<(PathBuf, Result<ReportInspection, String>)>,
+    /// Query typed into the command palette, while it is open.
+    command_palette: Option<String>,
     preview_rx: Option<Receiver<PreviewMessage>>,
     worker_rx: Option<Receiver<WorkerMessage>>,
-    worker_cancel: Option<Arc<AtomicBool>>,
+    worker_cancel: Option<CancellationToken>,
+    /// Window title or ID typed for window capture.
+    pub capture_window: String,
+    pub capture_error: Option<String>,
+    capture_rx: Option<Receiver<CaptureMessage>>,
+    capture_stop: Option<Arc<AtomicBool>>,
+    /// Holds the raw recording while it is the input. It still shows every
+    /// face, so it is deleted as soon as another input is chosen.
+    capture_dir: Option<tempfile::TempDir>,
+    power_saving: Arc<AtomicBool>,
     tab_hovered: [bool; 3],
     pub browse_hovered: bool,
     pub drop_zone_hovered: bool,
@@ -138,54 +344,125 @@ pub struct App {
     pub blur_another_hovered: bool,
     pub restore_defaults_hovered: bool,
     pub website_hovered: bool,
+    /// Window whose close was held back because an export is running,
+    /// while the user decides what happens to the export.
+    close_prompt: Option<window::Id>,
+    pending_exit: Option<PendingExit>,
+    /// The window while another app has focus, to flash when a job ends.
+    unfocused_window: Option<window::Id>,
+    window_position: Option<Point>,
+    window_size: Size,
+    monitor_size: Option<Size>,
 }
 
 impl App {
     pub fn new() -> (Self, Task<Message>) {
-        (
-            Self {
-                active_tab: Tab::Blur,
-                settings: Settings::load(),
-                input_path: None,
-                output_path: None,
-                processing: ProcessingState::Idle,
-                faces_well: FacesWellState::new(),
-                detection_cache: None,
-                gpu_context: blurrer_factory::create_gpu_context(),
-                model_cache: ModelCache::new(),
-                preview_rx: None,
-                worker_rx: None,
-                worker_cancel: None,
-                tab_hovered: [false; 3],
-                browse_hovered: false,
-                drop_zone_hovered: false,
-                blur_button_hovered: false,
-                change_input_hovered: false,
-                change_output_hovered: false,
-                choose_faces_hovered: false,
-                cancel_hovered: false,
-                rescan_hovered: false,
-                face_card_hovered: HashSet::new(),
-                show_folder_hovered: false,
-                blur_another_hovered: false,
-                restore_defaults_hovered: false,
-                website_hovered: false,
-            },
-            Task::none(),
-        )
+        let settings = Settings::load();
+        settings.apply_model_dir();
+        let shutdown = CancellationToken::new();
+        let model_cache = ModelCache::new(&shutdown, settings.detector_hardware.backend());
+        let onboarding = if settings.onboarded {
+            model_cache.start();
+            None
+        } else {
+            Some(OnboardingState::new(settings.model_dir.clone()))
+        };
+        let window_size = settings
+            .window
+            .map_or(DEFAULT_WINDOW_SIZE, |w| Size::new(w.width, w.height));
+        let mut faces_well = FacesWellState::new();
+        faces_well.content_width = window_size.width - CONTENT_INSET;
+        let mut app = Self {
+            active_tab: settings.last_tab,
+            window_position: settings
+                .window
+                .and_then(|w| w.position)
+                .map(|(x, y)| Point::new(x, y)),
+            window_size,
+            monitor_size: None,
+            close_prompt: None,
+            pending_exit: None,
+            unfocused_window: None,
+            settings,
+            pending_changes: Vec::new(),
+            input_path: None,
+            output_path: None,
+            input_size: None,
+            trim_start: String::new(),
+            trim_end: String::new(),
+            processing: ProcessingState::Idle,
+            faces_well,
+            compare: None,
+            compare_rx: None,
+            sample_frame: None,
+            timeline: Vec::new(),
+            detection_cache: None,
+            widest_face: 0,
+            gpu_context: blurrer_factory::create_gpu_context(),
+            shutdown,
+            model_cache,
+            onboarding,
+            model_updates: ModelUpdateState::Idle,
+            model_update_rx: None,
+            job_warnings: Vec::new(),
+            keyword_review: None,
+            checklist_policy: ChecklistPolicy::load(),
+            checklist: None,
+            ledger_passphrase: String::new(),
+            metadata_info: None,
+            report_inspection: None,
+            command_palette: None,
+            preview_rx: None,
+            worker_rx: None,
+            worker_cancel: None,
+            capture_window: String::new(),
+            capture_error: None,
+            capture_rx: None,
+            capture_stop: None,
+            capture_dir: None,
+            power_saving: Arc::new(AtomicBool::new(false)),
+            tab_hovered: [false; 3],
+            browse_hovered: false,
+            drop_zone_hovered: false,
+            blur_button_hovered: false,
+            change_input_hovered: false,
+            change_output_hovered: false,
+            choose_faces_hovered: false,
+            cancel_hovered: false,
+            rescan_hovered: false,
+            face_card_hovered: HashSet::new(),
+            show_folder_hovered: false,
+            blur_another_hovered: false,
+            restore_defaults_hovered: false,
+            website_hovered: false,
+        };
+        app.apply_system_signals(
+            crate::platform::prefers_reduced_motion(),
+            crate::platform::is_power_saving(),
+        );
+        let check_monitor = window::oldest().and_then(|id| {
+            window::monitor_size(id).map(move |size| Message::MonitorDetected(id, size))
+        });
+        (app, check_monitor)
     }
 
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::TabSelected(tab) => {
                 self.active_tab = tab;
+                self.settings.last_tab = tab;
+                self.settings.save();
                 return operation::snap_to(SCROLL_ID, operation::RelativeOffset::START);
             }
             Message::OpenWebsite => {
                 let _ = open::that(WEBSITE_URL);
             }
             Message::SelectInput => return self.pick_input_file(),
-            Message::InputSelected(Some(path)) => self.set_input(path),
+            Message::InputSelected(Some(path)) => {
+                if !self.checklist_holds() {
+                    self.set_input(path);
+                }
+            }
             Message::InputSelected(None) => {}
             Message::SelectOutput => return self.pick_output_file(),
             Message::OutputSelected(Some(path)) => {
@@ -195,23 +472,81 @@ impl App {
             Message::RunPreview => self.start_preview(),
             Message::RunBlur => self.start_blur(),
             Message::CancelWork => {
+                // Cancelling from the restored window keeps the app open.
+                self.pending_exit = None;
                 if let Some(ref cancel) = self.worker_cancel {
-                    cancel.store(true, Ordering::Relaxed);
+                    cancel.cancel(CancelReason::User);
                 }
             }
             Message::WorkerTick => {
-                self.drain_preview_messages();
-                self.drain_blur_messages();
+                self.drain_prefetch_messages();
+                let scanned = self.drain_preview_messages();
+                let exported = self.drain_blur_messages();
+                self.drain_capture_messages();
+                self.drain_compare_messages();
+                self.drain_model_update_messages();
+                if self.keyword_review.is_some() {
+                    // The export waits for the review; bring the window back.
+                    if let Some(PendingExit::WhenDone(id)) = self.pending_exit.take() {
+                        return Task::batch([window::minimize(id, false), window::gain_focus(id)]);
+                    }
+                }
+                if self.worker_rx.is_none() {
+                    if self.close_prompt.take().is_some() {
+                        return self.exit();
+                    }
+                    if let Some(pending) = self.pending_exit.take() {
+                        return self.finish_pending_exit(pending);
+                    }
+                }
+                if let Some(outcome) = exported.or(scanned) {
+                    return self.announce(outcome);
+                }
+            }
+            Message::CompareDragStarted => {
+                if let Some(ref mut compare) = self.compare {
+                    compare.start_drag();
+                }
+            }
+            Message::CompareDragEnded => {
+                if let Some(ref mut compare) = self.compare {
+                    compare.end_drag();
+                }
+            }
+            Message::ComparePointerMoved(fraction) => {
+                if let Some(ref mut compare) = self.compare {
+                    compare.pointer_moved(fraction);
+                }
             }
+            Message::SampleFrameChanged(frame) => self.sample_frame = Some(frame as usize),
+            Message::SampleFrameReleased => self.refresh_compare(),
             Message::ToggleFace(track_id) => {
                 self.faces_well.toggle_face(track_id);
+                self.refresh_compare();
             }
             Message::ToggleGroup(group_idx) => {
                 self.faces_well.toggle_group(group_idx);
+                self.refresh_compare();
             }
             Message::GroupFacesToggled(enabled) => {
                 self.faces_well.group_faces = enabled;
             }
+            Message::EditTrack(track_id) => {
+                self.faces_well.editing = track_id;
+            }
+            Message::TrackLabelChanged(track_id, label) => {
+                self.faces_well.project.set_label(track_id, label);
+                self.save_project();
+            }
+            Message::TrackNoteChanged(track_id, note) => {
+                self.faces_well.project.set_note(track_id, note);
+                self.save_project();
+            }
+            Message::TrackRangesChanged(track_id, ranges) => {
+                self.faces_well.project.set_blur_ranges(track_id, ranges);
+                self.save_project();
+                self.refresh_compare();
+            }
             Message::ShowInFolder => {
                 if let Some(ref output) = self.output_path {
                     if let Some(parent) = output.parent() {
@@ -219,39 +554,204 @@ impl App {
                     }
                 }
             }
-            Message::StartOver => self.reset(),
-            Message::BlurShapeChanged(shape) => {
-                self.settings.blur_shape = shape;
+            Message::DragOutput => return self.start_output_drag(),
+            Message::OutputDragEnded(Ok(())) => {}
+            Message::OutputDragEnded(Err(e)) => {
+                log::warn!("Drag-out failed: {e}");
+            }
+            Message::StartOver => {
+                if !self.checklist_holds() {
+                    self.reset();
+                }
+            }
+            Message::ToggleKeywordHit(index) => {
+                if let Some(ref mut review) = self.keyword_review {
+                    review.toggle(index);
+                }
+            }
+            Message::ConfirmKeywordReview => {
+                if let Some(review) = self.keyword_review.take() {
+                    review.confirm();
+                }
+            }
+            Message::ToggleChecklistItem(index) => {
+                if let Some(ref mut checklist) = self.checklist {
+                    checklist.toggle(index);
+                }
+            }
+            Message::DismissChecklist => {
+                if self.checklist.as_ref().is_some_and(Checklist::can_dismiss) {
+                    self.checklist = None;
+                }
+            }
+            Message::DismissWarnings => self.job_warnings.clear(),
+            Message::ShowMetadata => {
+                if let Some(ref input) = self.input_path {
+                    self.metadata_info =
+                        Some(read_source_metadata(input).map_err(|e| e.to_string()));
+                }
+            }
+            Message::CloseMetadata => self.metadata_info = None,
+            Message::InspectReport => return self.pick_report_file(),
+            Message::ReportPicked(Some(path)) => {
+                let inspection = inspect_report(&path).map_err(|e| e.to_string());
+                self.report_inspection = Some((path, inspection));
+            }
+            Message::ReportPicked(None) => {}
+            Message::ExportSelection => return self.pick_selection_file(),
+            Message::SelectionExportPicked(Some(path)) => self.export_selection(&path),
+            Message::SelectionExportPicked(None) => {}
+            Message::CloseInspection => self.report_inspection = None,
+            Message::RunCommand(command) => return self.run_command(command),
+            Message::OpenCommandPalette => {
+                if self.onboarding.is_none() {
+                    self.command_palette = Some(String::new());
+                    return operation::focus(command_palette::INPUT_ID);
+                }
+            }
+            Message::CommandPaletteQueryChanged(query) => {
+                if self.command_palette.is_some() {
+                    self.command_palette = Some(query);
+                }
+            }
+            Message::CommandPaletteSubmit => {
+                let first = self
+                    .command_palette
+                    .as_deref()
+                    .and_then(|query| command_palette::matching(query).first().copied());
+                if let Some(command) = first {
+                    return self.run_command(command);
+                }
+            }
+            Message::CloseCommandPalette => self.command_palette = None,
+            Message::CaptureSourceChanged(source) => {
+                self.settings.capture_source = source;
                 self.settings.save();
             }
-            Message::ConfidenceChanged(val) => {
-                self.settings.confidence = val;
+            Message::CaptureWindowChanged(window) => {
+                self.capture_window = window;
+            }
+            Message::TrimStartChanged(start) => {
+                self.trim_start = start;
+            }
+            Message::TrimEndChanged(end) => {
+                self.trim_end = end;
+            }
+            Message::StartRecording => self.start_recording(),
+            Message::StopRecording => {
+                if let Some(ref stop) = self.capture_stop {
+                    stop.store(true, Ordering::Relaxed);
+                }
+            }
+            message @ (Message::PresetChanged(_)
+            | Message::ConfidenceChanged(_)
+            | Message::BlurCoverageChanged(_)
+            | Message::CenterOffsetChanged(_)
+            | Message::DetectionIntervalChanged(_)
+            | Message::RestoreDefaults) => self.change_detection_settings(message),
+            Message::BlurShapeChanged(shape) => {
+                self.settings.blur_shape = shape;
                 self.settings.save();
-                self.invalidate_detection();
+                self.refresh_compare();
             }
             Message::BlurStrengthChanged(val) => {
                 self.settings.blur_strength = if val % 2 == 0 { val + 1 } else { val };
                 self.settings.save();
+                self.refresh_compare();
             }
-            Message::BlurCoverageChanged(val) => {
-                self.settings.blur_coverage = val;
+            Message::BlurFeatherChanged(val) => {
+                self.settings.blur_feather = val;
                 self.settings.save();
-                self.invalidate_detection();
-            }
-            Message::CenterOffsetChanged(val) => {
-                self.settings.center_offset = val;
-                self.settings.save();
-                self.invalidate_detection();
+                self.refresh_compare();
             }
             Message::LookaheadChanged(val) => {
                 self.settings.lookahead = val;
                 self.settings.save();
+                self.refresh_compare();
+            }
+            Message::ConfirmRescan => {
+                let changes = std::mem::take(&mut self.pending_changes);
+                self.apply_settings(self.settings_with(&changes));
+            }
+            Message::KeepSelection => {
+                self.pending_changes.clear();
+                self.refresh_compare();
             }
             Message::QualityChanged(val) => {
                 self.settings.quality = val;
                 self.settings.save();
             }
-            Message::RestoreDefaults => self.restore_defaults(),
+            Message::VideoCodecChanged(codec) => {
+                self.settings.video_codec = codec;
+                self.settings.save();
+                self.refresh_output_extension();
+            }
+            Message::ImageFormatChanged(format) => {
+                self.settings.image_format = format;
+                self.settings.save();
+                self.refresh_output_extension();
+            }
+            Message::ImageQualityChanged(val) => {
+                self.settings.image_quality = val;
+                self.settings.save();
+            }
+            Message::PngCompressionChanged(compression) => {
+                self.settings.png_compression = compression;
+                self.settings.save();
+            }
+            Message::CheckModelUpdates => {
+                if self.model_update_rx.is_none() {
+                    self.model_updates = ModelUpdateState::Checking;
+                    self.model_update_rx = Some(model_update_worker::spawn_check());
+                }
+            }
+            Message::InstallModelUpdates => {
+                if let ModelUpdateState::Available(ref updates) = self.model_updates {
+                    let updates = updates.clone();
+                    self.model_update_rx = Some(model_update_worker::spawn_install(
+                        updates.clone(),
+                        self.shutdown.child(),
+                    ));
+                    self.model_updates = ModelUpdateState::Installing(updates, 0, 0, 0);
+                }
+            }
+            Message::WindowMoved(id, position) => {
+                self.window_position = Some(position);
+                // The window may have been dragged to another monitor.
+                return window::monitor_size(id)
+                    .map(move |size| Message::MonitorDetected(id, size));
+            }
+            Message::WindowResized(size) => {
+                self.window_size = size;
+                self.faces_well.content_width = size.width - CONTENT_INSET;
+            }
+            Message::WindowFocusChanged(id, focused) => {
+                self.unfocused_window = (!focused).then_some(id);
+            }
+            Message::MonitorDetected(id, size) => return self.check_monitor(id, size),
+            Message::WindowCloseRequested(id) => {
+                if self.worker_rx.is_none() {
+                    return self.exit();
+                }
+                if !matches!(self.pending_exit, Some(PendingExit::AfterCleanup)) {
+                    self.active_tab = Tab::Blur;
+                    self.close_prompt = Some(id);
+                }
+            }
+            Message::CancelAndQuit => {
+                self.close_prompt = None;
+                self.pending_exit = Some(PendingExit::AfterCleanup);
+                if let Some(ref cancel) = self.worker_cancel {
+                    cancel.cancel(CancelReason::Shutdown);
+                }
+            }
+            Message::QuitWhenDone => {
+                if let Some(id) = self.close_prompt.take() {
+                    self.pending_exit = Some(PendingExit::WhenDone(id));
+                    return window::minimize(id, true);
+                }
+            }
+            Message::KeepWindowOpen => self.close_prompt = None,
             Message::AppearanceChanged(appearance) => {
                 self.settings.appearance = appearance;
                 self.settings.save();
@@ -259,6 +759,22 @@ impl App {
             Message::HighContrastChanged(enabled) => {
                 self.settings.high_contrast = enabled;
                 self.settings.save();
+                self.sync_compare_overlay();
+            }
+            Message::ShowRegionsToggled(show) => {
+                self.settings.show_regions = show;
+                self.settings.save();
+                self.sync_compare_overlay();
+            }
+            Message::ShowDetectionsToggled(show) => {
+                self.settings.show_detections = show;
+                self.settings.save();
+                self.sync_compare_overlay();
+            }
+            Message::OverlayPaletteChanged(palette) => {
+                self.settings.overlay_palette = palette;
+                self.settings.save();
+                self.sync_compare_overlay();
             }
             Message::FontScaleChanged(scale) => {
                 self.settings.font_scale = scale;
@@ -268,10 +784,39 @@ impl App {
                 self.settings.audio_processing = enabled;
                 self.settings.save();
             }
+            Message::AnonymizeThumbnailsChanged(enabled) => {
+                self.settings.anonymize_thumbnails = enabled;
+                self.settings.save();
+            }
+            Message::CacheDetectionsChanged(enabled) => {
+                self.settings.cache_detections = enabled;
+                self.settings.save();
+            }
+            Message::SkipBriefDetectionsChanged(enabled) => {
+                self.settings.skip_brief_detections = enabled;
+                self.settings.save();
+            }
+            Message::DetectorHardwareChanged(hardware) => {
+                self.settings.detector_hardware = hardware;
+                self.settings.save();
+                self.model_cache.set_detector_backend(hardware.backend());
+            }
             Message::BleepKeywordsChanged(keywords) => {
                 self.settings.bleep_keywords = keywords;
                 self.settings.save();
             }
+            Message::BleepPiiChanged(category, enabled) => {
+                self.settings.set_bleeps_pii(category, enabled);
+                self.settings.save();
+            }
+            Message::SpeechLanguageChanged(language) => {
+                self.settings.speech_language = language.code().to_string();
+                self.settings.save();
+            }
+            Message::KeywordMatchingChanged(matching) => {
+                self.settings.keyword_matching = matching;
+                self.settings.save();
+            }
             Message::BleepSoundChanged(sound) => {
                 self.settings.bleep_sound = sound;
                 self.settings.save();
@@ -280,7 +825,54 @@ impl App {
                 self.settings.voice_disguise = disguise;
                 self.settings.save();
             }
+            Message::AudioFallbackChanged(fallback) => {
+                self.settings.audio_fallback = fallback;
+                self.settings.save();
+            }
+            Message::PostExportChecklistChanged(enabled) => {
+                self.settings.post_export_checklist = enabled;
+                self.settings.save();
+            }
+            Message::FinishSoundChanged(enabled) => {
+                self.settings.finish_sound = enabled;
+                self.settings.save();
+            }
+            Message::FinishAttentionChanged(enabled) => {
+                self.settings.finish_attention = enabled;
+                self.settings.save();
+            }
+            Message::WriteLedgerChanged(enabled) => {
+                self.settings.write_ledger = enabled;
+                self.settings.save();
+            }
+            Message::LedgerOperatorChanged(operator) => {
+                self.settings.ledger_operator = operator;
+                self.settings.save();
+            }
+            Message::LedgerPassphraseChanged(passphrase) => {
+                self.ledger_passphrase = passphrase;
+            }
             Message::PollSystemTheme => {}
+            Message::PollSystemSignals => {
+                // Probing spawns OS tools on macOS and Linux, so keep it off
+                // the UI thread.
+                return Task::perform(
+                    async {
+                        (
+                            crate::platform::prefers_reduced_motion(),
+                            crate::platform::is_power_saving(),
+                        )
+                    },
+                    |(reduced_motion, power_saving)| Message::SystemSignals {
+                        reduced_motion,
+                        power_saving,
+                    },
+                );
+            }
+            Message::SystemSignals {
+                reduced_motion,
+                power_saving,
+            } => self.apply_system_signals(reduced_motion, power_saving),
             Message::FileDropped(path) => {
                 self.drop_zone_hovered = false;
                 if has_supported_extension(&path) {
@@ -328,6 +920,26 @@ impl App {
             Message::WebsiteHover(hovered) => {
                 self.website_hovered = hovered;
             }
+            Message::ChooseModelDir => return self.pick_model_dir(),
+            Message::ModelDirSelected(Some(dir)) => {
+                if let Some(ref mut onboarding) = self.onboarding {
+                    onboarding.model_dir = dir;
+                    onboarding.error = None;
+                }
+            }
+            Message::ModelDirSelected(None) => {}
+            Message::DownloadModels => self.start_prefetch(),
+            Message::SkipOnboarding => self.finish_onboarding(),
+            Message::DownloadModelsHover(hovered) => {
+                if let Some(ref mut onboarding) = self.onboarding {
+                    onboarding.download_hovered = hovered;
+                }
+            }
+            Message::SkipOnboardingHover(hovered) => {
+                if let Some(ref mut onboarding) = self.onboarding {
+                    onboarding.skip_hovered = hovered;
+                }
+            }
             Message::FaceCardHover(id, hovered) => {
                 if hovered {
                     self.face_card_hovered.insert(id);
@@ -344,6 +956,13 @@ impl App {
         let current_theme = self.theme();
         let palette = current_theme.palette();
 
+        if let Some(ref onboarding) = self.onboarding {
+            let content = container(tabs::onboarding::view(fs, onboarding, &current_theme))
+                .center_x(Length::Fill)
+                .padding(32);
+            return scrollable(content).height(Length::Fill).into();
+        }
+
         let surface = theme::surface_color(&current_theme);
         let border_light = iced::Color {
             a: 0.12,
@@ -391,7 +1010,16 @@ impl App {
                 self.output_path.as_deref(),
                 &self.processing,
                 &self.faces_well,
+                self.compare.as_ref(),
+                self.compare_rx.is_some(),
+                self.preview_controls(),
+                self.blur_estimate(),
+                self.weak_blur(),
+                self.trim_fields(),
                 &current_theme,
+                self.settings.capture_source,
+                &self.capture_window,
+                self.capture_error.as_deref(),
                 self.browse_hovered,
                 self.drop_zone_hovered,
                 self.blur_button_hovered,
@@ -405,12 +1033,67 @@ impl App {
                 self.blur_another_hovered,
             ),
             Tab::Settings => tabs::settings_tab::view(
-                &self.settings,
+                &self.shown_settings(),
+                self.selection_guard(),
+                self.detection_interval_effect(),
                 self.gpu_context.is_some(),
+                &self.model_updates,
+                &self.ledger_passphrase,
+                &self.checklist_policy,
                 self.restore_defaults_hovered,
             ),
             Tab::About => tabs::about_tab::view(fs, &current_theme, self.website_hovered),
         };
+        let content: Element<'_, Message> = if self.active_tab == Tab::Blur {
+            let mut stacked = column![].spacing(16);
+            if self.close_prompt.is_some() {
+                stacked = stacked.push(tabs::main_tab::close_prompt(fs, &current_theme));
+            } else if matches!(self.pending_exit, Some(PendingExit::AfterCleanup)) {
+                stacked = stacked.push(tabs::main_tab::quitting_notice(fs, &current_theme));
+            }
+            if let Some(ref query) = self.command_palette {
+                stacked = stacked.push(command_palette::view(fs, query, &current_theme));
+            }
+            if let Some(ref checklist) = self.checklist {
+                stacked = stacked.push(tabs::main_tab::checklist_panel(
+                    fs,
+                    checklist,
+                    &current_theme,
+                ));
+            }
+            if let Some(ref review) = self.keyword_review {
+                stacked = stacked.push(tabs::main_tab::keyword_review_panel(
+                    fs,
+                    review,
+                    &current_theme,
+                ));
+            }
+            if !self.job_warnings.is_empty() {
+                stacked = stacked.push(tabs::main_tab::warnings_banner(
+                    fs,
+                    &self.job_warnings,
+                    &current_theme,
+                ));
+            }
+            if let Some(ref info) = self.metadata_info {
+                stacked = stacked.push(tabs::main_tab::metadata_panel(fs, info, &current_theme));
+            }
+            if let Some((ref path, ref inspection)) = self.report_inspection {
+                stacked = stacked.push(tabs::main_tab::inspection_panel(
+                    fs,
+                    path,
+                    inspection,
+                    &current_theme,
+                ));
+            }
+            stacked.push(content).into()
+        } else if let Some(ref query) = self.command_palette {
+            column![command_palette::view(fs, query, &current_theme), content]
+                .spacing(16)
+                .into()
+        } else {
+            content
+        };
 
         let padded_content = container(content).padding(iced::Padding {
             top: 24.0,
@@ -429,6 +1112,20 @@ impl App {
             .into()
     }
 
+    /// The window title, which also shows an export's progress in the
+    /// taskbar or dock while the window is minimized to finish it.
+    pub fn title(&self) -> String {
+        match (self.pending_exit, &self.processing) {
+            (Some(PendingExit::WhenDone(_)), ProcessingState::Blurring(current, total))
+                if *total > 0 =>
+            {
+                format!("Blurring {}% \u{2014} FaceGuard", current * 100 / total)
+            }
+            (Some(PendingExit::WhenDone(_)), _) => "Exporting \u{2014} FaceGuard".to_string(),
+            _ => APP_TITLE.to_string(),
+        }
+    }
+
     pub fn theme(&self) -> Theme {
         theme::resolve_theme(self.settings.appearance, self.settings.high_contrast)
     }
@@ -440,24 +1137,55 @@ impl App {
             subs.push(iced::time::every(Duration::from_secs(2)).map(|_| Message::PollSystemTheme));
         }
 
-        if self.worker_rx.is_some() || self.preview_rx.is_some() {
+        subs.push(
+            iced::time::every(SYSTEM_SIGNALS_POLL_INTERVAL).map(|_| Message::PollSystemSignals),
+        );
+
+        let prefetching = self
+            .onboarding
+            .as_ref()
+            .is_some_and(OnboardingState::is_downloading);
+        if self.worker_rx.is_some()
+            || self.preview_rx.is_some()
+            || self.capture_rx.is_some()
+            || self.compare_rx.is_some()
+            || self.model_update_rx.is_some()
+            || prefetching
+        {
             subs.push(iced::time::every(Duration::from_millis(50)).map(|_| Message::WorkerTick));
         }
 
-        subs.push(iced::event::listen_with(
-            |event, _status, _id| match event {
-                iced::Event::Window(iced::window::Event::FileDropped(path)) => {
-                    Some(Message::FileDropped(path))
-                }
-                iced::Event::Window(iced::window::Event::FileHovered(_)) => {
-                    Some(Message::DropZoneHover(true))
-                }
-                iced::Event::Window(iced::window::Event::FilesHoveredLeft) => {
-                    Some(Message::DropZoneHover(false))
-                }
-                _ => None,
-            },
-        ));
+        subs.push(iced::event::listen_with(|event, status, id| match event {
+            iced::Event::Keyboard(iced::keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+                Command::from_key(&key, modifiers, status == iced::event::Status::Captured)
+                    .map(Message::RunCommand)
+            }
+            iced::Event::Window(iced::window::Event::FileDropped(path)) => {
+                Some(Message::FileDropped(path))
+            }
+            iced::Event::Window(iced::window::Event::FileHovered(_)) => {
+                Some(Message::DropZoneHover(true))
+            }
+            iced::Event::Window(iced::window::Event::FilesHoveredLeft) => {
+                Some(Message::DropZoneHover(false))
+            }
+            iced::Event::Window(iced::window::Event::Moved(position)) => {
+                Some(Message::WindowMoved(id, position))
+            }
+            iced::Event::Window(iced::window::Event::Resized(size)) => {
+                Some(Message::WindowResized(size))
+            }
+            iced::Event::Window(iced::window::Event::Focused) => {
+                Some(Message::WindowFocusChanged(id, true))
+            }
+            iced::Event::Window(iced::window::Event::Unfocused) => {
+                Some(Message::WindowFocusChanged(id, false))
+            }
+            iced::Event::Window(iced::window::Event::CloseRequested) => {
+                Some(Message::WindowCloseRequested(id))
+            }
+            _ => None,
+        }));
 
         Subscription::batch(subs)
     }
@@ -466,6 +1194,71 @@ impl App {
 // --- Private helpers (lower-level details) ---
 
 impl App {
+    /// Runs a command from a shortcut or the palette. Commands that don't
+    /// apply to the current state are ignored, as their buttons would be
+    /// hidden.
+    fn run_command(&mut self, command: Command) -> Task<Message> {
+        if command != Command::Cancel {
+            self.command_palette = None;
+        }
+        if self.onboarding.is_some() {
+            return Task::none();
+        }
+        let idle = matches!(
+            self.processing,
+            ProcessingState::Idle | ProcessingState::Previewed | ProcessingState::Error(_)
+        );
+        match command {
+            Command::OpenFile if idle => {
+                self.active_tab = Tab::Blur;
+                return self.pick_input_file();
+            }
+            Command::Blur if idle && self.input_path.is_some() => {
+                self.active_tab = Tab::Blur;
+                self.start_blur();
+            }
+            Command::ChooseFaces
+                if self.input_path.is_some()
+                    && matches!(
+                        self.processing,
+                        ProcessingState::Idle | ProcessingState::Previewed
+                    ) =>
+            {
+                self.active_tab = Tab::Blur;
+                self.start_preview();
+            }
+            Command::Cancel => {
+                if self.command_palette.take().is_none() {
+                    return self.update(Message::CancelWork);
+                }
+            }
+            Command::ExportSelection if self.faces_well.has_faces() => {
+                return self.update(Message::ExportSelection);
+            }
+            Command::ShowMetadata if self.input_path.is_some() => {
+                self.active_tab = Tab::Blur;
+                return self.update(Message::ShowMetadata);
+            }
+            Command::InspectReport => {
+                self.active_tab = Tab::Blur;
+                return self.update(Message::InspectReport);
+            }
+            Command::StartOver
+                if (idle || matches!(self.processing, ProcessingState::Complete))
+                    && !self.checklist_holds() =>
+            {
+                self.active_tab = Tab::Blur;
+                self.reset();
+            }
+            Command::BlurTab => return self.update(Message::TabSelected(Tab::Blur)),
+            Command::Settings => return self.update(Message::TabSelected(Tab::Settings)),
+            Command::About => return self.update(Message::TabSelected(Tab::About)),
+            Command::CommandPalette => return self.update(Message::OpenCommandPalette),
+            _ => {}
+        }
+        Task::none()
+    }
+
     fn pick_input_file(&self) -> Task<Message> {
         Task::perform(
             async {
@@ -480,21 +1273,277 @@ impl App {
         )
     }
 
+    fn pick_report_file(&self) -> Task<Message> {
+        Task::perform(
+            async {
+                rfd::AsyncFileDialog::new()
+                    .set_title("Inspect report")
+                    .add_filter("Reports", &["json", "csv"])
+                    .pick_file()
+                    .await
+                    .map(|h| h.path().to_path_buf())
+            },
+            Message::ReportPicked,
+        )
+    }
+
+    fn pick_selection_file(&self) -> Task<Message> {
+        let start = self.input_path.as_ref().map(|input| {
+            let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+            input.with_file_name(format!("{stem}.selection.json"))
+        });
+        Task::perform(
+            async move {
+                let mut dialog = rfd::AsyncFileDialog::new()
+                    .set_title("Export face selection")
+                    .add_filter("Face selection", &["json"]);
+                if let Some(start) = start {
+                    if let Some(dir) = start.parent() {
+                        dialog = dialog.set_directory(dir);
+                    }
+                    if let Some(name) = start.file_name() {
+                        dialog = dialog.set_file_name(name.to_string_lossy());
+                    }
+                }
+                dialog.save_file().await.map(|h| h.path().to_path_buf())
+            },
+            Message::SelectionExportPicked,
+        )
+    }
+
+    /// Saves the faces well's selection, labels, and blur ranges for the
+    /// CLI's `--selection`, so the export can run on another machine.
+    fn export_selection(&self, path: &std::path::Path) {
+        let Some(ref input) = self.input_path else {
+            return;
+        };
+        let settings = &self.settings;
+        let scan = detection_cache::cli_params(
+            settings.confidence,
+            settings.blur_coverage,
+            settings.center_offset,
+            settings.detection_interval,
+        );
+        let mut track_ids: Vec<u32> = self.faces_well.crops.keys().copied().collect();
+        track_ids.sort();
+        let selection = self.faces_well.project.selection(
+            input,
+            scan,
+            &track_ids,
+            &self.faces_well.selected,
+            &self.faces_well.groups,
+        );
+        match selection.save(path) {
+            Ok(()) => log::info!("Face selection written to {}", path.display()),
+            Err(e) => log::warn!("Failed to export face selection: {e}"),
+        }
+    }
+
+    fn pick_model_dir(&self) -> Task<Message> {
+        let start_dir = self.onboarding.as_ref().map(|o| o.model_dir.clone());
+        Task::perform(
+            async move {
+                let mut dialog = rfd::AsyncFileDialog::new().set_title("Choose model folder");
+                if let Some(dir) = start_dir.filter(|d| d.exists()) {
+                    dialog = dialog.set_directory(dir);
+                }
+                dialog.pick_folder().await.map(|h| h.path().to_path_buf())
+            },
+            Message::ModelDirSelected,
+        )
+    }
+
+    /// Stores the chosen model folder (only if it differs from the default)
+    /// and points the model resolver at it.
+    fn commit_model_dir(&mut self) {
+        let Some(ref onboarding) = self.onboarding else {
+            return;
+        };
+        let default_dir = model_resolver::model_cache_dir().ok();
+        if self.settings.model_dir.is_some() || Some(&onboarding.model_dir) != default_dir.as_ref()
+        {
+            self.settings.model_dir = Some(onboarding.model_dir.clone());
+        }
+        self.settings.apply_model_dir();
+    }
+
+    fn start_prefetch(&mut self) {
+        self.commit_model_dir();
+        if let Some(ref mut onboarding) = self.onboarding {
+            onboarding.error = None;
+            onboarding.download = None;
+            onboarding.prefetch_rx = Some(prefetch_worker::spawn(self.shutdown.child()));
+        }
+    }
+
+    fn finish_onboarding(&mut self) {
+        self.commit_model_dir();
+        self.onboarding = None;
+        self.settings.onboarded = true;
+        self.settings.save();
+        self.model_cache.start();
+    }
+
+    fn drain_prefetch_messages(&mut self) {
+        let Some(onboarding) = self.onboarding.as_mut() else {
+            return;
+        };
+        let messages: Vec<_> = onboarding
+            .prefetch_rx
+            .as_ref()
+            .map(|rx| rx.try_iter().collect())
+            .unwrap_or_default();
+
+        for msg in messages {
+            match msg {
+                PrefetchMessage::Progress(model, dl, total) => {
+                    onboarding.download = Some((model, dl, total));
+                }
+                PrefetchMessage::Complete => {
+                    self.finish_onboarding();
+                    return;
+                }
+                PrefetchMessage::Error(e) => {
+                    onboarding.download = None;
+                    onboarding.prefetch_rx = None;
+                    onboarding.error = Some(e);
+                }
+            }
+        }
+    }
+
     fn set_input(&mut self, path: PathBuf) {
+        if self.capture_rx.is_some() {
+            // The recorder is still writing into the capture folder.
+            return;
+        }
         let stem = path
             .file_stem()
             .unwrap_or_default()
             .to_string_lossy()
             .to_string();
-        let ext = path
-            .extension()
-            .map(|e| format!(".{}", e.to_string_lossy()))
-            .unwrap_or_default();
-        self.output_path = Some(path.with_file_name(format!("{stem}_blurred{ext}")));
+        let ext = self.output_extension(&path);
+        let name = if ext.is_empty() {
+            format!("{stem}_blurred")
+        } else {
+            format!("{stem}_blurred.{ext}")
+        };
+        self.output_path = Some(path.with_file_name(name));
+        if !self.is_raw_recording(&path) {
+            self.capture_dir = None;
+        }
+        self.faces_well.project = Project::load(&path);
+        self.input_size = probe_input_size(&path);
+        self.trim_start.clear();
+        self.trim_end.clear();
         self.input_path = Some(path);
         self.processing = ProcessingState::Idle;
         self.faces_well.clear();
         self.detection_cache = None;
+        self.release_pending_changes();
+        self.clear_compare();
+        self.job_warnings.clear();
+        self.checklist = None;
+        self.metadata_info = None;
+    }
+
+    fn save_project(&self) {
+        if let Some(ref input) = self.input_path {
+            self.faces_well.project.save(input);
+        }
+    }
+
+    /// Writes the anonymization report next to the output, listing each
+    /// detected track with its label, note, and whether it was blurred,
+    /// and any warnings the job raised. Tracks are only known after a scan,
+    /// since that is what assigns track IDs.
+    fn write_report(&self) {
+        let (Some(input), Some(output)) = (&self.input_path, &self.output_path) else {
+            return;
+        };
+        if !self.faces_well.has_faces() && self.job_warnings.is_empty() {
+            return;
+        }
+        let mut track_ids: Vec<u32> = self.faces_well.crops.keys().copied().collect();
+        track_ids.sort();
+        match self.faces_well.project.write_report(
+            input,
+            output,
+            &track_ids,
+            &self.faces_well.selected,
+            &self.job_warnings,
+        ) {
+            Ok(path) => log::info!("Report written to {}", path.display()),
+            Err(e) => log::warn!("Failed to write report: {e}"),
+        }
+    }
+
+    /// Writes the anonymization ledger next to the output when enabled:
+    /// every track of the face scan, whether it was blurred, and when it
+    /// appears, with the settings behind those decisions.
+    fn write_ledger(&self) {
+        if !self.settings.write_ledger {
+            return;
+        }
+        let (Some(input), Some(output)) = (&self.input_path, &self.output_path) else {
+            return;
+        };
+        let Some(ref detections) = self.detection_cache else {
+            log::warn!("No ledger written: it lists the tracks of a face scan, and none was run");
+            return;
+        };
+        let blurred = self.faces_well.get_selected_ids().unwrap_or_else(|| {
+            detections
+                .values()
+                .flatten()
+                .filter_map(|region| region.track_id)
+                .collect()
+        });
+        let settings = &self.settings;
+        let ledger = AnonymizationLedger::new(input, output, &settings.ledger_operator)
+            .with_setting("blur_shape", settings.blur_shape)
+            .with_setting("blur_strength", settings.blur_strength)
+            .with_setting("blur_coverage_percent", settings.blur_coverage)
+            .with_setting("confidence_percent", settings.confidence)
+            .with_setting("detection_interval", settings.detection_interval);
+        let fps = self.input_size.as_ref().map_or(0.0, |size| size.fps);
+        match self.faces_well.project.write_ledger(
+            output,
+            ledger,
+            detections,
+            fps,
+            &blurred,
+            &self.ledger_passphrase,
+        ) {
+            Ok(path) => log::info!("Ledger written to {}", path.display()),
+            Err(e) => log::warn!("Failed to write ledger: {e}"),
+        }
+    }
+
+    /// Output extension for `input`: the input's own, unless it is an image
+    /// and an image format override is set, or a video whose container
+    /// can't carry the chosen codec.
+    fn output_extension(&self, input: &std::path::Path) -> String {
+        let input_ext = input
+            .extension()
+            .map(|e| e.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let is_image = IMAGE_EXTENSIONS.contains(&input_ext.to_lowercase().as_str());
+        if !is_image {
+            return self.settings.video_codec.output_extension(&input_ext);
+        }
+        match self.settings.image_format.extension() {
+            Some(ext) => ext.to_string(),
+            None => input_ext,
+        }
+    }
+
+    /// Re-derives the output's extension after a format or codec change.
+    fn refresh_output_extension(&mut self) {
+        if let (Some(input), Some(output)) = (&self.input_path, &self.output_path) {
+            let ext = self.output_extension(input);
+            self.output_path = Some(output.with_extension(ext));
+        }
     }
 
     fn pick_output_file(&self) -> Task<Message> {
@@ -524,6 +1573,34 @@ impl App {
         )
     }
 
+    /// Follow the OS reduced-motion and power-saver signals. Running jobs
+    /// share the power-saving flag, so they speed up again when plugged in.
+    fn apply_system_signals(&mut self, reduced_motion: bool, saving: bool) {
+        theme::set_reduced_motion(reduced_motion);
+
+        if self.power_saving.swap(saving, Ordering::Relaxed) != saving {
+            log::info!(
+                "Power saving {}: processing {}",
+                if saving { "on" } else { "off" },
+                if saving { "throttled" } else { "at full speed" }
+            );
+        }
+    }
+
+    fn start_output_drag(&self) -> Task<Message> {
+        let Some(path) = self.output_path.clone() else {
+            return Task::none();
+        };
+        iced::window::oldest()
+            .and_then(move |id| {
+                let path = path.clone();
+                iced::window::run(id, move |window| {
+                    crate::platform::start_file_drag(window, &path)
+                })
+            })
+            .map(Message::OutputDragEnded)
+    }
+
     fn start_preview(&mut self) {
         if let Some(input) = self.input_path.clone() {
             let params = PreviewParams {
@@ -531,9 +1608,15 @@ impl App {
                 confidence: self.settings.confidence,
                 blur_coverage: self.settings.blur_coverage,
                 center_offset: self.settings.center_offset,
+                anonymize_thumbnails: self.settings.anonymize_thumbnails,
+                cache_detections: self.settings.cache_detections,
+                min_track_frames: self.settings.min_track_frames(),
+                detection_interval: self.settings.detection_interval,
                 model_cache: self.model_cache.clone(),
+                power_saving: self.power_saving.clone(),
             };
-            let (rx, cancel) = preview_worker::spawn(params);
+            self.supersede_worker();
+            let (rx, cancel) = preview_worker::spawn(params, &self.shutdown);
             self.preview_rx = Some(rx);
             self.worker_cancel = Some(cancel);
             self.processing = ProcessingState::Preparing;
@@ -541,6 +1624,10 @@ impl App {
     }
 
     fn start_blur(&mut self) {
+        let Ok(trim) = self.trim() else {
+            // The error is already shown under the trim fields.
+            return;
+        };
         if let (Some(input), Some(output)) = (self.input_path.clone(), self.output_path.clone()) {
             let params = BlurParams {
                 input_path: input,
@@ -548,27 +1635,117 @@ impl App {
                 blur_shape: self.settings.blur_shape,
                 confidence: self.settings.confidence,
                 blur_strength: self.settings.blur_strength,
+                blur_feather: self.settings.blur_feather,
                 blur_coverage: self.settings.blur_coverage,
                 center_offset: self.settings.center_offset,
                 lookahead: self.settings.lookahead,
                 quality: self.settings.quality,
+                video_codec: self.settings.video_codec,
                 detection_cache: self.detection_cache.clone(),
                 blur_ids: self.faces_well.get_selected_ids(),
+                time_rules: self.faces_well.time_rules(),
+                trim,
+                reuse_unchanged_frames: self.is_raw_recording(&input),
+                cache_detections: self.settings.cache_detections,
+                min_track_frames: self.settings.min_track_frames(),
+                detection_interval: self.settings.detection_interval,
                 model_cache: self.model_cache.clone(),
                 gpu_context: self.gpu_context.clone(),
                 audio_processing: self.settings.audio_processing,
                 bleep_keywords: self.settings.bleep_keywords.clone(),
+                keyword_matching: self.settings.keyword_matching,
+                bleep_pii: self.settings.bleep_pii(),
+                speech_language: self.settings.speech_language(),
                 bleep_sound: self.settings.bleep_sound,
                 voice_disguise: self.settings.voice_disguise,
+                audio_fallback: self.settings.audio_fallback,
+                image_format: self.settings.image_format,
+                image_quality: self.settings.image_quality,
+                png_compression: self.settings.png_compression,
+                power_saving: self.power_saving.clone(),
+                warnings: Warnings::new(),
             };
-            let (rx, cancel) = blur_worker::spawn(params);
+            self.job_warnings.clear();
+            self.supersede_worker();
+            let (rx, cancel) = blur_worker::spawn(params, &self.shutdown);
             self.worker_rx = Some(rx);
             self.worker_cancel = Some(cancel);
             self.processing = ProcessingState::Preparing;
         }
     }
 
-    fn drain_preview_messages(&mut self) {
+    fn start_recording(&mut self) {
+        let target = match self.settings.capture_source {
+            CaptureSource::Screen => CaptureTarget::Screen,
+            CaptureSource::Window if self.capture_window.trim().is_empty() => {
+                self.capture_error = Some("Enter the window to record".into());
+                return;
+            }
+            CaptureSource::Window => CaptureTarget::Window(self.capture_window.clone()),
+        };
+        let dir = match tempfile::tempdir() {
+            Ok(dir) => dir,
+            Err(e) => {
+                self.capture_error = Some(format!("Could not create a temp folder: {e}"));
+                return;
+            }
+        };
+        let params = CaptureParams {
+            target,
+            output_path: dir.path().join("Screen recording.mp4"),
+            quality: self.settings.quality,
+        };
+        let (rx, stop) = capture_worker::spawn(params);
+        self.reset();
+        self.capture_dir = Some(dir);
+        self.capture_rx = Some(rx);
+        self.capture_stop = Some(stop);
+        self.processing = ProcessingState::Recording(0);
+    }
+
+    /// Whether `path` is the raw recording from the screen recorder.
+    fn is_raw_recording(&self, path: &std::path::Path) -> bool {
+        self.capture_dir
+            .as_ref()
+            .is_some_and(|dir| path.starts_with(dir.path()))
+    }
+
+    fn drain_capture_messages(&mut self) {
+        let messages: Vec<_> = self
+            .capture_rx
+            .as_ref()
+            .map(|rx| rx.try_iter().collect())
+            .unwrap_or_default();
+
+        for msg in messages {
+            match msg {
+                CaptureMessage::Recording(frames) => {
+                    self.processing = ProcessingState::Recording(frames);
+                }
+                CaptureMessage::Complete(path) => {
+                    self.capture_rx = None;
+                    self.capture_stop = None;
+                    self.set_input(path);
+                    self.output_path = Some(recording_output_path());
+                    // Anonymize right away, so the raw recording is only
+                    // ever a temp file.
+                    self.start_blur();
+                }
+                CaptureMessage::Error(e) => {
+                    self.capture_rx = None;
+                    self.capture_stop = None;
+                    self.capture_dir = None;
+                    self.processing = ProcessingState::Idle;
+                    self.capture_error = Some(e);
+                }
+            }
+        }
+    }
+
+    /// Applies the scan worker's messages, returning how the scan ended if
+    /// it did.
+    fn drain_preview_messages(&mut self) -> Option<Outcome> {
+        let mut ended = None;
         let messages: Vec<_> = self
             .preview_rx
             .as_ref()
@@ -583,16 +1760,29 @@ impl App {
                 PreviewMessage::ScanProgress(current, total) => {
                     self.processing = ProcessingState::Scanning(current, total);
                 }
+                PreviewMessage::Measured(stage, rate) => {
+                    self.settings.performance.record(stage, rate);
+                    self.settings.save();
+                }
                 PreviewMessage::Complete(result) => {
-                    self.faces_well
-                        .populate(result.crops, result.groups, result.temp_dir);
+                    self.faces_well.populate(
+                        result.crops,
+                        result.sharp_thumbnails,
+                        result.ranked_thumbnails,
+                        result.groups,
+                        result.temp_dir,
+                    );
+                    self.widest_face = widest_face(result.detection_cache.values().flatten());
                     self.detection_cache = Some(Arc::new(result.detection_cache));
                     self.processing = ProcessingState::Previewed;
                     self.clear_worker_state(true);
+                    self.refresh_compare();
+                    ended = Some(Outcome::Finished);
                 }
                 PreviewMessage::Error(e) => {
                     self.processing = ProcessingState::Error(e);
                     self.clear_worker_state(true);
+                    ended = Some(Outcome::Failed);
                 }
                 PreviewMessage::Cancelled => {
                     self.processing = ProcessingState::Idle;
@@ -600,9 +1790,13 @@ impl App {
                 }
             }
         }
+        ended
     }
 
-    fn drain_blur_messages(&mut self) {
+    /// Applies the blur worker's messages, returning how the export ended
+    /// if it did.
+    fn drain_blur_messages(&mut self) -> Option<Outcome> {
+        let mut ended = None;
         let messages: Vec<_> = self
             .worker_rx
             .as_ref()
@@ -617,13 +1811,33 @@ impl App {
                 WorkerMessage::BlurProgress(current, total) => {
                     self.processing = ProcessingState::Blurring(current, total);
                 }
+                WorkerMessage::Measured(stage, rate) => {
+                    self.settings.performance.record(stage, rate);
+                    self.settings.save();
+                }
+                WorkerMessage::ReviewHits(hits, reply) => {
+                    self.keyword_review = Some(KeywordReview::new(hits, reply));
+                }
+                WorkerMessage::Warnings(warnings) => {
+                    self.job_warnings = warnings;
+                }
                 WorkerMessage::Complete => {
+                    self.write_report();
+                    self.write_ledger();
                     self.processing = ProcessingState::Complete;
+                    if self
+                        .checklist_policy
+                        .shows(self.settings.post_export_checklist)
+                    {
+                        self.checklist = Some(Checklist::new(&self.checklist_policy));
+                    }
                     self.clear_worker_state(false);
+                    ended = Some(Outcome::Finished);
                 }
                 WorkerMessage::Error(e) => {
                     self.processing = ProcessingState::Error(e);
                     self.clear_worker_state(false);
+                    ended = Some(Outcome::Failed);
                 }
                 WorkerMessage::Cancelled => {
                     self.processing = ProcessingState::Idle;
@@ -631,6 +1845,275 @@ impl App {
                 }
             }
         }
+        ended
+    }
+
+    /// Tells the user a scan or export ended, as the settings allow: a
+    /// chime, and a flash of the window if it is in the background.
+    /// Cancelled jobs were the user's doing and stay quiet.
+    fn announce(&self, outcome: Outcome) -> Task<Message> {
+        if self.settings.finish_sound {
+            crate::platform::play_chime(outcome);
+        }
+        match self.unfocused_window {
+            Some(id) if self.settings.finish_attention => {
+                crate::platform::request_attention(id, outcome)
+            }
+            _ => Task::none(),
+        }
+    }
+
+    /// How long the blur button's job should take on this machine, from
+    /// earlier jobs. `None` until the needed stages have been measured.
+    fn blur_estimate(&self) -> Option<String> {
+        let size = self.input_size?;
+        let size = match self.trim() {
+            Ok(Some(range)) => size.trimmed(range),
+            _ => size,
+        };
+        let detections_known = self.detection_cache.is_some();
+        let seconds = self.settings.performance.blur_seconds(
+            &size,
+            self.gpu_context.is_some(),
+            detections_known,
+            self.settings.detection_interval,
+        )?;
+        Some(performance::format_estimate(seconds))
+    }
+
+    /// Whether the intensity is too low for the largest face of the scan.
+    fn weak_blur(&self) -> Option<WeakBlur> {
+        self.detection_cache.as_ref()?;
+        WeakBlur::check(self.widest_face, self.settings.blur_strength as usize)
+    }
+
+    /// The part of a video input to export, from the typed in and out
+    /// points. `Ok(None)` exports all of it.
+    fn trim(&self) -> Result<Option<TimeRange>, String> {
+        let (start, end) = (self.trim_start.trim(), self.trim_end.trim());
+        if self.input_size.is_none() || (start.is_empty() && end.is_empty()) {
+            return Ok(None);
+        }
+        let start = if start.is_empty() {
+            0.0
+        } else {
+            parse_timestamp(start)?
+        };
+        let end = if end.is_empty() {
+            f64::INFINITY
+        } else {
+            parse_timestamp(end)?
+        };
+        if end <= start {
+            return Err("The end must come after the start.".into());
+        }
+        let duration = self
+            .input_size
+            .filter(|size| size.fps > 0.0)
+            .map(|size| size.frames as f64 / size.fps);
+        if duration.is_some_and(|duration| start >= duration) {
+            return Err("The start is past the end of the video.".into());
+        }
+        Ok(Some(TimeRange::new(start, end)))
+    }
+
+    /// In and out point inputs, shown for video inputs only.
+    fn trim_fields(&self) -> Option<TrimFields<'_>> {
+        self.input_size?;
+        Some(TrimFields {
+            start: &self.trim_start,
+            end: &self.trim_end,
+            error: self.trim().err(),
+        })
+    }
+
+    /// The scrubber and blur settings under the sample frame, once there is
+    /// one.
+    fn preview_controls(&self) -> Option<PreviewControls> {
+        self.compare.as_ref()?;
+        let shown = self.shown_settings();
+        let size = self.input_size.as_ref();
+        Some(PreviewControls {
+            frame: self.sample_frame.unwrap_or(0),
+            frames: size.map_or(1, |size| size.frames),
+            fps: size.map_or(0.0, |size| size.fps),
+            shape: self.settings.blur_shape,
+            strength: self.settings.blur_strength,
+            coverage: shown.blur_coverage,
+            coverage_held: shown.blur_coverage != self.settings.blur_coverage,
+            timeline: self.timeline.clone(),
+        })
+    }
+
+    /// Settings as the settings tab shows them: with held changes, if any.
+    fn shown_settings(&self) -> Settings {
+        self.settings_with(&self.pending_changes)
+    }
+
+    /// While a scan holds a face selection, which settings would clear it
+    /// and which of those a held change touches.
+    fn selection_guard(&self) -> Option<SelectionGuard> {
+        self.detection_cache.as_ref()?;
+        Some(SelectionGuard {
+            key: scan_params(&self.settings),
+            pending: self.rescan_changes(&self.shown_settings()),
+        })
+    }
+
+    /// What the detection interval means for the chosen video, or for a
+    /// 30 fps one: how long positions are extrapolated between detections,
+    /// and how long a scan should take once scans have been measured.
+    fn detection_interval_effect(&self) -> String {
+        let interval = self.shown_settings().detection_interval;
+        let fps = self
+            .input_size
+            .map(|size| size.fps)
+            .filter(|fps| *fps > 0.0)
+            .unwrap_or(30.0);
+        let mut effect = if interval <= 1 {
+            "Faces are found on every frame.".to_string()
+        } else {
+            let gap_ms = (interval as f64 / fps * 1000.0).round();
+            format!("At {fps:.0} fps, positions are predicted for up to {gap_ms:.0} ms between detections.")
+        };
+        let scan = self
+            .input_size
+            .as_ref()
+            .and_then(|size| self.settings.performance.scan_seconds(size, interval));
+        if let Some(seconds) = scan {
+            effect.push_str(&format!(
+                " Scanning this video takes {}.",
+                performance::format_estimate(seconds)
+            ));
+        }
+        effect
+    }
+
+    /// Re-renders the review screen's sample frame with the current blur
+    /// settings and selection, including a held coverage change. The frame
+    /// is the one scrubbed to, or else the one with the most faces in the
+    /// scan; without faces there is nothing to compare.
+    fn refresh_compare(&mut self) {
+        if !matches!(self.processing, ProcessingState::Previewed) {
+            return;
+        }
+        let (Some(input), Some(cache)) = (&self.input_path, &self.detection_cache) else {
+            return;
+        };
+        let Some(frame_index) = self.sample_frame.or_else(|| representative_frame(cache)) else {
+            return;
+        };
+        self.sample_frame = Some(frame_index);
+        let blur_ids = self.faces_well.get_selected_ids();
+        let time_rules = self.faces_well.time_rules();
+        self.timeline = match self.input_size {
+            Some(size) => detection_timeline::coverage(
+                cache,
+                size.frames,
+                blur_ids.as_ref(),
+                &time_rules,
+                size.fps,
+                TIMELINE_BUCKETS,
+            ),
+            None => Vec::new(),
+        };
+        let params = CompareParams {
+            input_path: input.clone(),
+            frame_index,
+            detection_cache: cache.clone(),
+            blur_ids,
+            time_rules,
+            blur_shape: self.settings.blur_shape,
+            blur_strength: self.settings.blur_strength,
+            blur_feather: self.settings.blur_feather,
+            scan_coverage: self.settings.blur_coverage,
+            blur_coverage: self.shown_settings().blur_coverage,
+            lookahead: self.settings.lookahead,
+            gpu_context: self.gpu_context.clone(),
+        };
+        // Replacing the receiver drops any render still running for older
+        // settings.
+        self.compare_rx = Some(compare_worker::spawn(params));
+    }
+
+    /// Applies the region and track ID overlay settings to the sample
+    /// frame.
+    fn sync_compare_overlay(&mut self) {
+        let overlay = self
+            .settings
+            .show_regions
+            .then_some((self.settings.overlay_palette, self.settings.high_contrast));
+        if let Some(ref mut compare) = self.compare {
+            compare.set_overlay(overlay);
+            compare.set_show_detections(self.settings.show_detections);
+        }
+    }
+
+    fn clear_compare(&mut self) {
+        self.compare = None;
+        self.compare_rx = None;
+        self.sample_frame = None;
+        self.timeline.clear();
+    }
+
+    fn drain_compare_messages(&mut self) {
+        let Some(message) = self.compare_rx.as_ref().and_then(|rx| rx.try_recv().ok()) else {
+            return;
+        };
+        self.compare_rx = None;
+        match message {
+            CompareMessage::Ready(original, blurred, regions, detections) => {
+                match self.compare {
+                    Some(ref mut compare) => {
+                        compare.replace(original, blurred, regions, detections)
+                    }
+                    None => {
+                        self.compare =
+                            Some(CompareState::new(original, blurred, regions, detections))
+                    }
+                }
+                self.sync_compare_overlay();
+            }
+            CompareMessage::Error(e) => log::warn!("Sample frame render failed: {e}"),
+        }
+    }
+
+    fn drain_model_update_messages(&mut self) {
+        let messages: Vec<_> = self
+            .model_update_rx
+            .as_ref()
+            .map(|rx| rx.try_iter().collect())
+            .unwrap_or_default();
+        for message in messages {
+            match message {
+                ModelUpdateMessage::Checked(updates) => {
+                    self.model_update_rx = None;
+                    self.model_updates = ModelUpdateState::Available(updates);
+                }
+                ModelUpdateMessage::Progress(index, dl, total) => {
+                    if let ModelUpdateState::Installing(_, ref mut at, ref mut done, ref mut of) =
+                        self.model_updates
+                    {
+                        (*at, *done, *of) = (index, dl, total);
+                    }
+                }
+                ModelUpdateMessage::Installed => {
+                    self.model_update_rx = None;
+                    self.model_updates = ModelUpdateState::Installed;
+                }
+                ModelUpdateMessage::Error(e) => {
+                    self.model_update_rx = None;
+                    self.model_updates = ModelUpdateState::Failed(e);
+                }
+            }
+        }
+    }
+
+    /// Stops a job still running when another one starts in its place.
+    fn supersede_worker(&mut self) {
+        if let Some(cancel) = self.worker_cancel.take() {
+            cancel.cancel(CancelReason::Superseded);
+        }
     }
 
     fn clear_worker_state(&mut self, is_preview: bool) {
@@ -638,6 +2121,7 @@ impl App {
             self.preview_rx = None;
         } else {
             self.worker_rx = None;
+            self.keyword_review = None;
         }
         self.worker_cancel = None;
     }
@@ -645,6 +2129,7 @@ impl App {
     fn invalidate_detection(&mut self) {
         if self.detection_cache.is_some() {
             self.detection_cache = None;
+            self.clear_compare();
             self.faces_well.clear();
             if matches!(self.processing, ProcessingState::Previewed) {
                 self.processing = ProcessingState::Idle;
@@ -652,26 +2137,155 @@ impl App {
         }
     }
 
+    /// Whether a required checklist still has unticked items, which keeps
+    /// the finished export on screen.
+    fn checklist_holds(&self) -> bool {
+        self.checklist.as_ref().is_some_and(|c| !c.can_dismiss())
+    }
+
     fn reset(&mut self) {
+        self.release_pending_changes();
+        self.checklist = None;
         self.processing = ProcessingState::Idle;
+        self.capture_dir = None;
+        self.capture_error = None;
         self.input_path = None;
         self.output_path = None;
+        self.input_size = None;
+        self.trim_start.clear();
+        self.trim_end.clear();
         self.faces_well.clear();
+        self.faces_well.project = Project::default();
         self.detection_cache = None;
+        self.clear_compare();
+        self.job_warnings.clear();
+        self.metadata_info = None;
     }
 
-    fn restore_defaults(&mut self) {
-        let defaults = Settings::default();
-        let detection_changed = self.settings.confidence != defaults.confidence;
-        self.settings = Settings {
-            // Preserve nothing — full restore
-            ..defaults
-        };
+    /// Applies a detection settings change. After a scan, a change to the
+    /// detection cache key would discard the face selection, so it is held
+    /// in `pending_changes` until confirmed.
+    fn change_detection_settings(&mut self, message: Message) {
+        let mut changes = std::mem::take(&mut self.pending_changes);
+        changes.push(message);
+        let changed = self.settings_with(&changes);
+        if self.detection_cache.is_some() && !self.rescan_changes(&changed).is_empty() {
+            self.pending_changes = changes;
+            // The sample frame previews a held coverage change.
+            self.refresh_compare();
+        } else {
+            self.apply_settings(changed);
+        }
+    }
+
+    /// The current settings with `changes` applied, in order.
+    fn settings_with(&self, changes: &[Message]) -> Settings {
+        let mut settings = self.settings.clone();
+        for change in changes {
+            match *change {
+                Message::PresetChanged(preset) => settings.apply_preset(preset),
+                Message::ConfidenceChanged(val) => settings.confidence = val,
+                Message::BlurCoverageChanged(val) => settings.blur_coverage = val,
+                Message::CenterOffsetChanged(val) => settings.center_offset = val,
+                Message::DetectionIntervalChanged(val) => settings.detection_interval = val,
+                Message::RestoreDefaults => settings = restored_defaults(&settings),
+                _ => {}
+            }
+        }
+        settings
+    }
+
+    /// Detection cache key settings that differ between the current
+    /// settings and `settings`.
+    fn rescan_changes(&self, settings: &Settings) -> Vec<CacheKeyParam> {
+        scan_params(&self.settings).changed(&scan_params(settings))
+    }
+
+    fn apply_settings(&mut self, settings: Settings) {
+        let rescan = !self.rescan_changes(&settings).is_empty();
+        self.settings = settings;
         self.settings.save();
-        if detection_changed {
+        self.refresh_output_extension();
+        self.sync_compare_overlay();
+        if rescan {
             self.invalidate_detection();
+        } else {
+            self.refresh_compare();
+        }
+    }
+
+    /// A change held for confirmation no longer clears anything once the
+    /// scan is gone, so it is applied.
+    fn release_pending_changes(&mut self) {
+        if !self.pending_changes.is_empty() {
+            let changes = std::mem::take(&mut self.pending_changes);
+            self.settings = self.settings_with(&changes);
+            self.settings.save();
         }
     }
+
+    /// Records the monitor the window is on. At launch, a saved position
+    /// from a monitor that is no longer there (or whose size changed) is
+    /// replaced by a centred window that fits the current monitor.
+    fn check_monitor(&mut self, id: window::Id, size: Option<Size>) -> Task<Message> {
+        let first_check = self.monitor_size.is_none();
+        let Some(monitor) = size else {
+            // Not on any monitor: the saved position is off-screen.
+            return if first_check && self.settings.window.is_some() {
+                window::move_to(id, Point::ORIGIN)
+            } else {
+                Task::none()
+            };
+        };
+        self.monitor_size = Some(monitor);
+        let Some(saved) = self.settings.window.filter(|_| first_check) else {
+            return Task::none();
+        };
+        if saved.monitor == Some((monitor.width, monitor.height)) {
+            return Task::none();
+        }
+        let fitted = Size::new(
+            self.window_size
+                .width
+                .min(monitor.width)
+                .max(MIN_WINDOW_SIZE.width),
+            self.window_size
+                .height
+                .min(monitor.height)
+                .max(MIN_WINDOW_SIZE.height),
+        );
+        let centred = Point::new(
+            ((monitor.width - fitted.width) / 2.0).max(0.0),
+            ((monitor.height - fitted.height) / 2.0).max(0.0),
+        );
+        Task::batch([window::resize(id, fitted), window::move_to(id, centred)])
+    }
+
+    fn exit(&mut self) -> Task<Message> {
+        self.shutdown.cancel(CancelReason::Shutdown);
+        self.save_window_placement();
+        iced::exit()
+    }
+
+    fn finish_pending_exit(&mut self, pending: PendingExit) -> Task<Message> {
+        match pending {
+            // Bring the window back so a failed export isn't lost.
+            PendingExit::WhenDone(id) if matches!(self.processing, ProcessingState::Error(_)) => {
+                Task::batch([window::minimize(id, false), window::gain_focus(id)])
+            }
+            _ => self.exit(),
+        }
+    }
+
+    fn save_window_placement(&mut self) {
+        self.settings.window = Some(WindowPlacement {
+            width: self.window_size.width,
+            height: self.window_size.height,
+            position: self.window_position.map(|p| (p.x, p.y)),
+            monitor: self.monitor_size.map(|m| (m.width, m.height)),
+        });
+        self.settings.save();
+    }
 }
 
 fn tab_button<'a>(
@@ -751,7 +2365,9 @@ fn tab_button<'a>(
             .align_x(iced::Alignment::Center)
             .into()
     })
-    .animation(Easing::EASE_OUT.with_duration(Duration::from_millis(200)))
+    .animation(
+        Easing::EASE_OUT.with_duration(theme::animation_duration(Duration::from_millis(200))),
+    )
     .into();
 
     mouse_area(tab_content)
@@ -760,6 +2376,57 @@ fn tab_button<'a>(
         .into()
 }
 
+/// Default output for a screen recording: the Videos folder (or home),
+/// named by the time recording stopped.
+fn recording_output_path() -> PathBuf {
+    let dir = dirs::video_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(std::env::temp_dir);
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    dir.join(format!("Screen recording {stamp}_blurred.mp4"))
+}
+
+/// Reads a video's frame count and resolution from its header. Images are
+/// done in a moment and get no estimate.
+/// The detection cache key settings a scan with `settings` depends on.
+fn scan_params(settings: &Settings) -> CacheKeyParams {
+    detection_cache::params(
+        settings.confidence,
+        settings.blur_coverage,
+        settings.center_offset,
+        settings.detection_interval,
+    )
+}
+
+/// `settings` back at their defaults, keeping what isn't a preference.
+fn restored_defaults(settings: &Settings) -> Settings {
+    Settings {
+        // Onboarding choices are not preferences; keep them.
+        onboarded: settings.onboarded,
+        model_dir: settings.model_dir.clone(),
+        // Nor is window state.
+        window: settings.window,
+        last_tab: settings.last_tab,
+        // Measurements describe the machine, not a preference.
+        performance: settings.performance,
+        ..Settings::default()
+    }
+}
+
+fn probe_input_size(path: &std::path::Path) -> Option<InputSize> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    if IMAGE_EXTENSIONS.contains(&ext.as_str()) {
+        return None;
+    }
+    let mut reader = FfmpegReader::new();
+    let metadata = reader.open(path).ok()?;
+    reader.close();
+    Some(InputSize::from(&metadata))
+}
+
 fn has_supported_extension(path: &std::path::Path) -> bool {
     path.extension()
         .and_then(|e| e.to_str())
@@ -0,0 +1,118 @@
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+
+/// Questions asked when the organization's policy lists none.
+const DEFAULT_ITEMS: &[&str] = &[
+    "Watched the output from start to end?",
+    "Checked the audio for spoken names?",
+    "Verified no faces show in reflections, screens, or photos?",
+];
+
+/// An organization's post-export checklist, read from `checklist.json`
+/// beside the settings file so it can be deployed with the app:
+///
+/// ```json
+/// { "enabled": true, "required": true, "items": ["Watched the output?"] }
+/// ```
+///
+/// `enabled` turns the checklist on or off for everyone, overriding the
+/// user's setting; left out, the user decides. `required` keeps the
+/// checklist open until every item is ticked. `items` replaces the default
+/// questions.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ChecklistPolicy {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub items: Vec<String>,
+}
+
+impl ChecklistPolicy {
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|d| d.join("FaceGuard").join("checklist.json"))
+    }
+
+    /// The deployed policy, or the default when there is no file. A file
+    /// that can't be read turns the checklist on with the default
+    /// questions, so a broken policy doesn't quietly drop the step.
+    pub fn load() -> Self {
+        let Some(path) = Self::path().filter(|path| path.exists()) else {
+            return Self::default();
+        };
+        let policy = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()));
+        policy.unwrap_or_else(|e| {
+            log::warn!("Ignoring checklist policy {}: {e}", path.display());
+            Self {
+                enabled: Some(true),
+                ..Self::default()
+            }
+        })
+    }
+
+    /// Whether the checklist is shown after exports, given the user's
+    /// setting.
+    pub fn shows(&self, user_enabled: bool) -> bool {
+        self.enabled.unwrap_or(user_enabled)
+    }
+
+    /// Whether the organization decides if the checklist is shown.
+    pub fn is_enforced(&self) -> bool {
+        self.enabled.is_some()
+    }
+}
+
+/// The checklist shown over a finished export.
+pub struct Checklist {
+    items: Vec<String>,
+    checked: Vec<bool>,
+    required: bool,
+}
+
+impl Checklist {
+    pub fn new(policy: &ChecklistPolicy) -> Self {
+        let mut items: Vec<String> = policy
+            .items
+            .iter()
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect();
+        if items.is_empty() {
+            items = DEFAULT_ITEMS.iter().map(|item| item.to_string()).collect();
+        }
+        let checked = vec![false; items.len()];
+        Self {
+            items,
+            checked,
+            required: policy.required,
+        }
+    }
+
+    pub fn toggle(&mut self, index: usize) {
+        if let Some(checked) = self.checked.get_mut(index) {
+            *checked = !*checked;
+        }
+    }
+
+    /// Each question with whether it is ticked.
+    pub fn items(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.items
+            .iter()
+            .map(String::as_str)
+            .zip(self.checked.iter().copied())
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Whether the checklist may be closed: always, unless the policy
+    /// requires every item first.
+    pub fn can_dismiss(&self) -> bool {
+        !self.required || self.checked.iter().all(|&checked| checked)
+    }
+}
@@ -1,6 +1,10 @@
 mod app;
+mod checklist;
+mod performance;
 mod platform;
+mod project;
 mod settings;
+mod shortcuts;
 mod tabs;
 mod theme;
 mod widgets;
@@ -12,17 +16,32 @@ fn main() -> iced::Result {
     env_logger::init();
 
     iced::application(App::new, App::update, App::view)
-        .title("FaceGuard \u{2014} Neutrino Graphics")
+        .title(App::title)
         .theme(App::theme)
         .subscription(App::subscription)
-        .window(iced::window::Settings {
-            size: iced::Size::new(620.0, 540.0),
-            icon: load_icon(),
-            ..Default::default()
-        })
+        .window(window_settings())
+        .exit_on_close_request(false)
         .run()
 }
 
+/// Reopens the window where it was last closed. `App` moves it back on
+/// screen if that monitor is gone.
+fn window_settings() -> iced::window::Settings {
+    let saved = settings::Settings::load().window;
+    iced::window::Settings {
+        size: saved.map_or(app::DEFAULT_WINDOW_SIZE, |w| {
+            iced::Size::new(w.width, w.height)
+        }),
+        position: match saved.and_then(|w| w.position) {
+            Some((x, y)) => iced::window::Position::Specific(iced::Point::new(x, y)),
+            None => iced::window::Position::Centered,
+        },
+        min_size: Some(app::MIN_WINDOW_SIZE),
+        icon: load_icon(),
+        ..Default::default()
+    }
+}
+
 fn load_icon() -> Option<iced::window::Icon> {
     let icon_bytes = include_bytes!("../assets/icon.png");
     let img = image::load_from_memory(icon_bytes).ok()?.into_rgba8();
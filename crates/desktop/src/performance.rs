@@ -0,0 +1,164 @@
+use faceguard_core::shared::track_time_rules::TimeRange;
+use faceguard_core::shared::video_metadata::VideoMetadata;
+use serde::{Deserialize, Serialize};
+
+use crate::workers::detection_cache::DEFAULT_DETECTION_INTERVAL;
+
+/// Weight of the newest measurement; older jobs fade out as the machine's
+/// load, drivers, or power settings change.
+const SMOOTHING: f64 = 0.3;
+
+/// A part of a job whose throughput is measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Decoding and face detection, as in the preview scan.
+    Scan,
+    /// A blur job with known detections, blurring on the CPU.
+    BlurCpu,
+    /// A blur job with known detections, blurring on the GPU.
+    BlurGpu,
+    /// Time spent in the video encoder.
+    Encode,
+}
+
+/// This machine's measured throughput per stage, in megapixels per second
+/// (frames × width × height), so a measurement on one resolution carries
+/// over to inputs of another. Stored in the settings file and refined after
+/// every job.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceHistory {
+    #[serde(default)]
+    pub scan: Option<f64>,
+    #[serde(default)]
+    pub blur_cpu: Option<f64>,
+    #[serde(default)]
+    pub blur_gpu: Option<f64>,
+    #[serde(default)]
+    pub encode: Option<f64>,
+}
+
+/// Pixel volume of a video input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InputSize {
+    pub frames: usize,
+    pub width: u32,
+    pub height: u32,
+    pub fps: f64,
+}
+
+impl From<&VideoMetadata> for InputSize {
+    fn from(metadata: &VideoMetadata) -> Self {
+        Self {
+            frames: metadata.total_frames,
+            width: metadata.width,
+            height: metadata.height,
+            fps: metadata.fps,
+        }
+    }
+}
+
+impl InputSize {
+    pub fn megapixels(&self) -> f64 {
+        self.frames as f64 * self.width as f64 * self.height as f64 / 1_000_000.0
+    }
+
+    /// The part of the input inside `range`, as a trimmed export reads it.
+    pub fn trimmed(&self, range: TimeRange) -> Self {
+        if self.fps <= 0.0 {
+            return *self;
+        }
+        let frame_at =
+            |seconds: f64| ((seconds * self.fps).ceil().max(0.0) as usize).min(self.frames);
+        Self {
+            frames: frame_at(range.end).saturating_sub(frame_at(range.start)),
+            ..*self
+        }
+    }
+}
+
+/// Work of a scan at `detection_interval` relative to one at the default
+/// interval. Scans are recorded as if run at the default, so a change of
+/// interval does not skew the history. Detection is taken to be the whole
+/// cost of a scan, so estimates for sparse intervals err on the short side.
+pub fn detection_work(detection_interval: u32) -> f64 {
+    DEFAULT_DETECTION_INTERVAL as f64 / detection_interval.max(1) as f64
+}
+
+impl PerformanceHistory {
+    /// Folds a new measurement into the running average. Measurements that
+    /// are not positive and finite (an empty or instant job) are ignored.
+    pub fn record(&mut self, stage: Stage, megapixels_per_second: f64) {
+        if !megapixels_per_second.is_finite() || megapixels_per_second <= 0.0 {
+            return;
+        }
+        let slot = self.slot(stage);
+        *slot = Some(match *slot {
+            Some(old) => old + SMOOTHING * (megapixels_per_second - old),
+            None => megapixels_per_second,
+        });
+    }
+
+    pub fn rate(&self, stage: Stage) -> Option<f64> {
+        match stage {
+            Stage::Scan => self.scan,
+            Stage::BlurCpu => self.blur_cpu,
+            Stage::BlurGpu => self.blur_gpu,
+            Stage::Encode => self.encode,
+        }
+    }
+
+    fn slot(&mut self, stage: Stage) -> &mut Option<f64> {
+        match stage {
+            Stage::Scan => &mut self.scan,
+            Stage::BlurCpu => &mut self.blur_cpu,
+            Stage::BlurGpu => &mut self.blur_gpu,
+            Stage::Encode => &mut self.encode,
+        }
+    }
+
+    /// Seconds a preview scan of `input` should take, detecting every
+    /// `detection_interval` frames, once a scan has been measured.
+    pub fn scan_seconds(&self, input: &InputSize, detection_interval: u32) -> Option<f64> {
+        Some(input.megapixels() / self.scan? * detection_work(detection_interval))
+    }
+
+    /// Seconds a blur job on `input` should take. Without detections from a
+    /// preview the job detects faces as it goes, which costs about a scan.
+    pub fn blur_seconds(
+        &self,
+        input: &InputSize,
+        gpu: bool,
+        detections_known: bool,
+        detection_interval: u32,
+    ) -> Option<f64> {
+        let blur = self.rate(if gpu { Stage::BlurGpu } else { Stage::BlurCpu })?;
+        // Encoding runs alongside blurring, so the slower of the two bounds
+        // the job.
+        let rate = self.encode.map_or(blur, |encode| blur.min(encode));
+        let mut seconds = input.megapixels() / rate;
+        if !detections_known {
+            seconds += self.scan_seconds(input, detection_interval)?;
+        }
+        Some(seconds)
+    }
+}
+
+/// Rounded, human wording for a duration estimate: "under a minute",
+/// "about 14 minutes", "about 2 hours".
+pub fn format_estimate(seconds: f64) -> String {
+    let minutes = (seconds / 60.0).round() as u64;
+    if seconds < 60.0 {
+        "under a minute".into()
+    } else if minutes <= 1 {
+        "about a minute".into()
+    } else if minutes < 90 {
+        format!("about {minutes} minutes")
+    } else {
+        let hours = (seconds / 1800.0).round() / 2.0;
+        if hours.fract() == 0.0 {
+            format!("about {hours:.0} hours")
+        } else {
+            format!("about {hours:.1} hours")
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use std::io::Cursor;
+use std::path::Path;
+
+use iced::window::Window;
+
+const DRAG_IMAGE_SIZE: u32 = 64;
+
+pub fn start_file_drag(window: &dyn Window, path: &Path) -> Result<(), String> {
+    drag::start_drag(
+        &window,
+        drag::DragItem::Files(vec![path.to_path_buf()]),
+        drag::Image::Raw(drag_image()),
+        |_result, _cursor| {},
+        drag::Options::default(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// The app icon, downscaled so the drag preview is thumbnail-sized.
+fn drag_image() -> Vec<u8> {
+    let icon_bytes = include_bytes!("../../assets/icon.png");
+    let Ok(img) = image::load_from_memory(icon_bytes) else {
+        return icon_bytes.to_vec();
+    };
+    let mut out = Cursor::new(Vec::new());
+    match img
+        .thumbnail(DRAG_IMAGE_SIZE, DRAG_IMAGE_SIZE)
+        .write_to(&mut out, image::ImageFormat::Png)
+    {
+        Ok(()) => out.into_inner(),
+        Err(_) => icon_bytes.to_vec(),
+    }
+}
@@ -0,0 +1,41 @@
+use super::Outcome;
+
+pub fn prefers_reduced_motion() -> bool {
+    // GNOME and most GTK desktops expose this; absent keys mean animations on.
+    std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "enable-animations"])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim() == "false")
+        .unwrap_or(false)
+}
+
+pub fn is_power_saving() -> bool {
+    // A machine is on battery when it has a mains supply that reports offline.
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let dir = entry.path();
+        let kind = std::fs::read_to_string(dir.join("type")).unwrap_or_default();
+        let online = std::fs::read_to_string(dir.join("online")).unwrap_or_default();
+        kind.trim() == "Mains" && online.trim() == "0"
+    })
+}
+
+pub fn play_chime(outcome: Outcome) {
+    // Sound theme names. libcanberra honors the desktop's event sounds
+    // switch; paplay covers desktops without it.
+    let id = match outcome {
+        Outcome::Finished => "complete",
+        Outcome::Failed => "dialog-error",
+    };
+    let played = std::process::Command::new("canberra-gtk-play")
+        .args(["--id", id])
+        .status()
+        .is_ok_and(|status| status.success());
+    if !played {
+        let _ = std::process::Command::new("paplay")
+            .arg(format!("/usr/share/sounds/freedesktop/stereo/{id}.oga"))
+            .status();
+    }
+}
@@ -1,3 +1,5 @@
+use super::Outcome;
+
 pub fn is_dark_mode() -> bool {
     std::process::Command::new("defaults")
         .args(["read", "-g", "AppleInterfaceStyle"])
@@ -9,3 +11,40 @@ pub fn is_dark_mode() -> bool {
         })
         .unwrap_or(true)
 }
+
+pub fn prefers_reduced_motion() -> bool {
+    std::process::Command::new("defaults")
+        .args(["read", "com.apple.universalaccess", "reduceMotion"])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim() == "1")
+        .unwrap_or(false)
+}
+
+pub fn is_power_saving() -> bool {
+    // `pmset -g batt` reports the active source; `pmset -g` lists Low Power Mode.
+    let on_battery = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).contains("'Battery Power'"))
+        .unwrap_or(false);
+    let low_power = std::process::Command::new("pmset")
+        .arg("-g")
+        .output()
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .any(|l| l.split_whitespace().eq(["lowpowermode", "1"]))
+        })
+        .unwrap_or(false);
+    on_battery || low_power
+}
+
+pub fn play_chime(outcome: Outcome) {
+    let sound = match outcome {
+        Outcome::Finished => "Glass",
+        Outcome::Failed => "Basso",
+    };
+    let _ = std::process::Command::new("afplay")
+        .arg(format!("/System/Library/Sounds/{sound}.aiff"))
+        .status();
+}
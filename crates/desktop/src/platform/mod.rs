@@ -1,8 +1,24 @@
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+mod drag_out;
+#[cfg(target_os = "linux")]
+mod linux;
 #[cfg(target_os = "macos")]
 mod macos;
 #[cfg(target_os = "windows")]
 mod windows;
 
+use std::path::Path;
+
+use iced::window::{self, UserAttention};
+use iced::Task;
+
+/// How a long job ended, for [`play_chime`] and [`request_attention`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Finished,
+    Failed,
+}
+
 /// Detect whether the operating system is in dark mode.
 pub fn is_dark_mode() -> bool {
     #[cfg(target_os = "macos")]
@@ -18,3 +34,101 @@ pub fn is_dark_mode() -> bool {
         true
     }
 }
+
+/// Detect whether the user has asked the OS to minimize UI motion.
+pub fn prefers_reduced_motion() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        macos::prefers_reduced_motion()
+    }
+    #[cfg(target_os = "windows")]
+    {
+        windows::prefers_reduced_motion()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::prefers_reduced_motion()
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        false
+    }
+}
+
+/// Detect whether the machine is running on battery or in a low-power mode.
+pub fn is_power_saving() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        macos::is_power_saving()
+    }
+    #[cfg(target_os = "windows")]
+    {
+        windows::is_power_saving()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::is_power_saving()
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        false
+    }
+}
+
+/// Play the system's completion or error sound without blocking. The sound
+/// goes through the system output, so a muted machine stays silent.
+pub fn play_chime(outcome: Outcome) {
+    std::thread::spawn(move || {
+        #[cfg(target_os = "macos")]
+        macos::play_chime(outcome);
+        #[cfg(target_os = "windows")]
+        windows::play_chime(outcome);
+        #[cfg(target_os = "linux")]
+        linux::play_chime(outcome);
+        #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+        let _ = outcome;
+    });
+}
+
+/// Flash the taskbar button or bounce the dock icon of window `id` until it
+/// is focused. A failure asks more insistently where the platform tells
+/// the two apart.
+pub fn request_attention<T>(id: window::Id, outcome: Outcome) -> Task<T> {
+    let level = match outcome {
+        Outcome::Finished => UserAttention::Informational,
+        Outcome::Failed => UserAttention::Critical,
+    };
+    window::request_user_attention(id, Some(level))
+}
+
+/// Whether the platform can start a drag session carrying a file out of the app.
+pub fn supports_file_drag() -> bool {
+    cfg!(any(target_os = "macos", target_os = "windows"))
+}
+
+/// Placeholder for the window field of the screen recorder, or `None` when
+/// the platform can only record the whole screen.
+pub fn window_capture_hint() -> Option<&'static str> {
+    if cfg!(target_os = "windows") {
+        Some("Window title, e.g. Untitled - Notepad")
+    } else if cfg!(target_os = "linux") {
+        Some("X11 window ID (run xwininfo and click the window)")
+    } else {
+        None
+    }
+}
+
+/// Start an OS drag session carrying `path`, so it can be dropped into a file
+/// manager, mail client, or chat app. Must be called while the mouse button
+/// that began the drag is still held.
+pub fn start_file_drag(window: &dyn iced::window::Window, path: &Path) -> Result<(), String> {
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    {
+        drag_out::start_file_drag(window, path)
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = (window, path);
+        Err("Dragging files out of the app is not supported on this platform".to_string())
+    }
+}
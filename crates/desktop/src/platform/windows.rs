@@ -1,3 +1,5 @@
+use super::Outcome;
+
 pub fn is_dark_mode() -> bool {
     // HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize
     // AppsUseLightTheme: DWORD 0 = dark, 1 = light
@@ -16,3 +18,47 @@ pub fn is_dark_mode() -> bool {
         })
         .unwrap_or(true)
 }
+
+pub fn prefers_reduced_motion() -> bool {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION,
+    };
+
+    // "Show animations in Windows", read without spawning anything.
+    let mut animations: i32 = 1;
+    let ok = unsafe {
+        SystemParametersInfoW(
+            SPI_GETCLIENTAREAANIMATION,
+            0,
+            (&mut animations as *mut i32).cast(),
+            0,
+        )
+    };
+    ok != 0 && animations == 0
+}
+
+pub fn is_power_saving() -> bool {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    // ACLineStatus 0 = on battery (1 = plugged in, 255 = unknown, as on
+    // desktops without a battery). SystemStatusFlag 1 = battery saver on.
+    // A plain system call, so it can be polled without spawning anything.
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return false;
+    }
+    status.ACLineStatus == 0 || status.SystemStatusFlag == 1
+}
+
+pub fn play_chime(outcome: Outcome) {
+    use windows_sys::Win32::System::Diagnostics::Debug::MessageBeep;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{MB_ICONASTERISK, MB_ICONHAND};
+
+    // Sounds of the user's sound scheme, so "No Sounds" silences them.
+    // Played asynchronously, with no console window to flash.
+    let sound = match outcome {
+        Outcome::Finished => MB_ICONASTERISK,
+        Outcome::Failed => MB_ICONHAND,
+    };
+    unsafe { MessageBeep(sound) };
+}
@@ -0,0 +1,312 @@
+use faceguard_core::detection::infrastructure::cache_key_params::CacheKeyParams;
+use faceguard_core::pipeline::anonymization_ledger::AnonymizationLedger;
+use faceguard_core::pipeline::face_selection::{FaceSelection, SelectedTrack};
+use faceguard_core::shared::region::Region;
+use faceguard_core::shared::schema::{Schema, SchemaError};
+use faceguard_core::shared::track_time_rules::{TimeRange, TrackTimeRules};
+use faceguard_core::shared::warnings::Warning;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Project file layout history. Files from before the layout was
+/// versioned have no `version` and read as version 0.
+const PROJECT_SCHEMA: Schema = Schema::new("Project", 1, &[v0_to_v1]).with_unversioned(0);
+
+/// Version 1 only added the `version` field, which `Schema::upgrade` stamps.
+fn v0_to_v1(_: &mut serde_json::Map<String, serde_json::Value>) -> Result<(), String> {
+    Ok(())
+}
+
+/// Per-input project state, stored next to the source as
+/// `<input>.faceguard.json` so track labels and notes are still there when
+/// the same file is opened again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    version: u32,
+    #[serde(default)]
+    pub tracks: BTreeMap<u32, TrackNote>,
+    /// Set when the file was written by a newer FaceGuard. It is read as
+    /// far as this build understands it but never overwritten, so fields
+    /// this build doesn't know survive.
+    #[serde(skip)]
+    read_only: bool,
+}
+
+impl Default for Project {
+    fn default() -> Self {
+        Self {
+            version: PROJECT_SCHEMA.version(),
+            tracks: BTreeMap::new(),
+            read_only: false,
+        }
+    }
+}
+
+/// User-assigned description of a face track.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrackNote {
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub note: String,
+    /// Comma-separated `START-END` ranges; when set, the track is only
+    /// blurred inside them.
+    #[serde(default)]
+    pub blur_ranges: String,
+}
+
+impl TrackNote {
+    fn is_empty(&self) -> bool {
+        self.label.trim().is_empty()
+            && self.note.trim().is_empty()
+            && self.blur_ranges.trim().is_empty()
+    }
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    source: &'a Path,
+    output: &'a Path,
+    tracks: Vec<ReportTrack<'a>>,
+    warnings: Vec<ReportWarning<'a>>,
+}
+
+#[derive(Serialize)]
+struct ReportTrack<'a> {
+    track_id: u32,
+    label: &'a str,
+    note: &'a str,
+    blur_ranges: &'a str,
+    blurred: bool,
+}
+
+#[derive(Serialize)]
+struct ReportWarning<'a> {
+    kind: &'static str,
+    message: &'a str,
+}
+
+impl Project {
+    fn path_for(input: &Path) -> PathBuf {
+        let name = input.file_name().unwrap_or_default().to_string_lossy();
+        input.with_file_name(format!("{name}.faceguard.json"))
+    }
+
+    pub fn load(input: &Path) -> Self {
+        let path = Self::path_for(input);
+        let Some(document) = fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+        else {
+            return Self::default();
+        };
+        let (document, read_only) = match PROJECT_SCHEMA.upgrade(document.clone()) {
+            Ok(upgraded) => (upgraded, false),
+            Err(e @ SchemaError::Newer { .. }) => {
+                log::warn!("{e}; {} is opened without saving", path.display());
+                (document, true)
+            }
+            Err(e) => {
+                log::warn!("Ignoring project {}: {e}", path.display());
+                return Self::default();
+            }
+        };
+        serde_json::from_value(document)
+            .map(|project| Self {
+                read_only,
+                ..project
+            })
+            .unwrap_or_default()
+    }
+
+    /// Writes the project file, or removes it once every note is empty so
+    /// unlabelled inputs don't accumulate sidecars.
+    pub fn save(&self, input: &Path) {
+        let path = Self::path_for(input);
+        if self.read_only {
+            return;
+        }
+        if self.tracks.is_empty() {
+            let _ = fs::remove_file(path);
+        } else if let Ok(json) = serde_json::to_string_pretty(self) {
+            if let Err(e) = fs::write(&path, json) {
+                log::warn!("Failed to save project {}: {e}", path.display());
+            }
+        }
+    }
+
+    pub fn track(&self, track_id: u32) -> Option<&TrackNote> {
+        self.tracks.get(&track_id)
+    }
+
+    pub fn set_label(&mut self, track_id: u32, label: String) {
+        self.update_track(track_id, |t| t.label = label);
+    }
+
+    pub fn set_note(&mut self, track_id: u32, note: String) {
+        self.update_track(track_id, |t| t.note = note);
+    }
+
+    pub fn set_blur_ranges(&mut self, track_id: u32, ranges: String) {
+        self.update_track(track_id, |t| t.blur_ranges = ranges);
+    }
+
+    /// Builds pipeline time rules from each track's ranges. A range set on
+    /// a group's representative covers the whole group. Unparseable ranges
+    /// are skipped, which leaves that track blurred throughout.
+    pub fn time_rules(&self, groups: &[Vec<u32>]) -> TrackTimeRules {
+        let mut rules = TrackTimeRules::new();
+        for (&track_id, track) in &self.tracks {
+            let Ok(ranges) = TimeRange::parse_list(&track.blur_ranges) else {
+                continue;
+            };
+            let members = groups
+                .iter()
+                .find(|g| g.first() == Some(&track_id))
+                .map(|g| g.as_slice())
+                .unwrap_or(std::slice::from_ref(&track_id));
+            for &member in members {
+                for &range in &ranges {
+                    rules.blur_during(member, range);
+                }
+            }
+        }
+        rules
+    }
+
+    /// The face selection for the CLI's `--selection`: each of `track_ids`
+    /// with its label, whether it is in `blurred`, and its blur ranges. A
+    /// group's representative adds its ranges to every member, as in
+    /// [`time_rules`](Self::time_rules), and lends its label to members
+    /// without one.
+    pub fn selection(
+        &self,
+        input: &Path,
+        scan: CacheKeyParams,
+        track_ids: &[u32],
+        blurred: &HashSet<u32>,
+        groups: &[Vec<u32>],
+    ) -> FaceSelection {
+        // Unparseable ranges are skipped, which blurs throughout.
+        let ranges =
+            |note: &TrackNote| TimeRange::parse_list(&note.blur_ranges).unwrap_or_default();
+        let tracks = track_ids
+            .iter()
+            .map(|&id| {
+                let own = self.track(id);
+                let representative = groups
+                    .iter()
+                    .find(|g| g.contains(&id))
+                    .and_then(|g| g.first())
+                    .filter(|&&first| first != id)
+                    .and_then(|&first| self.track(first));
+                let label = own
+                    .map(|note| note.label.trim())
+                    .filter(|label| !label.is_empty())
+                    .or_else(|| representative.map(|note| note.label.trim()))
+                    .unwrap_or_default();
+                SelectedTrack {
+                    track_id: id,
+                    label: label.to_string(),
+                    blurred: blurred.contains(&id),
+                    blur_ranges: own
+                        .into_iter()
+                        .chain(representative)
+                        .flat_map(ranges)
+                        .collect(),
+                }
+            })
+            .collect();
+        FaceSelection {
+            source: input
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+            scan,
+            tracks,
+        }
+    }
+
+    fn update_track(&mut self, track_id: u32, edit: impl FnOnce(&mut TrackNote)) {
+        let track = self.tracks.entry(track_id).or_default();
+        edit(track);
+        if track.is_empty() {
+            self.tracks.remove(&track_id);
+        }
+    }
+
+    /// Writes `<output>.report.json` listing every detected track with its
+    /// label, note, and whether it was blurred, plus any warnings the job
+    /// raised.
+    pub fn write_report(
+        &self,
+        input: &Path,
+        output: &Path,
+        track_ids: &[u32],
+        blurred: &HashSet<u32>,
+        warnings: &[Warning],
+    ) -> std::io::Result<PathBuf> {
+        let empty = TrackNote::default();
+        let tracks = track_ids
+            .iter()
+            .map(|&id| {
+                let note = self.track(id).unwrap_or(&empty);
+                ReportTrack {
+                    track_id: id,
+                    label: &note.label,
+                    note: &note.note,
+                    blur_ranges: &note.blur_ranges,
+                    blurred: blurred.contains(&id),
+                }
+            })
+            .collect();
+        let report = Report {
+            source: input,
+            output,
+            tracks,
+            warnings: warnings
+                .iter()
+                .map(|w| ReportWarning {
+                    kind: w.kind.code(),
+                    message: &w.message,
+                })
+                .collect(),
+        };
+
+        let name = output.file_name().unwrap_or_default().to_string_lossy();
+        let path = output.with_file_name(format!("{name}.report.json"));
+        let json = serde_json::to_string_pretty(&report).map_err(std::io::Error::from)?;
+        fs::write(&path, json)?;
+        Ok(path)
+    }
+
+    /// Writes `<output>.ledger.json`: `ledger` with every track in
+    /// `detections`, its label, whether it was blurred, and when it
+    /// appears. Signed when `passphrase` is not empty.
+    pub fn write_ledger(
+        &self,
+        output: &Path,
+        ledger: AnonymizationLedger,
+        detections: &HashMap<usize, Vec<Region>>,
+        fps: f64,
+        blurred: &HashSet<u32>,
+        passphrase: &str,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let labels: HashMap<u32, String> = self
+            .tracks
+            .iter()
+            .filter(|(_, note)| !note.label.is_empty())
+            .map(|(&id, note)| (id, note.label.clone()))
+            .collect();
+        let ledger = ledger.with_tracks(detections, fps, &labels, blurred);
+        let json = ledger.to_json((!passphrase.is_empty()).then_some(passphrase))?;
+
+        let name = output.file_name().unwrap_or_default().to_string_lossy();
+        let path = output.with_file_name(format!("{name}.ledger.json"));
+        fs::write(&path, json)?;
+        Ok(path)
+    }
+}
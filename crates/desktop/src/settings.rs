@@ -1,3 +1,13 @@
+use crate::app::Tab;
+use crate::performance::PerformanceHistory;
+use crate::workers::detection_cache::DEFAULT_DETECTION_INTERVAL;
+use faceguard_core::audio::domain::keyword_matcher::{KeywordMatcher, PhoneticStrictness};
+use faceguard_core::audio::domain::speech_language::SpeechLanguage;
+use faceguard_core::audio::domain::spoken_entity::PiiCategory;
+use faceguard_core::detection::domain::detector_backend::DetectorBackend;
+use faceguard_core::detection::domain::track_duration_filter::DEFAULT_MIN_TRACK_FRAMES;
+use faceguard_core::detection::infrastructure::model_resolver;
+use faceguard_core::pipeline::blur_preset::BlurPreset;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -44,6 +54,30 @@ impl std::fmt::Display for Appearance {
     }
 }
 
+/// Colors for outlining blur regions on the sample frame; see
+/// `theme::overlay_stroke`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlayPalette {
+    Standard,
+    /// Okabe-Ito colors, distinguishable with the common color vision
+    /// deficiencies.
+    ColorBlind,
+}
+
+impl OverlayPalette {
+    pub const ALL: &[OverlayPalette] = &[OverlayPalette::Standard, OverlayPalette::ColorBlind];
+}
+
+impl std::fmt::Display for OverlayPalette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OverlayPalette::Standard => write!(f, "Standard"),
+            OverlayPalette::ColorBlind => write!(f, "Color-blind safe"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum VoiceDisguise {
@@ -68,51 +102,422 @@ impl std::fmt::Display for VoiceDisguise {
 #[serde(rename_all = "lowercase")]
 pub enum BleepSound {
     Tone,
+    Noise,
     Silence,
 }
 
 impl BleepSound {
-    pub const ALL: &[BleepSound] = &[BleepSound::Tone, BleepSound::Silence];
+    pub const ALL: &[BleepSound] = &[BleepSound::Tone, BleepSound::Noise, BleepSound::Silence];
 }
 
 impl std::fmt::Display for BleepSound {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             BleepSound::Tone => write!(f, "Tone"),
+            BleepSound::Noise => write!(f, "Noise"),
             BleepSound::Silence => write!(f, "Silence"),
         }
     }
 }
 
+/// How a transcribed word has to agree with a keyword to be bleeped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeywordMatching {
+    Exact,
+    Strict,
+    Normal,
+    Loose,
+}
+
+impl KeywordMatching {
+    pub const ALL: &[KeywordMatching] = &[
+        KeywordMatching::Exact,
+        KeywordMatching::Strict,
+        KeywordMatching::Normal,
+        KeywordMatching::Loose,
+    ];
+
+    pub fn matcher(self) -> KeywordMatcher {
+        match self {
+            KeywordMatching::Exact => KeywordMatcher::Exact,
+            KeywordMatching::Strict => KeywordMatcher::Phonetic(PhoneticStrictness::Strict),
+            KeywordMatching::Normal => KeywordMatcher::Phonetic(PhoneticStrictness::Normal),
+            KeywordMatching::Loose => KeywordMatcher::Phonetic(PhoneticStrictness::Loose),
+        }
+    }
+}
+
+impl std::fmt::Display for KeywordMatching {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeywordMatching::Exact => write!(f, "Exact"),
+            KeywordMatching::Strict => write!(f, "Close spellings"),
+            KeywordMatching::Normal => write!(f, "Sounds alike"),
+            KeywordMatching::Loose => write!(f, "Loose"),
+        }
+    }
+}
+
+/// What a blur job does when audio processing fails after the video is done.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioFallback {
+    Fail,
+    Original,
+    Silent,
+}
+
+impl AudioFallback {
+    pub const ALL: &[AudioFallback] = &[
+        AudioFallback::Fail,
+        AudioFallback::Original,
+        AudioFallback::Silent,
+    ];
+}
+
+impl std::fmt::Display for AudioFallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioFallback::Fail => write!(f, "Fail"),
+            AudioFallback::Original => write!(f, "Original audio"),
+            AudioFallback::Silent => write!(f, "Silent"),
+        }
+    }
+}
+
+/// What runs the detection model; each maps to a [`DetectorBackend`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DetectorHardware {
+    #[default]
+    Auto,
+    Cpu,
+    Cuda,
+    CoreMl,
+    DirectMl,
+}
+
+impl DetectorHardware {
+    /// The choices that can work on this platform.
+    #[cfg(target_os = "macos")]
+    pub const ALL: &[DetectorHardware] = &[
+        DetectorHardware::Auto,
+        DetectorHardware::Cpu,
+        DetectorHardware::CoreMl,
+    ];
+    #[cfg(target_os = "windows")]
+    pub const ALL: &[DetectorHardware] = &[
+        DetectorHardware::Auto,
+        DetectorHardware::Cpu,
+        DetectorHardware::Cuda,
+        DetectorHardware::DirectMl,
+    ];
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    pub const ALL: &[DetectorHardware] = &[
+        DetectorHardware::Auto,
+        DetectorHardware::Cpu,
+        DetectorHardware::Cuda,
+    ];
+
+    pub fn backend(self) -> DetectorBackend {
+        match self {
+            DetectorHardware::Auto => DetectorBackend::Auto,
+            DetectorHardware::Cpu => DetectorBackend::Cpu,
+            DetectorHardware::Cuda => DetectorBackend::Cuda,
+            DetectorHardware::CoreMl => DetectorBackend::CoreMl,
+            DetectorHardware::DirectMl => DetectorBackend::DirectMl,
+        }
+    }
+}
+
+impl std::fmt::Display for DetectorHardware {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DetectorHardware::Auto => write!(f, "Automatic"),
+            DetectorHardware::Cpu => write!(f, "CPU"),
+            DetectorHardware::Cuda => write!(f, "NVIDIA CUDA"),
+            DetectorHardware::CoreMl => write!(f, "CoreML"),
+            DetectorHardware::DirectMl => write!(f, "DirectML"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Original,
+    Jpeg,
+    Png,
+    Webp,
+}
+
+impl ImageFormat {
+    pub const ALL: &[ImageFormat] = &[
+        ImageFormat::Original,
+        ImageFormat::Jpeg,
+        ImageFormat::Png,
+        ImageFormat::Webp,
+    ];
+
+    /// File extension to force on image outputs, or `None` to keep the input's.
+    pub fn extension(self) -> Option<&'static str> {
+        match self {
+            ImageFormat::Original => None,
+            ImageFormat::Jpeg => Some("jpg"),
+            ImageFormat::Png => Some("png"),
+            ImageFormat::Webp => Some("webp"),
+        }
+    }
+}
+
+impl std::fmt::Display for ImageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageFormat::Original => write!(f, "Original"),
+            ImageFormat::Jpeg => write!(f, "JPEG"),
+            ImageFormat::Png => write!(f, "PNG"),
+            ImageFormat::Webp => write!(f, "WebP"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VideoCodec {
+    H264,
+    Hevc,
+    Vp9,
+    Av1,
+}
+
+impl VideoCodec {
+    pub const ALL: &[VideoCodec] = &[
+        VideoCodec::H264,
+        VideoCodec::Hevc,
+        VideoCodec::Vp9,
+        VideoCodec::Av1,
+    ];
+
+    pub fn core(self) -> faceguard_core::video::domain::video_codec::VideoCodec {
+        use faceguard_core::video::domain::video_codec::VideoCodec as Core;
+        match self {
+            VideoCodec::H264 => Core::H264,
+            VideoCodec::Hevc => Core::Hevc,
+            VideoCodec::Vp9 => Core::Vp9,
+            VideoCodec::Av1 => Core::Av1,
+        }
+    }
+
+    /// Extension for a video output of an input with `input_ext`: the
+    /// input's own when its container can carry the codec, otherwise mp4,
+    /// which carries all of them.
+    pub fn output_extension(self, input_ext: &str) -> String {
+        if self
+            .core()
+            .extensions()
+            .contains(&input_ext.to_lowercase().as_str())
+        {
+            input_ext.to_string()
+        } else {
+            "mp4".to_string()
+        }
+    }
+}
+
+impl std::fmt::Display for VideoCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VideoCodec::H264 => write!(f, "H.264"),
+            VideoCodec::Hevc => write!(f, "HEVC"),
+            VideoCodec::Vp9 => write!(f, "VP9"),
+            VideoCodec::Av1 => write!(f, "AV1"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PngCompression {
+    Fast,
+    Default,
+    Best,
+}
+
+impl PngCompression {
+    pub const ALL: &[PngCompression] = &[
+        PngCompression::Fast,
+        PngCompression::Default,
+        PngCompression::Best,
+    ];
+}
+
+impl std::fmt::Display for PngCompression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PngCompression::Fast => write!(f, "Fast"),
+            PngCompression::Default => write!(f, "Balanced"),
+            PngCompression::Best => write!(f, "Smallest"),
+        }
+    }
+}
+
+/// What the screen recorder captures.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureSource {
+    #[default]
+    Screen,
+    Window,
+}
+
+impl CaptureSource {
+    pub const ALL: &[CaptureSource] = &[CaptureSource::Screen, CaptureSource::Window];
+}
+
+impl std::fmt::Display for CaptureSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureSource::Screen => write!(f, "Entire screen"),
+            CaptureSource::Window => write!(f, "One window"),
+        }
+    }
+}
+
+/// Main window state from the last session, in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowPlacement {
+    pub width: f32,
+    pub height: f32,
+    /// Top-left corner; `None` if the window was never moved (centred).
+    pub position: Option<(f32, f32)>,
+    /// Size of the monitor the window was on. A different size at launch
+    /// means that monitor is gone (or changed), so the position is not
+    /// trusted.
+    pub monitor: Option<(f32, f32)>,
+}
+
+/// Top of the intensity slider.
+pub const MAX_BLUR_STRENGTH: u32 = 401;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     pub blur_shape: BlurShape,
     pub confidence: u32,
     pub blur_strength: u32,
+    /// Percent of an ellipse's radius its blur fades out over; 0 is a hard
+    /// edge.
+    #[serde(default)]
+    pub blur_feather: u32,
     #[serde(default = "default_blur_coverage")]
     pub blur_coverage: u32,
     #[serde(default)]
     pub center_offset: i32,
     pub lookahead: u32,
+    #[serde(default = "default_detection_interval")]
+    pub detection_interval: u32,
     #[serde(default = "default_quality")]
     pub quality: u32,
+    #[serde(default = "default_video_codec")]
+    pub video_codec: VideoCodec,
+    #[serde(default = "default_image_format")]
+    pub image_format: ImageFormat,
+    #[serde(default = "default_image_quality")]
+    pub image_quality: u32,
+    #[serde(default = "default_png_compression")]
+    pub png_compression: PngCompression,
     pub appearance: Appearance,
     pub high_contrast: bool,
     pub font_scale: f32,
+    /// Outline blur regions on the sample frame (debug aid).
+    #[serde(default)]
+    pub show_regions: bool,
+    /// Label the faces on the sample frame with their track IDs.
+    #[serde(default)]
+    pub show_detections: bool,
+    #[serde(default = "default_overlay_palette")]
+    pub overlay_palette: OverlayPalette,
     #[serde(default)]
     pub audio_processing: bool,
     #[serde(default)]
     pub bleep_keywords: String,
+    /// Also bleep words that sound like a keyword.
+    #[serde(default = "default_keyword_matching")]
+    pub keyword_matching: KeywordMatching,
+    /// Also bleep spoken names, numbers, and places found in the transcript.
+    #[serde(default)]
+    pub bleep_names: bool,
+    #[serde(default)]
+    pub bleep_numbers: bool,
+    #[serde(default)]
+    pub bleep_locations: bool,
+    /// Language code of the speech, or `auto` to detect it.
+    #[serde(default = "default_speech_language")]
+    pub speech_language: String,
     #[serde(default = "default_bleep_sound")]
     pub bleep_sound: BleepSound,
     #[serde(default = "default_voice_disguise")]
     pub voice_disguise: VoiceDisguise,
+    #[serde(default = "default_audio_fallback")]
+    pub audio_fallback: AudioFallback,
+    /// Ask the post-export checklist after each export, unless the
+    /// organization's checklist policy decides.
+    #[serde(default)]
+    pub post_export_checklist: bool,
+    /// Play a chime when a scan or export finishes or fails.
+    #[serde(default)]
+    pub finish_sound: bool,
+    /// Flash the window's taskbar button or dock icon when a job finishes
+    /// while the app is in the background.
+    #[serde(default = "default_finish_attention")]
+    pub finish_attention: bool,
+    /// Write an anonymization ledger next to each video export.
+    #[serde(default)]
+    pub write_ledger: bool,
+    /// Operator name recorded in the ledger.
+    #[serde(default)]
+    pub ledger_operator: String,
+    /// Settings files written before onboarding existed count as onboarded.
+    #[serde(default = "default_onboarded")]
+    pub onboarded: bool,
+    /// Model folder chosen during onboarding; `None` uses the platform default.
+    #[serde(default)]
+    pub model_dir: Option<PathBuf>,
+    /// Write face thumbnails to the temp folder blurred; show them sharp
+    /// from memory only.
+    #[serde(default)]
+    pub anonymize_thumbnails: bool,
+    /// Keep detection results on disk so re-exporting a file skips detection.
+    #[serde(default = "default_cache_detections")]
+    pub cache_detections: bool,
+    /// Ignore faces seen on only a few frames as false positives.
+    #[serde(default = "default_skip_brief_detections")]
+    pub skip_brief_detections: bool,
+    #[serde(default)]
+    pub detector_hardware: DetectorHardware,
+    #[serde(default)]
+    pub capture_source: CaptureSource,
+    #[serde(default)]
+    pub window: Option<WindowPlacement>,
+    #[serde(default)]
+    pub last_tab: Tab,
+    /// Measured throughput, for time estimates.
+    #[serde(default)]
+    pub performance: PerformanceHistory,
 }
 
 fn default_blur_coverage() -> u32 {
     40
 }
 
+fn default_speech_language() -> String {
+    SpeechLanguage::default().code().to_string()
+}
+
+fn default_keyword_matching() -> KeywordMatching {
+    KeywordMatching::Exact
+}
+
 fn default_bleep_sound() -> BleepSound {
     BleepSound::Tone
 }
@@ -121,10 +526,54 @@ fn default_voice_disguise() -> VoiceDisguise {
     VoiceDisguise::Off
 }
 
+fn default_audio_fallback() -> AudioFallback {
+    AudioFallback::Fail
+}
+
+fn default_onboarded() -> bool {
+    true
+}
+
+fn default_detection_interval() -> u32 {
+    DEFAULT_DETECTION_INTERVAL
+}
+
+fn default_cache_detections() -> bool {
+    true
+}
+
+fn default_finish_attention() -> bool {
+    true
+}
+
+fn default_skip_brief_detections() -> bool {
+    true
+}
+
 fn default_quality() -> u32 {
     crf_to_quality(faceguard_core::video::infrastructure::ffmpeg_writer::DEFAULT_CRF)
 }
 
+fn default_overlay_palette() -> OverlayPalette {
+    OverlayPalette::Standard
+}
+
+fn default_video_codec() -> VideoCodec {
+    VideoCodec::H264
+}
+
+fn default_image_format() -> ImageFormat {
+    ImageFormat::Original
+}
+
+fn default_image_quality() -> u32 {
+    faceguard_core::video::infrastructure::image_file_writer::DEFAULT_JPEG_QUALITY as u32
+}
+
+fn default_png_compression() -> PngCompression {
+    PngCompression::Default
+}
+
 pub fn quality_to_crf(quality: u32) -> u32 {
     51 - (quality.min(100) * 51 / 100)
 }
@@ -139,22 +588,124 @@ impl Default for Settings {
             blur_shape: BlurShape::Ellipse,
             confidence: 50,
             blur_strength: 201,
+            blur_feather: 0,
             blur_coverage: 40,
             center_offset: 0,
             lookahead: 10,
+            detection_interval: default_detection_interval(),
             quality: default_quality(),
+            video_codec: default_video_codec(),
+            image_format: default_image_format(),
+            image_quality: default_image_quality(),
+            png_compression: default_png_compression(),
             appearance: Appearance::System,
             high_contrast: false,
             font_scale: 1.0,
+            show_regions: false,
+            show_detections: false,
+            overlay_palette: default_overlay_palette(),
             audio_processing: false,
             bleep_keywords: String::new(),
+            keyword_matching: default_keyword_matching(),
+            bleep_names: false,
+            bleep_numbers: false,
+            bleep_locations: false,
+            speech_language: default_speech_language(),
             bleep_sound: default_bleep_sound(),
             voice_disguise: default_voice_disguise(),
+            audio_fallback: default_audio_fallback(),
+            post_export_checklist: false,
+            finish_sound: false,
+            finish_attention: default_finish_attention(),
+            write_ledger: false,
+            ledger_operator: String::new(),
+            onboarded: false,
+            model_dir: None,
+            anonymize_thumbnails: false,
+            cache_detections: default_cache_detections(),
+            skip_brief_detections: default_skip_brief_detections(),
+            detector_hardware: DetectorHardware::Auto,
+            capture_source: CaptureSource::Screen,
+            window: None,
+            last_tab: Tab::Blur,
+            performance: PerformanceHistory::default(),
         }
     }
 }
 
 impl Settings {
+    /// Sets the knobs the app exposes to `preset`'s values. Leak
+    /// verification is not part of a desktop export, so only the CLI's
+    /// maximum preset runs it.
+    pub fn apply_preset(&mut self, preset: BlurPreset) {
+        let values = preset.settings();
+        self.confidence = (values.confidence * 100.0).round() as u32;
+        self.blur_strength = values.blur_strength as u32;
+        self.blur_coverage = (values.padding * 100.0).round() as u32;
+        self.lookahead = values.lookahead as u32;
+        self.detection_interval = values.detection_interval as u32;
+        self.skip_brief_detections = values.min_track_frames > 1;
+    }
+
+    /// The preset the current values equal; `None` ("Custom") once one of
+    /// them was changed on its own.
+    pub fn preset(&self) -> Option<BlurPreset> {
+        let bundled = |s: &Settings| {
+            (
+                s.confidence,
+                s.blur_strength,
+                s.blur_coverage,
+                s.lookahead,
+                s.detection_interval,
+                s.skip_brief_detections,
+            )
+        };
+        BlurPreset::ALL.into_iter().find(|&preset| {
+            let mut applied = self.clone();
+            applied.apply_preset(preset);
+            bundled(&applied) == bundled(self)
+        })
+    }
+
+    /// Whether spoken `category` is bleeped.
+    pub fn bleeps_pii(&self, category: PiiCategory) -> bool {
+        match category {
+            PiiCategory::Name => self.bleep_names,
+            PiiCategory::Number => self.bleep_numbers,
+            PiiCategory::Location => self.bleep_locations,
+        }
+    }
+
+    pub fn set_bleeps_pii(&mut self, category: PiiCategory, enabled: bool) {
+        match category {
+            PiiCategory::Name => self.bleep_names = enabled,
+            PiiCategory::Number => self.bleep_numbers = enabled,
+            PiiCategory::Location => self.bleep_locations = enabled,
+        }
+    }
+
+    /// The kinds of personal information bleeped, in `PiiCategory::ALL` order.
+    pub fn bleep_pii(&self) -> Vec<PiiCategory> {
+        PiiCategory::ALL
+            .into_iter()
+            .filter(|&category| self.bleeps_pii(category))
+            .collect()
+    }
+
+    /// The speech language; detection for a code this build doesn't know.
+    pub fn speech_language(&self) -> SpeechLanguage {
+        SpeechLanguage::parse(&self.speech_language).unwrap_or_default()
+    }
+
+    /// Shortest track kept by preview and blur; 0 keeps every track.
+    pub fn min_track_frames(&self) -> usize {
+        if self.skip_brief_detections {
+            DEFAULT_MIN_TRACK_FRAMES
+        } else {
+            0
+        }
+    }
+
     fn config_path() -> Option<PathBuf> {
         dirs::config_dir().map(|d| d.join("FaceGuard").join("settings.json"))
     }
@@ -166,6 +717,13 @@ impl Settings {
             .unwrap_or_default()
     }
 
+    /// Points the core model resolver at the chosen model folder.
+    pub fn apply_model_dir(&self) {
+        if let Some(ref dir) = self.model_dir {
+            std::env::set_var(model_resolver::MODEL_DIR_ENV, dir);
+        }
+    }
+
     pub fn save(&self) {
         if let Some(path) = Self::config_path() {
             if let Some(parent) = path.parent() {
@@ -0,0 +1,107 @@
+use iced::keyboard::key::Named;
+use iced::keyboard::{Key, Modifiers};
+
+/// An action reachable from the keyboard and the command palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    OpenFile,
+    Blur,
+    ChooseFaces,
+    ExportSelection,
+    Cancel,
+    ShowMetadata,
+    InspectReport,
+    StartOver,
+    BlurTab,
+    Settings,
+    About,
+    CommandPalette,
+}
+
+impl Command {
+    /// Commands listed in the palette, in order. The palette itself is left
+    /// out.
+    pub const PALETTE: &[Command] = &[
+        Command::OpenFile,
+        Command::Blur,
+        Command::ChooseFaces,
+        Command::ExportSelection,
+        Command::Cancel,
+        Command::ShowMetadata,
+        Command::InspectReport,
+        Command::StartOver,
+        Command::BlurTab,
+        Command::Settings,
+        Command::About,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Command::OpenFile => "Open File\u{2026}",
+            Command::Blur => "Blur Faces",
+            Command::ChooseFaces => "Choose Specific Faces\u{2026}",
+            Command::ExportSelection => "Export Face Selection\u{2026}",
+            Command::Cancel => "Cancel",
+            Command::ShowMetadata => "Show Metadata",
+            Command::InspectReport => "Inspect Report\u{2026}",
+            Command::StartOver => "Start Over",
+            Command::BlurTab => "Go to Blur",
+            Command::Settings => "Go to Settings",
+            Command::About => "Go to About",
+            Command::CommandPalette => "Command Palette",
+        }
+    }
+
+    /// Key hint shown next to the command in the palette.
+    pub fn shortcut(self) -> Option<String> {
+        let command_key = if cfg!(target_os = "macos") {
+            "\u{2318}"
+        } else {
+            "Ctrl+"
+        };
+        match self {
+            Command::OpenFile => Some("O".into()),
+            Command::Blur => Some("B".into()),
+            Command::ChooseFaces => Some("P".into()),
+            Command::Cancel => Some("Esc".into()),
+            Command::Settings => Some(format!("{command_key},")),
+            Command::CommandPalette => Some(format!("{command_key}K")),
+            Command::ExportSelection
+            | Command::ShowMetadata
+            | Command::InspectReport
+            | Command::StartOver
+            | Command::BlurTab
+            | Command::About => None,
+        }
+    }
+
+    /// Whether the command's label contains every word of `query`, ignoring
+    /// case.
+    pub fn matches(self, query: &str) -> bool {
+        let label = self.label().to_lowercase();
+        query
+            .to_lowercase()
+            .split_whitespace()
+            .all(|word| label.contains(word))
+    }
+
+    /// The command bound to a key press. Single-letter shortcuts only apply
+    /// when no text field took the key, so typing a label or keyword never
+    /// starts a job.
+    pub fn from_key(key: &Key, modifiers: Modifiers, captured: bool) -> Option<Command> {
+        match key.as_ref() {
+            Key::Named(Named::Escape) => Some(Command::Cancel),
+            Key::Character(",") if modifiers.command() => Some(Command::Settings),
+            Key::Character("k") if modifiers.command() => Some(Command::CommandPalette),
+            Key::Character(c) if !captured && modifiers.is_empty() => {
+                match c.to_lowercase().as_str() {
+                    "o" => Some(Command::OpenFile),
+                    "b" => Some(Command::Blur),
+                    "p" => Some(Command::ChooseFaces),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
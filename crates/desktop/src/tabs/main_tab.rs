@@ -1,15 +1,54 @@
 use std::path::Path;
 
-use iced::widget::{button, column, container, progress_bar, row, svg, text, Space};
+use iced::widget::{
+    button, checkbox, column, container, mouse_area, pick_list, progress_bar, row, slider, svg,
+    text, text_input, Space,
+};
 use iced::{Element, Length, Theme};
 
 use crate::app::{scaled, Message, ProcessingState};
-use crate::theme::{muted_color, tertiary_color};
+use crate::checklist::Checklist;
+use crate::platform;
+use crate::settings::{BlurShape, CaptureSource, MAX_BLUR_STRENGTH};
+use crate::theme::{muted_color, surface_color, tertiary_color};
+use crate::widgets::compare_slider::{self, CompareState};
+use crate::widgets::detection_timeline::{self, Coverage};
 use crate::widgets::drop_zone;
 use crate::widgets::faces_well::{self, FacesWellState};
 use crate::widgets::file_row;
 use crate::widgets::primary_button;
 use crate::widgets::secondary_button;
+use crossbeam_channel::Sender;
+use faceguard_core::audio::domain::keyword_hit::KeywordHit;
+use faceguard_core::blurring::domain::weak_blur::WeakBlur;
+use faceguard_core::pipeline::report_inspection::{ReportInspection, TrackSummary};
+use faceguard_core::shared::warnings::Warning;
+use faceguard_core::video::domain::source_metadata::MetadataField;
+use faceguard_core::video::infrastructure::screen_capture_reader::DEFAULT_CAPTURE_FPS;
+
+/// In and out points typed for a video input, and why they can't be used,
+/// if they can't.
+pub struct TrimFields<'a> {
+    pub start: &'a str,
+    pub end: &'a str,
+    pub error: Option<String>,
+}
+
+/// Where the sample frame is in the video, and the blur settings that can
+/// be tried on it without leaving the Blur tab.
+pub struct PreviewControls {
+    pub frame: usize,
+    pub frames: usize,
+    pub fps: f64,
+    pub shape: BlurShape,
+    pub strength: u32,
+    pub coverage: u32,
+    /// The coverage differs from the scan's and only the sample shows it
+    /// until the user re-scans.
+    pub coverage_held: bool,
+    /// Where the scan found faces along the video.
+    pub timeline: Vec<Coverage>,
+}
 
 #[allow(clippy::too_many_arguments)]
 pub fn view<'a>(
@@ -18,7 +57,16 @@ pub fn view<'a>(
     output_path: Option<&Path>,
     processing: &ProcessingState,
     faces_well: &FacesWellState,
+    compare: Option<&CompareState>,
+    compare_pending: bool,
+    preview: Option<PreviewControls>,
+    estimate: Option<String>,
+    weak_blur: Option<WeakBlur>,
+    trim: Option<TrimFields<'a>>,
     theme: &Theme,
+    capture_source: CaptureSource,
+    capture_window: &str,
+    capture_error: Option<&str>,
     browse_hovered: bool,
     drop_zone_hovered: bool,
     blur_button_hovered: bool,
@@ -34,8 +82,25 @@ pub fn view<'a>(
     let muted = muted_color(theme);
     let tertiary = tertiary_color(theme);
 
+    if let ProcessingState::Recording(frames) = processing {
+        return recording_state(fs, tertiary, *frames, cancel_hovered);
+    }
+
     if input_path.is_none() {
-        return drop_zone::view(fs, tertiary, theme, browse_hovered, drop_zone_hovered);
+        return column![
+            drop_zone::view(fs, tertiary, theme, browse_hovered, drop_zone_hovered),
+            Space::new().height(16),
+            capture_panel(
+                fs,
+                tertiary,
+                theme,
+                capture_source,
+                capture_window,
+                capture_error
+            ),
+        ]
+        .height(Length::Fill)
+        .into();
     }
 
     if let ProcessingState::Complete = processing {
@@ -60,6 +125,12 @@ pub fn view<'a>(
         output_path,
         processing,
         faces_well,
+        compare,
+        compare_pending,
+        preview,
+        estimate,
+        weak_blur,
+        trim,
         theme,
         blur_button_hovered,
         change_input_hovered,
@@ -71,6 +142,105 @@ pub fn view<'a>(
     )
 }
 
+/// Screen recorder controls under the drop zone.
+fn capture_panel<'a>(
+    fs: f32,
+    tertiary: iced::Color,
+    theme: &Theme,
+    source: CaptureSource,
+    window: &str,
+    error: Option<&str>,
+) -> Element<'a, Message> {
+    let window_hint = platform::window_capture_hint();
+    let sources = CaptureSource::ALL
+        .iter()
+        .filter(|&&s| s == CaptureSource::Screen || window_hint.is_some())
+        .map(|&s| -> Element<'a, Message> {
+            let style: fn(&Theme, button::Status) -> button::Style = if s == source {
+                button::primary
+            } else {
+                button::secondary
+            };
+            button(text(s.to_string()).size(scaled(13.0, fs)))
+                .on_press(Message::CaptureSourceChanged(s))
+                .padding([6, 12])
+                .style(style)
+                .into()
+        });
+
+    let mut col = column![row![
+        text("Or record your screen")
+            .size(scaled(15.0, fs))
+            .width(Length::Fill),
+        row(sources).spacing(6),
+    ]
+    .align_y(iced::Alignment::Center)]
+    .spacing(10);
+
+    if let (CaptureSource::Window, Some(hint)) = (source, window_hint) {
+        col = col.push(
+            text_input(hint, window)
+                .on_input(Message::CaptureWindowChanged)
+                .size(scaled(14.0, fs)),
+        );
+    }
+
+    col = col
+        .push(
+            button(text("Start Recording").size(scaled(15.0, fs)).center())
+                .on_press(Message::StartRecording)
+                .padding([12, 20])
+                .width(Length::Fill),
+        )
+        .push(
+            text("Faces are blurred as soon as you stop. Audio is not recorded.")
+                .size(scaled(13.0, fs))
+                .color(tertiary),
+        );
+
+    if let Some(error) = error {
+        col = col.push(
+            text(error.to_owned())
+                .size(scaled(13.0, fs))
+                .color(theme.palette().danger),
+        );
+    }
+
+    col.into()
+}
+
+fn recording_state<'a>(
+    fs: f32,
+    tertiary: iced::Color,
+    frames: usize,
+    stop_hovered: bool,
+) -> Element<'a, Message> {
+    let seconds = frames / DEFAULT_CAPTURE_FPS as usize;
+    centered(
+        column![
+            text("Recording").size(scaled(22.0, fs)).font(iced::Font {
+                weight: iced::font::Weight::Semibold,
+                ..iced::Font::DEFAULT
+            }),
+            Space::new().height(6),
+            text(format!("{}:{:02}", seconds / 60, seconds % 60))
+                .size(scaled(15.0, fs))
+                .color(tertiary),
+            Space::new().height(24),
+            secondary_button::secondary_button_small(
+                move || text("Stop Recording").size(scaled(14.0, fs)).into(),
+                Message::StopRecording,
+                stop_hovered,
+                Message::CancelHover,
+                [8, 20],
+            ),
+        ]
+        .align_x(iced::Alignment::Center)
+        .width(280)
+        .into(),
+    )
+}
+
 fn complete_state<'a>(
     fs: f32,
     _muted: iced::Color,
@@ -181,10 +351,7 @@ fn complete_state<'a>(
                 })
                 .center(),
             Space::new().height(6),
-            text(format!("Saved as {filename}"))
-                .size(scaled(15.0, fs))
-                .color(tertiary)
-                .center(),
+            saved_as(fs, tertiary, filename),
             Space::new().height(28),
             show_btn,
             Space::new().height(10),
@@ -196,6 +363,706 @@ fn complete_state<'a>(
     )
 }
 
+/// The "Saved as" label, which doubles as a drag handle for the output file
+/// on platforms that support dragging files out of the app.
+fn saved_as<'a>(fs: f32, tertiary: iced::Color, filename: String) -> Element<'a, Message> {
+    let label = text(format!("Saved as {filename}"))
+        .size(scaled(15.0, fs))
+        .color(tertiary)
+        .center();
+
+    if !platform::supports_file_drag() {
+        return label.into();
+    }
+
+    mouse_area(
+        column![
+            label,
+            text("Drag to share or move the file")
+                .size(scaled(12.0, fs))
+                .color(tertiary)
+                .center(),
+        ]
+        .spacing(2)
+        .align_x(iced::Alignment::Center),
+    )
+    .on_press(Message::DragOutput)
+    .interaction(iced::mouse::Interaction::Grab)
+    .into()
+}
+
+/// Dismissible notice listing the non-fatal problems from the last job.
+pub fn warnings_banner<'a>(fs: f32, warnings: &[Warning], theme: &Theme) -> Element<'a, Message> {
+    let text_color = theme.palette().text;
+    let (background, border) = if is_dark_theme(theme) {
+        (
+            iced::Color::from_rgba(
+                0xE0 as f32 / 255.0,
+                0xA1 as f32 / 255.0,
+                0x2B as f32 / 255.0,
+                0.15,
+            ),
+            iced::Color::from_rgba(
+                0xE0 as f32 / 255.0,
+                0xA1 as f32 / 255.0,
+                0x2B as f32 / 255.0,
+                0.5,
+            ),
+        )
+    } else {
+        (
+            iced::Color::from_rgb(
+                0xFD as f32 / 255.0,
+                0xF4 as f32 / 255.0,
+                0xE3 as f32 / 255.0,
+            ),
+            iced::Color::from_rgb(
+                0xE8 as f32 / 255.0,
+                0xC2 as f32 / 255.0,
+                0x7A as f32 / 255.0,
+            ),
+        )
+    };
+
+    let title = if warnings.len() == 1 {
+        "Finished with a warning".to_string()
+    } else {
+        format!("Finished with {} warnings", warnings.len())
+    };
+    let mut list = column![row![
+        text(title)
+            .size(scaled(15.0, fs))
+            .font(iced::Font {
+                weight: iced::font::Weight::Semibold,
+                ..iced::Font::DEFAULT
+            })
+            .width(Length::Fill),
+        button(text("Dismiss").size(scaled(13.0, fs)))
+            .on_press(Message::DismissWarnings)
+            .padding([4, 10])
+            .style(button::text),
+    ]
+    .align_y(iced::Alignment::Center)]
+    .spacing(6);
+    for warning in warnings {
+        list = list.push(
+            text(format!("\u{2022} {warning}"))
+                .size(scaled(13.0, fs))
+                .color(text_color),
+        );
+    }
+
+    container(list)
+        .padding([12, 16])
+        .width(Length::Fill)
+        .style(move |_theme: &Theme| container::Style {
+            background: Some(background.into()),
+            border: iced::border::Border {
+                color: border,
+                width: 1.0,
+                radius: 10.0.into(),
+            },
+            ..container::Style::default()
+        })
+        .into()
+}
+
+/// Asks what happens to a running export when the window is closed, since
+/// quitting outright would leave a partial file behind.
+pub fn close_prompt<'a>(fs: f32, theme: &Theme) -> Element<'a, Message> {
+    let muted = muted_color(theme);
+    let list = column![
+        text("An export is still running")
+            .size(scaled(15.0, fs))
+            .font(iced::Font {
+                weight: iced::font::Weight::Semibold,
+                ..iced::Font::DEFAULT
+            }),
+        text(
+            "Cancel it and the partial file is deleted before FaceGuard quits, \
+             or let it finish with the window minimized and quit when it is done."
+        )
+        .size(scaled(13.0, fs))
+        .color(muted),
+        row![
+            button(text("Cancel Export and Quit").size(scaled(13.0, fs)))
+                .on_press(Message::CancelAndQuit)
+                .padding([6, 12])
+                .style(button::danger),
+            button(text("Finish in Background").size(scaled(13.0, fs)))
+                .on_press(Message::QuitWhenDone)
+                .padding([6, 12])
+                .style(button::primary),
+            Space::new().width(Length::Fill),
+            button(text("Keep Open").size(scaled(13.0, fs)))
+                .on_press(Message::KeepWindowOpen)
+                .padding([6, 12])
+                .style(button::text),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center),
+    ]
+    .spacing(8);
+
+    panel(list, theme)
+}
+
+/// Keyword hits a running export found in the audio, while the user picks
+/// which to bleep. The export waits on `reply`.
+pub struct KeywordReview {
+    hits: Vec<KeywordHit>,
+    keep: Vec<bool>,
+    reply: Sender<Vec<KeywordHit>>,
+}
+
+impl KeywordReview {
+    /// Every hit starts checked, so confirming without changes bleeps all.
+    pub fn new(hits: Vec<KeywordHit>, reply: Sender<Vec<KeywordHit>>) -> Self {
+        let keep = vec![true; hits.len()];
+        Self { hits, keep, reply }
+    }
+
+    pub fn toggle(&mut self, index: usize) {
+        if let Some(keep) = self.keep.get_mut(index) {
+            *keep = !*keep;
+        }
+    }
+
+    fn kept(&self) -> usize {
+        self.keep.iter().filter(|&&keep| keep).count()
+    }
+
+    /// Lets the export go on with the checked hits.
+    pub fn confirm(self) {
+        let kept = self
+            .hits
+            .into_iter()
+            .zip(self.keep)
+            .filter_map(|(hit, keep)| keep.then_some(hit))
+            .collect();
+        // A job cancelled meanwhile no longer listens.
+        let _ = self.reply.send(kept);
+    }
+}
+
+/// Lists the keyword hits of a running export with their time, recognized
+/// word, and confidence, so misheard words can be unchecked before the
+/// bleep pass.
+pub fn keyword_review_panel<'a>(
+    fs: f32,
+    review: &KeywordReview,
+    theme: &Theme,
+) -> Element<'a, Message> {
+    let muted = muted_color(theme);
+    let count = review.hits.len();
+    let mut list = column![
+        text("Review bleeps")
+            .size(scaled(15.0, fs))
+            .font(iced::Font {
+                weight: iced::font::Weight::Semibold,
+                ..iced::Font::DEFAULT
+            }),
+        text(format!(
+            "{count} spoken {} matched your bleep keywords. Uncheck any the \
+             recognizer misheard; the export finishes once you confirm.",
+            if count == 1 { "word" } else { "words" }
+        ))
+        .size(scaled(13.0, fs))
+        .color(muted),
+    ]
+    .spacing(8);
+
+    for (i, (hit, &keep)) in review.hits.iter().zip(&review.keep).enumerate() {
+        let word = &hit.word;
+        let label = format!(
+            "{}  \u{201c}{}\u{201d}  {:.0}%",
+            clock(word.start_time),
+            word.word,
+            word.confidence * 100.0
+        );
+        let mut entry = row![checkbox(keep)
+            .label(label)
+            .on_toggle(move |_| Message::ToggleKeywordHit(i))
+            .text_size(scaled(13.0, fs))]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+        if !word.word.eq_ignore_ascii_case(&hit.keyword) {
+            entry = entry.push(
+                text(format!("for \u{201c}{}\u{201d}", hit.keyword))
+                    .size(scaled(13.0, fs))
+                    .color(muted),
+            );
+        }
+        list = list.push(entry);
+    }
+
+    let kept = review.kept();
+    let confirm = match kept {
+        0 => "Bleep Nothing".to_string(),
+        1 => "Bleep 1 Word".to_string(),
+        n => format!("Bleep {n} Words"),
+    };
+    list = list.push(
+        row![
+            button(text(confirm).size(scaled(13.0, fs)))
+                .on_press(Message::ConfirmKeywordReview)
+                .padding([6, 12])
+                .style(button::primary),
+            Space::new().width(Length::Fill),
+            button(text("Cancel Export").size(scaled(13.0, fs)))
+                .on_press(Message::CancelWork)
+                .padding([6, 12])
+                .style(button::text),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center),
+    );
+
+    panel(list, theme)
+}
+
+/// Questions to answer about the finished export before sharing it.
+pub fn checklist_panel<'a>(fs: f32, checklist: &Checklist, theme: &Theme) -> Element<'a, Message> {
+    let muted = muted_color(theme);
+    let mut list = column![
+        text("Before you share")
+            .size(scaled(15.0, fs))
+            .font(iced::Font {
+                weight: iced::font::Weight::Semibold,
+                ..iced::Font::DEFAULT
+            }),
+        text(if checklist.is_required() {
+            "Blurring can miss faces and words. Your organization asks you to confirm each check."
+        } else {
+            "Blurring can miss faces and words. Review the output yourself."
+        })
+        .size(scaled(13.0, fs))
+        .color(muted),
+    ]
+    .spacing(8);
+
+    for (i, (item, checked)) in checklist.items().enumerate() {
+        list = list.push(
+            checkbox(checked)
+                .label(item.to_string())
+                .on_toggle(move |_| Message::ToggleChecklistItem(i))
+                .text_size(scaled(13.0, fs)),
+        );
+    }
+
+    list = list.push(
+        button(text("Done").size(scaled(13.0, fs)))
+            .on_press_maybe(checklist.can_dismiss().then_some(Message::DismissChecklist))
+            .padding([6, 12])
+            .style(button::primary),
+    );
+
+    panel(list, theme)
+}
+
+/// A scrubber for the sample frame, and blur settings that re-render it
+/// as they change.
+fn preview_controls<'a>(fs: f32, theme: &Theme, preview: PreviewControls) -> Element<'a, Message> {
+    let tertiary = tertiary_color(theme);
+    let label = |s: &str| text(s.to_string()).size(scaled(13.0, fs)).color(tertiary);
+    let mut controls = column![].spacing(10);
+
+    if preview.frames > 1 {
+        let position = if preview.fps > 0.0 {
+            clock(preview.frame as f64 / preview.fps)
+        } else {
+            format!("Frame {}", preview.frame + 1)
+        };
+        controls = controls.push(
+            row![
+                label("Sample at"),
+                detection_timeline::view(
+                    &preview.timeline,
+                    preview.frame,
+                    preview.frames,
+                    position,
+                    fs,
+                    theme,
+                ),
+            ]
+            .spacing(10),
+        );
+    }
+
+    controls = controls.push(
+        row![
+            label("Intensity"),
+            slider(51..=401, preview.strength, Message::BlurStrengthChanged).step(2u32),
+            label("Coverage"),
+            slider(0..=100, preview.coverage, Message::BlurCoverageChanged).step(5u32),
+            pick_list(
+                BlurShape::ALL,
+                Some(preview.shape),
+                Message::BlurShapeChanged
+            )
+            .text_size(scaled(13.0, fs)),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center),
+    );
+
+    if preview.coverage_held {
+        controls = controls.push(
+            row![
+                text("The new coverage is only previewed here. Re-scan to use it in the export; that clears your face selection.")
+                    .size(scaled(13.0, fs))
+                    .color(theme.palette().danger)
+                    .width(Length::Fill),
+                button(text("Re-scan").size(scaled(13.0, fs)))
+                    .on_press(Message::ConfirmRescan)
+                    .padding([6, 12])
+                    .style(button::danger),
+                button(text("Keep Selection").size(scaled(13.0, fs)))
+                    .on_press(Message::KeepSelection)
+                    .padding([6, 12])
+                    .style(button::text),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+        );
+    }
+
+    controls.into()
+}
+
+/// `M:SS.s`, for positions in the video or its audio.
+fn clock(seconds: f64) -> String {
+    let tenths = (seconds.max(0.0) * 10.0).round() as u64;
+    format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
+
+/// Shown while a cancelled export cleans up before the app quits.
+pub fn quitting_notice<'a>(fs: f32, theme: &Theme) -> Element<'a, Message> {
+    let notice = text("Cancelling the export and deleting the partial file\u{2026}")
+        .size(scaled(13.0, fs))
+        .color(muted_color(theme));
+    panel(column![notice], theme)
+}
+
+fn panel<'a>(content: iced::widget::Column<'a, Message>, theme: &Theme) -> Element<'a, Message> {
+    let surface = surface_color(theme);
+    let border = iced::Color {
+        a: 0.12,
+        ..theme.palette().text
+    };
+    container(content)
+        .padding([12, 16])
+        .width(Length::Fill)
+        .style(move |_theme: &Theme| container::Style {
+            background: Some(surface.into()),
+            border: iced::border::Border {
+                color: border,
+                width: 1.0,
+                radius: 10.0.into(),
+            },
+            ..container::Style::default()
+        })
+        .into()
+}
+
+/// Lists the metadata found in the input, which exports strip, so users
+/// can check what was there before sharing.
+pub fn metadata_panel<'a>(
+    fs: f32,
+    info: &Result<Vec<MetadataField>, String>,
+    theme: &Theme,
+) -> Element<'a, Message> {
+    let muted = muted_color(theme);
+    let tertiary = tertiary_color(theme);
+    let surface = surface_color(theme);
+    let border = iced::Color {
+        a: 0.12,
+        ..theme.palette().text
+    };
+
+    let mut list = column![row![
+        text("File Metadata")
+            .size(scaled(15.0, fs))
+            .font(iced::Font {
+                weight: iced::font::Weight::Semibold,
+                ..iced::Font::DEFAULT
+            })
+            .width(Length::Fill),
+        button(text("Close").size(scaled(13.0, fs)))
+            .on_press(Message::CloseMetadata)
+            .padding([4, 10])
+            .style(button::text),
+    ]
+    .align_y(iced::Alignment::Center)]
+    .spacing(6);
+
+    match info {
+        Err(e) => {
+            list = list.push(
+                text(format!("Metadata could not be read: {e}"))
+                    .size(scaled(13.0, fs))
+                    .color(muted),
+            );
+        }
+        Ok(fields) if fields.is_empty() => {
+            list = list.push(
+                text("No metadata found in this file.")
+                    .size(scaled(13.0, fs))
+                    .color(muted),
+            );
+        }
+        Ok(fields) => {
+            list = list.push(
+                text("Exported files keep none of these fields.")
+                    .size(scaled(13.0, fs))
+                    .color(muted),
+            );
+            let mut category = None;
+            for field in fields {
+                if category != Some(field.category) {
+                    category = Some(field.category);
+                    list = list.push(
+                        text(field.category.label().to_uppercase())
+                            .size(scaled(12.0, fs))
+                            .font(iced::Font {
+                                weight: iced::font::Weight::Semibold,
+                                ..iced::Font::DEFAULT
+                            })
+                            .color(tertiary),
+                    );
+                }
+                list = list.push(
+                    text(format!(
+                        "{}: {}  ({})",
+                        field.key,
+                        truncated(&field.value, METADATA_VALUE_CHARS),
+                        field.scope
+                    ))
+                    .size(scaled(13.0, fs)),
+                );
+            }
+        }
+    }
+
+    container(list)
+        .padding([12, 16])
+        .width(Length::Fill)
+        .style(move |_theme: &Theme| container::Style {
+            background: Some(surface.into()),
+            border: iced::border::Border {
+                color: border,
+                width: 1.0,
+                radius: 10.0.into(),
+            },
+            ..container::Style::default()
+        })
+        .into()
+}
+
+/// Summarizes a report or detection export opened for review: coverage,
+/// each track's redaction timeline, and the job's warnings. Read-only, so
+/// reviewers can audit outputs without the source media.
+pub fn inspection_panel<'a>(
+    fs: f32,
+    path: &Path,
+    inspection: &Result<ReportInspection, String>,
+    theme: &Theme,
+) -> Element<'a, Message> {
+    let muted = muted_color(theme);
+    let tertiary = tertiary_color(theme);
+    let surface = surface_color(theme);
+    let border = iced::Color {
+        a: 0.12,
+        ..theme.palette().text
+    };
+    let semibold = iced::Font {
+        weight: iced::font::Weight::Semibold,
+        ..iced::Font::DEFAULT
+    };
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let mut list = column![row![
+        text(format!("Inspect {name}"))
+            .size(scaled(15.0, fs))
+            .font(semibold)
+            .width(Length::Fill),
+        button(text("Close").size(scaled(13.0, fs)))
+            .on_press(Message::CloseInspection)
+            .padding([4, 10])
+            .style(button::text),
+    ]
+    .align_y(iced::Alignment::Center)]
+    .spacing(6);
+
+    let inspection = match inspection {
+        Err(e) => {
+            list = list.push(
+                text(format!("Report could not be read: {e}"))
+                    .size(scaled(13.0, fs))
+                    .color(muted),
+            );
+            return inspection_container(list, surface, border);
+        }
+        Ok(inspection) => inspection,
+    };
+
+    let mut summary = vec![inspection.kind.label().to_string()];
+    if let (Some(width), Some(height)) = (inspection.width, inspection.height) {
+        summary.push(format!("{width}x{height}"));
+    }
+    if let Some(coverage) = inspection.coverage() {
+        summary.push(format!(
+            "faces on {} of {} frames ({:.1}%)",
+            inspection.frames_with_faces,
+            inspection.total_frames,
+            coverage * 100.0
+        ));
+    }
+    list = list.push(
+        text(summary.join(" \u{00b7} "))
+            .size(scaled(13.0, fs))
+            .color(muted),
+    );
+    for (label, value) in [
+        ("Source", &inspection.source),
+        ("Output", &inspection.output),
+    ] {
+        if let Some(value) = value {
+            list = list.push(
+                text(format!("{label}: {value}"))
+                    .size(scaled(13.0, fs))
+                    .color(muted),
+            );
+        }
+    }
+
+    list = list.push(
+        text(format!("TRACKS ({})", inspection.tracks.len()))
+            .size(scaled(12.0, fs))
+            .font(semibold)
+            .color(tertiary),
+    );
+    for track in &inspection.tracks {
+        list = list.push(inspected_track(fs, inspection, track, theme));
+    }
+
+    if !inspection.warnings.is_empty() {
+        list = list.push(
+            text(format!("WARNINGS ({})", inspection.warnings.len()))
+                .size(scaled(12.0, fs))
+                .font(semibold)
+                .color(tertiary),
+        );
+        for warning in &inspection.warnings {
+            list = list.push(text(warning.message.clone()).size(scaled(13.0, fs)));
+        }
+    }
+
+    inspection_container(list, surface, border)
+}
+
+/// Buckets in each track's timeline strip.
+const INSPECTION_TIMELINE_BUCKETS: usize = 60;
+
+fn inspected_track<'a>(
+    fs: f32,
+    inspection: &ReportInspection,
+    track: &TrackSummary,
+    theme: &Theme,
+) -> Element<'a, Message> {
+    let mut parts = vec![track
+        .track_id
+        .map(|id| format!("Face {id}"))
+        .unwrap_or_else(|| "Untracked faces".into())];
+    if !track.label.is_empty() {
+        parts.push(track.label.clone());
+    }
+    match track.blurred {
+        Some(true) => parts.push("blurred".into()),
+        Some(false) => parts.push("left visible".into()),
+        None => {}
+    }
+    if track.frames > 0 {
+        parts.push(format!("{} frames", track.frames));
+    }
+    if let Some(confidence) = track.mean_confidence {
+        parts.push(format!("confidence {confidence:.2}"));
+    }
+    if !track.blur_ranges.is_empty() {
+        parts.push(format!("ranges {}", track.blur_ranges));
+    }
+    if !track.note.is_empty() {
+        parts.push(track.note.clone());
+    }
+    let color = if track.blurred == Some(false) {
+        theme.palette().danger
+    } else {
+        theme.palette().text
+    };
+    let mut entry = column![text(parts.join(" \u{00b7} "))
+        .size(scaled(13.0, fs))
+        .color(color)]
+    .spacing(3);
+
+    if inspection.total_frames > 0 && !track.spans.is_empty() {
+        let filled = theme.palette().primary;
+        let empty = iced::Color {
+            a: 0.08,
+            ..theme.palette().text
+        };
+        let strip = track
+            .occupancy(inspection.total_frames, INSPECTION_TIMELINE_BUCKETS)
+            .into_iter()
+            .fold(row![].spacing(1), |strip, on| {
+                let fill = if on { filled } else { empty };
+                strip.push(
+                    container(Space::new())
+                        .width(Length::FillPortion(1))
+                        .height(6)
+                        .style(move |_theme: &Theme| container::Style {
+                            background: Some(fill.into()),
+                            ..container::Style::default()
+                        }),
+                )
+            });
+        entry = entry.push(strip);
+    }
+    entry.into()
+}
+
+fn inspection_container<'a>(
+    list: iced::widget::Column<'a, Message>,
+    surface: iced::Color,
+    border: iced::Color,
+) -> Element<'a, Message> {
+    container(list)
+        .padding([12, 16])
+        .width(Length::Fill)
+        .style(move |_theme: &Theme| container::Style {
+            background: Some(surface.into()),
+            border: iced::border::Border {
+                color: border,
+                width: 1.0,
+                radius: 10.0.into(),
+            },
+            ..container::Style::default()
+        })
+        .into()
+}
+
+/// Longest metadata value shown in full; binary tags can run to kilobytes.
+const METADATA_VALUE_CHARS: usize = 80;
+
+fn truncated(value: &str, max_chars: usize) -> String {
+    match value.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}\u{2026}", &value[..end]),
+        None => value.to_string(),
+    }
+}
+
 fn error_state<'a>(
     fs: f32,
     _muted: iced::Color,
@@ -234,6 +1101,12 @@ fn workflow_view<'a>(
     output_path: Option<&Path>,
     processing: &ProcessingState,
     faces_well: &FacesWellState,
+    compare: Option<&CompareState>,
+    compare_pending: bool,
+    preview: Option<PreviewControls>,
+    estimate: Option<String>,
+    weak_blur: Option<WeakBlur>,
+    trim: Option<TrimFields<'a>>,
     theme: &Theme,
     blur_button_hovered: bool,
     change_input_hovered: bool,
@@ -256,16 +1129,30 @@ fn workflow_view<'a>(
     let mut col = column![].spacing(0);
 
     if !is_processing {
+        col = col.push(file_row::file_row(
+            fs,
+            "Input",
+            input_path,
+            Message::SelectInput,
+            change_input_hovered,
+            Message::ChangeInputHover,
+            theme,
+        ));
+        if input_path.is_some() {
+            col = col.push(row![
+                Space::new().width(Length::Fill),
+                button(text("Show Metadata\u{2026}").size(scaled(13.0, fs)))
+                    .on_press(Message::ShowMetadata)
+                    .padding([4, 10])
+                    .style(button::text),
+            ]);
+        }
+        if let Some(trim) = trim {
+            col = col
+                .push(Space::new().height(4))
+                .push(trim_row(fs, tertiary, theme, trim));
+        }
         col = col
-            .push(file_row::file_row(
-                fs,
-                "Input",
-                input_path,
-                Message::SelectInput,
-                change_input_hovered,
-                Message::ChangeInputHover,
-                theme,
-            ))
             .push(Space::new().height(12))
             .push(file_row::file_row(
                 fs,
@@ -334,6 +1221,11 @@ fn workflow_view<'a>(
                     [14, 20],
                 ),
             );
+            if let Some(estimate) = estimate {
+                col = col
+                    .push(Space::new().height(10))
+                    .push(estimate_line(fs, tertiary, &estimate));
+            }
         }
         ProcessingState::Preparing => {
             col = col.push(progress_with_cancel(
@@ -413,20 +1305,53 @@ fn workflow_view<'a>(
             );
             col = col
                 .push(faces_well::view(faces_well, fs, theme, face_card_hovered))
-                .push(Space::new().height(16))
-                .push(
-                    row![
-                        blur_btn,
-                        secondary_button::secondary_button(
-                            move || text("Re-scan").size(scaled(15.0, fs)).into(),
-                            Message::RunPreview,
-                            rescan_hovered,
-                            Message::RescanHover,
-                            [14, 20],
-                        ),
-                    ]
-                    .spacing(10),
-                );
+                .push(Space::new().height(16));
+            if let Some(compare) = compare {
+                col = col
+                    .push(compare_slider::view(
+                        compare,
+                        fs,
+                        theme,
+                        faces_well.content_width,
+                    ))
+                    .push(Space::new().height(16));
+                if let Some(preview) = preview {
+                    col = col
+                        .push(preview_controls(fs, theme, preview))
+                        .push(Space::new().height(16));
+                }
+            } else if compare_pending {
+                col = col
+                    .push(
+                        text("Rendering a sample frame\u{2026}")
+                            .size(scaled(13.0, fs))
+                            .color(tertiary),
+                    )
+                    .push(Space::new().height(16));
+            }
+            if let Some(weak) = weak_blur {
+                col = col
+                    .push(weak_blur_notice(fs, theme, weak))
+                    .push(Space::new().height(12));
+            }
+            col = col.push(
+                row![
+                    blur_btn,
+                    secondary_button::secondary_button(
+                        move || text("Re-scan").size(scaled(15.0, fs)).into(),
+                        Message::RunPreview,
+                        rescan_hovered,
+                        Message::RescanHover,
+                        [14, 20],
+                    ),
+                ]
+                .spacing(10),
+            );
+            if let Some(estimate) = estimate {
+                col = col
+                    .push(Space::new().height(10))
+                    .push(estimate_line(fs, tertiary, &estimate));
+            }
         }
         ProcessingState::Blurring(current, total) => {
             let (status, detail, pct) = frame_progress("Blurring faces", *current, *total);
@@ -446,6 +1371,73 @@ fn workflow_view<'a>(
     col.into()
 }
 
+/// In and out point inputs for exporting part of a video.
+fn trim_row<'a>(
+    fs: f32,
+    tertiary: iced::Color,
+    theme: &Theme,
+    trim: TrimFields<'a>,
+) -> Element<'a, Message> {
+    let fields = row![
+        text("Export from").size(scaled(13.0, fs)).color(tertiary),
+        text_input("start", trim.start)
+            .on_input(Message::TrimStartChanged)
+            .size(scaled(13.0, fs))
+            .width(96),
+        text("to").size(scaled(13.0, fs)).color(tertiary),
+        text_input("end", trim.end)
+            .on_input(Message::TrimEndChanged)
+            .size(scaled(13.0, fs))
+            .width(96),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center);
+
+    let note = match trim.error {
+        Some(error) => text(error).color(theme.palette().danger),
+        None => text("Times are MM:SS or HH:MM:SS. Leave blank to export the whole video.")
+            .color(tertiary),
+    };
+    column![fields, note.size(scaled(12.0, fs))]
+        .spacing(4)
+        .into()
+}
+
+/// Above the blur button when the intensity may leave the largest face of
+/// the scan recognizable, with a button to raise it as far as it needs or
+/// the slider goes.
+fn weak_blur_notice<'a>(fs: f32, theme: &Theme, weak: WeakBlur) -> Element<'a, Message> {
+    let mut notice = row![text(format!(
+        "Faces up to {}px wide were found. At this intensity they may stay recognizable.",
+        weak.face_width
+    ))
+    .size(scaled(13.0, fs))
+    .color(theme.palette().danger)
+    .width(Length::Fill)]
+    .spacing(10)
+    .align_y(iced::Alignment::Center);
+    let strength = (weak.recommended as u32).min(MAX_BLUR_STRENGTH);
+    if strength > weak.blur_strength as u32 {
+        notice = notice.push(
+            button(text("Raise Intensity").size(scaled(13.0, fs)))
+                .on_press(Message::BlurStrengthChanged(strength))
+                .padding([4, 10])
+                .style(button::text),
+        );
+    }
+    notice.into()
+}
+
+/// Upfront time estimate under the blur button.
+fn estimate_line<'a>(fs: f32, tertiary: iced::Color, estimate: &str) -> Element<'a, Message> {
+    text(format!("Blurring takes {estimate} on this computer."))
+        .size(scaled(13.0, fs))
+        .color(tertiary)
+        .width(Length::Fill)
+        .align_x(iced::Alignment::Center)
+        .into()
+}
+
 fn progress_with_cancel<'a>(
     fs: f32,
     _muted: iced::Color,
@@ -520,7 +1512,7 @@ fn progress_with_cancel_detail<'a>(
         .into()
 }
 
-fn styled_progress_bar(pct: f32) -> Element<'static, Message> {
+pub(crate) fn styled_progress_bar(pct: f32) -> Element<'static, Message> {
     progress_bar(0.0..=100.0, pct)
         .girth(8.0)
         .style(|theme: &Theme| {
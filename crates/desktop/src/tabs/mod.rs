@@ -1,3 +1,4 @@
 pub mod about_tab;
 pub mod main_tab;
+pub mod onboarding;
 pub mod settings_tab;
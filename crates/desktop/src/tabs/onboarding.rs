@@ -0,0 +1,226 @@
+use std::path::PathBuf;
+
+use crossbeam_channel::Receiver;
+use iced::widget::{button, column, container, row, text, Space};
+use iced::{Color, Element, Length, Theme};
+
+use faceguard_core::detection::infrastructure::model_resolver::{self, REGISTERED_MODELS};
+
+use crate::app::{scaled, Message};
+use crate::tabs::main_tab::styled_progress_bar;
+use crate::theme::{muted_color, surface_color, tertiary_color};
+use crate::widgets::primary_button;
+use crate::widgets::secondary_button;
+use crate::workers::prefetch_worker::PrefetchMessage;
+
+/// First-run flow: explains local-only processing, lets the user pick the
+/// model folder, and optionally downloads the models before the first job.
+pub struct OnboardingState {
+    pub model_dir: PathBuf,
+    /// `(model index, downloaded, total)` while a prefetch is running.
+    pub download: Option<(usize, u64, u64)>,
+    pub error: Option<String>,
+    pub prefetch_rx: Option<Receiver<PrefetchMessage>>,
+    pub download_hovered: bool,
+    pub skip_hovered: bool,
+}
+
+impl OnboardingState {
+    pub fn new(model_dir: Option<PathBuf>) -> Self {
+        Self {
+            model_dir: model_dir
+                .or_else(|| model_resolver::model_cache_dir().ok())
+                .unwrap_or_default(),
+            download: None,
+            error: None,
+            prefetch_rx: None,
+            download_hovered: false,
+            skip_hovered: false,
+        }
+    }
+
+    pub fn is_downloading(&self) -> bool {
+        self.prefetch_rx.is_some()
+    }
+}
+
+pub fn view<'a>(fs: f32, state: &OnboardingState, theme: &Theme) -> Element<'a, Message> {
+    let muted = muted_color(theme);
+    let tertiary = tertiary_color(theme);
+    let surface = surface_color(theme);
+    let palette = theme.palette();
+    let border_color = Color {
+        a: 0.15,
+        ..palette.text
+    };
+    let semibold = iced::Font {
+        weight: iced::font::Weight::Semibold,
+        ..iced::Font::DEFAULT
+    };
+
+    let header = column![
+        text("Welcome to FaceGuard")
+            .size(scaled(26.0, fs))
+            .font(semibold),
+        Space::new().height(8),
+        text("A quick setup before your first video.")
+            .size(scaled(15.0, fs))
+            .color(muted),
+    ]
+    .width(Length::Fill)
+    .align_x(iced::Alignment::Center);
+
+    let privacy = card(
+        column![
+            text("Everything stays on this computer")
+                .size(scaled(15.0, fs))
+                .font(semibold),
+            Space::new().height(8),
+            text(
+                "Videos, photos, and detected faces are processed locally and never \
+                 uploaded. The only network access is a one-time download of the \
+                 detection models.",
+            )
+            .size(scaled(14.0, fs))
+            .color(muted),
+        ],
+        surface,
+        border_color,
+    );
+
+    let change_btn = button(text("Change\u{2026}").size(scaled(13.0, fs)))
+        .padding([4, 10])
+        .style(button::text);
+    let change_btn = if state.is_downloading() {
+        change_btn
+    } else {
+        change_btn.on_press(Message::ChooseModelDir)
+    };
+    let folder = card(
+        column![
+            text("Model folder").size(scaled(15.0, fs)).font(semibold),
+            Space::new().height(8),
+            row![
+                text(state.model_dir.display().to_string())
+                    .size(scaled(13.0, fs))
+                    .color(tertiary)
+                    .width(Length::Fill),
+                change_btn,
+            ]
+            .align_y(iced::Alignment::Center),
+            Space::new().height(4),
+            text("About 250 MB for face detection, face grouping, and speech recognition.")
+                .size(scaled(13.0, fs))
+                .color(tertiary),
+        ],
+        surface,
+        border_color,
+    );
+
+    let mut col = column![
+        header,
+        Space::new().height(24),
+        privacy,
+        Space::new().height(12),
+        folder,
+        Space::new().height(24),
+    ]
+    .width(Length::Fill);
+
+    if let Some((model, downloaded, total)) = state.download {
+        let name = REGISTERED_MODELS.get(model).map_or("", |m| m.name);
+        let status = format!(
+            "Downloading {name} ({} of {})",
+            model + 1,
+            REGISTERED_MODELS.len()
+        );
+        let pct = if total > 0 {
+            downloaded as f32 / total as f32 * 100.0
+        } else {
+            0.0
+        };
+        col = col.push(
+            column![
+                text(status).size(scaled(14.0, fs)).color(tertiary),
+                styled_progress_bar(pct),
+            ]
+            .spacing(8)
+            .align_x(iced::Alignment::Center),
+        );
+    } else if state.is_downloading() {
+        col = col.push(
+            text("Checking models\u{2026}")
+                .size(scaled(14.0, fs))
+                .color(tertiary),
+        );
+    } else {
+        if let Some(ref error) = state.error {
+            col = col
+                .push(
+                    text(format!("Download failed: {error}"))
+                        .size(scaled(13.0, fs))
+                        .color(palette.danger),
+                )
+                .push(Space::new().height(12));
+        }
+        let download_label = if state.error.is_some() {
+            "Try Again"
+        } else {
+            "Download Models Now"
+        };
+        col = col.push(
+            row![
+                secondary_button::secondary_button(
+                    move || text("Later").size(scaled(15.0, fs)).into(),
+                    Message::SkipOnboarding,
+                    state.skip_hovered,
+                    Message::SkipOnboardingHover,
+                    [12, 24],
+                ),
+                primary_button::primary_button(
+                    move || {
+                        text(download_label)
+                            .size(scaled(15.0, fs))
+                            .font(semibold)
+                            .into()
+                    },
+                    Message::DownloadModels,
+                    state.download_hovered,
+                    Message::DownloadModelsHover,
+                    [12, 24],
+                ),
+            ]
+            .spacing(12),
+        );
+        col = col.push(Space::new().height(8)).push(
+            text("With \u{201c}Later\u{201d}, models download when you start your first job.")
+                .size(scaled(13.0, fs))
+                .color(tertiary),
+        );
+    }
+
+    container(col.align_x(iced::Alignment::Center))
+        .width(Length::Fill)
+        .max_width(520)
+        .into()
+}
+
+fn card<'a>(
+    content: impl Into<Element<'a, Message>>,
+    surface: Color,
+    border_color: Color,
+) -> Element<'a, Message> {
+    container(content)
+        .padding(18)
+        .width(Length::Fill)
+        .style(move |_theme: &Theme| container::Style {
+            background: Some(surface.into()),
+            border: iced::border::Border {
+                color: border_color,
+                width: 1.0,
+                radius: 12.0.into(),
+            },
+            ..container::Style::default()
+        })
+        .into()
+}
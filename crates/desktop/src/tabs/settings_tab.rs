@@ -1,14 +1,49 @@
-use iced::widget::{button, checkbox, column, container, row, slider, text, text_input, Space};
+use iced::widget::{
+    button, checkbox, column, container, pick_list, row, slider, text, text_input, Space,
+};
 use iced::{Color, Element, Length, Theme};
 
-use crate::app::{scaled, Message};
-use crate::settings::{Appearance, BleepSound, BlurShape, Settings, VoiceDisguise};
+use crate::app::{scaled, Message, ModelUpdateState};
+use crate::checklist::ChecklistPolicy;
+use crate::settings::{
+    Appearance, AudioFallback, BleepSound, BlurShape, DetectorHardware, ImageFormat,
+    KeywordMatching, OverlayPalette, PngCompression, Settings, VideoCodec, VoiceDisguise,
+    MAX_BLUR_STRENGTH,
+};
 use crate::theme::{muted_color, section_color, surface_color, tertiary_color};
 use crate::widgets::secondary_button;
+use crate::workers::detection_cache::MAX_DETECTION_INTERVAL;
+use faceguard_core::audio::domain::speech_language::SpeechLanguage;
+use faceguard_core::audio::domain::spoken_entity::PiiCategory;
+use faceguard_core::detection::infrastructure::cache_key_params::{CacheKeyParam, CacheKeyParams};
+use faceguard_core::pipeline::blur_preset::BlurPreset;
+
+/// Settings whose change clears the face selection: every detection
+/// setting shown here.
+const SELECTION_PARAMS: [CacheKeyParam; 4] = [
+    CacheKeyParam::Confidence,
+    CacheKeyParam::Padding,
+    CacheKeyParam::CenterOffset,
+    CacheKeyParam::SkipFrames,
+];
+
+/// How the settings relate to the face selection of a finished scan, so a
+/// change that discards it is flagged and confirmed first.
+pub struct SelectionGuard {
+    /// Settings the scan's detection cache key was built from.
+    pub key: CacheKeyParams,
+    /// Key settings touched by a held change awaiting confirmation.
+    pub pending: Vec<CacheKeyParam>,
+}
 
 pub fn view<'a>(
     settings: &Settings,
+    guard: Option<SelectionGuard>,
+    detection_interval_effect: String,
     gpu_available: bool,
+    model_updates: &ModelUpdateState,
+    ledger_passphrase: &str,
+    checklist_policy: &ChecklistPolicy,
     restore_defaults_hovered: bool,
 ) -> Element<'a, Message> {
     let fs = settings.font_scale;
@@ -19,6 +54,8 @@ pub fn view<'a>(
     let surface = surface_color(&theme);
     let border = border_light_color(&theme);
     let accent = theme.palette().primary;
+    let warning = theme.palette().danger;
+    let guard = guard.as_ref();
 
     let restore_btn = secondary_button::secondary_button_small(
         move || text("Restore Defaults").size(scaled(14.0, fs)).into(),
@@ -29,8 +66,12 @@ pub fn view<'a>(
     );
 
     column![
+        preset_section(settings, guard, fs, section, tertiary, surface, border, accent, warning),
+        Space::new().height(28),
         blur_section(
             settings,
+            guard,
+            warning,
             fs,
             muted,
             section,
@@ -41,13 +82,47 @@ pub fn view<'a>(
             gpu_available
         ),
         Space::new().height(28),
-        detection_section(settings, fs, muted, section, tertiary, surface, border, accent),
+        detection_section(
+            settings,
+            guard,
+            warning,
+            detection_interval_effect,
+            fs,
+            muted,
+            section,
+            tertiary,
+            surface,
+            border,
+            accent
+        ),
+        Space::new().height(28),
+        models_section(
+            model_updates,
+            fs,
+            section,
+            tertiary,
+            surface,
+            border,
+            accent
+        ),
         Space::new().height(28),
         audio_section(settings, fs, muted, section, tertiary, surface, border, accent),
         Space::new().height(28),
+        ledger_section(
+            settings,
+            ledger_passphrase,
+            checklist_policy,
+            fs,
+            section,
+            tertiary,
+            surface,
+            border
+        ),
+        Space::new().height(28),
         appearance_section(settings, fs, section, tertiary, surface, border, accent),
         Space::new().height(24),
         restore_btn,
+        rescan_note(guard, &SELECTION_PARAMS, fs, warning),
     ]
     .spacing(0)
     .into()
@@ -73,9 +148,70 @@ fn setting_card<'a>(
         .into()
 }
 
+#[allow(clippy::too_many_arguments)]
+fn preset_section<'a>(
+    settings: &Settings,
+    guard: Option<&SelectionGuard>,
+    fs: f32,
+    section: iced::Color,
+    tertiary: iced::Color,
+    surface: iced::Color,
+    border: iced::Color,
+    accent: iced::Color,
+    warning: iced::Color,
+) -> Element<'a, Message> {
+    let current = settings.preset();
+    let preset_pills: Element<'a, Message> = row(BlurPreset::ALL.iter().map(|&variant| {
+        pill_button(
+            variant.label().to_string(),
+            current == Some(variant),
+            Message::PresetChanged(variant),
+            fs,
+            accent,
+            border,
+            tertiary,
+        )
+    }))
+    .spacing(8)
+    .into();
+
+    let description = match current {
+        Some(BlurPreset::Fast) => {
+            "Detects less often with a lighter blur. For quick drafts with few, slow-moving faces."
+        }
+        Some(BlurPreset::Balanced) => "Good protection at a reasonable speed.",
+        Some(BlurPreset::MaximumProtection) => {
+            "Detects every frame, keeps brief faces, and blurs wider and harder. Several times slower."
+        }
+        None => "Custom: some blur or detection settings below differ from every preset.",
+    };
+
+    column![
+        section_label("PRESET", fs, section),
+        Space::new().height(14),
+        setting_card(
+            column![
+                setting_name("Speed and protection", fs),
+                Space::new().height(8),
+                preset_pills,
+                Space::new().height(4),
+                text(description).size(scaled(14.0, fs)).color(tertiary),
+                rescan_note(guard, &SELECTION_PARAMS, fs, warning),
+            ]
+            .spacing(0),
+            surface,
+            border,
+        ),
+    ]
+    .spacing(0)
+    .into()
+}
+
 #[allow(clippy::too_many_arguments)]
 fn blur_section<'a>(
     settings: &Settings,
+    guard: Option<&SelectionGuard>,
+    warning: iced::Color,
     fs: f32,
     _muted: iced::Color,
     section: iced::Color,
@@ -139,7 +275,7 @@ fn blur_section<'a>(
                 .color(tertiary),
             Space::new().height(12),
             slider(
-                51..=401,
+                51..=MAX_BLUR_STRENGTH,
                 settings.blur_strength,
                 Message::BlurStrengthChanged
             )
@@ -151,6 +287,37 @@ fn blur_section<'a>(
         border,
     );
 
+    let feather_label = if settings.blur_feather == 0 {
+        "Hard".to_string()
+    } else {
+        format!("{}%", settings.blur_feather)
+    };
+
+    let feather_card = setting_card(
+        column![
+            row![
+                setting_name("Edge softness", fs),
+                Space::new().width(Length::Fill),
+                value_badge(feather_label, fs, accent),
+            ]
+            .align_y(iced::Alignment::Center),
+            Space::new().height(4),
+            text(match settings.blur_shape {
+                BlurShape::Ellipse => "How gradually the blur fades into the picture at its edge.",
+                BlurShape::Rect => "Only applies to the ellipse shape.",
+            })
+            .size(scaled(14.0, fs))
+            .color(tertiary),
+            Space::new().height(12),
+            slider(0..=50, settings.blur_feather, Message::BlurFeatherChanged)
+                .step(5u32)
+                .style(slider_style),
+        ]
+        .spacing(0),
+        surface,
+        border,
+    );
+
     let coverage_card = setting_card(
         column![
             row![
@@ -163,6 +330,7 @@ fn blur_section<'a>(
             text("How far the blur extends beyond the detected face.")
                 .size(scaled(14.0, fs))
                 .color(tertiary),
+            rescan_note(guard, &[CacheKeyParam::Padding], fs, warning),
             Space::new().height(12),
             slider(
                 0..=100,
@@ -195,6 +363,7 @@ fn blur_section<'a>(
             text("Shift the blur toward the back of the head for profile faces.")
                 .size(scaled(14.0, fs))
                 .color(tertiary),
+            rescan_note(guard, &[CacheKeyParam::CenterOffset], fs, warning),
             Space::new().height(12),
             slider(
                 -50..=50i32,
@@ -209,6 +378,20 @@ fn blur_section<'a>(
         border,
     );
 
+    let codec_pills: Element<'a, Message> = row(VideoCodec::ALL.iter().map(|&variant| {
+        pill_button(
+            variant.to_string(),
+            variant == settings.video_codec,
+            Message::VideoCodecChanged(variant),
+            fs,
+            accent,
+            border,
+            tertiary,
+        )
+    }))
+    .spacing(8)
+    .into();
+
     let quality_card = setting_card(
         column![
             row![
@@ -225,6 +408,84 @@ fn blur_section<'a>(
             slider(0..=100, settings.quality, Message::QualityChanged).style(slider_style),
             Space::new().height(8),
             text(backend_label).size(scaled(12.0, fs)).color(tertiary),
+            Space::new().height(14),
+            setting_name("Video codec", fs),
+            Space::new().height(8),
+            codec_pills,
+            Space::new().height(4),
+            text(match settings.video_codec {
+                VideoCodec::H264 => "H.264 plays everywhere.",
+                VideoCodec::Hevc => "HEVC makes smaller files; older players may not open them.",
+                VideoCodec::Vp9 => "VP9 suits the web; saved as .webm, .mkv, or .mp4.",
+                VideoCodec::Av1 => "AV1 makes the smallest files but encodes slowest.",
+            })
+            .size(scaled(14.0, fs))
+            .color(tertiary),
+        ]
+        .spacing(0),
+        surface,
+        border,
+    );
+
+    let format_pills: Element<'a, Message> = row(ImageFormat::ALL.iter().map(|&variant| {
+        pill_button(
+            variant.to_string(),
+            variant == settings.image_format,
+            Message::ImageFormatChanged(variant),
+            fs,
+            accent,
+            border,
+            tertiary,
+        )
+    }))
+    .spacing(8)
+    .into();
+
+    let compression_pills: Element<'a, Message> = row(PngCompression::ALL.iter().map(|&variant| {
+        pill_button(
+            variant.to_string(),
+            variant == settings.png_compression,
+            Message::PngCompressionChanged(variant),
+            fs,
+            accent,
+            border,
+            tertiary,
+        )
+    }))
+    .spacing(8)
+    .into();
+
+    let image_card = setting_card(
+        column![
+            setting_name("Image output", fs),
+            Space::new().height(8),
+            format_pills,
+            Space::new().height(4),
+            text("Original keeps the input's format.")
+                .size(scaled(14.0, fs))
+                .color(tertiary),
+            Space::new().height(14),
+            row![
+                setting_name("JPEG quality", fs),
+                Space::new().width(Length::Fill),
+                value_badge(format!("{}%", settings.image_quality), fs, accent),
+            ]
+            .align_y(iced::Alignment::Center),
+            Space::new().height(12),
+            slider(
+                1..=100,
+                settings.image_quality,
+                Message::ImageQualityChanged
+            )
+            .style(slider_style),
+            Space::new().height(14),
+            setting_name("PNG compression", fs),
+            Space::new().height(8),
+            compression_pills,
+            Space::new().height(4),
+            text("PNG is lossless; smaller files take longer to save.")
+                .size(scaled(14.0, fs))
+                .color(tertiary),
         ]
         .spacing(0),
         surface,
@@ -238,11 +499,15 @@ fn blur_section<'a>(
         Space::new().height(10),
         intensity_card,
         Space::new().height(10),
+        feather_card,
+        Space::new().height(10),
         coverage_card,
         Space::new().height(10),
         center_offset_card,
         Space::new().height(10),
         quality_card,
+        Space::new().height(10),
+        image_card,
     ]
     .spacing(0)
     .into()
@@ -251,6 +516,9 @@ fn blur_section<'a>(
 #[allow(clippy::too_many_arguments)]
 fn detection_section<'a>(
     settings: &Settings,
+    guard: Option<&SelectionGuard>,
+    warning: iced::Color,
+    detection_interval_effect: String,
     fs: f32,
     _muted: iced::Color,
     section: iced::Color,
@@ -273,6 +541,7 @@ fn detection_section<'a>(
             text("How certain the detector must be that something is a face. Lower values catch more faces but may have false positives.")
                 .size(scaled(14.0, fs))
                 .color(tertiary),
+            rescan_note(guard, &[CacheKeyParam::Confidence], fs, warning),
             Space::new().height(12),
             slider(10..=100, settings.confidence, Message::ConfidenceChanged).style(slider_style),
         ]
@@ -302,12 +571,218 @@ fn detection_section<'a>(
         border,
     );
 
+    let frequency_card = setting_card(
+        column![
+            row![
+                setting_name("Detection frequency", fs),
+                Space::new().width(Length::Fill),
+                value_badge(detection_frequency_label(settings.detection_interval), fs, accent),
+            ]
+            .align_y(iced::Alignment::Center),
+            Space::new().height(4),
+            text("How often the detector looks for faces; in between, faces are followed from their last movement. Less often scans and blurs faster, but the blur can lag behind quick movement and a face entering the shot is found later.")
+                .size(scaled(14.0, fs))
+                .color(tertiary),
+            Space::new().height(4),
+            text(detection_interval_effect)
+                .size(scaled(14.0, fs))
+                .color(tertiary),
+            rescan_note(guard, &[CacheKeyParam::SkipFrames], fs, warning),
+            Space::new().height(12),
+            slider(
+                1..=MAX_DETECTION_INTERVAL,
+                settings.detection_interval,
+                Message::DetectionIntervalChanged
+            )
+            .style(slider_style),
+        ]
+        .spacing(0),
+        surface,
+        border,
+    );
+
+    let hardware_pills: Element<'a, Message> = row(DetectorHardware::ALL.iter().map(|&variant| {
+        pill_button(
+            variant.to_string(),
+            variant == settings.detector_hardware,
+            Message::DetectorHardwareChanged(variant),
+            fs,
+            accent,
+            border,
+            tertiary,
+        )
+    }))
+    .spacing(8)
+    .into();
+
+    let hardware_card = setting_card(
+        column![
+            setting_name("Detection hardware", fs),
+            Space::new().height(8),
+            hardware_pills,
+            Space::new().height(4),
+            text("What runs the face detector. Automatic uses the GPU or Neural Engine where the platform has a standard way to; CUDA needs an NVIDIA GPU and its runtime. Anything unavailable falls back to the CPU.")
+                .size(scaled(14.0, fs))
+                .color(tertiary),
+        ]
+        .spacing(0),
+        surface,
+        border,
+    );
+
+    let thumbnails_card = setting_card(
+        column![
+            checkbox(settings.anonymize_thumbnails)
+                .label("Blur stored face thumbnails")
+                .on_toggle(Message::AnonymizeThumbnailsChanged)
+                .text_size(scaled(15.0, fs)),
+            Space::new().height(4),
+            text("Thumbnails saved to the temporary folder are blurred. Faces are shown sharp from memory only.")
+                .size(scaled(14.0, fs))
+                .color(tertiary),
+        ]
+        .spacing(0),
+        surface,
+        border,
+    );
+
+    let brief_card = setting_card(
+        column![
+            checkbox(settings.skip_brief_detections)
+                .label("Ignore brief detections")
+                .on_toggle(Message::SkipBriefDetectionsChanged)
+                .text_size(scaled(15.0, fs)),
+            Space::new().height(4),
+            text("Faces seen for only a few frames are usually posters or patterns and are neither listed nor blurred. Turn off to blur every detection.")
+                .size(scaled(14.0, fs))
+                .color(tertiary),
+        ]
+        .spacing(0),
+        surface,
+        border,
+    );
+
+    let cache_card = setting_card(
+        column![
+            checkbox(settings.cache_detections)
+                .label("Remember detections")
+                .on_toggle(Message::CacheDetectionsChanged)
+                .text_size(scaled(15.0, fs)),
+            Space::new().height(4),
+            text("Exporting the same video again with the same detection settings skips scanning. Face positions only, up to 256 MB; the oldest are removed first.")
+                .size(scaled(14.0, fs))
+                .color(tertiary),
+        ]
+        .spacing(0),
+        surface,
+        border,
+    );
+
     column![
         section_label("DETECTION", fs, section),
         Space::new().height(14),
         sensitivity_card,
         Space::new().height(10),
         lookahead_card,
+        Space::new().height(10),
+        frequency_card,
+        Space::new().height(10),
+        hardware_card,
+        Space::new().height(10),
+        brief_card,
+        Space::new().height(10),
+        thumbnails_card,
+        Space::new().height(10),
+        cache_card,
+    ]
+    .spacing(0)
+    .into()
+}
+
+fn models_section<'a>(
+    state: &ModelUpdateState,
+    fs: f32,
+    section: iced::Color,
+    tertiary: iced::Color,
+    surface: iced::Color,
+    border: iced::Color,
+    accent: iced::Color,
+) -> Element<'a, Message> {
+    let note = |content: String| text(content).size(scaled(14.0, fs)).color(tertiary);
+    let action = |label: &str, message: Message| {
+        pill_button(
+            label.to_string(),
+            false,
+            message,
+            fs,
+            accent,
+            border,
+            tertiary,
+        )
+    };
+
+    let mut body = column![
+        setting_name("Detection models", fs),
+        Space::new().height(4),
+        note(
+            "FaceGuard never checks for model updates on its own. Checking reads the list of \
+             model releases from GitHub; nothing about you or your files is sent."
+                .into()
+        ),
+        Space::new().height(12),
+    ]
+    .spacing(0);
+    body = match state {
+        ModelUpdateState::Idle => body.push(action(
+            "Check for model updates",
+            Message::CheckModelUpdates,
+        )),
+        ModelUpdateState::Checking => body.push(note("Checking for model updates\u{2026}".into())),
+        ModelUpdateState::Available(updates) if updates.is_empty() => body
+            .push(note("All models are up to date.".into()))
+            .push(Space::new().height(8))
+            .push(action("Check again", Message::CheckModelUpdates)),
+        ModelUpdateState::Available(updates) => {
+            for update in updates {
+                body = body
+                    .push(text(update.summary()).size(scaled(14.0, fs)))
+                    .push(note(update.release.changelog.clone()));
+                if update.needs_app_update {
+                    let required = update.release.min_app_version.clone().unwrap_or_default();
+                    body = body.push(note(format!(
+                        "Needs FaceGuard {required} or newer; update the app to install it."
+                    )));
+                }
+                body = body.push(Space::new().height(10));
+            }
+            if updates.iter().any(|u| !u.needs_app_update) {
+                body.push(action("Download and install", Message::InstallModelUpdates))
+            } else {
+                body
+            }
+        }
+        ModelUpdateState::Installing(updates, index, downloaded, total) => {
+            let name = updates.get(*index).map_or("", |u| u.release.name.as_str());
+            let progress = if *total > 0 {
+                format!("{}%", downloaded * 100 / total)
+            } else {
+                format!("{:.1} MB", *downloaded as f64 / 1_000_000.0)
+            };
+            body.push(note(format!("Downloading {name}\u{2026} {progress}")))
+        }
+        ModelUpdateState::Installed => body.push(note(
+            "Models updated. Restart FaceGuard to detect faces with them.".into(),
+        )),
+        ModelUpdateState::Failed(error) => body
+            .push(note(error.clone()))
+            .push(Space::new().height(8))
+            .push(action("Try again", Message::CheckModelUpdates)),
+    };
+
+    column![
+        section_label("MODELS", fs, section),
+        Space::new().height(14),
+        setting_card(body, surface, border),
     ]
     .spacing(0)
     .into()
@@ -341,6 +816,34 @@ fn audio_section<'a>(
         border,
     );
 
+    let pii_checkboxes: Element<'a, Message> = row(PiiCategory::ALL.iter().map(|&category| {
+        checkbox(settings.bleeps_pii(category))
+            .label(match category {
+                PiiCategory::Name => "Names",
+                PiiCategory::Number => "Numbers",
+                PiiCategory::Location => "Places",
+            })
+            .on_toggle(move |enabled| Message::BleepPiiChanged(category, enabled))
+            .text_size(scaled(14.0, fs))
+            .into()
+    }))
+    .spacing(16)
+    .into();
+
+    let matching_pills: Element<'a, Message> = row(KeywordMatching::ALL.iter().map(|&variant| {
+        pill_button(
+            variant.to_string(),
+            variant == settings.keyword_matching,
+            Message::KeywordMatchingChanged(variant),
+            fs,
+            accent,
+            border,
+            tertiary,
+        )
+    }))
+    .spacing(8)
+    .into();
+
     // Keywords input
     let keywords_card = setting_card(
         column![
@@ -353,6 +856,35 @@ fn audio_section<'a>(
             text_input("e.g. name, address, phone", &settings.bleep_keywords)
                 .on_input(Message::BleepKeywordsChanged)
                 .size(scaled(14.0, fs)),
+            Space::new().height(14),
+            setting_name("Match keywords", fs),
+            Space::new().height(4),
+            text("Names are often transcribed with another spelling. Match words that sound like a keyword too: close spellings only, any spelling of the same sound, or loosely, which also catches unrelated words.")
+                .size(scaled(14.0, fs))
+                .color(tertiary),
+            Space::new().height(8),
+            matching_pills,
+            Space::new().height(14),
+            setting_name("Also bleep", fs),
+            Space::new().height(4),
+            text("Spoken names, phone and account numbers, and addresses, found in the transcript. Review the words before they are bleeped.")
+                .size(scaled(14.0, fs))
+                .color(tertiary),
+            Space::new().height(8),
+            pii_checkboxes,
+            Space::new().height(14),
+            setting_name("Spoken language", fs),
+            Space::new().height(4),
+            text("Keywords only match speech in the language they are written in. Choose the language if detection picks the wrong one.")
+                .size(scaled(14.0, fs))
+                .color(tertiary),
+            Space::new().height(8),
+            pick_list(
+                SpeechLanguage::all(),
+                Some(settings.speech_language()),
+                Message::SpeechLanguageChanged,
+            )
+            .text_size(scaled(14.0, fs)),
         ]
         .spacing(0),
         surface,
@@ -424,6 +956,42 @@ fn audio_section<'a>(
         border,
     );
 
+    // Audio failure fallback pills
+    let fallback_pills: Element<'a, Message> = row(AudioFallback::ALL.iter().map(|&variant| {
+        pill_button(
+            variant.to_string(),
+            variant == settings.audio_fallback,
+            Message::AudioFallbackChanged(variant),
+            fs,
+            accent,
+            border,
+            tertiary,
+        )
+    }))
+    .spacing(8)
+    .into();
+
+    let fallback_card = setting_card(
+        column![
+            setting_name("If audio processing fails", fs),
+            Space::new().height(8),
+            fallback_pills,
+            Space::new().height(4),
+            text(match settings.audio_fallback {
+                AudioFallback::Fail => "The export fails and the video is left without audio.",
+                AudioFallback::Original => {
+                    "Keeps the export with the original audio: voices are not disguised and nothing is bleeped."
+                }
+                AudioFallback::Silent => "Keeps the export with a silent audio track.",
+            })
+            .size(scaled(14.0, fs))
+            .color(tertiary),
+        ]
+        .spacing(0),
+        surface,
+        border,
+    );
+
     column![
         section_label("AUDIO", fs, section),
         Space::new().height(14),
@@ -434,6 +1002,109 @@ fn audio_section<'a>(
         bleep_card,
         Space::new().height(10),
         voice_card,
+        Space::new().height(10),
+        fallback_card,
+    ]
+    .spacing(0)
+    .into()
+}
+
+fn ledger_section<'a>(
+    settings: &Settings,
+    passphrase: &str,
+    checklist_policy: &ChecklistPolicy,
+    fs: f32,
+    section: iced::Color,
+    tertiary: iced::Color,
+    surface: iced::Color,
+    border: iced::Color,
+) -> Element<'a, Message> {
+    let checklist_toggle = checkbox(checklist_policy.shows(settings.post_export_checklist))
+        .label("Show a checklist after each export")
+        .text_size(scaled(15.0, fs));
+    let checklist_card = setting_card(
+        column![
+            if checklist_policy.is_enforced() {
+                checklist_toggle
+            } else {
+                checklist_toggle.on_toggle(Message::PostExportChecklistChanged)
+            },
+            Space::new().height(4),
+            text(if checklist_policy.is_enforced() {
+                "Set by your organization's checklist policy."
+            } else {
+                "Asks whether the output was watched, the audio checked for names, and reflections looked at, before you share it."
+            })
+            .size(scaled(14.0, fs))
+            .color(tertiary),
+        ]
+        .spacing(0),
+        surface,
+        border,
+    );
+
+    let ledger_card = setting_card(
+        column![
+            checkbox(settings.write_ledger)
+                .label("Write an anonymization ledger")
+                .on_toggle(Message::WriteLedgerChanged)
+                .text_size(scaled(15.0, fs)),
+            Space::new().height(4),
+            text("Saves <output>.ledger.json after each video export: every face track, its label, whether it was blurred, and when it appears, so decisions can be reviewed without the footage. Needs a face scan.")
+                .size(scaled(14.0, fs))
+                .color(tertiary),
+            Space::new().height(14),
+            setting_name("Operator", fs),
+            Space::new().height(8),
+            text_input("Name recorded in the ledger", &settings.ledger_operator)
+                .on_input(Message::LedgerOperatorChanged)
+                .size(scaled(14.0, fs)),
+            Space::new().height(14),
+            setting_name("Signing passphrase", fs),
+            Space::new().height(8),
+            text_input("Leave empty for an unsigned ledger", passphrase)
+                .on_input(Message::LedgerPassphraseChanged)
+                .secure(true)
+                .size(scaled(14.0, fs)),
+            Space::new().height(4),
+            text("With a passphrase, any later edit to the ledger is detected by `faceguard verify-ledger`. It is not saved; enter it again after a restart.")
+                .size(scaled(14.0, fs))
+                .color(tertiary),
+        ]
+        .spacing(0),
+        surface,
+        border,
+    );
+
+    let notify_card = setting_card(
+        column![
+            checkbox(settings.finish_sound)
+                .label("Play a sound when a scan or export ends")
+                .on_toggle(Message::FinishSoundChanged)
+                .text_size(scaled(15.0, fs)),
+            Space::new().height(10),
+            checkbox(settings.finish_attention)
+                .label("Flash the app in the taskbar or dock when it ends in the background")
+                .on_toggle(Message::FinishAttentionChanged)
+                .text_size(scaled(15.0, fs)),
+            Space::new().height(4),
+            text("Uses the system's alert sounds, so they follow its volume and mute. Cancelling is silent.")
+                .size(scaled(14.0, fs))
+                .color(tertiary),
+        ]
+        .spacing(0),
+        surface,
+        border,
+    );
+
+    column![
+        section_label("AFTER EXPORT", fs, section),
+        Space::new().height(14),
+        notify_card,
+        Space::new().height(10),
+        checklist_card,
+        Space::new().height(10),
+        ledger_card,
     ]
     .spacing(0)
     .into()
@@ -464,6 +1135,20 @@ fn appearance_section<'a>(
     .spacing(8)
     .into();
 
+    let overlay_pills: Element<'a, Message> = row(OverlayPalette::ALL.iter().map(|&variant| {
+        pill_button(
+            variant.to_string(),
+            variant == settings.overlay_palette,
+            Message::OverlayPaletteChanged(variant),
+            fs,
+            accent,
+            border,
+            tertiary,
+        )
+    }))
+    .spacing(8)
+    .into();
+
     let theme_card = setting_card(
         column![
             setting_name("Theme", fs),
@@ -474,6 +1159,14 @@ fn appearance_section<'a>(
                 .label("High contrast")
                 .on_toggle(Message::HighContrastChanged)
                 .text_size(scaled(14.0, fs)),
+            Space::new().height(14),
+            setting_name("Blur region outlines", fs),
+            Space::new().height(8),
+            overlay_pills,
+            Space::new().height(4),
+            text("Colors for \"Show blur regions\" on the sample frame. Each face also gets its own line pattern; high contrast draws thicker lines with a dark edge.")
+                .size(scaled(14.0, fs))
+                .color(tertiary),
         ]
         .spacing(0),
         surface,
@@ -528,6 +1221,15 @@ fn quality_label(quality: u32) -> String {
     format!("{qual} ({quality}%)")
 }
 
+fn detection_frequency_label(interval: u32) -> String {
+    match interval {
+        0 | 1 => "Every frame".into(),
+        2 => "Every 2nd frame".into(),
+        3 => "Every 3rd frame".into(),
+        n => format!("Every {n}th frame"),
+    }
+}
+
 fn sensitivity_label(confidence: u32) -> String {
     let qual = match confidence {
         10..=35 => "Low",
@@ -537,6 +1239,42 @@ fn sensitivity_label(confidence: u32) -> String {
     format!("{qual} ({confidence}%)")
 }
 
+/// Under a setting the face selection depends on: a warning while a
+/// selection exists, and the confirm buttons while a change to the setting
+/// is held.
+fn rescan_note<'a>(
+    guard: Option<&SelectionGuard>,
+    params: &[CacheKeyParam],
+    fs: f32,
+    warning: Color,
+) -> Element<'a, Message> {
+    let Some(guard) = guard.filter(|g| params.iter().any(|&p| g.key.contains(p))) else {
+        return Space::new().height(0).into();
+    };
+    let mut note = column![
+        Space::new().height(6),
+        text("Changing this requires re-scanning and clears your face selection.")
+            .size(scaled(13.0, fs))
+            .color(warning),
+    ];
+    if params.iter().any(|p| guard.pending.contains(p)) {
+        note = note.push(Space::new().height(8)).push(
+            row![
+                button(text("Re-scan and Clear Selection").size(scaled(13.0, fs)))
+                    .on_press(Message::ConfirmRescan)
+                    .padding([6, 12])
+                    .style(button::danger),
+                button(text("Keep Selection").size(scaled(13.0, fs)))
+                    .on_press(Message::KeepSelection)
+                    .padding([6, 12])
+                    .style(button::text),
+            ]
+            .spacing(8),
+        );
+    }
+    note.spacing(0).into()
+}
+
 fn section_label<'a>(label: &str, fs: f32, color: Color) -> iced::widget::Text<'a> {
     text(label.to_string())
         .size(scaled(12.0, fs))
@@ -1,8 +1,11 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
 use iced::color;
 use iced::theme::Palette;
 use iced::{Color, Theme};
 
-use crate::settings::Appearance;
+use crate::settings::{Appearance, OverlayPalette};
 
 /// Resolve the iced Theme from appearance + high_contrast settings.
 pub fn resolve_theme(appearance: Appearance, high_contrast: bool) -> Theme {
@@ -22,6 +25,28 @@ pub fn resolve_theme(appearance: Appearance, high_contrast: bool) -> Theme {
     Theme::custom("FaceGuard", palette)
 }
 
+/// Set from the OS "reduce motion" preference. Global rather than threaded
+/// through view arguments because animations are built deep inside widgets.
+static REDUCED_MOTION: AtomicBool = AtomicBool::new(false);
+
+pub fn set_reduced_motion(reduced: bool) {
+    REDUCED_MOTION.store(reduced, Ordering::Relaxed);
+}
+
+pub fn reduced_motion() -> bool {
+    REDUCED_MOTION.load(Ordering::Relaxed)
+}
+
+/// Return the duration to animate over, or an effectively instant one when
+/// reduced motion is on (a zero duration would divide by zero in the easing).
+pub fn animation_duration(base: Duration) -> Duration {
+    if reduced_motion() {
+        Duration::from_millis(1)
+    } else {
+        base
+    }
+}
+
 /// Return a muted text color (secondary) appropriate for the current theme.
 pub fn muted_color(theme: &Theme) -> Color {
     let p = theme.palette();
@@ -56,6 +81,85 @@ pub fn section_color(theme: &Theme) -> Color {
     Color { a: 0.40, ..p.text }
 }
 
+/// How a region outline is stroked. Patterns cycle with the colors, so
+/// tracks stay apart even when two colors look alike to the viewer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlayPattern {
+    Solid,
+    Dashed,
+    Dotted,
+    /// Only the corners of the box.
+    Corners,
+}
+
+const OVERLAY_PATTERNS: [OverlayPattern; 4] = [
+    OverlayPattern::Solid,
+    OverlayPattern::Dashed,
+    OverlayPattern::Dotted,
+    OverlayPattern::Corners,
+];
+
+/// Outline of one blur region on the sample frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverlayStroke {
+    pub color: [u8; 3],
+    pub pattern: OverlayPattern,
+    /// Line width in pixels.
+    pub width: u32,
+    /// Surround the line with black, so it stands out on any footage.
+    pub halo: bool,
+}
+
+/// Saturated hues matching the UI accents.
+const STANDARD_OVERLAY: [[u8; 3]; 6] = [
+    [0x5b, 0x86, 0xf0],
+    [0x4a, 0xde, 0x80],
+    [0xfb, 0xbf, 0x24],
+    [0xf8, 0x71, 0x71],
+    [0xc0, 0x84, 0xfc],
+    [0x22, 0xd3, 0xee],
+];
+
+/// Okabe-Ito palette, without its black: distinguishable with protanopia,
+/// deuteranopia, and tritanopia.
+const COLOR_BLIND_OVERLAY: [[u8; 3]; 7] = [
+    [0xe6, 0x9f, 0x00],
+    [0x56, 0xb4, 0xe9],
+    [0x00, 0x9e, 0x73],
+    [0xf0, 0xe4, 0x42],
+    [0x00, 0x72, 0xb2],
+    [0xd5, 0x5e, 0x00],
+    [0xcc, 0x79, 0xa7],
+];
+
+/// Stroke for a region of `track_id`. Colors and patterns cycle at
+/// different lengths, so neighbouring track IDs differ in both. High
+/// contrast mode thickens the line and adds a halo. Untracked regions are
+/// dotted white.
+pub fn overlay_stroke(
+    palette: OverlayPalette,
+    high_contrast: bool,
+    track_id: Option<u32>,
+) -> OverlayStroke {
+    let colors: &[[u8; 3]] = match palette {
+        OverlayPalette::Standard => &STANDARD_OVERLAY,
+        OverlayPalette::ColorBlind => &COLOR_BLIND_OVERLAY,
+    };
+    let (color, pattern) = match track_id {
+        Some(id) => (
+            colors[id as usize % colors.len()],
+            OVERLAY_PATTERNS[id as usize % OVERLAY_PATTERNS.len()],
+        ),
+        None => ([0xff, 0xff, 0xff], OverlayPattern::Dotted),
+    };
+    OverlayStroke {
+        color,
+        pattern,
+        width: if high_contrast { 3 } else { 2 },
+        halo: high_contrast,
+    }
+}
+
 fn dark_palette() -> Palette {
     Palette {
         background: color!(0x1c, 0x1a, 0x17),
@@ -0,0 +1,86 @@
+use iced::widget::{button, column, container, row, text, text_input};
+use iced::{Element, Length, Theme};
+
+use crate::app::{scaled, Message};
+use crate::shortcuts::Command;
+use crate::theme::{muted_color, surface_color};
+
+pub const INPUT_ID: &str = "command-palette-input";
+
+/// Commands whose label matches `query`, in palette order.
+pub fn matching(query: &str) -> Vec<Command> {
+    Command::PALETTE
+        .iter()
+        .copied()
+        .filter(|command| command.matches(query))
+        .collect()
+}
+
+pub fn view<'a>(fs: f32, query: &str, theme: &Theme) -> Element<'a, Message> {
+    let muted = muted_color(theme);
+    let surface = surface_color(theme);
+    let border = iced::Color {
+        a: 0.12,
+        ..theme.palette().text
+    };
+
+    let mut list = column![row![
+        text_input("Type a command", query)
+            .id(iced::widget::Id::new(INPUT_ID))
+            .on_input(Message::CommandPaletteQueryChanged)
+            .on_submit(Message::CommandPaletteSubmit)
+            .size(scaled(14.0, fs))
+            .padding([6, 10]),
+        button(text("Close").size(scaled(13.0, fs)))
+            .on_press(Message::CloseCommandPalette)
+            .padding([4, 10])
+            .style(button::text),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center)]
+    .spacing(2);
+
+    let commands = matching(query);
+    if commands.is_empty() {
+        list = list.push(
+            container(
+                text("No matching commands")
+                    .size(scaled(13.0, fs))
+                    .color(muted),
+            )
+            .padding([4, 10]),
+        );
+    }
+    for command in commands {
+        let hint = command.shortcut().unwrap_or_default();
+        list = list.push(
+            button(
+                row![
+                    text(command.label())
+                        .size(scaled(13.0, fs))
+                        .width(Length::Fill),
+                    text(hint).size(scaled(12.0, fs)).color(muted),
+                ]
+                .align_y(iced::Alignment::Center),
+            )
+            .on_press(Message::RunCommand(command))
+            .padding([4, 10])
+            .width(Length::Fill)
+            .style(button::text),
+        );
+    }
+
+    container(list)
+        .padding([12, 16])
+        .width(Length::Fill)
+        .style(move |_theme: &Theme| container::Style {
+            background: Some(surface.into()),
+            border: iced::border::Border {
+                color: border,
+                width: 1.0,
+                radius: 10.0.into(),
+            },
+            ..container::Style::default()
+        })
+        .into()
+}
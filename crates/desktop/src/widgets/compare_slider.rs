@@ -0,0 +1,237 @@
+use iced::widget::{checkbox, column, image, mouse_area, row, stack, text, Space};
+use iced::{Element, Length, Theme};
+
+use faceguard_core::shared::region::Region;
+
+use crate::app::{scaled, Message};
+use crate::settings::OverlayPalette;
+use crate::theme::{overlay_stroke, tertiary_color, OverlayPattern, OverlayStroke};
+use crate::widgets::detection_overlay::{self, OverlayBox};
+
+const DIVIDER_WIDTH: usize = 2;
+
+/// Dash and gap length of dashed outlines, in pixels.
+const DASH: u32 = 8;
+/// Dot and gap length of dotted outlines, in pixels.
+const DOT: u32 = 3;
+
+/// Before/after view of a sample frame: blurred left of the divider,
+/// original right of it. Kept in memory only, like the sharp thumbnails.
+pub struct CompareState {
+    original: ::image::RgbaImage,
+    blurred: ::image::RgbaImage,
+    /// Blurred regions in display pixels.
+    regions: Vec<Region>,
+    /// Palette and high contrast flag when regions are outlined.
+    overlay: Option<(OverlayPalette, bool)>,
+    /// Faces the scan found on the frame, in display pixels.
+    detections: Vec<OverlayBox>,
+    show_detections: bool,
+    /// Divider position as a fraction of the width.
+    split: f32,
+    /// Last pointer position over the frame, as a fraction of the width.
+    pointer: f32,
+    dragging: bool,
+    handle: image::Handle,
+}
+
+impl CompareState {
+    pub fn new(
+        original: ::image::RgbaImage,
+        blurred: ::image::RgbaImage,
+        regions: Vec<Region>,
+        detections: Vec<OverlayBox>,
+    ) -> Self {
+        let mut state = Self {
+            original,
+            blurred,
+            regions,
+            overlay: None,
+            detections,
+            show_detections: false,
+            split: 0.5,
+            pointer: 0.5,
+            dragging: false,
+            handle: image::Handle::from_rgba(1, 1, vec![0; 4]),
+        };
+        state.composite();
+        state
+    }
+
+    /// Swaps in a re-rendered frame, keeping the divider where it was.
+    pub fn replace(
+        &mut self,
+        original: ::image::RgbaImage,
+        blurred: ::image::RgbaImage,
+        regions: Vec<Region>,
+        detections: Vec<OverlayBox>,
+    ) {
+        self.original = original;
+        self.blurred = blurred;
+        self.regions = regions;
+        self.detections = detections;
+        self.composite();
+    }
+
+    /// Outlines the blurred regions with `palette`, or hides them.
+    pub fn set_overlay(&mut self, overlay: Option<(OverlayPalette, bool)>) {
+        if self.overlay != overlay {
+            self.overlay = overlay;
+            self.composite();
+        }
+    }
+
+    pub fn shows_regions(&self) -> bool {
+        self.overlay.is_some()
+    }
+
+    /// Labels every face found on the frame with its track ID, or hides
+    /// the labels.
+    pub fn set_show_detections(&mut self, show: bool) {
+        self.show_detections = show;
+    }
+
+    /// Starts dragging, jumping the divider to where the button went down.
+    pub fn start_drag(&mut self) {
+        self.dragging = true;
+        self.split = self.pointer;
+        self.composite();
+    }
+
+    pub fn end_drag(&mut self) {
+        self.dragging = false;
+    }
+
+    /// Records the pointer at `fraction` of the width, moving the divider
+    /// there while dragging.
+    pub fn pointer_moved(&mut self, fraction: f32) {
+        self.pointer = fraction.clamp(0.0, 1.0);
+        if self.dragging {
+            self.split = self.pointer;
+            self.composite();
+        }
+    }
+
+    fn composite(&mut self) {
+        let (w, h) = self.blurred.dimensions();
+        let row = w as usize * 4;
+        let split_x = ((self.split * w as f32).round() as usize).min(w as usize);
+        let divider_start = split_x.saturating_sub(DIVIDER_WIDTH / 2);
+        let divider_end = (divider_start + DIVIDER_WIDTH).min(w as usize);
+        let original = self.original.as_raw();
+        let mut out = self.blurred.as_raw().clone();
+        for y in 0..h as usize {
+            let line = y * row;
+            out[line + split_x * 4..line + row]
+                .copy_from_slice(&original[line + split_x * 4..line + row]);
+            out[line + divider_start * 4..line + divider_end * 4].fill(255);
+        }
+        if let Some((palette, high_contrast)) = self.overlay {
+            for region in &self.regions {
+                let stroke = overlay_stroke(palette, high_contrast, region.track_id);
+                outline(&mut out, w, h, region, &stroke);
+            }
+        }
+        self.handle = image::Handle::from_rgba(w, h, out);
+    }
+}
+
+/// Strokes the border of `region` into an RGBA buffer of `w`×`h`, on both
+/// sides of the divider.
+fn outline(out: &mut [u8], w: u32, h: u32, region: &Region, stroke: &OverlayStroke) {
+    if stroke.halo {
+        let halo = OverlayStroke {
+            color: [0, 0, 0],
+            pattern: stroke.pattern,
+            width: stroke.width + 2,
+            halo: false,
+        };
+        let grown = Region {
+            x: region.x - 1,
+            y: region.y - 1,
+            width: region.width + 2,
+            height: region.height + 2,
+            ..region.clone()
+        };
+        outline(out, w, h, &grown, &halo);
+    }
+    let (x0, y0) = (region.x, region.y);
+    let (x1, y1) = (region.x + region.width - 1, region.y + region.height - 1);
+    if x1 < x0 || y1 < y0 {
+        return;
+    }
+    let corner = (region.width.min(region.height) / 4).max(4) as u32;
+    let mut plot = |x: i32, y: i32, along: u32, edge_len: u32| {
+        let on = match stroke.pattern {
+            OverlayPattern::Solid => true,
+            OverlayPattern::Dashed => (along / DASH) % 2 == 0,
+            OverlayPattern::Dotted => (along / DOT) % 2 == 0,
+            OverlayPattern::Corners => along < corner || along + corner >= edge_len,
+        };
+        if !on || x < 0 || y < 0 || x >= w as i32 || y >= h as i32 {
+            return;
+        }
+        let i = (y as usize * w as usize + x as usize) * 4;
+        out[i..i + 3].copy_from_slice(&stroke.color);
+        out[i + 3] = 255;
+    };
+    let (width, height) = (region.width as u32, region.height as u32);
+    for t in 0..stroke.width as i32 {
+        for x in x0..=x1 {
+            let along = (x - x0) as u32;
+            plot(x, y0 + t, along, width);
+            plot(x, y1 - t, along, width);
+        }
+        for y in y0..=y1 {
+            let along = (y - y0) as u32;
+            plot(x0 + t, y, along, height);
+            plot(x1 - t, y, along, height);
+        }
+    }
+}
+
+/// The sample frame at `width`, with the divider following the mouse while
+/// the button is held.
+pub fn view<'a>(state: &CompareState, fs: f32, theme: &Theme, width: f32) -> Element<'a, Message> {
+    let (w, h) = state.blurred.dimensions();
+    let height = width * h as f32 / w.max(1) as f32;
+    let frame = image(state.handle.clone())
+        .width(Length::Fixed(width))
+        .height(Length::Fixed(height));
+    let frame: Element<'a, Message> = if state.show_detections {
+        stack![
+            frame,
+            detection_overlay::view(&state.detections, w, width, height, fs, theme)
+        ]
+        .into()
+    } else {
+        frame.into()
+    };
+
+    column![
+        mouse_area(frame)
+            .on_press(Message::CompareDragStarted)
+            .on_release(Message::CompareDragEnded)
+            .on_exit(Message::CompareDragEnded)
+            .on_move(move |p| Message::ComparePointerMoved(p.x / width))
+            .interaction(iced::mouse::Interaction::ResizingHorizontally),
+        row![
+            text("Blurred on the left, original on the right. Drag to compare.")
+                .size(scaled(13.0, fs))
+                .color(tertiary_color(theme)),
+            Space::new().width(Length::Fill),
+            checkbox(state.show_detections)
+                .label("Show track IDs")
+                .on_toggle(Message::ShowDetectionsToggled)
+                .text_size(scaled(13.0, fs)),
+            checkbox(state.shows_regions())
+                .label("Show blur regions")
+                .on_toggle(Message::ShowRegionsToggled)
+                .text_size(scaled(13.0, fs)),
+        ]
+        .spacing(12)
+        .align_y(iced::Alignment::Center),
+    ]
+    .spacing(6)
+    .into()
+}
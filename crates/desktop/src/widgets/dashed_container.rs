@@ -161,7 +161,9 @@ impl<Message> Widget<Message, Theme, Renderer> for DashedContainer<'_, Message>
             let state = tree.state.downcast_mut::<AnimState>();
             state.target = if self.hovered { 1.0 } else { 0.0 };
 
-            if (state.hover_amount - state.target).abs() > 0.001 {
+            if crate::theme::reduced_motion() {
+                state.hover_amount = state.target;
+            } else if (state.hover_amount - state.target).abs() > 0.001 {
                 let dt = now.duration_since(state.last_tick).as_secs_f32();
                 let speed = 1.0 / ANIMATION_SECS;
                 if state.hover_amount < state.target {
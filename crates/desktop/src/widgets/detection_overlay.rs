@@ -0,0 +1,113 @@
+use iced::widget::canvas::{self, Frame, Path, Stroke};
+use iced::{mouse, Color, Element, Length, Pixels, Point, Rectangle, Renderer, Size, Theme};
+
+use faceguard_core::shared::region::Region;
+
+use crate::app::{scaled, Message};
+
+/// Height of a track label, before font scaling.
+const LABEL_SIZE: f32 = 12.0;
+
+/// A face the scan found on the sample frame, in the frame's display
+/// pixels, and whether the export blurs it.
+#[derive(Clone, Debug)]
+pub struct OverlayBox {
+    pub region: Region,
+    pub blurred: bool,
+}
+
+/// Boxes and track IDs drawn over the sample frame, so faces the export
+/// would leave visible stand out.
+struct DetectionOverlay {
+    boxes: Vec<OverlayBox>,
+    /// Widget pixels per display pixel.
+    scale: f32,
+    blurred: Color,
+    visible: Color,
+    label_size: f32,
+}
+
+impl canvas::Program<Message> for DetectionOverlay {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        for overlay_box in &self.boxes {
+            let color = if overlay_box.blurred {
+                self.blurred
+            } else {
+                self.visible
+            };
+            let r = &overlay_box.region;
+            let top_left = Point::new(r.x as f32 * self.scale, r.y as f32 * self.scale);
+            let size = Size::new(r.width as f32 * self.scale, r.height as f32 * self.scale);
+            frame.stroke(
+                &Path::rectangle(top_left, size),
+                Stroke::default().with_color(color).with_width(2.0),
+            );
+
+            let label = box_label(overlay_box);
+            // Above the box, or inside it when the box touches the top.
+            let label_y = if top_left.y >= self.label_size + 4.0 {
+                top_left.y - self.label_size - 4.0
+            } else {
+                top_left.y
+            };
+            let label_width = label.chars().count() as f32 * self.label_size * 0.6 + 6.0;
+            frame.fill_rectangle(
+                Point::new(top_left.x, label_y),
+                Size::new(label_width, self.label_size + 4.0),
+                Color { a: 0.75, ..color },
+            );
+            frame.fill_text(canvas::Text {
+                content: label,
+                position: Point::new(top_left.x + 3.0, label_y + 2.0),
+                color: Color::WHITE,
+                size: Pixels(self.label_size),
+                ..canvas::Text::default()
+            });
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+fn box_label(overlay_box: &OverlayBox) -> String {
+    let id = overlay_box
+        .region
+        .track_id
+        .map_or_else(|| "?".to_string(), |id| format!("#{id}"));
+    if overlay_box.blurred {
+        id
+    } else {
+        format!("{id} visible")
+    }
+}
+
+/// The overlay for a frame `image_width` display pixels wide, drawn at
+/// `width`×`height` to sit exactly over it.
+pub fn view<'a>(
+    boxes: &[OverlayBox],
+    image_width: u32,
+    width: f32,
+    height: f32,
+    fs: f32,
+    theme: &Theme,
+) -> Element<'a, Message> {
+    canvas::Canvas::new(DetectionOverlay {
+        boxes: boxes.to_vec(),
+        scale: width / image_width.max(1) as f32,
+        blurred: theme.palette().primary,
+        visible: theme.palette().danger,
+        label_size: scaled(LABEL_SIZE, fs),
+    })
+    .width(Length::Fixed(width))
+    .height(Length::Fixed(height))
+    .into()
+}
@@ -0,0 +1,146 @@
+use std::collections::{HashMap, HashSet};
+
+use iced::widget::canvas::{self, Frame};
+use iced::widget::{column, row, slider, text};
+use iced::{mouse, Color, Element, Length, Point, Rectangle, Renderer, Size, Theme};
+
+use faceguard_core::shared::region::Region;
+use faceguard_core::shared::track_time_rules::TrackTimeRules;
+
+use crate::app::{scaled, Message};
+use crate::theme::tertiary_color;
+
+/// Stretches the timeline strip divides the video into.
+pub const TIMELINE_BUCKETS: usize = 120;
+
+/// What the scan found in one stretch of the video, in increasing
+/// order of concern.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Coverage {
+    /// No faces.
+    #[default]
+    Empty,
+    /// Faces, all of them blurred.
+    Blurred,
+    /// At least one face the export leaves visible.
+    Visible,
+}
+
+/// Summarizes a scan of `frames` frames into `count` stretches, checking
+/// each detection against the selection and time ranges like the export.
+pub fn coverage(
+    detections: &HashMap<usize, Vec<Region>>,
+    frames: usize,
+    blur_ids: Option<&HashSet<u32>>,
+    time_rules: &TrackTimeRules,
+    fps: f64,
+    count: usize,
+) -> Vec<Coverage> {
+    let mut buckets = vec![Coverage::Empty; count];
+    if frames == 0 || count == 0 {
+        return buckets;
+    }
+    for (&frame, regions) in detections {
+        if regions.is_empty() {
+            continue;
+        }
+        let bucket = (frame * count / frames).min(count - 1);
+        let blurred = time_rules.filter(&Region::filter(regions, blur_ids, None), frame, fps);
+        let found = if blurred.len() < regions.len() {
+            Coverage::Visible
+        } else {
+            Coverage::Blurred
+        };
+        buckets[bucket] = buckets[bucket].max(found);
+    }
+    buckets
+}
+
+/// The strip of stretches with a playhead at `position`, a fraction of
+/// the video.
+struct Strip {
+    buckets: Vec<Coverage>,
+    position: f32,
+    empty: Color,
+    blurred: Color,
+    visible: Color,
+    playhead: Color,
+}
+
+impl canvas::Program<Message> for Strip {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let step = bounds.width / self.buckets.len().max(1) as f32;
+        for (i, bucket) in self.buckets.iter().enumerate() {
+            let color = match bucket {
+                Coverage::Empty => self.empty,
+                Coverage::Blurred => self.blurred,
+                Coverage::Visible => self.visible,
+            };
+            frame.fill_rectangle(
+                Point::new(i as f32 * step, 0.0),
+                Size::new((step - 1.0).max(1.0), bounds.height),
+                color,
+            );
+        }
+        let x = (self.position * bounds.width).clamp(0.0, bounds.width - 2.0);
+        frame.fill_rectangle(
+            Point::new(x, 0.0),
+            Size::new(2.0, bounds.height),
+            self.playhead,
+        );
+        vec![frame.into_geometry()]
+    }
+}
+
+/// Where faces were found along the video, over a scrubber that moves the
+/// sample frame. The frame renders when the handle is released.
+pub fn view<'a>(
+    buckets: &[Coverage],
+    frame: usize,
+    frames: usize,
+    position: String,
+    fs: f32,
+    theme: &Theme,
+) -> Element<'a, Message> {
+    let tertiary = tertiary_color(theme);
+    let last = frames.saturating_sub(1);
+    let strip = canvas::Canvas::new(Strip {
+        buckets: buckets.to_vec(),
+        position: frame as f32 / last.max(1) as f32,
+        empty: Color {
+            a: 0.08,
+            ..theme.palette().text
+        },
+        blurred: theme.palette().primary,
+        visible: theme.palette().danger,
+        playhead: theme.palette().text,
+    })
+    .width(Length::Fill)
+    .height(8);
+
+    column![
+        strip,
+        row![
+            slider(0..=last as u32, frame as u32, Message::SampleFrameChanged)
+                .on_release(Message::SampleFrameReleased),
+            text(position).size(scaled(13.0, fs)),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center),
+        text("Marked where faces were found, in red where the export leaves one visible.")
+            .size(scaled(12.0, fs))
+            .color(tertiary),
+    ]
+    .spacing(4)
+    .into()
+}
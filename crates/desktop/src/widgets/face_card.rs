@@ -1,4 +1,3 @@
-use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use iced::widget::{button, container, image, mouse_area, row, stack, text, Space};
@@ -26,7 +25,7 @@ const IMG_SIZE: f32 = FULL_CARD_SIZE - BORDER_WIDTH * 4.0;
 
 #[allow(clippy::too_many_arguments)]
 pub fn face_card<'a>(
-    path: &Path,
+    thumbnail: image::Handle,
     selected: bool,
     on_press: Message,
     badge: Option<String>,
@@ -37,14 +36,13 @@ pub fn face_card<'a>(
 ) -> Element<'a, Message> {
     let palette = theme.palette();
     let surface_alt = surface_alt_color(theme);
-    let path_buf = path.to_path_buf();
 
     let target = if hovered { 1.0_f32 } else { 0.0 };
 
     let animated: Element<'a, Message> = AnimationBuilder::new(target, move |t: f32| {
         let t = t.clamp(0.0, 1.0);
         build_card(
-            &path_buf,
+            &thumbnail,
             selected,
             &on_press,
             &badge,
@@ -55,7 +53,7 @@ pub fn face_card<'a>(
         )
     })
     .animates_layout(true)
-    .animation(Easing::EASE_OUT.with_duration(ANIMATION_DURATION))
+    .animation(Easing::EASE_OUT.with_duration(crate::theme::animation_duration(ANIMATION_DURATION)))
     .into();
 
     // Fixed-size outer container establishes grid footprint.
@@ -75,7 +73,7 @@ pub fn face_card<'a>(
 
 #[allow(clippy::too_many_arguments)]
 fn build_card<'a>(
-    path: &PathBuf,
+    thumbnail: &image::Handle,
     selected: bool,
     on_press: &Message,
     badge: &Option<String>,
@@ -88,7 +86,7 @@ fn build_card<'a>(
     let card_size = IMG_SIZE + grow * 2.0;
 
     let inner_radius = (CORNER_RADIUS - BORDER_WIDTH).max(0.0);
-    let img = image(image::Handle::from_path(path))
+    let img = image(thumbnail.clone())
         .width(card_size)
         .height(card_size)
         .border_radius(inner_radius);
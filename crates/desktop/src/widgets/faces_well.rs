@@ -1,20 +1,41 @@
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
-use iced::widget::{checkbox, column, row, text, Space};
-use iced::{Element, Length, Theme};
+use iced::widget::{button, checkbox, column, container, image, row, text, text_input, Space};
+use iced::{Color, Element, Length, Theme};
 
 use crate::app::{scaled, Message};
+use crate::project::Project;
 use crate::theme::tertiary_color;
 use crate::widgets::face_card;
+use faceguard_core::shared::track_time_rules::{TimeRange, TrackTimeRules};
 
 const CARD_SPACING: f32 = 10.0;
+const CAPTION_MAX_CHARS: usize = 14;
+/// Crops shown in a group's collage.
+const COLLAGE_CROPS: usize = 3;
+/// Edge length of a collage, same as a thumbnail.
+const COLLAGE_SIZE: u32 = 256;
+/// Gap between the crops of a collage.
+const COLLAGE_GAP: u32 = 4;
 
 pub struct FacesWellState {
     pub crops: HashMap<u32, PathBuf>,
+    /// Sharp in-memory thumbnails for crops that were stored blurred.
+    sharp_thumbnails: HashMap<u32, image::Handle>,
     pub groups: Vec<Vec<u32>>,
+    /// Collage of each group's top crops, by group index. `None` when a
+    /// group has a single crop, which is shown as is.
+    group_collages: Vec<Option<image::Handle>>,
     pub group_faces: bool,
     pub selected: HashSet<u32>,
+    /// Labels and notes for the current input. Survives `clear()` so a
+    /// re-scan keeps them.
+    pub project: Project,
+    /// Track whose label/note editor is open.
+    pub editing: Option<u32>,
+    /// Width available to the card grid; follows the window size.
+    pub content_width: f32,
     temp_dir: Option<tempfile::TempDir>,
 }
 
@@ -22,9 +43,14 @@ impl FacesWellState {
     pub fn new() -> Self {
         Self {
             crops: HashMap::new(),
+            sharp_thumbnails: HashMap::new(),
             groups: vec![],
+            group_collages: vec![],
             group_faces: true,
             selected: HashSet::new(),
+            project: Project::default(),
+            editing: None,
+            content_width: 548.0,
             temp_dir: None,
         }
     }
@@ -32,19 +58,35 @@ impl FacesWellState {
     pub fn populate(
         &mut self,
         crops: HashMap<u32, PathBuf>,
+        sharp_thumbnails: HashMap<u32, ::image::RgbImage>,
+        ranked_thumbnails: HashMap<u32, Vec<::image::RgbImage>>,
         groups: Vec<Vec<u32>>,
         temp_dir: tempfile::TempDir,
     ) {
         self.selected = crops.keys().copied().collect();
         self.crops = crops;
+        self.sharp_thumbnails = sharp_thumbnails
+            .into_iter()
+            .map(|(id, img)| (id, to_handle(img)))
+            .collect();
+        self.group_collages = groups
+            .iter()
+            .map(|group| {
+                let crops = top_group_crops(group, &ranked_thumbnails);
+                (crops.len() > 1).then(|| to_handle(collage(&crops)))
+            })
+            .collect();
         self.groups = groups;
         self.temp_dir = Some(temp_dir);
     }
 
     pub fn clear(&mut self) {
         self.crops.clear();
+        self.sharp_thumbnails.clear();
         self.groups.clear();
+        self.group_collages.clear();
         self.selected.clear();
+        self.editing = None;
         self.temp_dir = None;
     }
 
@@ -77,6 +119,24 @@ impl FacesWellState {
         }
     }
 
+    /// Image to show for a track: the sharp in-memory copy when the file
+    /// on disk is blurred, otherwise the file itself.
+    pub fn thumbnail(&self, track_id: u32) -> Option<image::Handle> {
+        if let Some(handle) = self.sharp_thumbnails.get(&track_id) {
+            return Some(handle.clone());
+        }
+        self.crops.get(&track_id).map(image::Handle::from_path)
+    }
+
+    /// Image to show for a group: its collage, or the first track's
+    /// thumbnail when the group has a single crop.
+    fn group_thumbnail(&self, group_idx: usize, representative_id: u32) -> Option<image::Handle> {
+        match self.group_collages.get(group_idx) {
+            Some(Some(handle)) => Some(handle.clone()),
+            _ => self.thumbnail(representative_id),
+        }
+    }
+
     pub fn total_count(&self) -> usize {
         self.crops.len()
     }
@@ -88,6 +148,13 @@ impl FacesWellState {
     pub fn has_faces(&self) -> bool {
         !self.crops.is_empty()
     }
+
+    /// Per-track time rules for the blur pass. Ranges follow the grouping
+    /// that is on screen, since that is how they were entered.
+    pub fn time_rules(&self) -> TrackTimeRules {
+        let groups: &[Vec<u32>] = if self.group_faces { &self.groups } else { &[] };
+        self.project.time_rules(groups)
+    }
 }
 
 pub fn view<'a>(
@@ -136,9 +203,127 @@ pub fn view<'a>(
         build_individual_grid(state, fs, theme, hovered)
     };
 
-    column![header, Space::new().height(14), grid,]
-        .spacing(0)
+    let mut col = column![header, Space::new().height(14), grid].spacing(0);
+    if let Some(track_id) = state.editing {
+        col = col
+            .push(Space::new().height(14))
+            .push(track_editor(state, track_id, fs, theme));
+    }
+    col.width(Length::Fill).into()
+}
+
+/// Label shown under a card; clicking it opens the editor for `track_id`.
+fn card_with_caption<'a>(
+    card: Element<'a, Message>,
+    state: &FacesWellState,
+    track_id: u32,
+    fs: f32,
+    theme: &Theme,
+) -> Element<'a, Message> {
+    let tertiary = tertiary_color(theme);
+    let label = state
+        .project
+        .track(track_id)
+        .map(|t| t.label.trim())
+        .filter(|l| !l.is_empty());
+    let (caption, color) = match label {
+        Some(label) => (truncate(label), theme.palette().text),
+        None => ("Add label".to_string(), tertiary),
+    };
+
+    let caption_btn = button(
+        text(caption)
+            .size(scaled(12.0, fs))
+            .color(color)
+            .width(Length::Fill)
+            .align_x(iced::Alignment::Center),
+    )
+    .on_press(Message::EditTrack(Some(track_id)))
+    .padding([2, 0])
+    .width(face_card::FULL_CARD_SIZE)
+    .style(|_theme: &Theme, _status: button::Status| button::Style {
+        background: None,
+        ..button::Style::default()
+    });
+
+    column![card, caption_btn]
+        .width(face_card::FULL_CARD_SIZE)
+        .into()
+}
+
+fn truncate(label: &str) -> String {
+    if label.chars().count() <= CAPTION_MAX_CHARS {
+        label.to_string()
+    } else {
+        let head: String = label.chars().take(CAPTION_MAX_CHARS - 1).collect();
+        format!("{head}\u{2026}")
+    }
+}
+
+fn track_editor<'a>(
+    state: &FacesWellState,
+    track_id: u32,
+    fs: f32,
+    theme: &Theme,
+) -> Element<'a, Message> {
+    let palette = theme.palette();
+    let border = Color {
+        a: 0.15,
+        ..palette.text
+    };
+    let (label, note, ranges) = state
+        .project
+        .track(track_id)
+        .map(|t| (t.label.clone(), t.note.clone(), t.blur_ranges.clone()))
+        .unwrap_or_default();
+    let range_error = TimeRange::parse_list(&ranges).err();
+
+    let header = row![
+        text(format!("Track {track_id}")).size(scaled(14.0, fs)),
+        Space::new().width(Length::Fill),
+        button(text("Done").size(scaled(13.0, fs)))
+            .on_press(Message::EditTrack(None))
+            .padding([4, 12]),
+    ]
+    .align_y(iced::Alignment::Center);
+
+    let fields = column![
+        header,
+        text_input("Label, e.g. interviewee or bystander 1", &label)
+            .on_input(move |value| Message::TrackLabelChanged(track_id, value))
+            .size(scaled(14.0, fs))
+            .padding(8),
+        text_input("Note", &note)
+            .on_input(move |value| Message::TrackNoteChanged(track_id, value))
+            .size(scaled(14.0, fs))
+            .padding(8),
+        text_input("Blur only during, e.g. 02:10-05:30, 08:00-09:15", &ranges)
+            .on_input(move |value| Message::TrackRangesChanged(track_id, value))
+            .on_submit(Message::EditTrack(None))
+            .size(scaled(14.0, fs))
+            .padding(8),
+    ]
+    .spacing(8);
+    let fields = match range_error {
+        Some(error) => fields.push(text(error).size(scaled(12.0, fs)).color(palette.danger)),
+        None => fields.push(
+            text("Leave empty to blur this face for the whole video.")
+                .size(scaled(12.0, fs))
+                .color(tertiary_color(theme)),
+        ),
+    };
+
+    container(fields)
+        .padding(12)
         .width(Length::Fill)
+        .style(move |_theme: &Theme| container::Style {
+            border: iced::border::Border {
+                color: border,
+                width: 1.0,
+                radius: 10.0.into(),
+            },
+            ..container::Style::default()
+        })
         .into()
 }
 
@@ -154,10 +339,10 @@ fn build_individual_grid<'a>(
     let cards: Vec<Element<'a, Message>> = sorted_ids
         .into_iter()
         .filter_map(|track_id| {
-            let path = state.crops.get(&track_id)?;
+            let thumbnail = state.thumbnail(track_id)?;
             let is_selected = state.selected.contains(&track_id);
-            Some(face_card::face_card(
-                path,
+            let card = face_card::face_card(
+                thumbnail,
                 is_selected,
                 Message::ToggleFace(track_id),
                 None,
@@ -165,11 +350,12 @@ fn build_individual_grid<'a>(
                 track_id,
                 fs,
                 theme,
-            ))
+            );
+            Some(card_with_caption(card, state, track_id, fs, theme))
         })
         .collect();
 
-    wrap_cards(cards)
+    wrap_cards(cards, state.content_width)
 }
 
 fn build_grouped_grid<'a>(
@@ -184,15 +370,15 @@ fn build_grouped_grid<'a>(
         .enumerate()
         .filter_map(|(group_idx, group)| {
             let representative_id = group.first()?;
-            let path = state.crops.get(representative_id)?;
+            let thumbnail = state.group_thumbnail(group_idx, *representative_id)?;
             let all_selected = group.iter().all(|id| state.selected.contains(id));
             let badge = if group.len() > 1 {
                 Some(format!("\u{00d7}{}", group.len()))
             } else {
                 None
             };
-            Some(face_card::face_card(
-                path,
+            let card = face_card::face_card(
+                thumbnail,
                 all_selected,
                 Message::ToggleGroup(group_idx),
                 badge,
@@ -200,15 +386,72 @@ fn build_grouped_grid<'a>(
                 *representative_id,
                 fs,
                 theme,
+            );
+            Some(card_with_caption(
+                card,
+                state,
+                *representative_id,
+                fs,
+                theme,
             ))
         })
         .collect();
 
-    wrap_cards(cards)
+    wrap_cards(cards, state.content_width)
+}
+
+fn to_handle(img: ::image::RgbImage) -> image::Handle {
+    let (w, h) = img.dimensions();
+    let rgba = ::image::DynamicImage::ImageRgb8(img).into_rgba8();
+    image::Handle::from_rgba(w, h, rgba.into_raw())
+}
+
+/// A group's top crops: each track's best crop in group order, then each
+/// track's second best, and so on, so a group of several tracks shows
+/// several tracks.
+fn top_group_crops<'a>(
+    group: &[u32],
+    ranked: &'a HashMap<u32, Vec<::image::RgbImage>>,
+) -> Vec<&'a ::image::RgbImage> {
+    let per_track: Vec<&Vec<::image::RgbImage>> =
+        group.iter().filter_map(|id| ranked.get(id)).collect();
+    let deepest = per_track.iter().map(|crops| crops.len()).max().unwrap_or(0);
+    (0..deepest)
+        .flat_map(|rank| per_track.iter().filter_map(move |crops| crops.get(rank)))
+        .take(COLLAGE_CROPS)
+        .collect()
+}
+
+/// Tiles two or three crops into one square: two side by side, or the
+/// best one on the left with the other two stacked on the right.
+fn collage(crops: &[&::image::RgbImage]) -> ::image::RgbImage {
+    let size = COLLAGE_SIZE;
+    let half = (size - COLLAGE_GAP) / 2;
+    // (x, y, width, height) of each tile.
+    let tiles = if crops.len() >= 3 {
+        let main = size * 2 / 3;
+        let side = size - main - COLLAGE_GAP;
+        vec![
+            (0, 0, main, size),
+            (main + COLLAGE_GAP, 0, side, half),
+            (main + COLLAGE_GAP, size - half, side, half),
+        ]
+    } else {
+        vec![(0, 0, half, size), (size - half, 0, half, size)]
+    };
+
+    let mut out = ::image::RgbImage::new(size, size);
+    for (crop, (x, y, w, h)) in crops.iter().zip(tiles) {
+        let tile = ::image::DynamicImage::ImageRgb8((*crop).clone())
+            .resize_to_fill(w, h, ::image::imageops::FilterType::Triangle)
+            .into_rgb8();
+        ::image::imageops::replace(&mut out, &tile, x as i64, y as i64);
+    }
+    out
 }
 
-fn wrap_cards(cards: Vec<Element<'_, Message>>) -> Element<'_, Message> {
-    let cards_per_row = ((548.0) / (face_card::FULL_CARD_SIZE + CARD_SPACING)).floor() as usize;
+fn wrap_cards(cards: Vec<Element<'_, Message>>, width: f32) -> Element<'_, Message> {
+    let cards_per_row = (width / (face_card::FULL_CARD_SIZE + CARD_SPACING)).floor() as usize;
     let cards_per_row = cards_per_row.max(1);
 
     let mut rows_col = column![].spacing(CARD_SPACING);
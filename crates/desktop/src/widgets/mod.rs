@@ -1,4 +1,8 @@
+pub mod command_palette;
+pub mod compare_slider;
 pub mod dashed_container;
+pub mod detection_overlay;
+pub mod detection_timeline;
 pub mod drop_zone;
 pub mod face_card;
 pub mod faces_well;
@@ -59,7 +59,7 @@ fn primary_button_with_width<'a, Message: Clone + 'a>(
         build_button(&content, &on_press, padding, width, t)
     })
     .animates_layout(true)
-    .animation(Easing::EASE_OUT.with_duration(ANIMATION_DURATION))
+    .animation(Easing::EASE_OUT.with_duration(crate::theme::animation_duration(ANIMATION_DURATION)))
     .into();
 
     mouse_area(animated)
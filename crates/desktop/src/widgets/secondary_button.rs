@@ -82,7 +82,7 @@ fn secondary_button_inner<'a, Message: Clone + 'a>(
         build_button(&content, &on_press, padding, width, radius, t)
     })
     .animates_layout(true)
-    .animation(Easing::EASE_OUT.with_duration(ANIMATION_DURATION))
+    .animation(Easing::EASE_OUT.with_duration(crate::theme::animation_duration(ANIMATION_DURATION)))
     .into();
 
     mouse_area(animated)
@@ -1,40 +1,70 @@
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
 
 use crossbeam_channel::{Receiver, Sender};
 
+use faceguard_core::audio::domain::bleep_style::BleepStyle;
+use faceguard_core::audio::domain::keyword_hit::KeywordHit;
+use faceguard_core::audio::domain::speech_language::SpeechLanguage;
+use faceguard_core::audio::domain::spoken_entity::PiiCategory;
 use faceguard_core::blurring::infrastructure::blurrer_factory;
 use faceguard_core::blurring::infrastructure::gpu_context::GpuContext;
 use faceguard_core::detection::domain::face_detector::FaceDetector;
-use faceguard_core::detection::domain::face_region_builder::FaceRegionBuilder;
+use faceguard_core::detection::domain::face_region_builder::{
+    FaceRegionBuilder, DEFAULT_UNCERTAINTY_GROWTH,
+};
 use faceguard_core::detection::domain::region_merger::RegionMerger;
 use faceguard_core::detection::domain::region_smoother::{RegionSmoother, DEFAULT_ALPHA};
 use faceguard_core::detection::infrastructure::bytetrack_tracker::ByteTracker;
 use faceguard_core::detection::infrastructure::cached_face_detector::CachedFaceDetector;
+use faceguard_core::detection::infrastructure::disk_backed_detections::DiskBackedDetections;
+use faceguard_core::detection::infrastructure::model_resolver;
 use faceguard_core::detection::infrastructure::onnx_yolo_detector::OnnxYoloDetector;
+use faceguard_core::detection::infrastructure::recording_face_detector::RecordingFaceDetector;
 use faceguard_core::detection::infrastructure::skip_frame_detector::SkipFrameDetector;
 use faceguard_core::pipeline::blur_faces_use_case::BlurFacesUseCase;
 use faceguard_core::pipeline::blur_image_use_case::BlurImageUseCase;
 use faceguard_core::pipeline::infrastructure::threaded_pipeline_executor::ThreadedPipelineExecutor;
-use faceguard_core::shared::constants::{IMAGE_EXTENSIONS, TRACKER_MAX_LOST};
+use faceguard_core::shared::cancellation::{CancellationToken, Cancelled};
+use faceguard_core::shared::constants::{IMAGE_EXTENSIONS, TRACKER_MAX_LOST, YOLO_MODEL_NAME};
+use faceguard_core::shared::frame::Frame;
 use faceguard_core::shared::region::Region;
+use faceguard_core::shared::time_map::TimeMap;
+use faceguard_core::shared::track_time_rules::{TimeRange, TrackTimeRules};
+use faceguard_core::shared::video_metadata::VideoMetadata;
+use faceguard_core::shared::warnings::{Warning, Warnings};
 use faceguard_core::video::domain::image_writer::ImageWriter;
 use faceguard_core::video::domain::video_reader::VideoReader;
 use faceguard_core::video::domain::video_writer::VideoWriter;
+use faceguard_core::video::infrastructure::cut_range_reader::CutRangeReader;
+use faceguard_core::video::infrastructure::disk_space::{estimate_output_bytes, DiskSpaceCheck};
 use faceguard_core::video::infrastructure::ffmpeg_reader::FfmpegReader;
 use faceguard_core::video::infrastructure::ffmpeg_writer::FfmpegWriter;
 use faceguard_core::video::infrastructure::image_file_reader::ImageFileReader;
-use faceguard_core::video::infrastructure::image_file_writer::ImageFileWriter;
+use faceguard_core::video::infrastructure::image_file_writer::{
+    ImageFileWriter, ImageOutputFormat, PngCompression,
+};
 
+use super::detection_cache;
 use super::model_cache::ModelCache;
+use super::throttle::Throttle;
+use crate::performance::{InputSize, Stage};
 
 #[derive(Debug, Clone)]
 pub enum WorkerMessage {
     DownloadProgress(u64, u64),
     BlurProgress(usize, usize),
+    /// Measured throughput in megapixels per second, sent before `Complete`.
+    Measured(Stage, f64),
+    /// Keyword hits found in the audio, sent before any is bleeped. The job
+    /// waits for the hits to bleep on the sender, or for cancellation.
+    ReviewHits(Vec<KeywordHit>, Sender<Vec<KeywordHit>>),
+    /// Non-fatal problems, sent just before `Complete` when there are any.
+    Warnings(Vec<Warning>),
     Complete,
     Error(String),
     Cancelled,
@@ -46,28 +76,63 @@ pub struct BlurParams {
     pub blur_shape: crate::settings::BlurShape,
     pub confidence: u32,
     pub blur_strength: u32,
+    /// Percent of an ellipse's radius the blur fades out over.
+    pub blur_feather: u32,
     pub blur_coverage: u32,
     pub center_offset: i32,
     pub lookahead: u32,
     pub quality: u32,
+    pub video_codec: crate::settings::VideoCodec,
     pub detection_cache: Option<Arc<HashMap<usize, Vec<Region>>>>,
     pub blur_ids: Option<HashSet<u32>>,
+    pub time_rules: TrackTimeRules,
+    /// Part of a video to export, in source seconds; the rest is cut from
+    /// both picture and sound.
+    pub trim: Option<TimeRange>,
+    /// Skip frames that did not change outside the blurred regions; set for
+    /// screen recordings.
+    pub reuse_unchanged_frames: bool,
+    /// Reuse and save detections in the on-disk cache.
+    pub cache_detections: bool,
+    /// Tracks seen on fewer frames are not blurred; 0 keeps every track.
+    pub min_track_frames: usize,
+    /// Frames between detections; positions are extrapolated in between.
+    pub detection_interval: u32,
     pub model_cache: Arc<ModelCache>,
     pub gpu_context: Option<Arc<GpuContext>>,
     pub audio_processing: bool,
     pub bleep_keywords: String,
+    pub keyword_matching: crate::settings::KeywordMatching,
+    /// Personal information found in the transcript and bleeped too.
+    pub bleep_pii: Vec<PiiCategory>,
+    pub speech_language: SpeechLanguage,
     pub bleep_sound: crate::settings::BleepSound,
     pub voice_disguise: crate::settings::VoiceDisguise,
+    pub audio_fallback: crate::settings::AudioFallback,
+    pub image_format: crate::settings::ImageFormat,
+    pub image_quality: u32,
+    pub png_compression: crate::settings::PngCompression,
+    pub power_saving: Arc<AtomicBool>,
+    pub warnings: Warnings,
 }
 
-pub fn spawn(params: BlurParams) -> (Receiver<WorkerMessage>, Arc<AtomicBool>) {
+/// Starts the job on a child of `parent`, so closing the app stops it too.
+/// The returned token cancels just this job.
+pub fn spawn(
+    params: BlurParams,
+    parent: &CancellationToken,
+) -> (Receiver<WorkerMessage>, CancellationToken) {
     let (tx, rx) = crossbeam_channel::unbounded::<WorkerMessage>();
-    let cancelled = Arc::new(AtomicBool::new(false));
+    let cancelled = parent.child();
     let cancelled_clone = cancelled.clone();
 
     thread::spawn(move || {
-        if let Err(e) = run_blur(&tx, &cancelled_clone, &params) {
-            if cancelled_clone.load(Ordering::Relaxed) {
+        let writing = AtomicBool::new(false);
+        if let Err(e) = run_blur(&tx, &cancelled_clone, &params, &writing) {
+            if Cancelled::reason_of(&*e).is_some() || cancelled_clone.is_cancelled() {
+                if writing.load(Ordering::Relaxed) {
+                    remove_partial_output(&params.output_path);
+                }
                 let _ = tx.send(WorkerMessage::Cancelled);
             } else {
                 let _ = tx.send(WorkerMessage::Error(e.to_string()));
@@ -80,39 +145,141 @@ pub fn spawn(params: BlurParams) -> (Receiver<WorkerMessage>, Arc<AtomicBool>) {
 
 fn run_blur(
     tx: &Sender<WorkerMessage>,
-    cancelled: &Arc<AtomicBool>,
+    cancelled: &CancellationToken,
     params: &BlurParams,
+    writing: &AtomicBool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let input = &params.input_path;
     let output = &params.output_path;
     let confidence = params.confidence as f64 / 100.0;
 
-    let detector = build_detector(params, tx, cancelled, confidence)?;
+    if !is_image(input) {
+        check_disk_space(input, output, remuxes_audio(params))?;
+    }
+
+    // Frame reuse and trimming skip detection on some frames, so their
+    // detections are incomplete and not worth caching.
+    let disk_cache = if params.cache_detections
+        && params.detection_cache.is_none()
+        && !params.reuse_unchanged_frames
+        && params.trim.is_none()
+        && !is_image(input)
+    {
+        detection_cache::entry(
+            input,
+            &detection_cache::params(
+                params.confidence,
+                params.blur_coverage,
+                params.center_offset,
+                params.detection_interval,
+            ),
+        )
+    } else {
+        None
+    };
+    let cached = disk_cache
+        .as_ref()
+        .and_then(|(cache, key)| cache.load_streamed(key, DiskBackedDetections::new()));
+
+    let detections_known = params.detection_cache.is_some() || cached.is_some();
+    let (detector, detection_log) = match cached {
+        Some(detections) => {
+            log::info!("Blur: reusing cached detections");
+            let detector: Box<dyn FaceDetector> =
+                Box::new(CachedFaceDetector::disk_backed(detections));
+            (detector, None)
+        }
+        None => {
+            let detector = build_detector(params, tx, cancelled, confidence)?;
+            if disk_cache.is_some() {
+                let recorder = RecordingFaceDetector::new(detector);
+                let recorded = recorder.log();
+                (Box::new(recorder) as Box<dyn FaceDetector>, Some(recorded))
+            } else {
+                (detector, None)
+            }
+        }
+    };
     let blurrer = build_blurrer(params);
+    // The trimmed video and audio share one output timeline.
+    let time_map =
+        TimeMap::new().with_cuts(&params.trim.map(|trim| trim.trim_cuts()).unwrap_or_default());
 
+    writing.store(true, Ordering::Relaxed);
     if is_image(input) {
         blur_image(input, output, detector, blurrer, params)?;
     } else {
-        blur_video(input, output, detector, blurrer, params, tx, cancelled)?;
+        blur_video(
+            input,
+            output,
+            detector,
+            blurrer,
+            params,
+            &time_map,
+            detections_known,
+            tx,
+            cancelled,
+        )?;
     }
 
-    // Audio processing (if enabled)
-    if params.audio_processing {
-        run_audio_processing(input, output, params)?;
+    if let (Some((cache, key)), Some(recorded)) = (&disk_cache, &detection_log) {
+        if !cancelled.is_cancelled() {
+            let mut detections = recorded.lock().map_err(|_| "Detection log poisoned")?;
+            if let Err(e) = cache.store_streamed(key, &mut detections) {
+                log::warn!("Could not save detections to the cache: {e}");
+            }
+        }
     }
 
-    if cancelled.load(Ordering::Relaxed) {
-        return Err("Cancelled".into());
+    // Audio processing (if enabled), or trimming the audio to match
+    if remuxes_audio(params) {
+        run_audio_processing(tx, input, output, params, &time_map, cancelled)?;
     }
+    cancelled.check()?;
 
+    if !params.warnings.is_empty() {
+        let _ = tx.send(WorkerMessage::Warnings(params.warnings.snapshot()));
+    }
     let _ = tx.send(WorkerMessage::Complete);
     Ok(())
 }
 
+/// Whether the job replaces the source audio: to process it, or to trim it
+/// along with the video.
+fn remuxes_audio(params: &BlurParams) -> bool {
+    params.audio_processing || params.trim.is_some()
+}
+
+/// Deletes what a cancelled job wrote, which would otherwise look like a
+/// finished export that stops partway through.
+fn remove_partial_output(output: &std::path::Path) {
+    match std::fs::remove_file(output) {
+        Ok(()) => log::info!("Removed partial output {}", output.display()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => log::warn!("Could not remove partial output {}: {e}", output.display()),
+    }
+}
+
+/// Fails before any work starts when the output folder lacks room for the
+/// export, rather than partway through the encode.
+fn check_disk_space(
+    input: &std::path::Path,
+    output: &std::path::Path,
+    audio_remux: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let metadata = FfmpegReader::new().open(input)?;
+    let input_bytes = std::fs::metadata(input)?.len();
+    DiskSpaceCheck::new()
+        .with_output(output, estimate_output_bytes(&metadata, input_bytes, None))
+        .with_audio_remux(audio_remux)
+        .run()?;
+    Ok(())
+}
+
 fn build_detector(
     params: &BlurParams,
     tx: &Sender<WorkerMessage>,
-    cancelled: &Arc<AtomicBool>,
+    cancelled: &CancellationToken,
     confidence: f64,
 ) -> Result<Box<dyn FaceDetector>, Box<dyn std::error::Error>> {
     if let Some(ref cache) = params.detection_cache {
@@ -129,15 +296,13 @@ fn build_detector(
             cancelled,
         )
         .map_err(|e| -> Box<dyn std::error::Error> { e.into() })?;
-
-    if cancelled.load(Ordering::Relaxed) {
-        return Err("Cancelled".into());
-    }
+    cancelled.check()?;
 
     let padding = params.blur_coverage as f64 / 100.0;
     let center_offset = params.center_offset as f64 / 100.0;
     let smoother = RegionSmoother::new(DEFAULT_ALPHA);
-    let region_builder = FaceRegionBuilder::new(padding, center_offset, Some(Box::new(smoother)));
+    let region_builder = FaceRegionBuilder::new(padding, center_offset, Some(Box::new(smoother)))
+        .with_uncertainty_growth(DEFAULT_UNCERTAINTY_GROWTH);
     let tracker = ByteTracker::new(TRACKER_MAX_LOST);
 
     let det = match params.model_cache.get_yolo_session() {
@@ -155,9 +320,13 @@ fn build_detector(
             log::info!("Blur: building new YOLO session from path");
             OnnxYoloDetector::new(&model_path, region_builder, tracker, confidence)?
         }
-    };
+    }
+    .with_calibration(model_resolver::calibration_for(YOLO_MODEL_NAME));
 
-    Ok(Box::new(SkipFrameDetector::new(Box::new(det), 2)?))
+    Ok(Box::new(SkipFrameDetector::new(
+        Box::new(det),
+        params.detection_interval as usize,
+    )?))
 }
 
 fn build_blurrer(
@@ -170,6 +339,7 @@ fn build_blurrer(
     blurrer_factory::create_blurrer_with_context(
         blur_shape,
         params.blur_strength as usize,
+        params.blur_feather as f32 / 100.0,
         params.gpu_context.clone(),
     )
 }
@@ -182,7 +352,7 @@ fn blur_image(
     params: &BlurParams,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let reader: Box<dyn VideoReader> = Box::new(ImageFileReader::new());
-    let writer: Box<dyn ImageWriter> = Box::new(ImageFileWriter::new());
+    let writer: Box<dyn ImageWriter> = Box::new(build_image_writer(params));
     let mut use_case = BlurImageUseCase::new(
         reader,
         writer,
@@ -195,32 +365,69 @@ fn blur_image(
     Ok(())
 }
 
+fn build_image_writer(params: &BlurParams) -> ImageFileWriter {
+    let png_compression = match params.png_compression {
+        crate::settings::PngCompression::Fast => PngCompression::Fast,
+        crate::settings::PngCompression::Default => PngCompression::Default,
+        crate::settings::PngCompression::Best => PngCompression::Best,
+    };
+    let mut writer = ImageFileWriter::new()
+        .with_jpeg_quality(params.image_quality.min(100) as u8)
+        .with_png_compression(png_compression);
+    let format = match params.image_format {
+        crate::settings::ImageFormat::Original => None,
+        crate::settings::ImageFormat::Jpeg => Some(ImageOutputFormat::Jpeg),
+        crate::settings::ImageFormat::Png => Some(ImageOutputFormat::Png),
+        crate::settings::ImageFormat::Webp => Some(ImageOutputFormat::Webp),
+    };
+    if let Some(format) = format {
+        writer = writer.with_format(format);
+    }
+    writer
+}
+
+#[allow(clippy::too_many_arguments)]
 fn blur_video(
     input: &std::path::Path,
     output: &std::path::Path,
     detector: Box<dyn FaceDetector>,
     blurrer: Box<dyn faceguard_core::blurring::domain::frame_blurrer::FrameBlurrer>,
     params: &BlurParams,
+    time_map: &TimeMap,
+    detections_known: bool,
     tx: &Sender<WorkerMessage>,
-    cancelled: &Arc<AtomicBool>,
+    cancelled: &CancellationToken,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let mut reader: Box<dyn VideoReader> = Box::new(FfmpegReader::new());
+    let mut reader: Box<dyn VideoReader> =
+        Box::new(FfmpegReader::new().with_warnings(params.warnings.clone()));
+    if !time_map.cuts().is_empty() {
+        reader = Box::new(CutRangeReader::new(reader, time_map.clone()));
+    }
     let metadata = reader.open(input)?;
     let crf = crate::settings::quality_to_crf(params.quality);
-    let mut ffmpeg_writer = FfmpegWriter::new().with_crf(crf);
-    if params.audio_processing {
+    let mut ffmpeg_writer = FfmpegWriter::new()
+        .with_crf(crf)
+        .with_codec(params.video_codec.core())
+        .with_time_map(time_map.clone())
+        .with_warnings(params.warnings.clone());
+    if remuxes_audio(params) {
         ffmpeg_writer.set_skip_audio_passthrough(true);
     }
-    let writer: Box<dyn VideoWriter> = Box::new(ffmpeg_writer);
+    let encode_time = Arc::new(Mutex::new(Duration::ZERO));
+    let writer: Box<dyn VideoWriter> = Box::new(TimedWriter {
+        inner: Box::new(ffmpeg_writer),
+        busy: encode_time.clone(),
+    });
     let merger = RegionMerger::new();
 
     let _ = tx.send(WorkerMessage::BlurProgress(0, metadata.total_frames));
 
     let tx_progress = tx.clone();
-    let cancelled_progress = cancelled.clone();
+    let throttle = Throttle::new(params.power_saving.clone());
     let progress: Box<dyn Fn(usize, usize) -> bool + Send> = Box::new(move |current, total| {
+        throttle.pace();
         let _ = tx_progress.send(WorkerMessage::BlurProgress(current, total));
-        !cancelled_progress.load(Ordering::Relaxed)
+        true
     });
 
     let mut use_case = BlurFacesUseCase::new(
@@ -235,17 +442,90 @@ fn blur_video(
         None,
         Some(progress),
         Some(cancelled.clone()),
-    );
+    )
+    .with_time_rules(params.time_rules.clone())
+    .with_reuse_unchanged_frames(params.reuse_unchanged_frames)
+    .with_min_track_frames(params.min_track_frames)
+    .with_warnings(params.warnings.clone());
+    let started = Instant::now();
     use_case.execute(&metadata, output)?;
+    let elapsed = started.elapsed();
+
+    // Throttled jobs and jobs that skipped unchanged frames are no measure
+    // of this machine. Jobs that also detected faces say nothing about
+    // blurring alone.
+    if !cancelled.is_cancelled()
+        && !params.power_saving.load(Ordering::Relaxed)
+        && !params.reuse_unchanged_frames
+    {
+        let megapixels = InputSize::from(&metadata).megapixels();
+        let encode_time = *encode_time.lock().map_err(|_| "Encode timer poisoned")?;
+        if !encode_time.is_zero() {
+            let rate = megapixels / encode_time.as_secs_f64();
+            let _ = tx.send(WorkerMessage::Measured(Stage::Encode, rate));
+        }
+        if detections_known {
+            let stage = if params.gpu_context.is_some() {
+                Stage::BlurGpu
+            } else {
+                Stage::BlurCpu
+            };
+            let rate = megapixels / elapsed.as_secs_f64();
+            let _ = tx.send(WorkerMessage::Measured(stage, rate));
+        }
+    }
     Ok(())
 }
 
+/// Adds up the time spent encoding, for the encode throughput.
+struct TimedWriter {
+    inner: Box<dyn VideoWriter>,
+    busy: Arc<Mutex<Duration>>,
+}
+
+impl TimedWriter {
+    fn timed<T>(
+        &mut self,
+        f: impl FnOnce(&mut dyn VideoWriter) -> Result<T, Box<dyn std::error::Error>>,
+    ) -> Result<T, Box<dyn std::error::Error>> {
+        let started = Instant::now();
+        let result = f(self.inner.as_mut());
+        if let Ok(mut busy) = self.busy.lock() {
+            *busy += started.elapsed();
+        }
+        result
+    }
+}
+
+impl VideoWriter for TimedWriter {
+    fn open(
+        &mut self,
+        path: &std::path::Path,
+        metadata: &VideoMetadata,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.timed(|w| w.open(path, metadata))
+    }
+
+    fn write(&mut self, frame: &Frame) -> Result<(), Box<dyn std::error::Error>> {
+        self.timed(|w| w.write(frame))
+    }
+
+    fn close(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.timed(|w| w.close())
+    }
+}
+
 fn run_audio_processing(
+    tx: &Sender<WorkerMessage>,
     input: &std::path::Path,
     output: &std::path::Path,
     params: &BlurParams,
+    time_map: &TimeMap,
+    cancelled: &CancellationToken,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    use faceguard_core::audio::domain::audio_failure_policy::AudioFailurePolicy;
     use faceguard_core::audio::domain::audio_transformer::AudioTransformer;
+    use faceguard_core::audio::infrastructure::pattern_entity_recognizer::PatternEntityRecognizer;
     use faceguard_core::audio::infrastructure::voice_morph_transformer::VoiceMorphTransformer;
     use faceguard_core::pipeline::process_audio_use_case::ProcessAudioUseCase;
     use faceguard_core::video::infrastructure::ffmpeg_audio_reader::FfmpegAudioReader;
@@ -254,8 +534,10 @@ fn run_audio_processing(
     let reader = Box::new(FfmpegAudioReader);
     let writer = Box::new(FfmpegAudioWriter);
 
-    // Set up voice transformer based on disguise setting
+    // Set up voice transformer based on disguise setting. A trim alone
+    // only cuts the audio.
     let transformer: Option<Box<dyn AudioTransformer>> = match params.voice_disguise {
+        _ if !params.audio_processing => None,
         crate::settings::VoiceDisguise::Off => None,
         crate::settings::VoiceDisguise::On => Some(Box::new(VoiceMorphTransformer::new(
             faceguard_core::audio::infrastructure::pitch_shift_transformer::DEFAULT_SEMITONES,
@@ -265,7 +547,7 @@ fn run_audio_processing(
     };
 
     // Parse keywords
-    let keywords: Vec<String> = if params.bleep_keywords.is_empty() {
+    let keywords: Vec<String> = if !params.audio_processing || params.bleep_keywords.is_empty() {
         vec![]
     } else {
         params
@@ -276,16 +558,19 @@ fn run_audio_processing(
             .collect()
     };
 
+    let bleep_pii: &[PiiCategory] = if params.audio_processing {
+        &params.bleep_pii
+    } else {
+        &[]
+    };
+
     let recognizer: Option<
         Box<dyn faceguard_core::audio::domain::speech_recognizer::SpeechRecognizer>,
-    > = if !keywords.is_empty() {
+    > = if !keywords.is_empty() || !bleep_pii.is_empty() {
         use faceguard_core::audio::infrastructure::whisper_recognizer::WhisperRecognizer;
-        match params
-            .model_cache
-            .wait_for_whisper(&|_, _| {}, &AtomicBool::new(false))
-        {
+        match params.model_cache.wait_for_whisper(&|_, _| {}, cancelled) {
             Ok(model_path) => match WhisperRecognizer::new(&model_path) {
-                Ok(r) => Some(Box::new(r)),
+                Ok(r) => Some(Box::new(r.with_language(params.speech_language))),
                 Err(e) => {
                     log::warn!("Failed to create WhisperRecognizer: {e}");
                     None
@@ -300,13 +585,10 @@ fn run_audio_processing(
         None
     };
 
-    let bleep_mode = match params.bleep_sound {
-        crate::settings::BleepSound::Tone => {
-            faceguard_core::audio::domain::word_censor::BleepMode::Tone
-        }
-        crate::settings::BleepSound::Silence => {
-            faceguard_core::audio::domain::word_censor::BleepMode::Silence
-        }
+    let bleep_style = match params.bleep_sound {
+        crate::settings::BleepSound::Tone => BleepStyle::default(),
+        crate::settings::BleepSound::Noise => BleepStyle::BrownNoise,
+        crate::settings::BleepSound::Silence => BleepStyle::Silence,
     };
 
     let use_case = ProcessAudioUseCase::new(
@@ -315,13 +597,58 @@ fn run_audio_processing(
         recognizer,
         transformer,
         keywords,
-        bleep_mode,
-    );
-    use_case.run(input, output)?;
+        bleep_style,
+    )
+    .with_failure_policy(match params.audio_fallback {
+        crate::settings::AudioFallback::Fail => AudioFailurePolicy::Fail,
+        crate::settings::AudioFallback::Original => AudioFailurePolicy::OriginalAudio,
+        crate::settings::AudioFallback::Silent => AudioFailurePolicy::Silent,
+    })
+    .with_time_map(time_map.clone())
+    .with_keyword_matcher(params.keyword_matching.matcher())
+    .with_warnings(params.warnings.clone())
+    .with_cancellation(cancelled.child());
+    let use_case = if bleep_pii.is_empty() {
+        use_case
+    } else {
+        use_case.with_entity_recognizer(Box::new(PatternEntityRecognizer::new(
+            bleep_pii.iter().copied(),
+        )))
+    };
+    let mut recognition = match use_case.recognize(input) {
+        Ok(recognition) => recognition,
+        Err(e) => return use_case.recover(input, output, e),
+    };
+    if !recognition.hits().is_empty() {
+        let kept = review_hits(tx, recognition.hits().to_vec(), cancelled)?;
+        recognition.set_hits(kept);
+    }
+    use_case.apply(recognition, output)?;
 
     Ok(())
 }
 
+/// Sends `hits` to the app for review and waits for the ones to bleep.
+fn review_hits(
+    tx: &Sender<WorkerMessage>,
+    hits: Vec<KeywordHit>,
+    cancelled: &CancellationToken,
+) -> Result<Vec<KeywordHit>, Box<dyn std::error::Error>> {
+    let (reply_tx, reply_rx) = crossbeam_channel::bounded(1);
+    tx.send(WorkerMessage::ReviewHits(hits, reply_tx))
+        .map_err(|_| "The app stopped listening")?;
+    loop {
+        cancelled.check()?;
+        match reply_rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(kept) => return Ok(kept),
+            Err(crossbeam_channel::RecvTimeoutError::Timeout) => {}
+            Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
+                return Err("Keyword review was closed without an answer".into())
+            }
+        }
+    }
+}
+
 fn is_image(path: &std::path::Path) -> bool {
     path.extension()
         .and_then(|ext| ext.to_str())
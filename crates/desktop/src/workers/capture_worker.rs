@@ -0,0 +1,86 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use crossbeam_channel::{Receiver, Sender};
+
+use faceguard_core::video::domain::video_reader::VideoReader;
+use faceguard_core::video::domain::video_writer::VideoWriter;
+use faceguard_core::video::infrastructure::ffmpeg_writer::FfmpegWriter;
+use faceguard_core::video::infrastructure::screen_capture_reader::{
+    CaptureTarget, ScreenCaptureReader, DEFAULT_CAPTURE_FPS,
+};
+
+/// Frames between `Recording` updates (about one per second).
+const PROGRESS_INTERVAL: usize = DEFAULT_CAPTURE_FPS as usize;
+
+pub enum CaptureMessage {
+    /// Frames recorded so far.
+    Recording(usize),
+    /// The recording was stopped and written to this (unblurred) file.
+    Complete(PathBuf),
+    Error(String),
+}
+
+pub struct CaptureParams {
+    pub target: CaptureTarget,
+    /// Where the raw recording goes. It still shows every face, so callers
+    /// keep it in a temp folder.
+    pub output_path: PathBuf,
+    pub quality: u32,
+}
+
+/// Records until the returned flag is set, then finishes the file.
+pub fn spawn(params: CaptureParams) -> (Receiver<CaptureMessage>, Arc<AtomicBool>) {
+    let (tx, rx) = crossbeam_channel::unbounded::<CaptureMessage>();
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_clone = stop.clone();
+
+    thread::spawn(move || {
+        let message = match record(&tx, &stop_clone, &params) {
+            Ok(()) => CaptureMessage::Complete(params.output_path.clone()),
+            Err(e) => CaptureMessage::Error(e.to_string()),
+        };
+        let _ = tx.send(message);
+    });
+
+    (rx, stop)
+}
+
+fn record(
+    tx: &Sender<CaptureMessage>,
+    stop: &AtomicBool,
+    params: &CaptureParams,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut reader = ScreenCaptureReader::new(params.target.clone());
+    let metadata = reader.open(Path::new(""))?;
+    let mut writer = FfmpegWriter::new().with_crf(crate::settings::quality_to_crf(params.quality));
+    writer.open(&params.output_path, &metadata)?;
+
+    let mut recorded = 0;
+    let mut failure = None;
+    for frame in reader.frames() {
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        if let Err(e) = frame.and_then(|frame| writer.write(&frame)) {
+            failure = Some(e);
+            break;
+        }
+        recorded += 1;
+        if recorded % PROGRESS_INTERVAL == 0 {
+            let _ = tx.send(CaptureMessage::Recording(recorded));
+        }
+    }
+    reader.close();
+    // Finish the file even after a failure, so what was recorded is kept.
+    writer.close()?;
+    if let Some(e) = failure {
+        return Err(e);
+    }
+    if recorded == 0 {
+        return Err("No frames were captured".into());
+    }
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+
+use crossbeam_channel::Receiver;
+
+use faceguard_core::blurring::infrastructure::blurrer_factory;
+use faceguard_core::blurring::infrastructure::gpu_context::GpuContext;
+use faceguard_core::pipeline::preview_frame_use_case::PreviewFrameUseCase;
+use faceguard_core::shared::constants::IMAGE_EXTENSIONS;
+use faceguard_core::shared::frame::Frame;
+use faceguard_core::shared::region::Region;
+use faceguard_core::shared::track_time_rules::TrackTimeRules;
+use faceguard_core::video::domain::video_reader::VideoReader;
+use faceguard_core::video::infrastructure::ffmpeg_reader::FfmpegReader;
+use faceguard_core::video::infrastructure::image_file_reader::ImageFileReader;
+
+use crate::widgets::detection_overlay::OverlayBox;
+
+/// Widest the sample frame is kept; enough for the Blur tab at any window
+/// size, and small enough to recomposite on every drag.
+const MAX_SAMPLE_WIDTH: u32 = 1280;
+
+pub enum CompareMessage {
+    /// The sample frame before and after blurring, at display size, the
+    /// blurred regions scaled to match, and every face the scan found on
+    /// the frame.
+    Ready(
+        image::RgbaImage,
+        image::RgbaImage,
+        Vec<Region>,
+        Vec<OverlayBox>,
+    ),
+    Error(String),
+}
+
+pub struct CompareParams {
+    pub input_path: PathBuf,
+    pub frame_index: usize,
+    pub detection_cache: Arc<HashMap<usize, Vec<Region>>>,
+    pub blur_ids: Option<HashSet<u32>>,
+    pub time_rules: TrackTimeRules,
+    pub blur_shape: crate::settings::BlurShape,
+    pub blur_strength: u32,
+    pub blur_feather: u32,
+    /// Coverage the scan's regions were built with.
+    pub scan_coverage: u32,
+    /// Coverage to preview, which may differ until the user re-scans.
+    pub blur_coverage: u32,
+    pub lookahead: u32,
+    pub gpu_context: Option<Arc<GpuContext>>,
+}
+
+/// Renders one frame of the input as it is and as the blur job would
+/// output it, reusing the preview scan's detections. Nothing is written to
+/// disk.
+pub fn spawn(params: CompareParams) -> Receiver<CompareMessage> {
+    let (tx, rx) = crossbeam_channel::unbounded::<CompareMessage>();
+
+    thread::spawn(move || {
+        let _ = tx.send(match render(&params) {
+            Ok((original, blurred, regions, detections)) => {
+                CompareMessage::Ready(original, blurred, regions, detections)
+            }
+            Err(e) => CompareMessage::Error(e.to_string()),
+        });
+    });
+
+    rx
+}
+
+type Rendered = (
+    image::RgbaImage,
+    image::RgbaImage,
+    Vec<Region>,
+    Vec<OverlayBox>,
+);
+
+fn render(params: &CompareParams) -> Result<Rendered, Box<dyn std::error::Error>> {
+    let reader: Box<dyn VideoReader> = if is_image(&params.input_path) {
+        Box::new(ImageFileReader::new())
+    } else {
+        Box::new(FfmpegReader::new())
+    };
+    let blur_shape = match params.blur_shape {
+        crate::settings::BlurShape::Ellipse => blurrer_factory::BlurShape::Elliptical,
+        crate::settings::BlurShape::Rect => blurrer_factory::BlurShape::Rectangular,
+    };
+    let blurrer = blurrer_factory::create_blurrer_with_context(
+        blur_shape,
+        params.blur_strength as usize,
+        params.blur_feather as f32 / 100.0,
+        params.gpu_context.clone(),
+    );
+
+    let mut use_case = PreviewFrameUseCase::new(
+        reader,
+        params.detection_cache.clone(),
+        blurrer,
+        params.lookahead as usize,
+    )
+    .with_blur_ids(params.blur_ids.clone())
+    .with_time_rules(params.time_rules.clone())
+    .with_padding(
+        params.scan_coverage as f64 / 100.0,
+        params.blur_coverage as f64 / 100.0,
+    );
+    let rendered = use_case.render(&params.input_path, params.frame_index)?;
+    // Phone videos are stored sideways; show the frame as a player would.
+    let (turn, decoded_w, decoded_h) = (
+        rendered.rotation,
+        rendered.original.width(),
+        rendered.original.height(),
+    );
+    let rendered = rendered.upright();
+    let original = to_display_image(&rendered.original)?;
+    let scale = original.width() as f64 / rendered.original.width().max(1) as f64;
+    let regions: Vec<Region> = rendered
+        .regions
+        .iter()
+        .map(|r| scale_region(r, scale))
+        .collect();
+    // A face counts as blurred when its track made it into the blur.
+    let blurred_ids: HashSet<u32> = regions.iter().filter_map(|r| r.track_id).collect();
+    let detections = params
+        .detection_cache
+        .get(&params.frame_index)
+        .map_or(&[][..], Vec::as_slice)
+        .iter()
+        .map(|r| OverlayBox {
+            region: scale_region(&r.rotated(turn, decoded_w, decoded_h), scale),
+            blurred: r.track_id.is_some_and(|id| blurred_ids.contains(&id)),
+        })
+        .collect();
+    Ok((
+        original,
+        to_display_image(&rendered.processed)?,
+        regions,
+        detections,
+    ))
+}
+
+fn scale_region(region: &Region, scale: f64) -> Region {
+    let scaled = |v: i32| (v as f64 * scale).round() as i32;
+    Region {
+        x: scaled(region.x),
+        y: scaled(region.y),
+        width: scaled(region.width),
+        height: scaled(region.height),
+        track_id: region.track_id,
+        full_width: None,
+        full_height: None,
+        unclamped_x: None,
+        unclamped_y: None,
+    }
+}
+
+fn to_display_image(frame: &Frame) -> Result<image::RgbaImage, Box<dyn std::error::Error>> {
+    let (w, h) = (frame.width(), frame.height());
+    let rgb = image::RgbImage::from_raw(w, h, frame.data().to_vec())
+        .ok_or("Sample frame has an unexpected size")?;
+    let rgba = image::DynamicImage::ImageRgb8(rgb).into_rgba8();
+    if w <= MAX_SAMPLE_WIDTH {
+        return Ok(rgba);
+    }
+    let height = (h as u64 * MAX_SAMPLE_WIDTH as u64 / w as u64).max(1) as u32;
+    Ok(image::imageops::resize(
+        &rgba,
+        MAX_SAMPLE_WIDTH,
+        height,
+        image::imageops::FilterType::Triangle,
+    ))
+}
+
+fn is_image(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use faceguard_core::detection::domain::blur_target::BlurTarget;
+use faceguard_core::detection::domain::face_region_builder::DEFAULT_UNCERTAINTY_GROWTH;
+use faceguard_core::detection::infrastructure::cache_key_params::{CacheKeyParam, CacheKeyParams};
+use faceguard_core::detection::infrastructure::detection_disk_cache::DetectionDiskCache;
+use faceguard_core::detection::infrastructure::model_updates;
+use faceguard_core::shared::constants::YOLO_MODEL_NAME;
+
+/// Frames between detections in both workers' `SkipFrameDetector`, unless
+/// changed in settings.
+pub const DEFAULT_DETECTION_INTERVAL: u32 = 2;
+
+/// Sparsest detection interval offered in settings.
+pub const MAX_DETECTION_INTERVAL: u32 = 6;
+
+/// The detection settings a scan's cache key is built from. The preview
+/// and blur workers build the same detector, so a preview scan fills the
+/// entry that the blur (or a later session) reads; the app compares these
+/// to warn before a change discards the face selection.
+pub fn params(
+    confidence: u32,
+    blur_coverage: u32,
+    center_offset: i32,
+    detection_interval: u32,
+) -> CacheKeyParams {
+    CacheKeyParams::new()
+        .with(
+            CacheKeyParam::Model,
+            model_updates::model_tag(YOLO_MODEL_NAME),
+        )
+        .with(CacheKeyParam::Confidence, confidence)
+        .with(CacheKeyParam::Padding, blur_coverage)
+        .with(CacheKeyParam::CenterOffset, center_offset)
+        .with(CacheKeyParam::UncertaintyGrowth, DEFAULT_UNCERTAINTY_GROWTH)
+        .with(CacheKeyParam::SkipFrames, detection_interval)
+}
+
+/// The same settings as [`params`] in the CLI's terms (fractions rather
+/// than percentages, and the blur target), recorded in an exported face
+/// selection so the CLI can check it detects the way the scan did.
+pub fn cli_params(
+    confidence: u32,
+    blur_coverage: u32,
+    center_offset: i32,
+    detection_interval: u32,
+) -> CacheKeyParams {
+    CacheKeyParams::new()
+        .with(
+            CacheKeyParam::Model,
+            model_updates::model_tag(YOLO_MODEL_NAME),
+        )
+        .with(CacheKeyParam::Target, BlurTarget::Face.name())
+        .with(CacheKeyParam::Confidence, confidence as f64 / 100.0)
+        .with(CacheKeyParam::Padding, blur_coverage as f64 / 100.0)
+        .with(CacheKeyParam::CenterOffset, center_offset as f64 / 100.0)
+        .with(CacheKeyParam::UncertaintyGrowth, DEFAULT_UNCERTAINTY_GROWTH)
+        .with(CacheKeyParam::SkipFrames, detection_interval)
+}
+
+/// The on-disk cache entry for detecting faces in `input` with `params`.
+///
+/// `None` when the cache folder is unknown or the input can't be read.
+pub fn entry(input: &Path, params: &CacheKeyParams) -> Option<(DetectionDiskCache, String)> {
+    let dir = DetectionDiskCache::default_dir()?;
+    match DetectionDiskCache::key(input, &params.to_string()) {
+        Ok(key) => Some((DetectionDiskCache::new(dir), key)),
+        Err(e) => {
+            log::warn!("Detection cache unavailable: {e}");
+            None
+        }
+    }
+}
@@ -1,3 +1,9 @@
 pub mod blur_worker;
+pub mod capture_worker;
+pub mod compare_worker;
+pub mod detection_cache;
 pub mod model_cache;
+pub mod model_update_worker;
+pub mod prefetch_worker;
 pub mod preview_worker;
+pub mod throttle;
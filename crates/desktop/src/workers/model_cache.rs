@@ -1,24 +1,31 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
 use std::time::Duration;
 
+use faceguard_core::detection::domain::detector_backend::DetectorBackend;
 use faceguard_core::detection::infrastructure::model_resolver;
 use faceguard_core::detection::infrastructure::onnx_yolo_detector;
+use faceguard_core::shared::cancellation::CancellationToken;
 use faceguard_core::shared::constants::{
     EMBEDDING_MODEL_NAME, EMBEDDING_MODEL_URL, WHISPER_MODEL_NAME, WHISPER_MODEL_URL,
     YOLO_MODEL_NAME, YOLO_MODEL_URL,
 };
 
 /// Shared model cache that resolves models and pre-builds ONNX sessions
-/// in the background at startup. Workers can grab pre-built sessions or
+/// in the background once started. Workers can grab pre-built sessions or
 /// fall back to building from the cached path.
 pub struct ModelCache {
     yolo_path: Arc<ModelSlot>,
     embedding_path: Arc<ModelSlot>,
     whisper_path: Arc<ModelSlot>,
     yolo_session: Arc<SessionSlot>,
+    /// Execution provider for the YOLO session; read when it is built.
+    backend: Arc<Mutex<DetectorBackend>>,
+    started: AtomicBool,
+    /// Stops background downloads, e.g. when the app closes.
+    cancelled: CancellationToken,
 }
 
 struct ModelSlot {
@@ -32,59 +39,84 @@ struct SessionSlot {
     input_size: Mutex<u32>,
     ready: Condvar,
     /// Set to true once the first build attempt completes (success or failure).
+    /// Cleared again while a new backend's session is built.
     built: Mutex<bool>,
+    /// Held for a whole build, so a rebuild waits for the one before it.
+    building: Mutex<()>,
 }
 
 impl ModelCache {
-    /// Create a new `ModelCache` and begin resolving models in the background.
-    pub fn new() -> Arc<Self> {
-        let cache = Arc::new(Self {
+    /// Create an idle `ModelCache`. Nothing is resolved (or downloaded)
+    /// until [`ModelCache::start`], so first-run onboarding can choose the
+    /// model directory first. Downloads stop when `parent` is cancelled.
+    pub fn new(parent: &CancellationToken, backend: DetectorBackend) -> Arc<Self> {
+        Arc::new(Self {
             yolo_path: Arc::new(ModelSlot::new()),
             embedding_path: Arc::new(ModelSlot::new()),
             whisper_path: Arc::new(ModelSlot::new()),
             yolo_session: Arc::new(SessionSlot::new()),
-        });
+            backend: Arc::new(Mutex::new(backend)),
+            started: AtomicBool::new(false),
+            cancelled: parent.child(),
+        })
+    }
+
+    /// Begin resolving models in the background. Later calls are no-ops.
+    pub fn start(&self) {
+        if self.started.swap(true, Ordering::Relaxed) {
+            return;
+        }
 
-        let yolo_path_slot = cache.yolo_path.clone();
-        let embedding_path_slot = cache.embedding_path.clone();
-        let whisper_path_slot = cache.whisper_path.clone();
-        let session_slot = cache.yolo_session.clone();
+        let yolo_path_slot = self.yolo_path.clone();
+        let embedding_path_slot = self.embedding_path.clone();
+        let whisper_path_slot = self.whisper_path.clone();
+        let session_slot = self.yolo_session.clone();
+        let backend = self.backend.clone();
+        let cancelled = self.cancelled.clone();
         thread::spawn(move || {
             // Resolve YOLO model path (may download)
-            yolo_path_slot.resolve(YOLO_MODEL_NAME, YOLO_MODEL_URL);
+            yolo_path_slot.resolve(YOLO_MODEL_NAME, YOLO_MODEL_URL, &cancelled);
 
             // Pre-build the ONNX session from the resolved path
-            if let Some(Ok(ref path)) = *yolo_path_slot.result.lock().unwrap() {
-                log::info!("Building YOLO session from {}", path.display());
-                let start = std::time::Instant::now();
-                if let Ok(session) = onnx_yolo_detector::OnnxYoloDetector::build_session(path) {
-                    log::info!("YOLO session built in {:?}", start.elapsed());
-                    let input_size = onnx_yolo_detector::session_input_size(&session);
-                    *session_slot.input_size.lock().unwrap() = input_size;
-                    *session_slot.session.lock().unwrap() = Some(Arc::new(Mutex::new(session)));
-                } else {
-                    log::warn!("Failed to build YOLO session");
-                }
-            }
-            *session_slot.built.lock().unwrap() = true;
-            session_slot.ready.notify_all();
+            let path = yolo_path_slot.resolved();
+            session_slot.build(path.as_deref(), &backend);
 
             // Resolve embedding model path
-            embedding_path_slot.resolve(EMBEDDING_MODEL_NAME, EMBEDDING_MODEL_URL);
+            embedding_path_slot.resolve(EMBEDDING_MODEL_NAME, EMBEDDING_MODEL_URL, &cancelled);
 
             // Resolve whisper model path
-            whisper_path_slot.resolve(WHISPER_MODEL_NAME, WHISPER_MODEL_URL);
+            whisper_path_slot.resolve(WHISPER_MODEL_NAME, WHISPER_MODEL_URL, &cancelled);
         });
+    }
 
-        cache
+    /// Run the YOLO session on `backend` from now on. A session already
+    /// built is rebuilt in the background; jobs started meanwhile wait for
+    /// the new one, and keep the old one if the rebuild fails.
+    pub fn set_detector_backend(&self, backend: DetectorBackend) {
+        {
+            let mut current = self.backend.lock().unwrap();
+            if *current == backend {
+                return;
+            }
+            *current = backend;
+        }
+        // Before the model is resolved, the first build reads the new
+        // backend itself.
+        let Some(path) = self.yolo_path.resolved() else {
+            return;
+        };
+        let session_slot = self.yolo_session.clone();
+        let backend = self.backend.clone();
+        thread::spawn(move || session_slot.build(Some(&path), &backend));
     }
 
     /// Wait for the YOLO model path. Calls `on_progress(downloaded, total)`
-    /// while a download is in progress. Returns early if `cancelled` is set.
+    /// while a download is in progress. Returns early once `cancelled` is
+    /// cancelled; the download itself carries on for the next job.
     pub fn wait_for_yolo(
         &self,
         on_progress: &dyn Fn(u64, u64),
-        cancelled: &AtomicBool,
+        cancelled: &CancellationToken,
     ) -> Result<PathBuf, String> {
         self.yolo_path.wait(on_progress, cancelled)
     }
@@ -114,7 +146,7 @@ impl ModelCache {
     pub fn wait_for_whisper(
         &self,
         on_progress: &dyn Fn(u64, u64),
-        cancelled: &AtomicBool,
+        cancelled: &CancellationToken,
     ) -> Result<PathBuf, String> {
         self.whisper_path.wait(on_progress, cancelled)
     }
@@ -123,7 +155,7 @@ impl ModelCache {
     pub fn wait_for_embedding(
         &self,
         on_progress: &dyn Fn(u64, u64),
-        cancelled: &AtomicBool,
+        cancelled: &CancellationToken,
     ) -> Result<PathBuf, String> {
         self.embedding_path.wait(on_progress, cancelled)
     }
@@ -138,15 +170,24 @@ impl ModelSlot {
         }
     }
 
-    fn resolve(&self, name: &str, url: &str) {
+    /// The path, if resolving has finished and succeeded.
+    fn resolved(&self) -> Option<PathBuf> {
+        match *self.result.lock().unwrap() {
+            Some(Ok(ref path)) => Some(path.clone()),
+            _ => None,
+        }
+    }
+
+    fn resolve(&self, name: &str, url: &str, cancelled: &CancellationToken) {
         let progress_mutex = self.progress.clone();
-        let result = model_resolver::resolve(
+        let result = model_resolver::resolve_cancellable(
             name,
             url,
             None,
             Some(Box::new(move |downloaded, total| {
                 *progress_mutex.lock().unwrap() = (downloaded, total);
             })),
+            cancelled,
         );
         *self.result.lock().unwrap() = Some(result.map_err(|e| e.to_string()));
         self.ready.notify_all();
@@ -155,12 +196,12 @@ impl ModelSlot {
     fn wait(
         &self,
         on_progress: &dyn Fn(u64, u64),
-        cancelled: &AtomicBool,
+        cancelled: &CancellationToken,
     ) -> Result<PathBuf, String> {
         let mut guard = self.result.lock().unwrap();
         loop {
-            if cancelled.load(Ordering::Relaxed) {
-                return Err("Cancelled".into());
+            if let Err(e) = cancelled.check() {
+                return Err(e.to_string());
             }
             if let Some(ref result) = *guard {
                 return result.clone();
@@ -188,6 +229,34 @@ impl SessionSlot {
             input_size: Mutex::new(0),
             ready: Condvar::new(),
             built: Mutex::new(false),
+            building: Mutex::new(()),
         }
     }
+
+    /// Build the session from `path` on the current `backend`, or only mark
+    /// the attempt done when the model couldn't be resolved.
+    fn build(&self, path: Option<&Path>, backend: &Mutex<DetectorBackend>) {
+        let _building = self.building.lock().unwrap();
+        if let Some(path) = path {
+            *self.built.lock().unwrap() = false;
+            let backend = *backend.lock().unwrap();
+            log::info!(
+                "Building YOLO session on {} from {}",
+                backend.label(),
+                path.display()
+            );
+            let start = std::time::Instant::now();
+            match onnx_yolo_detector::OnnxYoloDetector::build_session_on(path, backend) {
+                Ok(session) => {
+                    log::info!("YOLO session built in {:?}", start.elapsed());
+                    let input_size = onnx_yolo_detector::session_input_size(&session);
+                    *self.input_size.lock().unwrap() = input_size;
+                    *self.session.lock().unwrap() = Some(Arc::new(Mutex::new(session)));
+                }
+                Err(e) => log::warn!("Failed to build YOLO session: {e}"),
+            }
+        }
+        *self.built.lock().unwrap() = true;
+        self.ready.notify_all();
+    }
 }
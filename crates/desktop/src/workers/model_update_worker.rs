@@ -0,0 +1,61 @@
+use std::thread;
+
+use crossbeam_channel::Receiver;
+
+use faceguard_core::detection::infrastructure::model_updates::{self, ModelUpdate};
+use faceguard_core::shared::cancellation::CancellationToken;
+
+pub enum ModelUpdateMessage {
+    Checked(Vec<ModelUpdate>),
+    /// `(update index, bytes_downloaded, total_bytes)`.
+    Progress(usize, u64, u64),
+    Installed,
+    Error(String),
+}
+
+/// Fetches the model manifest once. Only started from the "Check for model
+/// updates" button; the app never checks on its own.
+pub fn spawn_check() -> Receiver<ModelUpdateMessage> {
+    let (tx, rx) = crossbeam_channel::unbounded::<ModelUpdateMessage>();
+
+    thread::spawn(move || {
+        let _ = tx.send(match model_updates::check_for_updates() {
+            Ok(updates) => ModelUpdateMessage::Checked(updates),
+            Err(e) => ModelUpdateMessage::Error(e.to_string()),
+        });
+    });
+
+    rx
+}
+
+/// Installs `updates` one after another, skipping those that need a newer
+/// app. Stops at the first failure or once `cancelled` is cancelled.
+pub fn spawn_install(
+    updates: Vec<ModelUpdate>,
+    cancelled: CancellationToken,
+) -> Receiver<ModelUpdateMessage> {
+    let (tx, rx) = crossbeam_channel::unbounded::<ModelUpdateMessage>();
+
+    thread::spawn(move || {
+        for (index, update) in updates.iter().enumerate() {
+            if update.needs_app_update {
+                continue;
+            }
+            let progress_tx = tx.clone();
+            let result = model_updates::install_update(
+                update,
+                Some(Box::new(move |dl, total| {
+                    let _ = progress_tx.send(ModelUpdateMessage::Progress(index, dl, total));
+                })),
+                &cancelled,
+            );
+            if let Err(e) = result {
+                let _ = tx.send(ModelUpdateMessage::Error(e.to_string()));
+                return;
+            }
+        }
+        let _ = tx.send(ModelUpdateMessage::Installed);
+    });
+
+    rx
+}
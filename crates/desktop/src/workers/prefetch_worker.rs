@@ -0,0 +1,37 @@
+use std::sync::Arc;
+use std::thread;
+
+use crossbeam_channel::Receiver;
+
+use faceguard_core::detection::infrastructure::model_resolver;
+use faceguard_core::shared::cancellation::CancellationToken;
+
+pub enum PrefetchMessage {
+    /// `(model index, bytes_downloaded, total_bytes)` into
+    /// `model_resolver::REGISTERED_MODELS`.
+    Progress(usize, u64, u64),
+    Complete,
+    Error(String),
+}
+
+/// Downloads every registered model that is not cached yet, reporting
+/// progress per model. Stops once `cancelled` is cancelled.
+pub fn spawn(cancelled: CancellationToken) -> Receiver<PrefetchMessage> {
+    let (tx, rx) = crossbeam_channel::unbounded::<PrefetchMessage>();
+
+    thread::spawn(move || {
+        let progress_tx = tx.clone();
+        let result = model_resolver::prefetch_all(
+            Some(Arc::new(move |model, dl, total| {
+                let _ = progress_tx.send(PrefetchMessage::Progress(model, dl, total));
+            })),
+            &cancelled,
+        );
+        let _ = tx.send(match result {
+            Ok(_) => PrefetchMessage::Complete,
+            Err(e) => PrefetchMessage::Error(e.to_string()),
+        });
+    });
+
+    rx
+}
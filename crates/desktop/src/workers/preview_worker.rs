@@ -3,19 +3,27 @@ use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
+use std::time::Instant;
 
 use crossbeam_channel::{Receiver, Sender};
 
+use faceguard_core::blurring::infrastructure::cpu_rectangular_blurrer::CpuRectangularBlurrer;
 use faceguard_core::detection::domain::face_detector::FaceDetector;
 use faceguard_core::detection::domain::face_grouper::FaceGrouper;
-use faceguard_core::detection::domain::face_region_builder::FaceRegionBuilder;
+use faceguard_core::detection::domain::face_region_builder::{
+    FaceRegionBuilder, DEFAULT_UNCERTAINTY_GROWTH,
+};
 use faceguard_core::detection::domain::region_smoother::{RegionSmoother, DEFAULT_ALPHA};
 use faceguard_core::detection::infrastructure::bytetrack_tracker::ByteTracker;
+use faceguard_core::detection::infrastructure::cached_face_detector::CachedFaceDetector;
 use faceguard_core::detection::infrastructure::histogram_face_grouper::HistogramFaceGrouper;
+use faceguard_core::detection::infrastructure::model_resolver;
 use faceguard_core::detection::infrastructure::onnx_yolo_detector::OnnxYoloDetector;
 use faceguard_core::detection::infrastructure::skip_frame_detector::SkipFrameDetector;
 use faceguard_core::pipeline::preview_faces_use_case::PreviewFacesUseCase;
-use faceguard_core::shared::constants::{IMAGE_EXTENSIONS, TRACKER_MAX_LOST};
+use faceguard_core::shared::cancellation::{CancellationToken, Cancelled};
+use faceguard_core::shared::constants::{IMAGE_EXTENSIONS, TRACKER_MAX_LOST, YOLO_MODEL_NAME};
+use faceguard_core::shared::frame::Frame;
 use faceguard_core::shared::region::Region;
 use faceguard_core::video::domain::image_writer::ImageWriter;
 use faceguard_core::video::domain::video_reader::VideoReader;
@@ -23,11 +31,24 @@ use faceguard_core::video::infrastructure::ffmpeg_reader::FfmpegReader;
 use faceguard_core::video::infrastructure::image_file_reader::ImageFileReader;
 use faceguard_core::video::infrastructure::image_file_writer::ImageFileWriter;
 
+use super::detection_cache;
 use super::model_cache::ModelCache;
+use super::throttle::Throttle;
+use crate::performance::{detection_work, InputSize, Stage};
+
+/// Kernel for thumbnails stored blurred on disk. Matches the default blur
+/// strength so a saved thumbnail is no more recognizable than the output.
+const THUMBNAIL_BLUR_KERNEL: usize = 201;
+/// Edge length of the in-memory sharp thumbnails, same as the saved ones.
+const THUMBNAIL_SIZE: u32 = 256;
+/// Crops kept per track for the group collages.
+const RANKED_CROPS: usize = 3;
 
 pub enum PreviewMessage {
     DownloadProgress(u64, u64),
     ScanProgress(usize, usize),
+    /// Measured throughput in megapixels per second, sent before `Complete`.
+    Measured(Stage, f64),
     Complete(PreviewResult),
     Error(String),
     Cancelled,
@@ -35,6 +56,12 @@ pub enum PreviewMessage {
 
 pub struct PreviewResult {
     pub crops: HashMap<u32, PathBuf>,
+    /// Unblurred thumbnails when the files in `crops` were stored blurred.
+    /// Only ever held in memory.
+    pub sharp_thumbnails: HashMap<u32, image::RgbImage>,
+    /// Up to three unblurred thumbnails per track, best first, taken from
+    /// different moments. Only ever held in memory.
+    pub ranked_thumbnails: HashMap<u32, Vec<image::RgbImage>>,
     pub groups: Vec<Vec<u32>>,
     pub detection_cache: HashMap<usize, Vec<Region>>,
     pub temp_dir: tempfile::TempDir,
@@ -45,17 +72,31 @@ pub struct PreviewParams {
     pub confidence: u32,
     pub blur_coverage: u32,
     pub center_offset: i32,
+    /// Store thumbnails blurred on disk and keep sharp copies in memory only.
+    pub anonymize_thumbnails: bool,
+    /// Reuse and save detections in the on-disk cache.
+    pub cache_detections: bool,
+    /// Tracks seen on fewer frames get no thumbnail; 0 keeps every track.
+    pub min_track_frames: usize,
+    /// Frames between detections; positions are extrapolated in between.
+    pub detection_interval: u32,
     pub model_cache: Arc<ModelCache>,
+    pub power_saving: Arc<AtomicBool>,
 }
 
-pub fn spawn(params: PreviewParams) -> (Receiver<PreviewMessage>, Arc<AtomicBool>) {
+/// Starts the scan on a child of `parent`; the returned token cancels just
+/// this scan.
+pub fn spawn(
+    params: PreviewParams,
+    parent: &CancellationToken,
+) -> (Receiver<PreviewMessage>, CancellationToken) {
     let (tx, rx) = crossbeam_channel::unbounded::<PreviewMessage>();
-    let cancelled = Arc::new(AtomicBool::new(false));
+    let cancelled = parent.child();
     let cancelled_clone = cancelled.clone();
 
     thread::spawn(move || {
         if let Err(e) = run_preview(&tx, &cancelled_clone, &params) {
-            if cancelled_clone.load(Ordering::Relaxed) {
+            if Cancelled::reason_of(&*e).is_some() || cancelled_clone.is_cancelled() {
                 let _ = tx.send(PreviewMessage::Cancelled);
             } else {
                 let _ = tx.send(PreviewMessage::Error(e.to_string()));
@@ -68,18 +109,36 @@ pub fn spawn(params: PreviewParams) -> (Receiver<PreviewMessage>, Arc<AtomicBool
 
 fn run_preview(
     tx: &Sender<PreviewMessage>,
-    cancelled: &Arc<AtomicBool>,
+    cancelled: &CancellationToken,
     params: &PreviewParams,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let input = &params.input_path;
     let confidence = params.confidence as f64 / 100.0;
 
-    let detector = build_detector(params, tx, cancelled, confidence)?;
+    let disk_cache = if params.cache_detections && !is_image(input) {
+        detection_cache::entry(
+            input,
+            &detection_cache::params(
+                params.confidence,
+                params.blur_coverage,
+                params.center_offset,
+                params.detection_interval,
+            ),
+        )
+    } else {
+        None
+    };
+    let cached = disk_cache.as_ref().and_then(|(cache, key)| cache.load(key));
+    let cache_hit = cached.is_some();
+    let detector: Box<dyn FaceDetector> = match cached {
+        Some(detections) => {
+            log::info!("Preview: reusing cached detections");
+            Box::new(CachedFaceDetector::new(Arc::new(detections)))
+        }
+        None => build_detector(params, tx, cancelled, confidence)?,
+    };
     let embedding_path = wait_for_embedding(params, tx, cancelled);
-
-    if cancelled.load(Ordering::Relaxed) {
-        return Err("Cancelled".into());
-    }
+    cancelled.check()?;
 
     let mut reader: Box<dyn VideoReader> = if is_image(input) {
         Box::new(ImageFileReader::new())
@@ -93,24 +152,56 @@ fn run_preview(
     let image_writer: Box<dyn ImageWriter> = Box::new(ImageFileWriter::new());
 
     let tx_progress = tx.clone();
-    let cancelled_progress = cancelled.clone();
+    let throttle = Throttle::new(params.power_saving.clone());
     let progress: Box<dyn Fn(usize, usize) -> bool + Send> = Box::new(move |current, total| {
+        throttle.pace();
         let _ = tx_progress.send(PreviewMessage::ScanProgress(current, total));
-        !cancelled_progress.load(Ordering::Relaxed)
+        true
     });
 
-    let mut use_case = PreviewFacesUseCase::new(reader, detector, image_writer, Some(progress));
+    let mut use_case = PreviewFacesUseCase::new(reader, detector, image_writer, Some(progress))
+        .with_min_track_frames(params.min_track_frames)
+        .with_ranked_crops(RANKED_CROPS)
+        .with_cancellation(cancelled.child());
+    if params.anonymize_thumbnails {
+        use_case =
+            use_case.with_crop_blurrer(Box::new(CpuRectangularBlurrer::new(THUMBNAIL_BLUR_KERNEL)));
+    }
+    let started = Instant::now();
     let (crops, detection_cache) = use_case.execute(&metadata, &temp_path)?;
+    let elapsed = started.elapsed().as_secs_f64();
+    let sharp_thumbnails = to_thumbnails(use_case.take_sharp_crops());
+    let ranked_thumbnails = use_case
+        .take_ranked_crops()
+        .into_iter()
+        .map(|(track_id, crops)| {
+            let thumbnails = crops.into_iter().filter_map(to_thumbnail).collect();
+            (track_id, thumbnails)
+        })
+        .collect();
+    cancelled.check()?;
 
-    if cancelled.load(Ordering::Relaxed) {
-        return Err("Cancelled".into());
+    if let (Some((cache, key)), false) = (&disk_cache, cache_hit) {
+        if let Err(e) = cache.store(key, &detection_cache) {
+            log::warn!("Could not save detections to the cache: {e}");
+        }
     }
 
     let embedding_result = embedding_path.map_err(|e| -> Box<dyn std::error::Error> { e.into() });
-    let groups = group_faces(&crops, &embedding_result)?;
+    let groups = group_faces(&crops, &sharp_thumbnails, &embedding_result)?;
+
+    // Cache hits skip detection, and throttled scans are slower than the
+    // machine; neither says how long a real scan takes.
+    if !cache_hit && !is_image(input) && !params.power_saving.load(Ordering::Relaxed) {
+        let megapixels = InputSize::from(&metadata).megapixels();
+        let rate = megapixels / elapsed * detection_work(params.detection_interval);
+        let _ = tx.send(PreviewMessage::Measured(Stage::Scan, rate));
+    }
 
     let _ = tx.send(PreviewMessage::Complete(PreviewResult {
         crops,
+        sharp_thumbnails,
+        ranked_thumbnails,
         groups,
         detection_cache,
         temp_dir,
@@ -122,7 +213,7 @@ fn run_preview(
 fn build_detector(
     params: &PreviewParams,
     tx: &Sender<PreviewMessage>,
-    cancelled: &Arc<AtomicBool>,
+    cancelled: &CancellationToken,
     confidence: f64,
 ) -> Result<Box<dyn FaceDetector>, Box<dyn std::error::Error>> {
     let tx_dl = tx.clone();
@@ -135,15 +226,13 @@ fn build_detector(
             cancelled,
         )
         .map_err(|e| -> Box<dyn std::error::Error> { e.into() })?;
-
-    if cancelled.load(Ordering::Relaxed) {
-        return Err("Cancelled".into());
-    }
+    cancelled.check()?;
 
     let padding = params.blur_coverage as f64 / 100.0;
     let center_offset = params.center_offset as f64 / 100.0;
     let smoother = RegionSmoother::new(DEFAULT_ALPHA);
-    let region_builder = FaceRegionBuilder::new(padding, center_offset, Some(Box::new(smoother)));
+    let region_builder = FaceRegionBuilder::new(padding, center_offset, Some(Box::new(smoother)))
+        .with_uncertainty_growth(DEFAULT_UNCERTAINTY_GROWTH);
     let tracker = ByteTracker::new(TRACKER_MAX_LOST);
 
     let det = match params.model_cache.get_yolo_session() {
@@ -155,15 +244,19 @@ fn build_detector(
             confidence,
         ),
         None => OnnxYoloDetector::new(&model_path, region_builder, tracker, confidence)?,
-    };
+    }
+    .with_calibration(model_resolver::calibration_for(YOLO_MODEL_NAME));
 
-    Ok(Box::new(SkipFrameDetector::new(Box::new(det), 2)?))
+    Ok(Box::new(SkipFrameDetector::new(
+        Box::new(det),
+        params.detection_interval as usize,
+    )?))
 }
 
 fn wait_for_embedding(
     params: &PreviewParams,
     tx: &Sender<PreviewMessage>,
-    cancelled: &Arc<AtomicBool>,
+    cancelled: &CancellationToken,
 ) -> Result<PathBuf, String> {
     let tx_dl = tx.clone();
     params.model_cache.wait_for_embedding(
@@ -174,9 +267,30 @@ fn wait_for_embedding(
     )
 }
 
+fn to_thumbnails(crops: HashMap<u32, Frame>) -> HashMap<u32, image::RgbImage> {
+    crops
+        .into_iter()
+        .filter_map(|(track_id, crop)| Some((track_id, to_thumbnail(crop)?)))
+        .collect()
+}
+
+fn to_thumbnail(crop: Frame) -> Option<image::RgbImage> {
+    let (w, h) = (crop.width(), crop.height());
+    let img = image::RgbImage::from_raw(w, h, crop.data().to_vec())?;
+    Some(image::imageops::resize(
+        &img,
+        THUMBNAIL_SIZE,
+        THUMBNAIL_SIZE,
+        image::imageops::FilterType::Triangle,
+    ))
+}
+
 /// Try embedding-based grouping first; fall back to histogram on any failure.
+/// Groups on the sharp thumbnails when there are any, since the files on
+/// disk may be blurred.
 fn group_faces(
     crops: &HashMap<u32, PathBuf>,
+    sharp: &HashMap<u32, image::RgbImage>,
     embedding_path: &Result<PathBuf, Box<dyn std::error::Error>>,
 ) -> Result<Vec<Vec<u32>>, Box<dyn std::error::Error>> {
     if crops.is_empty() {
@@ -185,7 +299,10 @@ fn group_faces(
 
     let mut crop_data: Vec<(u32, Vec<u8>, u32, u32)> = Vec::new();
     for (&track_id, path) in crops {
-        let img = image::open(path)?.to_rgb8();
+        let img = match sharp.get(&track_id) {
+            Some(img) => img.clone(),
+            None => image::open(path)?.to_rgb8(),
+        };
         let (w, h) = img.dimensions();
         crop_data.push((track_id, img.into_raw(), w, h));
     }
@@ -0,0 +1,37 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Upper bound on a single pause so cancellation and progress stay responsive.
+const MAX_PAUSE: Duration = Duration::from_millis(100);
+
+/// Slows a worker to roughly half speed while power saving is active.
+///
+/// Called once per frame from the progress callback. When enabled, it sleeps
+/// for as long as the previous frame took to process, giving a ~50% duty
+/// cycle. The flag is shared with the UI, so a job started on battery speeds
+/// back up as soon as the machine is plugged in.
+pub struct Throttle {
+    enabled: Arc<AtomicBool>,
+    last_frame: Mutex<Instant>,
+}
+
+impl Throttle {
+    pub fn new(enabled: Arc<AtomicBool>) -> Self {
+        Self {
+            enabled,
+            last_frame: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub fn pace(&self) {
+        let Ok(mut last) = self.last_frame.lock() else {
+            return;
+        };
+        if self.enabled.load(Ordering::Relaxed) {
+            thread::sleep(last.elapsed().min(MAX_PAUSE));
+        }
+        *last = Instant::now();
+    }
+}